@@ -1,7 +1,7 @@
 use arrow::error::ArrowError;
 use arrow::{
     array::FixedSizeListArray,
-    datatypes::{DataType, Field, Float64Type, Int64Type},
+    datatypes::{DataType, Field, Float64Type, Int32Type, Int64Type},
 };
 use arrow::{
     array::{
@@ -25,8 +25,8 @@ use std::sync::Arc;
 
 use crate::primitives::Coordinate2D;
 use crate::primitives::{
-    CategoryDataRef, FeatureData, FeatureDataRef, FeatureDataType, FeatureDataValue, FloatDataRef,
-    Geometry, IntDataRef, TextDataRef, TimeInterval,
+    BytesDataRef, CategoryDataRef, DictionaryDataRef, FeatureData, FeatureDataRef, FeatureDataType,
+    FeatureDataValue, FloatDataRef, Geometry, IntDataRef, TextDataRef, TimeInterval,
 };
 use crate::util::arrow::{downcast_array, ArrowTyped};
 use crate::util::helpers::SomeIter;
@@ -458,7 +458,7 @@ where
                     arrow::compute::lt_utf8_scalar,
                 )?;
             }
-            FeatureDataType::Category => {
+            FeatureDataType::Category | FeatureDataType::Bytes | FeatureDataType::Dictionary => {
                 return Err(error::FeatureCollectionError::WrongDataType.into());
             }
         }
@@ -990,6 +990,29 @@ where
                     )
                     .into()
                 }
+                FeatureDataType::Bytes => {
+                    let array: &arrow::array::BinaryArray = downcast_array(column);
+                    BytesDataRef::new(
+                        array.value_data(),
+                        array.value_offsets(),
+                        array.data_ref().null_bitmap(),
+                    )
+                    .into()
+                }
+                FeatureDataType::Dictionary => {
+                    let array: &arrow::array::DictionaryArray<Int32Type> = downcast_array(column);
+                    let values: &arrow::array::StringArray = downcast_array(array.values());
+                    DictionaryDataRef::new(
+                        array.keys().values(),
+                        array.data_ref().null_bitmap(),
+                        TextDataRef::new(
+                            values.value_data(),
+                            values.value_offsets(),
+                            values.data_ref().null_bitmap(),
+                        ),
+                    )
+                    .into()
+                }
                 FeatureDataType::Int => {
                     let array: &arrow::array::Int64Array = downcast_array(column);
                     IntDataRef::new(array.values(), array.data_ref().null_bitmap()).into()
@@ -1209,6 +1232,85 @@ where
         )
     }
 
+    /// Replaces the geometries and returns an updated collection.
+    /// Collections without geometries are copied.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the number of `geometries` does not equal the length of the collection
+    ///
+    pub fn replace_geometries(&self, geometries: Vec<CollectionType>) -> Result<Self> {
+        self.with_geometries(geometries)
+    }
+
+    /// Creates a collection of another geometry type with the `geometries` and the time intervals and attributes of this collection.
+    /// If `GeometryType` has no geometries, the attributes are copied into a data collection.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the number of `geometries` does not equal the length of the collection
+    ///
+    pub fn with_geometries<GeometryType>(
+        &self,
+        geometries: Vec<GeometryType>,
+    ) -> Result<FeatureCollection<GeometryType>>
+    where
+        GeometryType: Geometry + ArrowTyped,
+    {
+        let mut columns = Vec::<Field>::with_capacity(self.table.num_columns());
+        let mut column_values = Vec::<ArrayRef>::with_capacity(self.table.num_columns());
+
+        if GeometryType::IS_GEOMETRY {
+            ensure!(
+                geometries.len() == self.table.len(),
+                error::UnmatchedLength {
+                    a: geometries.len(),
+                    b: self.table.len()
+                }
+            );
+
+            columns.push(Field::new(
+                Self::GEOMETRY_COLUMN_NAME,
+                GeometryType::arrow_data_type(),
+                false,
+            ));
+            column_values.push(Arc::new(GeometryType::from_vec(geometries)?));
+        }
+
+        // copy time data
+        columns.push(Field::new(
+            Self::TIME_COLUMN_NAME,
+            TimeInterval::arrow_data_type(),
+            false,
+        ));
+        column_values.push(
+            self.table
+                .column_by_name(Self::TIME_COLUMN_NAME)
+                .expect("The time column must exist")
+                .clone(),
+        );
+
+        // copy remaining attribute data
+        for (column_name, column_type) in &self.types {
+            columns.push(Field::new(
+                column_name,
+                column_type.arrow_data_type(),
+                column_type.nullable(),
+            ));
+            column_values.push(
+                self.table
+                    .column_by_name(column_name)
+                    .expect("The attribute column must exist")
+                    .clone(),
+            );
+        }
+
+        Ok(FeatureCollection::new_from_internals(
+            struct_array_from_data(columns, column_values, self.table.len()),
+            self.types.clone(),
+        ))
+    }
+
     /// Checks for name conflicts with reserved names
     pub(super) fn is_reserved_name(name: &str) -> bool {
         name == Self::GEOMETRY_COLUMN_NAME || name == Self::TIME_COLUMN_NAME
@@ -4,9 +4,10 @@ use crate::primitives::{FeatureDataType, FeatureDataValue, Geometry, TimeInterva
 use crate::util::arrow::{downcast_mut_array, ArrowTyped};
 use crate::util::Result;
 use arrow::array::{
-    ArrayBuilder, Float64Builder, Int64Builder, StringBuilder, StructBuilder, UInt8Builder,
+    ArrayBuilder, BinaryBuilder, Float64Builder, Int64Builder, StringBuilder,
+    StringDictionaryBuilder, StructBuilder, UInt8Builder,
 };
-use arrow::datatypes::Field;
+use arrow::datatypes::{Field, Int32Type};
 use snafu::ensure;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
@@ -206,6 +207,36 @@ where
                     string_builder.append_null()?;
                 }
             }
+            FeatureDataValue::Bytes(value) => {
+                self.string_bytes += value.len();
+
+                let bytes_builder: &mut BinaryBuilder = downcast_mut_array(data_builder.as_mut());
+                bytes_builder.append_value(&value)?;
+            }
+            FeatureDataValue::NullableBytes(value) => {
+                self.string_bytes += value.as_ref().map_or(0, Vec::len);
+
+                let bytes_builder: &mut BinaryBuilder = downcast_mut_array(data_builder.as_mut());
+                if let Some(v) = &value {
+                    bytes_builder.append_value(v)?;
+                } else {
+                    bytes_builder.append_null()?;
+                }
+            }
+            FeatureDataValue::Dictionary(value) => {
+                let dictionary_builder: &mut StringDictionaryBuilder<Int32Type> =
+                    downcast_mut_array(data_builder.as_mut());
+                dictionary_builder.append(&value)?;
+            }
+            FeatureDataValue::NullableDictionary(value) => {
+                let dictionary_builder: &mut StringDictionaryBuilder<Int32Type> =
+                    downcast_mut_array(data_builder.as_mut());
+                if let Some(v) = &value {
+                    dictionary_builder.append(v)?;
+                } else {
+                    dictionary_builder.append_null()?;
+                }
+            }
             FeatureDataValue::Int(value) => {
                 let int_builder: &mut Int64Builder = downcast_mut_array(data_builder.as_mut());
                 int_builder.append_value(value)?;
@@ -261,7 +292,12 @@ where
                     std::mem::size_of::<i64>()
                 } else if builder.as_any().is::<UInt8Builder>() {
                     std::mem::size_of::<u8>()
-                } else if builder.as_any().is::<StringBuilder>() {
+                } else if builder.as_any().is::<StringDictionaryBuilder<Int32Type>>() {
+                    // the distinct values are negligible
+                    std::mem::size_of::<i32>()
+                } else if builder.as_any().is::<StringBuilder>()
+                    || builder.as_any().is::<BinaryBuilder>()
+                {
                     0 // TODO: how to get this dynamic value
                 } else {
                     unreachable!("This type is not an attribute type");
@@ -508,6 +508,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn append_dictionaries() {
+        let collection_a = MultiPointCollection::from_data(
+            MultiPoint::many(vec![vec![(0., 0.)], vec![(1., 1.)]]).unwrap(),
+            vec![TimeInterval::new_unchecked(0, 1); 2],
+            {
+                let mut map = HashMap::new();
+                map.insert(
+                    "class".into(),
+                    FeatureData::Dictionary(vec!["a".to_string(), "b".to_string()]),
+                );
+                map
+            },
+        )
+        .unwrap();
+
+        let collection_b = MultiPointCollection::from_data(
+            MultiPoint::many(vec![vec![(2., 2.)], vec![(3., 3.)]]).unwrap(),
+            vec![TimeInterval::new_unchecked(0, 1); 2],
+            {
+                let mut map = HashMap::new();
+                map.insert(
+                    "class".into(),
+                    FeatureData::Dictionary(vec!["c".to_string(), "a".to_string()]),
+                );
+                map
+            },
+        )
+        .unwrap();
+
+        let collection_c = collection_a.append(&collection_b).unwrap();
+
+        assert_eq!(
+            collection_c
+                .data("class")
+                .unwrap()
+                .strings_iter()
+                .collect::<Vec<_>>(),
+            vec!["a", "b", "c", "a"]
+        );
+    }
+
     #[test]
     #[allow(clippy::too_many_lines)]
     fn to_geo_json() {
@@ -629,6 +671,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn to_geo_json_with_bytes() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![vec![(0., 0.)], vec![(1., 1.)]]).unwrap(),
+            vec![TimeInterval::new_unchecked(0, 1); 2],
+            {
+                let mut map = HashMap::new();
+                map.insert(
+                    "thumbnail".into(),
+                    FeatureData::NullableBytes(vec![Some(vec![0x89, 0x50, 0x4e, 0x47]), None]),
+                );
+                map
+            },
+        )
+        .unwrap();
+
+        let geo_json = from_str::<serde_json::Value>(collection.to_geo_json().as_str()).unwrap();
+
+        assert_eq!(
+            geo_json["features"][0]["properties"],
+            json!({"thumbnail": "iVBORw=="})
+        );
+        assert_eq!(
+            geo_json["features"][1]["properties"],
+            json!({ "thumbnail": null })
+        );
+    }
+
     #[test]
     fn reserved_columns_in_builder() {
         let mut builder = MultiPointCollection::builder();
@@ -1,4 +1,5 @@
 use crate::identifier;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 identifier!(DatasetProviderId);
@@ -7,7 +8,7 @@ identifier!(InternalDatasetId);
 
 identifier!(StagingDatasetId);
 
-#[derive(Debug, Clone, Hash, Eq, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase", tag = "type")]
 pub enum DatasetId {
     #[serde(rename_all = "camelCase")]
@@ -15,9 +16,14 @@ pub enum DatasetId {
         dataset_id: InternalDatasetId,
     },
     External(ExternalDatasetId),
+    /// A stable, human-readable name that is resolved to an `ExternalDatasetId`
+    #[serde(rename_all = "camelCase")]
+    Alias {
+        alias: String,
+    },
 }
 
-#[derive(Debug, Clone, Hash, Eq, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ExternalDatasetId {
     pub provider_id: DatasetProviderId,
@@ -41,6 +47,13 @@ impl DatasetId {
         }
         None
     }
+
+    pub fn alias(&self) -> Option<&str> {
+        if let Self::Alias { alias } = self {
+            return Some(alias);
+        }
+        None
+    }
 }
 
 impl From<InternalDatasetId> for DatasetId {
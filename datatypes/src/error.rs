@@ -148,6 +148,11 @@ pub enum Error {
         details: String,
     },
 
+    #[snafu(display("Image compositing failed: {}", details))]
+    ImageCompositing {
+        details: String,
+    },
+
     Primitives {
         source: PrimitivesError,
     },
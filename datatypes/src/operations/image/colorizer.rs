@@ -19,12 +19,24 @@ pub enum Colorizer {
         breakpoints: Breakpoints,
         no_data_color: RgbaColor,
         default_color: RgbaColor,
+        /// The color of values below the first breakpoint, `default_color` if omitted
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        under_color: Option<RgbaColor>,
+        /// The color of values above the last breakpoint, `default_color` if omitted
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        over_color: Option<RgbaColor>,
     },
     #[serde(rename_all = "camelCase")]
     LogarithmicGradient {
         breakpoints: Breakpoints,
         no_data_color: RgbaColor,
         default_color: RgbaColor,
+        /// The color of values below the first breakpoint, `default_color` if omitted
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        under_color: Option<RgbaColor>,
+        /// The color of values above the last breakpoint, `default_color` if omitted
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        over_color: Option<RgbaColor>,
     },
     #[serde(rename_all = "camelCase")]
     Palette {
@@ -53,6 +65,8 @@ impl Colorizer {
             breakpoints,
             no_data_color,
             default_color,
+            under_color: None,
+            over_color: None,
         };
 
         ensure!(
@@ -83,6 +97,8 @@ impl Colorizer {
             breakpoints,
             no_data_color,
             default_color,
+            under_color: None,
+            over_color: None,
         };
 
         ensure!(
@@ -102,7 +118,7 @@ impl Colorizer {
     }
 
     /// A palette maps values as classes to a certain color.
-    /// Unmapped values results in the NO DATA color
+    /// Unmapped values result in the default color and NaN results in the no data color
     pub fn palette(
         colors: HashMap<NotNan<f64>, RgbaColor>,
         no_data_color: RgbaColor,
@@ -127,6 +143,59 @@ impl Colorizer {
         Self::Rgba
     }
 
+    /// Sets dedicated colors for values below the first and above the last breakpoint of a gradient.
+    /// Without them, these values get the default color.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoengine_datatypes::operations::image::{Colorizer, RgbaColor};
+    /// use std::convert::TryInto;
+    ///
+    /// let colorizer = Colorizer::linear_gradient(
+    ///     vec![
+    ///         (0.0, RgbaColor::black()).try_into().unwrap(),
+    ///         (1.0, RgbaColor::white()).try_into().unwrap(),
+    ///     ],
+    ///     RgbaColor::transparent(),
+    ///     RgbaColor::pink(),
+    /// )
+    /// .unwrap()
+    /// .with_out_of_range_colors(RgbaColor::new(0, 0, 255, 255), RgbaColor::new(255, 0, 0, 255))
+    /// .unwrap();
+    ///
+    /// let color_mapper = colorizer.create_color_mapper();
+    ///
+    /// assert_eq!(color_mapper.call(-1.), RgbaColor::new(0, 0, 255, 255));
+    /// assert_eq!(color_mapper.call(2.), RgbaColor::new(255, 0, 0, 255));
+    /// assert_eq!(color_mapper.call(f64::NAN), RgbaColor::transparent());
+    /// ```
+    pub fn with_out_of_range_colors(
+        mut self,
+        under_color: RgbaColor,
+        over_color: RgbaColor,
+    ) -> Result<Self> {
+        match &mut self {
+            Self::LinearGradient {
+                under_color: under,
+                over_color: over,
+                ..
+            }
+            | Self::LogarithmicGradient {
+                under_color: under,
+                over_color: over,
+                ..
+            } => {
+                *under = Some(under_color);
+                *over = Some(over_color);
+                Ok(self)
+            }
+            Self::Palette { .. } | Self::Rgba => Err(error::Error::Colorizer {
+                details: "Only gradient colorizers have a range of values".to_string(),
+            }),
+        }
+    }
+
     /// Returns the minimum value that is covered by this colorizer
     ///
     /// # Examples
@@ -211,6 +280,110 @@ impl Colorizer {
         }
     }
 
+    /// Returns the color of values below the range of a gradient
+    pub fn under_color(&self) -> Option<RgbaColor> {
+        match self {
+            Colorizer::LinearGradient {
+                under_color,
+                default_color,
+                ..
+            }
+            | Colorizer::LogarithmicGradient {
+                under_color,
+                default_color,
+                ..
+            } => Some(under_color.unwrap_or(*default_color)),
+            Colorizer::Palette { .. } | Colorizer::Rgba => None,
+        }
+    }
+
+    /// Returns the color of values above the range of a gradient
+    pub fn over_color(&self) -> Option<RgbaColor> {
+        match self {
+            Colorizer::LinearGradient {
+                over_color,
+                default_color,
+                ..
+            }
+            | Colorizer::LogarithmicGradient {
+                over_color,
+                default_color,
+                ..
+            } => Some(over_color.unwrap_or(*default_color)),
+            Colorizer::Palette { .. } | Colorizer::Rgba => None,
+        }
+    }
+
+    /// Describes which values get which color, including no data and values out of range
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoengine_datatypes::operations::image::{Colorizer, LegendEntry, RgbaColor};
+    /// use std::convert::TryInto;
+    ///
+    /// let colorizer = Colorizer::linear_gradient(
+    ///     vec![
+    ///         (0.0, RgbaColor::black()).try_into().unwrap(),
+    ///         (1.0, RgbaColor::white()).try_into().unwrap(),
+    ///     ],
+    ///     RgbaColor::transparent(),
+    ///     RgbaColor::pink(),
+    /// ).unwrap();
+    ///
+    /// let legend = colorizer.legend();
+    ///
+    /// assert_eq!(
+    ///     legend.entries,
+    ///     vec![
+    ///         LegendEntry { value: 0.0, color: RgbaColor::black() },
+    ///         LegendEntry { value: 1.0, color: RgbaColor::white() },
+    ///     ]
+    /// );
+    /// assert_eq!(legend.no_data_color, RgbaColor::transparent());
+    /// assert_eq!(legend.under_color, Some(RgbaColor::pink()));
+    /// assert_eq!(legend.over_color, Some(RgbaColor::pink()));
+    /// assert_eq!(legend.default_color, None);
+    /// ```
+    pub fn legend(&self) -> Legend {
+        let entries = match self {
+            Colorizer::LinearGradient { breakpoints, .. }
+            | Colorizer::LogarithmicGradient { breakpoints, .. } => breakpoints
+                .iter()
+                .map(|breakpoint| LegendEntry {
+                    value: *breakpoint.value,
+                    color: breakpoint.color,
+                })
+                .collect(),
+            Colorizer::Palette { colors, .. } => {
+                let mut classes: Vec<(&NotNan<f64>, &RgbaColor)> = colors.0.iter().collect();
+                classes.sort_unstable_by_key(|(value, _)| **value);
+
+                classes
+                    .into_iter()
+                    .map(|(value, color)| LegendEntry {
+                        value: **value,
+                        color: *color,
+                    })
+                    .collect()
+            }
+            Colorizer::Rgba => Vec::new(),
+        };
+
+        let default_color = match self {
+            Colorizer::Palette { default_color, .. } => Some(*default_color),
+            _ => None,
+        };
+
+        Legend {
+            entries,
+            no_data_color: self.no_data_color(),
+            under_color: self.under_color(),
+            over_color: self.over_color(),
+            default_color,
+        }
+    }
+
     /// Creates a function for mapping raster values to colors
     ///
     /// # Examples
@@ -250,14 +423,18 @@ impl Colorizer {
 
         match self {
             Self::LinearGradient {
-                breakpoints: _,
                 no_data_color,
                 default_color,
+                under_color,
+                over_color,
+                ..
             }
             | Self::LogarithmicGradient {
-                breakpoints: _,
                 no_data_color,
                 default_color,
+                under_color,
+                over_color,
+                ..
             } => {
                 let color_table = self.color_table(COLOR_TABLE_SIZE, min_value, max_value);
 
@@ -266,7 +443,8 @@ impl Colorizer {
                     min_value,
                     max_value,
                     no_data_color: *no_data_color,
-                    default_color: *default_color,
+                    under_color: under_color.unwrap_or(*default_color),
+                    over_color: over_color.unwrap_or(*default_color),
                 }
             }
             Self::Palette {
@@ -354,7 +532,8 @@ pub enum ColorMapper<'c> {
         min_value: f64,
         max_value: f64,
         no_data_color: RgbaColor,
-        default_color: RgbaColor,
+        under_color: RgbaColor,
+        over_color: RgbaColor,
     },
     ColorMap {
         color_map: &'c Palette,
@@ -366,7 +545,8 @@ pub enum ColorMapper<'c> {
 
 // TODO: use Fn-trait once it is stable
 impl<'c> ColorMapper<'c> {
-    /// Map a raster value to a color from the colorizer
+    /// Map a raster value to a color from the colorizer.
+    /// NaN values get the no data color.
     pub fn call<T>(&self, value: T) -> RgbaColor
     where
         T: Pixel + RgbaTransmutable,
@@ -377,19 +557,22 @@ impl<'c> ColorMapper<'c> {
                 min_value,
                 max_value,
                 no_data_color,
-                default_color,
+                under_color,
+                over_color,
             } => {
                 let value: f64 = value.as_();
                 if f64::is_nan(value) {
                     *no_data_color
-                } else if value < *min_value || value > *max_value {
-                    *default_color
+                } else if value < *min_value {
+                    *under_color
+                } else if value > *max_value {
+                    *over_color
                 } else {
                     let color_table_factor = (color_table.len() - 1) as f64;
                     let table_entry = f64::round(
                         color_table_factor * ((value - *min_value) / (*max_value - *min_value)),
                     ) as usize;
-                    *color_table.get(table_entry).unwrap_or(default_color)
+                    *color_table.get(table_entry).unwrap_or(over_color)
                 }
             }
             ColorMapper::ColorMap {
@@ -406,6 +589,47 @@ impl<'c> ColorMapper<'c> {
             ColorMapper::Rgba => value.transmute_to_rgba(),
         }
     }
+
+    /// Map a raster value to a color from the colorizer.
+    /// `None` stands for a pixel that is no data or masked out and gets the no data color.
+    pub fn call_masked<T>(&self, value: Option<T>) -> RgbaColor
+    where
+        T: Pixel + RgbaTransmutable,
+    {
+        match value {
+            Some(value) => self.call(value),
+            None => self.no_data_color(),
+        }
+    }
+
+    pub fn no_data_color(&self) -> RgbaColor {
+        match self {
+            ColorMapper::ColorTable { no_data_color, .. }
+            | ColorMapper::ColorMap { no_data_color, .. } => *no_data_color,
+            ColorMapper::Rgba => RgbaColor::transparent(),
+        }
+    }
+}
+
+/// Explains the colors of a `Colorizer`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Legend {
+    /// The breakpoints of a gradient or the classes of a palette, ordered ascending by value
+    pub entries: Vec<LegendEntry>,
+    pub no_data_color: RgbaColor,
+    /// The color of values below the range of a gradient
+    pub under_color: Option<RgbaColor>,
+    /// The color of values above the range of a gradient
+    pub over_color: Option<RgbaColor>,
+    /// The color of values that are not a class of a palette
+    pub default_color: Option<RgbaColor>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LegendEntry {
+    pub value: f64,
+    pub color: RgbaColor,
 }
 
 /// A container type for breakpoints that specify a value to color mapping
@@ -681,4 +905,136 @@ mod tests {
             colorizer
         );
     }
+
+    #[test]
+    fn out_of_range_colors() {
+        let under = RgbaColor::new(0, 0, 255, 255);
+        let over = RgbaColor::new(255, 0, 0, 255);
+
+        let colorizer = Colorizer::linear_gradient(
+            vec![
+                (1.0, RgbaColor::black()).try_into().unwrap(),
+                (2.0, RgbaColor::white()).try_into().unwrap(),
+            ],
+            RgbaColor::transparent(),
+            RgbaColor::pink(),
+        )
+        .unwrap()
+        .with_out_of_range_colors(under, over)
+        .unwrap();
+
+        let color_mapper = colorizer.create_color_mapper();
+
+        assert_eq!(color_mapper.call(0.5), under);
+        assert_eq!(color_mapper.call(1.0), RgbaColor::black());
+        assert_eq!(color_mapper.call(2.0), RgbaColor::white());
+        assert_eq!(color_mapper.call(2.5), over);
+        assert_eq!(color_mapper.call(f64::NAN), RgbaColor::transparent());
+        assert_eq!(color_mapper.call_masked(Some(1.0)), RgbaColor::black());
+        assert_eq!(
+            color_mapper.call_masked::<f64>(None),
+            RgbaColor::transparent()
+        );
+
+        let serialized_colorizer = serde_json::to_value(&colorizer).unwrap();
+        assert_eq!(
+            serialized_colorizer,
+            serde_json::json!({
+                "type": "linearGradient",
+                "breakpoints": [{
+                    "value": 1.0,
+                    "color": [0, 0, 0, 255]
+                }, {
+                    "value": 2.0,
+                    "color": [255, 255, 255, 255]
+                }],
+                "noDataColor": [0, 0, 0, 0],
+                "defaultColor": [255, 0, 255, 255],
+                "underColor": [0, 0, 255, 255],
+                "overColor": [255, 0, 0, 255]
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<Colorizer>(serialized_colorizer).unwrap(),
+            colorizer
+        );
+    }
+
+    #[test]
+    fn out_of_range_colors_default_to_default_color() {
+        let colorizer = Colorizer::logarithmic_gradient(
+            vec![
+                (1.0, RgbaColor::black()).try_into().unwrap(),
+                (10.0, RgbaColor::white()).try_into().unwrap(),
+            ],
+            RgbaColor::transparent(),
+            RgbaColor::pink(),
+        )
+        .unwrap();
+
+        let color_mapper = colorizer.create_color_mapper();
+
+        assert_eq!(color_mapper.call(0.5), RgbaColor::pink());
+        assert_eq!(color_mapper.call(11.), RgbaColor::pink());
+        assert_eq!(colorizer.under_color(), Some(RgbaColor::pink()));
+        assert_eq!(colorizer.over_color(), Some(RgbaColor::pink()));
+    }
+
+    #[test]
+    fn palette_has_no_range() {
+        let colorizer = Colorizer::palette(
+            [(1.0.try_into().unwrap(), RgbaColor::white())]
+                .iter()
+                .copied()
+                .collect(),
+            RgbaColor::transparent(),
+            RgbaColor::pink(),
+        )
+        .unwrap();
+
+        assert!(colorizer
+            .with_out_of_range_colors(RgbaColor::black(), RgbaColor::white())
+            .is_err());
+    }
+
+    #[test]
+    fn palette_legend() {
+        let colorizer = Colorizer::palette(
+            [
+                (3.0.try_into().unwrap(), RgbaColor::black()),
+                (1.0.try_into().unwrap(), RgbaColor::white()),
+                (2.0.try_into().unwrap(), RgbaColor::pink()),
+            ]
+            .iter()
+            .copied()
+            .collect(),
+            RgbaColor::transparent(),
+            RgbaColor::new(1, 2, 3, 4),
+        )
+        .unwrap();
+
+        assert_eq!(
+            colorizer.legend(),
+            Legend {
+                entries: vec![
+                    LegendEntry {
+                        value: 1.0,
+                        color: RgbaColor::white()
+                    },
+                    LegendEntry {
+                        value: 2.0,
+                        color: RgbaColor::pink()
+                    },
+                    LegendEntry {
+                        value: 3.0,
+                        color: RgbaColor::black()
+                    },
+                ],
+                no_data_color: RgbaColor::transparent(),
+                under_color: None,
+                over_color: None,
+                default_color: Some(RgbaColor::new(1, 2, 3, 4)),
+            }
+        );
+    }
 }
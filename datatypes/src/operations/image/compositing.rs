@@ -0,0 +1,132 @@
+use crate::error;
+use crate::util::Result;
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+
+/// Composites PNG images with the same dimensions into a single PNG image.
+///
+/// The `layers` are pairs of PNG bytes and an opacity between `0` and `1` that scales the alpha channel of the layer.
+/// The first layer is at the bottom and every following layer is alpha-composited over the previous ones.
+pub fn composite_pngs(layers: &[(Vec<u8>, f64)]) -> Result<Vec<u8>> {
+    let mut layers = layers.iter();
+
+    let (bottom, opacity) = layers
+        .next()
+        .ok_or_else(|| error::Error::ImageCompositing {
+            details: "there are no layers".to_string(),
+        })?;
+
+    let mut composite = decode_png(bottom)?;
+    for pixel in composite.pixels_mut() {
+        *pixel = over(Rgba([0, 0, 0, 0]), *pixel, *opacity);
+    }
+
+    for (layer, opacity) in layers {
+        let layer = decode_png(layer)?;
+
+        if layer.dimensions() != composite.dimensions() {
+            return Err(error::Error::ImageCompositing {
+                details: format!(
+                    "the layer dimensions {:?} differ from {:?}",
+                    layer.dimensions(),
+                    composite.dimensions()
+                ),
+            });
+        }
+
+        for (pixel, layer_pixel) in composite.pixels_mut().zip(layer.pixels()) {
+            *pixel = over(*pixel, *layer_pixel, *opacity);
+        }
+    }
+
+    let mut buffer = Vec::new();
+
+    DynamicImage::ImageRgba8(composite)
+        .write_to(&mut buffer, ImageFormat::Png)
+        .map_err(|error| error::Error::ImageCompositing {
+            details: format!("encoding PNG failed: {}", error),
+        })?;
+
+    Ok(buffer)
+}
+
+fn decode_png(bytes: &[u8]) -> Result<RgbaImage> {
+    image::load_from_memory_with_format(bytes, ImageFormat::Png)
+        .map(|image| image.to_rgba8())
+        .map_err(|error| error::Error::ImageCompositing {
+            details: format!("decoding PNG failed: {}", error),
+        })
+}
+
+/// Places the `top` pixel with its alpha scaled by `opacity` over the `bottom` pixel
+fn over(bottom: Rgba<u8>, top: Rgba<u8>, opacity: f64) -> Rgba<u8> {
+    let top_alpha = f64::from(top[3]) / 255. * opacity.max(0.).min(1.);
+    let bottom_alpha = f64::from(bottom[3]) / 255. * (1. - top_alpha);
+    let alpha = top_alpha + bottom_alpha;
+
+    if alpha <= 0. {
+        return Rgba([0, 0, 0, 0]);
+    }
+
+    let channel = |i: usize| {
+        ((f64::from(top[i]) * top_alpha + f64::from(bottom[i]) * bottom_alpha) / alpha).round()
+            as u8
+    };
+
+    Rgba([
+        channel(0),
+        channel(1),
+        channel(2),
+        (alpha * 255.).round() as u8,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::image::{Colorizer, RgbaColor, ToPng};
+    use crate::raster::Grid2D;
+    use std::convert::TryInto;
+
+    /// A PNG whose left half has the `color` and whose right half is transparent
+    fn png(color: RgbaColor, width: u32) -> Vec<u8> {
+        let colorizer = Colorizer::linear_gradient(
+            vec![
+                (0.0, color).try_into().unwrap(),
+                (1.0, color).try_into().unwrap(),
+            ],
+            RgbaColor::transparent(),
+            RgbaColor::transparent(),
+        )
+        .unwrap();
+
+        Grid2D::new([1, 2].into(), vec![1_u8, 0], Some(0))
+            .unwrap()
+            .to_png(width, 1, &colorizer)
+            .unwrap()
+    }
+
+    #[test]
+    fn composites_in_order() {
+        let red = png(RgbaColor::new(255, 0, 0, 255), 2);
+        let blue = png(RgbaColor::new(0, 0, 255, 255), 2);
+
+        let composite =
+            decode_png(&composite_pngs(&[(red.clone(), 1.), (blue, 0.5)]).unwrap()).unwrap();
+
+        assert_eq!(composite.get_pixel(0, 0), &Rgba([128, 0, 128, 255]));
+        assert_eq!(composite.get_pixel(1, 0), &Rgba([0, 0, 0, 0]));
+
+        let composite = decode_png(&composite_pngs(&[(red, 0.5)]).unwrap()).unwrap();
+
+        assert_eq!(composite.get_pixel(0, 0), &Rgba([255, 0, 0, 128]));
+    }
+
+    #[test]
+    fn rejects_different_dimensions() {
+        let small = png(RgbaColor::black(), 2);
+        let large = png(RgbaColor::black(), 4);
+
+        assert!(composite_pngs(&[(small, 1.), (large, 1.)]).is_err());
+        assert!(composite_pngs(&[]).is_err());
+    }
+}
@@ -1,9 +1,11 @@
 mod colorizer;
+mod compositing;
 mod into_lossy;
 mod rgba_transmutable;
 mod to_png;
 
-pub use colorizer::{Breakpoints, Colorizer, RgbaColor};
+pub use colorizer::{Breakpoints, Colorizer, Legend, LegendEntry, RgbaColor};
+pub use compositing::composite_pngs;
 pub use into_lossy::LossyInto;
 pub use rgba_transmutable::RgbaTransmutable;
 pub use to_png::ToPng;
@@ -94,13 +94,11 @@ fn create_rgba_image<P: Pixel + RgbaTransmutable, N: Fn(P) -> bool>(
     RgbaImage::from_fn(width, height, |x, y| {
         let (grid_pixel_x, grid_pixel_y) = image_pixel_to_raster_pixel(x, y, scale_x, scale_y);
         if let Ok(pixel_value) = raster_grid.get_at_grid_index([grid_pixel_y, grid_pixel_x]) {
-            if is_no_data(pixel_value) {
-                return colorizer.no_data_color().into();
-            }
+            let valid_value = Some(pixel_value).filter(|&value| !is_no_data(value));
 
-            color_mapper.call(pixel_value)
+            color_mapper.call_masked(valid_value)
         } else {
-            colorizer.no_data_color()
+            color_mapper.no_data_color()
         }
         .into()
     })
@@ -156,6 +154,7 @@ mod tests {
     use super::*;
     use crate::operations::image::RgbaColor;
     use crate::raster::GridIndexAccessMut;
+    use crate::util::test::{assert_image_eq, ImageTolerance};
     use std::convert::TryInto;
 
     #[test]
@@ -179,9 +178,10 @@ mod tests {
 
         let image_bytes = raster.to_png(100, 100, &colorizer).unwrap();
 
-        assert_eq!(
-            include_bytes!("../../../test-data/colorizer/linear_gradient.png") as &[u8],
-            image_bytes.as_slice()
+        assert_image_eq(
+            include_bytes!("../../../test-data/colorizer/linear_gradient.png"),
+            image_bytes.as_slice(),
+            ImageTolerance::default(),
         );
     }
 
@@ -206,9 +206,10 @@ mod tests {
 
         let image_bytes = raster.to_png(100, 100, &colorizer).unwrap();
 
-        assert_eq!(
-            include_bytes!("../../../test-data/colorizer/logarithmic_gradient.png") as &[u8],
-            image_bytes.as_slice()
+        assert_image_eq(
+            include_bytes!("../../../test-data/colorizer/logarithmic_gradient.png"),
+            image_bytes.as_slice(),
+            ImageTolerance::default(),
         );
     }
 
@@ -235,9 +236,10 @@ mod tests {
 
         let image_bytes = raster.to_png(100, 100, &colorizer).unwrap();
 
-        assert_eq!(
-            include_bytes!("../../../test-data/colorizer/palette.png") as &[u8],
-            image_bytes.as_slice()
+        assert_image_eq(
+            include_bytes!("../../../test-data/colorizer/palette.png"),
+            image_bytes.as_slice(),
+            ImageTolerance::default(),
         );
     }
 
@@ -252,9 +254,10 @@ mod tests {
 
         let image_bytes = raster.to_png(100, 100, &colorizer).unwrap();
 
-        assert_eq!(
-            include_bytes!("../../../test-data/colorizer/rgba.png") as &[u8],
-            image_bytes.as_slice()
+        assert_image_eq(
+            include_bytes!("../../../test-data/colorizer/rgba.png"),
+            image_bytes.as_slice(),
+            ImageTolerance::default(),
         );
     }
 
@@ -276,9 +279,10 @@ mod tests {
 
         let image_bytes = raster.to_png(100, 100, &colorizer).unwrap();
 
-        assert_eq!(
-            include_bytes!("../../../test-data/colorizer/no_data.png") as &[u8],
-            image_bytes.as_slice()
+        assert_image_eq(
+            include_bytes!("../../../test-data/colorizer/no_data.png"),
+            image_bytes.as_slice(),
+            ImageTolerance::default(),
         );
     }
 
@@ -300,9 +304,10 @@ mod tests {
 
         let image_bytes = raster.to_png(100, 100, &colorizer).unwrap();
 
-        assert_eq!(
-            include_bytes!("../../../test-data/colorizer/empty.png") as &[u8],
-            image_bytes.as_slice()
+        assert_image_eq(
+            include_bytes!("../../../test-data/colorizer/empty.png"),
+            image_bytes.as_slice(),
+            ImageTolerance::default(),
         );
     }
 }
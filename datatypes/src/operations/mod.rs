@@ -1,2 +1,4 @@
 pub mod image;
+pub mod representative_point;
 pub mod reproject;
+pub mod simplify;
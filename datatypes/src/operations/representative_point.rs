@@ -0,0 +1,450 @@
+use crate::{
+    collections::{
+        IntoGeometryIterator, MultiLineStringCollection, MultiPointCollection,
+        MultiPolygonCollection,
+    },
+    primitives::{
+        Coordinate2D, MultiLineString, MultiLineStringAccess, MultiLineStringRef, MultiPoint,
+        MultiPolygon, MultiPolygonAccess, MultiPolygonRef,
+    },
+    util::Result,
+};
+
+/// Points that represent line and polygon geometries, e.g., for labeling or point-based joins
+pub trait RepresentativePoint {
+    type Out;
+
+    /// The center of mass of the polygon areas or the line lengths.
+    /// It may lie outside of the geometry, e.g., for concave polygons.
+    fn centroid(&self) -> Result<Self::Out>;
+
+    /// A point that lies in the interior of the largest polygon or on a vertex of the lines
+    fn point_on_surface(&self) -> Result<Self::Out>;
+}
+
+impl RepresentativePoint for MultiLineString {
+    type Out = MultiPoint;
+
+    fn centroid(&self) -> Result<MultiPoint> {
+        MultiPoint::new(vec![line_centroid(self.lines())])
+    }
+
+    fn point_on_surface(&self) -> Result<MultiPoint> {
+        MultiPoint::new(vec![line_point_on_surface(self.lines())])
+    }
+}
+
+impl<'g> RepresentativePoint for MultiLineStringRef<'g> {
+    type Out = MultiPoint;
+
+    fn centroid(&self) -> Result<MultiPoint> {
+        MultiPoint::new(vec![line_centroid(self.lines())])
+    }
+
+    fn point_on_surface(&self) -> Result<MultiPoint> {
+        MultiPoint::new(vec![line_point_on_surface(self.lines())])
+    }
+}
+
+impl RepresentativePoint for MultiPolygon {
+    type Out = MultiPoint;
+
+    fn centroid(&self) -> Result<MultiPoint> {
+        MultiPoint::new(vec![polygon_centroid(self)])
+    }
+
+    fn point_on_surface(&self) -> Result<MultiPoint> {
+        MultiPoint::new(vec![polygon_point_on_surface(self)])
+    }
+}
+
+impl<'g> RepresentativePoint for MultiPolygonRef<'g> {
+    type Out = MultiPoint;
+
+    fn centroid(&self) -> Result<MultiPoint> {
+        MultiPoint::new(vec![polygon_centroid(self)])
+    }
+
+    fn point_on_surface(&self) -> Result<MultiPoint> {
+        MultiPoint::new(vec![polygon_point_on_surface(self)])
+    }
+}
+
+impl RepresentativePoint for MultiLineStringCollection {
+    type Out = MultiPointCollection;
+
+    fn centroid(&self) -> Result<MultiPointCollection> {
+        let points = self
+            .geometries()
+            .map(|geometry| geometry.centroid())
+            .collect::<Result<Vec<_>>>()?;
+
+        self.with_geometries(points)
+    }
+
+    fn point_on_surface(&self) -> Result<MultiPointCollection> {
+        let points = self
+            .geometries()
+            .map(|geometry| geometry.point_on_surface())
+            .collect::<Result<Vec<_>>>()?;
+
+        self.with_geometries(points)
+    }
+}
+
+impl RepresentativePoint for MultiPolygonCollection {
+    type Out = MultiPointCollection;
+
+    fn centroid(&self) -> Result<MultiPointCollection> {
+        let points = self
+            .geometries()
+            .map(|geometry| geometry.centroid())
+            .collect::<Result<Vec<_>>>()?;
+
+        self.with_geometries(points)
+    }
+
+    fn point_on_surface(&self) -> Result<MultiPointCollection> {
+        let points = self
+            .geometries()
+            .map(|geometry| geometry.point_on_surface())
+            .collect::<Result<Vec<_>>>()?;
+
+        self.with_geometries(points)
+    }
+}
+
+/// The length-weighted mean of the segment midpoints or the mean of the vertices if all lines have no length
+fn line_centroid<L: AsRef<[Coordinate2D]>>(lines: &[L]) -> Coordinate2D {
+    // coordinates relative to the first vertex keep the sums small
+    let origin = first_coordinate(lines);
+
+    let mut length = 0.;
+    let mut weighted_sum = Coordinate2D::new(0., 0.);
+
+    for line in lines {
+        for segment in line.as_ref().windows(2) {
+            let (start, end) = (segment[0] - origin, segment[1] - origin);
+            let difference = end - start;
+            let segment_length = (difference.x * difference.x + difference.y * difference.y).sqrt();
+
+            length += segment_length;
+            weighted_sum = weighted_sum + (start + end) * (segment_length / 2.);
+        }
+    }
+
+    if length > 0. {
+        origin + weighted_sum / length
+    } else {
+        vertex_mean(lines.iter().flat_map(|line| line.as_ref().iter().copied()))
+    }
+}
+
+/// The vertex that is closest to the centroid
+fn line_point_on_surface<L: AsRef<[Coordinate2D]>>(lines: &[L]) -> Coordinate2D {
+    let centroid = line_centroid(lines);
+
+    lines
+        .iter()
+        .flat_map(|line| line.as_ref().iter().copied())
+        .fold(None, |closest: Option<(Coordinate2D, f64)>, vertex| {
+            let difference = vertex - centroid;
+            let distance = difference.x * difference.x + difference.y * difference.y;
+
+            match closest {
+                Some((_, min_distance)) if min_distance <= distance => closest,
+                _ => Some((vertex, distance)),
+            }
+        })
+        .map_or(centroid, |(vertex, _)| vertex)
+}
+
+/// The area-weighted centroid of the polygons, where holes have negative weights.
+/// Polygons without area are treated as lines.
+fn polygon_centroid<M: MultiPolygonAccess>(multi_polygon: &M) -> Coordinate2D {
+    let origin = multi_polygon
+        .polygons()
+        .iter()
+        .find_map(|polygon| {
+            polygon
+                .as_ref()
+                .first()
+                .and_then(|exterior| exterior.as_ref().first().copied())
+        })
+        .unwrap_or_else(|| Coordinate2D::new(0., 0.));
+
+    let mut area = 0.;
+    let mut weighted_sum = Coordinate2D::new(0., 0.);
+
+    for polygon in multi_polygon.polygons() {
+        for (i, ring) in polygon.as_ref().iter().enumerate() {
+            let (ring_area, ring_centroid) = ring_area_and_centroid(ring.as_ref(), origin);
+
+            // the first ring is the exterior, all others are holes
+            let ring_area = if i == 0 {
+                ring_area.abs()
+            } else {
+                -ring_area.abs()
+            };
+
+            area += ring_area;
+            weighted_sum = weighted_sum + ring_centroid * ring_area;
+        }
+    }
+
+    if area.abs() > 0. {
+        return origin + weighted_sum / area;
+    }
+
+    let rings: Vec<&[Coordinate2D]> = multi_polygon
+        .polygons()
+        .iter()
+        .flat_map(|polygon| polygon.as_ref().iter().map(|ring| ring.as_ref()))
+        .collect();
+
+    line_centroid(&rings)
+}
+
+/// A point in the middle of the widest interior section of a horizontal line through the largest polygon
+fn polygon_point_on_surface<M: MultiPolygonAccess>(multi_polygon: &M) -> Coordinate2D {
+    let largest_polygon = multi_polygon
+        .polygons()
+        .iter()
+        .map(|polygon| {
+            let origin = first_coordinate(polygon.as_ref());
+            let area: f64 = polygon
+                .as_ref()
+                .iter()
+                .enumerate()
+                .map(|(i, ring)| {
+                    let area = ring_area_and_centroid(ring.as_ref(), origin).0.abs();
+                    if i == 0 {
+                        area
+                    } else {
+                        -area
+                    }
+                })
+                .sum();
+            (polygon, area)
+        })
+        .fold(
+            None,
+            |largest: Option<(&M::R, f64)>, (polygon, area)| match largest {
+                Some((_, max_area)) if max_area >= area => largest,
+                _ => Some((polygon, area)),
+            },
+        );
+
+    let (polygon, area) = match largest_polygon {
+        Some(largest_polygon) => largest_polygon,
+        None => return Coordinate2D::new(0., 0.),
+    };
+
+    let rings = polygon.as_ref();
+
+    if area <= 0. {
+        return line_point_on_surface(rings);
+    }
+
+    let exterior = rings[0].as_ref();
+    let (min_y, max_y) = exterior
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min_y, max_y), c| {
+            (min_y.min(c.y), max_y.max(c.y))
+        });
+    let y = (min_y + max_y) / 2.;
+
+    // a vertex on the scan line counts as below it, s.t. each ring is crossed an even number of times
+    let mut crossings: Vec<f64> = rings
+        .iter()
+        .flat_map(|ring| ring.as_ref().windows(2))
+        .filter(|segment| (segment[0].y > y) != (segment[1].y > y))
+        .map(|segment| {
+            let (start, end) = (segment[0], segment[1]);
+            start.x + (y - start.y) * (end.x - start.x) / (end.y - start.y)
+        })
+        .collect();
+
+    crossings.sort_by(|a, b| a.partial_cmp(b).expect("finite coordinates"));
+
+    crossings
+        .chunks_exact(2)
+        .map(|section| (section[0], section[1]))
+        .fold(
+            None,
+            |widest: Option<(f64, f64)>, (start, end)| match widest {
+                Some((widest_start, widest_end)) if widest_end - widest_start >= end - start => {
+                    widest
+                }
+                _ => Some((start, end)),
+            },
+        )
+        .map_or_else(
+            || line_point_on_surface(rings),
+            |(start, end)| Coordinate2D::new((start + end) / 2., y),
+        )
+}
+
+/// The signed area and the centroid of a closed ring w.r.t. `origin`
+fn ring_area_and_centroid(ring: &[Coordinate2D], origin: Coordinate2D) -> (f64, Coordinate2D) {
+    let mut twice_area = 0.;
+    let mut weighted_sum = Coordinate2D::new(0., 0.);
+
+    for segment in ring.windows(2) {
+        let (a, b) = (segment[0] - origin, segment[1] - origin);
+        let cross = a.x * b.y - b.x * a.y;
+
+        twice_area += cross;
+        weighted_sum = weighted_sum + (a + b) * cross;
+    }
+
+    if twice_area == 0. {
+        return (0., Coordinate2D::new(0., 0.));
+    }
+
+    (twice_area / 2., weighted_sum / (3. * twice_area))
+}
+
+fn first_coordinate<L: AsRef<[Coordinate2D]>>(lines: &[L]) -> Coordinate2D {
+    lines
+        .iter()
+        .find_map(|line| line.as_ref().first().copied())
+        .unwrap_or_else(|| Coordinate2D::new(0., 0.))
+}
+
+fn vertex_mean<I: Iterator<Item = Coordinate2D>>(vertices: I) -> Coordinate2D {
+    let (sum, count) = vertices.fold((Coordinate2D::new(0., 0.), 0_usize), |(sum, count), c| {
+        (sum + c, count + 1)
+    });
+
+    if count == 0 {
+        sum
+    } else {
+        sum / count as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::FeatureCollectionInfos;
+    use crate::primitives::{FeatureData, MultiPointAccess, TimeInterval};
+    use float_cmp::approx_eq;
+
+    fn assert_point(multi_point: &MultiPoint, expected: (f64, f64)) {
+        let point = multi_point.points()[0];
+
+        assert_eq!(multi_point.points().len(), 1);
+        assert!(
+            approx_eq!(f64, point.x, expected.0, epsilon = 1e-9)
+                && approx_eq!(f64, point.y, expected.1, epsilon = 1e-9),
+            "{:?} != {:?}",
+            point,
+            expected
+        );
+    }
+
+    fn u_shape() -> MultiPolygon {
+        MultiPolygon::new(vec![vec![vec![
+            (0., 0.).into(),
+            (3., 0.).into(),
+            (3., 3.).into(),
+            (2., 3.).into(),
+            (2., 1.).into(),
+            (1., 1.).into(),
+            (1., 3.).into(),
+            (0., 3.).into(),
+            (0., 0.).into(),
+        ]]])
+        .unwrap()
+    }
+
+    #[test]
+    fn polygon_centroid_may_lie_outside() {
+        // 7 unit squares: 3 at y = 0.5, 2 at y = 1.5 and 2 at y = 2.5
+        assert_point(&u_shape().centroid().unwrap(), (1.5, 9.5 / 7.));
+    }
+
+    #[test]
+    fn point_on_surface_is_interior() {
+        assert_point(&u_shape().point_on_surface().unwrap(), (0.5, 1.5));
+    }
+
+    #[test]
+    fn holes_and_multiple_polygons() {
+        let polygons = MultiPolygon::new(vec![
+            vec![
+                vec![
+                    (0., 0.).into(),
+                    (4., 0.).into(),
+                    (4., 4.).into(),
+                    (0., 4.).into(),
+                    (0., 0.).into(),
+                ],
+                vec![
+                    (1., 1.).into(),
+                    (3., 1.).into(),
+                    (3., 3.).into(),
+                    (1., 3.).into(),
+                    (1., 1.).into(),
+                ],
+            ],
+            vec![vec![
+                (10., 0.).into(),
+                (11., 0.).into(),
+                (11., 1.).into(),
+                (10., 1.).into(),
+                (10., 0.).into(),
+            ]],
+        ])
+        .unwrap();
+
+        // a ring of area 12 around (2, 2) and a square of area 1 around (10.5, 0.5)
+        assert_point(
+            &polygons.centroid().unwrap(),
+            ((12. * 2. + 10.5) / 13., (12. * 2. + 0.5) / 13.),
+        );
+        assert_point(&polygons.point_on_surface().unwrap(), (0.5, 2.));
+    }
+
+    #[test]
+    fn lines() {
+        let lines = MultiLineString::new(vec![
+            vec![(0., 0.).into(), (2., 0.).into()],
+            vec![(0., 1.).into(), (0., 3.).into()],
+        ])
+        .unwrap();
+
+        assert_point(&lines.centroid().unwrap(), (0.5, 1.));
+        assert_point(&lines.point_on_surface().unwrap(), (0., 1.));
+    }
+
+    #[test]
+    fn collections_keep_attributes() {
+        let collection = MultiPolygonCollection::from_data(
+            vec![u_shape()],
+            vec![TimeInterval::new_unchecked(0, 1)],
+            [("name".to_string(), FeatureData::Text(vec!["u".to_string()]))]
+                .iter()
+                .cloned()
+                .collect(),
+        )
+        .unwrap();
+
+        let points = collection.point_on_surface().unwrap();
+
+        assert_eq!(
+            points,
+            MultiPointCollection::from_data(
+                vec![MultiPoint::new(vec![(0.5, 1.5).into()]).unwrap()],
+                vec![TimeInterval::new_unchecked(0, 1)],
+                [("name".to_string(), FeatureData::Text(vec!["u".to_string()]))]
+                    .iter()
+                    .cloned()
+                    .collect(),
+            )
+            .unwrap()
+        );
+        assert_eq!(points.len(), collection.len());
+    }
+}
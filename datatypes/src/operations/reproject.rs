@@ -1,5 +1,7 @@
 use num_traits::Zero;
-use proj::Proj;
+use proj::{Area, Proj};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use snafu::ensure;
 
 use crate::{
@@ -30,20 +32,101 @@ pub trait CoordinateProjection {
     fn target_srs(&self) -> SpatialReference;
 }
 
+/// Selects the transformation between two spatial references instead of the one PROJ picks by default, e.g., for
+/// controlling the datum shift between NAD27 and WGS84
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum CoordinateTransformation {
+    /// A PROJ pipeline, e.g., `+proj=pipeline +step ...`, that transforms from the source to the target spatial
+    /// reference. It is used as is, so it must take care of unit conversions and axis orders itself.
+    Pipeline { definition: String },
+    /// The area of interest in WGS84 longitude/latitude. PROJ picks the most accurate transformation that is
+    /// available for this area.
+    AreaOfInterest { bounds: BoundingBox2D },
+}
+
 pub struct CoordinateProjector {
     pub from: SpatialReference,
     pub to: SpatialReference,
+    transformation: Option<CoordinateTransformation>,
+    /// whether the `transformation` pipeline is applied in inverse direction
+    inverse: bool,
     p: Proj,
 }
 
+impl CoordinateProjector {
+    /// Creates a projector that uses the given `transformation` from `from` to `to` or PROJ's default if it is `None`
+    pub fn from_known_srs_with_transformation(
+        from: SpatialReference,
+        to: SpatialReference,
+        transformation: Option<CoordinateTransformation>,
+    ) -> Result<Self> {
+        let p = Self::create_proj(from, to, transformation.as_ref())?;
+
+        Ok(CoordinateProjector {
+            from,
+            to,
+            transformation,
+            inverse: false,
+            p,
+        })
+    }
+
+    /// Creates a projector from `to` to `from` that uses the same transformation
+    pub fn inverse(&self) -> Result<Self> {
+        Ok(CoordinateProjector {
+            from: self.to,
+            to: self.from,
+            transformation: self.transformation.clone(),
+            inverse: !self.inverse,
+            p: Self::create_proj(self.to, self.from, self.transformation.as_ref())?,
+        })
+    }
+
+    fn create_proj(
+        from: SpatialReference,
+        to: SpatialReference,
+        transformation: Option<&CoordinateTransformation>,
+    ) -> Result<Proj> {
+        let p = match transformation {
+            None => Proj::new_known_crs(&from.proj_string()?, &to.proj_string()?, None),
+            Some(CoordinateTransformation::AreaOfInterest { bounds }) => Proj::new_known_crs(
+                &from.proj_string()?,
+                &to.proj_string()?,
+                Some(Area::new(
+                    bounds.lower_left().x,
+                    bounds.lower_left().y,
+                    bounds.upper_right().x,
+                    bounds.upper_right().y,
+                )),
+            ),
+            // a pipeline is always created in its given direction, cf. `is_inverse_pipeline`
+            Some(CoordinateTransformation::Pipeline { definition }) => Proj::new(definition),
+        };
+
+        p.ok_or(error::Error::NoCoordinateProjector { from, to })
+    }
+
+    /// Pipelines are applied in their given direction, so the inverse direction must be requested explicitly
+    fn is_inverse_pipeline(&self) -> bool {
+        self.inverse
+            && matches!(
+                self.transformation,
+                Some(CoordinateTransformation::Pipeline { .. })
+            )
+    }
+}
+
 impl CoordinateProjection for CoordinateProjector {
     fn from_known_srs(from: SpatialReference, to: SpatialReference) -> Result<Self> {
-        let p = Proj::new_known_crs(&from.proj_string()?, &to.proj_string()?, None)
-            .ok_or(error::Error::NoCoordinateProjector { from, to })?;
-        Ok(CoordinateProjector { from, to, p })
+        Self::from_known_srs_with_transformation(from, to, None)
     }
 
     fn project_coordinate(&self, c: Coordinate2D) -> Result<Coordinate2D> {
+        if self.is_inverse_pipeline() {
+            return self.p.project(c, true).map_err(Into::into);
+        }
+
         self.p.convert(c).map_err(Into::into)
     }
 
@@ -53,6 +136,13 @@ impl CoordinateProjection for CoordinateProjector {
     ) -> Result<Vec<Coordinate2D>> {
         let c_ref = coords.as_ref();
 
+        if self.is_inverse_pipeline() {
+            return c_ref
+                .iter()
+                .map(|&c| self.p.project(c, true).map_err(Into::into))
+                .collect();
+        }
+
         let mut cc = Vec::from(c_ref);
         self.p.convert_array(&mut cc)?;
 
@@ -73,7 +163,9 @@ impl Clone for CoordinateProjector {
         CoordinateProjector {
             from: self.from,
             to: self.to,
-            p: Proj::new_known_crs(&self.from.to_string(), &self.to.to_string(), None)
+            transformation: self.transformation.clone(),
+            inverse: self.inverse,
+            p: Self::create_proj(self.from, self.to, self.transformation.as_ref())
                 .expect("worked before"),
         }
     }
@@ -515,6 +607,85 @@ mod tests {
         assert!(approx_eq!(f64, rp.y, MARBURG_EPSG_900_913.y));
     }
 
+    #[test]
+    fn proj_coordinate_with_area_of_interest() {
+        let from = SpatialReference::epsg_4326();
+        let to = SpatialReference::new(SpatialReferenceAuthority::Epsg, 900_913);
+        let p = CoordinateProjector::from_known_srs_with_transformation(
+            from,
+            to,
+            Some(CoordinateTransformation::AreaOfInterest {
+                bounds: BoundingBox2D::new((5.9, 47.3).into(), (15.0, 55.1).into()).unwrap(),
+            }),
+        )
+        .unwrap();
+        let rp = p.project_coordinate(MARBURG_EPSG_4326).unwrap();
+
+        assert!(approx_eq!(f64, rp.x, MARBURG_EPSG_900_913.x));
+        assert!(approx_eq!(f64, rp.y, MARBURG_EPSG_900_913.y));
+    }
+
+    #[test]
+    fn proj_coordinates_with_pipeline() {
+        let from = SpatialReference::epsg_4326();
+        let to = SpatialReference::new(SpatialReferenceAuthority::Epsg, 900_913);
+        let p = CoordinateProjector::from_known_srs_with_transformation(
+            from,
+            to,
+            Some(CoordinateTransformation::Pipeline {
+                definition: "+proj=pipeline +step +proj=unitconvert +xy_in=deg +xy_out=rad +step +proj=webmerc".to_owned(),
+            }),
+        )
+        .unwrap();
+
+        let rp = p.project_coordinate(MARBURG_EPSG_4326).unwrap();
+        assert!(approx_eq!(
+            f64,
+            rp.x,
+            MARBURG_EPSG_900_913.x,
+            epsilon = 0.000_01
+        ));
+        assert!(approx_eq!(
+            f64,
+            rp.y,
+            MARBURG_EPSG_900_913.y,
+            epsilon = 0.000_01
+        ));
+
+        let inverse = p.inverse().unwrap();
+        assert_eq!(inverse.source_srs(), to);
+        assert_eq!(inverse.target_srs(), from);
+
+        let rps = inverse
+            .clone()
+            .project_coordinates(&[MARBURG_EPSG_900_913, COLOGNE_EPSG_900_913])
+            .unwrap();
+        assert!(approx_eq!(
+            f64,
+            rps[0].x,
+            MARBURG_EPSG_4326.x,
+            epsilon = 0.000_01
+        ));
+        assert!(approx_eq!(
+            f64,
+            rps[0].y,
+            MARBURG_EPSG_4326.y,
+            epsilon = 0.000_01
+        ));
+        assert!(approx_eq!(
+            f64,
+            rps[1].x,
+            COLOGNE_EPSG_4326.x,
+            epsilon = 0.000_01
+        ));
+        assert!(approx_eq!(
+            f64,
+            rps[1].y,
+            COLOGNE_EPSG_4326.y,
+            epsilon = 0.000_01
+        ));
+    }
+
     #[test]
     fn reproject_coordinate_4326_900913() {
         let from = SpatialReference::epsg_4326();
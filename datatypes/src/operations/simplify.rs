@@ -0,0 +1,309 @@
+use crate::{
+    collections::{IntoGeometryIterator, MultiLineStringCollection, MultiPolygonCollection},
+    primitives::{
+        Coordinate2D, MultiLineString, MultiLineStringAccess, MultiLineStringRef, MultiPolygon,
+        MultiPolygonAccess, MultiPolygonRef,
+    },
+    util::Result,
+};
+
+/// Simplification of geometries by removing vertices that barely change their shape
+pub trait Simplify {
+    type Out;
+
+    /// Simplifies with the Douglas-Peucker algorithm.
+    /// The `tolerance` is the maximum distance of a removed vertex to the simplified geometry
+    /// in units of the spatial reference.
+    fn simplify(&self, tolerance: f64) -> Result<Self::Out>;
+}
+
+impl Simplify for MultiLineString {
+    type Out = MultiLineString;
+
+    fn simplify(&self, tolerance: f64) -> Result<MultiLineString> {
+        simplify_lines(self, tolerance)
+    }
+}
+
+impl<'g> Simplify for MultiLineStringRef<'g> {
+    type Out = MultiLineString;
+
+    fn simplify(&self, tolerance: f64) -> Result<MultiLineString> {
+        simplify_lines(self, tolerance)
+    }
+}
+
+impl Simplify for MultiPolygon {
+    type Out = MultiPolygon;
+
+    fn simplify(&self, tolerance: f64) -> Result<MultiPolygon> {
+        simplify_polygons(self, tolerance)
+    }
+}
+
+impl<'g> Simplify for MultiPolygonRef<'g> {
+    type Out = MultiPolygon;
+
+    fn simplify(&self, tolerance: f64) -> Result<MultiPolygon> {
+        simplify_polygons(self, tolerance)
+    }
+}
+
+impl Simplify for MultiLineStringCollection {
+    type Out = MultiLineStringCollection;
+
+    fn simplify(&self, tolerance: f64) -> Result<MultiLineStringCollection> {
+        let geometries = self
+            .geometries()
+            .map(|geometry| geometry.simplify(tolerance))
+            .collect::<Result<Vec<_>>>()?;
+
+        self.replace_geometries(geometries)
+    }
+}
+
+impl Simplify for MultiPolygonCollection {
+    type Out = MultiPolygonCollection;
+
+    fn simplify(&self, tolerance: f64) -> Result<MultiPolygonCollection> {
+        let geometries = self
+            .geometries()
+            .map(|geometry| geometry.simplify(tolerance))
+            .collect::<Result<Vec<_>>>()?;
+
+        self.replace_geometries(geometries)
+    }
+}
+
+fn simplify_lines<M: MultiLineStringAccess>(
+    multi_line_string: &M,
+    tolerance: f64,
+) -> Result<MultiLineString> {
+    let lines = multi_line_string
+        .lines()
+        .iter()
+        .map(|line| douglas_peucker(line.as_ref(), tolerance))
+        .collect();
+
+    MultiLineString::new(lines)
+}
+
+/// Simplifies each ring of the polygons.
+/// Rings that would collapse to less than a triangle are kept as they are.
+fn simplify_polygons<M: MultiPolygonAccess>(
+    multi_polygon: &M,
+    tolerance: f64,
+) -> Result<MultiPolygon> {
+    let polygons = multi_polygon
+        .polygons()
+        .iter()
+        .map(|polygon| {
+            polygon
+                .as_ref()
+                .iter()
+                .map(|ring| {
+                    let ring = ring.as_ref();
+                    let simplified = douglas_peucker(ring, tolerance);
+
+                    if simplified.len() < 4 {
+                        ring.to_vec()
+                    } else {
+                        simplified
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    MultiPolygon::new(polygons)
+}
+
+/// Keeps the first and last coordinate and recursively every coordinate
+/// that is farther than `tolerance` away from the segment between the kept ones
+fn douglas_peucker(coordinates: &[Coordinate2D], tolerance: f64) -> Vec<Coordinate2D> {
+    if coordinates.len() < 3 {
+        return coordinates.to_vec();
+    }
+
+    let mut keep = vec![false; coordinates.len()];
+    keep[0] = true;
+    keep[coordinates.len() - 1] = true;
+
+    let mut segments = vec![(0, coordinates.len() - 1)];
+
+    while let Some((start, end)) = segments.pop() {
+        let farthest = ((start + 1)..end)
+            .map(|i| {
+                (
+                    i,
+                    segment_distance(coordinates[i], coordinates[start], coordinates[end]),
+                )
+            })
+            .fold(
+                None,
+                |farthest: Option<(usize, f64)>, (i, distance)| match farthest {
+                    Some((_, max_distance)) if max_distance >= distance => farthest,
+                    _ => Some((i, distance)),
+                },
+            );
+
+        if let Some((i, distance)) = farthest {
+            if distance > tolerance {
+                keep[i] = true;
+                segments.push((start, i));
+                segments.push((i, end));
+            }
+        }
+    }
+
+    coordinates
+        .iter()
+        .zip(keep)
+        .filter_map(|(&coordinate, keep)| if keep { Some(coordinate) } else { None })
+        .collect()
+}
+
+/// The distance of `point` to the segment from `start` to `end`
+fn segment_distance(point: Coordinate2D, start: Coordinate2D, end: Coordinate2D) -> f64 {
+    let closest = if start == end {
+        start
+    } else {
+        let segment = end - start;
+        let squared_length = segment.x * segment.x + segment.y * segment.y;
+        let offset = point - start;
+        let fraction = ((offset.x * segment.x + offset.y * segment.y) / squared_length)
+            .max(0.)
+            .min(1.);
+        start + segment * fraction
+    };
+
+    let difference = point - closest;
+    (difference.x * difference.x + difference.y * difference.y).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::FeatureCollectionInfos;
+    use crate::primitives::{FeatureData, TimeInterval};
+
+    #[test]
+    fn it_simplifies_lines() {
+        let line = MultiLineString::new(vec![vec![
+            (0., 0.).into(),
+            (1., 0.1).into(),
+            (2., 0.).into(),
+            (3., 5.).into(),
+            (4., 10.).into(),
+        ]])
+        .unwrap();
+
+        assert_eq!(
+            line.simplify(0.5).unwrap(),
+            MultiLineString::new(vec![vec![
+                (0., 0.).into(),
+                (2., 0.).into(),
+                (4., 10.).into()
+            ]])
+            .unwrap()
+        );
+
+        assert_eq!(
+            line.simplify(0.05).unwrap(),
+            MultiLineString::new(vec![vec![
+                (0., 0.).into(),
+                (1., 0.1).into(),
+                (2., 0.).into(),
+                (4., 10.).into(),
+            ]])
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn it_keeps_collapsing_rings() {
+        let polygon = MultiPolygon::new(vec![vec![
+            vec![
+                (0., 0.).into(),
+                (10., 0.).into(),
+                (10., 10.).into(),
+                (5., 10.1).into(),
+                (0., 10.).into(),
+                (0., 0.).into(),
+            ],
+            vec![
+                (4., 4.).into(),
+                (4.1, 4.).into(),
+                (4.1, 4.1).into(),
+                (4., 4.).into(),
+            ],
+        ]])
+        .unwrap();
+
+        assert_eq!(
+            polygon.simplify(1.).unwrap(),
+            MultiPolygon::new(vec![vec![
+                vec![
+                    (0., 0.).into(),
+                    (10., 0.).into(),
+                    (10., 10.).into(),
+                    (0., 10.).into(),
+                    (0., 0.).into(),
+                ],
+                vec![
+                    (4., 4.).into(),
+                    (4.1, 4.).into(),
+                    (4.1, 4.1).into(),
+                    (4., 4.).into(),
+                ],
+            ]])
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn it_simplifies_collections() {
+        let collection = MultiLineStringCollection::from_data(
+            vec![
+                MultiLineString::new(vec![vec![(0., 0.).into(), (1., 0.).into()]]).unwrap(),
+                MultiLineString::new(vec![vec![
+                    (0., 0.).into(),
+                    (1., 0.1).into(),
+                    (2., 0.).into(),
+                ]])
+                .unwrap(),
+            ],
+            vec![
+                TimeInterval::new_unchecked(0, 1),
+                TimeInterval::new_unchecked(1, 2),
+            ],
+            [("foo".to_string(), FeatureData::Int(vec![1, 2]))]
+                .iter()
+                .cloned()
+                .collect(),
+        )
+        .unwrap();
+
+        let simplified = collection.simplify(0.5).unwrap();
+
+        assert_eq!(
+            simplified,
+            MultiLineStringCollection::from_data(
+                vec![
+                    MultiLineString::new(vec![vec![(0., 0.).into(), (1., 0.).into()]]).unwrap(),
+                    MultiLineString::new(vec![vec![(0., 0.).into(), (2., 0.).into()]]).unwrap(),
+                ],
+                vec![
+                    TimeInterval::new_unchecked(0, 1),
+                    TimeInterval::new_unchecked(1, 2)
+                ],
+                [("foo".to_string(), FeatureData::Int(vec![1, 2]))]
+                    .iter()
+                    .cloned()
+                    .collect(),
+            )
+            .unwrap()
+        );
+        assert_eq!(simplified.len(), 2);
+    }
+}
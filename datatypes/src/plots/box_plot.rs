@@ -0,0 +1,281 @@
+use crate::plots::{Plot, PlotData, PlotMetaData};
+use crate::primitives::Measurement;
+use crate::util::Result;
+
+/// The maximum number of outliers per box, split between the lower and the upper end
+const MAX_OUTLIERS: usize = 500;
+
+/// A plot that summarizes the distributions of one or more attributes by their quartiles.
+///
+/// The whiskers extend to the most extreme values within 1.5 times the interquartile range
+/// from the box. Values beyond the whiskers are drawn as outliers.
+pub struct BoxPlot {
+    boxes: Vec<BoxPlotAttribute>,
+    measurement: Measurement,
+}
+
+/// The summary of the values of a single attribute
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoxPlotAttribute {
+    pub name: String,
+    pub lower_whisker: f64,
+    pub q1: f64,
+    pub median: f64,
+    pub q3: f64,
+    pub upper_whisker: f64,
+    /// The most extreme values beyond the whiskers
+    pub outliers: Vec<f64>,
+}
+
+impl BoxPlot {
+    pub fn new(boxes: Vec<BoxPlotAttribute>, measurement: Measurement) -> Self {
+        Self { boxes, measurement }
+    }
+}
+
+impl BoxPlotAttribute {
+    /// Summarizes the `values` and ignores `NaN`s. Returns `None` if there are no other values.
+    pub fn from_values(name: String, mut values: Vec<f64>) -> Option<Self> {
+        values.retain(|value| !value.is_nan());
+
+        if values.is_empty() {
+            return None;
+        }
+
+        values.sort_unstable_by(|a, b| a.partial_cmp(b).expect("`NaN` values were removed"));
+
+        let quantile = |q: f64| {
+            let rank = q * (values.len() - 1) as f64;
+            let lower = values[rank.floor() as usize];
+            let upper = values[rank.ceil() as usize];
+
+            lower + (upper - lower) * rank.fract()
+        };
+
+        let q1 = quantile(0.25);
+        let median = quantile(0.5);
+        let q3 = quantile(0.75);
+
+        let fence = 1.5 * (q3 - q1);
+        let lower_fence = q1 - fence;
+        let upper_fence = q3 + fence;
+
+        let first_inside = values.partition_point(|&value| value < lower_fence);
+        let last_inside = values.partition_point(|&value| value <= upper_fence) - 1;
+
+        let lower_outliers = &values[..first_inside];
+        let upper_outliers = &values[last_inside + 1..];
+
+        let outliers = lower_outliers
+            .iter()
+            .take(MAX_OUTLIERS / 2)
+            .chain(
+                upper_outliers
+                    .iter()
+                    .skip(upper_outliers.len().saturating_sub(MAX_OUTLIERS / 2)),
+            )
+            .copied()
+            .collect();
+
+        Some(Self {
+            name,
+            lower_whisker: values[first_inside],
+            q1,
+            median,
+            q3,
+            upper_whisker: values[last_inside],
+            outliers,
+        })
+    }
+}
+
+impl Plot for BoxPlot {
+    fn to_vega_embeddable(&self, _allow_interactions: bool) -> Result<PlotData> {
+        let boxes = self
+            .boxes
+            .iter()
+            .map(|b| {
+                serde_json::json!({
+                    "name": b.name,
+                    "lowerWhisker": b.lower_whisker,
+                    "q1": b.q1,
+                    "median": b.median,
+                    "q3": b.q3,
+                    "upperWhisker": b.upper_whisker,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let outliers = self
+            .boxes
+            .iter()
+            .flat_map(|b| {
+                b.outliers.iter().map(move |outlier| {
+                    serde_json::json!({
+                        "name": b.name,
+                        "value": outlier,
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let x = serde_json::json!({
+            "field": "name",
+            "title": null,
+            "type": "nominal"
+        });
+        let y_title = self.measurement.to_string();
+
+        let vega_string = serde_json::json!({
+            "$schema": "https://vega.github.io/schema/vega-lite/v4.17.0.json",
+            "data": {
+                "values": boxes
+            },
+            "description": "Box Plot",
+            "layer": [
+                {
+                    "mark": "rule",
+                    "encoding": {
+                        "x": x,
+                        "y": {
+                            "field": "lowerWhisker",
+                            "title": y_title,
+                            "type": "quantitative",
+                            "scale": {
+                                "zero": false
+                            }
+                        },
+                        "y2": {
+                            "field": "upperWhisker"
+                        }
+                    }
+                },
+                {
+                    "mark": {
+                        "type": "bar",
+                        "size": 14
+                    },
+                    "encoding": {
+                        "x": x,
+                        "y": {
+                            "field": "q1",
+                            "type": "quantitative"
+                        },
+                        "y2": {
+                            "field": "q3"
+                        }
+                    }
+                },
+                {
+                    "mark": {
+                        "type": "tick",
+                        "color": "white",
+                        "size": 14
+                    },
+                    "encoding": {
+                        "x": x,
+                        "y": {
+                            "field": "median",
+                            "type": "quantitative"
+                        }
+                    }
+                },
+                {
+                    "data": {
+                        "values": outliers
+                    },
+                    "mark": "point",
+                    "encoding": {
+                        "x": x,
+                        "y": {
+                            "field": "value",
+                            "type": "quantitative"
+                        }
+                    }
+                }
+            ]
+        })
+        .to_string();
+
+        Ok(PlotData {
+            vega_string,
+            metadata: PlotMetaData::None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary() {
+        let attribute = BoxPlotAttribute::from_values(
+            "foo".to_string(),
+            vec![9., 1., 2., 3., f64::NAN, 4., 5., 6., 7., 8., 100.],
+        )
+        .unwrap();
+
+        assert_eq!(
+            attribute,
+            BoxPlotAttribute {
+                name: "foo".to_string(),
+                lower_whisker: 1.,
+                q1: 3.25,
+                median: 5.5,
+                q3: 7.75,
+                upper_whisker: 9.,
+                outliers: vec![100.],
+            }
+        );
+    }
+
+    #[test]
+    fn single_value() {
+        assert_eq!(
+            BoxPlotAttribute::from_values("foo".to_string(), vec![42.]).unwrap(),
+            BoxPlotAttribute {
+                name: "foo".to_string(),
+                lower_whisker: 42.,
+                q1: 42.,
+                median: 42.,
+                q3: 42.,
+                upper_whisker: 42.,
+                outliers: vec![],
+            }
+        );
+
+        assert!(BoxPlotAttribute::from_values("foo".to_string(), vec![f64::NAN]).is_none());
+    }
+
+    #[test]
+    fn serialization() {
+        let plot = BoxPlot::new(
+            vec![
+                BoxPlotAttribute::from_values("foo".to_string(), vec![1., 2., 3., 4., 50.])
+                    .unwrap(),
+            ],
+            Measurement::Unitless,
+        );
+
+        let plot_data = plot.to_vega_embeddable(false).unwrap();
+
+        assert_eq!(
+            plot_data.data_values().unwrap(),
+            serde_json::json!([{
+                "name": "foo",
+                "lowerWhisker": 1.0,
+                "q1": 2.0,
+                "median": 3.0,
+                "q3": 4.0,
+                "upperWhisker": 4.0,
+            }])
+        );
+
+        let spec: serde_json::Value = serde_json::from_str(&plot_data.vega_string).unwrap();
+        assert_eq!(
+            spec["layer"][3]["data"]["values"],
+            serde_json::json!([{"name": "foo", "value": 50.0}])
+        );
+    }
+}
@@ -22,6 +22,9 @@ pub struct Histogram {
 }
 
 impl Histogram {
+    /// The field of the data values that the range selections of interactive histograms refer to
+    pub const SELECTION_FIELD: &'static str = "binStart";
+
     fn new(
         number_of_buckets: usize,
         min: f64,
@@ -166,7 +169,9 @@ impl Histogram {
                     self.handle_data_item(value, is_null);
                 }
             }
-            FeatureDataRef::Text(..) => {
+            FeatureDataRef::Text(..)
+            | FeatureDataRef::Bytes(..)
+            | FeatureDataRef::Dictionary(..) => {
                 return error::Plot {
                     details: "Cannot add non-numerical data to the histogram.",
                 }
@@ -245,7 +250,7 @@ impl Plot for Histogram {
             "mark": "bar",
             "encoding": {
                 "x": {
-                    "field": "binStart",
+                    "field": Self::SELECTION_FIELD,
                     "bin": {
                         "binned": true,
                         "step": step,
@@ -285,7 +290,10 @@ impl Plot for Histogram {
         Ok(PlotData {
             vega_string: vega_spec.to_string(),
             metadata: selection_name.map_or(PlotMetaData::None, |selection_name| {
-                PlotMetaData::Selection { selection_name }
+                PlotMetaData::Selection {
+                    selection_name,
+                    filter: None,
+                }
             }),
         })
     }
@@ -536,6 +544,7 @@ mod tests {
                 vega_string: r#"{"$schema":"https://vega.github.io/schema/vega-lite/v4.json","data":{"values":[{"binStart":0.0,"binEnd":0.5,"Frequency":2},{"binStart":0.5,"binEnd":1.0,"Frequency":2}]},"mark":"bar","encoding":{"x":{"field":"binStart","bin":{"binned":true,"step":0.5},"axis":{"title":""}},"x2":{"field":"binEnd"},"y":{"field":"Frequency","type":"quantitative"}},"selection":{"range_selection":{"encodings":["x"],"type":"interval"}}}"#.to_owned(),
                 metadata: PlotMetaData::Selection {
                     selection_name: "range_selection".to_string(),
+                    filter: None,
                 }
             }
         );
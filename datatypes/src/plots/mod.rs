@@ -1,10 +1,19 @@
 mod area_line_plot;
+mod box_plot;
 mod histogram;
 mod multi_line_plot;
+mod pie_chart;
+mod render;
+mod scatter_plot;
+mod time_coverage;
 
 pub use area_line_plot::AreaLineChart;
+pub use box_plot::{BoxPlot, BoxPlotAttribute};
 pub use histogram::{Histogram, HistogramBuilder};
 pub use multi_line_plot::{DataPoint, MultiLineChart};
+pub use pie_chart::{PieChart, PieChartSlice};
+pub use scatter_plot::{DensityScatterPlot, ScatterPlot};
+pub use time_coverage::TimeCoverageChart;
 
 use crate::util::Result;
 use serde::{Deserialize, Serialize};
@@ -18,9 +27,6 @@ pub trait Plot {
     /// This method fails on internal errors of the plot.
     ///
     fn to_vega_embeddable(&self, allow_interactions: bool) -> Result<PlotData>;
-
-    // TODO: create some PNG output, cf. https://github.com/procyon-rs/vega_lite_3.rs/issues/18
-    // fn to_png(&self, width_px: u16, height_px: u16) -> Vec<u8>;
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
@@ -30,6 +36,27 @@ pub struct PlotData {
     pub metadata: PlotMetaData,
 }
 
+impl PlotData {
+    /// The data values of the Vega spec, e.g., the series or bins of the plot, for custom visualizations
+    pub fn data_values(&self) -> Result<serde_json::Value> {
+        let mut spec = self.vega_spec()?;
+
+        Ok(spec["data"]["values"].take())
+    }
+
+    /// Renders the plot as a PNG of `width` x `height` pixels.
+    /// The image contains the marks and the axes, but no labels.
+    pub fn to_png(&self, width: u32, height: u32) -> Result<Vec<u8>> {
+        render::render_vega_lite_png(&self.vega_spec()?, width, height)
+    }
+
+    fn vega_spec(&self) -> Result<serde_json::Value> {
+        serde_json::from_str(&self.vega_string).map_err(|error| crate::error::Error::Plot {
+            details: format!("invalid Vega spec: {}", error),
+        })
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
 #[serde(untagged)]
 pub enum PlotMetaData {
@@ -37,9 +64,27 @@ pub enum PlotMetaData {
     #[serde(rename_all = "camelCase")]
     Selection {
         selection_name: String,
+        /// The filter that the selection parametrizes, if there is one
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        filter: Option<SelectionFilter>,
     },
 }
 
+/// Maps the range of a plot selection to the parameters of a filter operator,
+/// s.t. selecting a range in a plot can filter the plotted data, e.g., on a map.
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelectionFilter {
+    /// The type of the filter operator
+    pub operator: String,
+    /// The parameters of the filter operator that do not depend on the selection
+    pub params: serde_json::Value,
+    /// The parameter of the filter operator that takes the selected range
+    pub range_parameter: String,
+    /// The field of the selection that contains the selected range
+    pub selection_field: String,
+}
+
 #[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
 pub enum PlotOutputFormat {
     JsonPlain,
@@ -47,6 +92,19 @@ pub enum PlotOutputFormat {
     ImagePng,
 }
 
+impl PlotMetaData {
+    /// Adds a `filter` to a selection and leaves plots without a selection unchanged
+    pub fn with_filter(self, filter: SelectionFilter) -> Self {
+        match self {
+            PlotMetaData::Selection { selection_name, .. } => PlotMetaData::Selection {
+                selection_name,
+                filter: Some(filter),
+            },
+            PlotMetaData::None => PlotMetaData::None,
+        }
+    }
+}
+
 impl Default for PlotMetaData {
     fn default() -> Self {
         PlotMetaData::None
@@ -0,0 +1,132 @@
+use crate::plots::{Plot, PlotData, PlotMetaData};
+use crate::util::Result;
+
+/// A plot that shows the share of each category of an attribute as a slice of a pie or donut.
+pub struct PieChart {
+    slices: Vec<PieChartSlice>,
+    legend_title: String,
+    donut: bool,
+}
+
+/// The number of occurrences of a category
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PieChartSlice {
+    pub label: String,
+    pub count: u64,
+}
+
+impl PieChart {
+    pub fn new(slices: Vec<PieChartSlice>, legend_title: String, donut: bool) -> Self {
+        Self {
+            slices,
+            legend_title,
+            donut,
+        }
+    }
+}
+
+impl Plot for PieChart {
+    fn to_vega_embeddable(&self, _allow_interactions: bool) -> Result<PlotData> {
+        let data = self
+            .slices
+            .iter()
+            .map(|slice| {
+                serde_json::json!({
+                    "label": slice.label,
+                    "count": slice.count,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mark = if self.donut {
+            serde_json::json!({
+                "type": "arc",
+                "innerRadius": 50,
+            })
+        } else {
+            serde_json::json!({
+                "type": "arc",
+            })
+        };
+
+        let vega_string = serde_json::json!({
+            "$schema": "https://vega.github.io/schema/vega-lite/v4.17.0.json",
+            "data": {
+                "values": data
+            },
+            "description": "Pie Chart",
+            "encoding": {
+                "theta": {
+                    "field": "count",
+                    "type": "quantitative",
+                    "stack": true
+                },
+                "color": {
+                    "field": "label",
+                    "title": self.legend_title,
+                    "type": "nominal",
+                    "sort": null
+                },
+                "order": {
+                    "field": "count",
+                    "type": "quantitative",
+                    "sort": "descending"
+                }
+            },
+            "mark": mark,
+            "view": {
+                "stroke": null
+            }
+        })
+        .to_string();
+
+        Ok(PlotData {
+            vega_string,
+            metadata: PlotMetaData::None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialization() {
+        let chart = PieChart::new(
+            vec![
+                PieChartSlice {
+                    label: "forest".to_string(),
+                    count: 3,
+                },
+                PieChartSlice {
+                    label: "water".to_string(),
+                    count: 1,
+                },
+            ],
+            "land cover".to_string(),
+            false,
+        );
+
+        assert_eq!(
+            chart.to_vega_embeddable(false).unwrap(),
+            PlotData {
+                vega_string: r#"{"$schema":"https://vega.github.io/schema/vega-lite/v4.17.0.json","data":{"values":[{"label":"forest","count":3},{"label":"water","count":1}]},"description":"Pie Chart","encoding":{"theta":{"field":"count","type":"quantitative","stack":true},"color":{"field":"label","title":"land cover","type":"nominal","sort":null},"order":{"field":"count","type":"quantitative","sort":"descending"}},"mark":{"type":"arc"},"view":{"stroke":null}}"#.to_owned(),
+                metadata: PlotMetaData::None,
+            }
+        );
+    }
+
+    #[test]
+    fn donut() {
+        let chart = PieChart::new(Vec::new(), "land cover".to_string(), true);
+
+        let spec: serde_json::Value =
+            serde_json::from_str(&chart.to_vega_embeddable(false).unwrap().vega_string).unwrap();
+
+        assert_eq!(
+            spec["mark"],
+            serde_json::json!({"type": "arc", "innerRadius": 50})
+        );
+    }
+}
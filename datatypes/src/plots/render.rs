@@ -0,0 +1,396 @@
+use chrono::DateTime;
+use image::{DynamicImage, ImageFormat, Pixel, Rgba, RgbaImage};
+use serde_json::Value;
+
+use crate::error;
+use crate::util::Result;
+
+const MARGIN: u32 = 10;
+const AXIS_COLOR: Rgba<u8> = Rgba([0, 0, 0, 255]);
+/// Vega's `category10` color scheme
+const SERIES_COLORS: [[u8; 3]; 10] = [
+    [31, 119, 180],
+    [255, 127, 14],
+    [44, 160, 44],
+    [214, 39, 40],
+    [148, 103, 189],
+    [140, 86, 75],
+    [227, 119, 194],
+    [127, 127, 127],
+    [188, 189, 34],
+    [23, 190, 207],
+];
+
+/// A data value of a Vega-Lite spec mapped to its encoding channels
+struct Mark {
+    x: f64,
+    x2: Option<f64>,
+    y: f64,
+    series: String,
+}
+
+/// Renders the bar, line and area charts of our Vega-Lite specs.
+/// The image only contains the marks and the axes, since there is no font rasterizer for the labels.
+pub(super) fn render_vega_lite_png(spec: &Value, width: u32, height: u32) -> Result<Vec<u8>> {
+    if width <= 2 * MARGIN || height <= 2 * MARGIN {
+        return Err(plot_error(format!(
+            "the image must be larger than {0}x{0} pixels",
+            2 * MARGIN
+        )));
+    }
+
+    let mark_type = spec["mark"]
+        .as_str()
+        .or_else(|| spec["mark"]["type"].as_str())
+        .unwrap_or_default();
+    let marks = encoded_marks(spec)?;
+
+    let mut image = RgbaImage::from_pixel(width, height, Rgba([255, 255, 255, 255]));
+    let scale = Scale::new(&marks, width, height);
+
+    match mark_type {
+        "bar" => draw_bars(&mut image, &scale, &marks),
+        "line" => draw_lines(&mut image, &scale, &marks, false),
+        "area" => draw_lines(&mut image, &scale, &marks, true),
+        _ => {
+            return Err(plot_error(format!(
+                "rendering `{}` marks is not supported",
+                mark_type
+            )))
+        }
+    }
+
+    let baseline = scale.y(0.);
+    draw_line(
+        &mut image,
+        (f64::from(MARGIN), f64::from(MARGIN)),
+        (f64::from(MARGIN), f64::from(height - MARGIN)),
+        AXIS_COLOR,
+    );
+    draw_line(
+        &mut image,
+        (f64::from(MARGIN), baseline),
+        (f64::from(width - MARGIN), baseline),
+        AXIS_COLOR,
+    );
+
+    let mut buffer = Vec::new();
+    DynamicImage::ImageRgba8(image)
+        .write_to(&mut buffer, ImageFormat::Png)
+        .map_err(|error| plot_error(format!("encoding PNG failed: {}", error)))?;
+
+    Ok(buffer)
+}
+
+fn plot_error(details: String) -> error::Error {
+    error::Error::Plot { details }
+}
+
+fn encoded_marks(spec: &Value) -> Result<Vec<Mark>> {
+    let values = spec["data"]["values"]
+        .as_array()
+        .ok_or_else(|| plot_error("the spec has no data values".to_string()))?;
+
+    let encoding = &spec["encoding"];
+    let field = |channel: &str| encoding[channel]["field"].as_str();
+
+    let (x_field, y_field) = match (field("x"), field("y")) {
+        (Some(x_field), Some(y_field)) => (x_field, y_field),
+        _ => {
+            return Err(plot_error(
+                "the spec must encode the `x` and `y` channels".to_string(),
+            ))
+        }
+    };
+    let x2_field = field("x2");
+    let color_field = field("color");
+
+    // values with missing or non-numeric fields are left out, as in Vega
+    Ok(values
+        .iter()
+        .filter_map(|value| {
+            Some(Mark {
+                x: number(&value[x_field])?,
+                x2: x2_field.and_then(|field| number(&value[field])),
+                y: number(&value[y_field])?,
+                series: color_field
+                    .map(|field| value[field].to_string())
+                    .unwrap_or_default(),
+            })
+        })
+        .collect())
+}
+
+/// Numbers and temporal values, which are mapped to milliseconds
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64().filter(|number| number.is_finite()),
+        Value::String(string) => DateTime::parse_from_rfc3339(string)
+            .ok()
+            .map(|time| time.timestamp_millis() as f64),
+        _ => None,
+    }
+}
+
+/// Maps data values to pixel coordinates
+struct Scale {
+    x_min: f64,
+    x_max: f64,
+    y_min: f64,
+    y_max: f64,
+    width: f64,
+    height: f64,
+}
+
+impl Scale {
+    fn new(marks: &[Mark], width: u32, height: u32) -> Self {
+        let mut x_min = f64::INFINITY;
+        let mut x_max = f64::NEG_INFINITY;
+        // the y-axis always includes the baseline
+        let mut y_min = 0_f64;
+        let mut y_max = 0_f64;
+
+        for mark in marks {
+            for x in std::iter::once(mark.x).chain(mark.x2) {
+                x_min = x_min.min(x);
+                x_max = x_max.max(x);
+            }
+            y_min = y_min.min(mark.y);
+            y_max = y_max.max(mark.y);
+        }
+
+        if !x_min.is_finite() {
+            x_min = 0.;
+            x_max = 1.;
+        } else if x_min >= x_max {
+            x_min -= 0.5;
+            x_max = x_min + 1.;
+        }
+        if y_min >= y_max {
+            y_max = y_min + 1.;
+        }
+
+        Self {
+            x_min,
+            x_max,
+            y_min,
+            y_max,
+            width: f64::from(width - 2 * MARGIN - 1),
+            height: f64::from(height - 2 * MARGIN - 1),
+        }
+    }
+
+    fn x(&self, x: f64) -> f64 {
+        f64::from(MARGIN) + (x - self.x_min) / (self.x_max - self.x_min) * self.width
+    }
+
+    fn y(&self, y: f64) -> f64 {
+        f64::from(MARGIN) + (self.y_max - y) / (self.y_max - self.y_min) * self.height
+    }
+}
+
+fn draw_bars(image: &mut RgbaImage, scale: &Scale, marks: &[Mark]) {
+    let color = series_color(0, 255);
+    // bars without an end are drawn with a fraction of the plot width
+    let half_width = scale.width / (4 * marks.len().max(1)) as f64;
+
+    for mark in marks {
+        let (left, right) = match mark.x2 {
+            Some(x2) => (scale.x(mark.x.min(x2)), scale.x(mark.x.max(x2)) - 1.),
+            None => (scale.x(mark.x) - half_width, scale.x(mark.x) + half_width),
+        };
+
+        fill_rectangle(
+            image,
+            (left, scale.y(mark.y.max(0.))),
+            (right, scale.y(mark.y.min(0.))),
+            color,
+        );
+    }
+}
+
+fn draw_lines(image: &mut RgbaImage, scale: &Scale, marks: &[Mark], draw_area: bool) {
+    let mut series: Vec<(&str, Vec<(f64, f64)>)> = Vec::new();
+
+    for mark in marks {
+        let point = (scale.x(mark.x), scale.y(mark.y));
+
+        match series.iter_mut().find(|(name, _)| *name == mark.series) {
+            Some((_, points)) => points.push(point),
+            None => series.push((&mark.series, vec![point])),
+        }
+    }
+
+    let baseline = scale.y(0.);
+
+    for (index, (_, points)) in series.iter_mut().enumerate() {
+        points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        if draw_area {
+            let color = series_color(index, 96);
+            for pair in points.windows(2) {
+                fill_area(image, pair[0], pair[1], baseline, color);
+            }
+        }
+
+        let color = series_color(index, 255);
+        for pair in points.windows(2) {
+            draw_line(image, pair[0], pair[1], color);
+        }
+        for &(x, y) in &*points {
+            fill_rectangle(image, (x - 1., y - 1.), (x + 1., y + 1.), color);
+        }
+    }
+}
+
+fn series_color(index: usize, alpha: u8) -> Rgba<u8> {
+    let [r, g, b] = SERIES_COLORS[index % SERIES_COLORS.len()];
+    Rgba([r, g, b, alpha])
+}
+
+fn blend_pixel(image: &mut RgbaImage, x: f64, y: f64, color: Rgba<u8>) {
+    let (x, y) = (x.round(), y.round());
+
+    if x >= 0. && y >= 0. && x < f64::from(image.width()) && y < f64::from(image.height()) {
+        let pixel = image.get_pixel_mut(x as u32, y as u32);
+        if color[3] == u8::MAX {
+            *pixel = color;
+        } else {
+            pixel.blend(&color);
+        }
+    }
+}
+
+fn fill_rectangle(
+    image: &mut RgbaImage,
+    (left, top): (f64, f64),
+    (right, bottom): (f64, f64),
+    color: Rgba<u8>,
+) {
+    let (left, right) = (left.round() as i64, right.round() as i64);
+    let (top, bottom) = (top.round() as i64, bottom.round() as i64);
+
+    for y in top..=bottom {
+        for x in left..=right {
+            blend_pixel(image, x as f64, y as f64, color);
+        }
+    }
+}
+
+/// Fills the area between the line from `a` to `b` and the `baseline` column by column
+fn fill_area(image: &mut RgbaImage, a: (f64, f64), b: (f64, f64), baseline: f64, color: Rgba<u8>) {
+    let (start, end) = (a.0.round() as i64, b.0.round() as i64);
+
+    for x in start..end.max(start + 1) {
+        let fraction = if end > start {
+            (x - start) as f64 / (end - start) as f64
+        } else {
+            0.
+        };
+        let y = a.1 + (b.1 - a.1) * fraction;
+
+        let (top, bottom) = (
+            y.min(baseline).round() as i64,
+            y.max(baseline).round() as i64,
+        );
+        for row in top..=bottom {
+            blend_pixel(image, x as f64, row as f64, color);
+        }
+    }
+}
+
+fn draw_line(image: &mut RgbaImage, from: (f64, f64), to: (f64, f64), color: Rgba<u8>) {
+    let steps = (to.0 - from.0)
+        .abs()
+        .max((to.1 - from.1).abs())
+        .ceil()
+        .max(1.);
+
+    for step in 0..=(steps as u64) {
+        let fraction = step as f64 / steps;
+        blend_pixel(
+            image,
+            from.0 + (to.0 - from.0) * fraction,
+            from.1 + (to.1 - from.1) * fraction,
+            color,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plots::{AreaLineChart, Histogram, Plot};
+    use crate::primitives::{Measurement, TimeInstance};
+
+    fn decode(png: &[u8]) -> RgbaImage {
+        image::load_from_memory_with_format(png, ImageFormat::Png)
+            .unwrap()
+            .into_rgba8()
+    }
+
+    #[test]
+    fn it_renders_histograms() {
+        let histogram = Histogram::builder(2, 0., 2., Measurement::Unitless)
+            .counts(vec![1, 2])
+            .build()
+            .unwrap();
+
+        let image = decode(
+            &histogram
+                .to_vega_embeddable(false)
+                .unwrap()
+                .to_png(120, 70)
+                .unwrap(),
+        );
+
+        assert_eq!(image.dimensions(), (120, 70));
+
+        let bar = series_color(0, 255);
+        let white = Rgba([255, 255, 255, 255]);
+
+        // the first bin reaches half of the plot height and the second one the top
+        assert_eq!(*image.get_pixel(30, 50), bar);
+        assert_eq!(*image.get_pixel(30, 20), white);
+        assert_eq!(*image.get_pixel(90, 20), bar);
+        // the axes
+        assert_eq!(*image.get_pixel(10, 30), AXIS_COLOR);
+        assert_eq!(*image.get_pixel(60, 59), AXIS_COLOR);
+    }
+
+    #[test]
+    fn it_renders_areas() {
+        let chart = AreaLineChart::new(
+            vec![
+                TimeInstance::from_millis_unchecked(0),
+                TimeInstance::from_millis_unchecked(1000),
+            ],
+            vec![1., 1.],
+            Measurement::Unitless,
+            true,
+        )
+        .unwrap();
+
+        let image = decode(
+            &chart
+                .to_vega_embeddable(false)
+                .unwrap()
+                .to_png(120, 70)
+                .unwrap(),
+        );
+
+        assert_eq!(*image.get_pixel(60, 10), series_color(0, 255));
+        assert_ne!(*image.get_pixel(60, 40), Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn it_rejects_unsupported_marks() {
+        let spec = serde_json::json!({
+            "data": { "values": [{ "x": 1, "y": 2 }] },
+            "mark": "arc",
+            "encoding": { "x": { "field": "x" }, "y": { "field": "y" } }
+        });
+
+        assert!(render_vega_lite_png(&spec, 100, 100).is_err());
+    }
+}
@@ -0,0 +1,288 @@
+use crate::plots::{Plot, PlotData, PlotMetaData};
+use crate::primitives::Coordinate2D;
+use crate::util::Result;
+
+/// A plot that shows pairs of values as points, e.g., to explore the correlation of two attributes.
+pub struct ScatterPlot {
+    x_label: String,
+    y_label: String,
+    points: Vec<Coordinate2D>,
+}
+
+impl ScatterPlot {
+    pub fn new(x_label: String, y_label: String, points: Vec<Coordinate2D>) -> Self {
+        Self {
+            x_label,
+            y_label,
+            points,
+        }
+    }
+}
+
+impl Plot for ScatterPlot {
+    fn to_vega_embeddable(&self, _allow_interactions: bool) -> Result<PlotData> {
+        let data = self
+            .points
+            .iter()
+            .map(|point| {
+                serde_json::json!({
+                    "x": point.x,
+                    "y": point.y,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let vega_string = serde_json::json!({
+            "$schema": "https://vega.github.io/schema/vega-lite/v4.17.0.json",
+            "data": {
+                "values": data
+            },
+            "description": "Scatter Plot",
+            "encoding": {
+                "x": {
+                    "field": "x",
+                    "title": self.x_label,
+                    "type": "quantitative",
+                    "scale": {
+                        "zero": false
+                    }
+                },
+                "y": {
+                    "field": "y",
+                    "title": self.y_label,
+                    "type": "quantitative",
+                    "scale": {
+                        "zero": false
+                    }
+                }
+            },
+            "mark": "point"
+        })
+        .to_string();
+
+        Ok(PlotData {
+            vega_string,
+            metadata: PlotMetaData::None,
+        })
+    }
+}
+
+/// A scatter plot that counts the points in a grid of bins instead of drawing each of them,
+/// which keeps plots of large collections small and readable.
+pub struct DensityScatterPlot {
+    x_label: String,
+    y_label: String,
+    bins: Vec<DensityBin>,
+}
+
+struct DensityBin {
+    x: f64,
+    x2: f64,
+    y: f64,
+    y2: f64,
+    count: u64,
+}
+
+impl DensityScatterPlot {
+    /// Divides the extent of the `points` into `bins_per_axis` x `bins_per_axis` bins.
+    /// An axis without extent gets a bin width of one.
+    pub fn new(
+        x_label: String,
+        y_label: String,
+        points: &[Coordinate2D],
+        bins_per_axis: usize,
+    ) -> Self {
+        let bins_per_axis = bins_per_axis.max(1);
+
+        let (min, max) = points.iter().fold(
+            (
+                Coordinate2D::new(f64::INFINITY, f64::INFINITY),
+                Coordinate2D::new(f64::NEG_INFINITY, f64::NEG_INFINITY),
+            ),
+            |(min, max), point| (min.min_elements(*point), max.max_elements(*point)),
+        );
+
+        let step = |min: f64, max: f64| {
+            if max > min {
+                (max - min) / bins_per_axis as f64
+            } else {
+                1.
+            }
+        };
+        let x_step = step(min.x, max.x);
+        let y_step = step(min.y, max.y);
+
+        let bin_index = |value: f64, min: f64, step: f64| {
+            (((value - min) / step) as usize).min(bins_per_axis - 1)
+        };
+
+        let mut counts = vec![0_u64; bins_per_axis * bins_per_axis];
+        for point in points {
+            let x = bin_index(point.x, min.x, x_step);
+            let y = bin_index(point.y, min.y, y_step);
+
+            counts[y * bins_per_axis + x] += 1;
+        }
+
+        let bins = counts
+            .into_iter()
+            .enumerate()
+            .filter(|(_, count)| *count > 0)
+            .map(|(index, count)| {
+                let x = min.x + (index % bins_per_axis) as f64 * x_step;
+                let y = min.y + (index / bins_per_axis) as f64 * y_step;
+
+                DensityBin {
+                    x,
+                    x2: x + x_step,
+                    y,
+                    y2: y + y_step,
+                    count,
+                }
+            })
+            .collect();
+
+        Self {
+            x_label,
+            y_label,
+            bins,
+        }
+    }
+}
+
+impl Plot for DensityScatterPlot {
+    fn to_vega_embeddable(&self, _allow_interactions: bool) -> Result<PlotData> {
+        let data = self
+            .bins
+            .iter()
+            .map(|bin| {
+                serde_json::json!({
+                    "x": bin.x,
+                    "x2": bin.x2,
+                    "y": bin.y,
+                    "y2": bin.y2,
+                    "count": bin.count,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let vega_string = serde_json::json!({
+            "$schema": "https://vega.github.io/schema/vega-lite/v4.17.0.json",
+            "data": {
+                "values": data
+            },
+            "description": "Density Scatter Plot",
+            "encoding": {
+                "x": {
+                    "field": "x",
+                    "title": self.x_label,
+                    "type": "quantitative",
+                    "scale": {
+                        "zero": false
+                    }
+                },
+                "x2": {
+                    "field": "x2"
+                },
+                "y": {
+                    "field": "y",
+                    "title": self.y_label,
+                    "type": "quantitative",
+                    "scale": {
+                        "zero": false
+                    }
+                },
+                "y2": {
+                    "field": "y2"
+                },
+                "color": {
+                    "field": "count",
+                    "title": "Count",
+                    "type": "quantitative"
+                }
+            },
+            "mark": "rect"
+        })
+        .to_string();
+
+        Ok(PlotData {
+            vega_string,
+            metadata: PlotMetaData::None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialization() {
+        let plot = ScatterPlot::new(
+            "foo".to_string(),
+            "bar".to_string(),
+            vec![(1., 2.).into(), (3., 4.).into()],
+        );
+
+        assert_eq!(
+            plot.to_vega_embeddable(false).unwrap(),
+            PlotData {
+                vega_string: r#"{"$schema":"https://vega.github.io/schema/vega-lite/v4.17.0.json","data":{"values":[{"x":1.0,"y":2.0},{"x":3.0,"y":4.0}]},"description":"Scatter Plot","encoding":{"x":{"field":"x","title":"foo","type":"quantitative","scale":{"zero":false}},"y":{"field":"y","title":"bar","type":"quantitative","scale":{"zero":false}}},"mark":"point"}"#.to_owned(),
+                metadata: PlotMetaData::None,
+            }
+        );
+    }
+
+    #[test]
+    fn density_bins() {
+        let plot = DensityScatterPlot::new(
+            "foo".to_string(),
+            "bar".to_string(),
+            &[
+                (0., 0.).into(),
+                (0.5, 0.5).into(),
+                (4., 0.).into(),
+                (4., 2.).into(),
+            ],
+            2,
+        );
+
+        let data = plot
+            .to_vega_embeddable(false)
+            .unwrap()
+            .data_values()
+            .unwrap();
+
+        assert_eq!(
+            data,
+            serde_json::json!([
+                {"x": 0.0, "x2": 2.0, "y": 0.0, "y2": 1.0, "count": 2},
+                {"x": 2.0, "x2": 4.0, "y": 0.0, "y2": 1.0, "count": 1},
+                {"x": 2.0, "x2": 4.0, "y": 1.0, "y2": 2.0, "count": 1},
+            ])
+        );
+    }
+
+    #[test]
+    fn density_bins_without_extent() {
+        let plot = DensityScatterPlot::new(
+            "foo".to_string(),
+            "bar".to_string(),
+            &[(1., 1.).into(), (1., 1.).into()],
+            10,
+        );
+
+        let data = plot
+            .to_vega_embeddable(false)
+            .unwrap()
+            .data_values()
+            .unwrap();
+
+        assert_eq!(
+            data,
+            serde_json::json!([
+                {"x": 1.0, "x2": 2.0, "y": 1.0, "y2": 2.0, "count": 2},
+            ])
+        );
+    }
+}
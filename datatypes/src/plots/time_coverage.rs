@@ -0,0 +1,82 @@
+use crate::plots::{Plot, PlotData, PlotMetaData};
+use crate::primitives::TimeInterval;
+use crate::util::Result;
+
+/// A Gantt-like chart that shows which time intervals are covered by data, e.g., by features or raster time steps
+pub struct TimeCoverageChart {
+    /// the covered intervals with the label of their row
+    intervals: Vec<(String, TimeInterval)>,
+}
+
+impl TimeCoverageChart {
+    pub fn new(intervals: Vec<(String, TimeInterval)>) -> Self {
+        Self { intervals }
+    }
+}
+
+impl Plot for TimeCoverageChart {
+    fn to_vega_embeddable(&self, _allow_interactions: bool) -> Result<PlotData> {
+        let data = self
+            .intervals
+            .iter()
+            .map(|(label, time)| {
+                serde_json::json!({
+                    "label": label,
+                    "start": time.start().as_rfc3339(),
+                    "end": time.end().as_rfc3339(),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let vega_string = serde_json::json!({
+            "$schema": "https://vega.github.io/schema/vega-lite/v4.17.0.json",
+            "data": {
+                "values": data
+            },
+            "description": "Time Coverage Chart",
+            "encoding": {
+                "x": {
+                    "field": "start",
+                    "title": "Time",
+                    "type": "temporal"
+                },
+                "x2": {
+                    "field": "end"
+                },
+                "y": {
+                    "field": "label",
+                    "title": "",
+                    "type": "nominal"
+                }
+            },
+            "mark": "bar"
+        })
+        .to_string();
+
+        Ok(PlotData {
+            vega_string,
+            metadata: PlotMetaData::None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialization() {
+        let chart = TimeCoverageChart::new(vec![
+            ("a".to_string(), TimeInterval::new_unchecked(0, 1000)),
+            ("b".to_string(), TimeInterval::new_unchecked(500, 2000)),
+        ]);
+
+        assert_eq!(
+            chart.to_vega_embeddable(false).unwrap(),
+            PlotData {
+                vega_string: r#"{"$schema":"https://vega.github.io/schema/vega-lite/v4.17.0.json","data":{"values":[{"label":"a","start":"1970-01-01T00:00:00+00:00","end":"1970-01-01T00:00:01+00:00"},{"label":"b","start":"1970-01-01T00:00:00.500+00:00","end":"1970-01-01T00:00:02+00:00"}]},"description":"Time Coverage Chart","encoding":{"x":{"field":"start","title":"Time","type":"temporal"},"x2":{"field":"end"},"y":{"field":"label","title":"","type":"nominal"}},"mark":"bar"}"#.to_owned(),
+                metadata: PlotMetaData::None,
+            }
+        );
+    }
+}
@@ -0,0 +1,509 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::error;
+use crate::primitives::{DataRef, FeatureData, FeatureDataRef, FeatureDataValue};
+use crate::util::Result;
+
+/// An arithmetic operation that is applied element-wise to two operands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ArithmeticOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+/// A comparison that is applied element-wise to two operands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ComparisonOperator {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl ComparisonOperator {
+    fn matches(self, ordering: Ordering) -> bool {
+        match self {
+            ComparisonOperator::Equal => ordering == Ordering::Equal,
+            ComparisonOperator::NotEqual => ordering != Ordering::Equal,
+            ComparisonOperator::Less => ordering == Ordering::Less,
+            ComparisonOperator::LessOrEqual => ordering != Ordering::Greater,
+            ComparisonOperator::Greater => ordering == Ordering::Greater,
+            ComparisonOperator::GreaterOrEqual => ordering != Ordering::Less,
+        }
+    }
+}
+
+/// An operand of a column kernel.
+/// Scalars are broadcast to the length of the other operand.
+#[derive(Debug, Clone)]
+pub enum ColumnOperand<'c> {
+    Column(FeatureDataRef<'c>),
+    Scalar(FeatureDataValue),
+}
+
+impl<'c> From<FeatureDataRef<'c>> for ColumnOperand<'c> {
+    fn from(column: FeatureDataRef<'c>) -> Self {
+        Self::Column(column)
+    }
+}
+
+impl From<FeatureDataValue> for ColumnOperand<'_> {
+    fn from(scalar: FeatureDataValue) -> Self {
+        Self::Scalar(scalar)
+    }
+}
+
+impl<'c> ColumnOperand<'c> {
+    fn len(&self) -> Option<usize> {
+        match self {
+            ColumnOperand::Column(FeatureDataRef::Category(column)) => Some(column.len()),
+            ColumnOperand::Column(FeatureDataRef::Int(column)) => Some(column.len()),
+            ColumnOperand::Column(FeatureDataRef::Float(column)) => Some(column.len()),
+            ColumnOperand::Column(FeatureDataRef::Text(column)) => Some(column.len()),
+            ColumnOperand::Column(FeatureDataRef::Bytes(column)) => Some(column.len()),
+            ColumnOperand::Column(FeatureDataRef::Dictionary(column)) => Some(column.len()),
+            ColumnOperand::Scalar(_) => None,
+        }
+    }
+
+    fn is_int(&self) -> bool {
+        matches!(
+            self,
+            ColumnOperand::Column(FeatureDataRef::Int(_))
+                | ColumnOperand::Scalar(FeatureDataValue::Int(_))
+                | ColumnOperand::Scalar(FeatureDataValue::NullableInt(_))
+        )
+    }
+
+    fn is_text(&self) -> bool {
+        matches!(
+            self,
+            ColumnOperand::Column(FeatureDataRef::Text(_))
+                | ColumnOperand::Column(FeatureDataRef::Dictionary(_))
+                | ColumnOperand::Scalar(FeatureDataValue::Text(_))
+                | ColumnOperand::Scalar(FeatureDataValue::NullableText(_))
+                | ColumnOperand::Scalar(FeatureDataValue::Dictionary(_))
+                | ColumnOperand::Scalar(FeatureDataValue::NullableDictionary(_))
+        )
+    }
+
+    /// Null flags of the operand or `None` if it has no nulls
+    fn nulls(&self, len: usize) -> Option<Vec<bool>> {
+        match self {
+            ColumnOperand::Column(column) if column.has_nulls() => Some(column.nulls()),
+            ColumnOperand::Column(_) => None,
+            ColumnOperand::Scalar(
+                FeatureDataValue::NullableInt(None)
+                | FeatureDataValue::NullableFloat(None)
+                | FeatureDataValue::NullableText(None)
+                | FeatureDataValue::NullableCategory(None)
+                | FeatureDataValue::NullableBytes(None)
+                | FeatureDataValue::NullableDictionary(None),
+            ) => Some(vec![true; len]),
+            ColumnOperand::Scalar(_) => None,
+        }
+    }
+
+    fn int_values(&self, len: usize) -> Result<Cow<'_, [i64]>> {
+        Ok(match self {
+            ColumnOperand::Column(FeatureDataRef::Int(column)) => Cow::Borrowed(column.as_ref()),
+            ColumnOperand::Scalar(FeatureDataValue::Int(value)) => Cow::Owned(vec![*value; len]),
+            ColumnOperand::Scalar(FeatureDataValue::NullableInt(value)) => {
+                Cow::Owned(vec![value.unwrap_or_default(); len])
+            }
+            _ => return Err(Self::not_numeric()),
+        })
+    }
+
+    fn float_values(&self, len: usize) -> Result<Cow<'_, [f64]>> {
+        Ok(match self {
+            ColumnOperand::Column(FeatureDataRef::Float(column)) => Cow::Borrowed(column.as_ref()),
+            ColumnOperand::Column(FeatureDataRef::Int(column)) => {
+                Cow::Owned(column.as_ref().iter().map(|&v| v as f64).collect())
+            }
+            ColumnOperand::Scalar(FeatureDataValue::Float(value)) => Cow::Owned(vec![*value; len]),
+            ColumnOperand::Scalar(FeatureDataValue::NullableFloat(value)) => {
+                Cow::Owned(vec![value.unwrap_or_default(); len])
+            }
+            ColumnOperand::Scalar(FeatureDataValue::Int(value)) => {
+                Cow::Owned(vec![*value as f64; len])
+            }
+            ColumnOperand::Scalar(FeatureDataValue::NullableInt(value)) => {
+                Cow::Owned(vec![value.unwrap_or_default() as f64; len])
+            }
+            _ => return Err(Self::not_numeric()),
+        })
+    }
+
+    fn text_values(&self, len: usize) -> Result<Vec<Option<&str>>> {
+        match self {
+            ColumnOperand::Column(FeatureDataRef::Text(column)) => {
+                (0..len).map(|i| column.text_at(i)).collect()
+            }
+            ColumnOperand::Column(FeatureDataRef::Dictionary(column)) => {
+                (0..len).map(|i| column.value_at(i)).collect()
+            }
+            ColumnOperand::Scalar(
+                FeatureDataValue::Text(value) | FeatureDataValue::Dictionary(value),
+            ) => Ok(vec![Some(value.as_str()); len]),
+            ColumnOperand::Scalar(
+                FeatureDataValue::NullableText(value) | FeatureDataValue::NullableDictionary(value),
+            ) => Ok(vec![value.as_deref(); len]),
+            _ => Err(error::Error::FeatureData {
+                details: "Operand must be a text column or value".to_string(),
+            }),
+        }
+    }
+
+    fn not_numeric() -> error::Error {
+        error::Error::FeatureData {
+            details: "Operand must be an int or float column or value".to_string(),
+        }
+    }
+}
+
+/// Determines the common length of two operands
+fn operands_len(lhs: &ColumnOperand, rhs: &ColumnOperand) -> Result<usize> {
+    match (lhs.len(), rhs.len()) {
+        (Some(lhs_len), Some(rhs_len)) => {
+            ensure!(
+                lhs_len == rhs_len,
+                error::FeatureData {
+                    details: format!(
+                        "Columns must have equal lengths ({} ≠ {})",
+                        lhs_len, rhs_len
+                    )
+                }
+            );
+            Ok(lhs_len)
+        }
+        (Some(len), None) | (None, Some(len)) => Ok(len),
+        (None, None) => Err(error::Error::FeatureData {
+            details: "At least one operand must be a column".to_string(),
+        }),
+    }
+}
+
+/// Combines the null flags of two operands, s.t. a result is null if any of its inputs is null
+fn combine_nulls(lhs: Option<Vec<bool>>, rhs: Option<Vec<bool>>) -> Option<Vec<bool>> {
+    match (lhs, rhs) {
+        (Some(mut lhs), Some(rhs)) => {
+            for (l, r) in lhs.iter_mut().zip(rhs) {
+                *l |= r;
+            }
+            Some(lhs)
+        }
+        (nulls, None) | (None, nulls) => nulls,
+    }
+}
+
+/// Applies `op` element-wise to two operands.
+///
+/// The result is null if any input is null.
+/// Two int operands result in an int column for all operations but division, which yields floats.
+/// Overflows and divisions by zero result in nulls.
+///
+/// # Errors
+///
+/// This function fails if an operand is not numeric, the column lengths differ or both operands are scalars
+///
+/// # Examples
+///
+/// ```rust
+/// use geoengine_datatypes::primitives::column_kernels::{arithmetic, ArithmeticOperator, ColumnOperand};
+/// use geoengine_datatypes::primitives::{FeatureData, FeatureDataRef, FeatureDataValue, FloatDataRef};
+///
+/// let values = [1., 2., 3.];
+/// let column = FeatureDataRef::Float(FloatDataRef::new(&values, &None));
+///
+/// let result = arithmetic(
+///     &ColumnOperand::Column(column),
+///     &ColumnOperand::Scalar(FeatureDataValue::Int(2)),
+///     ArithmeticOperator::Multiply,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(result, FeatureData::Float(vec![2., 4., 6.]));
+/// ```
+pub fn arithmetic(
+    lhs: &ColumnOperand,
+    rhs: &ColumnOperand,
+    op: ArithmeticOperator,
+) -> Result<FeatureData> {
+    let len = operands_len(lhs, rhs)?;
+    let nulls = combine_nulls(lhs.nulls(len), rhs.nulls(len));
+
+    if lhs.is_int() && rhs.is_int() && op != ArithmeticOperator::Divide {
+        let lhs = lhs.int_values(len)?;
+        let rhs = rhs.int_values(len)?;
+
+        let checked_op = match op {
+            ArithmeticOperator::Add => i64::checked_add,
+            ArithmeticOperator::Subtract => i64::checked_sub,
+            ArithmeticOperator::Multiply => i64::checked_mul,
+            ArithmeticOperator::Divide => unreachable!("dividing ints yields floats"),
+        };
+
+        let values: Vec<Option<i64>> = lhs
+            .iter()
+            .zip(rhs.iter())
+            .map(|(&l, &r)| checked_op(l, r))
+            .collect();
+
+        return Ok(int_feature_data(values, nulls));
+    }
+
+    let lhs = lhs.float_values(len)?;
+    let rhs = rhs.float_values(len)?;
+
+    let values: Vec<f64> = match op {
+        ArithmeticOperator::Add => lhs.iter().zip(rhs.iter()).map(|(l, r)| l + r).collect(),
+        ArithmeticOperator::Subtract => lhs.iter().zip(rhs.iter()).map(|(l, r)| l - r).collect(),
+        ArithmeticOperator::Multiply => lhs.iter().zip(rhs.iter()).map(|(l, r)| l * r).collect(),
+        ArithmeticOperator::Divide => lhs.iter().zip(rhs.iter()).map(|(l, r)| l / r).collect(),
+    };
+
+    Ok(float_feature_data(values, nulls))
+}
+
+fn int_feature_data(values: Vec<Option<i64>>, nulls: Option<Vec<bool>>) -> FeatureData {
+    let has_overflows = values.iter().any(Option::is_none);
+
+    match nulls {
+        Some(nulls) => FeatureData::NullableInt(
+            values
+                .into_iter()
+                .zip(nulls)
+                .map(|(value, is_null)| if is_null { None } else { value })
+                .collect(),
+        ),
+        None if has_overflows => FeatureData::NullableInt(values),
+        None => FeatureData::Int(values.into_iter().map(Option::unwrap_or_default).collect()),
+    }
+}
+
+fn float_feature_data(values: Vec<f64>, nulls: Option<Vec<bool>>) -> FeatureData {
+    let has_non_finite = values.iter().any(|v| !v.is_finite());
+
+    match nulls {
+        None if !has_non_finite => FeatureData::Float(values),
+        nulls => {
+            let nulls = nulls.unwrap_or_else(|| vec![false; values.len()]);
+            FeatureData::NullableFloat(
+                values
+                    .into_iter()
+                    .zip(nulls)
+                    .map(|(value, is_null)| {
+                        if is_null || !value.is_finite() {
+                            None
+                        } else {
+                            Some(value)
+                        }
+                    })
+                    .collect(),
+            )
+        }
+    }
+}
+
+/// Compares two operands element-wise.
+///
+/// Numeric operands are compared by value and text operands lexicographically.
+/// The result is `None` if any input is null.
+/// Use `Option::unwrap_or(false)` on the result to obtain a filter mask that drops nulls.
+///
+/// # Errors
+///
+/// This function fails if the operands are not both numeric or both text, the column lengths differ or both operands are scalars
+///
+pub fn compare(
+    lhs: &ColumnOperand,
+    rhs: &ColumnOperand,
+    op: ComparisonOperator,
+) -> Result<Vec<Option<bool>>> {
+    let len = operands_len(lhs, rhs)?;
+    let nulls = combine_nulls(lhs.nulls(len), rhs.nulls(len));
+
+    let results: Vec<bool> = if lhs.is_text() || rhs.is_text() {
+        let lhs = lhs.text_values(len)?;
+        let rhs = rhs.text_values(len)?;
+
+        lhs.iter()
+            .zip(rhs.iter())
+            .map(|(l, r)| op.matches(l.unwrap_or_default().cmp(r.unwrap_or_default())))
+            .collect()
+    } else if lhs.is_int() && rhs.is_int() {
+        let lhs = lhs.int_values(len)?;
+        let rhs = rhs.int_values(len)?;
+
+        lhs.iter()
+            .zip(rhs.iter())
+            .map(|(l, r)| op.matches(l.cmp(r)))
+            .collect()
+    } else {
+        let lhs = lhs.float_values(len)?;
+        let rhs = rhs.float_values(len)?;
+
+        // `NaN`s are incomparable and thus only unequal to anything
+        lhs.iter()
+            .zip(rhs.iter())
+            .map(|(l, r)| {
+                l.partial_cmp(r)
+                    .map_or(op == ComparisonOperator::NotEqual, |ordering| {
+                        op.matches(ordering)
+                    })
+            })
+            .collect()
+    };
+
+    Ok(match nulls {
+        Some(nulls) => results
+            .into_iter()
+            .zip(nulls)
+            .map(|(result, is_null)| if is_null { None } else { Some(result) })
+            .collect(),
+        None => results.into_iter().map(Some).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::{FloatDataRef, IntDataRef, TextDataRef};
+    use arrow::array::{Array, Float64Array, Int64Array, StringArray};
+
+    #[test]
+    fn it_adds_ints_and_propagates_nulls() {
+        let lhs = Int64Array::from(vec![Some(1), None, Some(3), Some(i64::MAX)]);
+        let rhs = Int64Array::from(vec![Some(10), Some(20), None, Some(1)]);
+
+        let lhs_nulls = lhs.data_ref().null_bitmap().clone();
+        let rhs_nulls = rhs.data_ref().null_bitmap().clone();
+
+        let result = arithmetic(
+            &FeatureDataRef::Int(IntDataRef::new(lhs.values(), &lhs_nulls)).into(),
+            &FeatureDataRef::Int(IntDataRef::new(rhs.values(), &rhs_nulls)).into(),
+            ArithmeticOperator::Add,
+        )
+        .unwrap();
+
+        assert_eq!(
+            result,
+            FeatureData::NullableInt(vec![Some(11), None, None, None])
+        );
+    }
+
+    #[test]
+    fn it_divides_into_floats() {
+        let lhs = [1, 2, 3];
+        let rhs = [2., 0., 4.];
+
+        let result = arithmetic(
+            &FeatureDataRef::Int(IntDataRef::new(&lhs, &None)).into(),
+            &FeatureDataRef::Float(FloatDataRef::new(&rhs, &None)).into(),
+            ArithmeticOperator::Divide,
+        )
+        .unwrap();
+
+        assert_eq!(
+            result,
+            FeatureData::NullableFloat(vec![Some(0.5), None, Some(0.75)])
+        );
+
+        let result = arithmetic(
+            &FeatureDataRef::Int(IntDataRef::new(&lhs, &None)).into(),
+            &FeatureDataValue::Int(2).into(),
+            ArithmeticOperator::Divide,
+        )
+        .unwrap();
+
+        assert_eq!(result, FeatureData::Float(vec![0.5, 1., 1.5]));
+    }
+
+    #[test]
+    fn it_broadcasts_null_scalars() {
+        let values = [1., 2.];
+
+        let result = arithmetic(
+            &FeatureDataValue::NullableFloat(None).into(),
+            &FeatureDataRef::Float(FloatDataRef::new(&values, &None)).into(),
+            ArithmeticOperator::Subtract,
+        )
+        .unwrap();
+
+        assert_eq!(result, FeatureData::NullableFloat(vec![None, None]));
+    }
+
+    #[test]
+    fn it_rejects_invalid_operands() {
+        let ints = [1, 2];
+        let floats = [1., 2., 3.];
+
+        assert!(arithmetic(
+            &FeatureDataRef::Int(IntDataRef::new(&ints, &None)).into(),
+            &FeatureDataRef::Float(FloatDataRef::new(&floats, &None)).into(),
+            ArithmeticOperator::Add,
+        )
+        .is_err());
+
+        assert!(arithmetic(
+            &FeatureDataValue::Int(1).into(),
+            &FeatureDataValue::Int(2).into(),
+            ArithmeticOperator::Add,
+        )
+        .is_err());
+
+        assert!(arithmetic(
+            &FeatureDataRef::Int(IntDataRef::new(&ints, &None)).into(),
+            &FeatureDataValue::Text("foo".to_string()).into(),
+            ArithmeticOperator::Add,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn it_compares_numbers() {
+        let values = Float64Array::from(vec![Some(1.), None, Some(3.), Some(f64::NAN)]);
+        let nulls = values.data_ref().null_bitmap().clone();
+
+        let result = compare(
+            &FeatureDataRef::Float(FloatDataRef::new(values.values(), &nulls)).into(),
+            &FeatureDataValue::Int(2).into(),
+            ComparisonOperator::Less,
+        )
+        .unwrap();
+
+        assert_eq!(result, vec![Some(true), None, Some(false), Some(false)]);
+    }
+
+    #[test]
+    fn it_compares_text() {
+        let values = StringArray::from(vec![Some("a"), Some("b"), None]);
+        let nulls = values.data_ref().null_bitmap().clone();
+
+        let column = FeatureDataRef::Text(TextDataRef::new(
+            values.value_data(),
+            values.value_offsets(),
+            &nulls,
+        ));
+
+        let result = compare(
+            &column.into(),
+            &FeatureDataValue::Text("b".to_string()).into(),
+            ComparisonOperator::GreaterOrEqual,
+        )
+        .unwrap();
+
+        assert_eq!(result, vec![Some(false), Some(true), None]);
+    }
+}
@@ -6,6 +6,7 @@ use ocl::OclPrm;
 #[cfg(feature = "postgres")]
 use postgres_types::{FromSql, ToSql};
 use proj::Coord;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::{
     fmt,
@@ -13,7 +14,9 @@ use std::{
     slice,
 };
 
-#[derive(Clone, Copy, Debug, Deserialize, PartialEq, PartialOrd, Serialize, Default)]
+#[derive(
+    Clone, Copy, Debug, Deserialize, PartialEq, PartialOrd, Serialize, Default, JsonSchema,
+)]
 #[cfg_attr(feature = "postgres", derive(ToSql, FromSql))]
 #[repr(C)]
 pub struct Coordinate2D {
@@ -9,7 +9,7 @@ use serde_json::Value;
 use snafu::ensure;
 use std::convert::TryFrom;
 use std::str;
-use std::{marker::PhantomData, slice};
+use std::{iter, marker::PhantomData, slice};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -18,6 +18,9 @@ pub enum FeatureDataType {
     Int,
     Float,
     Text,
+    Bytes,
+    /// Text with few distinct values that is stored as codes into a table of these values
+    Dictionary,
 }
 
 impl FeatureDataType {
@@ -26,6 +29,7 @@ impl FeatureDataType {
             OGRFieldType::OFTInteger | OGRFieldType::OFTInteger64 => Self::Int,
             OGRFieldType::OFTReal => Self::Float,
             OGRFieldType::OFTString => Self::Text,
+            OGRFieldType::OFTBinary => Self::Bytes,
             _ => return Err(error::Error::NoMatchingFeatureDataTypeForOgrFieldType),
         })
     }
@@ -45,6 +49,10 @@ pub enum FeatureData {
     NullableFloat(Vec<Option<f64>>),
     Text(Vec<String>),
     NullableText(Vec<Option<String>>),
+    Bytes(Vec<Vec<u8>>),
+    NullableBytes(Vec<Option<Vec<u8>>>),
+    Dictionary(Vec<String>),
+    NullableDictionary(Vec<Option<String>>),
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
@@ -57,6 +65,10 @@ pub enum FeatureDataValue {
     NullableFloat(Option<f64>),
     Text(String),
     NullableText(Option<String>),
+    Bytes(Vec<u8>),
+    NullableBytes(Option<Vec<u8>>),
+    Dictionary(String),
+    NullableDictionary(Option<String>),
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -65,6 +77,8 @@ pub enum FeatureDataRef<'f> {
     Int(IntDataRef<'f>),
     Float(FloatDataRef<'f>),
     Text(TextDataRef<'f>),
+    Bytes(BytesDataRef<'f>),
+    Dictionary(DictionaryDataRef<'f>),
 }
 
 impl<'f> FeatureDataRef<'f> {
@@ -72,6 +86,8 @@ impl<'f> FeatureDataRef<'f> {
     pub fn json_values(&self) -> Box<dyn Iterator<Item = serde_json::Value> + '_> {
         match self {
             FeatureDataRef::Text(data_ref) => data_ref.json_values(),
+            FeatureDataRef::Bytes(data_ref) => data_ref.json_values(),
+            FeatureDataRef::Dictionary(data_ref) => data_ref.json_values(),
             FeatureDataRef::Float(data_ref) => data_ref.json_values(),
             FeatureDataRef::Int(data_ref) => data_ref.json_values(),
             FeatureDataRef::Category(data_ref) => data_ref.json_values(),
@@ -82,6 +98,8 @@ impl<'f> FeatureDataRef<'f> {
     pub fn nulls(&self) -> Vec<bool> {
         match self {
             FeatureDataRef::Text(data_ref) => data_ref.nulls(),
+            FeatureDataRef::Bytes(data_ref) => data_ref.nulls(),
+            FeatureDataRef::Dictionary(data_ref) => data_ref.nulls(),
             FeatureDataRef::Float(data_ref) => data_ref.nulls(),
             FeatureDataRef::Int(data_ref) => data_ref.nulls(),
             FeatureDataRef::Category(data_ref) => data_ref.nulls(),
@@ -92,6 +110,8 @@ impl<'f> FeatureDataRef<'f> {
     pub fn has_nulls(&self) -> bool {
         match self {
             FeatureDataRef::Text(data_ref) => data_ref.has_nulls(),
+            FeatureDataRef::Bytes(data_ref) => data_ref.has_nulls(),
+            FeatureDataRef::Dictionary(data_ref) => data_ref.has_nulls(),
             FeatureDataRef::Float(data_ref) => data_ref.has_nulls(),
             FeatureDataRef::Int(data_ref) => data_ref.has_nulls(),
             FeatureDataRef::Category(data_ref) => data_ref.has_nulls(),
@@ -102,6 +122,8 @@ impl<'f> FeatureDataRef<'f> {
     pub fn get_unchecked(&self, i: usize) -> FeatureDataValue {
         match self {
             FeatureDataRef::Text(data_ref) => data_ref.get_unchecked(i),
+            FeatureDataRef::Bytes(data_ref) => data_ref.get_unchecked(i),
+            FeatureDataRef::Dictionary(data_ref) => data_ref.get_unchecked(i),
             FeatureDataRef::Float(data_ref) => data_ref.get_unchecked(i),
             FeatureDataRef::Int(data_ref) => data_ref.get_unchecked(i),
             FeatureDataRef::Category(data_ref) => data_ref.get_unchecked(i),
@@ -113,6 +135,8 @@ impl<'f> FeatureDataRef<'f> {
     pub fn strings_iter(&self) -> Box<dyn Iterator<Item = String> + '_> {
         match self {
             FeatureDataRef::Text(data_ref) => Box::new(data_ref.strings_iter()),
+            FeatureDataRef::Bytes(data_ref) => Box::new(data_ref.strings_iter()),
+            FeatureDataRef::Dictionary(data_ref) => Box::new(data_ref.strings_iter()),
             FeatureDataRef::Float(data_ref) => Box::new(data_ref.strings_iter()),
             FeatureDataRef::Int(data_ref) => Box::new(data_ref.strings_iter()),
             FeatureDataRef::Category(data_ref) => Box::new(data_ref.strings_iter()),
@@ -124,6 +148,8 @@ impl<'f> FeatureDataRef<'f> {
     pub fn float_options_iter(&self) -> Box<dyn Iterator<Item = Option<f64>> + '_> {
         match self {
             FeatureDataRef::Text(data_ref) => Box::new(data_ref.float_options_iter()),
+            FeatureDataRef::Bytes(data_ref) => Box::new(data_ref.float_options_iter()),
+            FeatureDataRef::Dictionary(data_ref) => Box::new(data_ref.float_options_iter()),
             FeatureDataRef::Float(data_ref) => Box::new(data_ref.float_options_iter()),
             FeatureDataRef::Int(data_ref) => Box::new(data_ref.float_options_iter()),
             FeatureDataRef::Category(data_ref) => Box::new(data_ref.float_options_iter()),
@@ -774,10 +800,389 @@ impl<'r> TextDataRef<'r> {
     }
 }
 
+/// A reference to nullable binary data, e.g., thumbnails or encoded sensor payloads
+///
+/// # Examples
+///
+/// ```rust
+/// use geoengine_datatypes::primitives::BytesDataRef;
+/// use arrow::array::{BinaryBuilder, Array};
+///
+/// let binary_array = {
+///     let mut builder = BinaryBuilder::new(3);
+///     builder.append_value(b"foo");
+///     builder.append_null();
+///     builder.append_value(b"");
+///     builder.finish()
+/// };
+///
+/// let bytes_data_ref = BytesDataRef::new(binary_array.value_data(), binary_array.value_offsets(), binary_array.data_ref().null_bitmap());
+///
+/// assert_eq!(bytes_data_ref.bytes_at(0).unwrap(), Some(&b"foo"[..]));
+/// assert_eq!(bytes_data_ref.bytes_at(1).unwrap(), None);
+/// assert_eq!(bytes_data_ref.bytes_at(2).unwrap(), Some(&b""[..]));
+/// assert!(bytes_data_ref.bytes_at(3).is_err());
+/// ```
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct BytesDataRef<'f> {
+    data_buffer: arrow::buffer::Buffer,
+    offsets: &'f [i32],
+    valid_bitmap: &'f Option<arrow::bitmap::Bitmap>,
+}
+
+impl<'f> AsRef<[u8]> for BytesDataRef<'f> {
+    fn as_ref(&self) -> &[u8] {
+        self.data_buffer.as_slice()
+    }
+}
+
+impl<'r> DataRef<'r, u8> for BytesDataRef<'r> {
+    /// Outputs base64 encoded strings since JSON has no binary type
+    fn json_values(&'r self) -> Box<dyn Iterator<Item = serde_json::Value> + 'r> {
+        Box::new((0..self.len()).map(move |pos| {
+            match self.bytes_at(pos).expect("pos is in data range") {
+                Some(bytes) => base64::encode(bytes).into(),
+                None => serde_json::Value::Null,
+            }
+        }))
+    }
+
+    fn json_value(value: &u8) -> Value {
+        (*value).into()
+    }
+
+    fn nulls(&self) -> Vec<bool> {
+        null_bitmap_to_bools(self.valid_bitmap, self.len())
+    }
+
+    fn is_valid(&self, i: usize) -> bool {
+        self.valid_bitmap
+            .as_ref()
+            .map_or(true, |bitmap| bitmap.is_set(i))
+    }
+
+    fn has_nulls(&self) -> bool {
+        self.valid_bitmap.is_some()
+    }
+
+    fn get_unchecked(&self, i: usize) -> FeatureDataValue {
+        let bytes = self.bytes_at(i).expect("unchecked").map(<[u8]>::to_vec);
+
+        if self.has_nulls() {
+            FeatureDataValue::NullableBytes(bytes)
+        } else {
+            FeatureDataValue::Bytes(bytes.expect("cannot be null"))
+        }
+    }
+
+    type StringsIter = BytesDataRefStringIter<'r>;
+
+    fn strings_iter(&'r self) -> Self::StringsIter {
+        Self::StringsIter::new(self)
+    }
+
+    fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    type FloatOptionsIter = iter::Take<iter::Repeat<Option<f64>>>;
+
+    /// Binary data is never convertible to numbers
+    fn float_options_iter(&'r self) -> Self::FloatOptionsIter {
+        iter::repeat(None).take(self.len())
+    }
+}
+
+/// Outputs base64 encoded strings
+pub struct BytesDataRefStringIter<'r> {
+    data_ref: &'r BytesDataRef<'r>,
+    i: usize,
+}
+
+impl<'r> BytesDataRefStringIter<'r> {
+    pub fn new(data_ref: &'r BytesDataRef<'r>) -> Self {
+        Self { data_ref, i: 0 }
+    }
+}
+
+impl<'r> Iterator for BytesDataRefStringIter<'r> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let i = self.i;
+        self.i += 1;
+
+        self.data_ref
+            .bytes_at(i)
+            .map(|bytes_option| bytes_option.map(base64::encode).unwrap_or_default())
+            .ok()
+    }
+}
+
+impl<'r> From<BytesDataRef<'r>> for FeatureDataRef<'r> {
+    fn from(data_ref: BytesDataRef<'r>) -> Self {
+        Self::Bytes(data_ref)
+    }
+}
+
+impl<'r> BytesDataRef<'r> {
+    pub fn new(
+        data_buffer: arrow::buffer::Buffer,
+        offsets: &'r [i32],
+        valid_bitmap: &'r Option<arrow::bitmap::Bitmap>,
+    ) -> Self {
+        Self {
+            data_buffer,
+            offsets,
+            valid_bitmap,
+        }
+    }
+
+    /// Returns the offsets of the individual byte arrays
+    pub fn offsets(&self) -> &[i32] {
+        self.offsets
+    }
+
+    /// Returns the bytes at a certain position in the feature collection
+    ///
+    /// # Errors
+    ///
+    /// This method fails if `pos` is out of bounds
+    ///
+    pub fn bytes_at(&self, pos: usize) -> Result<Option<&[u8]>> {
+        ensure!(
+            pos < self.len(),
+            error::FeatureData {
+                details: "Position must be in data range"
+            }
+        );
+
+        if self.is_null(pos) {
+            return Ok(None);
+        }
+
+        let start = self.offsets[pos] as usize;
+        let end = self.offsets[pos + 1] as usize;
+
+        Ok(Some(&self.data_buffer.as_slice()[start..end]))
+    }
+}
+
+/// A reference to nullable dictionary-encoded text data
+///
+/// # Examples
+///
+/// ```rust
+/// use geoengine_datatypes::primitives::{DictionaryDataRef, TextDataRef};
+/// use arrow::array::{Array, Int32Builder, StringArray, StringBuilder, StringDictionaryBuilder};
+/// use arrow::datatypes::Int32Type;
+///
+/// let dictionary_array = {
+///     let mut builder = StringDictionaryBuilder::new(Int32Builder::new(4), StringBuilder::new(2));
+///     builder.append("forest").unwrap();
+///     builder.append_null().unwrap();
+///     builder.append("water").unwrap();
+///     builder.append("forest").unwrap();
+///     builder.finish()
+/// };
+///
+/// let values: &StringArray = dictionary_array.values().as_any().downcast_ref().unwrap();
+///
+/// let dictionary_data_ref = DictionaryDataRef::new(
+///     dictionary_array.keys().values(),
+///     dictionary_array.data_ref().null_bitmap(),
+///     TextDataRef::new(values.value_data(), values.value_offsets(), values.data_ref().null_bitmap()),
+/// );
+///
+/// assert_eq!(dictionary_data_ref.codes(), &[0, 0, 1, 0]);
+/// assert_eq!(dictionary_data_ref.value_at(0).unwrap(), Some("forest"));
+/// assert_eq!(dictionary_data_ref.value_at(1).unwrap(), None);
+/// assert_eq!(dictionary_data_ref.value_at(2).unwrap(), Some("water"));
+/// assert!(dictionary_data_ref.value_at(4).is_err());
+///
+/// assert_eq!(dictionary_data_ref.class_counts(), vec![("forest", 2), ("water", 1)]);
+/// ```
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct DictionaryDataRef<'f> {
+    codes: &'f [i32],
+    valid_bitmap: &'f Option<arrow::bitmap::Bitmap>,
+    dictionary: TextDataRef<'f>,
+}
+
+impl<'f> AsRef<[i32]> for DictionaryDataRef<'f> {
+    fn as_ref(&self) -> &[i32] {
+        self.codes
+    }
+}
+
+impl<'r> DataRef<'r, i32> for DictionaryDataRef<'r> {
+    fn json_values(&'r self) -> Box<dyn Iterator<Item = serde_json::Value> + 'r> {
+        Box::new((0..self.len()).map(move |pos| {
+            match self.value_at(pos).expect("pos is in data range") {
+                Some(value) => value.into(),
+                None => serde_json::Value::Null,
+            }
+        }))
+    }
+
+    fn json_value(value: &i32) -> Value {
+        (*value).into()
+    }
+
+    fn nulls(&self) -> Vec<bool> {
+        null_bitmap_to_bools(self.valid_bitmap, self.len())
+    }
+
+    fn is_valid(&self, i: usize) -> bool {
+        self.valid_bitmap
+            .as_ref()
+            .map_or(true, |bitmap| bitmap.is_set(i))
+    }
+
+    fn has_nulls(&self) -> bool {
+        self.valid_bitmap.is_some()
+    }
+
+    fn get_unchecked(&self, i: usize) -> FeatureDataValue {
+        let value = self
+            .value_at(i)
+            .expect("unchecked")
+            .map(ToString::to_string);
+
+        if self.has_nulls() {
+            FeatureDataValue::NullableDictionary(value)
+        } else {
+            FeatureDataValue::Dictionary(value.expect("cannot be null"))
+        }
+    }
+
+    type StringsIter = DictionaryDataRefStringIter<'r>;
+
+    fn strings_iter(&'r self) -> Self::StringsIter {
+        Self::StringsIter::new(self)
+    }
+
+    fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    type FloatOptionsIter = iter::Take<iter::Repeat<Option<f64>>>;
+
+    /// Classes are never convertible to numbers
+    fn float_options_iter(&'r self) -> Self::FloatOptionsIter {
+        iter::repeat(None).take(self.len())
+    }
+}
+
+pub struct DictionaryDataRefStringIter<'r> {
+    data_ref: &'r DictionaryDataRef<'r>,
+    i: usize,
+}
+
+impl<'r> DictionaryDataRefStringIter<'r> {
+    pub fn new(data_ref: &'r DictionaryDataRef<'r>) -> Self {
+        Self { data_ref, i: 0 }
+    }
+}
+
+impl<'r> Iterator for DictionaryDataRefStringIter<'r> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let i = self.i;
+        self.i += 1;
+
+        self.data_ref
+            .value_at(i)
+            .map(|value_option| value_option.map(ToString::to_string).unwrap_or_default())
+            .ok()
+    }
+}
+
+impl<'r> From<DictionaryDataRef<'r>> for FeatureDataRef<'r> {
+    fn from(data_ref: DictionaryDataRef<'r>) -> Self {
+        Self::Dictionary(data_ref)
+    }
+}
+
+impl<'r> DictionaryDataRef<'r> {
+    pub fn new(
+        codes: &'r [i32],
+        valid_bitmap: &'r Option<arrow::bitmap::Bitmap>,
+        dictionary: TextDataRef<'r>,
+    ) -> Self {
+        Self {
+            codes,
+            valid_bitmap,
+            dictionary,
+        }
+    }
+
+    /// Returns the codes, i.e., the positions of the values in the dictionary
+    pub fn codes(&self) -> &[i32] {
+        self.codes
+    }
+
+    /// Returns the table of distinct values
+    pub fn dictionary(&self) -> &TextDataRef<'r> {
+        &self.dictionary
+    }
+
+    /// Returns the value at a certain position in the feature collection
+    ///
+    /// # Errors
+    ///
+    /// This method fails if `pos` is out of bounds
+    ///
+    pub fn value_at(&self, pos: usize) -> Result<Option<&str>> {
+        ensure!(
+            pos < self.len(),
+            error::FeatureData {
+                details: "Position must be in data range"
+            }
+        );
+
+        if self.is_null(pos) {
+            return Ok(None);
+        }
+
+        self.dictionary.text_at(self.codes[pos] as usize)
+    }
+
+    /// Counts the occurrences of the values, ordered by their first occurrence in the dictionary.
+    /// Nulls and unused values are omitted.
+    pub fn class_counts(&self) -> Vec<(&str, usize)> {
+        let mut counts = vec![0; self.dictionary.len()];
+
+        for (i, &code) in self.codes.iter().enumerate() {
+            if self.is_valid(i) {
+                counts[code as usize] += 1;
+            }
+        }
+
+        counts
+            .into_iter()
+            .enumerate()
+            .filter(|(_, count)| *count > 0)
+            .filter_map(|(code, count)| {
+                let value = self.dictionary.text_at(code).ok().flatten()?;
+                Some((value, count))
+            })
+            .collect()
+    }
+}
+
 impl FeatureDataType {
     pub fn arrow_data_type(self) -> arrow::datatypes::DataType {
         match self {
             Self::Text => arrow::datatypes::DataType::Utf8,
+            Self::Bytes => arrow::datatypes::DataType::Binary,
+            Self::Dictionary => arrow::datatypes::DataType::Dictionary(
+                Box::new(arrow::datatypes::DataType::Int32),
+                Box::new(arrow::datatypes::DataType::Utf8),
+            ),
             Self::Float => arrow::datatypes::DataType::Float64,
             Self::Int => arrow::datatypes::DataType::Int64,
             Self::Category => arrow::datatypes::DataType::UInt8,
@@ -792,6 +1197,11 @@ impl FeatureDataType {
     pub fn arrow_builder(self, len: usize) -> Box<dyn arrow::array::ArrayBuilder> {
         match self {
             Self::Text => Box::new(arrow::array::StringBuilder::new(len)),
+            Self::Bytes => Box::new(arrow::array::BinaryBuilder::new(len)),
+            Self::Dictionary => Box::new(arrow::array::StringDictionaryBuilder::new(
+                arrow::array::Int32Builder::new(len),
+                arrow::array::StringBuilder::new(len),
+            )),
             Self::Float => Box::new(arrow::array::Float64Builder::new(len)),
             Self::Int => Box::new(arrow::array::Int64Builder::new(len)),
             Self::Category => Box::new(arrow::array::UInt8Builder::new(len)),
@@ -812,6 +1222,10 @@ impl FeatureData {
         match self {
             FeatureData::Text(v) => v.len(),
             FeatureData::NullableText(v) => v.len(),
+            FeatureData::Bytes(v) => v.len(),
+            FeatureData::NullableBytes(v) => v.len(),
+            FeatureData::Dictionary(v) => v.len(),
+            FeatureData::NullableDictionary(v) => v.len(),
             FeatureData::Float(v) => v.len(),
             FeatureData::NullableFloat(v) => v.len(),
             FeatureData::Int(v) => v.len(),
@@ -853,6 +1267,48 @@ impl FeatureData {
                 }
                 Box::new(builder)
             }
+            Self::Bytes(v) => {
+                let mut builder = arrow::array::BinaryBuilder::new(v.len());
+                for bytes in v {
+                    builder.append_value(bytes)?;
+                }
+                Box::new(builder)
+            }
+            Self::NullableBytes(v) => {
+                let mut builder = arrow::array::BinaryBuilder::new(v.len());
+                for bytes_opt in v {
+                    if let Some(bytes) = bytes_opt {
+                        builder.append_value(bytes)?;
+                    } else {
+                        builder.append_null()?;
+                    }
+                }
+                Box::new(builder)
+            }
+            Self::Dictionary(v) => {
+                let mut builder = arrow::array::StringDictionaryBuilder::new(
+                    arrow::array::Int32Builder::new(v.len()),
+                    arrow::array::StringBuilder::new(v.len()),
+                );
+                for value in v {
+                    builder.append(value)?;
+                }
+                Box::new(builder)
+            }
+            Self::NullableDictionary(v) => {
+                let mut builder = arrow::array::StringDictionaryBuilder::new(
+                    arrow::array::Int32Builder::new(v.len()),
+                    arrow::array::StringBuilder::new(v.len()),
+                );
+                for value_opt in v {
+                    if let Some(value) = value_opt {
+                        builder.append(value)?;
+                    } else {
+                        builder.append_null()?;
+                    }
+                }
+                Box::new(builder)
+            }
             Self::Float(v) => {
                 let mut builder = arrow::array::Float64Builder::new(v.len());
                 builder.append_slice(v)?;
@@ -897,6 +1353,8 @@ impl From<&FeatureData> for FeatureDataType {
     fn from(value: &FeatureData) -> Self {
         match value {
             FeatureData::Text(_) | FeatureData::NullableText(_) => Self::Text,
+            FeatureData::Bytes(_) | FeatureData::NullableBytes(_) => Self::Bytes,
+            FeatureData::Dictionary(_) | FeatureData::NullableDictionary(_) => Self::Dictionary,
             FeatureData::Float(_) | FeatureData::NullableFloat(_) => Self::Float,
             FeatureData::Int(_) | FeatureData::NullableInt(_) => Self::Int,
             FeatureData::Category(_) | FeatureData::NullableCategory(_) => Self::Category,
@@ -908,6 +1366,10 @@ impl From<&FeatureDataValue> for FeatureDataType {
     fn from(value: &FeatureDataValue) -> Self {
         match value {
             FeatureDataValue::Text(_) | FeatureDataValue::NullableText(_) => Self::Text,
+            FeatureDataValue::Bytes(_) | FeatureDataValue::NullableBytes(_) => Self::Bytes,
+            FeatureDataValue::Dictionary(_) | FeatureDataValue::NullableDictionary(_) => {
+                Self::Dictionary
+            }
             FeatureDataValue::Float(_) | FeatureDataValue::NullableFloat(_) => Self::Float,
             FeatureDataValue::Int(_) | FeatureDataValue::NullableInt(_) => Self::Int,
             FeatureDataValue::Category(_) | FeatureDataValue::NullableCategory(_) => Self::Category,
@@ -919,6 +1381,8 @@ impl<'f> From<&'f FeatureDataRef<'f>> for FeatureDataType {
     fn from(value: &FeatureDataRef) -> Self {
         match value {
             FeatureDataRef::Text(_) => Self::Text,
+            FeatureDataRef::Bytes(_) => Self::Bytes,
+            FeatureDataRef::Dictionary(_) => Self::Dictionary,
             FeatureDataRef::Float(..) => Self::Float,
             FeatureDataRef::Int(_) => Self::Int,
             FeatureDataRef::Category(_) => Self::Category,
@@ -971,8 +1435,24 @@ impl<'s> TryFrom<&'s FeatureDataValue> for &'s str {
 
     fn try_from(value: &FeatureDataValue) -> Result<&str, Self::Error> {
         Ok(match value {
-            FeatureDataValue::Text(v) => v.as_ref(),
-            FeatureDataValue::NullableText(v) if v.is_some() => v.as_ref().unwrap(),
+            FeatureDataValue::Text(v) | FeatureDataValue::Dictionary(v) => v.as_ref(),
+            FeatureDataValue::NullableText(v) | FeatureDataValue::NullableDictionary(v)
+                if v.is_some() =>
+            {
+                v.as_ref().unwrap()
+            }
+            _ => return Err(crate::collections::FeatureCollectionError::WrongDataType),
+        })
+    }
+}
+
+impl<'s> TryFrom<&'s FeatureDataValue> for &'s [u8] {
+    type Error = crate::collections::FeatureCollectionError;
+
+    fn try_from(value: &FeatureDataValue) -> Result<&[u8], Self::Error> {
+        Ok(match value {
+            FeatureDataValue::Bytes(v) => v.as_ref(),
+            FeatureDataValue::NullableBytes(v) if v.is_some() => v.as_ref().unwrap(),
             _ => return Err(crate::collections::FeatureCollectionError::WrongDataType),
         })
     }
@@ -1071,4 +1551,101 @@ mod tests {
         let from_strings_cmp: Vec<Option<f64>> = vec![Some(1.0), None, None];
         assert_eq!(from_strings, from_strings_cmp);
     }
+
+    #[test]
+    fn bytes_data_ref() {
+        let collection = DataCollection::from_slices(
+            &[] as &[NoGeometry],
+            &[TimeInterval::default(); 3],
+            &[(
+                "bytes",
+                FeatureData::NullableBytes(vec![Some(vec![0, 1, 255]), Some(vec![]), None]),
+            )],
+        )
+        .unwrap();
+
+        let data = collection.data("bytes").unwrap();
+
+        assert_eq!(FeatureDataType::from(&data), FeatureDataType::Bytes);
+        assert_eq!(data.nulls(), vec![false, false, true]);
+
+        assert_eq!(
+            data.json_values().collect::<Vec<_>>(),
+            vec![
+                serde_json::json!("AAH/"),
+                serde_json::json!(""),
+                serde_json::Value::Null
+            ]
+        );
+        assert_eq!(
+            data.strings_iter().collect::<Vec<_>>(),
+            vec!["AAH/".to_string(), String::new(), String::new()]
+        );
+        assert_eq!(
+            data.float_options_iter().collect::<Vec<_>>(),
+            vec![None, None, None]
+        );
+
+        assert_eq!(
+            data.get_unchecked(0),
+            FeatureDataValue::NullableBytes(Some(vec![0, 1, 255]))
+        );
+        assert_eq!(data.get_unchecked(2), FeatureDataValue::NullableBytes(None));
+    }
+
+    #[test]
+    fn dictionary_data_ref() {
+        let collection = DataCollection::from_slices(
+            &[] as &[NoGeometry],
+            &[TimeInterval::default(); 4],
+            &[(
+                "classes",
+                FeatureData::NullableDictionary(vec![
+                    Some("forest".to_owned()),
+                    Some("water".to_owned()),
+                    None,
+                    Some("forest".to_owned()),
+                ]),
+            )],
+        )
+        .unwrap();
+
+        let data = collection.data("classes").unwrap();
+
+        assert_eq!(FeatureDataType::from(&data), FeatureDataType::Dictionary);
+        assert_eq!(data.nulls(), vec![false, false, true, false]);
+
+        assert_eq!(
+            data.json_values().collect::<Vec<_>>(),
+            vec![
+                serde_json::json!("forest"),
+                serde_json::json!("water"),
+                serde_json::Value::Null,
+                serde_json::json!("forest"),
+            ]
+        );
+        assert_eq!(
+            data.strings_iter().collect::<Vec<_>>(),
+            vec![
+                "forest".to_string(),
+                "water".to_string(),
+                String::new(),
+                "forest".to_string()
+            ]
+        );
+        assert_eq!(
+            data.get_unchecked(1),
+            FeatureDataValue::NullableDictionary(Some("water".to_owned()))
+        );
+
+        let data = if let FeatureDataRef::Dictionary(data) = data {
+            data
+        } else {
+            unreachable!()
+        };
+
+        assert_eq!(data.codes(), &[0, 1, 0, 0]);
+        assert_eq!(data.dictionary().len(), 2);
+        assert_eq!(data.class_counts(), vec![("forest", 2), ("water", 1)]);
+    }
 }
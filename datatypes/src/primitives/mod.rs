@@ -1,4 +1,5 @@
 mod bounding_box;
+pub mod column_kernels;
 mod coordinate;
 pub(self) mod error;
 mod feature_data;
@@ -14,14 +15,15 @@ mod spatial_resolution;
 mod spatio_temporal_bounded;
 mod time_instance;
 mod time_interval;
+mod time_interval_set;
 mod time_step;
 
 pub use bounding_box::BoundingBox2D;
 pub use coordinate::Coordinate2D;
 pub(crate) use error::PrimitivesError;
 pub use feature_data::{
-    CategoryDataRef, DataRef, FeatureData, FeatureDataRef, FeatureDataType, FeatureDataValue,
-    FloatDataRef, IntDataRef, TextDataRef,
+    BytesDataRef, CategoryDataRef, DataRef, DictionaryDataRef, FeatureData, FeatureDataRef,
+    FeatureDataType, FeatureDataValue, FloatDataRef, IntDataRef, TextDataRef,
 };
 pub use geometry::{Geometry, GeometryRef, TypedGeometry};
 pub use line::Line;
@@ -36,4 +38,5 @@ pub use spatio_temporal_bounded::{SpatialBounded, TemporalBounded};
 
 pub use time_instance::TimeInstance;
 pub use time_interval::TimeInterval;
+pub use time_interval_set::TimeIntervalSet;
 pub use time_step::{TimeGranularity, TimeStep, TimeStepIter};
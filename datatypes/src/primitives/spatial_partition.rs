@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use snafu::ensure;
 
@@ -26,7 +27,7 @@ pub trait AxisAlignedRectangle: Copy {
 }
 
 /// A partition of space that include the upper left but excludes the lower right coordinate
-#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SpatialPartition2D {
     upper_left_coordinate: Coordinate2D,
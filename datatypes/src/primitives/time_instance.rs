@@ -5,13 +5,16 @@ use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
 use postgres_types::private::BytesMut;
 #[cfg(feature = "postgres")]
 use postgres_types::{FromSql, IsNull, ToSql, Type};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use snafu::ensure;
 #[cfg(feature = "postgres")]
 use snafu::Error;
 use std::{convert::TryFrom, ops::Add};
 
-#[derive(Clone, Copy, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[derive(
+    Clone, Copy, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Debug, JsonSchema,
+)]
 #[repr(C)]
 pub struct TimeInstance(i64);
 
@@ -0,0 +1,293 @@
+use std::iter::FromIterator;
+
+use serde::{Deserialize, Serialize};
+
+use crate::primitives::TimeInterval;
+
+/// A set of points in time that is stored as a sorted list of disjoint time intervals.
+///
+/// Overlapping and touching intervals are coalesced on construction.
+///
+/// # Examples
+///
+/// ```
+/// use geoengine_datatypes::primitives::{TimeInterval, TimeIntervalSet};
+///
+/// let set = TimeIntervalSet::new(vec![
+///     TimeInterval::new_unchecked(5, 8),
+///     TimeInterval::new_unchecked(0, 2),
+///     TimeInterval::new_unchecked(1, 3),
+/// ]);
+///
+/// assert_eq!(
+///     set.intervals(),
+///     &[TimeInterval::new_unchecked(0, 3), TimeInterval::new_unchecked(5, 8)]
+/// );
+/// assert_eq!(
+///     set.gaps(TimeInterval::new_unchecked(0, 10)).intervals(),
+///     &[TimeInterval::new_unchecked(3, 5), TimeInterval::new_unchecked(8, 10)]
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Vec<TimeInterval>", into = "Vec<TimeInterval>")]
+pub struct TimeIntervalSet {
+    intervals: Vec<TimeInterval>,
+}
+
+impl TimeIntervalSet {
+    pub fn new<I>(intervals: I) -> Self
+    where
+        I: IntoIterator<Item = TimeInterval>,
+    {
+        Self {
+            intervals: coalesce(intervals.into_iter().collect()),
+        }
+    }
+
+    /// The sorted, disjoint intervals of the set
+    pub fn intervals(&self) -> &[TimeInterval] {
+        &self.intervals
+    }
+
+    pub fn into_intervals(self) -> Vec<TimeInterval> {
+        self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The smallest interval that covers all intervals of the set, or `None` if the set is empty
+    pub fn extent(&self) -> Option<TimeInterval> {
+        let first = self.intervals.first()?;
+        let last = self.intervals.last()?;
+
+        Some(first.extend(last))
+    }
+
+    /// Does any interval of the set intersect the `time_interval`?
+    pub fn intersects(&self, time_interval: &TimeInterval) -> bool {
+        self.intervals
+            .iter()
+            .any(|interval| interval.intersects(time_interval))
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.intervals
+            .iter()
+            .chain(&other.intervals)
+            .copied()
+            .collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+
+        let mut i = 0;
+        let mut j = 0;
+
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let a = self.intervals[i];
+            let b = other.intervals[j];
+
+            if let Some(intersection) = a.intersect(&b) {
+                intervals.push(intersection);
+            }
+
+            // the interval that ends first cannot intersect any further interval of the other set
+            if a.end() < b.end() || (a.end() == b.end() && a.start() <= b.start()) {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self::new(intervals)
+    }
+
+    /// Removes all points in time that are in the `other` set.
+    ///
+    /// Instants in `other` have no duration and thus do not split intervals of this set.
+    /// Instants of this set are removed if they intersect any interval of `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+
+        for interval in &self.intervals {
+            if interval.is_instant() {
+                if !other.intersects(interval) {
+                    intervals.push(*interval);
+                }
+                continue;
+            }
+
+            let mut start = interval.start();
+
+            for hole in &other.intervals {
+                if hole.is_instant() || hole.end() <= start {
+                    continue;
+                }
+                if hole.start() >= interval.end() {
+                    break;
+                }
+
+                if hole.start() > start {
+                    intervals.push(TimeInterval::new_unchecked(start, hole.start()));
+                }
+
+                start = hole.end();
+
+                if start >= interval.end() {
+                    break;
+                }
+            }
+
+            if start < interval.end() {
+                intervals.push(TimeInterval::new_unchecked(start, interval.end()));
+            }
+        }
+
+        Self::new(intervals)
+    }
+
+    /// The parts of `bounds` that are not covered by the set
+    pub fn gaps(&self, bounds: TimeInterval) -> Self {
+        Self::from(bounds).difference(self)
+    }
+}
+
+/// Sorts the intervals and merges the ones that overlap or touch
+fn coalesce(mut intervals: Vec<TimeInterval>) -> Vec<TimeInterval> {
+    intervals.sort_unstable_by_key(|interval| (interval.start(), interval.end()));
+
+    let mut coalesced: Vec<TimeInterval> = Vec::with_capacity(intervals.len());
+
+    for interval in intervals {
+        if let Some(last) = coalesced.last_mut() {
+            if let Ok(union) = last.union(&interval) {
+                *last = union;
+                continue;
+            }
+        }
+
+        coalesced.push(interval);
+    }
+
+    coalesced
+}
+
+impl From<TimeInterval> for TimeIntervalSet {
+    fn from(time_interval: TimeInterval) -> Self {
+        Self {
+            intervals: vec![time_interval],
+        }
+    }
+}
+
+impl From<Vec<TimeInterval>> for TimeIntervalSet {
+    fn from(intervals: Vec<TimeInterval>) -> Self {
+        Self::new(intervals)
+    }
+}
+
+impl From<TimeIntervalSet> for Vec<TimeInterval> {
+    fn from(set: TimeIntervalSet) -> Self {
+        set.intervals
+    }
+}
+
+impl FromIterator<TimeInterval> for TimeIntervalSet {
+    fn from_iter<T: IntoIterator<Item = TimeInterval>>(iter: T) -> Self {
+        Self::new(iter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i64, i64)]) -> TimeIntervalSet {
+        intervals
+            .iter()
+            .map(|&(start, end)| TimeInterval::new_unchecked(start, end))
+            .collect()
+    }
+
+    #[test]
+    fn coalesces() {
+        assert_eq!(
+            set(&[(4, 6), (0, 2), (2, 3), (5, 5), (1, 2), (8, 8)]),
+            TimeIntervalSet {
+                intervals: vec![
+                    TimeInterval::new_unchecked(0, 3),
+                    TimeInterval::new_unchecked(4, 6),
+                    TimeInterval::new_unchecked(8, 8),
+                ]
+            }
+        );
+    }
+
+    #[test]
+    fn union() {
+        assert_eq!(
+            set(&[(0, 2), (6, 8)]).union(&set(&[(1, 3), (8, 9), (12, 13)])),
+            set(&[(0, 3), (6, 9), (12, 13)])
+        );
+    }
+
+    #[test]
+    fn intersection() {
+        assert_eq!(
+            set(&[(0, 5), (6, 10)]).intersection(&set(&[(2, 7), (9, 12), (20, 30)])),
+            set(&[(2, 5), (6, 7), (9, 10)])
+        );
+        assert_eq!(
+            set(&[(0, 5)]).intersection(&set(&[(5, 10)])),
+            TimeIntervalSet::default()
+        );
+        assert_eq!(set(&[(0, 5)]).intersection(&set(&[(3, 3)])), set(&[(3, 3)]));
+    }
+
+    #[test]
+    fn difference() {
+        assert_eq!(
+            set(&[(0, 10), (20, 30)]).difference(&set(&[(2, 4), (5, 6), (8, 22), (25, 25)])),
+            set(&[(0, 2), (4, 5), (6, 8), (22, 30)])
+        );
+        assert_eq!(
+            set(&[(0, 10)]).difference(&set(&[(-5, 15)])),
+            TimeIntervalSet::default()
+        );
+        assert_eq!(
+            set(&[(3, 3), (12, 12)]).difference(&set(&[(0, 10)])),
+            set(&[(12, 12)])
+        );
+    }
+
+    #[test]
+    fn gaps_and_extent() {
+        let intervals = set(&[(2, 4), (6, 8)]);
+
+        assert_eq!(
+            intervals.gaps(TimeInterval::new_unchecked(0, 10)),
+            set(&[(0, 2), (4, 6), (8, 10)])
+        );
+        assert_eq!(
+            intervals.gaps(TimeInterval::new_unchecked(3, 7)),
+            set(&[(4, 6)])
+        );
+        assert_eq!(intervals.extent(), Some(TimeInterval::new_unchecked(2, 8)));
+        assert_eq!(TimeIntervalSet::default().extent(), None);
+    }
+
+    #[test]
+    fn serde() {
+        let intervals: TimeIntervalSet =
+            serde_json::from_str(r#"[{"start":3,"end":4},{"start":0,"end":3}]"#).unwrap();
+
+        assert_eq!(intervals, set(&[(0, 4)]));
+        assert_eq!(
+            serde_json::to_string(&intervals).unwrap(),
+            r#"[{"start":0,"end":4}]"#
+        );
+    }
+}
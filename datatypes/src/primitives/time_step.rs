@@ -2,6 +2,7 @@ use std::{cmp::max, convert::TryInto, ops::Add};
 
 use chrono::{Datelike, Duration, NaiveDate};
 use error::Error::NoDateTimeValid;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "postgres")]
@@ -15,7 +16,7 @@ use crate::util::Result;
 use super::TimeInterval;
 
 /// A time granularity.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[cfg_attr(feature = "postgres", derive(ToSql, FromSql))]
 pub enum TimeGranularity {
     Millis,
@@ -28,7 +29,7 @@ pub enum TimeGranularity {
 }
 
 /// A step in time.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[cfg_attr(feature = "postgres", derive(ToSql, FromSql))]
 pub struct TimeStep {
     pub granularity: TimeGranularity,
@@ -9,8 +9,8 @@ use crate::util::Result;
 
 use super::{
     grid_traits::{ChangeGridBounds, GridShapeAccess},
-    BoundedGrid, GridBoundingBox, GridBounds, GridContains, GridIdx, GridIdx2D, GridIndexAccess,
-    GridIndexAccessMut, GridSize, GridSpaceToLinearSpace, NoDataValue,
+    BoundedGrid, GridBoundingBox, GridBounds, GridContains, GridData, GridIdx, GridIdx2D,
+    GridIndexAccess, GridIndexAccessMut, GridSize, GridSpaceToLinearSpace, NoDataValue,
 };
 
 /// An `GridShape` describes the shape of an n-dimensional array by storing the size of each axis.
@@ -249,7 +249,7 @@ where
 #[serde(rename_all = "camelCase")]
 pub struct Grid<D, T> {
     pub shape: D,
-    pub data: Vec<T>,
+    pub data: GridData<T>,
     pub no_data_value: Option<T>,
 }
 
@@ -279,7 +279,7 @@ where
 
         Ok(Self {
             shape,
-            data,
+            data: data.into(),
             no_data_value,
         })
     }
@@ -436,7 +436,19 @@ where
     }
 
     fn set_grid_bounds(self, bounds: GridBoundingBox<I>) -> Result<Self::Output> {
-        Grid::new(bounds, self.data, self.no_data_value)
+        ensure!(
+            bounds.number_of_elements() == self.data.len(),
+            error::DimensionCapacityDoesNotMatchDataCapacity {
+                dimension_cap: bounds.number_of_elements(),
+                data_cap: self.data.len()
+            }
+        );
+
+        Ok(Grid {
+            shape: bounds,
+            data: self.data,
+            no_data_value: self.no_data_value,
+        })
     }
 }
 
@@ -0,0 +1,155 @@
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The pixel buffer of a `Grid`.
+///
+/// Clones share the same buffer, s.t. passing tiles through operators that do not modify them is cheap.
+/// The buffer is copied when a shared one is mutated (copy-on-write).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridData<T>(Arc<Vec<T>>);
+
+impl<T> GridData<T> {
+    /// Checks whether `self` and `other` share the same buffer
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<T: Clone> GridData<T> {
+    /// Returns the buffer and only copies it if it is shared
+    pub fn into_vec(self) -> Vec<T> {
+        Arc::try_unwrap(self.0).unwrap_or_else(|shared| (*shared).clone())
+    }
+}
+
+impl<T> Deref for GridData<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Vec<T> {
+        &self.0
+    }
+}
+
+impl<T: Clone> DerefMut for GridData<T> {
+    fn deref_mut(&mut self) -> &mut Vec<T> {
+        Arc::make_mut(&mut self.0)
+    }
+}
+
+impl<T> From<Vec<T>> for GridData<T> {
+    fn from(data: Vec<T>) -> Self {
+        Self(Arc::new(data))
+    }
+}
+
+impl<T: Clone> From<GridData<T>> for Vec<T> {
+    fn from(data: GridData<T>) -> Self {
+        data.into_vec()
+    }
+}
+
+impl<T> FromIterator<T> for GridData<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        iter.into_iter().collect::<Vec<T>>().into()
+    }
+}
+
+impl<T: Clone> IntoIterator for GridData<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_vec().into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a GridData<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<T: PartialEq> PartialEq<Vec<T>> for GridData<T> {
+    fn eq(&self, other: &Vec<T>) -> bool {
+        self.0.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: PartialEq> PartialEq<[T]> for GridData<T> {
+    fn eq(&self, other: &[T]) -> bool {
+        self.0.as_slice() == other
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq<[T; N]> for GridData<T> {
+    fn eq(&self, other: &[T; N]) -> bool {
+        self.0.as_slice() == other
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq<&[T; N]> for GridData<T> {
+    fn eq(&self, other: &&[T; N]) -> bool {
+        self.0.as_slice() == *other
+    }
+}
+
+impl<T: Serialize> Serialize for GridData<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for GridData<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<T>::deserialize(deserializer).map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_copies_on_write() {
+        let data = GridData::from(vec![1, 2, 3]);
+        let mut clone = data.clone();
+
+        assert!(clone.ptr_eq(&data));
+
+        clone[0] = 4;
+
+        assert!(!clone.ptr_eq(&data));
+        assert_eq!(data, [1, 2, 3]);
+        assert_eq!(clone, [4, 2, 3]);
+
+        // an unshared buffer is mutated in place
+        let ptr = clone.as_ptr();
+        clone[1] = 5;
+        assert_eq!(clone.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn it_unwraps_unshared_buffers() {
+        let data = GridData::from(vec![1, 2, 3]);
+        let ptr = data.as_ptr();
+
+        assert_eq!(data.into_vec().as_ptr(), ptr);
+    }
+
+    #[test]
+    fn serde() {
+        let data = GridData::from(vec![1, 2, 3]);
+
+        let serialized = serde_json::to_string(&data).unwrap();
+        assert_eq!(serialized, "[1,2,3]");
+
+        let deserialized: GridData<i32> = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, data);
+    }
+}
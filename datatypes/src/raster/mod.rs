@@ -15,6 +15,7 @@ pub use self::grid::{
 pub use self::grid_bounds::{
     GridBoundingBox, GridBoundingBox1D, GridBoundingBox2D, GridBoundingBox3D,
 };
+pub use self::grid_data::GridData;
 pub use self::grid_index::{GridIdx, GridIdx1D, GridIdx2D, GridIdx3D};
 pub use self::grid_or_empty::{GridOrEmpty, GridOrEmpty1D, GridOrEmpty2D, GridOrEmpty3D};
 pub use self::grid_traits::{
@@ -42,6 +43,7 @@ mod empty_grid;
 mod geo_transform;
 mod grid;
 mod grid_bounds;
+mod grid_data;
 mod grid_index;
 mod grid_or_empty;
 mod grid_traits;
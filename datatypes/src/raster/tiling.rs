@@ -25,8 +25,50 @@ impl TilingSpecification {
     pub fn strategy(self, x_pixel_size: f64, y_pixel_size: f64) -> TilingStrategy {
         TilingStrategy::new_with_tiling_spec(self, x_pixel_size, y_pixel_size)
     }
+
+    /// Moves the origin by less than a pixel, s.t. `coordinate` lies on a pixel corner of the pixel grid
+    /// with the given pixel sizes.
+    ///
+    /// This allows answering queries, e.g. from WMS clients, whose bounds are not aligned with the
+    /// default pixel grid without resampling. Since the origin moves by less than a pixel, the tiles
+    /// stay almost the same and requests with the same alignment share the same tiles.
+    #[must_use]
+    pub fn aligned_to(
+        self,
+        coordinate: Coordinate2D,
+        x_pixel_size: f64,
+        y_pixel_size: f64,
+    ) -> Self {
+        let align = |origin: f64, value: f64, pixel_size: f64| {
+            let pixel_size = pixel_size.abs();
+            if pixel_size <= 0. {
+                return origin;
+            }
+
+            let offset = (value - origin).rem_euclid(pixel_size);
+
+            if offset < pixel_size * ALIGNMENT_TOLERANCE
+                || pixel_size - offset < pixel_size * ALIGNMENT_TOLERANCE
+            {
+                origin
+            } else {
+                origin + offset
+            }
+        };
+
+        Self {
+            origin_coordinate: Coordinate2D::new(
+                align(self.origin_coordinate.x, coordinate.x, x_pixel_size),
+                align(self.origin_coordinate.y, coordinate.y, y_pixel_size),
+            ),
+            tile_size_in_pixels: self.tile_size_in_pixels,
+        }
+    }
 }
 
+/// The fraction of a pixel below which coordinates are considered to be aligned with the pixel grid
+const ALIGNMENT_TOLERANCE: f64 = 1e-9;
+
 /// A provider of tile (size) information for a raster/grid
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
 pub struct TilingStrategy {
@@ -220,3 +262,22 @@ impl SpatialPartitioned for TileInformation {
         SpatialPartition2D::new_unchecked(top_left_coord, lower_right_coord)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligned_to() {
+        let spec = TilingSpecification::new((0., 0.).into(), [600, 600].into());
+
+        let aligned = spec.aligned_to((10.25, -3.5).into(), 1., 2.);
+
+        assert_eq!(aligned.origin_coordinate, (0.25, 0.5).into());
+        assert_eq!(aligned.tile_size_in_pixels, spec.tile_size_in_pixels);
+
+        let unchanged = spec.aligned_to((10., -4.).into(), 1., 2.);
+
+        assert_eq!(unchanged.origin_coordinate, spec.origin_coordinate);
+    }
+}
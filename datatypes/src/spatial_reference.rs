@@ -10,6 +10,9 @@ use postgres_types::private::BytesMut;
 #[cfg(feature = "postgres")]
 use postgres_types::{FromSql, IsNull, ToSql, Type};
 use proj::Proj;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, Schema, SchemaObject, StringValidation};
+use schemars::JsonSchema;
 use serde::de::Visitor;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 #[cfg(feature = "postgres")]
@@ -157,6 +160,24 @@ impl<'de> Deserialize<'de> for SpatialReference {
     }
 }
 
+impl JsonSchema for SpatialReference {
+    fn schema_name() -> String {
+        "SpatialReference".to_owned()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            string: Some(Box::new(StringValidation {
+                pattern: Some("^(EPSG|SR-ORG|IAU2000|ESRI):[0-9]+$".to_owned()),
+                ..StringValidation::default()
+            })),
+            ..SchemaObject::default()
+        }
+        .into()
+    }
+}
+
 impl FromStr for SpatialReferenceAuthority {
     type Err = error::Error;
 
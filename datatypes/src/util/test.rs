@@ -1,5 +1,7 @@
 use crate::raster::{EmptyGrid, Grid, GridOrEmpty, NoDataValue};
+use image::{ImageFormat, RgbaImage};
 use std::panic;
+use uuid::Uuid;
 
 pub fn catch_unwind_silent<F: FnOnce() -> R + panic::UnwindSafe, R>(
     f: F,
@@ -59,10 +61,156 @@ where
     g1.shape.eq(&g2.shape) && g1.is_no_data(g2.no_data_value)
 }
 
+/// How much a rendered image may deviate from its golden image
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageTolerance {
+    /// The largest difference of a color channel for which two pixels still count as equal
+    pub max_channel_delta: u8,
+    /// The percentage of pixels that may differ by more than `max_channel_delta`
+    pub max_differing_pixels_percent: f64,
+}
+
+impl ImageTolerance {
+    /// Requires all pixels to be equal, but ignores how the images are encoded
+    pub fn exact() -> Self {
+        Self {
+            max_channel_delta: 0,
+            max_differing_pixels_percent: 0.,
+        }
+    }
+}
+
+impl Default for ImageTolerance {
+    /// Tolerates rounding differences of colors and a few differing pixels, e.g., at edges
+    fn default() -> Self {
+        Self {
+            max_channel_delta: 2,
+            max_differing_pixels_percent: 0.5,
+        }
+    }
+}
+
+/// The differences of two images of the same size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageDiff {
+    pub number_of_pixels: usize,
+    pub differing_pixels: usize,
+    pub max_channel_delta: u8,
+}
+
+impl ImageDiff {
+    pub fn differing_pixels_percent(&self) -> f64 {
+        if self.number_of_pixels == 0 {
+            return 0.;
+        }
+
+        self.differing_pixels as f64 / self.number_of_pixels as f64 * 100.
+    }
+
+    pub fn is_within(&self, tolerance: ImageTolerance) -> bool {
+        self.differing_pixels_percent() <= tolerance.max_differing_pixels_percent
+    }
+}
+
+/// Compares two RGBA images pixel by pixel. Pixels count as differing if any channel differs
+/// by more than `max_channel_delta`.
+///
+/// # Panics
+///
+/// Panics if the images have different dimensions
+///
+pub fn diff_images(expected: &RgbaImage, actual: &RgbaImage, max_channel_delta: u8) -> ImageDiff {
+    assert_eq!(
+        expected.dimensions(),
+        actual.dimensions(),
+        "images must have the same dimensions"
+    );
+
+    let mut diff = ImageDiff {
+        number_of_pixels: (expected.width() * expected.height()) as usize,
+        differing_pixels: 0,
+        max_channel_delta: 0,
+    };
+
+    for (expected_pixel, actual_pixel) in expected.pixels().zip(actual.pixels()) {
+        let pixel_delta = expected_pixel
+            .0
+            .iter()
+            .zip(actual_pixel.0.iter())
+            .map(|(e, a)| if e > a { e - a } else { a - e })
+            .max()
+            .unwrap_or_default();
+
+        diff.max_channel_delta = diff.max_channel_delta.max(pixel_delta);
+
+        if pixel_delta > max_channel_delta {
+            diff.differing_pixels += 1;
+        }
+    }
+
+    diff
+}
+
+/// Asserts that the `actual` PNG bytes show the same image as the `expected` (golden) PNG bytes
+/// within the `tolerance`. In contrast to comparing the bytes, this is independent of the PNG
+/// encoder and its settings.
+///
+/// On failure, the actual image is written to the temp directory for inspection.
+///
+/// # Panics
+///
+/// Panics if the images are not valid PNGs, have different dimensions or differ beyond the `tolerance`
+///
+#[track_caller]
+pub fn assert_image_eq(expected: &[u8], actual: &[u8], tolerance: ImageTolerance) {
+    let expected_image = image::load_from_memory_with_format(expected, ImageFormat::Png)
+        .expect("expected bytes must be a PNG")
+        .into_rgba8();
+    let actual_image = image::load_from_memory_with_format(actual, ImageFormat::Png)
+        .expect("actual bytes must be a PNG")
+        .into_rgba8();
+
+    if expected_image.dimensions() != actual_image.dimensions() {
+        panic!(
+            "image dimensions differ: expected {:?}, actual {:?}{}",
+            expected_image.dimensions(),
+            actual_image.dimensions(),
+            store_actual_image(actual)
+        );
+    }
+
+    let diff = diff_images(&expected_image, &actual_image, tolerance.max_channel_delta);
+
+    if !diff.is_within(tolerance) {
+        panic!(
+            "{:.2}% of the pixels differ by more than {} per channel (largest difference: {}), but only {:.2}% may{}",
+            diff.differing_pixels_percent(),
+            tolerance.max_channel_delta,
+            diff.max_channel_delta,
+            tolerance.max_differing_pixels_percent,
+            store_actual_image(actual)
+        );
+    }
+}
+
+/// Writes the image to a temporary file and describes where to find it
+fn store_actual_image(png_bytes: &[u8]) -> String {
+    let path = std::env::temp_dir().join(format!("geoengine-actual-image-{}.png", Uuid::new_v4()));
+
+    match std::fs::write(&path, png_bytes) {
+        Ok(()) => format!("; the actual image is at {}", path.display()),
+        Err(_) => String::new(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::raster::{EmptyGrid, Grid2D, GridShape2D};
-    use crate::util::test::{empty_grid_eq_with_no_data, grid_eq_with_no_data};
+    use crate::util::test::{
+        assert_image_eq, catch_unwind_silent, diff_images, empty_grid_eq_with_no_data,
+        grid_eq_with_no_data, ImageTolerance,
+    };
+    use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
 
     #[test]
     fn test_empty_grid_eq_with_no_data_integral_ok() {
@@ -407,4 +555,78 @@ mod tests {
 
         assert!(!grid_eq_with_no_data(&r1, &r2));
     }
+
+    fn image_with_deviations(deviating_pixels: u32, delta: u8) -> RgbaImage {
+        let mut image = RgbaImage::from_pixel(10, 10, Rgba([100, 100, 100, 255]));
+
+        for i in 0..deviating_pixels {
+            image.put_pixel(i % 10, i / 10, Rgba([100 + delta, 100, 100, 255]));
+        }
+
+        image
+    }
+
+    fn png_bytes(image: RgbaImage) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        DynamicImage::ImageRgba8(image)
+            .write_to(&mut bytes, ImageFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_diff_images() {
+        let diff = diff_images(
+            &image_with_deviations(0, 0),
+            &image_with_deviations(5, 3),
+            2,
+        );
+
+        assert_eq!(diff.number_of_pixels, 100);
+        assert_eq!(diff.differing_pixels, 5);
+        assert_eq!(diff.max_channel_delta, 3);
+        assert!((diff.differing_pixels_percent() - 5.).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_assert_image_eq_within_tolerance() {
+        let expected = png_bytes(image_with_deviations(0, 0));
+
+        assert_image_eq(
+            &expected,
+            &png_bytes(image_with_deviations(50, 2)),
+            ImageTolerance::default(),
+        );
+        assert_image_eq(
+            &expected,
+            &png_bytes(image_with_deviations(5, 50)),
+            ImageTolerance {
+                max_channel_delta: 0,
+                max_differing_pixels_percent: 5.,
+            },
+        );
+    }
+
+    #[test]
+    fn test_assert_image_eq_beyond_tolerance() {
+        let expected = png_bytes(image_with_deviations(0, 0));
+
+        let result = catch_unwind_silent(|| {
+            assert_image_eq(
+                &expected,
+                &png_bytes(image_with_deviations(1, 1)),
+                ImageTolerance::exact(),
+            );
+        });
+        assert!(result.is_err());
+
+        let result = catch_unwind_silent(|| {
+            assert_image_eq(
+                &expected,
+                &png_bytes(RgbaImage::new(5, 5)),
+                ImageTolerance::default(),
+            );
+        });
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,74 @@
+use geoengine_operators::processing::{ExpressionProgram, ExpressionTree, PixelInputs};
+
+use criterion::*;
+
+const TILE_SIZE: usize = 600;
+
+const EXPRESSION: &str =
+    "(A - B) / (A + B) * 2 + sqrt(abs(A - A_PREV)) - min(A_AT(1, 0), B_AT(0, 1)) * dt / 1000";
+
+fn values(offset: f64) -> Vec<f64> {
+    (0..TILE_SIZE * TILE_SIZE)
+        .map(|i| offset + (i % 255) as f64)
+        .collect()
+}
+
+/// This bench evaluates an expression for all pixels of a tile with the stack machine
+fn bench_600px_bytecode(c: &mut Criterion) {
+    let (a, b, previous_a) = (values(1.), values(2.), values(0.));
+    let inputs = PixelInputs {
+        rasters: &[&a, &b],
+        previous_a: &previous_a,
+        previous_b: &[],
+        width: TILE_SIZE,
+        height: TILE_SIZE,
+        t: 1_000.,
+        dt: 1_000.,
+    };
+
+    let program = ExpressionProgram::compile(EXPRESSION).unwrap();
+    let mut stack = program.stack();
+
+    c.bench_function("bench_600px_bytecode", |bencher| {
+        bencher.iter(|| {
+            let mut sum = 0.;
+            for y in 0..TILE_SIZE {
+                for x in 0..TILE_SIZE {
+                    sum += program.evaluate(&inputs, x, y, &mut stack);
+                }
+            }
+            black_box(sum)
+        })
+    });
+}
+
+/// This bench evaluates the same expression by recursively walking its syntax tree
+fn bench_600px_naive_interpreter(c: &mut Criterion) {
+    let (a, b, previous_a) = (values(1.), values(2.), values(0.));
+    let inputs = PixelInputs {
+        rasters: &[&a, &b],
+        previous_a: &previous_a,
+        previous_b: &[],
+        width: TILE_SIZE,
+        height: TILE_SIZE,
+        t: 1_000.,
+        dt: 1_000.,
+    };
+
+    let tree = ExpressionTree::from(&ExpressionProgram::compile(EXPRESSION).unwrap());
+
+    c.bench_function("bench_600px_naive_interpreter", |bencher| {
+        bencher.iter(|| {
+            let mut sum = 0.;
+            for y in 0..TILE_SIZE {
+                for x in 0..TILE_SIZE {
+                    sum += tree.evaluate(&inputs, x, y);
+                }
+            }
+            black_box(sum)
+        })
+    });
+}
+
+criterion_group!(benches, bench_600px_bytecode, bench_600px_naive_interpreter);
+criterion_main!(benches);
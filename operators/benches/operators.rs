@@ -0,0 +1,386 @@
+mod util;
+
+use std::convert::TryInto;
+
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::{
+    collections::{MultiPointCollection, MultiPolygonCollection, VectorDataType},
+    dataset::{DatasetId, InternalDatasetId},
+    operations::image::{Colorizer, RgbaColor},
+    primitives::{BoundingBox2D, Measurement, SpatialPartition2D, SpatialResolution, TimeInterval},
+    raster::{Pixel, RasterDataType},
+    spatial_reference::{SpatialReference, SpatialReferenceAuthority},
+    util::Identifier,
+};
+use geoengine_operators::{
+    engine::{
+        MockExecutionContext, MockQueryContext, QueryProcessor, RasterOperator,
+        RasterQueryProcessor, RasterQueryRectangle, SingleRasterOrVectorSource, StaticMetaData,
+        VectorOperator, VectorQueryRectangle, VectorResultDescriptor,
+    },
+    processing::{
+        Expression, ExpressionBackend, ExpressionParams, ExpressionSources, Reprojection,
+        ReprojectionParams,
+    },
+    source::{
+        OgrSource, OgrSourceDataset, OgrSourceDatasetTimeType, OgrSourceErrorSpec,
+        OgrSourceParameters,
+    },
+    util::{raster_stream_to_png::raster_stream_to_png_bytes, resampling::ResamplingMethod},
+};
+use util::{SyntheticRaster, NO_DATA_VALUE};
+
+use criterion::*;
+
+/// The synthetic rasters with a growing number of tiles
+fn rasters_by_tile_count() -> Vec<(usize, SyntheticRaster)> {
+    [1, 2, 4]
+        .iter()
+        .map(|&tiles| {
+            let raster = SyntheticRaster {
+                tiles_x: tiles,
+                tiles_y: tiles,
+                ..SyntheticRaster::default()
+            };
+            (tiles * tiles, raster)
+        })
+        .collect()
+}
+
+/// The synthetic rasters with a growing share of no data pixels
+fn rasters_by_no_data_fraction() -> Vec<(f64, SyntheticRaster)> {
+    [0., 0.5, 1.]
+        .iter()
+        .map(|&no_data_fraction| {
+            let raster = SyntheticRaster {
+                no_data_fraction,
+                ..SyntheticRaster::default()
+            };
+            (no_data_fraction, raster)
+        })
+        .collect()
+}
+
+fn pixel_throughput(raster: &SyntheticRaster) -> Throughput {
+    Throughput::Elements(
+        (raster.tiles_x * raster.tiles_y * raster.tile_size * raster.tile_size * raster.time_steps)
+            as u64,
+    )
+}
+
+async fn count_tiles<T: Pixel>(
+    processor: &dyn RasterQueryProcessor<RasterType = T>,
+    query: RasterQueryRectangle,
+) -> usize {
+    let ctx = MockQueryContext::default();
+
+    processor
+        .raster_query(query, &ctx)
+        .await
+        .unwrap()
+        .map(Result::unwrap)
+        .count()
+        .await
+}
+
+/// This bench projects the synthetic raster from EPSG:4326 to EPSG:3857
+fn bench_reprojection(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("reprojection");
+    group.sample_size(10);
+
+    for (tiles, raster) in rasters_by_tile_count() {
+        let exe_ctx = raster.execution_context();
+        let operator = runtime
+            .block_on(
+                RasterOperator::boxed(Reprojection {
+                    params: ReprojectionParams {
+                        target_spatial_reference: SpatialReference::new(
+                            SpatialReferenceAuthority::Epsg,
+                            3857,
+                        ),
+                        resampling: ResamplingMethod::Nearest,
+                        transformation: None,
+                    },
+                    sources: SingleRasterOrVectorSource {
+                        source: raster.source().into(),
+                    },
+                })
+                .initialize(&exe_ctx),
+            )
+            .unwrap();
+        let processor = operator.query_processor().unwrap().get_u8().unwrap();
+        let processor = processor.as_ref();
+
+        // the upper right corner of the synthetic raster is at about 1_335_000 meters in both directions
+        let extent = 1_300_000.;
+        let query = RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new_unchecked(
+                (0., extent).into(),
+                (extent, 0.).into(),
+            ),
+            time_interval: raster.query_rectangle().time_interval,
+            spatial_resolution: SpatialResolution::new_unchecked(
+                extent / (raster.tiles_x * raster.tile_size) as f64,
+                extent / (raster.tiles_y * raster.tile_size) as f64,
+            ),
+        };
+
+        group.throughput(pixel_throughput(&raster));
+        group.bench_with_input(BenchmarkId::new("tiles", tiles), &query, |b, &query| {
+            b.to_async(&runtime)
+                .iter(|| async move { count_tiles(processor, query).await })
+        });
+    }
+
+    group.finish();
+}
+
+fn initialize_expression(
+    runtime: &tokio::runtime::Runtime,
+    raster: &SyntheticRaster,
+    backend: ExpressionBackend,
+) -> Box<dyn RasterQueryProcessor<RasterType = f32>> {
+    let exe_ctx = raster.execution_context();
+    let operator = runtime
+        .block_on(
+            Expression {
+                params: ExpressionParams {
+                    expression: "(A - B) / (A + B)".to_string(),
+                    output_type: RasterDataType::F32,
+                    output_no_data_value: f64::NAN,
+                    output_measurement: Some(Measurement::Unitless),
+                    backend,
+                    map_no_data: false,
+                    no_data_defaults: Default::default(),
+                },
+                sources: ExpressionSources::new_a_b(raster.source(), raster.source()),
+            }
+            .boxed()
+            .initialize(&exe_ctx),
+        )
+        .unwrap();
+
+    operator.query_processor().unwrap().get_f32().unwrap()
+}
+
+/// This bench calculates a normalized difference of two synthetic rasters with both backends
+fn bench_expression(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("expression");
+    group.sample_size(10);
+
+    for (backend_name, backend) in &[
+        ("bytecode", ExpressionBackend::Bytecode),
+        ("opencl", ExpressionBackend::OpenCl),
+    ] {
+        for (tiles, raster) in rasters_by_tile_count() {
+            let processor = initialize_expression(&runtime, &raster, *backend);
+            let processor = processor.as_ref();
+
+            group.throughput(pixel_throughput(&raster));
+            group.bench_with_input(
+                BenchmarkId::new(format!("{}/tiles", backend_name), tiles),
+                &raster.query_rectangle(),
+                |b, &query| {
+                    b.to_async(&runtime)
+                        .iter(|| async move { count_tiles(processor, query).await })
+                },
+            );
+        }
+
+        for (no_data_fraction, raster) in rasters_by_no_data_fraction() {
+            let processor = initialize_expression(&runtime, &raster, *backend);
+            let processor = processor.as_ref();
+
+            group.throughput(pixel_throughput(&raster));
+            group.bench_with_input(
+                BenchmarkId::new(
+                    format!("{}/no_data_fraction", backend_name),
+                    no_data_fraction,
+                ),
+                &raster.query_rectangle(),
+                |b, &query| {
+                    b.to_async(&runtime)
+                        .iter(|| async move { count_tiles(processor, query).await })
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+/// This bench renders the synthetic raster as a PNG with a linear gradient
+fn bench_colorization(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("colorization");
+    group.sample_size(10);
+
+    let colorizer = Colorizer::linear_gradient(
+        vec![
+            (1.0, RgbaColor::black()).try_into().unwrap(),
+            (255.0, RgbaColor::white()).try_into().unwrap(),
+        ],
+        RgbaColor::transparent(),
+        RgbaColor::pink(),
+    )
+    .unwrap();
+
+    let inputs = rasters_by_tile_count()
+        .into_iter()
+        .map(|(tiles, raster)| (format!("tiles/{}", tiles), raster))
+        .chain(
+            rasters_by_no_data_fraction()
+                .into_iter()
+                .map(|(fraction, raster)| (format!("no_data_fraction/{}", fraction), raster)),
+        );
+
+    for (id, raster) in inputs {
+        let exe_ctx = raster.execution_context();
+        let operator = runtime
+            .block_on(raster.source().initialize(&exe_ctx))
+            .unwrap();
+        let operator = operator.as_ref();
+        let colorizer = &colorizer;
+
+        group.throughput(pixel_throughput(&raster));
+        group.bench_with_input(BenchmarkId::from_parameter(id), &raster, |b, raster| {
+            b.to_async(&runtime).iter(|| async move {
+                raster_stream_to_png_bytes(
+                    operator.query_processor().unwrap().get_u8().unwrap(),
+                    raster.query_rectangle(),
+                    MockQueryContext::default(),
+                    (raster.tiles_x * raster.tile_size) as u32,
+                    (raster.tiles_y * raster.tile_size) as u32,
+                    None,
+                    Some(colorizer.clone()),
+                    Some(NO_DATA_VALUE),
+                )
+                .await
+                .unwrap()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn ogr_source(
+    file_name: &str,
+    layer_name: &str,
+    result_descriptor: VectorResultDescriptor,
+) -> (MockExecutionContext, Box<dyn VectorOperator>) {
+    let dataset = DatasetId::Internal {
+        dataset_id: InternalDatasetId::new(),
+    };
+
+    let mut exe_ctx = MockExecutionContext::default();
+    exe_ctx.add_meta_data::<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>(
+        dataset.clone(),
+        Box::new(StaticMetaData {
+            loading_info: OgrSourceDataset {
+                file_name: file_name.into(),
+                layer_name: layer_name.to_string(),
+                data_type: Some(result_descriptor.data_type),
+                time: OgrSourceDatasetTimeType::None,
+                columns: None,
+                force_ogr_time_filter: false,
+                force_ogr_spatial_filter: false,
+                on_error: OgrSourceErrorSpec::Ignore,
+                sql_query: None,
+                encoding: None,
+                layer_spatial_reference: None,
+            },
+            result_descriptor,
+            phantom: Default::default(),
+        }),
+    );
+
+    let source = OgrSource {
+        params: OgrSourceParameters {
+            dataset,
+            attribute_projection: None,
+        },
+    }
+    .boxed();
+
+    (exe_ctx, source)
+}
+
+fn world_query() -> VectorQueryRectangle {
+    VectorQueryRectangle {
+        spatial_bounds: BoundingBox2D::new_unchecked((-180., -90.).into(), (180., 90.).into()),
+        time_interval: TimeInterval::default(),
+        spatial_resolution: SpatialResolution::one(),
+    }
+}
+
+/// This bench reads all features of vector files with the OGR source
+fn bench_ogr_ingest(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("ogr_ingest");
+
+    let (exe_ctx, source) = ogr_source(
+        "test-data/vector/data/ne_10m_ports/ne_10m_ports.shp",
+        "ne_10m_ports",
+        VectorResultDescriptor {
+            data_type: VectorDataType::MultiPoint,
+            spatial_reference: SpatialReference::epsg_4326().into(),
+            columns: Default::default(),
+        },
+    );
+    let source = runtime.block_on(source.initialize(&exe_ctx)).unwrap();
+    let processor = source.query_processor().unwrap().multi_point().unwrap();
+    let processor = processor.as_ref();
+
+    group.bench_function("ne_10m_ports", |b| {
+        b.to_async(&runtime).iter(|| async move {
+            let ctx = MockQueryContext::default();
+            processor
+                .query(world_query(), &ctx)
+                .await
+                .unwrap()
+                .try_collect::<Vec<MultiPointCollection>>()
+                .await
+                .unwrap()
+        })
+    });
+
+    let (exe_ctx, source) = ogr_source(
+        "test-data/vector/data/germany_polygon.gpkg",
+        "test_germany",
+        VectorResultDescriptor {
+            data_type: VectorDataType::MultiPolygon,
+            spatial_reference: SpatialReference::epsg_4326().into(),
+            columns: Default::default(),
+        },
+    );
+    let source = runtime.block_on(source.initialize(&exe_ctx)).unwrap();
+    let processor = source.query_processor().unwrap().multi_polygon().unwrap();
+    let processor = processor.as_ref();
+
+    group.bench_function("germany_polygon", |b| {
+        b.to_async(&runtime).iter(|| async move {
+            let ctx = MockQueryContext::default();
+            processor
+                .query(world_query(), &ctx)
+                .await
+                .unwrap()
+                .try_collect::<Vec<MultiPolygonCollection>>()
+                .await
+                .unwrap()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_reprojection,
+    bench_expression,
+    bench_colorization,
+    bench_ogr_ingest
+);
+criterion_main!(benches);
@@ -0,0 +1,153 @@
+//! Synthetic sources that are shared by the benchmarks
+
+use geoengine_datatypes::{
+    primitives::{Coordinate2D, Measurement, SpatialPartition2D, SpatialResolution, TimeInterval},
+    raster::{GeoTransform, Grid2D, RasterDataType, RasterTile2D, TilingSpecification},
+    spatial_reference::SpatialReference,
+};
+use geoengine_operators::{
+    engine::{MockExecutionContext, RasterOperator, RasterQueryRectangle, RasterResultDescriptor},
+    mock::{MockRasterSource, MockRasterSourceParams},
+};
+
+/// The value that marks missing pixels of the synthetic rasters
+pub const NO_DATA_VALUE: u8 = 0;
+
+/// The length of a time step of the synthetic rasters in milliseconds
+pub const TIME_STEP_MILLIS: i64 = 1000;
+
+/// A grid of `tiles_x` × `tiles_y` tiles in EPSG:4326 that is repeated for `time_steps` time steps.
+/// Its pixels are pseudo-random `u8` values, of which roughly `no_data_fraction` are no data.
+#[derive(Debug, Clone, Copy)]
+pub struct SyntheticRaster {
+    pub tiles_x: usize,
+    pub tiles_y: usize,
+    pub tile_size: usize,
+    pub time_steps: usize,
+    pub no_data_fraction: f64,
+    /// the size of a pixel in degrees
+    pub resolution: f64,
+}
+
+impl Default for SyntheticRaster {
+    fn default() -> Self {
+        Self {
+            tiles_x: 2,
+            tiles_y: 2,
+            tile_size: 600,
+            time_steps: 1,
+            no_data_fraction: 0.,
+            resolution: 0.01,
+        }
+    }
+}
+
+impl SyntheticRaster {
+    /// The tiles cover `[0, tiles_x * tile_size * resolution]` horizontally and
+    /// `[0, tiles_y * tile_size * resolution]` vertically.
+    pub fn tiles(&self) -> Vec<RasterTile2D<u8>> {
+        let geo_transform =
+            GeoTransform::new(Coordinate2D::default(), self.resolution, -self.resolution);
+
+        let mut tiles = Vec::with_capacity(self.time_steps * self.tiles_x * self.tiles_y);
+        for t in 0..self.time_steps {
+            let time = TimeInterval::new_unchecked(
+                t as i64 * TIME_STEP_MILLIS,
+                (t as i64 + 1) * TIME_STEP_MILLIS,
+            );
+
+            for y in 0..self.tiles_y {
+                for x in 0..self.tiles_x {
+                    let seed = ((t * self.tiles_y + y) * self.tiles_x + x) as u64;
+
+                    let grid = Grid2D::new(
+                        [self.tile_size, self.tile_size].into(),
+                        self.pixels(seed),
+                        Some(NO_DATA_VALUE),
+                    )
+                    .expect("the data must match the tile size");
+
+                    tiles.push(RasterTile2D::new(
+                        time,
+                        [-(y as isize) - 1, x as isize].into(),
+                        geo_transform,
+                        grid.into(),
+                    ));
+                }
+            }
+        }
+
+        tiles
+    }
+
+    fn pixels(&self, seed: u64) -> Vec<u8> {
+        let no_data_threshold = (self.no_data_fraction * u64::MAX as f64) as u64;
+
+        (0..self.tile_size * self.tile_size)
+            .map(|i| {
+                let hash = split_mix(seed.wrapping_mul(0x1000_0000_01B3) ^ i as u64);
+
+                if hash < no_data_threshold {
+                    NO_DATA_VALUE
+                } else {
+                    (hash % 255) as u8 + 1
+                }
+            })
+            .collect()
+    }
+
+    pub fn source(&self) -> Box<dyn RasterOperator> {
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: self.tiles(),
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: Some(f64::from(NO_DATA_VALUE)),
+                    bands: Vec::new(),
+                },
+            },
+        }
+        .boxed()
+    }
+
+    /// An execution context whose tiling matches the synthetic tiles
+    pub fn execution_context(&self) -> MockExecutionContext {
+        let mut exe_ctx = MockExecutionContext::default();
+        exe_ctx.tiling_specification = TilingSpecification::new(
+            Coordinate2D::default(),
+            [self.tile_size, self.tile_size].into(),
+        );
+        exe_ctx
+    }
+
+    pub fn spatial_bounds(&self) -> SpatialPartition2D {
+        let tile_extent = self.tile_size as f64 * self.resolution;
+
+        SpatialPartition2D::new_unchecked(
+            (0., self.tiles_y as f64 * tile_extent).into(),
+            (self.tiles_x as f64 * tile_extent, 0.).into(),
+        )
+    }
+
+    /// A query for all tiles and time steps in the native resolution
+    pub fn query_rectangle(&self) -> RasterQueryRectangle {
+        RasterQueryRectangle {
+            spatial_bounds: self.spatial_bounds(),
+            time_interval: TimeInterval::new_unchecked(
+                0,
+                self.time_steps as i64 * TIME_STEP_MILLIS,
+            ),
+            spatial_resolution: SpatialResolution::new_unchecked(self.resolution, self.resolution),
+        }
+    }
+}
+
+/// A fast and deterministic hash for generating pixel values without a random number generator
+fn split_mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
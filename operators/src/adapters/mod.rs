@@ -2,17 +2,24 @@ mod feature_collection_merger;
 mod raster_subquery_adapter;
 mod raster_time;
 mod raster_time_substream;
+mod raster_time_window;
+mod raster_zip;
 
 pub use feature_collection_merger::FeatureCollectionChunkMerger;
 pub use raster_subquery_adapter::{
-    fold_by_coordinate_lookup_future, FoldTileAccu, FoldTileAccuMut, RasterSubQueryAdapter,
-    SubQueryTileAggregator, TileReprojectionSubQuery,
+    fold_by_coordinate_lookup_future, EnlargedTile, FoldTileAccu, FoldTileAccuMut,
+    RasterSubQueryAdapter, SubQueryTileAggregator, TileReprojectionSubQuery, TileSubQueryEnlarged,
+    TileSubQueryWithMargin, TileWithMargin,
 };
 pub use raster_time::RasterTimeAdapter;
+pub use raster_time_window::{raster_time_windows, RasterTimeSlice, RasterTimeWindow};
+pub use raster_zip::RasterArrayZip;
 
 use self::raster_time_substream::RasterTimeMultiFold;
+use self::raster_zip::align_tile_pair;
 use crate::util::Result;
-use futures::{stream::Fuse, Future, Stream, StreamExt};
+use futures::stream::{Fuse, Map, Zip};
+use futures::{Future, Stream, StreamExt};
 use geoengine_datatypes::{
     collections::FeatureCollection,
     primitives::Geometry,
@@ -46,6 +53,30 @@ where
     {
         RasterTimeMultiFold::new(self, accum_init_fn, fold_fn)
     }
+
+    /// Zips this raster stream with another one tile by tile and checks that the tiles are aligned,
+    /// i.e., that they have the same tile position and intersecting validities.
+    /// Unaligned tiles result in an error.
+    ///
+    /// This method assumes both streams answer the same query and thus arrive geo first, time second.
+    ///
+    #[allow(clippy::type_complexity)]
+    fn zip_aligned<S, P2>(
+        self,
+        other: S,
+    ) -> Map<
+        Zip<Self, S>,
+        fn(
+            (Result<RasterTile2D<P>>, Result<RasterTile2D<P2>>),
+        ) -> Result<(RasterTile2D<P>, RasterTile2D<P2>)>,
+    >
+    where
+        Self: Sized,
+        S: Stream<Item = Result<RasterTile2D<P2>>>,
+        P2: Pixel,
+    {
+        self.zip(other).map(align_tile_pair as fn(_) -> _)
+    }
 }
 
 impl<T: ?Sized, P: Pixel> RasterStreamExt<P> for T where T: Stream<Item = Result<RasterTile2D<P>>> {}
@@ -1,7 +1,8 @@
 use crate::engine::{QueryContext, QueryProcessor, RasterQueryProcessor, RasterQueryRectangle};
 use crate::error;
+use crate::util::resampling::ResamplingMethod;
 use crate::util::Result;
-use futures::future::BoxFuture;
+use futures::future::{ready, BoxFuture, Ready};
 use futures::Stream;
 use futures::{
     ready,
@@ -13,11 +14,12 @@ use geoengine_datatypes::primitives::{SpatialPartition2D, SpatialPartitioned};
 use geoengine_datatypes::{
     error::Error::{GridIndexOutOfBounds, InvalidGridIndex},
     operations::reproject::{
-        project_coordinates_fail_tolerant, CoordinateProjection, CoordinateProjector, Reproject,
+        project_coordinates_fail_tolerant, CoordinateProjector, CoordinateTransformation, Reproject,
     },
     primitives::{SpatialResolution, TimeInterval},
     raster::{
-        grid_idx_iter_2d, BoundedGrid, EmptyGrid, Grid2D, MaterializedRasterTile2D, NoDataValue,
+        grid_idx_iter_2d, BoundedGrid, EmptyGrid, GeoTransform, Grid2D, GridIndexAccess,
+        GridShapeAccess, MaterializedRasterTile2D, NoDataValue, RasterDataType,
         TilingSpecification,
     },
     spatial_reference::SpatialReference,
@@ -31,6 +33,7 @@ use geoengine_datatypes::{
 };
 
 use log::debug;
+use num_traits::AsPrimitive;
 use pin_project::pin_project;
 use std::task::Poll;
 
@@ -200,6 +203,11 @@ where
 
         if this.running_query.as_ref().is_none() && this.running_fold.as_ref().is_none() {
             // there is no query and no stream pending
+            if this.query_ctx.abort_requested() {
+                *this.ended = true;
+                return Poll::Ready(Some(Err(error::Error::QueryAborted)));
+            }
+
             debug!("New running_query for: {:?}", &tile_query_rectangle);
 
             let tile_query_stream = this
@@ -373,7 +381,18 @@ where
         return Ok(accu);
     }
 
-    let TileWithProjectionCoordinates { accu_tile, coords } = accu;
+    if accu.resampling != ResamplingMethod::Nearest {
+        accumulate_resampled_pixels(&mut accu, &tile);
+        return Ok(accu);
+    }
+
+    let TileWithProjectionCoordinates {
+        accu_tile,
+        coords,
+        resampling,
+        footprint,
+        weighted_sums,
+    } = accu;
 
     let mut materialized_accu_tile = accu_tile.into_materialized_tile(); //in a fold chain the real materialization should only happen once. All other calls will be simple conversions.
 
@@ -381,11 +400,57 @@ where
         Ok(_) => Ok(TileWithProjectionCoordinates {
             accu_tile: materialized_accu_tile.into(),
             coords,
+            resampling,
+            footprint,
+            weighted_sums,
         }),
         Err(error) => Err(error),
     }
 }
 
+/// Adds the values of the pixels of `source` that contribute to the coordinates of `accu` to its weighted sums.
+/// Since the weights are computed on the global pixel grid, it does not matter which tile contains which contributing pixel.
+fn accumulate_resampled_pixels<T: Pixel>(
+    accu: &mut TileWithProjectionCoordinates<T>,
+    source: &RasterTile2D<T>,
+) {
+    let geo_transform = source.tile_geo_transform();
+    let [size_y, size_x] = source.grid_shape_array();
+    let footprint_x = accu.footprint.x / geo_transform.x_pixel_size.abs();
+    let footprint_y = accu.footprint.y / geo_transform.y_pixel_size.abs();
+
+    for ((_, coord), (weighted_sum, weight_sum)) in
+        accu.coords.iter().zip(accu.weighted_sums.iter_mut())
+    {
+        // the position in pixels relative to the center of the tile's upper left pixel
+        let x = (coord.x - geo_transform.origin_coordinate.x) / geo_transform.x_pixel_size - 0.5;
+        let y = (coord.y - geo_transform.origin_coordinate.y) / geo_transform.y_pixel_size - 0.5;
+
+        let x_weights = accu.resampling.axis_weights(x, footprint_x);
+
+        for (idx_y, weight_y) in accu.resampling.axis_weights(y, footprint_y) {
+            if idx_y < 0 || idx_y as usize >= size_y {
+                continue;
+            }
+
+            for &(idx_x, weight_x) in &x_weights {
+                if idx_x < 0 || idx_x as usize >= size_x {
+                    continue;
+                }
+
+                let value = source.get_at_grid_index_unchecked([idx_y, idx_x]);
+                if source.is_no_data(value) {
+                    continue;
+                }
+
+                let weight = weight_y * weight_x;
+                *weighted_sum += weight * AsPrimitive::<f64>::as_(value);
+                *weight_sum += weight;
+            }
+        }
+    }
+}
+
 /// This method takes two tiles and a map from `GridIdx2D` to `Coordinate2D`. Then for all `GridIdx2D` we set the values from the corresponding coordinate in the source tile.
 pub fn insert_projected_pixels<'a, T: Pixel, I: Iterator<Item = &'a (GridIdx2D, Coordinate2D)>>(
     target: &mut MaterializedRasterTile2D<T>,
@@ -526,13 +591,42 @@ where
 pub struct TileWithProjectionCoordinates<T> {
     accu_tile: RasterTile2D<T>,
     coords: Vec<(GridIdx2D, Coordinate2D)>,
+    resampling: ResamplingMethod,
+    /// The size of an output pixel in the input resolution
+    footprint: SpatialResolution,
+    /// The weighted sums of the input values and the sums of their weights for each coordinate.
+    /// They are not used for `ResamplingMethod::Nearest`.
+    weighted_sums: Vec<(f64, f64)>,
 }
 
 impl<T: Pixel> FoldTileAccu for TileWithProjectionCoordinates<T> {
     type RasterType = T;
 
     fn into_tile(self) -> RasterTile2D<Self::RasterType> {
-        self.accu_tile
+        if self
+            .weighted_sums
+            .iter()
+            .all(|&(_, weight)| weight.abs() < f64::EPSILON)
+        {
+            return self.accu_tile;
+        }
+
+        let is_float = matches!(T::TYPE, RasterDataType::F32 | RasterDataType::F64);
+
+        let mut tile = self.accu_tile.into_materialized_tile();
+        for ((idx, _), (weighted_sum, weight)) in self.coords.iter().zip(self.weighted_sums) {
+            // negative cubic weights may cancel out the others at the border of the data
+            if weight.abs() < f64::EPSILON {
+                continue;
+            }
+
+            let value = weighted_sum / weight;
+            let value = if is_float { value } else { value.round() };
+
+            tile.set_at_grid_index_unchecked(*idx, T::from_(value));
+        }
+
+        tile.into()
     }
 }
 
@@ -546,9 +640,24 @@ impl<T: Pixel> FoldTileAccuMut for TileWithProjectionCoordinates<T> {
 pub struct TileReprojectionSubQuery<T, F> {
     pub in_srs: SpatialReference,
     pub out_srs: SpatialReference,
+    /// The transformation from `in_srs` to `out_srs` or `None` for PROJ's default
+    pub transformation: Option<CoordinateTransformation>,
     pub no_data_and_fill_value: T,
     pub fold_fn: F,
     pub in_spatial_res: SpatialResolution,
+    pub resampling: ResamplingMethod,
+}
+
+impl<T, F> TileReprojectionSubQuery<T, F> {
+    fn out_in_projector(&self) -> Result<CoordinateProjector> {
+        CoordinateProjector::from_known_srs_with_transformation(
+            self.in_srs,
+            self.out_srs,
+            self.transformation.clone(),
+        )?
+        .inverse()
+        .map_err(Into::into)
+    }
 }
 
 impl<T, FoldM, FoldF> SubQueryTileAggregator<T> for TileReprojectionSubQuery<T, FoldM>
@@ -581,16 +690,19 @@ where
             EmptyGrid::new(tile_info.tile_size_in_pixels, self.no_data_and_fill_value);
 
         let idxs: Vec<GridIdx2D> = grid_idx_iter_2d(&output_raster.bounding_box()).collect();
+        let tile_geo_transform = tile_info.tile_geo_transform();
         let coords: Vec<Coordinate2D> = idxs
             .iter()
             .map(|&i| {
-                tile_info
-                    .tile_geo_transform()
-                    .grid_idx_to_upper_left_coordinate_2d(i)
+                if self.resampling == ResamplingMethod::Nearest {
+                    tile_geo_transform.grid_idx_to_upper_left_coordinate_2d(i)
+                } else {
+                    tile_geo_transform.grid_idx_to_center_coordinate_2d(i)
+                }
             })
             .collect();
 
-        let proj = CoordinateProjector::from_known_srs(self.out_srs, self.in_srs)?;
+        let proj = self.out_in_projector()?;
         let projected_coords = project_coordinates_fail_tolerant(&coords, &proj);
 
         let coords: Vec<(GridIdx2D, Coordinate2D)> = idxs
@@ -599,6 +711,12 @@ where
             .filter_map(|(i, c)| c.map(|c| (i, c)))
             .collect();
 
+        let weighted_sums = if self.resampling == ResamplingMethod::Nearest {
+            Vec::new()
+        } else {
+            vec![(0., 0.); coords.len()]
+        };
+
         Ok(TileWithProjectionCoordinates {
             accu_tile: RasterTile2D::new_with_tile_info(
                 query_rect.time_interval,
@@ -606,6 +724,9 @@ where
                 output_raster.into(),
             ),
             coords,
+            resampling: self.resampling,
+            footprint: self.in_spatial_res,
+            weighted_sums,
         })
     }
 
@@ -615,7 +736,7 @@ where
         query_rect: RasterQueryRectangle,
         start_time: TimeInstance,
     ) -> Result<RasterQueryRectangle> {
-        let proj = CoordinateProjector::from_known_srs(self.out_srs, self.in_srs)?;
+        let proj = self.out_in_projector()?;
 
         Ok(RasterQueryRectangle {
             spatial_bounds: tile_info
@@ -633,6 +754,293 @@ where
     }
 }
 
+/// This `SubQueryTileAggregator` queries each tile enlarged by a margin of `margin` pixels on every side.
+/// The enlarged tile is passed to `compute_fn`, which must return a grid of the same shape.
+/// The result is then cropped to the original tile.
+///
+/// This allows writing border-dependent operators, e.g. focal or terrain operators, without re-querying neighboring tiles.
+#[derive(Debug, Clone)]
+pub struct TileSubQueryWithMargin<T, F> {
+    pub margin: usize,
+    pub no_data_value: T,
+    pub compute_fn: F,
+}
+
+impl<T, F> SubQueryTileAggregator<T> for TileSubQueryWithMargin<T, F>
+where
+    T: Pixel,
+    F: Fn(&Grid2D<T>) -> Grid2D<T> + Clone + Send,
+{
+    type FoldFuture = Ready<Result<TileWithMargin<T, F>>>;
+
+    type FoldMethod = fn(TileWithMargin<T, F>, RasterTile2D<T>) -> Self::FoldFuture;
+
+    type TileAccu = TileWithMargin<T, F>;
+
+    fn result_no_data_value(&self) -> Option<T> {
+        Some(self.no_data_value)
+    }
+
+    fn initial_fill_value(&self) -> T {
+        self.no_data_value
+    }
+
+    fn new_fold_accu(
+        &self,
+        tile_info: TileInformation,
+        query_rect: RasterQueryRectangle,
+    ) -> Result<Self::TileAccu> {
+        Ok(TileWithMargin {
+            enlarged_tile: empty_enlarged_tile(
+                tile_info,
+                query_rect.time_interval,
+                self.margin,
+                self.no_data_value,
+            ),
+            tile_info,
+            margin: self.margin,
+            compute_fn: self.compute_fn.clone(),
+        })
+    }
+
+    fn tile_query_rectangle(
+        &self,
+        tile_info: TileInformation,
+        query_rect: RasterQueryRectangle,
+        start_time: TimeInstance,
+    ) -> Result<RasterQueryRectangle> {
+        enlarged_tile_query_rectangle(tile_info, query_rect, start_time, self.margin)
+    }
+
+    fn fold_method(&self) -> Self::FoldMethod {
+        fold_by_blit_with_margin
+    }
+}
+
+/// An empty tile that covers the tile of `tile_info` and a margin of `margin` pixels on every side
+fn empty_enlarged_tile<T: Pixel>(
+    tile_info: TileInformation,
+    time_interval: TimeInterval,
+    margin: usize,
+    no_data_value: T,
+) -> RasterTile2D<T> {
+    let [size_y, size_x] = tile_info.tile_size_in_pixels.into_inner();
+    let enlarged_shape = [size_y + 2 * margin, size_x + 2 * margin];
+
+    let enlarged_geo_transform = tile_info.tile_geo_transform();
+    let enlarged_geo_transform = GeoTransform::new(
+        enlarged_geo_transform
+            .grid_idx_to_upper_left_coordinate_2d([-(margin as isize), -(margin as isize)].into()),
+        enlarged_geo_transform.x_pixel_size,
+        enlarged_geo_transform.y_pixel_size,
+    );
+
+    RasterTile2D::new(
+        time_interval,
+        [0, 0].into(),
+        enlarged_geo_transform,
+        EmptyGrid::new(enlarged_shape.into(), no_data_value).into(),
+    )
+}
+
+/// The query rectangle of the tile of `tile_info` and a margin of `margin` pixels on every side
+fn enlarged_tile_query_rectangle(
+    tile_info: TileInformation,
+    query_rect: RasterQueryRectangle,
+    start_time: TimeInstance,
+    margin: usize,
+) -> Result<RasterQueryRectangle> {
+    let [size_y, size_x] = tile_info.tile_size_in_pixels.into_inner();
+    let margin = margin as isize;
+    let geo_transform = tile_info.tile_geo_transform();
+
+    Ok(RasterQueryRectangle {
+        spatial_bounds: SpatialPartition2D::new(
+            geo_transform.grid_idx_to_upper_left_coordinate_2d([-margin, -margin].into()),
+            geo_transform.grid_idx_to_upper_left_coordinate_2d(
+                [size_y as isize + margin, size_x as isize + margin].into(),
+            ),
+        )?,
+        time_interval: TimeInterval::new_instant(start_time)?,
+        spatial_resolution: query_rect.spatial_resolution,
+    })
+}
+
+/// An accumulator that holds a tile enlarged by a margin and crops it to the original tile when finished.
+#[derive(Debug, Clone)]
+pub struct TileWithMargin<T, F> {
+    enlarged_tile: RasterTile2D<T>,
+    tile_info: TileInformation,
+    margin: usize,
+    compute_fn: F,
+}
+
+impl<T, F> FoldTileAccu for TileWithMargin<T, F>
+where
+    T: Pixel,
+    F: Fn(&Grid2D<T>) -> Grid2D<T>,
+{
+    type RasterType = T;
+
+    fn into_tile(self) -> RasterTile2D<Self::RasterType> {
+        let enlarged_tile = self.enlarged_tile.into_materialized_tile();
+        let result = (self.compute_fn)(&enlarged_tile.grid_array);
+
+        let [_, enlarged_size_x] = result.shape.into_inner();
+        let [size_y, size_x] = self.tile_info.tile_size_in_pixels.into_inner();
+
+        let margin = self.margin;
+
+        let data = (margin..margin + size_y)
+            .flat_map(|y| {
+                let row_start = y * enlarged_size_x + margin;
+                result.data[row_start..row_start + size_x].iter().copied()
+            })
+            .collect();
+
+        RasterTile2D::new_with_tile_info(
+            enlarged_tile.time,
+            self.tile_info,
+            Grid2D::new(
+                self.tile_info.tile_size_in_pixels,
+                data,
+                result.no_data_value,
+            )
+            .expect("cropped grid must match the tile size")
+            .into(),
+        )
+    }
+}
+
+fn fold_by_blit_with_margin<T, F>(
+    accu: TileWithMargin<T, F>,
+    tile: RasterTile2D<T>,
+) -> Ready<Result<TileWithMargin<T, F>>>
+where
+    T: Pixel,
+{
+    let TileWithMargin {
+        enlarged_tile,
+        tile_info,
+        margin,
+        compute_fn,
+    } = accu;
+
+    ready(
+        fold_by_blit_impl(enlarged_tile, tile).map(|enlarged_tile| TileWithMargin {
+            enlarged_tile,
+            tile_info,
+            margin,
+            compute_fn,
+        }),
+    )
+}
+
+/// This `SubQueryTileAggregator` queries each tile enlarged by a margin of `margin` pixels
+/// on every side and outputs the enlarged tiles without cropping them.
+///
+/// The output tiles keep the position and global geo transform of the original tiles,
+/// but their grids additionally contain the margin,
+/// i.e., they start `margin` pixels above and left of the original tile.
+/// This allows combining the enlarged tiles of multiple sources before cropping the result,
+/// e.g., to access neighbors across tile borders in an expression over several rasters.
+#[derive(Debug, Clone)]
+pub struct TileSubQueryEnlarged<T> {
+    pub margin: usize,
+    pub no_data_value: T,
+}
+
+impl<T> SubQueryTileAggregator<T> for TileSubQueryEnlarged<T>
+where
+    T: Pixel,
+{
+    type FoldFuture = Ready<Result<EnlargedTile<T>>>;
+
+    type FoldMethod = fn(EnlargedTile<T>, RasterTile2D<T>) -> Self::FoldFuture;
+
+    type TileAccu = EnlargedTile<T>;
+
+    fn result_no_data_value(&self) -> Option<T> {
+        Some(self.no_data_value)
+    }
+
+    fn initial_fill_value(&self) -> T {
+        self.no_data_value
+    }
+
+    fn new_fold_accu(
+        &self,
+        tile_info: TileInformation,
+        query_rect: RasterQueryRectangle,
+    ) -> Result<Self::TileAccu> {
+        Ok(EnlargedTile {
+            enlarged_tile: empty_enlarged_tile(
+                tile_info,
+                query_rect.time_interval,
+                self.margin,
+                self.no_data_value,
+            ),
+            tile_info,
+        })
+    }
+
+    fn tile_query_rectangle(
+        &self,
+        tile_info: TileInformation,
+        query_rect: RasterQueryRectangle,
+        start_time: TimeInstance,
+    ) -> Result<RasterQueryRectangle> {
+        enlarged_tile_query_rectangle(tile_info, query_rect, start_time, self.margin)
+    }
+
+    fn fold_method(&self) -> Self::FoldMethod {
+        fold_by_blit_enlarged
+    }
+}
+
+/// An accumulator that holds a tile enlarged by a margin
+/// and outputs it at the position of the original tile
+#[derive(Debug, Clone)]
+pub struct EnlargedTile<T> {
+    enlarged_tile: RasterTile2D<T>,
+    tile_info: TileInformation,
+}
+
+impl<T> FoldTileAccu for EnlargedTile<T>
+where
+    T: Pixel,
+{
+    type RasterType = T;
+
+    fn into_tile(self) -> RasterTile2D<Self::RasterType> {
+        RasterTile2D::new_with_tile_info(
+            self.enlarged_tile.time,
+            self.tile_info,
+            self.enlarged_tile.grid_array,
+        )
+    }
+}
+
+fn fold_by_blit_enlarged<T>(
+    accu: EnlargedTile<T>,
+    tile: RasterTile2D<T>,
+) -> Ready<Result<EnlargedTile<T>>>
+where
+    T: Pixel,
+{
+    let EnlargedTile {
+        enlarged_tile,
+        tile_info,
+    } = accu;
+
+    ready(
+        fold_by_blit_impl(enlarged_tile, tile).map(|enlarged_tile| EnlargedTile {
+            enlarged_tile,
+            tile_info,
+        }),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use geoengine_datatypes::{
@@ -698,6 +1106,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    bands: Vec::new(),
                 },
             },
         }
@@ -714,9 +1123,7 @@ mod tests {
             spatial_resolution: SpatialResolution::one(),
         };
 
-        let query_ctx = MockQueryContext {
-            chunk_byte_size: 1024 * 1024,
-        };
+        let query_ctx = MockQueryContext::new(1024 * 1024);
         let tiling_strat = exe_ctx.tiling_specification;
 
         let op = mrs1.initialize(&exe_ctx).await.unwrap();
@@ -791,6 +1198,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    bands: Vec::new(),
                 },
             },
         }
@@ -807,9 +1215,7 @@ mod tests {
             spatial_resolution: SpatialResolution::one(),
         };
 
-        let query_ctx = MockQueryContext {
-            chunk_byte_size: 1024 * 1024,
-        };
+        let query_ctx = MockQueryContext::new(1024 * 1024);
         let tiling_strat = exe_ctx.tiling_specification;
 
         let op = mrs1.initialize(&exe_ctx).await.unwrap();
@@ -826,9 +1232,11 @@ mod tests {
         let state_gen = TileReprojectionSubQuery {
             in_srs: projection,
             out_srs: projection,
+            transformation: None,
             no_data_and_fill_value: no_data_v,
             fold_fn: fold_by_coordinate_lookup_future,
             in_spatial_res: query_rect.spatial_resolution,
+            resampling: ResamplingMethod::Nearest,
         };
         let a = RasterSubQueryAdapter::new(&qp, query_rect, tiling_strat, &query_ctx, state_gen);
         let res = a
@@ -837,4 +1245,207 @@ mod tests {
             .await;
         assert_eq!(data, res);
     }
+
+    #[tokio::test]
+    async fn margin() {
+        let no_data_value = Some(0);
+        let data = vec![
+            RasterTile2D {
+                time: TimeInterval::new_unchecked(0, 5),
+                tile_position: [-1, 0].into(),
+                global_geo_transform: Default::default(),
+                grid_array: Grid::new([2, 2].into(), vec![1, 2, 3, 4], no_data_value)
+                    .unwrap()
+                    .into(),
+                properties: Default::default(),
+            },
+            RasterTile2D {
+                time: TimeInterval::new_unchecked(0, 5),
+                tile_position: [-1, 1].into(),
+                global_geo_transform: Default::default(),
+                grid_array: Grid::new([2, 2].into(), vec![7, 8, 9, 10], no_data_value)
+                    .unwrap()
+                    .into(),
+                properties: Default::default(),
+            },
+        ];
+
+        let mrs1 = MockRasterSource {
+            params: MockRasterSourceParams {
+                data,
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: no_data_value.map(AsPrimitive::as_),
+                    bands: Vec::new(),
+                },
+            },
+        }
+        .boxed();
+
+        let mut exe_ctx = MockExecutionContext::default();
+        exe_ctx.tiling_specification.tile_size_in_pixels = GridShape {
+            shape_array: [2, 2],
+        };
+
+        let query_rect = RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new_unchecked((0., 2.).into(), (4., 0.).into()),
+            time_interval: TimeInterval::new_unchecked(0, 5),
+            spatial_resolution: SpatialResolution::one(),
+        };
+
+        let query_ctx = MockQueryContext::new(1024 * 1024);
+        let tiling_strat = exe_ctx.tiling_specification;
+
+        let op = mrs1.initialize(&exe_ctx).await.unwrap();
+
+        let qp = op.query_processor().unwrap().get_u8().unwrap();
+
+        // every pixel takes the value of its right neighbor
+        let shift_left = |grid: &Grid2D<u8>| {
+            let [size_y, size_x] = grid.shape.into_inner();
+            let mut data = grid.data.to_vec();
+            for y in 0..size_y {
+                for x in 0..size_x - 1 {
+                    data[y * size_x + x] = grid.data[y * size_x + x + 1];
+                }
+            }
+            Grid2D::new(grid.shape, data, grid.no_data_value).unwrap()
+        };
+
+        let a = RasterSubQueryAdapter::new(
+            &qp,
+            query_rect,
+            tiling_strat,
+            &query_ctx,
+            TileSubQueryWithMargin {
+                margin: 1,
+                no_data_value: 0,
+                compute_fn: shift_left,
+            },
+        );
+        let res = a
+            .map(Result::unwrap)
+            .map(|tile| {
+                (
+                    tile.tile_position,
+                    tile.time,
+                    tile.into_materialized_tile().grid_array.data.into_vec(),
+                )
+            })
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(
+            res,
+            vec![
+                (
+                    GridIdx2D::from([-1, 0]),
+                    TimeInterval::new_unchecked(0, 5),
+                    vec![2, 7, 4, 9]
+                ),
+                (
+                    GridIdx2D::from([-1, 1]),
+                    TimeInterval::new_unchecked(0, 5),
+                    vec![8, 0, 10, 0]
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn enlarged() {
+        let no_data_value = Some(0);
+        let data = vec![
+            RasterTile2D {
+                time: TimeInterval::new_unchecked(0, 5),
+                tile_position: [-1, 0].into(),
+                global_geo_transform: Default::default(),
+                grid_array: Grid::new([2, 2].into(), vec![1, 2, 3, 4], no_data_value)
+                    .unwrap()
+                    .into(),
+                properties: Default::default(),
+            },
+            RasterTile2D {
+                time: TimeInterval::new_unchecked(0, 5),
+                tile_position: [-1, 1].into(),
+                global_geo_transform: Default::default(),
+                grid_array: Grid::new([2, 2].into(), vec![7, 8, 9, 10], no_data_value)
+                    .unwrap()
+                    .into(),
+                properties: Default::default(),
+            },
+        ];
+
+        let mrs1 = MockRasterSource {
+            params: MockRasterSourceParams {
+                data,
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: no_data_value.map(AsPrimitive::as_),
+                    bands: Vec::new(),
+                },
+            },
+        }
+        .boxed();
+
+        let mut exe_ctx = MockExecutionContext::default();
+        exe_ctx.tiling_specification.tile_size_in_pixels = GridShape {
+            shape_array: [2, 2],
+        };
+
+        let query_rect = RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new_unchecked((0., 2.).into(), (4., 0.).into()),
+            time_interval: TimeInterval::new_unchecked(0, 5),
+            spatial_resolution: SpatialResolution::one(),
+        };
+
+        let query_ctx = MockQueryContext::new(1024 * 1024);
+        let tiling_strat = exe_ctx.tiling_specification;
+
+        let op = mrs1.initialize(&exe_ctx).await.unwrap();
+
+        let qp = op.query_processor().unwrap().get_u8().unwrap();
+
+        let a = RasterSubQueryAdapter::new(
+            &qp,
+            query_rect,
+            tiling_strat,
+            &query_ctx,
+            TileSubQueryEnlarged {
+                margin: 1,
+                no_data_value: 0,
+            },
+        );
+        let res = a
+            .map(Result::unwrap)
+            .map(|tile| {
+                (
+                    tile.tile_position,
+                    tile.grid_array.grid_shape_array(),
+                    tile.into_materialized_tile().grid_array.data.into_vec(),
+                )
+            })
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(
+            res,
+            vec![
+                (
+                    GridIdx2D::from([-1, 0]),
+                    [4, 4],
+                    vec![0, 0, 0, 0, 0, 1, 2, 7, 0, 3, 4, 9, 0, 0, 0, 0]
+                ),
+                (
+                    GridIdx2D::from([-1, 1]),
+                    [4, 4],
+                    vec![0, 0, 0, 0, 2, 7, 8, 0, 4, 9, 10, 0, 0, 0, 0, 0]
+                ),
+            ]
+        );
+    }
 }
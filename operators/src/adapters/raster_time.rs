@@ -244,7 +244,7 @@ where
 //             spatial_reference: SpatialReference::epsg_4326().into(),
 //             measurement: Measurement::Unitless,
 //             no_data_value: no_data_value.map(AsPrimitive::as_),
-//         },
+//, bands: Vec::new(),         },
 //     },
 // }
 // .boxed();
@@ -330,7 +330,7 @@ where
 //             spatial_reference: SpatialReference::epsg_4326().into(),
 //             measurement: Measurement::Unitless,
 //             no_data_value: no_data_value.map(AsPrimitive::as_),
-//         },
+//, bands: Vec::new(),         },
 //     },
 // }
 // .boxed();
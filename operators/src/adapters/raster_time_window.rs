@@ -0,0 +1,365 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::primitives::{TimeInstance, TimeInterval};
+use geoengine_datatypes::raster::{Pixel, RasterTile2D};
+
+use crate::engine::{QueryContext, RasterQueryProcessor, RasterQueryRectangle};
+use crate::util::Result;
+
+/// All tiles of a raster query with the same validity, in the order of the tiling strategy
+#[derive(Debug, Clone)]
+pub struct RasterTimeSlice<P: Pixel> {
+    pub time: TimeInterval,
+    pub tiles: Vec<RasterTile2D<P>>,
+}
+
+/// A time slice of a query together with its neighboring time slices.
+/// At the temporal borders of the data, the window contains fewer slices before or after its `center`.
+#[derive(Debug, Clone)]
+pub struct RasterTimeWindow<P: Pixel> {
+    pub slices: Vec<Arc<RasterTimeSlice<P>>>,
+    /// The index of the slice of the query in the `slices`
+    pub center: usize,
+}
+
+impl<P: Pixel> RasterTimeWindow<P> {
+    pub fn center_slice(&self) -> &RasterTimeSlice<P> {
+        &self.slices[self.center]
+    }
+}
+
+/// Queries the `source` and emits a window for each time slice of the `query`
+/// that contains up to `radius` time slices before and after it.
+///
+/// The slices before and after the `query` are loaded by additional queries for the instants next to it.
+/// At most `2 * radius + 1` time slices are buffered at once.
+pub fn raster_time_windows<'a, P: Pixel>(
+    source: &'a dyn RasterQueryProcessor<RasterType = P>,
+    query: RasterQueryRectangle,
+    ctx: &'a dyn QueryContext,
+    radius: usize,
+) -> BoxStream<'a, Result<RasterTimeWindow<P>>> {
+    let slices = time_slices_with_neighbors(source, query, ctx, radius);
+
+    stream::unfold(
+        WindowState {
+            slices,
+            buffer: VecDeque::with_capacity(2 * radius + 1),
+            next: 0,
+            radius,
+            finished: false,
+        },
+        |mut state| async move {
+            let window = state.next_window().await?;
+            Some((window, state))
+        },
+    )
+    .boxed()
+}
+
+/// A time slice and whether it belongs to the query or is only a neighbor
+type MarkedSlice<P> = (Arc<RasterTimeSlice<P>>, bool);
+
+struct WindowState<'a, P: Pixel> {
+    slices: BoxStream<'a, Result<MarkedSlice<P>>>,
+    buffer: VecDeque<MarkedSlice<P>>,
+    /// The index of the next slice in the `buffer` to emit a window for
+    next: usize,
+    radius: usize,
+    finished: bool,
+}
+
+impl<'a, P: Pixel> WindowState<'a, P> {
+    async fn next_window(&mut self) -> Option<Result<RasterTimeWindow<P>>> {
+        loop {
+            let following = self.buffer.len().saturating_sub(self.next + 1);
+
+            if self.next < self.buffer.len() && (following >= self.radius || self.finished) {
+                let center = self.next.min(self.radius);
+                let start = self.next - center;
+                let end = (self.next + self.radius).min(self.buffer.len() - 1);
+
+                let is_query_slice = self.buffer[self.next].1;
+                let slices = (start..=end).map(|i| self.buffer[i].0.clone()).collect();
+
+                self.next += 1;
+                if self.next > self.radius {
+                    self.buffer.pop_front();
+                    self.next -= 1;
+                }
+
+                if is_query_slice {
+                    return Some(Ok(RasterTimeWindow { slices, center }));
+                }
+
+                continue;
+            }
+
+            if self.finished {
+                return None;
+            }
+
+            match self.slices.next().await {
+                Some(Ok(slice)) => self.buffer.push_back(slice),
+                Some(Err(error)) => {
+                    self.finished = true;
+                    self.buffer.clear();
+                    return Some(Err(error));
+                }
+                None => self.finished = true,
+            }
+        }
+    }
+}
+
+/// The time slices of the `query`, preceded and followed by up to `radius` neighboring slices
+fn time_slices_with_neighbors<'a, P: Pixel>(
+    source: &'a dyn RasterQueryProcessor<RasterType = P>,
+    query: RasterQueryRectangle,
+    ctx: &'a dyn QueryContext,
+    radius: usize,
+) -> BoxStream<'a, Result<MarkedSlice<P>>> {
+    let query_slices = stream::once(async move {
+        let tiles = source.raster_query(query, ctx).await?;
+        Ok(time_slices(tiles))
+    })
+    .try_flatten()
+    .boxed();
+
+    stream::unfold(
+        (query_slices, None, false),
+        move |(mut query_slices, previous, done): (_, Option<TimeInterval>, bool)| async move {
+            if done {
+                return None;
+            }
+
+            let (slices, time, done) = match (query_slices.next().await, previous) {
+                (Some(Ok(slice)), Some(_)) => {
+                    let time = slice.time;
+                    (Ok(vec![(Arc::new(slice), true)]), Some(time), false)
+                }
+                (Some(Ok(slice)), None) => {
+                    let time = slice.time;
+                    let slices = neighbor_slices(source, query, ctx, time, radius, false)
+                        .await
+                        .map(|neighbors| {
+                            neighbors
+                                .into_iter()
+                                .rev()
+                                .map(|neighbor| (Arc::new(neighbor), false))
+                                .chain(std::iter::once((Arc::new(slice), true)))
+                                .collect()
+                        });
+                    (slices, Some(time), false)
+                }
+                (Some(Err(error)), _) => (Err(error), previous, true),
+                (None, Some(time)) => {
+                    let slices = neighbor_slices(source, query, ctx, time, radius, true)
+                        .await
+                        .map(|neighbors| {
+                            neighbors
+                                .into_iter()
+                                .map(|neighbor| (Arc::new(neighbor), false))
+                                .collect()
+                        });
+                    (slices, previous, true)
+                }
+                (None, None) => return None,
+            };
+
+            Some((slices, (query_slices, time, done)))
+        },
+    )
+    .map_ok(|slices: Vec<MarkedSlice<P>>| stream::iter(slices.into_iter().map(Ok)))
+    .try_flatten()
+    .boxed()
+}
+
+/// Loads up to `count` time slices before or after the slice with the validity `time`,
+/// ordered by their distance to it
+async fn neighbor_slices<P: Pixel>(
+    source: &dyn RasterQueryProcessor<RasterType = P>,
+    query: RasterQueryRectangle,
+    ctx: &dyn QueryContext,
+    time: TimeInterval,
+    count: usize,
+    following: bool,
+) -> Result<Vec<RasterTimeSlice<P>>> {
+    let mut slices: Vec<RasterTimeSlice<P>> = Vec::with_capacity(count);
+    let mut time = time;
+
+    while slices.len() < count {
+        let instant = if following {
+            if time.end() >= TimeInstance::MAX {
+                break;
+            }
+            time.end()
+        } else {
+            if time.start() <= TimeInstance::MIN {
+                break;
+            }
+            time.start() + -1
+        };
+
+        let tiles = source
+            .raster_query(
+                RasterQueryRectangle {
+                    time_interval: TimeInterval::new_instant(instant)?,
+                    ..query
+                },
+                ctx,
+            )
+            .await?;
+
+        let slice = match time_slices(tiles).next().await {
+            Some(slice) => slice?,
+            None => break,
+        };
+
+        // sources that do not have a slice for the instant might answer with the same slice again
+        let is_neighbor = if following {
+            slice.time.start() >= time.end()
+        } else {
+            slice.time.end() <= time.start()
+        };
+        if !is_neighbor {
+            break;
+        }
+
+        time = slice.time;
+        slices.push(slice);
+    }
+
+    Ok(slices)
+}
+
+/// Groups the consecutive tiles of a stream with the same validity
+fn time_slices<'a, P: Pixel>(
+    tiles: BoxStream<'a, Result<RasterTile2D<P>>>,
+) -> BoxStream<'a, Result<RasterTimeSlice<P>>> {
+    stream::unfold(
+        (tiles, None),
+        |(mut tiles, pending): (_, Option<RasterTile2D<P>>)| async move {
+            let mut slice = match pending {
+                Some(tile) => RasterTimeSlice {
+                    time: tile.time,
+                    tiles: vec![tile],
+                },
+                None => match tiles.next().await? {
+                    Ok(tile) => RasterTimeSlice {
+                        time: tile.time,
+                        tiles: vec![tile],
+                    },
+                    Err(error) => return Some((Err(error), (tiles, None))),
+                },
+            };
+
+            loop {
+                match tiles.next().await {
+                    Some(Ok(tile)) if tile.time == slice.time => slice.tiles.push(tile),
+                    Some(Ok(tile)) => return Some((Ok(slice), (tiles, Some(tile)))),
+                    Some(Err(error)) => return Some((Err(error), (tiles, None))),
+                    None => return Some((Ok(slice), (tiles, None))),
+                }
+            }
+        },
+    )
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{
+        MockExecutionContext, MockQueryContext, RasterOperator, RasterResultDescriptor,
+    };
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::primitives::{Measurement, SpatialPartition2D, SpatialResolution};
+    use geoengine_datatypes::raster::{Grid2D, RasterDataType, TileInformation};
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+
+    #[tokio::test]
+    async fn it_creates_windows() {
+        let tiles = (0..5)
+            .flat_map(|t| {
+                (0..2).map(move |x| {
+                    RasterTile2D::new_with_tile_info(
+                        TimeInterval::new_unchecked(t * 10, t * 10 + 10),
+                        TileInformation {
+                            global_tile_position: [-1, x].into(),
+                            tile_size_in_pixels: [2, 2].into(),
+                            global_geo_transform: Default::default(),
+                        },
+                        Grid2D::new([2, 2].into(), vec![t as u8; 4], None)
+                            .unwrap()
+                            .into(),
+                    )
+                })
+            })
+            .collect();
+
+        let source = MockRasterSource {
+            params: MockRasterSourceParams {
+                data: tiles,
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: None,
+                    bands: Vec::new(),
+                },
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .get_u8()
+        .unwrap();
+
+        let ctx = MockQueryContext::default();
+
+        let windows: Vec<RasterTimeWindow<u8>> = raster_time_windows(
+            source.as_ref(),
+            RasterQueryRectangle {
+                spatial_bounds: SpatialPartition2D::new_unchecked((0., 2.).into(), (4., 0.).into()),
+                time_interval: TimeInterval::new_unchecked(15, 35),
+                spatial_resolution: SpatialResolution::one(),
+            },
+            &ctx,
+            2,
+        )
+        .try_collect()
+        .await
+        .unwrap();
+
+        let windows: Vec<(Vec<i64>, usize, usize)> = windows
+            .iter()
+            .map(|window| {
+                (
+                    window
+                        .slices
+                        .iter()
+                        .map(|slice| slice.time.start().inner())
+                        .collect(),
+                    window.center,
+                    window.center_slice().tiles.len(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            windows,
+            vec![
+                (vec![0, 10, 20, 30], 1, 2),
+                (vec![0, 10, 20, 30, 40], 2, 2),
+                (vec![10, 20, 30, 40], 2, 2),
+            ]
+        );
+    }
+}
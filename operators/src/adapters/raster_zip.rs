@@ -0,0 +1,189 @@
+use crate::error;
+use crate::util::Result;
+use futures::stream::{Fuse, FusedStream, Stream};
+use futures::StreamExt;
+use geoengine_datatypes::raster::{Pixel, RasterTile2D};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Zips multiple raster streams of the same pixel type tile by tile.
+///
+/// All streams must be answers to the same query rectangle, s.t. they follow the same tile order
+/// (cf. [`RasterQueryProcessor::raster_query`](crate::engine::RasterQueryProcessor::raster_query)).
+/// Each output item contains one tile per input stream in the order of the input streams.
+/// Tiles that do not align, i.e., have different tile positions or non-intersecting validities, result in an error.
+///
+/// The stream ends as soon as one of the input streams ends.
+#[must_use = "streams do nothing unless polled"]
+pub struct RasterArrayZip<St, P>
+where
+    St: Stream<Item = Result<RasterTile2D<P>>> + Unpin,
+    P: Pixel,
+{
+    streams: Vec<Fuse<St>>,
+    buffer: Vec<Option<Result<RasterTile2D<P>>>>,
+    ended: bool,
+}
+
+impl<St, P> RasterArrayZip<St, P>
+where
+    St: Stream<Item = Result<RasterTile2D<P>>> + Unpin,
+    P: Pixel,
+{
+    pub fn new(streams: Vec<St>) -> Self {
+        Self {
+            buffer: streams.iter().map(|_| None).collect(),
+            ended: streams.is_empty(),
+            streams: streams.into_iter().map(StreamExt::fuse).collect(),
+        }
+    }
+}
+
+impl<St, P> Stream for RasterArrayZip<St, P>
+where
+    St: Stream<Item = Result<RasterTile2D<P>>> + Unpin,
+    P: Pixel,
+{
+    type Item = Result<Vec<RasterTile2D<P>>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.ended {
+            return Poll::Ready(None);
+        }
+
+        let this = &mut *self;
+
+        for (stream, slot) in this.streams.iter_mut().zip(this.buffer.iter_mut()) {
+            if slot.is_some() {
+                continue;
+            }
+
+            match stream.poll_next_unpin(cx) {
+                Poll::Ready(Some(item)) => *slot = Some(item),
+                Poll::Ready(None) => {
+                    this.ended = true;
+                    return Poll::Ready(None);
+                }
+                Poll::Pending => {}
+            }
+        }
+
+        if this.buffer.iter().any(Option::is_none) {
+            return Poll::Pending;
+        }
+
+        let tiles = this
+            .buffer
+            .iter_mut()
+            .map(|slot| slot.take().expect("checked"))
+            .collect::<Result<Vec<_>>>()
+            .and_then(|tiles| {
+                if let Some((first, others)) = tiles.split_first() {
+                    for other in others {
+                        check_alignment(first, other)?;
+                    }
+                }
+                Ok(tiles)
+            });
+
+        if tiles.is_err() {
+            this.ended = true;
+        }
+
+        Poll::Ready(Some(tiles))
+    }
+}
+
+impl<St, P> FusedStream for RasterArrayZip<St, P>
+where
+    St: Stream<Item = Result<RasterTile2D<P>>> + Unpin,
+    P: Pixel,
+{
+    fn is_terminated(&self) -> bool {
+        self.ended
+    }
+}
+
+/// Combines two tiles of a zipped raster stream and checks that they are aligned
+pub(crate) fn align_tile_pair<P1, P2>(
+    (tile_a, tile_b): (Result<RasterTile2D<P1>>, Result<RasterTile2D<P2>>),
+) -> Result<(RasterTile2D<P1>, RasterTile2D<P2>)>
+where
+    P1: Pixel,
+    P2: Pixel,
+{
+    let (tile_a, tile_b) = (tile_a?, tile_b?);
+    check_alignment(&tile_a, &tile_b)?;
+    Ok((tile_a, tile_b))
+}
+
+fn check_alignment<P1, P2>(tile_a: &RasterTile2D<P1>, tile_b: &RasterTile2D<P2>) -> Result<()>
+where
+    P1: Pixel,
+    P2: Pixel,
+{
+    if tile_a.tile_position == tile_b.tile_position && tile_a.time.intersects(&tile_b.time) {
+        Ok(())
+    } else {
+        Err(error::Error::UnalignedRasterTiles {
+            position_a: tile_a.tile_position,
+            time_a: tile_a.time,
+            position_b: tile_b.tile_position,
+            time_b: tile_b.time,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::RasterStreamExt;
+    use futures::stream;
+    use geoengine_datatypes::primitives::TimeInterval;
+    use geoengine_datatypes::raster::{Grid2D, TileInformation};
+
+    fn tile(position: [isize; 2], time: (i64, i64), value: u8) -> Result<RasterTile2D<u8>> {
+        Ok(RasterTile2D::new_with_tile_info(
+            TimeInterval::new_unchecked(time.0, time.1),
+            TileInformation {
+                global_tile_position: position.into(),
+                tile_size_in_pixels: [1, 1].into(),
+                global_geo_transform: Default::default(),
+            },
+            Grid2D::new([1, 1].into(), vec![value], None)
+                .unwrap()
+                .into(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn array_zip() {
+        let a = stream::iter(vec![tile([0, 0], (0, 5), 1), tile([0, 1], (0, 5), 2)]);
+        let b = stream::iter(vec![tile([0, 0], (0, 10), 3), tile([0, 1], (0, 10), 4)]);
+
+        let result: Vec<Vec<RasterTile2D<u8>>> = RasterArrayZip::new(vec![a, b])
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0][0].tile_position, [0, 0].into());
+        assert_eq!(result[0][1].tile_position, [0, 0].into());
+        assert_eq!(result[1][0].tile_position, [0, 1].into());
+        assert_eq!(result[1][1].tile_position, [0, 1].into());
+    }
+
+    #[tokio::test]
+    async fn unaligned() {
+        let a = stream::iter(vec![tile([0, 0], (0, 5), 1)]);
+        let b = stream::iter(vec![tile([0, 1], (0, 5), 2)]);
+
+        let result: Vec<_> = a.zip_aligned(b).collect().await;
+
+        assert_eq!(result.len(), 1);
+        assert!(matches!(
+            result[0],
+            Err(error::Error::UnalignedRasterTiles { .. })
+        ));
+    }
+}
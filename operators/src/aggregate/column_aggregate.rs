@@ -0,0 +1,235 @@
+use crate::engine::{
+    AggregateOperator, AggregateQueryProcessor, AggregateQueryRectangle, AggregateResultDescriptor,
+    ExecutionContext, InitializedAggregateOperator, InitializedVectorOperator, Operator,
+    QueryContext, SingleVectorSource, TypedAggregateQueryProcessor, VectorQueryProcessor,
+};
+use crate::error;
+use crate::processing::{Accumulator, AggregationFunction};
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use geoengine_datatypes::collections::{FeatureCollection, FeatureCollectionInfos};
+use geoengine_datatypes::primitives::{FeatureDataValue, Geometry};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+pub const COLUMN_AGGREGATE_OPERATOR_NAME: &str = "ColumnAggregate";
+
+/// An aggregate that reduces a column of all features of a query to a single number.
+///
+/// Null values are left out.
+pub type ColumnAggregate = Operator<ColumnAggregateParams, SingleVectorSource>;
+
+/// The parameter spec for `ColumnAggregate`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnAggregateParams {
+    pub column: String,
+    pub function: AggregationFunction,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl AggregateOperator for ColumnAggregate {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedAggregateOperator>> {
+        let source = self.sources.vector.initialize(context).await?;
+
+        let data_type = source
+            .result_descriptor()
+            .columns
+            .get(&self.params.column)
+            .ok_or_else(|| error::Error::ColumnDoesNotExist {
+                column: self.params.column.clone(),
+            })?;
+
+        // counting works for all columns
+        ensure!(
+            self.params.function == AggregationFunction::Count || data_type.is_numeric(),
+            error::InvalidFeatureDataType
+        );
+
+        Ok(InitializedColumnAggregate {
+            result_descriptor: AggregateResultDescriptor {},
+            vector_source: source,
+            params: self.params,
+        }
+        .boxed())
+    }
+}
+
+/// The initialization of `ColumnAggregate`
+pub struct InitializedColumnAggregate {
+    result_descriptor: AggregateResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    params: ColumnAggregateParams,
+}
+
+impl InitializedAggregateOperator for InitializedColumnAggregate {
+    fn query_processor(&self) -> Result<TypedAggregateQueryProcessor> {
+        let input_processor = self.vector_source.query_processor()?;
+
+        let processor = call_on_generic_vector_processor!(input_processor, features => {
+            ColumnAggregateQueryProcessor { params: self.params.clone(), features }.boxed()
+        });
+
+        Ok(TypedAggregateQueryProcessor::Scalar(processor))
+    }
+
+    fn result_descriptor(&self) -> &AggregateResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// A query processor that calculates the `ColumnAggregate` on its input.
+pub struct ColumnAggregateQueryProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    params: ColumnAggregateParams,
+    features: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+}
+
+#[async_trait]
+impl<G> AggregateQueryProcessor for ColumnAggregateQueryProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type Output = Option<f64>;
+
+    fn aggregate_type(&self) -> &'static str {
+        COLUMN_AGGREGATE_OPERATOR_NAME
+    }
+
+    async fn aggregate_query<'a>(
+        &'a self,
+        query: AggregateQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<Self::Output> {
+        let counts_only = self.params.function == AggregationFunction::Count;
+
+        let accumulator = self
+            .features
+            .query(query, ctx)
+            .await?
+            .try_fold(
+                Accumulator::default(),
+                |mut accumulator, features| async move {
+                    let data = features.data(&self.params.column)?;
+
+                    if counts_only {
+                        for is_null in data.nulls() {
+                            if !is_null {
+                                accumulator.add(0.);
+                            }
+                        }
+                    } else {
+                        for value in data.float_options_iter().flatten() {
+                            accumulator.add(value);
+                        }
+                    }
+
+                    Ok(accumulator)
+                },
+            )
+            .await?;
+
+        Ok(match accumulator.result(self.params.function) {
+            FeatureDataValue::Int(count) => Some(count as f64),
+            FeatureDataValue::NullableFloat(value) => value,
+            _ => unreachable!("accumulators only output counts or floats"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, VectorOperator};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::DataCollection;
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, FeatureData, NoGeometry, SpatialResolution, TimeInterval,
+    };
+
+    async fn aggregate(column: &str, function: AggregationFunction) -> Result<Option<f64>> {
+        let source = MockFeatureCollectionSource::single(
+            DataCollection::from_slices(
+                &[] as &[NoGeometry],
+                &[TimeInterval::default(); 4],
+                &[
+                    (
+                        "height",
+                        FeatureData::NullableFloat(vec![Some(1.), None, Some(4.), Some(7.)]),
+                    ),
+                    (
+                        "name",
+                        FeatureData::NullableText(vec![
+                            Some("a".to_string()),
+                            Some("b".to_string()),
+                            None,
+                            Some("c".to_string()),
+                        ]),
+                    ),
+                ],
+            )
+            .unwrap(),
+        )
+        .boxed();
+
+        let operator = ColumnAggregate {
+            params: ColumnAggregateParams {
+                column: column.to_string(),
+                function,
+            },
+            sources: source.into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await?;
+
+        let processor = operator.query_processor()?.scalar().unwrap();
+
+        processor
+            .aggregate_query(
+                AggregateQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((-180., -90.).into(), (180., 90.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::new(0),
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn mean() {
+        assert_eq!(
+            aggregate("height", AggregationFunction::Mean)
+                .await
+                .unwrap(),
+            Some(4.)
+        );
+    }
+
+    #[tokio::test]
+    async fn count_text() {
+        assert_eq!(
+            aggregate("name", AggregationFunction::Count).await.unwrap(),
+            Some(3.)
+        );
+    }
+
+    #[tokio::test]
+    async fn sum_of_text() {
+        assert!(matches!(
+            aggregate("name", AggregationFunction::Sum).await,
+            Err(error::Error::InvalidFeatureDataType)
+        ));
+    }
+}
@@ -0,0 +1,243 @@
+use crate::engine::{
+    AggregateOperator, AggregateQueryProcessor, AggregateQueryRectangle, AggregateResultDescriptor,
+    AggregateTable, ExecutionContext, InitializedAggregateOperator, InitializedVectorOperator,
+    Operator, QueryContext, SingleVectorSource, TypedAggregateQueryProcessor, VectorQueryProcessor,
+};
+use crate::error;
+use crate::util::number_statistics::NumberStatistics;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use geoengine_datatypes::collections::{FeatureCollection, FeatureCollectionInfos};
+use geoengine_datatypes::primitives::Geometry;
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+pub const COLUMN_STATISTICS_OPERATOR_NAME: &str = "ColumnStatistics";
+
+const TABLE_COLUMNS: [&str; 7] = [
+    "column",
+    "count",
+    "nullCount",
+    "min",
+    "max",
+    "mean",
+    "stddev",
+];
+
+/// An aggregate that outputs a table with basic statistics of numeric columns,
+/// one row per column.
+///
+/// Null values are counted separately and do not influence the statistics.
+pub type ColumnStatistics = Operator<ColumnStatisticsParams, SingleVectorSource>;
+
+/// The parameter spec for `ColumnStatistics`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnStatisticsParams {
+    /// The numeric columns to describe. If empty, all numeric columns are described.
+    #[serde(default)]
+    pub columns: Vec<String>,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl AggregateOperator for ColumnStatistics {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedAggregateOperator>> {
+        let source = self.sources.vector.initialize(context).await?;
+        let source_columns = &source.result_descriptor().columns;
+
+        let columns = if self.params.columns.is_empty() {
+            let mut columns: Vec<String> = source_columns
+                .iter()
+                .filter(|(_, data_type)| data_type.is_numeric())
+                .map(|(column, _)| column.clone())
+                .collect();
+            columns.sort();
+            columns
+        } else {
+            for column in &self.params.columns {
+                let data_type =
+                    source_columns
+                        .get(column)
+                        .ok_or_else(|| error::Error::ColumnDoesNotExist {
+                            column: column.clone(),
+                        })?;
+                ensure!(data_type.is_numeric(), error::InvalidFeatureDataType);
+            }
+            self.params.columns
+        };
+
+        Ok(InitializedColumnStatistics {
+            result_descriptor: AggregateResultDescriptor {},
+            vector_source: source,
+            columns,
+        }
+        .boxed())
+    }
+}
+
+/// The initialization of `ColumnStatistics`
+pub struct InitializedColumnStatistics {
+    result_descriptor: AggregateResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    columns: Vec<String>,
+}
+
+impl InitializedAggregateOperator for InitializedColumnStatistics {
+    fn query_processor(&self) -> Result<TypedAggregateQueryProcessor> {
+        let input_processor = self.vector_source.query_processor()?;
+
+        let processor = call_on_generic_vector_processor!(input_processor, features => {
+            ColumnStatisticsQueryProcessor { columns: self.columns.clone(), features }.boxed()
+        });
+
+        Ok(TypedAggregateQueryProcessor::Table(processor))
+    }
+
+    fn result_descriptor(&self) -> &AggregateResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// A query processor that calculates the `ColumnStatistics` on its input.
+pub struct ColumnStatisticsQueryProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    columns: Vec<String>,
+    features: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+}
+
+#[async_trait]
+impl<G> AggregateQueryProcessor for ColumnStatisticsQueryProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type Output = AggregateTable;
+
+    fn aggregate_type(&self) -> &'static str {
+        COLUMN_STATISTICS_OPERATOR_NAME
+    }
+
+    async fn aggregate_query<'a>(
+        &'a self,
+        query: AggregateQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<Self::Output> {
+        let statistics = self
+            .features
+            .query(query, ctx)
+            .await?
+            .try_fold(
+                vec![NumberStatistics::default(); self.columns.len()],
+                |mut statistics, features| async move {
+                    for (column, column_statistics) in self.columns.iter().zip(&mut statistics) {
+                        for value in features.data(column)?.float_options_iter() {
+                            match value {
+                                Some(value) => column_statistics.add(value),
+                                None => column_statistics.add_no_data(),
+                            }
+                        }
+                    }
+
+                    Ok(statistics)
+                },
+            )
+            .await?;
+
+        let rows = self
+            .columns
+            .iter()
+            .zip(statistics)
+            .map(|(column, statistics)| {
+                // `NaN`s, e.g., the mean of no values, become `null`s
+                vec![
+                    column.clone().into(),
+                    statistics.count().into(),
+                    statistics.no_data_count().into(),
+                    statistics.min().into(),
+                    statistics.max().into(),
+                    statistics.mean().into(),
+                    statistics.std_dev().into(),
+                ]
+            })
+            .collect();
+
+        Ok(AggregateTable {
+            columns: TABLE_COLUMNS.iter().map(ToString::to_string).collect(),
+            rows,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, VectorOperator};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::DataCollection;
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, FeatureData, NoGeometry, SpatialResolution, TimeInterval,
+    };
+
+    #[tokio::test]
+    async fn all_numeric_columns() {
+        let source = MockFeatureCollectionSource::single(
+            DataCollection::from_slices(
+                &[] as &[NoGeometry],
+                &[TimeInterval::default(); 3],
+                &[
+                    ("height", FeatureData::Float(vec![1., 2., 3.])),
+                    ("count", FeatureData::NullableInt(vec![Some(4), None, None])),
+                    (
+                        "name",
+                        FeatureData::Text(vec!["a".to_string(), "b".to_string(), "c".to_string()]),
+                    ),
+                ],
+            )
+            .unwrap(),
+        )
+        .boxed();
+
+        let operator = ColumnStatistics {
+            params: ColumnStatisticsParams::default(),
+            sources: source.into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .unwrap();
+
+        let processor = operator.query_processor().unwrap().table().unwrap();
+
+        let table = processor
+            .aggregate_query(
+                AggregateQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((-180., -90.).into(), (180., 90.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::new(0),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&table).unwrap(),
+            serde_json::json!({
+                "columns": ["column", "count", "nullCount", "min", "max", "mean", "stddev"],
+                "rows": [
+                    ["count", 1, 2, 4.0, 4.0, 4.0, null],
+                    ["height", 3, 0, 1.0, 3.0, 2.0, 0.816_496_580_927_726],
+                ],
+            })
+        );
+    }
+}
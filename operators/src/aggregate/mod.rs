@@ -0,0 +1,11 @@
+mod column_aggregate;
+mod column_statistics;
+
+pub use self::column_aggregate::{
+    ColumnAggregate, ColumnAggregateParams, ColumnAggregateQueryProcessor,
+    InitializedColumnAggregate,
+};
+pub use self::column_statistics::{
+    ColumnStatistics, ColumnStatisticsParams, ColumnStatisticsQueryProcessor,
+    InitializedColumnStatistics,
+};
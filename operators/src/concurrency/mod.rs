@@ -1,3 +1,5 @@
+mod query_scheduler;
 mod thread_pool;
 
+pub use query_scheduler::{QueryPermit, QueryScheduler, QuerySchedulerConfig};
 pub use thread_pool::{ThreadPool, ThreadPoolContext};
@@ -0,0 +1,312 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
+
+use crate::engine::QueryPriority;
+
+/// The parameters of a `QueryScheduler`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuerySchedulerConfig {
+    /// The number of queries that may run at the same time
+    pub max_concurrent_queries: usize,
+    /// The share of free slots that goes to waiting interactive queries
+    pub interactive_weight: u32,
+    /// The share of free slots that goes to waiting batch queries
+    pub batch_weight: u32,
+}
+
+impl Default for QuerySchedulerConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_queries: num_cpus::get().max(1),
+            interactive_weight: 4,
+            batch_weight: 1,
+        }
+    }
+}
+
+/// A scheduler that limits the number of concurrently running queries.
+///
+/// If all slots are taken, queries wait until a slot becomes free.
+/// Free slots are handed to the waiting queries of each `QueryPriority` by weighted round robin,
+/// s.t. interactive requests are not starved by large exports and vice versa.
+///
+/// Clones share the same slots.
+#[derive(Debug, Clone)]
+pub struct QueryScheduler {
+    state: Arc<Mutex<SchedulerState>>,
+}
+
+impl Default for QueryScheduler {
+    fn default() -> Self {
+        Self::new(QuerySchedulerConfig::default())
+    }
+}
+
+impl QueryScheduler {
+    pub fn new(config: QuerySchedulerConfig) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(SchedulerState {
+                free_slots: config.max_concurrent_queries.max(1),
+                queues: [
+                    PriorityQueue::new(config.batch_weight.max(1)),
+                    PriorityQueue::new(config.interactive_weight.max(1)),
+                ],
+            })),
+        }
+    }
+
+    /// Waits for a free slot. The slot is released when the returned `QueryPermit` is dropped.
+    pub async fn acquire(&self, priority: QueryPriority) -> QueryPermit {
+        let receiver = {
+            let mut state = self
+                .state
+                .lock()
+                .expect("scheduler state must not be poisoned");
+
+            if state.free_slots > 0 {
+                state.free_slots -= 1;
+                return QueryPermit {
+                    scheduler: Some(self.clone()),
+                };
+            }
+
+            let (sender, receiver) = oneshot::channel();
+            state.queues[queue_index(priority)]
+                .waiting
+                .push_back(sender);
+            receiver
+        };
+
+        match receiver.await {
+            Ok(permit) => permit,
+            Err(_) => unreachable!("the scheduler never drops waiting senders"),
+        }
+    }
+
+    /// The number of queries that are waiting for a slot
+    pub fn waiting_queries(&self) -> usize {
+        self.state
+            .lock()
+            .expect("scheduler state must not be poisoned")
+            .queues
+            .iter()
+            .flat_map(|queue| queue.waiting.iter())
+            .filter(|sender| !sender.is_closed())
+            .count()
+    }
+
+    /// Hands a released slot to the next waiting query or marks it as free
+    fn release(&self) {
+        let mut state = self
+            .state
+            .lock()
+            .expect("scheduler state must not be poisoned");
+
+        while let Some(sender) = state.next_waiting() {
+            let permit = QueryPermit {
+                scheduler: Some(self.clone()),
+            };
+
+            match sender.send(permit) {
+                Ok(()) => return,
+                Err(mut permit) => {
+                    // the query was cancelled while waiting, so the slot is still ours
+                    permit.scheduler = None;
+                }
+            }
+        }
+
+        state.free_slots += 1;
+    }
+}
+
+/// A slot of a `QueryScheduler` that is released on drop
+#[derive(Debug)]
+pub struct QueryPermit {
+    scheduler: Option<QueryScheduler>,
+}
+
+impl Drop for QueryPermit {
+    fn drop(&mut self) {
+        if let Some(scheduler) = self.scheduler.take() {
+            scheduler.release();
+        }
+    }
+}
+
+#[derive(Debug)]
+struct SchedulerState {
+    free_slots: usize,
+    /// one queue per `QueryPriority`
+    queues: [PriorityQueue; 2],
+}
+
+impl SchedulerState {
+    /// Selects the next waiting query using smooth weighted round robin
+    fn next_waiting(&mut self) -> Option<oneshot::Sender<QueryPermit>> {
+        let mut total_weight = 0;
+        for queue in self
+            .queues
+            .iter_mut()
+            .filter(|queue| !queue.waiting.is_empty())
+        {
+            queue.current_weight += i64::from(queue.weight);
+            total_weight += i64::from(queue.weight);
+        }
+
+        // on ties, the queue of the higher priority wins
+        let queue = self
+            .queues
+            .iter_mut()
+            .filter(|queue| !queue.waiting.is_empty())
+            .max_by_key(|queue| queue.current_weight)?;
+
+        queue.current_weight -= total_weight;
+        queue.waiting.pop_front()
+    }
+}
+
+#[derive(Debug)]
+struct PriorityQueue {
+    weight: u32,
+    current_weight: i64,
+    waiting: VecDeque<oneshot::Sender<QueryPermit>>,
+}
+
+impl PriorityQueue {
+    fn new(weight: u32) -> Self {
+        Self {
+            weight,
+            current_weight: 0,
+            waiting: VecDeque::new(),
+        }
+    }
+}
+
+fn queue_index(priority: QueryPriority) -> usize {
+    match priority {
+        QueryPriority::Batch => 0,
+        QueryPriority::Interactive => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn limits_concurrency() {
+        let scheduler = QueryScheduler::new(QuerySchedulerConfig {
+            max_concurrent_queries: 1,
+            interactive_weight: 1,
+            batch_weight: 1,
+        });
+
+        let permit = scheduler.acquire(QueryPriority::Batch).await;
+
+        let waiting = tokio::spawn({
+            let scheduler = scheduler.clone();
+            async move { scheduler.acquire(QueryPriority::Interactive).await }
+        });
+
+        tokio::task::yield_now().await;
+        while scheduler.waiting_queries() == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        drop(permit);
+
+        let _permit = waiting.await.unwrap();
+        assert_eq!(scheduler.waiting_queries(), 0);
+    }
+
+    #[tokio::test]
+    async fn weighted_order() {
+        let scheduler = QueryScheduler::new(QuerySchedulerConfig {
+            max_concurrent_queries: 1,
+            interactive_weight: 2,
+            batch_weight: 1,
+        });
+
+        let permit = scheduler.acquire(QueryPriority::Batch).await;
+
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        let mut handles = Vec::new();
+        for priority in [
+            QueryPriority::Batch,
+            QueryPriority::Batch,
+            QueryPriority::Interactive,
+            QueryPriority::Interactive,
+            QueryPriority::Interactive,
+            QueryPriority::Interactive,
+        ] {
+            let scheduler = scheduler.clone();
+            let sender = sender.clone();
+            handles.push(tokio::spawn(async move {
+                let _permit = scheduler.acquire(priority).await;
+                sender.send(priority).unwrap();
+            }));
+
+            let expected_waiting = handles.len();
+            while scheduler.waiting_queries() < expected_waiting {
+                tokio::task::yield_now().await;
+            }
+        }
+
+        drop(permit);
+
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        drop(sender);
+
+        let mut order = Vec::new();
+        while let Some(priority) = receiver.recv().await {
+            order.push(priority);
+        }
+
+        assert_eq!(
+            order,
+            vec![
+                QueryPriority::Interactive,
+                QueryPriority::Batch,
+                QueryPriority::Interactive,
+                QueryPriority::Interactive,
+                QueryPriority::Batch,
+                QueryPriority::Interactive,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn cancelled_waiters() {
+        let scheduler = QueryScheduler::new(QuerySchedulerConfig {
+            max_concurrent_queries: 1,
+            interactive_weight: 1,
+            batch_weight: 1,
+        });
+
+        let permit = scheduler.acquire(QueryPriority::Batch).await;
+
+        let waiting = tokio::spawn({
+            let scheduler = scheduler.clone();
+            async move { scheduler.acquire(QueryPriority::Interactive).await }
+        });
+        while scheduler.waiting_queries() == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        waiting.abort();
+        assert!(waiting.await.is_err());
+
+        drop(permit);
+
+        // the slot must be free again
+        let _permit = scheduler.acquire(QueryPriority::Batch).await;
+    }
+}
@@ -1,6 +1,6 @@
 use super::{
-    InitializedPlotOperator, InitializedRasterOperator, InitializedVectorOperator, PlotOperator,
-    RasterOperator, VectorOperator,
+    AggregateOperator, InitializedPlotOperator, InitializedRasterOperator,
+    InitializedVectorOperator, PlotOperator, RasterOperator, VectorOperator,
 };
 
 /// Helper trait for making boxed `RasterOperator`s cloneable
@@ -18,6 +18,11 @@ pub trait CloneablePlotOperator {
     fn clone_boxed_plot(&self) -> Box<dyn PlotOperator>;
 }
 
+/// Helper trait for making boxed `AggregateOperator`s cloneable
+pub trait CloneableAggregateOperator {
+    fn clone_boxed_aggregate(&self) -> Box<dyn AggregateOperator>;
+}
+
 impl<T> CloneableRasterOperator for T
 where
     T: 'static + RasterOperator + Clone,
@@ -45,6 +50,15 @@ where
     }
 }
 
+impl<T> CloneableAggregateOperator for T
+where
+    T: 'static + AggregateOperator + Clone,
+{
+    fn clone_boxed_aggregate(&self) -> Box<dyn AggregateOperator> {
+        Box::new(self.clone())
+    }
+}
+
 impl Clone for Box<dyn RasterOperator> {
     fn clone(&self) -> Box<dyn RasterOperator> {
         self.clone_boxed_raster()
@@ -63,6 +77,12 @@ impl Clone for Box<dyn PlotOperator> {
     }
 }
 
+impl Clone for Box<dyn AggregateOperator> {
+    fn clone(&self) -> Box<dyn AggregateOperator> {
+        self.clone_boxed_aggregate()
+    }
+}
+
 /// Helper trait for making boxed `InitializedRasterOperator`s cloneable
 pub trait CloneableInitializedRasterOperator {
     fn clone_boxed_raster(&self) -> Box<dyn InitializedRasterOperator>;
@@ -2,10 +2,12 @@ use crate::concurrency::{ThreadPool, ThreadPoolContext};
 use crate::engine::{RasterResultDescriptor, ResultDescriptor, VectorResultDescriptor};
 use crate::error::Error;
 use crate::mock::MockDatasetDataSourceLoadingInfo;
-use crate::source::{GdalLoadingInfo, OgrSourceDataset};
+use crate::source::{FilePathPolicy, GdalLoadingInfo, OgrSourceDataset};
 use crate::util::Result;
 use async_trait::async_trait;
+use chrono::Utc;
 use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::primitives::TimeInstance;
 use geoengine_datatypes::raster::GridShape;
 use geoengine_datatypes::raster::TilingSpecification;
 use serde::{Deserialize, Serialize};
@@ -14,7 +16,7 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 use std::marker::PhantomData;
 
-use super::{RasterQueryRectangle, VectorQueryRectangle};
+use super::{MockQueryContext, QueryClock, RasterQueryRectangle, VectorQueryRectangle};
 
 /// A context that provides certain utility access during operator initialization
 pub trait ExecutionContext: Send
@@ -25,6 +27,13 @@ pub trait ExecutionContext: Send
 {
     fn thread_pool(&self) -> ThreadPoolContext;
     fn tiling_specification(&self) -> TilingSpecification;
+    /// The files that sources may read
+    fn file_path_policy(&self) -> FilePathPolicy;
+
+    /// The current time, which is the system time by default
+    fn now(&self) -> TimeInstance {
+        Utc::now().into()
+    }
 }
 
 #[async_trait]
@@ -59,6 +68,9 @@ pub struct MockExecutionContext {
     pub thread_pool: ThreadPool,
     pub meta_data: HashMap<DatasetId, Box<dyn Any + Send + Sync>>,
     pub tiling_specification: TilingSpecification,
+    pub file_path_policy: FilePathPolicy,
+    /// Replaces the system time if set
+    pub clock: Option<QueryClock>,
 }
 
 impl Default for MockExecutionContext {
@@ -72,6 +84,8 @@ impl Default for MockExecutionContext {
                     shape_array: [600, 600],
                 },
             },
+            file_path_policy: FilePathPolicy::default(),
+            clock: None,
         }
     }
 }
@@ -89,6 +103,16 @@ impl MockExecutionContext {
         self.meta_data
             .insert(dataset, Box::new(meta_data) as Box<dyn Any + Send + Sync>);
     }
+
+    /// Creates a `MockQueryContext` that shares the clock of this context
+    pub fn mock_query_context(&self, chunk_byte_size: usize) -> MockQueryContext {
+        let query_context = MockQueryContext::new(chunk_byte_size);
+
+        match &self.clock {
+            Some(clock) => query_context.with_clock(clock.clone()),
+            None => query_context,
+        }
+    }
 }
 
 impl ExecutionContext for MockExecutionContext {
@@ -99,6 +123,16 @@ impl ExecutionContext for MockExecutionContext {
     fn tiling_specification(&self) -> TilingSpecification {
         self.tiling_specification
     }
+
+    fn file_path_policy(&self) -> FilePathPolicy {
+        self.file_path_policy.clone()
+    }
+
+    fn now(&self) -> TimeInstance {
+        self.clock
+            .as_ref()
+            .map_or_else(|| Utc::now().into(), QueryClock::now)
+    }
 }
 
 #[async_trait]
@@ -120,6 +154,62 @@ where
     }
 }
 
+/// An `ExecutionContext` that delegates to another one but uses a different `TilingSpecification`,
+/// e.g., one that is aligned with the pixel grid of a client request.
+pub struct TilingSpecificationOverride<'c, C>
+where
+    C: ExecutionContext + ?Sized,
+{
+    context: &'c C,
+    tiling_specification: TilingSpecification,
+}
+
+impl<'c, C> TilingSpecificationOverride<'c, C>
+where
+    C: ExecutionContext + ?Sized,
+{
+    pub fn new(context: &'c C, tiling_specification: TilingSpecification) -> Self {
+        Self {
+            context,
+            tiling_specification,
+        }
+    }
+}
+
+impl<C> ExecutionContext for TilingSpecificationOverride<'_, C>
+where
+    C: ExecutionContext + ?Sized,
+{
+    fn thread_pool(&self) -> ThreadPoolContext {
+        self.context.thread_pool()
+    }
+
+    fn tiling_specification(&self) -> TilingSpecification {
+        self.tiling_specification
+    }
+
+    fn file_path_policy(&self) -> FilePathPolicy {
+        self.context.file_path_policy()
+    }
+
+    fn now(&self) -> TimeInstance {
+        self.context.now()
+    }
+}
+
+#[async_trait]
+impl<L, R, Q, C> MetaDataProvider<L, R, Q> for TilingSpecificationOverride<'_, C>
+where
+    L: 'static,
+    R: 'static + ResultDescriptor,
+    Q: 'static,
+    C: ExecutionContext + MetaDataProvider<L, R, Q> + ?Sized,
+{
+    async fn meta_data(&self, dataset: &DatasetId) -> Result<Box<dyn MetaData<L, R, Q>>> {
+        MetaDataProvider::<L, R, Q>::meta_data(self.context, dataset).await
+    }
+}
+
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StaticMetaData<L, R, Q>
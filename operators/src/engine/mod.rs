@@ -1,35 +1,49 @@
 pub use clonable_operator::{
-    CloneableInitializedRasterOperator, CloneableInitializedVectorOperator, CloneablePlotOperator,
-    CloneableRasterOperator, CloneableVectorOperator,
+    CloneableAggregateOperator, CloneableInitializedRasterOperator,
+    CloneableInitializedVectorOperator, CloneablePlotOperator, CloneableRasterOperator,
+    CloneableVectorOperator,
 };
 pub use execution_context::{
     ExecutionContext, MetaData, MetaDataProvider, MockExecutionContext, StaticMetaData,
+    TilingSpecificationOverride,
 };
 pub use operator::{
-    InitializedPlotOperator, InitializedRasterOperator, InitializedVectorOperator,
-    OperatorDatasets, PlotOperator, RasterOperator, TypedOperator, VectorOperator,
+    AggregateOperator, InitializedAggregateOperator, InitializedPlotOperator,
+    InitializedRasterOperator, InitializedVectorOperator, OperatorDatasets, PlotOperator,
+    RasterOperator, TypedOperator, VectorOperator,
+};
+pub use operator_alias::{
+    resolve_operator_aliases, DeprecationWarning, OperatorAlias, OPERATOR_ALIASES,
 };
 pub use operator_impl::{
-    MultipleRasterSources, MultipleVectorSources, Operator, SingleRasterOrVectorSource,
-    SingleRasterSource, SingleVectorMultipleRasterSources, SingleVectorSource, SourceOperator,
+    MultipleRasterOrSingleVectorSource, MultipleRasterSources, MultipleVectorSources, Operator,
+    SingleRasterOrVectorSource, SingleRasterSource, SingleVectorMultipleRasterSources,
+    SingleVectorSource, SourceOperator,
 };
+pub use operator_registry::{operator_specs, OperatorOutputType, OperatorSpec};
+pub use operator_validation::{validate_operator, SchemaViolation};
 pub use query::{
-    MockQueryContext, PlotQueryRectangle, QueryContext, QueryRectangle, RasterQueryRectangle,
-    VectorQueryRectangle,
+    AggregateQueryRectangle, CachePolicyHint, MockQueryContext, PlotQueryRectangle,
+    QueryAbortToken, QueryClock, QueryContext, QueryContextExtensions, QueryPriority,
+    QueryRectangle, QueryRegion, RasterQueryRectangle, VectorQueryRectangle,
 };
 pub use query_processor::{
-    PlotQueryProcessor, QueryProcessor, RasterQueryProcessor, TypedPlotQueryProcessor,
+    AggregateQueryProcessor, AggregateTable, PlotQueryProcessor, QueryProcessor,
+    RasterQueryProcessor, TypedAggregateQueryProcessor, TypedPlotQueryProcessor,
     TypedRasterQueryProcessor, TypedVectorQueryProcessor, VectorQueryProcessor,
 };
 pub use result_descriptor::{
-    PlotResultDescriptor, RasterResultDescriptor, ResultDescriptor, TypedResultDescriptor,
-    VectorResultDescriptor,
+    AggregateResultDescriptor, PlotResultDescriptor, RasterBandDescriptor, RasterResultDescriptor,
+    ResultDescriptor, TypedResultDescriptor, VectorResultDescriptor,
 };
 
 mod clonable_operator;
 mod execution_context;
 mod operator;
+mod operator_alias;
 mod operator_impl;
+mod operator_registry;
+mod operator_validation;
 mod query;
 #[macro_use]
 mod query_processor;
@@ -7,8 +7,10 @@ use geoengine_datatypes::dataset::DatasetId;
 
 use super::{
     query_processor::{TypedRasterQueryProcessor, TypedVectorQueryProcessor},
-    CloneablePlotOperator, CloneableRasterOperator, CloneableVectorOperator, ExecutionContext,
-    PlotResultDescriptor, RasterResultDescriptor, TypedPlotQueryProcessor, VectorResultDescriptor,
+    AggregateResultDescriptor, CloneableAggregateOperator, CloneablePlotOperator,
+    CloneableRasterOperator, CloneableVectorOperator, ExecutionContext, PlotResultDescriptor,
+    RasterResultDescriptor, TypedAggregateQueryProcessor, TypedPlotQueryProcessor,
+    VectorResultDescriptor,
 };
 
 pub trait OperatorDatasets {
@@ -82,6 +84,26 @@ pub trait PlotOperator:
     }
 }
 
+/// Common methods for `AggregateOperator`s, which output a single number or a small table
+#[typetag::serde(tag = "type")]
+#[async_trait]
+pub trait AggregateOperator:
+    CloneableAggregateOperator + OperatorDatasets + Send + Sync + std::fmt::Debug
+{
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedAggregateOperator>>;
+
+    /// Wrap a box around an `AggregateOperator`
+    fn boxed(self) -> Box<dyn AggregateOperator>
+    where
+        Self: Sized + 'static,
+    {
+        Box::new(self)
+    }
+}
+
 pub trait InitializedRasterOperator: Send + Sync {
     /// Get the result descriptor of the `Operator`
     fn result_descriptor(&self) -> &RasterResultDescriptor;
@@ -130,6 +152,22 @@ pub trait InitializedPlotOperator: Send + Sync {
     }
 }
 
+pub trait InitializedAggregateOperator: Send + Sync {
+    /// Get the result descriptor of the `Operator`
+    fn result_descriptor(&self) -> &AggregateResultDescriptor;
+
+    /// Instantiate a `TypedAggregateQueryProcessor` from an `AggregateOperator`
+    fn query_processor(&self) -> Result<TypedAggregateQueryProcessor>;
+
+    /// Wrap a box around an `AggregateOperator`
+    fn boxed(self) -> Box<dyn InitializedAggregateOperator>
+    where
+        Self: Sized + 'static,
+    {
+        Box::new(self)
+    }
+}
+
 impl InitializedRasterOperator for Box<dyn InitializedRasterOperator> {
     fn result_descriptor(&self) -> &RasterResultDescriptor {
         self.as_ref().result_descriptor()
@@ -160,6 +198,16 @@ impl InitializedPlotOperator for Box<dyn InitializedPlotOperator> {
     }
 }
 
+impl InitializedAggregateOperator for Box<dyn InitializedAggregateOperator> {
+    fn result_descriptor(&self) -> &AggregateResultDescriptor {
+        self.as_ref().result_descriptor()
+    }
+
+    fn query_processor(&self) -> Result<TypedAggregateQueryProcessor> {
+        self.as_ref().query_processor()
+    }
+}
+
 /// An enum to differentiate between `Operator` variants
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "type", content = "operator")]
@@ -167,6 +215,7 @@ pub enum TypedOperator {
     Vector(Box<dyn VectorOperator>),
     Raster(Box<dyn RasterOperator>),
     Plot(Box<dyn PlotOperator>),
+    Aggregate(Box<dyn AggregateOperator>),
 }
 
 impl TypedOperator {
@@ -190,6 +239,13 @@ impl TypedOperator {
         }
         Err(error::Error::InvalidOperatorType)
     }
+
+    pub fn get_aggregate(self) -> Result<Box<dyn AggregateOperator>> {
+        if let TypedOperator::Aggregate(o) = self {
+            return Ok(o);
+        }
+        Err(error::Error::InvalidOperatorType)
+    }
 }
 
 impl From<Box<dyn VectorOperator>> for TypedOperator {
@@ -210,11 +266,18 @@ impl From<Box<dyn PlotOperator>> for TypedOperator {
     }
 }
 
+impl From<Box<dyn AggregateOperator>> for TypedOperator {
+    fn from(operator: Box<dyn AggregateOperator>) -> Self {
+        Self::Aggregate(operator)
+    }
+}
+
 /// An enum to differentiate between `InitializedOperator` variants
 pub enum TypedInitializedOperator {
     Vector(Box<dyn InitializedVectorOperator>),
     Raster(Box<dyn InitializedRasterOperator>),
     Plot(Box<dyn InitializedPlotOperator>),
+    Aggregate(Box<dyn InitializedAggregateOperator>),
 }
 
 impl From<Box<dyn InitializedVectorOperator>> for TypedInitializedOperator {
@@ -235,6 +298,12 @@ impl From<Box<dyn InitializedPlotOperator>> for TypedInitializedOperator {
     }
 }
 
+impl From<Box<dyn InitializedAggregateOperator>> for TypedInitializedOperator {
+    fn from(operator: Box<dyn InitializedAggregateOperator>) -> Self {
+        TypedInitializedOperator::Aggregate(operator)
+    }
+}
+
 #[macro_export]
 macro_rules! call_on_typed_operator {
     ($typed_operator:expr, $operator_var:ident => $function_call:expr) => {
@@ -242,6 +311,7 @@ macro_rules! call_on_typed_operator {
             $crate::engine::TypedOperator::Vector($operator_var) => $function_call,
             $crate::engine::TypedOperator::Raster($operator_var) => $function_call,
             $crate::engine::TypedOperator::Plot($operator_var) => $function_call,
+            $crate::engine::TypedOperator::Aggregate($operator_var) => $function_call,
         }
     };
 }
@@ -252,6 +322,7 @@ impl OperatorDatasets for TypedOperator {
             TypedOperator::Vector(v) => v.datasets_collect(datasets),
             TypedOperator::Raster(r) => r.datasets_collect(datasets),
             TypedOperator::Plot(p) => p.datasets_collect(datasets),
+            TypedOperator::Aggregate(a) => a.datasets_collect(datasets),
         }
     }
 }
@@ -0,0 +1,187 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A deprecated operator name that is still accepted in serialized operators
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperatorAlias {
+    pub deprecated: &'static str,
+    pub successor: &'static str,
+}
+
+/// The deprecated names of all operators.
+///
+/// When an operator is renamed, its previous name must be added here so that stored workflows keep working.
+/// This only applies if the parameters and sources are unchanged. Otherwise, the workflows need a migration.
+pub const OPERATOR_ALIASES: &[OperatorAlias] = &[];
+
+/// The use of a deprecated operator name in a serialized operator
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeprecationWarning {
+    /// The location of the operator in the operator tree, e.g., `/sources/raster`
+    pub path: String,
+    pub deprecated: String,
+    pub successor: String,
+}
+
+impl fmt::Display for DeprecationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The operator `{}` at `{}` is deprecated, use `{}` instead",
+            self.deprecated, self.path, self.successor
+        )
+    }
+}
+
+/// Replaces the deprecated operator names of a serialized operator and its sources by their successors.
+/// It returns a warning for each replaced name.
+pub fn resolve_operator_aliases(operator: &mut Value) -> Vec<DeprecationWarning> {
+    let mut warnings = Vec::new();
+    resolve_aliases(operator, OPERATOR_ALIASES, "", &mut warnings);
+    warnings
+}
+
+fn resolve_aliases(
+    operator: &mut Value,
+    aliases: &[OperatorAlias],
+    path: &str,
+    warnings: &mut Vec<DeprecationWarning>,
+) {
+    let operator = match operator.as_object_mut() {
+        Some(operator) => operator,
+        None => return,
+    };
+
+    let operator_type = operator.get("type").and_then(Value::as_str);
+    if let Some(alias) = aliases
+        .iter()
+        .find(|alias| Some(alias.deprecated) == operator_type)
+    {
+        operator.insert("type".to_owned(), Value::String(alias.successor.to_owned()));
+
+        warnings.push(DeprecationWarning {
+            path: path.to_owned(),
+            deprecated: alias.deprecated.to_owned(),
+            successor: alias.successor.to_owned(),
+        });
+    }
+
+    let sources = match operator.get_mut("sources").and_then(Value::as_object_mut) {
+        Some(sources) => sources,
+        None => return,
+    };
+
+    for (name, source) in sources.iter_mut() {
+        let source_path = format!("{}/sources/{}", path, name);
+
+        if let Value::Array(operators) = source {
+            for (index, operator) in operators.iter_mut().enumerate() {
+                resolve_aliases(
+                    operator,
+                    aliases,
+                    &format!("{}/{}", source_path, index),
+                    warnings,
+                );
+            }
+        } else {
+            resolve_aliases(source, aliases, &source_path, warnings);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::VectorOperator;
+
+    const TEST_ALIASES: &[OperatorAlias] = &[
+        OperatorAlias {
+            deprecated: "MockPoints",
+            successor: "MockPointSource",
+        },
+        OperatorAlias {
+            deprecated: "PointInPolygon",
+            successor: "PointInPolygonFilter",
+        },
+    ];
+
+    #[test]
+    fn it_resolves_aliases() {
+        let mut operator = serde_json::json!({
+            "type": "PointInPolygon",
+            "params": {},
+            "sources": {
+                "points": {
+                    "type": "MockPoints",
+                    "params": {
+                        "points": [{"x": 0.0, "y": 0.0}]
+                    }
+                },
+                "polygons": {
+                    "type": "MockFeatureCollectionSourceMultiPolygon",
+                    "params": {
+                        "collections": []
+                    }
+                }
+            }
+        });
+
+        assert!(serde_json::from_value::<Box<dyn VectorOperator>>(operator.clone()).is_err());
+
+        let mut warnings = Vec::new();
+        resolve_aliases(&mut operator, TEST_ALIASES, "", &mut warnings);
+
+        assert_eq!(
+            warnings,
+            vec![
+                DeprecationWarning {
+                    path: "".to_owned(),
+                    deprecated: "PointInPolygon".to_owned(),
+                    successor: "PointInPolygonFilter".to_owned(),
+                },
+                DeprecationWarning {
+                    path: "/sources/points".to_owned(),
+                    deprecated: "MockPoints".to_owned(),
+                    successor: "MockPointSource".to_owned(),
+                },
+            ]
+        );
+        assert_eq!(
+            warnings[1].to_string(),
+            "The operator `MockPoints` at `/sources/points` is deprecated, use `MockPointSource` instead"
+        );
+
+        assert!(serde_json::from_value::<Box<dyn VectorOperator>>(operator).is_ok());
+    }
+
+    #[test]
+    fn it_resolves_aliases_of_multiple_sources() {
+        let mut operator = serde_json::json!({
+            "type": "RasterStacker",
+            "params": {},
+            "sources": {
+                "rasters": [{
+                    "type": "MockRasterSource"
+                }, {
+                    "type": "MockPoints"
+                }]
+            }
+        });
+
+        let mut warnings = Vec::new();
+        resolve_aliases(&mut operator, TEST_ALIASES, "", &mut warnings);
+
+        assert_eq!(
+            warnings,
+            vec![DeprecationWarning {
+                path: "/sources/rasters/1".to_owned(),
+                deprecated: "MockPoints".to_owned(),
+                successor: "MockPointSource".to_owned(),
+            }]
+        );
+        assert_eq!(operator["sources"]["rasters"][1]["type"], "MockPointSource");
+    }
+}
@@ -1,7 +1,7 @@
 use geoengine_datatypes::dataset::DatasetId;
 use serde::{Deserialize, Serialize};
 
-use crate::util::input::RasterOrVectorOperator;
+use crate::util::input::{MultiRasterOrVectorOperator, RasterOrVectorOperator};
 
 use super::{OperatorDatasets, RasterOperator, VectorOperator};
 
@@ -36,6 +36,12 @@ pub struct SingleRasterOrVectorSource {
     pub source: RasterOrVectorOperator,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultipleRasterOrSingleVectorSource {
+    pub source: MultiRasterOrVectorOperator,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MultipleRasterSources {
@@ -95,6 +101,22 @@ impl From<Box<dyn RasterOperator>> for SingleRasterOrVectorSource {
     }
 }
 
+impl From<Box<dyn VectorOperator>> for MultipleRasterOrSingleVectorSource {
+    fn from(vector: Box<dyn VectorOperator>) -> Self {
+        Self {
+            source: MultiRasterOrVectorOperator::Vector(vector),
+        }
+    }
+}
+
+impl From<Vec<Box<dyn RasterOperator>>> for MultipleRasterOrSingleVectorSource {
+    fn from(rasters: Vec<Box<dyn RasterOperator>>) -> Self {
+        Self {
+            source: MultiRasterOrVectorOperator::Raster(rasters),
+        }
+    }
+}
+
 impl<Params, Sources> OperatorDatasets for Operator<Params, Sources>
 where
     Sources: OperatorDatasets,
@@ -119,6 +141,12 @@ impl OperatorDatasets for SingleRasterOrVectorSource {
     }
 }
 
+impl OperatorDatasets for MultipleRasterOrSingleVectorSource {
+    fn datasets_collect(&self, datasets: &mut Vec<DatasetId>) {
+        self.source.datasets_collect(datasets)
+    }
+}
+
 impl OperatorDatasets for SingleVectorSource {
     fn datasets_collect(&self, datasets: &mut Vec<DatasetId>) {
         self.vector.datasets_collect(datasets)
@@ -0,0 +1,211 @@
+use schemars::gen::SchemaGenerator;
+use schemars::schema::RootSchema;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::aggregate::{ColumnAggregateParams, ColumnStatisticsParams};
+use crate::plot::{
+    BoxPlotParams, FeatureAttributeValuesOverTimeParams, HistogramParams,
+    MeanRasterPixelValuesOverTimeParams, PieChartParams, ScatterPlotParams, StatisticsParams,
+    TemporalCoverageParams,
+};
+use crate::processing::{
+    BandSelectionParams, ChangeDetectionParams, ColumnRangeFilterParams, ExpressionParams,
+    FeatureAggregationParams, GeometryConversionParams, IdwInterpolationParams,
+    NeighborhoodAggregateParams, OutlierDetectionParams, PointInPolygonFilterParams,
+    RadianceParams, RasterClipParams, RasterLookupParams, RasterMosaicParams,
+    RasterOutlierDetectionParams, RasterSamplingParams, RasterStackerParams,
+    RasterVectorJoinParams, RepresentativePointsParams, ReprojectionParams, TemporalAnomalyParams,
+    TemporalRasterAggregationParameters, TemporalSmoothingParams, TextProcessingParams,
+    TimeDerivationParams, TimeSeriesPivotParams, TimeSeriesResampleParams, TimeSlicingParams,
+    TimeSynchronizationParams, VectorGeneralizationParams, VectorJoinParams,
+    VisualPointClusteringParams,
+};
+use crate::source::{
+    CheckerboardSourceParams, CsvSourceParameters, GdalSourceParameters, GradientSourceParams,
+    OgrSourceParameters, PixelAreaSourceParams, RandomPointsSourceParams,
+};
+
+/// The kind of result an operator produces
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OperatorOutputType {
+    Raster,
+    Vector,
+    Plot,
+    Aggregate,
+}
+
+/// The description of a registered operator, e.g., for generating forms in workflow editors
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorSpec {
+    /// The `type` of the serialized operator
+    pub name: String,
+    pub output_type: OperatorOutputType,
+    /// The JSON schema of the operator's `params`
+    pub params: RootSchema,
+}
+
+impl OperatorSpec {
+    fn new<Params: JsonSchema>(name: &str, output_type: OperatorOutputType) -> Self {
+        Self {
+            name: name.to_owned(),
+            output_type,
+            params: SchemaGenerator::default().into_root_schema_for::<Params>(),
+        }
+    }
+}
+
+/// Lists all operators that can be used in workflows, grouped by their output type.
+///
+/// Operators that are registered for more than one output type, e.g., the `Reprojection`, are listed once per type.
+/// New operators must be added here, since the registry of serializable operators cannot be enumerated.
+pub fn operator_specs() -> Vec<OperatorSpec> {
+    use OperatorOutputType::{Aggregate, Plot, Raster, Vector};
+
+    #[allow(unused_mut)]
+    let mut specs = vec![
+        OperatorSpec::new::<BandSelectionParams>("BandSelection", Raster),
+        OperatorSpec::new::<ChangeDetectionParams>("ChangeDetection", Raster),
+        OperatorSpec::new::<CheckerboardSourceParams>("CheckerboardSource", Raster),
+        OperatorSpec::new::<ExpressionParams>("Expression", Raster),
+        OperatorSpec::new::<GdalSourceParameters>("GdalSource", Raster),
+        OperatorSpec::new::<GradientSourceParams>("GradientSource", Raster),
+        OperatorSpec::new::<IdwInterpolationParams>("IdwInterpolation", Raster),
+        OperatorSpec::new::<NeighborhoodAggregateParams>("NeighborhoodAggregate", Raster),
+        OperatorSpec::new::<PixelAreaSourceParams>("PixelAreaSource", Raster),
+        OperatorSpec::new::<RadianceParams>("Radiance", Raster),
+        OperatorSpec::new::<RasterClipParams>("RasterClip", Raster),
+        OperatorSpec::new::<RasterLookupParams>("RasterLookup", Raster),
+        OperatorSpec::new::<RasterMosaicParams>("RasterMosaic", Raster),
+        OperatorSpec::new::<RasterOutlierDetectionParams>("RasterOutlierDetection", Raster),
+        OperatorSpec::new::<RasterStackerParams>("RasterStacker", Raster),
+        OperatorSpec::new::<ReprojectionParams>("Reprojection", Raster),
+        OperatorSpec::new::<TemporalAnomalyParams>("TemporalAnomaly", Raster),
+        OperatorSpec::new::<TemporalRasterAggregationParameters>(
+            "TemporalRasterAggregation",
+            Raster,
+        ),
+        OperatorSpec::new::<TemporalSmoothingParams>("TemporalSmoothing", Raster),
+        OperatorSpec::new::<TimeSlicingParams>("TimeSlicing", Raster),
+        OperatorSpec::new::<TimeSynchronizationParams>("TimeSynchronization", Raster),
+        OperatorSpec::new::<ColumnRangeFilterParams>("ColumnRangeFilter", Vector),
+        OperatorSpec::new::<CsvSourceParameters>("CsvSource", Vector),
+        OperatorSpec::new::<FeatureAggregationParams>("FeatureAggregation", Vector),
+        OperatorSpec::new::<GeometryConversionParams>("GeometryConversion", Vector),
+        OperatorSpec::new::<OgrSourceParameters>("OgrSource", Vector),
+        OperatorSpec::new::<OutlierDetectionParams>("OutlierDetection", Vector),
+        OperatorSpec::new::<PointInPolygonFilterParams>("PointInPolygonFilter", Vector),
+        OperatorSpec::new::<RandomPointsSourceParams>("RandomPointsSource", Vector),
+        OperatorSpec::new::<RasterSamplingParams>("RasterSampling", Vector),
+        OperatorSpec::new::<RasterVectorJoinParams>("RasterVectorJoin", Vector),
+        OperatorSpec::new::<RepresentativePointsParams>("RepresentativePoints", Vector),
+        OperatorSpec::new::<ReprojectionParams>("Reprojection", Vector),
+        OperatorSpec::new::<TextProcessingParams>("TextProcessing", Vector),
+        OperatorSpec::new::<TimeDerivationParams>("TimeDerivation", Vector),
+        OperatorSpec::new::<TimeSeriesPivotParams>("TimeSeriesPivot", Vector),
+        OperatorSpec::new::<TimeSeriesResampleParams>("TimeSeriesResample", Vector),
+        OperatorSpec::new::<VectorGeneralizationParams>("VectorGeneralization", Vector),
+        OperatorSpec::new::<VectorJoinParams>("VectorJoin", Vector),
+        OperatorSpec::new::<VisualPointClusteringParams>("VisualPointClustering", Vector),
+        OperatorSpec::new::<BoxPlotParams>("BoxPlot", Plot),
+        OperatorSpec::new::<FeatureAttributeValuesOverTimeParams>(
+            "FeatureAttributeValuesOverTime",
+            Plot,
+        ),
+        OperatorSpec::new::<HistogramParams>("Histogram", Plot),
+        OperatorSpec::new::<MeanRasterPixelValuesOverTimeParams>(
+            "MeanRasterPixelValuesOverTime",
+            Plot,
+        ),
+        OperatorSpec::new::<PieChartParams>("PieChart", Plot),
+        OperatorSpec::new::<ScatterPlotParams>("ScatterPlot", Plot),
+        OperatorSpec::new::<StatisticsParams>("Statistics", Plot),
+        OperatorSpec::new::<TemporalCoverageParams>("TemporalCoverage", Plot),
+        OperatorSpec::new::<ColumnAggregateParams>("ColumnAggregate", Aggregate),
+        OperatorSpec::new::<ColumnStatisticsParams>("ColumnStatistics", Aggregate),
+    ];
+
+    #[cfg(feature = "pro")]
+    specs.push(
+        OperatorSpec::new::<crate::pro::ml::ClassifierTrainingParams>("ClassifierTraining", Plot),
+    );
+
+    specs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{AggregateOperator, PlotOperator, RasterOperator, VectorOperator};
+
+    #[test]
+    fn it_lists_all_operators() {
+        let specs = operator_specs();
+
+        assert_eq!(specs.len(), if cfg!(feature = "pro") { 51 } else { 50 });
+
+        // every spec must refer to an operator that is registered for its output type
+        for spec in &specs {
+            let operator = serde_json::json!({
+                "type": spec.name,
+                "params": {},
+            });
+
+            let error = match spec.output_type {
+                OperatorOutputType::Raster => {
+                    serde_json::from_value::<Box<dyn RasterOperator>>(operator).err()
+                }
+                OperatorOutputType::Vector => {
+                    serde_json::from_value::<Box<dyn VectorOperator>>(operator).err()
+                }
+                OperatorOutputType::Plot => {
+                    serde_json::from_value::<Box<dyn PlotOperator>>(operator).err()
+                }
+                OperatorOutputType::Aggregate => {
+                    serde_json::from_value::<Box<dyn AggregateOperator>>(operator).err()
+                }
+            };
+
+            if let Some(error) = error {
+                assert!(
+                    !error.to_string().starts_with("unknown variant"),
+                    "{}: {}",
+                    spec.name,
+                    error
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn it_derives_parameter_schemas() {
+        let spec = operator_specs()
+            .into_iter()
+            .find(|spec| spec.name == "Reprojection")
+            .unwrap();
+
+        assert_eq!(spec.output_type, OperatorOutputType::Raster);
+
+        let schema = serde_json::to_value(&spec.params).unwrap();
+
+        assert_eq!(
+            schema["required"],
+            serde_json::json!(["targetSpatialReference"])
+        );
+        assert_eq!(
+            schema["properties"]["targetSpatialReference"],
+            serde_json::json!({
+                "$ref": "#/definitions/SpatialReference"
+            })
+        );
+        assert_eq!(
+            schema["definitions"]["SpatialReference"],
+            serde_json::json!({
+                "type": "string",
+                "pattern": "^(EPSG|SR-ORG|IAU2000|ESRI):[0-9]+$"
+            })
+        );
+        assert_eq!(schema["properties"]["resampling"]["default"], "nearest");
+    }
+}
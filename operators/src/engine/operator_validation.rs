@@ -0,0 +1,545 @@
+use std::fmt;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use schemars::schema::{InstanceType, Schema, SchemaObject, SingleOrVec};
+use schemars::Map;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{operator_specs, OperatorSpec};
+
+lazy_static! {
+    static ref OPERATOR_SPECS: Vec<OperatorSpec> = operator_specs();
+}
+
+/// A value of a serialized operator that does not conform to the JSON schema of the operator
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaViolation {
+    /// The JSON pointer to the invalid value, e.g., `/operator/sources/vector/params/columns/2`
+    pub pointer: String,
+    pub message: String,
+}
+
+impl SchemaViolation {
+    fn new(pointer: &str, message: String) -> Self {
+        Self {
+            pointer: pointer.to_owned(),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.pointer.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "`{}`: {}", self.pointer, self.message)
+        }
+    }
+}
+
+/// Validates a serialized operator and its sources against the parameter schemas of the [`operator_specs`].
+///
+/// Unlike deserialization, which stops at the first error, this lists all invalid values together with their
+/// location. `pointer` is the location of the operator in the surrounding document, e.g., `/operator`.
+/// The parameters of operators without a spec, e.g., mocks, are not validated, but their sources are.
+/// Deprecated operator names must be resolved beforehand, cf. [`resolve_operator_aliases`](super::resolve_operator_aliases).
+pub fn validate_operator(operator: &Value, pointer: &str) -> Vec<SchemaViolation> {
+    let mut violations = Vec::new();
+    validate_operator_node(operator, pointer, &mut violations);
+    violations
+}
+
+fn validate_operator_node(operator: &Value, pointer: &str, violations: &mut Vec<SchemaViolation>) {
+    let operator = match operator.as_object() {
+        Some(operator) => operator,
+        None => {
+            violations.push(SchemaViolation::new(
+                pointer,
+                format!(
+                    "expected an operator object, found {}",
+                    value_type(operator)
+                ),
+            ));
+            return;
+        }
+    };
+
+    match operator.get("type").and_then(Value::as_str) {
+        Some(name) => {
+            if let Some(spec) = OPERATOR_SPECS.iter().find(|spec| spec.name == name) {
+                match operator.get("params") {
+                    Some(params) => validate_schema_object(
+                        params,
+                        &spec.params.schema,
+                        &spec.params.definitions,
+                        &child_pointer(pointer, "params"),
+                        violations,
+                    ),
+                    None => violations.push(SchemaViolation::new(
+                        pointer,
+                        "missing required property `params`".to_owned(),
+                    )),
+                }
+            }
+        }
+        None => violations.push(SchemaViolation::new(
+            pointer,
+            "missing the operator name in property `type`".to_owned(),
+        )),
+    }
+
+    let sources = match operator.get("sources").and_then(Value::as_object) {
+        Some(sources) => sources,
+        None => return,
+    };
+
+    for (name, source) in sources {
+        let source_pointer = child_pointer(pointer, &format!("sources/{}", escape(name)));
+
+        if let Value::Array(operators) = source {
+            for (index, operator) in operators.iter().enumerate() {
+                validate_operator_node(
+                    operator,
+                    &child_pointer(&source_pointer, &index.to_string()),
+                    violations,
+                );
+            }
+        } else {
+            validate_operator_node(source, &source_pointer, violations);
+        }
+    }
+}
+
+fn validate_schema(
+    value: &Value,
+    schema: &Schema,
+    definitions: &Map<String, Schema>,
+    pointer: &str,
+    violations: &mut Vec<SchemaViolation>,
+) {
+    match schema {
+        Schema::Bool(true) => {}
+        Schema::Bool(false) => {
+            violations.push(SchemaViolation::new(pointer, "is not allowed".to_owned()));
+        }
+        Schema::Object(schema) => {
+            validate_schema_object(value, schema, definitions, pointer, violations);
+        }
+    }
+}
+
+fn validate_schema_object(
+    value: &Value,
+    schema: &SchemaObject,
+    definitions: &Map<String, Schema>,
+    pointer: &str,
+    violations: &mut Vec<SchemaViolation>,
+) {
+    // other keywords next to a reference are ignored in draft 7
+    if let Some(reference) = &schema.reference {
+        if let Some(definition) = reference
+            .strip_prefix("#/definitions/")
+            .and_then(|name| definitions.get(name))
+        {
+            validate_schema(value, definition, definitions, pointer, violations);
+        }
+        return;
+    }
+
+    if let Some(instance_type) = &schema.instance_type {
+        let types: &[InstanceType] = match instance_type {
+            SingleOrVec::Single(instance_type) => std::slice::from_ref(instance_type),
+            SingleOrVec::Vec(types) => types,
+        };
+
+        if !types
+            .iter()
+            .any(|instance_type| is_instance_of(value, instance_type))
+        {
+            let expected: Vec<&str> = types.iter().map(instance_type_name).collect();
+            violations.push(SchemaViolation::new(
+                pointer,
+                format!(
+                    "expected {}, found {}",
+                    expected.join(" or "),
+                    value_type(value)
+                ),
+            ));
+            return;
+        }
+    }
+
+    if let Some(enum_values) = &schema.enum_values {
+        if !enum_values.contains(value) {
+            let expected: Vec<String> = enum_values.iter().map(ToString::to_string).collect();
+            violations.push(SchemaViolation::new(
+                pointer,
+                format!("expected one of {}, found {}", expected.join(", "), value),
+            ));
+        }
+    }
+
+    if let Some(const_value) = &schema.const_value {
+        if const_value != value {
+            violations.push(SchemaViolation::new(
+                pointer,
+                format!("expected {}, found {}", const_value, value),
+            ));
+        }
+    }
+
+    if let Some(subschemas) = &schema.subschemas {
+        for subschema in subschemas.all_of.iter().flatten() {
+            validate_schema(value, subschema, definitions, pointer, violations);
+        }
+
+        // `oneOf` is treated like `anyOf`, since serde uses the first variant that matches
+        for alternatives in subschemas.any_of.iter().chain(&subschemas.one_of) {
+            validate_alternatives(value, alternatives, definitions, pointer, violations);
+        }
+    }
+
+    match value {
+        Value::Number(number) => {
+            if let (Some(validation), Some(number)) = (&schema.number, number.as_f64()) {
+                let violated_bound = validation
+                    .minimum
+                    .filter(|&minimum| number < minimum)
+                    .map(|minimum| format!("must be at least {}", minimum))
+                    .or_else(|| {
+                        validation
+                            .exclusive_minimum
+                            .filter(|&minimum| number <= minimum)
+                            .map(|minimum| format!("must be greater than {}", minimum))
+                    })
+                    .or_else(|| {
+                        validation
+                            .maximum
+                            .filter(|&maximum| number > maximum)
+                            .map(|maximum| format!("must be at most {}", maximum))
+                    })
+                    .or_else(|| {
+                        validation
+                            .exclusive_maximum
+                            .filter(|&maximum| number >= maximum)
+                            .map(|maximum| format!("must be less than {}", maximum))
+                    });
+
+                if let Some(message) = violated_bound {
+                    violations.push(SchemaViolation::new(pointer, message));
+                }
+            }
+        }
+        Value::String(string) => {
+            if let Some(validation) = &schema.string {
+                let length = string.chars().count();
+
+                if let Some(min_length) = validation.min_length {
+                    if length < min_length as usize {
+                        violations.push(SchemaViolation::new(
+                            pointer,
+                            format!("must have at least {} characters", min_length),
+                        ));
+                    }
+                }
+
+                if let Some(max_length) = validation.max_length {
+                    if length > max_length as usize {
+                        violations.push(SchemaViolation::new(
+                            pointer,
+                            format!("must have at most {} characters", max_length),
+                        ));
+                    }
+                }
+
+                if let Some(pattern) = &validation.pattern {
+                    if let Ok(regex) = Regex::new(pattern) {
+                        if !regex.is_match(string) {
+                            violations.push(SchemaViolation::new(
+                                pointer,
+                                format!("`{}` does not match the pattern `{}`", string, pattern),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(validation) = &schema.array {
+                if let Some(min_items) = validation.min_items {
+                    if items.len() < min_items as usize {
+                        violations.push(SchemaViolation::new(
+                            pointer,
+                            format!("must have at least {} items", min_items),
+                        ));
+                    }
+                }
+
+                if let Some(max_items) = validation.max_items {
+                    if items.len() > max_items as usize {
+                        violations.push(SchemaViolation::new(
+                            pointer,
+                            format!("must have at most {} items", max_items),
+                        ));
+                    }
+                }
+
+                for (index, item) in items.iter().enumerate() {
+                    let item_schema = match &validation.items {
+                        Some(SingleOrVec::Single(schema)) => Some(schema.as_ref()),
+                        Some(SingleOrVec::Vec(schemas)) => schemas
+                            .get(index)
+                            .or_else(|| validation.additional_items.as_deref()),
+                        None => None,
+                    };
+
+                    if let Some(item_schema) = item_schema {
+                        validate_schema(
+                            item,
+                            item_schema,
+                            definitions,
+                            &child_pointer(pointer, &index.to_string()),
+                            violations,
+                        );
+                    }
+                }
+            }
+        }
+        Value::Object(object) => {
+            if let Some(validation) = &schema.object {
+                for property in &validation.required {
+                    if !object.contains_key(property) {
+                        violations.push(SchemaViolation::new(
+                            pointer,
+                            format!("missing required property `{}`", property),
+                        ));
+                    }
+                }
+
+                for (property, value) in object {
+                    let property_schema = validation
+                        .properties
+                        .get(property)
+                        .or_else(|| validation.additional_properties.as_deref());
+
+                    if let Some(property_schema) = property_schema {
+                        validate_schema(
+                            value,
+                            property_schema,
+                            definitions,
+                            &child_pointer(pointer, &escape(property)),
+                            violations,
+                        );
+                    }
+                }
+            }
+        }
+        Value::Null | Value::Bool(_) => {}
+    }
+}
+
+/// Reports the violations of the alternative that is closest to the `value` if none of them matches.
+/// An alternative is closer if it has fewer violations of the value itself, e.g., a wrong type, and fewer violations
+/// in total.
+fn validate_alternatives(
+    value: &Value,
+    alternatives: &[Schema],
+    definitions: &Map<String, Schema>,
+    pointer: &str,
+    violations: &mut Vec<SchemaViolation>,
+) {
+    let mut closest_violations: Option<Vec<SchemaViolation>> = None;
+
+    for alternative in alternatives {
+        let mut alternative_violations = Vec::new();
+        validate_schema(
+            value,
+            alternative,
+            definitions,
+            pointer,
+            &mut alternative_violations,
+        );
+
+        if alternative_violations.is_empty() {
+            return;
+        }
+
+        if closest_violations.as_ref().map_or(true, |closest| {
+            distance(&alternative_violations, pointer) < distance(closest, pointer)
+        }) {
+            closest_violations = Some(alternative_violations);
+        }
+    }
+
+    violations.extend(closest_violations.unwrap_or_default());
+}
+
+/// The number of violations of the value at `pointer` itself and the total number of violations
+fn distance(violations: &[SchemaViolation], pointer: &str) -> (usize, usize) {
+    let own_violations = violations
+        .iter()
+        .filter(|violation| violation.pointer == pointer)
+        .count();
+
+    (own_violations, violations.len())
+}
+
+fn is_instance_of(value: &Value, instance_type: &InstanceType) -> bool {
+    match instance_type {
+        InstanceType::Null => value.is_null(),
+        InstanceType::Boolean => value.is_boolean(),
+        InstanceType::Object => value.is_object(),
+        InstanceType::Array => value.is_array(),
+        InstanceType::Number => value.is_number(),
+        InstanceType::String => value.is_string(),
+        InstanceType::Integer => {
+            value.is_i64()
+                || value.is_u64()
+                || value.as_f64().map_or(false, |number| number.fract() == 0.)
+        }
+    }
+}
+
+fn instance_type_name(instance_type: &InstanceType) -> &'static str {
+    match instance_type {
+        InstanceType::Null => "null",
+        InstanceType::Boolean => "boolean",
+        InstanceType::Object => "object",
+        InstanceType::Array => "array",
+        InstanceType::Number => "number",
+        InstanceType::String => "string",
+        InstanceType::Integer => "integer",
+    }
+}
+
+fn value_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn child_pointer(pointer: &str, token: &str) -> String {
+    format!("{}/{}", pointer, token)
+}
+
+/// Escapes a reference token of a JSON pointer (RFC 6901)
+fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_accepts_valid_operators() {
+        let operator = json!({
+            "type": "Reprojection",
+            "params": {
+                "targetSpatialReference": "EPSG:4326"
+            },
+            "sources": {
+                "source": {
+                    "type": "MockPointSource",
+                    "params": {
+                        "points": [{ "x": 0.0, "y": 0.1 }]
+                    }
+                }
+            }
+        });
+
+        assert!(validate_operator(&operator, "/operator").is_empty());
+    }
+
+    #[test]
+    fn it_locates_invalid_parameters() {
+        let operator = json!({
+            "type": "RasterVectorJoin",
+            "params": {
+                "names": ["a", "b", 3],
+                "featureAggregation": "first",
+                "temporalAggregation": "none"
+            },
+            "sources": {
+                "vector": {
+                    "type": "Reprojection",
+                    "params": {
+                        "targetSpatialReference": "EPSG-4326",
+                        "resampling": "nearest",
+                        "unknown": true
+                    },
+                    "sources": {
+                        "source": {
+                            "type": "MockPointSource",
+                            "params": {}
+                        }
+                    }
+                },
+                "rasters": [{
+                    "type": "GdalSource"
+                }]
+            }
+        });
+
+        assert_eq!(
+            validate_operator(&operator, "/operator"),
+            vec![
+                SchemaViolation {
+                    pointer: "/operator/params/names/2".to_owned(),
+                    message: "expected string, found number".to_owned(),
+                },
+                SchemaViolation {
+                    pointer: "/operator/sources/rasters/0".to_owned(),
+                    message: "missing required property `params`".to_owned(),
+                },
+                SchemaViolation {
+                    pointer: "/operator/sources/vector/params/targetSpatialReference".to_owned(),
+                    message:
+                        "`EPSG-4326` does not match the pattern `^(EPSG|SR-ORG|IAU2000|ESRI):[0-9]+$`"
+                            .to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_reports_the_closest_alternative() {
+        let operator = json!({
+            "type": "Reprojection",
+            "params": {
+                "targetSpatialReference": "EPSG:4326",
+                "transformation": {
+                    "type": "areaOfInterest",
+                    "bounds": {
+                        "lowerLeftCoordinate": { "x": 0.0, "y": 0.0 },
+                        "upperRightCoordinate": { "x": 1.0 }
+                    }
+                }
+            },
+            "sources": {}
+        });
+
+        assert_eq!(
+            validate_operator(&operator, ""),
+            vec![SchemaViolation {
+                pointer: "/params/transformation/bounds/upperRightCoordinate".to_owned(),
+                message: "missing required property `y`".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn it_escapes_pointers() {
+        assert_eq!(escape("a/b~c"), "a~1b~0c");
+    }
+}
@@ -1,7 +1,21 @@
+use crate::error::{self, Error};
+use crate::mock::MockQueryFaults;
+use crate::processing::PointInPolygonTester;
+use crate::util::Result;
+use chrono::Utc;
+use geoengine_datatypes::collections::MultiPolygonCollection;
 use geoengine_datatypes::primitives::{
-    AxisAlignedRectangle, BoundingBox2D, SpatialPartition2D, SpatialPartitioned, SpatialResolution,
-    TimeInterval,
+    AxisAlignedRectangle, BoundingBox2D, Coordinate2D, MultiPolygon, SpatialPartition2D,
+    SpatialPartitioned, SpatialResolution, TimeInstance, TimeInterval,
 };
+use geoengine_datatypes::raster::{GridShape2D, GridSize, TileInformation};
+use snafu::ensure;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 /// A spatio-temporal rectangle for querying data with a bounding box
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -14,6 +28,7 @@ pub struct QueryRectangle<SpatialBounds: AxisAlignedRectangle> {
 pub type VectorQueryRectangle = QueryRectangle<BoundingBox2D>;
 pub type RasterQueryRectangle = QueryRectangle<SpatialPartition2D>;
 pub type PlotQueryRectangle = QueryRectangle<BoundingBox2D>;
+pub type AggregateQueryRectangle = QueryRectangle<BoundingBox2D>;
 
 impl SpatialPartitioned for VectorQueryRectangle {
     fn spatial_partition(&self) -> SpatialPartition2D {
@@ -39,23 +54,248 @@ impl From<VectorQueryRectangle> for RasterQueryRectangle {
 
 pub trait QueryContext: Send + Sync {
     fn chunk_byte_size(&self) -> usize;
+
+    /// Additional, typed information about the query that operators and sinks can read
+    fn extensions(&self) -> &QueryContextExtensions;
+
+    fn extensions_mut(&mut self) -> &mut QueryContextExtensions;
+
+    /// Returns true if the query was aborted via its `QueryAbortToken`
+    fn abort_requested(&self) -> bool {
+        self.extensions()
+            .get::<QueryAbortToken>()
+            .map_or(false, QueryAbortToken::is_aborted)
+    }
+
+    /// The current time, which is the system time unless the query has a `QueryClock`
+    fn now(&self) -> TimeInstance {
+        self.extensions()
+            .get::<QueryClock>()
+            .map_or_else(|| Utc::now().into(), QueryClock::now)
+    }
+}
+
+/// A typed map of additional information for a query, e.g., the requesting user, a priority or cache hints.
+/// It holds at most one value per type.
+#[derive(Default)]
+pub struct QueryContextExtensions {
+    map: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl QueryContextExtensions {
+    /// Inserts a value and returns the previous value of the same type, if any
+    pub fn insert<T: Any + Send + Sync>(&mut self, value: T) -> Option<T> {
+        self.map
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(|previous| previous.downcast().ok().map(|previous| *previous))
+    }
+
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.map
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref())
+    }
+
+    pub fn remove<T: Any + Send + Sync>(&mut self) -> Option<T> {
+        self.map
+            .remove(&TypeId::of::<T>())
+            .and_then(|value| value.downcast().ok().map(|value| *value))
+    }
+}
+
+impl Debug for QueryContextExtensions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueryContextExtensions")
+            .field("len", &self.map.len())
+            .finish()
+    }
+}
+
+/// The priority of a query, s.t. interactive requests can be preferred over batch exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum QueryPriority {
+    Batch,
+    Interactive,
+}
+
+/// A hint on how caches should treat the results of a query
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CachePolicyHint {
+    /// Results may be read from and written to caches
+    Default,
+    /// Results must be computed and should not be cached, e.g., for one-time exports
+    Bypass,
+}
+
+/// A token for aborting a running query, e.g., if the client disconnected.
+/// Clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct QueryAbortToken {
+    aborted: Arc<AtomicBool>,
+}
+
+impl QueryAbortToken {
+    pub fn abort(&self) {
+        self.aborted.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_aborted(&self) -> bool {
+        self.aborted.load(Ordering::Relaxed)
+    }
+}
+
+/// A clock that replaces the system time of a query, e.g., for testing time-dependent behavior.
+/// It only advances when told to. Clones share the same time.
+#[derive(Debug, Clone)]
+pub struct QueryClock {
+    millis: Arc<AtomicI64>,
+}
+
+impl QueryClock {
+    pub fn new(time: TimeInstance) -> Self {
+        Self {
+            millis: Arc::new(AtomicI64::new(time.inner())),
+        }
+    }
+
+    pub fn now(&self) -> TimeInstance {
+        TimeInstance::from_millis_unchecked(self.millis.load(Ordering::Relaxed))
+    }
+
+    pub fn set(&self, time: TimeInstance) {
+        self.millis.store(time.inner(), Ordering::Relaxed);
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.millis
+            .fetch_add(duration.as_millis() as i64, Ordering::Relaxed);
+    }
+}
+
+/// A polygonal region of a query, e.g., the boundary of an area of interest.
+/// The spatial bounds of the query should cover its bounding box.
+/// Operators that aggregate raster values, like statistics, only consider the pixels whose centers lie inside of it.
+#[derive(Clone)]
+pub struct QueryRegion {
+    tester: Arc<PointInPolygonTester>,
+    bounding_box: BoundingBox2D,
+}
+
+impl QueryRegion {
+    pub fn new(polygon: MultiPolygon) -> Result<Self> {
+        ensure!(
+            polygon
+                .as_ref()
+                .iter()
+                .flatten()
+                .all(|ring| ring.len() >= 4 && ring.first() == ring.last()),
+            error::InvalidQueryRegion
+        );
+
+        let bounding_box =
+            BoundingBox2D::from_coord_ref_iter(polygon.as_ref().iter().flatten().flatten())
+                .ok_or(Error::InvalidQueryRegion)?;
+
+        let polygons = MultiPolygonCollection::from_data(
+            vec![polygon],
+            vec![TimeInterval::default()],
+            HashMap::new(),
+        )?;
+
+        Ok(Self {
+            tester: Arc::new(PointInPolygonTester::new(polygons)),
+            bounding_box,
+        })
+    }
+
+    pub fn bounding_box(&self) -> BoundingBox2D {
+        self.bounding_box
+    }
+
+    pub fn contains_coordinate(&self, coordinate: &Coordinate2D) -> bool {
+        self.bounding_box.contains_coordinate(coordinate)
+            && self
+                .tester
+                .is_coordinate_in_any_polygon(coordinate, &TimeInterval::default())
+    }
+
+    /// Computes for each pixel of a tile, in row-major order, whether its center lies inside the region
+    pub fn tile_mask(&self, tile_information: &TileInformation) -> Vec<bool> {
+        let geo_transform = tile_information.tile_geo_transform();
+        let shape: GridShape2D = tile_information.tile_size_in_pixels;
+
+        let mut mask = Vec::with_capacity(shape.number_of_elements());
+        for y in 0..shape.axis_size_y() as isize {
+            for x in 0..shape.axis_size_x() as isize {
+                let center = geo_transform.grid_idx_to_center_coordinate_2d([y, x].into());
+                mask.push(self.contains_coordinate(&center));
+            }
+        }
+
+        mask
+    }
+
+    /// Keeps the values of the pixels of a tile whose centers lie inside the region
+    pub fn masked_values<T: Copy>(
+        &self,
+        tile_information: &TileInformation,
+        values: &[T],
+    ) -> Vec<T> {
+        values
+            .iter()
+            .zip(self.tile_mask(tile_information))
+            .filter_map(|(&value, inside)| if inside { Some(value) } else { None })
+            .collect()
+    }
+
+    /// Counts the pixels of a tile whose centers lie inside the region
+    pub fn number_of_pixels_inside(&self, tile_information: &TileInformation) -> usize {
+        self.tile_mask(tile_information)
+            .into_iter()
+            .filter(|&inside| inside)
+            .count()
+    }
+}
+
+impl Debug for QueryRegion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueryRegion")
+            .field("bounding_box", &self.bounding_box)
+            .finish()
+    }
 }
 
 pub struct MockQueryContext {
     pub chunk_byte_size: usize,
+    pub extensions: QueryContextExtensions,
 }
 
 impl Default for MockQueryContext {
     fn default() -> Self {
-        Self {
-            chunk_byte_size: 1024 * 1024,
-        }
+        Self::new(1024 * 1024)
     }
 }
 
 impl MockQueryContext {
     pub fn new(chunk_byte_size: usize) -> Self {
-        Self { chunk_byte_size }
+        Self {
+            chunk_byte_size,
+            extensions: QueryContextExtensions::default(),
+        }
+    }
+
+    /// Uses the `clock` instead of the system time
+    #[must_use]
+    pub fn with_clock(mut self, clock: QueryClock) -> Self {
+        self.extensions.insert(clock);
+        self
+    }
+
+    /// Lets mock sources fail or delay their results
+    #[must_use]
+    pub fn with_faults(mut self, faults: MockQueryFaults) -> Self {
+        self.extensions.insert(faults);
+        self
     }
 }
 
@@ -63,4 +303,92 @@ impl QueryContext for MockQueryContext {
     fn chunk_byte_size(&self) -> usize {
         self.chunk_byte_size
     }
+
+    fn extensions(&self) -> &QueryContextExtensions {
+        &self.extensions
+    }
+
+    fn extensions_mut(&mut self) -> &mut QueryContextExtensions {
+        &mut self.extensions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::raster::GeoTransform;
+
+    #[test]
+    fn extensions() {
+        let mut ctx = MockQueryContext::default();
+
+        assert_eq!(ctx.extensions().get::<QueryPriority>(), None);
+
+        ctx.extensions_mut().insert(QueryPriority::Batch);
+        assert_eq!(
+            ctx.extensions_mut().insert(QueryPriority::Interactive),
+            Some(QueryPriority::Batch)
+        );
+        assert_eq!(
+            ctx.extensions().get::<QueryPriority>(),
+            Some(&QueryPriority::Interactive)
+        );
+
+        assert!(!ctx.abort_requested());
+
+        let token = QueryAbortToken::default();
+        ctx.extensions_mut().insert(token.clone());
+        token.abort();
+
+        assert!(ctx.abort_requested());
+    }
+
+    #[test]
+    fn query_region() {
+        let region = QueryRegion::new(
+            MultiPolygon::new(vec![vec![vec![
+                (0., 0.).into(),
+                (5., 0.).into(),
+                (0., 5.).into(),
+                (0., 0.).into(),
+            ]]])
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            region.bounding_box(),
+            BoundingBox2D::new_unchecked((0., 0.).into(), (5., 5.).into())
+        );
+
+        assert!(region.contains_coordinate(&(1., 1.).into()));
+        assert!(!region.contains_coordinate(&(3., 3.).into()));
+
+        let tile_information = TileInformation {
+            global_tile_position: [0, 0].into(),
+            tile_size_in_pixels: [2, 2].into(),
+            global_geo_transform: GeoTransform::new((0., 4.).into(), 2., -2.),
+        };
+
+        // the pixel centers are (1, 3), (3, 3), (1, 1) and (3, 1)
+        assert_eq!(
+            region.tile_mask(&tile_information),
+            vec![true, false, true, true]
+        );
+    }
+
+    #[test]
+    fn query_region_with_open_ring() {
+        let polygon: MultiPolygon = serde_json::from_value(serde_json::json!({
+            "polygons": [[[
+                {"x": 0.0, "y": 0.0},
+                {"x": 1.0, "y": 0.0},
+                {"x": 1.0, "y": 1.0},
+                {"x": 0.0, "y": 1.0}
+            ]]]
+        }))
+        .unwrap();
+
+        assert!(QueryRegion::new(polygon).is_err());
+    }
 }
@@ -1,5 +1,7 @@
 use super::query::{QueryContext, QueryRectangle};
-use super::{PlotQueryRectangle, RasterQueryRectangle, VectorQueryRectangle};
+use super::{
+    AggregateQueryRectangle, PlotQueryRectangle, RasterQueryRectangle, VectorQueryRectangle,
+};
 use crate::util::Result;
 use async_trait::async_trait;
 use futures::stream::BoxStream;
@@ -10,6 +12,7 @@ use geoengine_datatypes::plots::{PlotData, PlotOutputFormat};
 use geoengine_datatypes::primitives::{AxisAlignedRectangle, BoundingBox2D, SpatialPartition2D};
 use geoengine_datatypes::raster::Pixel;
 use geoengine_datatypes::{collections::MultiPointCollection, raster::RasterTile2D};
+use serde::{Deserialize, Serialize};
 
 /// An instantiation of an operator that produces a stream of results for a query
 #[async_trait]
@@ -28,6 +31,13 @@ pub trait QueryProcessor: Send + Sync {
 pub trait RasterQueryProcessor: Sync + Send {
     type RasterType: Pixel;
 
+    /// Queries the raster tiles that intersect the query rectangle.
+    ///
+    /// The tiles are ordered geo first, time second: all tiles of a time step are emitted in the
+    /// order of the tiling strategy (row by row) before the tiles of the next time step follow.
+    /// Thus, the results of multiple processors for the same query can be combined tile by tile,
+    /// e.g., by using [`RasterStreamExt::zip_aligned`](crate::adapters::RasterStreamExt::zip_aligned)
+    /// or [`RasterArrayZip`](crate::adapters::RasterArrayZip).
     async fn raster_query<'a>(
         &'a self,
         query: RasterQueryRectangle,
@@ -113,6 +123,27 @@ pub trait PlotQueryProcessor: Sync + Send {
     }
 }
 
+/// An instantiation of an aggregate operator that computes a single result for a query
+#[async_trait]
+pub trait AggregateQueryProcessor: Sync + Send {
+    type Output;
+
+    fn aggregate_type(&self) -> &'static str;
+
+    async fn aggregate_query<'a>(
+        &'a self,
+        query: AggregateQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<Self::Output>;
+
+    fn boxed(self) -> Box<dyn AggregateQueryProcessor<Output = Self::Output>>
+    where
+        Self: Sized + 'static,
+    {
+        Box::new(self)
+    }
+}
+
 #[async_trait]
 impl<T, S> QueryProcessor for Box<dyn QueryProcessor<Output = T, SpatialBounds = S>>
 where
@@ -399,6 +430,47 @@ impl TypedPlotQueryProcessor {
     }
 }
 
+/// A small table that is the result of an aggregate query, e.g., one row of statistics per column
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregateTable {
+    pub columns: Vec<String>,
+    /// The rows of the table, each with one value per column
+    pub rows: Vec<Vec<serde_json::Value>>,
+}
+
+/// An enum that contains all possible aggregate query processor variants
+pub enum TypedAggregateQueryProcessor {
+    /// A single number that is `None` if it is undefined, e.g., the mean of no values
+    Scalar(Box<dyn AggregateQueryProcessor<Output = Option<f64>>>),
+    Table(Box<dyn AggregateQueryProcessor<Output = AggregateTable>>),
+}
+
+impl TypedAggregateQueryProcessor {
+    pub fn aggregate_type(&self) -> &'static str {
+        match self {
+            TypedAggregateQueryProcessor::Scalar(p) => p.aggregate_type(),
+            TypedAggregateQueryProcessor::Table(p) => p.aggregate_type(),
+        }
+    }
+
+    pub fn scalar(self) -> Option<Box<dyn AggregateQueryProcessor<Output = Option<f64>>>> {
+        if let TypedAggregateQueryProcessor::Scalar(p) = self {
+            Some(p)
+        } else {
+            None
+        }
+    }
+
+    pub fn table(self) -> Option<Box<dyn AggregateQueryProcessor<Output = AggregateTable>>> {
+        if let TypedAggregateQueryProcessor::Table(p) = self {
+            Some(p)
+        } else {
+            None
+        }
+    }
+}
+
 /// Maps a `TypedVectorQueryProcessor` to another `TypedVectorQueryProcessor` by calling a function on its variant.
 /// Call via `map_typed_query_processor!(input, processor => function)`.
 #[macro_export]
@@ -43,6 +43,25 @@ pub struct RasterResultDescriptor {
     pub spatial_reference: SpatialReferenceOption,
     pub measurement: Measurement,
     pub no_data_value: Option<f64>,
+    /// The bands of a multiband raster, whose streams contain a tile per band for each tile position and time step.
+    /// Single band rasters have no band descriptors.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bands: Vec<RasterBandDescriptor>,
+}
+
+impl RasterResultDescriptor {
+    /// The number of tiles per tile position and time step
+    pub fn number_of_bands(&self) -> usize {
+        self.bands.len().max(1)
+    }
+}
+
+/// The description of a band of a multiband raster
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RasterBandDescriptor {
+    pub name: String,
+    pub measurement: Measurement,
 }
 
 impl ResultDescriptor for RasterResultDescriptor {
@@ -63,6 +82,7 @@ impl ResultDescriptor for RasterResultDescriptor {
         Self {
             data_type: f(&self.data_type),
             measurement: self.measurement.clone(),
+            bands: self.bands.clone(),
             ..*self
         }
     }
@@ -74,6 +94,7 @@ impl ResultDescriptor for RasterResultDescriptor {
         Self {
             spatial_reference: f(&self.spatial_reference),
             measurement: self.measurement.clone(),
+            bands: self.bands.clone(),
             ..*self
         }
     }
@@ -163,10 +184,39 @@ impl ResultDescriptor for PlotResultDescriptor {
         *self
     }
 }
+/// A `ResultDescriptor` for aggregate queries
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AggregateResultDescriptor {}
+
+impl ResultDescriptor for AggregateResultDescriptor {
+    type DataType = ();
+
+    fn data_type(&self) -> Self::DataType {}
+
+    fn spatial_reference(&self) -> SpatialReferenceOption {
+        SpatialReferenceOption::Unreferenced
+    }
+
+    fn map_data_type<F>(&self, _f: F) -> Self
+    where
+        F: Fn(&Self::DataType) -> Self::DataType,
+    {
+        *self
+    }
+
+    fn map_spatial_reference<F>(&self, _f: F) -> Self
+    where
+        F: Fn(&SpatialReferenceOption) -> SpatialReferenceOption,
+    {
+        *self
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase", tag = "type")]
 pub enum TypedResultDescriptor {
     Plot(PlotResultDescriptor),
+    Aggregate(AggregateResultDescriptor),
     Raster(RasterResultDescriptor),
     Vector(VectorResultDescriptor),
 }
@@ -177,6 +227,12 @@ impl From<PlotResultDescriptor> for TypedResultDescriptor {
     }
 }
 
+impl From<AggregateResultDescriptor> for TypedResultDescriptor {
+    fn from(value: AggregateResultDescriptor) -> Self {
+        Self::Aggregate(value)
+    }
+}
+
 impl From<RasterResultDescriptor> for TypedResultDescriptor {
     fn from(value: RasterResultDescriptor) -> Self {
         Self::Raster(value)
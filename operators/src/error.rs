@@ -1,6 +1,7 @@
 use chrono::ParseError;
 use geoengine_datatypes::dataset::DatasetId;
-use geoengine_datatypes::primitives::FeatureDataType;
+use geoengine_datatypes::primitives::{FeatureDataType, TimeInterval};
+use geoengine_datatypes::raster::GridIdx2D;
 use snafu::Snafu;
 use std::ops::Range;
 
@@ -66,6 +67,11 @@ pub enum Error {
         column: String,
     },
 
+    #[snafu(display("Raster band {} does not exist", band))]
+    RasterBandDoesNotExist {
+        band: String,
+    },
+
     #[snafu(display("GdalError: {}", source))]
     Gdal {
         source: gdal::errors::GdalError,
@@ -109,8 +115,26 @@ pub enum Error {
 
     InvalidExpression,
 
+    #[snafu(display("Invalid expression: {}", reason))]
+    InvalidExpressionSyntax {
+        reason: String,
+    },
+
     InvalidNumberOfExpressionInputs,
 
+    #[snafu(display(
+        "The sources of an expression must be assigned without gaps, e.g., `c` requires `b`"
+    ))]
+    NonContiguousExpressionSources,
+
+    #[snafu(display(
+        "The OpenCL backend of the expression operator does not support {}",
+        feature
+    ))]
+    UnsupportedOpenClExpressionFeature {
+        feature: String,
+    },
+
     InvalidNoDataValueValueForOutputDataType,
 
     InvalidType {
@@ -176,6 +200,16 @@ pub enum Error {
 
     FilePathNotRepresentableAsString,
 
+    #[snafu(display("The file path {} is outside of the data directories", path))]
+    FilePathOutsideOfDataRoots {
+        path: String,
+    },
+
+    #[snafu(display("The scheme {} is not allowed in file paths", scheme))]
+    FilePathSchemeNotAllowed {
+        scheme: String,
+    },
+
     TokioJoin {
         source: tokio::task::JoinError,
     },
@@ -197,6 +231,24 @@ pub enum Error {
 
     WindowSizeMustNotBeZero,
 
+    #[snafu(display("Invalid neighborhood: {}", reason))]
+    InvalidNeighborhood {
+        reason: String,
+    },
+
+    #[snafu(display(
+        "The neighborhood of {}x{} pixels exceeds the maximum of {}x{} pixels",
+        rows,
+        columns,
+        max_dimension,
+        max_dimension
+    ))]
+    NeighborhoodTooLarge {
+        rows: usize,
+        columns: usize,
+        max_dimension: usize,
+    },
+
     NotYetImplemented,
 
     TemporalRasterAggregationLastValidRequiresNoData,
@@ -212,6 +264,11 @@ pub enum Error {
 
     NotImplemented,
 
+    #[snafu(display("TileArchiveError: {}", source))]
+    TileArchive {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
     TileLimitExceeded {
         limit: usize,
     },
@@ -220,9 +277,53 @@ pub enum Error {
 
     FeatureDataLengthMismatch,
 
+    #[snafu(display("InvalidRegex: {}", source))]
+    InvalidRegex {
+        source: regex::Error,
+    },
+
+    #[snafu(display("InvalidTextTemplate: {}", reason))]
+    InvalidTextTemplate {
+        reason: String,
+    },
+
+    #[snafu(display(
+        "UnalignedRasterTiles: tile {:?} at {:?} does not match tile {:?} at {:?}",
+        position_a,
+        time_a,
+        position_b,
+        time_b
+    ))]
+    UnalignedRasterTiles {
+        position_a: GridIdx2D,
+        time_a: TimeInterval,
+        position_b: GridIdx2D,
+        time_b: TimeInterval,
+    },
+
+    QueryAborted,
+
+    #[snafu(display("A query region must consist of closed polygon rings"))]
+    InvalidQueryRegion,
+
     OgrSqlQuery,
 
     GdalRasterDataTypeNotSupported,
+
+    #[snafu(display("Invalid generalization levels: {}", reason))]
+    InvalidGeneralizationLevels {
+        reason: String,
+    },
+
+    #[snafu(display("Invalid lookup table: {}", reason))]
+    InvalidLookupTable {
+        reason: String,
+    },
+
+    #[snafu(display("Injected failure of result item {}", item))]
+    InjectedFailure {
+        item: usize,
+    },
 }
 
 impl From<geoengine_datatypes::error::Error> for Error {
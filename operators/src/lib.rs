@@ -27,6 +27,7 @@
 #![allow(clippy::semicolon_if_nothing_returned)]
 
 pub mod adapters;
+pub mod aggregate;
 pub mod concurrency;
 #[macro_use]
 pub mod engine;
@@ -4,6 +4,7 @@ use crate::engine::{
     VectorResultDescriptor,
 };
 use crate::engine::{QueryContext, VectorQueryRectangle};
+use crate::mock::inject_faults;
 use crate::util::Result;
 use async_trait::async_trait;
 use futures::stream::{self, BoxStream, StreamExt};
@@ -33,12 +34,14 @@ where
     async fn vector_query<'a>(
         &'a self,
         _query: VectorQueryRectangle,
-        _ctx: &'a dyn QueryContext,
+        ctx: &'a dyn QueryContext,
     ) -> Result<BoxStream<'a, Result<Self::VectorType>>> {
         // TODO: chunk it up
         // let chunk_size = ctx.chunk_byte_size / std::mem::size_of::<Coordinate2D>();
 
-        Ok(stream::iter(self.collections.iter().map(|c| Ok(c.clone()))).boxed())
+        let stream = stream::iter(self.collections.iter().map(|c| Ok(c.clone()))).boxed();
+
+        Ok(inject_faults(stream, ctx))
     }
 }
 
@@ -1,4 +1,5 @@
 use crate::engine::{OperatorDatasets, QueryContext, VectorQueryRectangle};
+use crate::mock::inject_faults;
 use crate::{
     engine::{
         ExecutionContext, InitializedVectorOperator, SourceOperator, TypedVectorQueryProcessor,
@@ -31,16 +32,16 @@ impl VectorQueryProcessor for MockPointSourceProcessor {
         ctx: &'a dyn QueryContext,
     ) -> Result<BoxStream<'a, Result<Self::VectorType>>> {
         let chunk_size = ctx.chunk_byte_size() / std::mem::size_of::<Coordinate2D>();
-        Ok(
-            stream::iter(self.points.chunks(chunk_size).map(move |chunk| {
-                Ok(MultiPointCollection::from_data(
-                    chunk.iter().map(Into::into).collect(),
-                    vec![TimeInterval::default(); chunk.len()],
-                    HashMap::new(),
-                )?)
-            }))
-            .boxed(),
-        )
+        let stream = stream::iter(self.points.chunks(chunk_size).map(move |chunk| {
+            Ok(MultiPointCollection::from_data(
+                chunk.iter().map(Into::into).collect(),
+                vec![TimeInterval::default(); chunk.len()],
+                HashMap::new(),
+            )?)
+        }))
+        .boxed();
+
+        Ok(inject_faults(stream, ctx))
     }
 }
 
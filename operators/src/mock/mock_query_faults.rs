@@ -0,0 +1,156 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::stream::BoxStream;
+use futures::StreamExt;
+
+use crate::engine::{QueryClock, QueryContext};
+use crate::error::Error;
+use crate::util::random::{split_mix, unit_interval};
+use crate::util::Result;
+
+/// Failures and delays that the mock sources inject into their result streams,
+/// e.g., for testing how operators handle errors, aborts and slow sources.
+///
+/// Random failures and delays are derived from the `seed`, so they are the same on every run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MockQueryFaults {
+    /// The (zero-based) positions of the items of a result stream that are replaced by an error
+    pub failing_items: Vec<usize>,
+    /// The probability in `[0, 1]` that an item is replaced by an error
+    pub failure_rate: f64,
+    /// The delay before each item
+    pub latency: Duration,
+    /// The maximum random delay that is added to the `latency`
+    pub latency_jitter: Duration,
+    pub seed: u64,
+}
+
+impl MockQueryFaults {
+    fn fails(&self, item: usize) -> bool {
+        self.failing_items.contains(&item)
+            || (self.failure_rate > 0. && self.random(2 * item as u64) < self.failure_rate)
+    }
+
+    fn delay(&self, item: usize) -> Duration {
+        self.latency
+            + self
+                .latency_jitter
+                .mul_f64(self.random(2 * item as u64 + 1))
+    }
+
+    fn random(&self, index: u64) -> f64 {
+        unit_interval(split_mix(self.seed.wrapping_add(index)))
+    }
+}
+
+/// Applies the `MockQueryFaults` of the query context, if any, to the results of a mock source.
+///
+/// Delays also advance the `QueryClock` of the query context.
+pub(crate) fn inject_faults<'a, T>(
+    stream: BoxStream<'a, Result<T>>,
+    ctx: &dyn QueryContext,
+) -> BoxStream<'a, Result<T>>
+where
+    T: Send + 'a,
+{
+    let faults = match ctx.extensions().get::<MockQueryFaults>() {
+        Some(faults) => Arc::new(faults.clone()),
+        None => return stream,
+    };
+    let clock = ctx.extensions().get::<QueryClock>().cloned();
+
+    stream
+        .enumerate()
+        .then(move |(item, result)| {
+            let faults = faults.clone();
+            let clock = clock.clone();
+
+            async move {
+                let delay = faults.delay(item);
+                if delay > Duration::ZERO {
+                    tokio::time::sleep(delay).await;
+
+                    if let Some(clock) = clock {
+                        clock.advance(delay);
+                    }
+                }
+
+                if faults.fails(item) {
+                    Err(Error::InjectedFailure { item })
+                } else {
+                    result
+                }
+            }
+        })
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::MockQueryContext;
+    use futures::stream;
+    use geoengine_datatypes::primitives::TimeInstance;
+
+    async fn results(ctx: &MockQueryContext) -> Vec<Option<usize>> {
+        inject_faults(stream::iter((0..10).map(Ok)).boxed(), ctx)
+            .map(Result::ok)
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn failing_items() {
+        let ctx = MockQueryContext::default().with_faults(MockQueryFaults {
+            failing_items: vec![1, 3],
+            ..Default::default()
+        });
+
+        let results = results(&ctx).await;
+
+        assert_eq!(results.len(), 10);
+        assert_eq!(results[..5], [Some(0), None, Some(2), None, Some(4)]);
+    }
+
+    #[tokio::test]
+    async fn failures_depend_on_the_seed() {
+        let faults = |seed| MockQueryFaults {
+            failure_rate: 0.5,
+            seed,
+            ..Default::default()
+        };
+
+        let first = results(&MockQueryContext::default().with_faults(faults(1))).await;
+        let second = results(&MockQueryContext::default().with_faults(faults(1))).await;
+        let other = results(&MockQueryContext::default().with_faults(faults(2))).await;
+
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+        assert!(first.contains(&None));
+    }
+
+    #[tokio::test]
+    async fn latency_advances_the_clock() {
+        let clock = QueryClock::new(TimeInstance::from_millis_unchecked(0));
+
+        let ctx = MockQueryContext::default()
+            .with_clock(clock.clone())
+            .with_faults(MockQueryFaults {
+                latency: Duration::from_millis(2),
+                ..Default::default()
+            });
+
+        assert_eq!(results(&ctx).await.len(), 10);
+        assert_eq!(clock.now(), TimeInstance::from_millis_unchecked(20));
+        assert_eq!(ctx.now(), clock.now());
+    }
+
+    #[tokio::test]
+    async fn without_faults() {
+        assert_eq!(
+            results(&MockQueryContext::default()).await,
+            (0..10).map(Some).collect::<Vec<_>>()
+        );
+    }
+}
@@ -3,6 +3,7 @@ use crate::engine::{
     InitializedRasterOperator, OperatorDatasets, RasterOperator, RasterQueryProcessor,
     RasterResultDescriptor, SourceOperator, TypedRasterQueryProcessor,
 };
+use crate::mock::inject_faults;
 use crate::util::Result;
 use async_trait::async_trait;
 use futures::{stream, stream::StreamExt};
@@ -38,10 +39,10 @@ where
     async fn raster_query<'a>(
         &'a self,
         query: crate::engine::RasterQueryRectangle,
-        _ctx: &'a dyn crate::engine::QueryContext,
+        ctx: &'a dyn crate::engine::QueryContext,
     ) -> Result<futures::stream::BoxStream<crate::util::Result<RasterTile2D<Self::RasterType>>>>
     {
-        Ok(stream::iter(
+        let stream = stream::iter(
             self.data
                 .iter()
                 .filter(move |t| {
@@ -53,7 +54,9 @@ where
                 .cloned()
                 .map(Result::Ok),
         )
-        .boxed())
+        .boxed();
+
+        Ok(inject_faults(stream, ctx))
     }
 }
 
@@ -153,6 +156,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    bands: Vec::new(),
                 },
             },
         }
@@ -1,9 +1,12 @@
 mod mock_dataset_data_source;
 mod mock_feature_collection_source;
 mod mock_point_source;
+mod mock_query_faults;
 mod mock_raster_source;
 
 pub use mock_dataset_data_source::*;
 pub use mock_feature_collection_source::*;
 pub use mock_point_source::*;
+pub(crate) use mock_query_faults::inject_faults;
+pub use mock_query_faults::MockQueryFaults;
 pub use mock_raster_source::*;
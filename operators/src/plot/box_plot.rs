@@ -0,0 +1,525 @@
+use crate::engine::{
+    ExecutionContext, InitializedPlotOperator, InitializedRasterOperator,
+    InitializedVectorOperator, MultipleRasterOrSingleVectorSource, Operator, PlotOperator,
+    PlotQueryProcessor, PlotResultDescriptor, QueryContext, QueryProcessor, QueryRegion,
+    TypedPlotQueryProcessor, TypedRasterQueryProcessor, TypedVectorQueryProcessor,
+    VectorQueryRectangle,
+};
+use crate::error;
+use crate::util::input::MultiRasterOrVectorOperator;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::future::try_join_all;
+use futures::stream::select_all;
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::FeatureCollectionInfos;
+use geoengine_datatypes::plots::{self, BoxPlotAttribute, Plot, PlotData};
+use geoengine_datatypes::primitives::Measurement;
+use geoengine_datatypes::raster::{GridOrEmpty, NoDataValue};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt};
+
+pub const BOXPLOT_OPERATOR_NAME: &str = "BoxPlot";
+
+/// A box plot about one or more numeric attributes of a vector input or about one or more raster inputs.
+///
+/// Each attribute or raster becomes a box that shows the quartiles of its values.
+/// Attributes and rasters without any valid value are left out.
+pub type BoxPlot = Operator<BoxPlotParams, MultipleRasterOrSingleVectorSource>;
+
+/// The parameter spec for `BoxPlot`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BoxPlotParams {
+    /// The numeric attributes of a vector input. Ignored for raster inputs.
+    #[serde(default)]
+    pub column_names: Vec<String>,
+    /// The names of the boxes of raster inputs, which default to `Raster 1`, `Raster 2`, etc.
+    /// Ignored for vector inputs.
+    #[serde(default)]
+    pub raster_names: Vec<String>,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl PlotOperator for BoxPlot {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedPlotOperator>> {
+        match self.sources.source {
+            MultiRasterOrVectorOperator::Raster(rasters) => {
+                ensure!(
+                    !rasters.is_empty(),
+                    error::InvalidOperatorSpec {
+                        reason: "the box plot needs at least one raster".to_string()
+                    }
+                );
+
+                let names = if self.params.raster_names.is_empty() {
+                    (1..=rasters.len())
+                        .map(|i| format!("Raster {}", i))
+                        .collect()
+                } else {
+                    ensure!(
+                        self.params.raster_names.len() == rasters.len(),
+                        error::InvalidOperatorSpec {
+                            reason: "there must be one name per raster".to_string()
+                        }
+                    );
+                    self.params.raster_names
+                };
+
+                let rasters =
+                    try_join_all(rasters.into_iter().map(|s| s.initialize(context))).await?;
+
+                for raster in &rasters {
+                    ensure!(
+                        raster.result_descriptor().number_of_bands() == 1,
+                        error::InvalidOperatorSpec {
+                            reason: "multiband rasters cannot be summarized in a box plot"
+                                .to_string()
+                        }
+                    );
+                }
+
+                let measurement = rasters[0].result_descriptor().measurement.clone();
+                let measurement = if rasters
+                    .iter()
+                    .all(|raster| raster.result_descriptor().measurement == measurement)
+                {
+                    measurement
+                } else {
+                    Measurement::Unitless
+                };
+
+                Ok(InitializedBoxPlot {
+                    result_descriptor: PlotResultDescriptor {},
+                    names,
+                    measurement,
+                    source: rasters,
+                }
+                .boxed())
+            }
+            MultiRasterOrVectorOperator::Vector(vector) => {
+                ensure!(
+                    !self.params.column_names.is_empty(),
+                    error::InvalidOperatorSpec {
+                        reason: "the box plot needs at least one column".to_string()
+                    }
+                );
+
+                let vector = vector.initialize(context).await?;
+
+                for column in &self.params.column_names {
+                    let data_type =
+                        vector
+                            .result_descriptor()
+                            .columns
+                            .get(column)
+                            .ok_or_else(|| error::Error::ColumnDoesNotExist {
+                                column: column.clone(),
+                            })?;
+
+                    ensure!(
+                        data_type.is_numeric(),
+                        error::InvalidOperatorSpec {
+                            reason: format!("column `{}` must be numeric", column)
+                        }
+                    );
+                }
+
+                Ok(InitializedBoxPlot {
+                    result_descriptor: PlotResultDescriptor {},
+                    names: self.params.column_names,
+                    measurement: Measurement::Unitless, // TODO: incorporate measurement once it is there
+                    source: vector,
+                }
+                .boxed())
+            }
+        }
+    }
+}
+
+/// The initialization of `BoxPlot`
+pub struct InitializedBoxPlot<Op> {
+    result_descriptor: PlotResultDescriptor,
+    /// The names of the boxes, i.e., the columns or the names of the rasters
+    names: Vec<String>,
+    measurement: Measurement,
+    source: Op,
+}
+
+impl InitializedPlotOperator for InitializedBoxPlot<Vec<Box<dyn InitializedRasterOperator>>> {
+    fn query_processor(&self) -> Result<TypedPlotQueryProcessor> {
+        let processor = BoxPlotRasterQueryProcessor {
+            input: self
+                .source
+                .iter()
+                .map(|source| source.query_processor())
+                .collect::<Result<Vec<_>>>()?,
+            names: self.names.clone(),
+            measurement: self.measurement.clone(),
+        };
+
+        Ok(TypedPlotQueryProcessor::JsonVega(processor.boxed()))
+    }
+
+    fn result_descriptor(&self) -> &PlotResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+impl InitializedPlotOperator for InitializedBoxPlot<Box<dyn InitializedVectorOperator>> {
+    fn query_processor(&self) -> Result<TypedPlotQueryProcessor> {
+        let processor = BoxPlotVectorQueryProcessor {
+            input: self.source.query_processor()?,
+            column_names: self.names.clone(),
+            measurement: self.measurement.clone(),
+        };
+
+        Ok(TypedPlotQueryProcessor::JsonVega(processor.boxed()))
+    }
+
+    fn result_descriptor(&self) -> &PlotResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// A query processor that calculates the box plot about its raster inputs.
+pub struct BoxPlotRasterQueryProcessor {
+    input: Vec<TypedRasterQueryProcessor>,
+    names: Vec<String>,
+    measurement: Measurement,
+}
+
+/// A query processor that calculates the box plot about its vector input.
+pub struct BoxPlotVectorQueryProcessor {
+    input: TypedVectorQueryProcessor,
+    column_names: Vec<String>,
+    measurement: Measurement,
+}
+
+#[async_trait]
+impl PlotQueryProcessor for BoxPlotRasterQueryProcessor {
+    type OutputFormat = PlotData;
+
+    fn plot_type(&self) -> &'static str {
+        BOXPLOT_OPERATOR_NAME
+    }
+
+    async fn plot_query<'p>(
+        &'p self,
+        query: VectorQueryRectangle,
+        ctx: &'p dyn QueryContext,
+    ) -> Result<Self::OutputFormat> {
+        let mut queries = Vec::with_capacity(self.input.len());
+        for (i, raster_processor) in self.input.iter().enumerate() {
+            queries.push(
+                call_on_generic_raster_processor!(raster_processor, processor => {
+                    processor.query(query.into(), ctx).await?
+                             .map(move |r| r.map(|tile| (i, tile.convert::<f64>())))
+                             .boxed()
+                }),
+            );
+        }
+
+        let region = ctx.extensions().get::<QueryRegion>();
+
+        let values = select_all(queries)
+            .try_fold(
+                vec![Vec::new(); self.input.len()],
+                |mut values: Vec<Vec<f64>>, (i, tile)| async move {
+                    let tile_information = tile.tile_information();
+
+                    if let GridOrEmpty::Grid(grid) = tile.grid_array {
+                        let is_valid = |value: &f64| !grid.is_no_data(*value);
+
+                        match region {
+                            Some(region) => values[i].extend(
+                                region
+                                    .masked_values(&tile_information, &grid.data)
+                                    .into_iter()
+                                    .filter(is_valid),
+                            ),
+                            None => values[i].extend(grid.data.iter().copied().filter(is_valid)),
+                        }
+                    }
+
+                    Ok(values)
+                },
+            )
+            .await?;
+
+        box_plot(&self.names, values, &self.measurement)
+    }
+}
+
+#[async_trait]
+impl PlotQueryProcessor for BoxPlotVectorQueryProcessor {
+    type OutputFormat = PlotData;
+
+    fn plot_type(&self) -> &'static str {
+        BOXPLOT_OPERATOR_NAME
+    }
+
+    async fn plot_query<'p>(
+        &'p self,
+        query: VectorQueryRectangle,
+        ctx: &'p dyn QueryContext,
+    ) -> Result<Self::OutputFormat> {
+        let values = call_on_generic_vector_processor!(&self.input, processor => {
+            processor
+                .query(query, ctx)
+                .await?
+                .try_fold(
+                    vec![Vec::new(); self.column_names.len()],
+                    |mut values: Vec<Vec<f64>>, collection| async move {
+                        for (column, column_values) in self.column_names.iter().zip(&mut values) {
+                            let data = collection.data(column)?;
+                            column_values.extend(data.float_options_iter().flatten());
+                        }
+
+                        Ok(values)
+                    },
+                )
+                .await?
+        });
+
+        box_plot(&self.column_names, values, &self.measurement)
+    }
+}
+
+fn box_plot(
+    names: &[String],
+    values: Vec<Vec<f64>>,
+    measurement: &Measurement,
+) -> Result<PlotData> {
+    let boxes = names
+        .iter()
+        .zip(values)
+        .filter_map(|(name, values)| BoxPlotAttribute::from_values(name.clone(), values))
+        .collect();
+
+    plots::BoxPlot::new(boxes, measurement.clone())
+        .to_vega_embeddable(false)
+        .context(error::DataType)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{
+        MockExecutionContext, MockQueryContext, RasterOperator, RasterResultDescriptor,
+        VectorOperator,
+    };
+    use crate::mock::{MockFeatureCollectionSource, MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::collections::DataCollection;
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, FeatureData, NoGeometry, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::raster::{Grid2D, RasterDataType, RasterTile2D, TileInformation};
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+
+    fn raster(data: Vec<u8>) -> Box<dyn RasterOperator> {
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![RasterTile2D::new_with_tile_info(
+                    TimeInterval::default(),
+                    TileInformation {
+                        global_geo_transform: Default::default(),
+                        global_tile_position: [0, 0].into(),
+                        tile_size_in_pixels: [2, 3].into(),
+                    },
+                    Grid2D::new([2, 3].into(), data, Some(0)).unwrap().into(),
+                )],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: Some(0.),
+                    bands: Vec::new(),
+                },
+            },
+        }
+        .boxed()
+    }
+
+    async fn plot(operator: BoxPlot) -> Result<serde_json::Value> {
+        let processor = operator
+            .boxed()
+            .initialize(&MockExecutionContext::default())
+            .await?
+            .query_processor()?
+            .json_vega()
+            .unwrap();
+
+        let plot = processor
+            .plot_query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((0., -2.).into(), (3., 0.).into()).unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::new(0),
+            )
+            .await?;
+
+        Ok(plot.data_values().unwrap())
+    }
+
+    #[test]
+    fn serialization() {
+        let box_plot = BoxPlot {
+            params: BoxPlotParams {
+                column_names: vec!["foo".to_string()],
+                raster_names: vec![],
+            },
+            sources: MockFeatureCollectionSource::<NoGeometry>::multiple(vec![])
+                .boxed()
+                .into(),
+        };
+
+        let serialized = serde_json::to_value(&box_plot).unwrap();
+
+        assert_eq!(
+            serialized,
+            serde_json::json!({
+                "params": {
+                    "columnNames": ["foo"],
+                    "rasterNames": [],
+                },
+                "sources": {
+                    "source": {
+                        "type": "MockFeatureCollectionSourceNoGeometry",
+                        "params": {
+                            "collections": []
+                        }
+                    }
+                }
+            })
+        );
+
+        let deserialized: BoxPlot = serde_json::from_value(serialized).unwrap();
+
+        assert_eq!(deserialized.params, box_plot.params);
+    }
+
+    #[tokio::test]
+    async fn rasters() {
+        let data = plot(BoxPlot {
+            params: BoxPlotParams {
+                column_names: vec![],
+                raster_names: vec!["foo".to_string(), "bar".to_string()],
+            },
+            sources: vec![raster(vec![1, 2, 0, 3, 4, 5]), raster(vec![0; 6])].into(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            data,
+            serde_json::json!([{
+                "name": "foo",
+                "lowerWhisker": 1.0,
+                "q1": 2.0,
+                "median": 3.0,
+                "q3": 4.0,
+                "upperWhisker": 5.0,
+            }])
+        );
+    }
+
+    #[tokio::test]
+    async fn columns() {
+        let source = MockFeatureCollectionSource::single(
+            DataCollection::from_slices(
+                &[] as &[NoGeometry],
+                &[TimeInterval::default(); 5],
+                &[
+                    ("foo", FeatureData::Int(vec![1, 2, 3, 4, 5])),
+                    (
+                        "bar",
+                        FeatureData::NullableFloat(vec![Some(2.), None, Some(4.), Some(6.), None]),
+                    ),
+                ],
+            )
+            .unwrap(),
+        )
+        .boxed();
+
+        let data = plot(BoxPlot {
+            params: BoxPlotParams {
+                column_names: vec!["foo".to_string(), "bar".to_string()],
+                raster_names: vec![],
+            },
+            sources: source.into(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            data,
+            serde_json::json!([{
+                "name": "foo",
+                "lowerWhisker": 1.0,
+                "q1": 2.0,
+                "median": 3.0,
+                "q3": 4.0,
+                "upperWhisker": 5.0,
+            }, {
+                "name": "bar",
+                "lowerWhisker": 2.0,
+                "q1": 3.0,
+                "median": 4.0,
+                "q3": 5.0,
+                "upperWhisker": 6.0,
+            }])
+        );
+    }
+
+    #[tokio::test]
+    async fn raster_names_must_match() {
+        let result = plot(BoxPlot {
+            params: BoxPlotParams {
+                column_names: vec![],
+                raster_names: vec!["foo".to_string()],
+            },
+            sources: vec![raster(vec![1; 6]), raster(vec![1; 6])].into(),
+        })
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(error::Error::InvalidOperatorSpec { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn non_numeric_column() {
+        let source = MockFeatureCollectionSource::single(
+            DataCollection::from_slices(
+                &[] as &[NoGeometry],
+                &[TimeInterval::default()],
+                &[("foo", FeatureData::Text(vec!["a".to_string()]))],
+            )
+            .unwrap(),
+        )
+        .boxed();
+
+        let result = plot(BoxPlot {
+            params: BoxPlotParams {
+                column_names: vec!["foo".to_string()],
+                raster_names: vec![],
+            },
+            sources: source.into(),
+        })
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(error::Error::InvalidOperatorSpec { .. })
+        ));
+    }
+}
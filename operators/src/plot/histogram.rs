@@ -8,8 +8,8 @@ use crate::{
     engine::{
         ExecutionContext, InitializedPlotOperator, InitializedRasterOperator,
         InitializedVectorOperator, Operator, PlotOperator, PlotQueryProcessor,
-        PlotResultDescriptor, QueryContext, SingleRasterOrVectorSource, TypedPlotQueryProcessor,
-        TypedRasterQueryProcessor, TypedVectorQueryProcessor,
+        PlotResultDescriptor, QueryContext, QueryRegion, SingleRasterOrVectorSource,
+        TypedPlotQueryProcessor, TypedRasterQueryProcessor, TypedVectorQueryProcessor,
     },
     util::input::RasterOrVectorOperator,
 };
@@ -17,7 +17,7 @@ use async_trait::async_trait;
 use float_cmp::approx_eq;
 use futures::stream::BoxStream;
 use futures::{StreamExt, TryFutureExt};
-use geoengine_datatypes::plots::{Plot, PlotData};
+use geoengine_datatypes::plots::{Plot, PlotData, SelectionFilter};
 use geoengine_datatypes::primitives::{
     DataRef, FeatureDataRef, FeatureDataType, Geometry, Measurement,
 };
@@ -26,6 +26,7 @@ use geoengine_datatypes::{
     collections::{FeatureCollection, FeatureCollectionInfos},
     raster::GridSize,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use snafu::{ensure, OptionExt};
 use std::convert::TryFrom;
@@ -39,7 +40,7 @@ pub const HISTOGRAM_OPERATOR_NAME: &str = "Histogram";
 pub type Histogram = Operator<HistogramParams, SingleRasterOrVectorSource>;
 
 /// The parameter spec for `Histogram`
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct HistogramParams {
     /// Name of the (numeric) attribute to compute the histogram on. Ignored for operation on rasters.
@@ -56,7 +57,7 @@ pub struct HistogramParams {
 string_token!(Data, "data");
 
 /// Let the bounds either be computed or given.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum HistogramBounds {
     Data(Data),
@@ -81,12 +82,17 @@ impl PlotOperator for Histogram {
                     }
                 );
 
-                InitializedHistogram::new(
-                    PlotResultDescriptor {},
-                    self.params,
-                    raster_source.initialize(context).await?,
-                )
-                .boxed()
+                let raster_source = raster_source.initialize(context).await?;
+
+                ensure!(
+                    raster_source.result_descriptor().number_of_bands() == 1,
+                    error::InvalidOperatorSpec {
+                        reason: "multiband rasters cannot be summarized in a histogram".to_string()
+                    }
+                );
+
+                InitializedHistogram::new(PlotResultDescriptor {}, self.params, raster_source)
+                    .boxed()
             }
             RasterOrVectorOperator::Vector(vector_source) => {
                 let column_name =
@@ -106,7 +112,12 @@ impl PlotOperator for Histogram {
                             column: column_name.to_string(),
                         });
                     }
-                    Some(FeatureDataType::Category | FeatureDataType::Text) => {
+                    Some(
+                        FeatureDataType::Category
+                        | FeatureDataType::Text
+                        | FeatureDataType::Bytes
+                        | FeatureDataType::Dictionary,
+                    ) => {
                         // TODO: incorporate category data
                         return Err(Error::InvalidOperatorSpec {
                             reason: format!("column `{}` must be numerical", column_name),
@@ -274,15 +285,24 @@ impl HistogramRasterQueryProcessor {
         async fn process_metadata<T: Pixel>(
             mut input: BoxStream<'_, Result<RasterTile2D<T>>>,
             metadata: HistogramMetadataOptions,
+            region: Option<&QueryRegion>,
         ) -> Result<HistogramMetadata> {
             let mut computed_metadata = HistogramMetadataInProgress::default();
 
             while let Some(tile) = input.next().await {
-                match tile?.grid_array {
-                    geoengine_datatypes::raster::GridOrEmpty::Grid(g) => {
+                let tile = tile?;
+                let tile_information = tile.tile_information();
+                match (tile.grid_array, region) {
+                    (geoengine_datatypes::raster::GridOrEmpty::Grid(g), None) => {
                         computed_metadata.add_raster_batch(&g.data, g.no_data_value);
                     }
-                    geoengine_datatypes::raster::GridOrEmpty::Empty(_) => {} // TODO: find out if we really do nothing for empty tiles?
+                    (geoengine_datatypes::raster::GridOrEmpty::Grid(g), Some(region)) => {
+                        computed_metadata.add_raster_batch(
+                            &region.masked_values(&tile_information, &g.data),
+                            g.no_data_value,
+                        );
+                    }
+                    (geoengine_datatypes::raster::GridOrEmpty::Empty(_), _) => {} // TODO: find out if we really do nothing for empty tiles?
                 }
             }
 
@@ -296,7 +316,7 @@ impl HistogramRasterQueryProcessor {
         // TODO: compute only number of buckets if possible
 
         call_on_generic_raster_processor!(&self.input, processor => {
-            process_metadata(processor.query(query.into(), ctx).await?, self.metadata).await
+            process_metadata(processor.query(query.into(), ctx).await?, self.metadata, ctx.extensions().get::<QueryRegion>()).await
         })
     }
 
@@ -315,15 +335,20 @@ impl HistogramRasterQueryProcessor {
         .build()
         .map_err(Error::from)?;
 
+        let region = ctx.extensions().get::<QueryRegion>();
+
         call_on_generic_raster_processor!(&self.input, processor => {
             let mut query = processor.query(query.into(), ctx).await?;
 
             while let Some(tile) = query.next().await {
-
-
-                match tile?.grid_array {
-                    geoengine_datatypes::raster::GridOrEmpty::Grid(g) => histogram.add_raster_data(&g.data, g.no_data_value),
-                    geoengine_datatypes::raster::GridOrEmpty::Empty(n) => histogram.add_nodata_batch(n.number_of_elements() as u64) // TODO: why u64?
+                let tile = tile?;
+                let tile_information = tile.tile_information();
+
+                match (tile.grid_array, region) {
+                    (geoengine_datatypes::raster::GridOrEmpty::Grid(g), None) => histogram.add_raster_data(&g.data, g.no_data_value),
+                    (geoengine_datatypes::raster::GridOrEmpty::Grid(g), Some(region)) => histogram.add_raster_data(&region.masked_values(&tile_information, &g.data), g.no_data_value),
+                    (geoengine_datatypes::raster::GridOrEmpty::Empty(n), None) => histogram.add_nodata_batch(n.number_of_elements() as u64), // TODO: why u64?
+                    (geoengine_datatypes::raster::GridOrEmpty::Empty(_), Some(region)) => histogram.add_nodata_batch(region.number_of_pixels_inside(&tile_information) as u64),
                 }
             }
         });
@@ -348,6 +373,23 @@ impl HistogramRasterQueryProcessor {
 }
 
 impl HistogramVectorQueryProcessor {
+    /// Creates the chart, whose range selection filters the histogram's column if it is interactive
+    fn chart(&self, histogram: &geoengine_datatypes::plots::Histogram) -> Result<PlotData> {
+        let mut chart = histogram.to_vega_embeddable(self.interactive)?;
+
+        chart.metadata = chart.metadata.with_filter(SelectionFilter {
+            operator: "ColumnRangeFilter".to_string(),
+            params: serde_json::json!({
+                "column": self.column_name,
+                "keepNulls": false,
+            }),
+            range_parameter: "ranges".to_string(),
+            selection_field: geoengine_datatypes::plots::Histogram::SELECTION_FIELD.to_string(),
+        });
+
+        Ok(chart)
+    }
+
     async fn preprocess<'p>(
         &'p self,
         query: VectorQueryRectangle,
@@ -412,9 +454,7 @@ impl HistogramVectorQueryProcessor {
             }
         });
 
-        let chart = histogram.to_vega_embeddable(self.interactive)?;
-
-        Ok(chart)
+        self.chart(&histogram)
     }
 
     fn empty_histogram(
@@ -425,9 +465,7 @@ impl HistogramVectorQueryProcessor {
                 .build()
                 .map_err(Error::from)?;
 
-        let chart = histogram.to_vega_embeddable(self.interactive)?;
-
-        Ok(chart)
+        self.chart(&histogram)
     }
 }
 
@@ -553,7 +591,10 @@ impl HistogramMetadataInProgress {
             FeatureDataRef::Float(values) => {
                 add_data_ref(self, &values);
             }
-            FeatureDataRef::Category(_) | FeatureDataRef::Text(_) => {
+            FeatureDataRef::Category(_)
+            | FeatureDataRef::Text(_)
+            | FeatureDataRef::Bytes(_)
+            | FeatureDataRef::Dictionary(_) => {
                 // do nothing since we don't support them
                 // TODO: fill with live once we support category and text types
             }
@@ -591,6 +632,7 @@ mod tests {
     };
     use chrono::NaiveDate;
     use geoengine_datatypes::dataset::{DatasetId, InternalDatasetId};
+    use geoengine_datatypes::plots::PlotMetaData;
     use geoengine_datatypes::primitives::{
         BoundingBox2D, FeatureData, NoGeometry, SpatialResolution, TimeInterval,
     };
@@ -724,6 +766,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    bands: Vec::new(),
                 },
             },
         }
@@ -879,14 +922,29 @@ mod tests {
             .await
             .unwrap();
 
-        assert_eq!(
-            result,
+        let expected =
             geoengine_datatypes::plots::Histogram::builder(3, 0., 8., Measurement::Unitless)
                 .counts(vec![4, 5, 3])
                 .build()
                 .unwrap()
                 .to_vega_embeddable(true)
-                .unwrap()
+                .unwrap();
+
+        assert_eq!(result.vega_string, expected.vega_string);
+        assert_eq!(
+            result.metadata,
+            PlotMetaData::Selection {
+                selection_name: "range_selection".to_string(),
+                filter: Some(SelectionFilter {
+                    operator: "ColumnRangeFilter".to_string(),
+                    params: serde_json::json!({
+                        "column": "foo",
+                        "keepNulls": false,
+                    }),
+                    range_parameter: "ranges".to_string(),
+                    selection_field: "binStart".to_string(),
+                }),
+            }
         );
     }
 
@@ -1003,12 +1061,15 @@ mod tests {
                             "name".to_string(),
                             "website".to_string(),
                         ],
+                        dictionary: vec![],
                         rename: None,
                     }),
                     force_ogr_time_filter: false,
                     force_ogr_spatial_filter: false,
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
+                    encoding: None,
+                    layer_spatial_reference: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -1065,6 +1126,7 @@ mod tests {
                         spatial_reference: SpatialReference::epsg_4326().into(),
                         measurement: Measurement::Unitless,
                         no_data_value: no_data_value.map(AsPrimitive::as_),
+                        bands: Vec::new(),
                     },
                 },
             }
@@ -1252,6 +1314,7 @@ mod tests {
                         spatial_reference: SpatialReference::epsg_4326().into(),
                         measurement: Measurement::Unitless,
                         no_data_value: no_data_value.map(AsPrimitive::as_),
+                        bands: Vec::new(),
                     },
                 },
             }
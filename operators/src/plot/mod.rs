@@ -1,16 +1,36 @@
+mod box_plot;
 mod histogram;
+mod pie_chart;
+mod scatter_plot;
 mod statistics;
+mod temporal_coverage;
 mod temporal_raster_mean_plot;
 mod temporal_vector_line_plot;
 
+pub use self::box_plot::{
+    BoxPlot, BoxPlotParams, BoxPlotRasterQueryProcessor, BoxPlotVectorQueryProcessor,
+    InitializedBoxPlot,
+};
 pub use self::histogram::{
     Histogram, HistogramBounds, HistogramParams, HistogramRasterQueryProcessor,
     HistogramVectorQueryProcessor, InitializedHistogram,
 };
+pub use self::pie_chart::{InitializedPieChart, PieChart, PieChartParams, PieChartQueryProcessor};
+pub use self::scatter_plot::{
+    InitializedScatterPlot, ScatterPlot, ScatterPlotDensity, ScatterPlotParams,
+    ScatterPlotQueryProcessor,
+};
 pub use self::statistics::{
     InitializedStatistics, Statistics, StatisticsParams, StatisticsQueryProcessor,
 };
+pub use self::temporal_coverage::{
+    InitializedTemporalCoverage, TemporalCoverage, TemporalCoverageParams,
+    TemporalCoverageRasterQueryProcessor, TemporalCoverageVectorQueryProcessor,
+};
 pub use self::temporal_raster_mean_plot::{
     InitializedMeanRasterPixelValuesOverTime, MeanRasterPixelValuesOverTime,
     MeanRasterPixelValuesOverTimeParams, MeanRasterPixelValuesOverTimeQueryProcessor,
 };
+pub use self::temporal_vector_line_plot::{
+    FeatureAttributeValuesOverTime, FeatureAttributeValuesOverTimeParams,
+};
@@ -0,0 +1,314 @@
+use crate::engine::{
+    ExecutionContext, InitializedPlotOperator, InitializedVectorOperator, Operator, PlotOperator,
+    PlotQueryProcessor, PlotResultDescriptor, QueryContext, SingleVectorSource,
+    TypedPlotQueryProcessor, VectorQueryProcessor, VectorQueryRectangle,
+};
+use crate::error;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use geoengine_datatypes::collections::{FeatureCollection, FeatureCollectionInfos};
+use geoengine_datatypes::plots::{self, PieChartSlice, Plot, PlotData};
+use geoengine_datatypes::primitives::{FeatureDataType, Geometry};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt};
+use std::collections::HashMap;
+
+pub const PIE_CHART_OPERATOR_NAME: &str = "PieChart";
+
+/// The label of the slice that sums up the least frequent categories
+const OTHER_SLICE_LABEL: &str = "other";
+
+/// A pie chart of the number of occurrences of the categories of a vector column.
+///
+/// Features with a null value are left out.
+pub type PieChart = Operator<PieChartParams, SingleVectorSource>;
+
+/// The parameter spec for `PieChart`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PieChartParams {
+    /// A text, category or integer column
+    pub column_name: String,
+    /// The maximum number of slices.
+    /// If there are more categories, the least frequent ones are combined into a slice "other".
+    #[serde(default = "default_max_slices")]
+    pub max_slices: usize,
+    /// Draws the chart as a donut instead of a pie
+    #[serde(default)]
+    pub donut: bool,
+}
+
+fn default_max_slices() -> usize {
+    10
+}
+
+#[typetag::serde]
+#[async_trait]
+impl PlotOperator for PieChart {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedPlotOperator>> {
+        ensure!(
+            self.params.max_slices > 0,
+            error::InvalidOperatorSpec {
+                reason: "the pie chart needs at least one slice".to_string()
+            }
+        );
+
+        let source = self.sources.vector.initialize(context).await?;
+
+        let data_type = source
+            .result_descriptor()
+            .columns
+            .get(&self.params.column_name)
+            .ok_or_else(|| error::Error::ColumnDoesNotExist {
+                column: self.params.column_name.clone(),
+            })?;
+
+        ensure!(
+            matches!(
+                data_type,
+                FeatureDataType::Text
+                    | FeatureDataType::Dictionary
+                    | FeatureDataType::Category
+                    | FeatureDataType::Int
+            ),
+            error::InvalidOperatorSpec {
+                reason: format!(
+                    "column `{}` must contain categories, texts or integers",
+                    self.params.column_name
+                )
+            }
+        );
+
+        Ok(InitializedPieChart {
+            result_descriptor: PlotResultDescriptor {},
+            vector_source: source,
+            params: self.params,
+        }
+        .boxed())
+    }
+}
+
+/// The initialization of `PieChart`
+pub struct InitializedPieChart {
+    result_descriptor: PlotResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    params: PieChartParams,
+}
+
+impl InitializedPlotOperator for InitializedPieChart {
+    fn query_processor(&self) -> Result<TypedPlotQueryProcessor> {
+        let input_processor = self.vector_source.query_processor()?;
+
+        let processor = call_on_generic_vector_processor!(input_processor, features => {
+            PieChartQueryProcessor { params: self.params.clone(), features }.boxed()
+        });
+
+        Ok(TypedPlotQueryProcessor::JsonVega(processor))
+    }
+
+    fn result_descriptor(&self) -> &PlotResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// A query processor that calculates the `PieChart` on its input.
+pub struct PieChartQueryProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    params: PieChartParams,
+    features: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+}
+
+#[async_trait]
+impl<G> PlotQueryProcessor for PieChartQueryProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type OutputFormat = PlotData;
+
+    fn plot_type(&self) -> &'static str {
+        PIE_CHART_OPERATOR_NAME
+    }
+
+    async fn plot_query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<Self::OutputFormat> {
+        let counts = self
+            .features
+            .query(query, ctx)
+            .await?
+            .try_fold(
+                HashMap::<String, u64>::new(),
+                |mut counts, features| async move {
+                    let data = features.data(&self.params.column_name)?;
+
+                    for (value, is_null) in data.strings_iter().zip(data.nulls()) {
+                        if !is_null {
+                            *counts.entry(value).or_default() += 1;
+                        }
+                    }
+
+                    Ok(counts)
+                },
+            )
+            .await?;
+
+        let slices = slices(counts, self.params.max_slices);
+
+        plots::PieChart::new(slices, self.params.column_name.clone(), self.params.donut)
+            .to_vega_embeddable(false)
+            .context(error::DataType)
+    }
+}
+
+/// Sorts the categories by their number of occurrences and combines the tail into one slice
+/// if there are more than `max_slices` categories
+fn slices(counts: HashMap<String, u64>, max_slices: usize) -> Vec<PieChartSlice> {
+    let mut slices: Vec<PieChartSlice> = counts
+        .into_iter()
+        .map(|(label, count)| PieChartSlice { label, count })
+        .collect();
+
+    slices.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.label.cmp(&b.label)));
+
+    if slices.len() > max_slices {
+        let other_count = slices
+            .drain(max_slices - 1..)
+            .map(|slice| slice.count)
+            .sum();
+
+        slices.push(PieChartSlice {
+            label: OTHER_SLICE_LABEL.to_string(),
+            count: other_count,
+        });
+    }
+
+    slices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, VectorOperator};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::DataCollection;
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, FeatureData, NoGeometry, SpatialResolution, TimeInterval,
+    };
+
+    fn source() -> Box<dyn VectorOperator> {
+        MockFeatureCollectionSource::single(
+            DataCollection::from_slices(
+                &[] as &[NoGeometry],
+                &[TimeInterval::default(); 7],
+                &[
+                    (
+                        "landcover",
+                        FeatureData::NullableText(vec![
+                            Some("forest".to_string()),
+                            Some("water".to_string()),
+                            Some("forest".to_string()),
+                            None,
+                            Some("urban".to_string()),
+                            Some("cropland".to_string()),
+                            Some("forest".to_string()),
+                        ]),
+                    ),
+                    ("height", FeatureData::Float(vec![0.; 7])),
+                ],
+            )
+            .unwrap(),
+        )
+        .boxed()
+    }
+
+    async fn plot(params: PieChartParams) -> Result<serde_json::Value> {
+        let operator = PieChart {
+            params,
+            sources: source().into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await?;
+
+        let query_processor = operator.query_processor()?.json_vega().unwrap();
+
+        let plot = query_processor
+            .plot_query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((-180., -90.).into(), (180., 90.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::new(0),
+            )
+            .await?;
+
+        Ok(plot.data_values().unwrap())
+    }
+
+    #[tokio::test]
+    async fn counts() {
+        let data = plot(PieChartParams {
+            column_name: "landcover".to_string(),
+            max_slices: 10,
+            donut: false,
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            data,
+            serde_json::json!([
+                {"label": "forest", "count": 3},
+                {"label": "cropland", "count": 1},
+                {"label": "urban", "count": 1},
+                {"label": "water", "count": 1},
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn other_slice() {
+        let data = plot(PieChartParams {
+            column_name: "landcover".to_string(),
+            max_slices: 2,
+            donut: true,
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            data,
+            serde_json::json!([
+                {"label": "forest", "count": 3},
+                {"label": "other", "count": 3},
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn non_categorical_column() {
+        let result = plot(PieChartParams {
+            column_name: "height".to_string(),
+            max_slices: 10,
+            donut: false,
+        })
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(error::Error::InvalidOperatorSpec { .. })
+        ));
+    }
+}
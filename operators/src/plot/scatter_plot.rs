@@ -0,0 +1,326 @@
+use crate::engine::{
+    ExecutionContext, InitializedPlotOperator, InitializedVectorOperator, Operator, PlotOperator,
+    PlotQueryProcessor, PlotResultDescriptor, QueryContext, QueryProcessor, SingleVectorSource,
+    TypedPlotQueryProcessor, VectorQueryProcessor, VectorQueryRectangle,
+};
+use crate::error;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use geoengine_datatypes::collections::{FeatureCollection, FeatureCollectionInfos};
+use geoengine_datatypes::plots::{self, Plot, PlotData};
+use geoengine_datatypes::primitives::{Coordinate2D, Geometry};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt};
+
+pub const SCATTER_PLOT_OPERATOR_NAME: &str = "ScatterPlot";
+
+/// A scatter plot of two numeric attributes of a vector input.
+///
+/// Features with a null value in either column are left out.
+pub type ScatterPlot = Operator<ScatterPlotParams, SingleVectorSource>;
+
+/// The parameter spec for `ScatterPlot`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScatterPlotParams {
+    /// The numeric column that is shown on the x-axis
+    pub column_x: String,
+    /// The numeric column that is shown on the y-axis
+    pub column_y: String,
+    /// Counts the points in bins instead of drawing each of them if there are many
+    #[serde(default)]
+    pub density: Option<ScatterPlotDensity>,
+}
+
+/// When and how to turn a scatter plot into a density plot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScatterPlotDensity {
+    /// The number of points above which the plot shows their density
+    #[serde(default = "default_density_threshold")]
+    pub threshold: usize,
+    /// The number of bins along each axis
+    #[serde(default = "default_density_bins")]
+    pub bins: usize,
+}
+
+fn default_density_threshold() -> usize {
+    10_000
+}
+
+fn default_density_bins() -> usize {
+    50
+}
+
+#[typetag::serde]
+#[async_trait]
+impl PlotOperator for ScatterPlot {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedPlotOperator>> {
+        if let Some(density) = &self.params.density {
+            ensure!(
+                density.bins > 0,
+                error::InvalidOperatorSpec {
+                    reason: "the density plot needs at least one bin".to_string()
+                }
+            );
+        }
+
+        let source = self.sources.vector.initialize(context).await?;
+        let columns = &source.result_descriptor().columns;
+
+        for column in [&self.params.column_x, &self.params.column_y] {
+            let data_type =
+                columns
+                    .get(column)
+                    .ok_or_else(|| error::Error::ColumnDoesNotExist {
+                        column: column.clone(),
+                    })?;
+
+            ensure!(
+                data_type.is_numeric(),
+                error::InvalidOperatorSpec {
+                    reason: format!("column `{}` must be numeric", column)
+                }
+            );
+        }
+
+        Ok(InitializedScatterPlot {
+            result_descriptor: PlotResultDescriptor {},
+            vector_source: source,
+            params: self.params,
+        }
+        .boxed())
+    }
+}
+
+/// The initialization of `ScatterPlot`
+pub struct InitializedScatterPlot {
+    result_descriptor: PlotResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    params: ScatterPlotParams,
+}
+
+impl InitializedPlotOperator for InitializedScatterPlot {
+    fn query_processor(&self) -> Result<TypedPlotQueryProcessor> {
+        let input_processor = self.vector_source.query_processor()?;
+
+        let processor = call_on_generic_vector_processor!(input_processor, features => {
+            ScatterPlotQueryProcessor { params: self.params.clone(), features }.boxed()
+        });
+
+        Ok(TypedPlotQueryProcessor::JsonVega(processor))
+    }
+
+    fn result_descriptor(&self) -> &PlotResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// A query processor that calculates the `ScatterPlot` on its input.
+pub struct ScatterPlotQueryProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    params: ScatterPlotParams,
+    features: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+}
+
+#[async_trait]
+impl<G> PlotQueryProcessor for ScatterPlotQueryProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type OutputFormat = PlotData;
+
+    fn plot_type(&self) -> &'static str {
+        SCATTER_PLOT_OPERATOR_NAME
+    }
+
+    async fn plot_query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<Self::OutputFormat> {
+        let points = self
+            .features
+            .query(query, ctx)
+            .await?
+            .try_fold(Vec::new(), |mut points, features| async move {
+                let xs = features.data(&self.params.column_x)?;
+                let ys = features.data(&self.params.column_y)?;
+
+                points.extend(
+                    xs.float_options_iter()
+                        .zip(ys.float_options_iter())
+                        .filter_map(|(x, y)| Some(Coordinate2D::new(x?, y?))),
+                );
+
+                Ok(points)
+            })
+            .await?;
+
+        let x_label = self.params.column_x.clone();
+        let y_label = self.params.column_y.clone();
+
+        match &self.params.density {
+            Some(density) if points.len() > density.threshold => {
+                plots::DensityScatterPlot::new(x_label, y_label, &points, density.bins)
+                    .to_vega_embeddable(false)
+            }
+            _ => plots::ScatterPlot::new(x_label, y_label, points).to_vega_embeddable(false),
+        }
+        .context(error::DataType)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, VectorOperator};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::DataCollection;
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, FeatureData, NoGeometry, SpatialResolution, TimeInterval,
+    };
+
+    fn source() -> Box<dyn VectorOperator> {
+        MockFeatureCollectionSource::single(
+            DataCollection::from_slices(
+                &[] as &[NoGeometry],
+                &[TimeInterval::default(); 4],
+                &[
+                    (
+                        "foo",
+                        FeatureData::NullableInt(vec![Some(1), Some(2), None, Some(4)]),
+                    ),
+                    ("bar", FeatureData::Float(vec![1.5, 2.5, 3.5, 4.5])),
+                    ("baz", FeatureData::Text(vec![String::new(); 4])),
+                ],
+            )
+            .unwrap(),
+        )
+        .boxed()
+    }
+
+    async fn plot(params: ScatterPlotParams) -> Result<serde_json::Value> {
+        let operator = ScatterPlot {
+            params,
+            sources: source().into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await?;
+
+        let query_processor = operator.query_processor()?.json_vega().unwrap();
+
+        let plot = query_processor
+            .plot_query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((-180., -90.).into(), (180., 90.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::new(0),
+            )
+            .await?;
+
+        Ok(plot.data_values().unwrap())
+    }
+
+    #[tokio::test]
+    async fn points() {
+        let data = plot(ScatterPlotParams {
+            column_x: "foo".to_string(),
+            column_y: "bar".to_string(),
+            density: None,
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            data,
+            serde_json::json!([
+                {"x": 1.0, "y": 1.5},
+                {"x": 2.0, "y": 2.5},
+                {"x": 4.0, "y": 4.5},
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn density() {
+        let data = plot(ScatterPlotParams {
+            column_x: "foo".to_string(),
+            column_y: "bar".to_string(),
+            density: Some(ScatterPlotDensity {
+                threshold: 2,
+                bins: 1,
+            }),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            data,
+            serde_json::json!([
+                {"x": 1.0, "x2": 4.0, "y": 1.5, "y2": 4.5, "count": 3},
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn density_below_threshold() {
+        let data = plot(ScatterPlotParams {
+            column_x: "foo".to_string(),
+            column_y: "bar".to_string(),
+            density: Some(ScatterPlotDensity {
+                threshold: 3,
+                bins: 1,
+            }),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(data.as_array().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn non_numeric_column() {
+        let result = plot(ScatterPlotParams {
+            column_x: "foo".to_string(),
+            column_y: "baz".to_string(),
+            density: None,
+        })
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(error::Error::InvalidOperatorSpec { .. })
+        ));
+    }
+
+    #[test]
+    fn deserialize_density_defaults() {
+        let params: ScatterPlotParams = serde_json::from_value(serde_json::json!({
+            "columnX": "foo",
+            "columnY": "bar",
+            "density": {},
+        }))
+        .unwrap();
+
+        assert_eq!(
+            params.density,
+            Some(ScatterPlotDensity {
+                threshold: 10_000,
+                bins: 50,
+            })
+        );
+    }
+}
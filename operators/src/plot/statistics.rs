@@ -1,16 +1,20 @@
 use crate::engine::{
     ExecutionContext, InitializedPlotOperator, InitializedRasterOperator, MultipleRasterSources,
     Operator, PlotOperator, PlotQueryProcessor, PlotResultDescriptor, QueryContext, QueryProcessor,
-    TypedPlotQueryProcessor, TypedRasterQueryProcessor, VectorQueryRectangle,
+    QueryRegion, TypedPlotQueryProcessor, TypedRasterQueryProcessor, VectorQueryRectangle,
 };
+use crate::error;
 use crate::util::number_statistics::NumberStatistics;
 use crate::util::Result;
 use async_trait::async_trait;
-use futures::future::join_all;
+use futures::future::try_join_all;
 use futures::stream::select_all;
 use futures::{FutureExt, StreamExt};
-use geoengine_datatypes::raster::{Grid2D, GridOrEmpty, GridSize, NoDataValue};
+use geoengine_datatypes::primitives::TimeInterval;
+use geoengine_datatypes::raster::{GridOrEmpty, GridSize};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use snafu::ensure;
 
 pub const STATISTICS_OPERATOR_NAME: &str = "Statistics";
 
@@ -22,9 +26,20 @@ pub const STATISTICS_OPERATOR_NAME: &str = "Statistics";
 pub type Statistics = Operator<StatisticsParams, MultipleRasterSources>;
 
 /// The parameter spec for `Statistics`
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct StatisticsParams {}
+pub struct StatisticsParams {
+    /// If less than this share of the pixels of a raster is valid, i.e., not masked by no data,
+    /// its `min`, `max`, `mean` and `stddev` are `null` instead of describing a few observed pixels.
+    #[serde(default)]
+    pub min_valid_fraction: Option<f64>,
+    /// The percentiles between 0 and 100 that are computed in addition to the median, e.g., `[5, 95]`
+    #[serde(default)]
+    pub percentiles: Vec<f64>,
+    /// Whether to output the statistics of each time step of the query instead of the whole query interval
+    #[serde(default)]
+    pub per_time_step: bool,
+}
 
 #[typetag::serde]
 #[async_trait]
@@ -33,17 +48,46 @@ impl PlotOperator for Statistics {
         self: Box<Self>,
         context: &dyn ExecutionContext,
     ) -> Result<Box<dyn InitializedPlotOperator>> {
-        let rasters = join_all(
+        if let Some(min_valid_fraction) = self.params.min_valid_fraction {
+            ensure!(
+                (0. ..=1.).contains(&min_valid_fraction),
+                error::InvalidOperatorSpec {
+                    reason: "`minValidFraction` must be between 0 and 1".to_string(),
+                }
+            );
+        }
+
+        ensure!(
+            self.params
+                .percentiles
+                .iter()
+                .all(|percentile| (0. ..=100.).contains(percentile)),
+            error::InvalidOperatorSpec {
+                reason: "`percentiles` must be between 0 and 100".to_string(),
+            }
+        );
+
+        let rasters = try_join_all(
             self.sources
                 .rasters
                 .into_iter()
                 .map(|s| s.initialize(context)),
         )
-        .await;
+        .await?;
+
+        for raster in &rasters {
+            ensure!(
+                raster.result_descriptor().number_of_bands() == 1,
+                error::InvalidOperatorSpec {
+                    reason: "multiband rasters cannot be summarized".to_string()
+                }
+            );
+        }
 
         let initialized_operator = InitializedStatistics {
             result_descriptor: PlotResultDescriptor {},
-            rasters: rasters.into_iter().collect::<Result<Vec<_>>>()?,
+            rasters,
+            params: self.params,
         };
 
         Ok(initialized_operator.boxed())
@@ -54,6 +98,7 @@ impl PlotOperator for Statistics {
 pub struct InitializedStatistics {
     result_descriptor: PlotResultDescriptor,
     rasters: Vec<Box<dyn InitializedRasterOperator>>,
+    params: StatisticsParams,
 }
 
 impl InitializedPlotOperator for InitializedStatistics {
@@ -65,6 +110,7 @@ impl InitializedPlotOperator for InitializedStatistics {
                     .iter()
                     .map(|source| source.query_processor())
                     .collect::<Result<Vec<_>>>()?,
+                params: self.params.clone(),
             }
             .boxed(),
         ))
@@ -78,6 +124,7 @@ impl InitializedPlotOperator for InitializedStatistics {
 /// A query processor that calculates the statistics about its inputs.
 pub struct StatisticsQueryProcessor {
     rasters: Vec<TypedRasterQueryProcessor>,
+    params: StatisticsParams,
 }
 
 #[async_trait]
@@ -104,71 +151,259 @@ impl PlotQueryProcessor for StatisticsQueryProcessor {
             );
         }
 
-        let number_statistics = vec![NumberStatistics::default(); self.rasters.len()];
+        let raster_statistics = vec![RasterStatistics::default(); self.rasters.len()];
+
+        let region = ctx.extensions().get::<QueryRegion>();
+        let per_time_step = self.params.per_time_step;
 
         select_all(queries)
             .fold(
-                Ok(number_statistics),
-                |number_statistics: Result<Vec<NumberStatistics>>, enumerated_raster_tile| async move {
-                    let mut number_statistics = number_statistics?;
+                Ok(raster_statistics),
+                |raster_statistics: Result<Vec<RasterStatistics>>, enumerated_raster_tile| async move {
+                    let mut raster_statistics = raster_statistics?;
                     let (i, raster_tile) = enumerated_raster_tile?;
-                    match raster_tile.grid_array {
-                        GridOrEmpty::Grid(g) => process_raster(&mut number_statistics[i], &g),
-                        GridOrEmpty::Empty(n) => number_statistics[i].add_no_data_batch(n.number_of_elements())
+                    let tile_information = raster_tile.tile_information();
+                    let value_statistics = raster_statistics[i]
+                        .time_step_mut(if per_time_step { Some(raster_tile.time) } else { None });
+                    match (raster_tile.grid_array, region) {
+                        (GridOrEmpty::Grid(g), None) => process_raster(value_statistics, &g.data, g.no_data_value),
+                        (GridOrEmpty::Grid(g), Some(region)) => process_raster(
+                            value_statistics,
+                            &region.masked_values(&tile_information, &g.data),
+                            g.no_data_value,
+                        ),
+                        (GridOrEmpty::Empty(n), None) => value_statistics.add_no_data_batch(n.number_of_elements()),
+                        (GridOrEmpty::Empty(_), Some(region)) => value_statistics.add_no_data_batch(region.number_of_pixels_inside(&tile_information)),
                     }
 
-                    Ok(number_statistics)
+                    Ok(raster_statistics)
                 },
             )
-            .map(|number_statistics| {
-                let output: Vec<StatisticsOutput> = number_statistics?.iter().map(StatisticsOutput::from).collect();
-                serde_json::to_value(&output).map_err(Into::into)
+            .map(|raster_statistics| {
+                let raster_statistics = raster_statistics?;
+
+                if per_time_step {
+                    let output: Vec<Vec<TimeStepStatisticsOutput>> = raster_statistics
+                        .into_iter()
+                        .map(|raster_statistics| {
+                            raster_statistics
+                                .into_time_steps()
+                                .into_iter()
+                                .map(|(time, mut value_statistics)| TimeStepStatisticsOutput {
+                                    time,
+                                    statistics: StatisticsOutput::new(&mut value_statistics, &self.params),
+                                })
+                                .collect()
+                        })
+                        .collect();
+                    serde_json::to_value(&output).map_err(Into::into)
+                } else {
+                    let output: Vec<StatisticsOutput> = raster_statistics
+                        .into_iter()
+                        .map(|raster_statistics| {
+                            StatisticsOutput::new(&mut raster_statistics.into_total(), &self.params)
+                        })
+                        .collect();
+                    serde_json::to_value(&output).map_err(Into::into)
+                }
             })
             .await
     }
 }
 
 #[allow(clippy::float_cmp)] // allow since NO DATA is a specific value
-fn process_raster(number_statistics: &mut NumberStatistics, tile_grid: &Grid2D<f64>) {
-    let no_data_value = tile_grid.no_data_value();
-
+fn process_raster(
+    value_statistics: &mut ValueStatistics,
+    values: &[f64],
+    no_data_value: Option<f64>,
+) {
     if let Some(no_data_value) = no_data_value {
-        for &value in &tile_grid.data {
+        for &value in values {
             if value == no_data_value {
-                number_statistics.add_no_data();
+                value_statistics.add_no_data();
             } else {
-                number_statistics.add(value);
+                value_statistics.add(value);
             }
         }
     } else {
-        for &value in &tile_grid.data {
-            number_statistics.add(value);
+        for &value in values {
+            value_statistics.add(value);
+        }
+    }
+}
+
+/// The statistics of a raster, either of the whole query or grouped by the time steps of its tiles
+#[derive(Debug, Clone, Default)]
+struct RasterStatistics {
+    /// The time step is `None` if the statistics are not grouped
+    time_steps: Vec<(Option<TimeInterval>, ValueStatistics)>,
+}
+
+impl RasterStatistics {
+    fn time_step_mut(&mut self, time: Option<TimeInterval>) -> &mut ValueStatistics {
+        let index = match self.time_steps.iter().position(|(t, _)| *t == time) {
+            Some(index) => index,
+            None => {
+                self.time_steps.push((time, ValueStatistics::default()));
+                self.time_steps.len() - 1
+            }
+        };
+
+        &mut self.time_steps[index].1
+    }
+
+    fn into_total(self) -> ValueStatistics {
+        self.time_steps
+            .into_iter()
+            .next()
+            .map(|(_, value_statistics)| value_statistics)
+            .unwrap_or_default()
+    }
+
+    fn into_time_steps(self) -> Vec<(TimeInterval, ValueStatistics)> {
+        let mut time_steps: Vec<(TimeInterval, ValueStatistics)> = self
+            .time_steps
+            .into_iter()
+            .filter_map(|(time, value_statistics)| time.map(|time| (time, value_statistics)))
+            .collect();
+
+        time_steps.sort_by_key(|(time, _)| (time.start(), time.end()));
+
+        time_steps
+    }
+}
+
+/// `NumberStatistics` that also keep the valid values for computing the median and percentiles
+#[derive(Debug, Clone, Default)]
+struct ValueStatistics {
+    number_statistics: NumberStatistics,
+    values: Vec<f64>,
+}
+
+impl ValueStatistics {
+    fn add(&mut self, value: f64) {
+        self.number_statistics.add(value);
+
+        if !value.is_nan() {
+            self.values.push(value);
         }
     }
+
+    fn add_no_data(&mut self) {
+        self.number_statistics.add_no_data();
+    }
+
+    fn add_no_data_batch(&mut self, batch_size: usize) {
+        self.number_statistics.add_no_data_batch(batch_size);
+    }
+
+    /// Computes the percentiles between 0 and 100 by linear interpolation between the closest ranks.
+    /// The percentiles are `NaN` if there are no valid values.
+    fn percentiles(&mut self, percentiles: &[f64]) -> Vec<f64> {
+        self.values
+            .sort_unstable_by(|a, b| a.partial_cmp(b).expect("`NaN` values are not stored"));
+
+        let values = &self.values;
+
+        percentiles
+            .iter()
+            .map(|percentile| {
+                if values.is_empty() {
+                    return f64::NAN;
+                }
+
+                let rank = percentile / 100. * (values.len() - 1) as f64;
+                let lower = values[rank.floor() as usize];
+                let upper = values[rank.ceil() as usize];
+
+                lower + (upper - lower) * rank.fract()
+            })
+            .collect()
+    }
 }
 
 /// The statistics summary output type for each raster input
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct StatisticsOutput {
+    /// The number of valid pixels
     pub pixel_count: usize,
+    /// The number of invalid pixels, i.e., `NaN` values and pixels without data
     pub nan_count: usize,
+    /// The pixels without data, e.g., masked clouds, which are part of the `nan_count`
+    pub masked_pixel_count: usize,
+    pub valid_fraction: f64,
     pub min: f64,
     pub max: f64,
     pub mean: f64,
     pub stddev: f64,
+    pub median: f64,
+    /// The requested percentiles in the order of the parameters
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub percentiles: Vec<PercentileOutput>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PercentileOutput {
+    pub percentile: f64,
+    pub value: f64,
+}
+
+/// The statistics of a raster input for a single time step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TimeStepStatisticsOutput {
+    pub time: TimeInterval,
+    #[serde(flatten)]
+    pub statistics: StatisticsOutput,
 }
 
-impl From<&NumberStatistics> for StatisticsOutput {
-    fn from(number_statistics: &NumberStatistics) -> Self {
-        Self {
+impl StatisticsOutput {
+    fn new(value_statistics: &mut ValueStatistics, params: &StatisticsParams) -> Self {
+        let percentile_values = value_statistics.percentiles(&params.percentiles);
+        let median = value_statistics.percentiles(&[50.])[0];
+
+        let number_statistics = &value_statistics.number_statistics;
+        let valid_fraction = number_statistics.valid_fraction();
+
+        let mut output = Self {
             pixel_count: number_statistics.count(),
             nan_count: number_statistics.nan_count(),
+            masked_pixel_count: number_statistics.no_data_count(),
+            valid_fraction,
             min: number_statistics.min(),
             max: number_statistics.max(),
             mean: number_statistics.mean(),
             stddev: number_statistics.std_dev(),
+            median,
+            percentiles: params
+                .percentiles
+                .iter()
+                .zip(percentile_values)
+                .map(|(&percentile, value)| PercentileOutput { percentile, value })
+                .collect(),
+        };
+
+        let is_poorly_observed = params
+            .min_valid_fraction
+            .map_or(false, |min_valid_fraction| {
+                valid_fraction.is_nan() || valid_fraction < min_valid_fraction
+            });
+
+        // `NaN` is serialized as `null`
+        if is_poorly_observed {
+            output.min = f64::NAN;
+            output.max = f64::NAN;
+            output.mean = f64::NAN;
+            output.stddev = f64::NAN;
+            output.median = f64::NAN;
+            for percentile in &mut output.percentiles {
+                percentile.value = f64::NAN;
+            }
         }
+
+        output
     }
 }
 
@@ -183,7 +418,7 @@ mod tests {
     };
     use crate::mock::{MockRasterSource, MockRasterSourceParams};
     use geoengine_datatypes::primitives::{
-        BoundingBox2D, Measurement, SpatialResolution, TimeInterval,
+        BoundingBox2D, Measurement, MultiPolygon, SpatialResolution, TimeInterval,
     };
     use geoengine_datatypes::raster::{Grid2D, RasterDataType, RasterTile2D, TileInformation};
     use geoengine_datatypes::spatial_reference::SpatialReference;
@@ -192,7 +427,7 @@ mod tests {
     #[test]
     fn serialization() {
         let statistics = Statistics {
-            params: StatisticsParams {},
+            params: StatisticsParams::default(),
             sources: MultipleRasterSources { rasters: vec![] },
         };
 
@@ -231,13 +466,14 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    bands: Vec::new(),
                 },
             },
         }
         .boxed();
 
         let statistics = Statistics {
-            params: StatisticsParams {},
+            params: StatisticsParams::default(),
             sources: vec![raster_source].into(),
         };
 
@@ -269,12 +505,341 @@ mod tests {
             json!([{
                 "pixelCount": 6,
                 "nanCount": 0,
+                "maskedPixelCount": 0,
+                "validFraction": 1.0,
                 "min": 1.0,
                 "max": 6.0,
                 "mean": 3.5,
-                "stddev": 1.707_825_127_659_933
+                "stddev": 1.707_825_127_659_933,
+                "median": 3.5
             }])
             .to_string()
         );
     }
+    #[tokio::test]
+    async fn single_raster_in_region() {
+        let raster_source = MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![RasterTile2D::new_with_tile_info(
+                    TimeInterval::default(),
+                    TileInformation {
+                        global_geo_transform: Default::default(),
+                        global_tile_position: [0, 0].into(),
+                        tile_size_in_pixels: [3, 2].into(),
+                    },
+                    Grid2D::new([3, 2].into(), vec![1, 2, 3, 4, 5, 6], None)
+                        .unwrap()
+                        .into(),
+                )],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: None,
+                    bands: Vec::new(),
+                },
+            },
+        }
+        .boxed();
+
+        let statistics = Statistics {
+            params: StatisticsParams::default(),
+            sources: vec![raster_source].into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .unwrap();
+
+        let processor = statistics.query_processor().unwrap().json_plain().unwrap();
+
+        // the left column of the raster
+        let region = QueryRegion::new(
+            MultiPolygon::new(vec![vec![vec![
+                (0., 0.).into(),
+                (1., 0.).into(),
+                (1., -3.).into(),
+                (0., -3.).into(),
+                (0., 0.).into(),
+            ]]])
+            .unwrap(),
+        )
+        .unwrap();
+
+        let mut query_context = MockQueryContext::new(0);
+        query_context.extensions_mut().insert(region.clone());
+
+        let result = processor
+            .plot_query(
+                VectorQueryRectangle {
+                    spatial_bounds: region.bounding_box(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &query_context,
+            )
+            .await
+            .unwrap();
+
+        let result: Vec<StatisticsOutput> = serde_json::from_value(result).unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].pixel_count, 3);
+        assert_eq!(result[0].nan_count, 0);
+        assert!((result[0].min - 1.).abs() < f64::EPSILON);
+        assert!((result[0].max - 5.).abs() < f64::EPSILON);
+        assert!((result[0].mean - 3.).abs() < f64::EPSILON);
+        assert!((result[0].stddev - (8_f64 / 3.).sqrt()).abs() < 1e-10);
+    }
+
+    #[tokio::test]
+    async fn masked_pixels() {
+        let no_data_value = Some(0);
+
+        let raster_source = || {
+            MockRasterSource {
+                params: MockRasterSourceParams {
+                    data: vec![RasterTile2D::new_with_tile_info(
+                        TimeInterval::default(),
+                        TileInformation {
+                            global_geo_transform: Default::default(),
+                            global_tile_position: [0, 0].into(),
+                            tile_size_in_pixels: [3, 2].into(),
+                        },
+                        Grid2D::new([3, 2].into(), vec![1, 0, 0, 4, 0, 6], no_data_value)
+                            .unwrap()
+                            .into(),
+                    )],
+                    result_descriptor: RasterResultDescriptor {
+                        data_type: RasterDataType::U8,
+                        spatial_reference: SpatialReference::epsg_4326().into(),
+                        measurement: Measurement::Unitless,
+                        no_data_value: no_data_value.map(AsPrimitive::as_),
+                        bands: Vec::new(),
+                    },
+                },
+            }
+            .boxed()
+        };
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((-180., -90.).into(), (180., 90.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        };
+
+        let statistics = Statistics {
+            params: StatisticsParams {
+                min_valid_fraction: Some(0.5),
+                ..StatisticsParams::default()
+            },
+            sources: vec![raster_source(), raster_source()].into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .unwrap();
+
+        let result = statistics
+            .query_processor()
+            .unwrap()
+            .json_plain()
+            .unwrap()
+            .plot_query(query, &MockQueryContext::new(0))
+            .await
+            .unwrap();
+
+        let result: Vec<StatisticsOutput> = serde_json::from_value(result).unwrap();
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].pixel_count, 3);
+        assert_eq!(result[0].nan_count, 3);
+        assert_eq!(result[0].masked_pixel_count, 3);
+        assert!((result[0].valid_fraction - 0.5).abs() < f64::EPSILON);
+        assert!((result[0].min - 1.).abs() < f64::EPSILON);
+        assert!((result[0].max - 6.).abs() < f64::EPSILON);
+        assert!((result[0].mean - 11. / 3.).abs() < 1e-10);
+
+        let statistics = Statistics {
+            params: StatisticsParams {
+                min_valid_fraction: Some(0.75),
+                ..StatisticsParams::default()
+            },
+            sources: vec![raster_source()].into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .unwrap();
+
+        let result = statistics
+            .query_processor()
+            .unwrap()
+            .json_plain()
+            .unwrap()
+            .plot_query(query, &MockQueryContext::new(0))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result[0],
+            json!({
+                "pixelCount": 3,
+                "nanCount": 3,
+                "maskedPixelCount": 3,
+                "validFraction": 0.5,
+                "min": null,
+                "max": null,
+                "mean": null,
+                "stddev": null,
+                "median": null
+            })
+        );
+
+        assert!(Statistics {
+            params: StatisticsParams {
+                min_valid_fraction: Some(1.5),
+                ..StatisticsParams::default()
+            },
+            sources: vec![raster_source()].into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .is_err());
+    }
+
+    fn tile(time: TimeInterval, values: Vec<u8>) -> RasterTile2D<u8> {
+        RasterTile2D::new_with_tile_info(
+            time,
+            TileInformation {
+                global_geo_transform: Default::default(),
+                global_tile_position: [0, 0].into(),
+                tile_size_in_pixels: [3, 2].into(),
+            },
+            Grid2D::new([3, 2].into(), values, None).unwrap().into(),
+        )
+    }
+
+    fn raster_source(data: Vec<RasterTile2D<u8>>) -> Box<dyn RasterOperator> {
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data,
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: None,
+                    bands: Vec::new(),
+                },
+            },
+        }
+        .boxed()
+    }
+
+    #[tokio::test]
+    async fn percentiles() {
+        let statistics = Statistics {
+            params: StatisticsParams {
+                percentiles: vec![0., 25., 100.],
+                ..StatisticsParams::default()
+            },
+            sources: vec![raster_source(vec![tile(
+                TimeInterval::default(),
+                vec![6, 2, 4, 1, 3, 5],
+            )])]
+            .into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .unwrap();
+
+        let result = statistics
+            .query_processor()
+            .unwrap()
+            .json_plain()
+            .unwrap()
+            .plot_query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((0., -3.).into(), (2., 0.).into()).unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::new(0),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result[0]["median"], json!(3.5));
+        assert_eq!(
+            result[0]["percentiles"],
+            json!([
+                { "percentile": 0.0, "value": 1.0 },
+                { "percentile": 25.0, "value": 2.25 },
+                { "percentile": 100.0, "value": 6.0 }
+            ])
+        );
+
+        assert!(Statistics {
+            params: StatisticsParams {
+                percentiles: vec![101.],
+                ..StatisticsParams::default()
+            },
+            sources: vec![raster_source(vec![])].into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn per_time_step() {
+        let statistics = Statistics {
+            params: StatisticsParams {
+                per_time_step: true,
+                ..StatisticsParams::default()
+            },
+            sources: vec![raster_source(vec![
+                tile(TimeInterval::new_unchecked(0, 10), vec![1, 2, 3, 4, 5, 6]),
+                tile(TimeInterval::new_unchecked(10, 20), vec![7, 7, 7, 9, 9, 9]),
+            ])]
+            .into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .unwrap();
+
+        let result = statistics
+            .query_processor()
+            .unwrap()
+            .json_plain()
+            .unwrap()
+            .plot_query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((0., -3.).into(), (2., 0.).into()).unwrap(),
+                    time_interval: TimeInterval::new_unchecked(0, 20),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::new(0),
+            )
+            .await
+            .unwrap();
+
+        let result: Vec<Vec<TimeStepStatisticsOutput>> = serde_json::from_value(result).unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].len(), 2);
+
+        assert_eq!(result[0][0].time, TimeInterval::new_unchecked(0, 10));
+        assert_eq!(result[0][0].statistics.pixel_count, 6);
+        assert!((result[0][0].statistics.median - 3.5).abs() < f64::EPSILON);
+
+        assert_eq!(result[0][1].time, TimeInterval::new_unchecked(10, 20));
+        assert_eq!(result[0][1].statistics.pixel_count, 6);
+        assert!((result[0][1].statistics.mean - 8.).abs() < f64::EPSILON);
+        assert!((result[0][1].statistics.median - 8.).abs() < f64::EPSILON);
+    }
 }
@@ -0,0 +1,453 @@
+use crate::engine::{
+    ExecutionContext, InitializedPlotOperator, InitializedRasterOperator,
+    InitializedVectorOperator, Operator, PlotOperator, PlotQueryProcessor, PlotResultDescriptor,
+    QueryContext, QueryProcessor, SingleRasterOrVectorSource, TypedPlotQueryProcessor,
+    TypedRasterQueryProcessor, TypedVectorQueryProcessor, VectorQueryRectangle,
+};
+use crate::error;
+use crate::util::input::RasterOrVectorOperator;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::StreamExt;
+use geoengine_datatypes::collections::FeatureCollectionInfos;
+use geoengine_datatypes::plots::{Plot, PlotData, TimeCoverageChart};
+use geoengine_datatypes::primitives::TimeInterval;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt};
+use std::collections::BTreeMap;
+
+pub const TEMPORAL_COVERAGE_NAME: &str = "Temporal Coverage";
+const MAX_LABELS: usize = 20;
+const RASTER_LABEL: &str = "Raster";
+const FEATURES_LABEL: &str = "Features";
+
+/// A plot that shows the time intervals for which its raster or vector input has data.
+///
+/// For vector inputs, the features can be split into rows by the values of a label column.
+///
+pub type TemporalCoverage = Operator<TemporalCoverageParams, SingleRasterOrVectorSource>;
+
+/// The parameter spec for `TemporalCoverage`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TemporalCoverageParams {
+    /// Name of the attribute to label the features by. Must not be set for rasters.
+    #[serde(default)]
+    pub label_column: Option<String>,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl PlotOperator for TemporalCoverage {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedPlotOperator>> {
+        Ok(match self.sources.source {
+            RasterOrVectorOperator::Raster(raster_source) => {
+                ensure!(
+                    self.params.label_column.is_none(),
+                    error::InvalidOperatorSpec {
+                        reason:
+                            "Temporal coverage on raster input must not have `labelColumn` field set"
+                                .to_string(),
+                    }
+                );
+
+                InitializedTemporalCoverage {
+                    result_descriptor: PlotResultDescriptor {},
+                    source: raster_source.initialize(context).await?,
+                    label_column: None,
+                }
+                .boxed()
+            }
+            RasterOrVectorOperator::Vector(vector_source) => {
+                let vector_source = vector_source.initialize(context).await?;
+
+                if let Some(label_column) = &self.params.label_column {
+                    ensure!(
+                        vector_source
+                            .result_descriptor()
+                            .columns
+                            .contains_key(label_column),
+                        error::ColumnDoesNotExist {
+                            column: label_column.clone()
+                        }
+                    );
+                }
+
+                InitializedTemporalCoverage {
+                    result_descriptor: PlotResultDescriptor {},
+                    source: vector_source,
+                    label_column: self.params.label_column,
+                }
+                .boxed()
+            }
+        })
+    }
+}
+
+/// The initialization of `TemporalCoverage`
+pub struct InitializedTemporalCoverage<Op> {
+    result_descriptor: PlotResultDescriptor,
+    source: Op,
+    label_column: Option<String>,
+}
+
+impl InitializedPlotOperator for InitializedTemporalCoverage<Box<dyn InitializedRasterOperator>> {
+    fn query_processor(&self) -> Result<TypedPlotQueryProcessor> {
+        let processor = TemporalCoverageRasterQueryProcessor {
+            input: self.source.query_processor()?,
+        };
+
+        Ok(TypedPlotQueryProcessor::JsonVega(processor.boxed()))
+    }
+
+    fn result_descriptor(&self) -> &PlotResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+impl InitializedPlotOperator for InitializedTemporalCoverage<Box<dyn InitializedVectorOperator>> {
+    fn query_processor(&self) -> Result<TypedPlotQueryProcessor> {
+        let processor = TemporalCoverageVectorQueryProcessor {
+            input: self.source.query_processor()?,
+            label_column: self.label_column.clone(),
+        };
+
+        Ok(TypedPlotQueryProcessor::JsonVega(processor.boxed()))
+    }
+
+    fn result_descriptor(&self) -> &PlotResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// A query processor that collects the time steps of its raster input.
+pub struct TemporalCoverageRasterQueryProcessor {
+    input: TypedRasterQueryProcessor,
+}
+
+/// A query processor that collects the validities of the features of its vector input.
+pub struct TemporalCoverageVectorQueryProcessor {
+    input: TypedVectorQueryProcessor,
+    label_column: Option<String>,
+}
+
+#[async_trait]
+impl PlotQueryProcessor for TemporalCoverageRasterQueryProcessor {
+    type OutputFormat = PlotData;
+
+    fn plot_type(&self) -> &'static str {
+        TEMPORAL_COVERAGE_NAME
+    }
+
+    async fn plot_query<'p>(
+        &'p self,
+        query: VectorQueryRectangle,
+        ctx: &'p dyn QueryContext,
+    ) -> Result<Self::OutputFormat> {
+        let mut coverage = Coverage::<MAX_LABELS>::default();
+
+        call_on_generic_raster_processor!(&self.input, processor => {
+            let mut tiles = processor.query(query.into(), ctx).await?;
+
+            // all tiles of a time step share its time interval
+            let mut last_time = None;
+            while let Some(tile) = tiles.next().await {
+                let time = tile?.time;
+
+                if last_time != Some(time) {
+                    coverage.add(RASTER_LABEL, time);
+                    last_time = Some(time);
+                }
+            }
+        });
+
+        coverage
+            .into_chart()
+            .to_vega_embeddable(false)
+            .context(error::DataType)
+    }
+}
+
+#[async_trait]
+impl PlotQueryProcessor for TemporalCoverageVectorQueryProcessor {
+    type OutputFormat = PlotData;
+
+    fn plot_type(&self) -> &'static str {
+        TEMPORAL_COVERAGE_NAME
+    }
+
+    async fn plot_query<'p>(
+        &'p self,
+        query: VectorQueryRectangle,
+        ctx: &'p dyn QueryContext,
+    ) -> Result<Self::OutputFormat> {
+        let mut coverage = Coverage::<MAX_LABELS>::default();
+
+        call_on_generic_vector_processor!(&self.input, processor => {
+            let mut collections = processor.query(query, ctx).await?;
+
+            while let Some(collection) = collections.next().await {
+                let collection = collection?;
+
+                if let Some(label_column) = &self.label_column {
+                    let labels = collection.data(label_column)?;
+
+                    for (label, &time) in labels.strings_iter().zip(collection.time_intervals()) {
+                        coverage.add(&label, time);
+                    }
+                } else {
+                    for &time in collection.time_intervals() {
+                        coverage.add(FEATURES_LABEL, time);
+                    }
+                }
+            }
+        });
+
+        coverage
+            .into_chart()
+            .to_vega_embeddable(false)
+            .context(error::DataType)
+    }
+}
+
+/// The time intervals per label. If there are already `LENGTH` labels, new labels are ignored.
+struct Coverage<const LENGTH: usize> {
+    intervals: BTreeMap<String, Vec<TimeInterval>>,
+}
+
+impl<const LENGTH: usize> Default for Coverage<LENGTH> {
+    fn default() -> Self {
+        Self {
+            intervals: BTreeMap::new(),
+        }
+    }
+}
+
+impl<const LENGTH: usize> Coverage<LENGTH> {
+    fn add(&mut self, label: &str, time: TimeInterval) {
+        if let Some(intervals) = self.intervals.get_mut(label) {
+            intervals.push(time);
+        } else if self.intervals.len() < LENGTH {
+            self.intervals.insert(label.to_string(), vec![time]);
+        }
+    }
+
+    /// Merges the overlapping and adjacent intervals of each label
+    fn into_chart(self) -> TimeCoverageChart {
+        let mut chart_intervals = Vec::new();
+
+        for (label, mut intervals) in self.intervals {
+            intervals.sort_unstable_by_key(TimeInterval::start);
+
+            let mut merged: Vec<TimeInterval> = Vec::with_capacity(intervals.len());
+            for time in intervals {
+                match merged.last_mut().map(|last| (last.union(&time), last)) {
+                    Some((Ok(union), last)) => *last = union,
+                    _ => merged.push(time),
+                }
+            }
+
+            chart_intervals.extend(merged.into_iter().map(|time| (label.clone(), time)));
+        }
+
+        TimeCoverageChart::new(chart_intervals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::{
+        MockExecutionContext, MockQueryContext, RasterOperator, RasterResultDescriptor,
+        VectorOperator,
+    };
+    use crate::mock::{MockFeatureCollectionSource, MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::plots::PlotMetaData;
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, FeatureData, Measurement, MultiPoint, SpatialResolution,
+    };
+    use geoengine_datatypes::raster::{Grid2D, RasterDataType, RasterTile2D, TileInformation};
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+
+    fn query_rectangle() -> VectorQueryRectangle {
+        VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((-180., -90.).into(), (180., 90.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        }
+    }
+
+    #[tokio::test]
+    async fn vector_data() {
+        let point_source = MockFeatureCollectionSource::single(
+            MultiPointCollection::from_data(
+                MultiPoint::many(vec![
+                    vec![(0., 0.)],
+                    vec![(1., 1.)],
+                    vec![(2., 2.)],
+                    vec![(3., 3.)],
+                ])
+                .unwrap(),
+                vec![
+                    TimeInterval::new_unchecked(2000, 3000),
+                    TimeInterval::new_unchecked(0, 1000),
+                    TimeInterval::new_unchecked(500, 1500),
+                    TimeInterval::new_unchecked(0, 1000),
+                ],
+                [(
+                    "label".to_string(),
+                    FeatureData::Text(vec![
+                        "a".to_owned(),
+                        "a".to_owned(),
+                        "a".to_owned(),
+                        "b".to_owned(),
+                    ]),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            )
+            .unwrap(),
+        )
+        .boxed();
+
+        let operator = TemporalCoverage {
+            params: TemporalCoverageParams {
+                label_column: Some("label".to_string()),
+            },
+            sources: point_source.into(),
+        };
+
+        let query_processor = operator
+            .boxed()
+            .initialize(&MockExecutionContext::default())
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .json_vega()
+            .unwrap();
+
+        let result = query_processor
+            .plot_query(query_rectangle(), &MockQueryContext::new(0))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result,
+            TimeCoverageChart::new(vec![
+                ("a".to_string(), TimeInterval::new_unchecked(0, 1500)),
+                ("a".to_string(), TimeInterval::new_unchecked(2000, 3000)),
+                ("b".to_string(), TimeInterval::new_unchecked(0, 1000)),
+            ])
+            .to_vega_embeddable(false)
+            .unwrap()
+        );
+        assert_eq!(result.metadata, PlotMetaData::None);
+    }
+
+    #[tokio::test]
+    async fn missing_label_column() {
+        let point_source = MockFeatureCollectionSource::single(
+            MultiPointCollection::from_data(
+                MultiPoint::many(vec![vec![(0., 0.)]]).unwrap(),
+                vec![TimeInterval::default()],
+                Default::default(),
+            )
+            .unwrap(),
+        )
+        .boxed();
+
+        let operator = TemporalCoverage {
+            params: TemporalCoverageParams {
+                label_column: Some("label".to_string()),
+            },
+            sources: point_source.into(),
+        };
+
+        assert!(matches!(
+            operator
+                .boxed()
+                .initialize(&MockExecutionContext::default())
+                .await,
+            Err(error::Error::ColumnDoesNotExist { column }) if column == "label"
+        ));
+    }
+
+    #[tokio::test]
+    async fn raster_data() {
+        let tile = |time: TimeInterval, position: [isize; 2]| {
+            RasterTile2D::new_with_tile_info(
+                time,
+                TileInformation {
+                    global_geo_transform: Default::default(),
+                    global_tile_position: position.into(),
+                    tile_size_in_pixels: [3, 2].into(),
+                },
+                Grid2D::new([3, 2].into(), vec![1_u8; 6], None)
+                    .unwrap()
+                    .into(),
+            )
+        };
+
+        let raster_source = MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![
+                    tile(TimeInterval::new_unchecked(0, 10), [0, 0]),
+                    tile(TimeInterval::new_unchecked(0, 10), [0, 1]),
+                    tile(TimeInterval::new_unchecked(10, 20), [0, 0]),
+                    tile(TimeInterval::new_unchecked(10, 20), [0, 1]),
+                    tile(TimeInterval::new_unchecked(30, 40), [0, 0]),
+                    tile(TimeInterval::new_unchecked(30, 40), [0, 1]),
+                ],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: None,
+                    bands: Vec::new(),
+                },
+            },
+        }
+        .boxed();
+
+        let operator = TemporalCoverage {
+            params: TemporalCoverageParams { label_column: None },
+            sources: raster_source.into(),
+        };
+
+        let query_processor = operator
+            .boxed()
+            .initialize(&MockExecutionContext::default())
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .json_vega()
+            .unwrap();
+
+        let result = query_processor
+            .plot_query(query_rectangle(), &MockQueryContext::new(0))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result,
+            TimeCoverageChart::new(vec![
+                (RASTER_LABEL.to_string(), TimeInterval::new_unchecked(0, 20)),
+                (
+                    RASTER_LABEL.to_string(),
+                    TimeInterval::new_unchecked(30, 40)
+                ),
+            ])
+            .to_vega_embeddable(false)
+            .unwrap()
+        );
+    }
+}
@@ -3,6 +3,7 @@ use crate::engine::{
     PlotQueryProcessor, PlotResultDescriptor, QueryContext, QueryProcessor, RasterQueryProcessor,
     SingleRasterSource, TypedPlotQueryProcessor, VectorQueryRectangle,
 };
+use crate::error;
 use crate::util::math::average_floor;
 use crate::util::Result;
 use async_trait::async_trait;
@@ -11,7 +12,9 @@ use futures::StreamExt;
 use geoengine_datatypes::plots::{AreaLineChart, Plot, PlotData};
 use geoengine_datatypes::primitives::{Measurement, TimeInstance, TimeInterval};
 use geoengine_datatypes::raster::{Pixel, RasterTile2D};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use snafu::ensure;
 use std::collections::BTreeMap;
 
 pub const MEAN_RASTER_PIXEL_VALUES_OVER_TIME_NAME: &str = "Mean Raster Pixel Values over Time";
@@ -21,7 +24,7 @@ pub type MeanRasterPixelValuesOverTime =
     Operator<MeanRasterPixelValuesOverTimeParams, SingleRasterSource>;
 
 /// The parameter spec for `MeanRasterPixelValuesOverTime`
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct MeanRasterPixelValuesOverTimeParams {
     /// Where should the x-axis (time) tick be positioned?
@@ -37,7 +40,7 @@ const fn default_true() -> bool {
     true
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum MeanRasterPixelValuesOverTimePosition {
     Start,
@@ -52,9 +55,18 @@ impl PlotOperator for MeanRasterPixelValuesOverTime {
         self: Box<Self>,
         context: &dyn ExecutionContext,
     ) -> Result<Box<dyn InitializedPlotOperator>> {
+        let raster = self.sources.raster.initialize(context).await?;
+
+        ensure!(
+            raster.result_descriptor().number_of_bands() == 1,
+            error::InvalidOperatorSpec {
+                reason: "multiband rasters cannot be averaged over time".to_string()
+            }
+        );
+
         let initialized_operator = InitializedMeanRasterPixelValuesOverTime {
             result_descriptor: PlotResultDescriptor {},
-            raster: self.sources.raster.initialize(context).await?,
+            raster,
             state: self.params,
         };
 
@@ -396,6 +408,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    bands: Vec::new(),
                 },
             },
         }
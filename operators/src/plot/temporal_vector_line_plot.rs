@@ -21,6 +21,7 @@ use geoengine_datatypes::{
     primitives::{Geometry, Measurement, TimeInterval},
     util::arrow::ArrowTyped,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use snafu::{ensure, ResultExt};
 use std::collections::HashMap;
@@ -37,7 +38,7 @@ pub type FeatureAttributeValuesOverTime =
     Operator<FeatureAttributeValuesOverTimeParams, SingleVectorSource>;
 
 /// The parameter spec for `FeatureAttributeValuesOverTime`
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FeatureAttributeValuesOverTimeParams {
     pub id_column: String,
@@ -76,7 +77,8 @@ impl PlotOperator for FeatureAttributeValuesOverTime {
         ensure!(
             id_type == &FeatureDataType::Text
                 || id_type == &FeatureDataType::Int
-                || id_type == &FeatureDataType::Category,
+                || id_type == &FeatureDataType::Category
+                || id_type == &FeatureDataType::Dictionary,
             error::InvalidFeatureDataType,
         );
 
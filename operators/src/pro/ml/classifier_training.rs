@@ -0,0 +1,469 @@
+use std::collections::BTreeSet;
+
+use crate::engine::{
+    ExecutionContext, InitializedPlotOperator, InitializedVectorOperator, Operator, PlotOperator,
+    PlotQueryProcessor, PlotResultDescriptor, QueryContext, QueryProcessor, SingleVectorSource,
+    TypedPlotQueryProcessor, VectorQueryProcessor, VectorQueryRectangle,
+};
+use crate::error::{self, Error};
+use crate::pro::ml::decision_tree::{
+    train_decision_tree, train_random_forest, ClassifierModel, TrainingSamples, TreeLimits,
+};
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use geoengine_datatypes::collections::{FeatureCollection, FeatureCollectionInfos};
+use geoengine_datatypes::primitives::Geometry;
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+pub const CLASSIFIER_TRAINING_OPERATOR_NAME: &str = "ClassifierTraining";
+
+/// Trains a classifier on labeled samples, e.g., points with raster values
+/// that were attached by a `RasterVectorJoin`.
+///
+/// The result is the serialized `ClassifierModel`.
+/// Samples with a null label or a null or non-finite feature value are left out.
+pub type ClassifierTraining = Operator<ClassifierTrainingParams, SingleVectorSource>;
+
+/// The parameter spec for `ClassifierTraining`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassifierTrainingParams {
+    /// The numeric columns that the classifier uses as features
+    pub feature_columns: Vec<String>,
+    /// The column with the class labels, whose values are treated as text
+    pub label_column: String,
+    #[serde(default)]
+    pub classifier: ClassifierType,
+    /// The maximum depth of the trees, unlimited if not set
+    #[serde(default)]
+    pub max_depth: Option<usize>,
+    /// Nodes with fewer samples are not split any further
+    #[serde(default = "default_min_samples_split")]
+    pub min_samples_split: usize,
+    /// The seed of the random sampling of random forests
+    #[serde(default)]
+    pub seed: u64,
+}
+
+fn default_min_samples_split() -> usize {
+    2
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum ClassifierType {
+    DecisionTree,
+    #[serde(rename_all = "camelCase")]
+    RandomForest {
+        number_of_trees: usize,
+    },
+}
+
+impl Default for ClassifierType {
+    fn default() -> Self {
+        Self::DecisionTree
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl PlotOperator for ClassifierTraining {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedPlotOperator>> {
+        ensure!(
+            !self.params.feature_columns.is_empty(),
+            error::InvalidOperatorSpec {
+                reason: "at least one feature column is required".to_string()
+            }
+        );
+        ensure!(
+            self.params.min_samples_split >= 2,
+            error::InvalidOperatorSpec {
+                reason: "`minSamplesSplit` must be at least 2".to_string()
+            }
+        );
+        if let ClassifierType::RandomForest { number_of_trees } = self.params.classifier {
+            ensure!(
+                number_of_trees > 0,
+                error::InvalidOperatorSpec {
+                    reason: "a random forest needs at least one tree".to_string()
+                }
+            );
+        }
+
+        let source = self.sources.vector.initialize(context).await?;
+        let columns = &source.result_descriptor().columns;
+
+        for column in &self.params.feature_columns {
+            let data_type = columns
+                .get(column)
+                .ok_or_else(|| Error::ColumnDoesNotExist {
+                    column: column.clone(),
+                })?;
+
+            ensure!(
+                data_type.is_numeric(),
+                error::InvalidOperatorSpec {
+                    reason: format!("feature column `{}` must be numeric", column)
+                }
+            );
+        }
+
+        ensure!(
+            columns.contains_key(&self.params.label_column),
+            error::ColumnDoesNotExist {
+                column: self.params.label_column.clone()
+            }
+        );
+
+        Ok(InitializedClassifierTraining {
+            result_descriptor: PlotResultDescriptor {},
+            vector_source: source,
+            params: self.params,
+        }
+        .boxed())
+    }
+}
+
+/// The initialization of `ClassifierTraining`
+pub struct InitializedClassifierTraining {
+    result_descriptor: PlotResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    params: ClassifierTrainingParams,
+}
+
+impl InitializedPlotOperator for InitializedClassifierTraining {
+    fn query_processor(&self) -> Result<TypedPlotQueryProcessor> {
+        let input_processor = self.vector_source.query_processor()?;
+
+        let processor = call_on_generic_vector_processor!(input_processor, features => {
+            ClassifierTrainingQueryProcessor { params: self.params.clone(), features }.boxed()
+        });
+
+        Ok(TypedPlotQueryProcessor::JsonPlain(processor))
+    }
+
+    fn result_descriptor(&self) -> &PlotResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// A query processor that trains a classifier on the samples of its input.
+pub struct ClassifierTrainingQueryProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    params: ClassifierTrainingParams,
+    features: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+}
+
+#[async_trait]
+impl<G> PlotQueryProcessor for ClassifierTrainingQueryProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type OutputFormat = serde_json::Value;
+
+    fn plot_type(&self) -> &'static str {
+        CLASSIFIER_TRAINING_OPERATOR_NAME
+    }
+
+    async fn plot_query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<Self::OutputFormat> {
+        let samples = self
+            .features
+            .query(query, ctx)
+            .await?
+            .try_fold(Vec::new(), |mut samples, collection| async move {
+                self.extend_samples(&mut samples, &collection)?;
+                Ok(samples)
+            })
+            .await?;
+
+        ensure!(!samples.is_empty(), error::EmptyInput);
+
+        let params = self.params.clone();
+        let model = tokio::task::spawn_blocking(move || train(&params, samples)).await?;
+
+        Ok(serde_json::to_value(&model)?)
+    }
+}
+
+impl<G> ClassifierTrainingQueryProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    fn extend_samples(
+        &self,
+        samples: &mut Vec<(Vec<f64>, String)>,
+        collection: &FeatureCollection<G>,
+    ) -> Result<()> {
+        let feature_values = self
+            .params
+            .feature_columns
+            .iter()
+            .map(|column| Ok(collection.data(column)?.float_options_iter().collect()))
+            .collect::<Result<Vec<Vec<Option<f64>>>>>()?;
+
+        let labels = collection.data(&self.params.label_column)?;
+        let label_nulls = labels.nulls();
+
+        for (row, label) in labels.strings_iter().enumerate() {
+            if label_nulls[row] {
+                continue;
+            }
+
+            let features: Option<Vec<f64>> = feature_values
+                .iter()
+                .map(|column| column[row].filter(|value| value.is_finite()))
+                .collect();
+
+            if let Some(features) = features {
+                samples.push((features, label));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn train(params: &ClassifierTrainingParams, samples: Vec<(Vec<f64>, String)>) -> ClassifierModel {
+    let classes: Vec<String> = samples
+        .iter()
+        .map(|(_, label)| label.clone())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let (features, labels): (Vec<Vec<f64>>, Vec<usize>) = samples
+        .into_iter()
+        .map(|(features, label)| {
+            let class = classes
+                .binary_search(&label)
+                .expect("classes contain all labels");
+            (features, class)
+        })
+        .unzip();
+
+    let samples = TrainingSamples {
+        features,
+        labels,
+        number_of_classes: classes.len(),
+    };
+
+    let limits = TreeLimits {
+        max_depth: params.max_depth,
+        min_samples_split: params.min_samples_split,
+    };
+
+    let trees = match params.classifier {
+        ClassifierType::DecisionTree => vec![train_decision_tree(&samples, limits)],
+        ClassifierType::RandomForest { number_of_trees } => {
+            train_random_forest(&samples, limits, number_of_trees, params.seed)
+        }
+    };
+
+    ClassifierModel {
+        feature_columns: params.feature_columns.clone(),
+        classes,
+        trees,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, VectorOperator};
+    use crate::mock::MockFeatureCollectionSource;
+    use crate::pro::ml::DecisionTree;
+    use geoengine_datatypes::collections::DataCollection;
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, FeatureData, NoGeometry, SpatialResolution, TimeInterval,
+    };
+
+    fn source() -> Box<dyn VectorOperator> {
+        MockFeatureCollectionSource::single(
+            DataCollection::from_slices(
+                &[] as &[NoGeometry],
+                &[TimeInterval::default(); 6],
+                &[
+                    (
+                        "ndvi",
+                        FeatureData::NullableFloat(vec![
+                            Some(0.125),
+                            Some(0.25),
+                            Some(0.75),
+                            Some(0.875),
+                            None,
+                            Some(f64::NAN),
+                        ]),
+                    ),
+                    (
+                        "land_cover",
+                        FeatureData::NullableText(vec![
+                            Some("urban".to_string()),
+                            Some("urban".to_string()),
+                            Some("forest".to_string()),
+                            None,
+                            Some("water".to_string()),
+                            Some("water".to_string()),
+                        ]),
+                    ),
+                ],
+            )
+            .unwrap(),
+        )
+        .boxed()
+    }
+
+    fn query() -> VectorQueryRectangle {
+        VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((-180., -90.).into(), (180., 90.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        }
+    }
+
+    fn training(classifier: ClassifierType) -> Box<dyn PlotOperator> {
+        ClassifierTraining {
+            params: ClassifierTrainingParams {
+                feature_columns: vec!["ndvi".to_string()],
+                label_column: "land_cover".to_string(),
+                classifier,
+                max_depth: None,
+                min_samples_split: 2,
+                seed: 0,
+            },
+            sources: source().into(),
+        }
+        .boxed()
+    }
+
+    async fn train_model(classifier: ClassifierType) -> ClassifierModel {
+        let processor = training(classifier)
+            .initialize(&MockExecutionContext::default())
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .json_plain()
+            .unwrap();
+
+        let result = processor
+            .plot_query(query(), &MockQueryContext::default())
+            .await
+            .unwrap();
+
+        serde_json::from_value(result).unwrap()
+    }
+
+    #[tokio::test]
+    async fn decision_tree() {
+        let model = train_model(ClassifierType::DecisionTree).await;
+
+        // samples without label or with invalid features are left out
+        assert_eq!(
+            model,
+            ClassifierModel {
+                feature_columns: vec!["ndvi".to_string()],
+                classes: vec!["forest".to_string(), "urban".to_string()],
+                trees: vec![DecisionTree::Split {
+                    feature: 0,
+                    threshold: 0.5,
+                    left: Box::new(DecisionTree::Leaf { class: 1 }),
+                    right: Box::new(DecisionTree::Leaf { class: 0 }),
+                }],
+            }
+        );
+
+        assert_eq!(model.predict(&[0.15]), "urban");
+        assert_eq!(model.predict(&[0.9]), "forest");
+    }
+
+    #[tokio::test]
+    async fn random_forest() {
+        let model = train_model(ClassifierType::RandomForest { number_of_trees: 5 }).await;
+
+        assert_eq!(model.trees.len(), 5);
+        assert_eq!(
+            model,
+            train_model(ClassifierType::RandomForest { number_of_trees: 5 }).await
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_params() {
+        let execution_context = MockExecutionContext::default();
+        let training = |params: ClassifierTrainingParams| {
+            ClassifierTraining {
+                params,
+                sources: source().into(),
+            }
+            .boxed()
+            .initialize(&execution_context)
+        };
+
+        let params = ClassifierTrainingParams {
+            feature_columns: vec!["land_cover".to_string()],
+            label_column: "land_cover".to_string(),
+            classifier: ClassifierType::DecisionTree,
+            max_depth: None,
+            min_samples_split: 2,
+            seed: 0,
+        };
+
+        // non-numeric feature
+        assert!(training(params.clone()).await.is_err());
+
+        assert!(training(ClassifierTrainingParams {
+            feature_columns: vec!["ndvi".to_string()],
+            label_column: "foo".to_string(),
+            ..params.clone()
+        })
+        .await
+        .is_err());
+
+        assert!(training(ClassifierTrainingParams {
+            feature_columns: vec!["ndvi".to_string()],
+            classifier: ClassifierType::RandomForest { number_of_trees: 0 },
+            ..params
+        })
+        .await
+        .is_err());
+    }
+
+    #[test]
+    fn deserialize_defaults() {
+        let params: ClassifierTrainingParams = serde_json::from_value(serde_json::json!({
+            "featureColumns": ["ndvi"],
+            "labelColumn": "land_cover",
+        }))
+        .unwrap();
+
+        assert_eq!(params.classifier, ClassifierType::DecisionTree);
+        assert_eq!(params.min_samples_split, 2);
+
+        let params: ClassifierTrainingParams = serde_json::from_value(serde_json::json!({
+            "featureColumns": ["ndvi"],
+            "labelColumn": "land_cover",
+            "classifier": { "type": "randomForest", "numberOfTrees": 10 },
+        }))
+        .unwrap();
+
+        assert_eq!(
+            params.classifier,
+            ClassifierType::RandomForest {
+                number_of_trees: 10
+            }
+        );
+    }
+}
@@ -0,0 +1,368 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::util::random::{split_mix, unit_interval};
+
+/// A trained classifier, i.e., a single decision tree or the trees of a random forest.
+///
+/// It is self-contained, s.t. it can be stored and later applied to new samples.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassifierModel {
+    /// The names of the features in the order that `predict` expects them
+    pub feature_columns: Vec<String>,
+    /// The labels of the classes that the trees refer to by index
+    pub classes: Vec<String>,
+    pub trees: Vec<DecisionTree>,
+}
+
+impl ClassifierModel {
+    /// Predicts the class label of a sample by a majority vote of the trees.
+    /// Ties are resolved in favor of the class that comes first.
+    pub fn predict(&self, features: &[f64]) -> &str {
+        let mut votes = vec![0_usize; self.classes.len()];
+        for tree in &self.trees {
+            votes[tree.predict(features)] += 1;
+        }
+
+        &self.classes[majority(&votes)]
+    }
+}
+
+/// A binary decision tree whose leafs are class indices
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum DecisionTree {
+    Leaf {
+        class: usize,
+    },
+    /// Samples whose `feature` is less than or equal to the `threshold` go to the `left`
+    Split {
+        feature: usize,
+        threshold: f64,
+        left: Box<DecisionTree>,
+        right: Box<DecisionTree>,
+    },
+}
+
+impl DecisionTree {
+    pub fn predict(&self, features: &[f64]) -> usize {
+        let mut node = self;
+        loop {
+            match node {
+                DecisionTree::Leaf { class } => return *class,
+                DecisionTree::Split {
+                    feature,
+                    threshold,
+                    left,
+                    right,
+                } => {
+                    node = if features[*feature] <= *threshold {
+                        left
+                    } else {
+                        right
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// Labeled samples with finite feature values
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingSamples {
+    pub features: Vec<Vec<f64>>,
+    /// The class index of each sample
+    pub labels: Vec<usize>,
+    pub number_of_classes: usize,
+}
+
+/// Limits the growth of the decision trees
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TreeLimits {
+    pub max_depth: Option<usize>,
+    /// Nodes with fewer samples become leafs
+    pub min_samples_split: usize,
+}
+
+/// Trains a decision tree by recursively choosing the split with the lowest Gini impurity
+pub fn train_decision_tree(samples: &TrainingSamples, limits: TreeLimits) -> DecisionTree {
+    let indices = (0..samples.labels.len()).collect();
+
+    TreeGrower {
+        samples,
+        limits,
+        features_per_split: None,
+        random: Random::new(0),
+    }
+    .grow(indices, 0)
+}
+
+/// Trains a random forest, i.e., trees on bootstrap samples that only consider
+/// a random subset of the features at each split.
+///
+/// The trees only depend on the `seed`, s.t. training is reproducible.
+pub fn train_random_forest(
+    samples: &TrainingSamples,
+    limits: TreeLimits,
+    number_of_trees: usize,
+    seed: u64,
+) -> Vec<DecisionTree> {
+    let number_of_samples = samples.labels.len();
+    let number_of_features = samples.features.first().map_or(0, Vec::len);
+    let features_per_split = ((number_of_features as f64).sqrt().ceil() as usize).max(1);
+
+    (0..number_of_trees as u64)
+        .map(|tree| {
+            let mut random = Random::new(split_mix(seed.wrapping_add(tree)));
+
+            let bootstrap = (0..number_of_samples)
+                .map(|_| random.below(number_of_samples))
+                .collect();
+
+            TreeGrower {
+                samples,
+                limits,
+                features_per_split: Some(features_per_split),
+                random,
+            }
+            .grow(bootstrap, 0)
+        })
+        .collect()
+}
+
+struct TreeGrower<'s> {
+    samples: &'s TrainingSamples,
+    limits: TreeLimits,
+    /// The number of randomly chosen features that are considered at each split, or all if `None`
+    features_per_split: Option<usize>,
+    random: Random,
+}
+
+impl<'s> TreeGrower<'s> {
+    fn grow(&mut self, mut indices: Vec<usize>, depth: usize) -> DecisionTree {
+        let counts = self.class_counts(&indices);
+        let class = majority(&counts);
+
+        let is_pure = counts.iter().filter(|&&count| count > 0).count() <= 1;
+        let is_too_deep = self.limits.max_depth.map_or(false, |max| depth >= max);
+        if is_pure || is_too_deep || indices.len() < self.limits.min_samples_split {
+            return DecisionTree::Leaf { class };
+        }
+
+        let (feature, threshold) = match self.best_split(&mut indices, &counts) {
+            Some(split) => split,
+            None => return DecisionTree::Leaf { class },
+        };
+
+        let (left, right): (Vec<usize>, Vec<usize>) = indices
+            .into_iter()
+            .partition(|&i| self.samples.features[i][feature] <= threshold);
+
+        DecisionTree::Split {
+            feature,
+            threshold,
+            left: Box::new(self.grow(left, depth + 1)),
+            right: Box::new(self.grow(right, depth + 1)),
+        }
+    }
+
+    /// Finds the feature and threshold that minimize the weighted Gini impurity of the two halves.
+    /// Returns `None` if no split reduces the impurity.
+    fn best_split(&mut self, indices: &mut [usize], counts: &[usize]) -> Option<(usize, f64)> {
+        let features = &self.samples.features;
+        let labels = &self.samples.labels;
+        let number_of_samples = indices.len();
+
+        let mut best_impurity = gini(counts, number_of_samples);
+        let mut best_split = None;
+
+        for feature in self.candidate_features() {
+            indices.sort_unstable_by(|&a, &b| {
+                features[a][feature]
+                    .partial_cmp(&features[b][feature])
+                    .unwrap_or(Ordering::Equal)
+            });
+
+            let mut left_counts = vec![0; counts.len()];
+            for position in 0..number_of_samples - 1 {
+                left_counts[labels[indices[position]]] += 1;
+
+                let value = features[indices[position]][feature];
+                let next_value = features[indices[position + 1]][feature];
+                if next_value <= value {
+                    // samples with equal values cannot be separated
+                    continue;
+                }
+
+                let right_counts: Vec<usize> = counts
+                    .iter()
+                    .zip(&left_counts)
+                    .map(|(count, left_count)| count - left_count)
+                    .collect();
+
+                let left_size = position + 1;
+                let right_size = number_of_samples - left_size;
+                let impurity = (left_size as f64 * gini(&left_counts, left_size)
+                    + right_size as f64 * gini(&right_counts, right_size))
+                    / number_of_samples as f64;
+
+                if impurity < best_impurity {
+                    best_impurity = impurity;
+                    best_split = Some((feature, (value + next_value) / 2.));
+                }
+            }
+        }
+
+        best_split
+    }
+
+    fn candidate_features(&mut self) -> Vec<usize> {
+        let number_of_features = self.samples.features.first().map_or(0, Vec::len);
+        let mut features: Vec<usize> = (0..number_of_features).collect();
+
+        if let Some(features_per_split) = self.features_per_split {
+            // partial Fisher-Yates shuffle
+            let chosen = features_per_split.min(number_of_features);
+            for i in 0..chosen {
+                let j = i + self.random.below(number_of_features - i);
+                features.swap(i, j);
+            }
+            features.truncate(chosen);
+        }
+
+        features
+    }
+
+    fn class_counts(&self, indices: &[usize]) -> Vec<usize> {
+        let mut counts = vec![0; self.samples.number_of_classes];
+        for &i in indices {
+            counts[self.samples.labels[i]] += 1;
+        }
+        counts
+    }
+}
+
+fn gini(counts: &[usize], total: usize) -> f64 {
+    if total == 0 {
+        return 0.;
+    }
+
+    1. - counts
+        .iter()
+        .map(|&count| (count as f64 / total as f64).powi(2))
+        .sum::<f64>()
+}
+
+/// The index of the largest count, preferring the first one
+fn majority(counts: &[usize]) -> usize {
+    counts
+        .iter()
+        .enumerate()
+        .fold((0, 0), |(best, best_count), (class, &count)| {
+            if count > best_count {
+                (class, count)
+            } else {
+                (best, best_count)
+            }
+        })
+        .0
+}
+
+/// A reproducible sequence of random numbers
+struct Random {
+    seed: u64,
+    index: u64,
+}
+
+impl Random {
+    fn new(seed: u64) -> Self {
+        Self { seed, index: 0 }
+    }
+
+    /// A random number in `[0, n)`
+    fn below(&mut self, n: usize) -> usize {
+        let value = unit_interval(split_mix(self.seed.wrapping_add(self.index)));
+        self.index += 1;
+
+        ((value * n as f64) as usize).min(n.saturating_sub(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Class 1 iff the first feature is greater than 5, the second feature is noise
+    fn samples() -> TrainingSamples {
+        let features: Vec<Vec<f64>> = (0..20)
+            .map(|i| vec![f64::from(i) / 2., f64::from((i * 7) % 5)])
+            .collect();
+        let labels = features
+            .iter()
+            .map(|sample| if sample[0] > 5. { 1 } else { 0 })
+            .collect();
+
+        TrainingSamples {
+            features,
+            labels,
+            number_of_classes: 2,
+        }
+    }
+
+    const UNLIMITED: TreeLimits = TreeLimits {
+        max_depth: None,
+        min_samples_split: 2,
+    };
+
+    #[test]
+    fn decision_tree() {
+        let tree = train_decision_tree(&samples(), UNLIMITED);
+
+        assert_eq!(
+            tree,
+            DecisionTree::Split {
+                feature: 0,
+                threshold: 5.25,
+                left: Box::new(DecisionTree::Leaf { class: 0 }),
+                right: Box::new(DecisionTree::Leaf { class: 1 }),
+            }
+        );
+
+        assert_eq!(tree.predict(&[1., 3.]), 0);
+        assert_eq!(tree.predict(&[7., 3.]), 1);
+    }
+
+    #[test]
+    fn max_depth() {
+        let tree = train_decision_tree(
+            &samples(),
+            TreeLimits {
+                max_depth: Some(0),
+                ..UNLIMITED
+            },
+        );
+
+        // 11 samples of class 0 vs. 9 samples of class 1
+        assert_eq!(tree, DecisionTree::Leaf { class: 0 });
+    }
+
+    #[test]
+    fn random_forest() {
+        let samples = samples();
+
+        let trees = train_random_forest(&samples, UNLIMITED, 9, 42);
+        assert_eq!(trees.len(), 9);
+        assert_eq!(trees, train_random_forest(&samples, UNLIMITED, 9, 42));
+
+        let model = ClassifierModel {
+            feature_columns: vec!["a".to_string(), "b".to_string()],
+            classes: vec!["low".to_string(), "high".to_string()],
+            trees,
+        };
+
+        assert_eq!(model.predict(&[0., 1.]), "low");
+        assert_eq!(model.predict(&[9.5, 1.]), "high");
+    }
+}
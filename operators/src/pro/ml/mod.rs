@@ -0,0 +1,11 @@
+mod classifier_training;
+mod decision_tree;
+
+pub use self::classifier_training::{
+    ClassifierTraining, ClassifierTrainingParams, ClassifierTrainingQueryProcessor, ClassifierType,
+    InitializedClassifierTraining,
+};
+pub use self::decision_tree::{
+    train_decision_tree, train_random_forest, ClassifierModel, DecisionTree, TrainingSamples,
+    TreeLimits,
+};
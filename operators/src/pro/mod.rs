@@ -1 +1,3 @@
 // This is an inclusion point of Geo Engine Pro
+
+pub mod ml;
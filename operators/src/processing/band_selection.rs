@@ -0,0 +1,446 @@
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use geoengine_datatypes::primitives::{Measurement, SpatialPartition2D};
+use geoengine_datatypes::raster::{Pixel, RasterTile2D};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::engine::{
+    ExecutionContext, InitializedRasterOperator, Operator, QueryContext, QueryProcessor,
+    RasterBandDescriptor, RasterOperator, RasterQueryProcessor, RasterQueryRectangle,
+    RasterResultDescriptor, SingleRasterSource, TypedRasterQueryProcessor,
+};
+use crate::error;
+use crate::util::Result;
+
+/// An operator that selects bands of a multiband raster, e.g., of a `RasterStacker`, by their names or indices.
+///
+/// The selection of a single band results in a single band raster with the measurement of the selected band.
+/// Otherwise, the output is a multiband raster with the selected bands in the order of the selection.
+pub type BandSelection = Operator<BandSelectionParams, SingleRasterSource>;
+
+/// The parameter spec for `BandSelection`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BandSelectionParams {
+    pub bands: Vec<BandSelector>,
+}
+
+/// A band of a multiband raster
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum BandSelector {
+    /// The zero-based index of the band
+    Index(usize),
+    Name(String),
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for BandSelection {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        ensure!(
+            !self.params.bands.is_empty(),
+            error::InvalidOperatorSpec {
+                reason: "at least one band must be selected".to_string()
+            }
+        );
+
+        let source = self.sources.raster.initialize(context).await?;
+        let source_descriptor = source.result_descriptor();
+
+        ensure!(
+            !source_descriptor.bands.is_empty(),
+            error::InvalidOperatorSpec {
+                reason: "only bands of multiband rasters can be selected".to_string()
+            }
+        );
+
+        let mut indices = Vec::with_capacity(self.params.bands.len());
+        for selector in self.params.bands {
+            let index = match selector {
+                BandSelector::Index(index) if index < source_descriptor.bands.len() => index,
+                BandSelector::Index(index) => {
+                    return Err(error::Error::RasterBandDoesNotExist {
+                        band: index.to_string(),
+                    })
+                }
+                BandSelector::Name(name) => source_descriptor
+                    .bands
+                    .iter()
+                    .position(|band| band.name == name)
+                    .ok_or(error::Error::RasterBandDoesNotExist { band: name })?,
+            };
+
+            ensure!(
+                !indices.contains(&index),
+                error::InvalidOperatorSpec {
+                    reason: format!(
+                        "band `{}` is selected more than once",
+                        source_descriptor.bands[index].name
+                    )
+                }
+            );
+
+            indices.push(index);
+        }
+
+        let result_descriptor = if let [index] = indices[..] {
+            RasterResultDescriptor {
+                measurement: source_descriptor.bands[index].measurement.clone(),
+                bands: Vec::new(),
+                ..source_descriptor.clone()
+            }
+        } else {
+            let bands: Vec<RasterBandDescriptor> = indices
+                .iter()
+                .map(|&index| source_descriptor.bands[index].clone())
+                .collect();
+
+            RasterResultDescriptor {
+                measurement: if bands
+                    .iter()
+                    .all(|band| band.measurement == bands[0].measurement)
+                {
+                    bands[0].measurement.clone()
+                } else {
+                    Measurement::Unitless
+                },
+                bands,
+                ..source_descriptor.clone()
+            }
+        };
+
+        Ok(InitializedBandSelection {
+            result_descriptor,
+            source_bands: source_descriptor.bands.len(),
+            indices,
+            source,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedBandSelection {
+    result_descriptor: RasterResultDescriptor,
+    source_bands: usize,
+    indices: Vec<usize>,
+    source: Box<dyn InitializedRasterOperator>,
+}
+
+impl InitializedRasterOperator for InitializedBandSelection {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let source_processor = self.source.query_processor()?;
+
+        Ok(call_on_generic_raster_processor!(
+            source_processor, source => BandSelectionProcessor {
+                source,
+                source_bands: self.source_bands,
+                indices: self.indices.clone(),
+                single_band: self.result_descriptor.bands.is_empty(),
+            }
+            .boxed()
+            .into()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct BandSelectionProcessor<T> {
+    source: Box<dyn RasterQueryProcessor<RasterType = T>>,
+    /// The number of tiles of the source per position and time
+    source_bands: usize,
+    indices: Vec<usize>,
+    single_band: bool,
+}
+
+#[async_trait]
+impl<T> QueryProcessor for BandSelectionProcessor<T>
+where
+    T: Pixel,
+{
+    type Output = RasterTile2D<T>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        Ok(self
+            .source
+            .query(query, ctx)
+            .await?
+            .chunks(self.source_bands)
+            .flat_map(move |tiles| {
+                let tiles: Vec<Result<RasterTile2D<T>>> =
+                    match tiles.into_iter().collect::<Result<Vec<_>>>() {
+                        Ok(tiles) if tiles.len() == self.source_bands => self
+                            .indices
+                            .iter()
+                            .map(|&index| {
+                                let mut tile = tiles[index].clone();
+                                if self.single_band {
+                                    tile.properties.band_name = None;
+                                }
+                                Ok(tile)
+                            })
+                            .collect(),
+                        Ok(_) => vec![Err(error::Error::InvalidOperatorSpec {
+                            reason: "the source ended with an incomplete set of bands".to_string(),
+                        })],
+                        Err(error) => vec![Err(error)],
+                    };
+
+                stream::iter(tiles)
+            })
+            .boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, MultipleRasterSources};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use crate::processing::{RasterStacker, RasterStackerBand, RasterStackerParams};
+    use futures::TryStreamExt;
+    use geoengine_datatypes::primitives::{Measurement, SpatialResolution, TimeInterval};
+    use geoengine_datatypes::raster::{Grid2D, RasterDataType, TileInformation};
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+
+    fn make_raster(values: Vec<u8>) -> Box<dyn RasterOperator> {
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![RasterTile2D::new_with_tile_info(
+                    TimeInterval::new_unchecked(0, 1),
+                    TileInformation {
+                        global_tile_position: [-1, 0].into(),
+                        tile_size_in_pixels: [2, 2].into(),
+                        global_geo_transform: Default::default(),
+                    },
+                    Grid2D::new([2, 2].into(), values, Some(0)).unwrap().into(),
+                )],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: Some(0.),
+                    bands: Vec::new(),
+                },
+            },
+        }
+        .boxed()
+    }
+
+    fn stack() -> Box<dyn RasterOperator> {
+        RasterStacker {
+            params: RasterStackerParams {
+                bands: vec![
+                    RasterStackerBand {
+                        name: "red".to_string(),
+                        measurement: None,
+                    },
+                    RasterStackerBand {
+                        name: "nir".to_string(),
+                        measurement: Some(Measurement::continuous("reflectance".to_string(), None)),
+                    },
+                ],
+            },
+            sources: MultipleRasterSources {
+                rasters: vec![make_raster(vec![1, 2, 3, 4]), make_raster(vec![5, 6, 7, 8])],
+            },
+        }
+        .boxed()
+    }
+
+    #[tokio::test]
+    async fn it_selects_bands() {
+        let initialized = BandSelection {
+            params: BandSelectionParams {
+                bands: vec![BandSelector::Name("nir".to_string())],
+            },
+            sources: SingleRasterSource { raster: stack() },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .unwrap();
+
+        assert_eq!(initialized.result_descriptor().number_of_bands(), 1);
+        assert_eq!(
+            initialized.result_descriptor().measurement,
+            Measurement::continuous("reflectance".to_string(), None)
+        );
+
+        let processor = initialized.query_processor().unwrap().get_u8().unwrap();
+
+        let tiles: Vec<RasterTile2D<u8>> = processor
+            .query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new_unchecked(
+                        (0., 2.).into(),
+                        (2., 0.).into(),
+                    ),
+                    time_interval: TimeInterval::new_unchecked(0, 1),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(tiles.len(), 1);
+        assert_eq!(tiles[0].properties.band_name, None);
+        assert_eq!(
+            tiles[0].clone().into_materialized_tile().grid_array.data,
+            vec![5, 6, 7, 8]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_checks_the_band() {
+        let result = BandSelection {
+            params: BandSelectionParams {
+                bands: vec![BandSelector::Name("blue".to_string())],
+            },
+            sources: SingleRasterSource { raster: stack() },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(error::Error::RasterBandDoesNotExist { band }) if band == "blue"
+        ));
+
+        let result = BandSelection {
+            params: BandSelectionParams {
+                bands: vec![BandSelector::Index(2)],
+            },
+            sources: SingleRasterSource { raster: stack() },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(error::Error::RasterBandDoesNotExist { band }) if band == "2"
+        ));
+
+        let result = BandSelection {
+            params: BandSelectionParams {
+                bands: vec![
+                    BandSelector::Index(1),
+                    BandSelector::Name("nir".to_string()),
+                ],
+            },
+            sources: SingleRasterSource { raster: stack() },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(error::Error::InvalidOperatorSpec { .. })
+        ));
+
+        let result = BandSelection {
+            params: BandSelectionParams {
+                bands: vec![BandSelector::Index(0)],
+            },
+            sources: SingleRasterSource {
+                raster: make_raster(vec![1, 2, 3, 4]),
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(error::Error::InvalidOperatorSpec { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_reorders_bands() {
+        let initialized = BandSelection {
+            params: BandSelectionParams {
+                bands: vec![
+                    BandSelector::Index(1),
+                    BandSelector::Name("red".to_string()),
+                ],
+            },
+            sources: SingleRasterSource { raster: stack() },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .unwrap();
+
+        let band_names: Vec<&str> = initialized
+            .result_descriptor()
+            .bands
+            .iter()
+            .map(|band| band.name.as_str())
+            .collect();
+        assert_eq!(band_names, vec!["nir", "red"]);
+        assert_eq!(
+            initialized.result_descriptor().measurement,
+            Measurement::Unitless
+        );
+
+        let processor = initialized.query_processor().unwrap().get_u8().unwrap();
+
+        let tiles: Vec<RasterTile2D<u8>> = processor
+            .query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new_unchecked(
+                        (0., 2.).into(),
+                        (2., 0.).into(),
+                    ),
+                    time_interval: TimeInterval::new_unchecked(0, 1),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        let tiles: Vec<(Option<String>, Vec<u8>)> = tiles
+            .into_iter()
+            .map(|tile| {
+                (
+                    tile.properties.band_name.clone(),
+                    tile.into_materialized_tile().grid_array.data.to_vec(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            tiles,
+            vec![
+                (Some("nir".to_string()), vec![5, 6, 7, 8]),
+                (Some("red".to_string()), vec![1, 2, 3, 4]),
+            ]
+        );
+    }
+}
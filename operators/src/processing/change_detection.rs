@@ -0,0 +1,590 @@
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::{try_join, StreamExt, TryStreamExt};
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::primitives::{
+    Measurement, SpatialPartition2D, TimeInstance, TimeInterval,
+};
+use geoengine_datatypes::raster::{
+    EmptyGrid2D, FromPrimitive, Grid2D, GridOrEmpty, NoDataValue, Pixel, RasterDataType,
+    RasterTile2D, TileInformation,
+};
+use num_traits::AsPrimitive;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::engine::{
+    ExecutionContext, InitializedRasterOperator, Operator, OperatorDatasets, QueryContext,
+    QueryProcessor, RasterOperator, RasterQueryProcessor, RasterQueryRectangle,
+    RasterResultDescriptor, TypedRasterQueryProcessor,
+};
+use crate::error;
+use crate::util::Result;
+
+const NO_CHANGE: u8 = 0;
+const CHANGE: u8 = 1;
+const CLASSIFICATION_NO_DATA: u8 = 255;
+
+/// An operator that compares a raster at two points in time.
+///
+/// The `raster` is evaluated at `before` and the `afterRaster`, which defaults to the `raster`,
+/// is evaluated at `after`. The output is valid from `before` to `after`.
+///
+/// Without `thresholds`, the output contains the per-pixel change as `F64`.
+/// With `thresholds`, it is a classification with `1` for change and `0` for no change.
+/// Pixels with no data at either point in time have no data in the output.
+pub type ChangeDetection = Operator<ChangeDetectionParams, ChangeDetectionSources>;
+
+/// The parameter spec for `ChangeDetection`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeDetectionParams {
+    pub before: TimeInstance,
+    pub after: TimeInstance,
+    pub method: ChangeDetectionMethod,
+    #[serde(default)]
+    pub thresholds: Option<ChangeThresholds>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeDetectionMethod {
+    /// `after - before`
+    Difference,
+    /// `after / before`, which is no data if `before` is zero
+    Ratio,
+}
+
+impl ChangeDetectionMethod {
+    fn change(self, before: f64, after: f64) -> Option<f64> {
+        match self {
+            ChangeDetectionMethod::Difference => Some(after - before),
+            ChangeDetectionMethod::Ratio if before == 0. => None,
+            ChangeDetectionMethod::Ratio => Some(after / before),
+        }
+    }
+}
+
+/// A change below `lower` or above `upper` is classified as change
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeThresholds {
+    #[serde(default)]
+    pub lower: Option<f64>,
+    #[serde(default)]
+    pub upper: Option<f64>,
+}
+
+impl ChangeThresholds {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            self.lower.is_some() || self.upper.is_some(),
+            error::InvalidOperatorSpec {
+                reason: "the `thresholds` need a `lower` or an `upper` bound".to_string(),
+            }
+        );
+
+        if let (Some(lower), Some(upper)) = (self.lower, self.upper) {
+            ensure!(
+                lower <= upper,
+                error::InvalidOperatorSpec {
+                    reason: "the `lower` threshold must not exceed the `upper` one".to_string(),
+                }
+            );
+        }
+
+        Ok(())
+    }
+
+    fn classify(&self, change: f64) -> u8 {
+        let below = self.lower.map_or(false, |lower| change < lower);
+        let above = self.upper.map_or(false, |upper| change > upper);
+
+        if below || above {
+            CHANGE
+        } else {
+            NO_CHANGE
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeDetectionSources {
+    /// The raster that is evaluated at `before`
+    pub raster: Box<dyn RasterOperator>,
+    /// The raster that is evaluated at `after`, if it differs from `raster`
+    #[serde(default)]
+    pub after_raster: Option<Box<dyn RasterOperator>>,
+}
+
+impl OperatorDatasets for ChangeDetectionSources {
+    fn datasets_collect(&self, datasets: &mut Vec<DatasetId>) {
+        self.raster.datasets_collect(datasets);
+        if let Some(after_raster) = &self.after_raster {
+            after_raster.datasets_collect(datasets);
+        }
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for ChangeDetection {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let params = self.params;
+
+        ensure!(
+            params.before < params.after,
+            error::InvalidOperatorSpec {
+                reason: "`before` must be earlier than `after`".to_string(),
+            }
+        );
+        if let Some(thresholds) = &params.thresholds {
+            thresholds.validate()?;
+        }
+
+        let (before, after) = match self.sources.after_raster {
+            Some(after_raster) => {
+                let (before, after) = try_join!(
+                    self.sources.raster.initialize(context),
+                    after_raster.initialize(context)
+                )?;
+                (before, Some(after))
+            }
+            None => (self.sources.raster.initialize(context).await?, None),
+        };
+
+        for source in std::iter::once(&before).chain(&after) {
+            ensure!(
+                source.result_descriptor().number_of_bands() == 1,
+                error::InvalidOperatorSpec {
+                    reason: "multiband rasters cannot be checked for changes".to_string()
+                }
+            );
+        }
+
+        if let Some(after) = &after {
+            let expected = before.result_descriptor().spatial_reference;
+            let found = after.result_descriptor().spatial_reference;
+            ensure!(
+                expected == found,
+                error::InvalidSpatialReference { expected, found }
+            );
+        }
+
+        let source_descriptor = before.result_descriptor();
+        let result_descriptor = if params.thresholds.is_some() {
+            RasterResultDescriptor {
+                data_type: RasterDataType::U8,
+                measurement: Measurement::Classification {
+                    measurement: "change".to_string(),
+                    classes: [
+                        (NO_CHANGE, "no change".to_string()),
+                        (CHANGE, "change".to_string()),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                },
+                no_data_value: Some(f64::from(CLASSIFICATION_NO_DATA)),
+                ..source_descriptor.clone()
+            }
+        } else {
+            RasterResultDescriptor {
+                data_type: RasterDataType::F64,
+                measurement: match params.method {
+                    ChangeDetectionMethod::Difference => source_descriptor.measurement.clone(),
+                    ChangeDetectionMethod::Ratio => Measurement::Unitless,
+                },
+                no_data_value: Some(f64::NAN),
+                ..source_descriptor.clone()
+            }
+        };
+
+        Ok(InitializedChangeDetection {
+            result_descriptor,
+            before,
+            after,
+            params,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedChangeDetection {
+    result_descriptor: RasterResultDescriptor,
+    before: Box<dyn InitializedRasterOperator>,
+    after: Option<Box<dyn InitializedRasterOperator>>,
+    params: ChangeDetectionParams,
+}
+
+impl InitializedChangeDetection {
+    fn processor<T: Pixel>(&self) -> Result<ChangeDetectionProcessor<T>> {
+        Ok(ChangeDetectionProcessor {
+            before: self.before.query_processor()?,
+            after: self
+                .after
+                .as_ref()
+                .unwrap_or(&self.before)
+                .query_processor()?,
+            time: TimeInterval::new(self.params.before, self.params.after)?,
+            method: self.params.method,
+            thresholds: self.params.thresholds,
+            pixel_type: PhantomData,
+        })
+    }
+}
+
+impl InitializedRasterOperator for InitializedChangeDetection {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        Ok(if self.params.thresholds.is_some() {
+            TypedRasterQueryProcessor::U8(self.processor::<u8>()?.boxed())
+        } else {
+            TypedRasterQueryProcessor::F64(self.processor::<f64>()?.boxed())
+        })
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// Outputs `u8` classes if there are `thresholds` and `f64` changes otherwise
+pub struct ChangeDetectionProcessor<T> {
+    before: TypedRasterQueryProcessor,
+    after: TypedRasterQueryProcessor,
+    time: TimeInterval,
+    method: ChangeDetectionMethod,
+    thresholds: Option<ChangeThresholds>,
+    pixel_type: PhantomData<T>,
+}
+
+/// The values of a source tile with `None` for no data
+struct TileValues {
+    time: TimeInterval,
+    tile_info: TileInformation,
+    values: Option<Vec<Option<f64>>>,
+}
+
+impl TileValues {
+    fn new<P: Pixel>(tile: RasterTile2D<P>) -> Self {
+        let values = match &tile.grid_array {
+            GridOrEmpty::Grid(grid) => Some(
+                grid.data
+                    .iter()
+                    .map(|&value| {
+                        if grid.is_no_data(value) {
+                            None
+                        } else {
+                            Some(value.as_())
+                        }
+                    })
+                    .collect(),
+            ),
+            GridOrEmpty::Empty(_) => None,
+        };
+
+        Self {
+            time: tile.time,
+            tile_info: tile.tile_information(),
+            values,
+        }
+    }
+}
+
+impl<T> ChangeDetectionProcessor<T>
+where
+    T: Pixel,
+{
+    fn no_data_value(&self) -> T {
+        if self.thresholds.is_some() {
+            T::from_(CLASSIFICATION_NO_DATA)
+        } else {
+            T::from_(f64::NAN)
+        }
+    }
+
+    async fn values_at<'a>(
+        processor: &'a TypedRasterQueryProcessor,
+        instant: TimeInstance,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<TileValues>>> {
+        let query = RasterQueryRectangle {
+            time_interval: TimeInterval::new_instant(instant)?,
+            ..query
+        };
+
+        Ok(call_on_generic_raster_processor!(processor, processor => {
+            processor
+                .raster_query(query, ctx)
+                .await?
+                .map_ok(TileValues::new)
+                .boxed()
+        }))
+    }
+
+    fn change_tile(&self, before: TileValues, after: TileValues) -> Result<RasterTile2D<T>> {
+        let tile_info = before.tile_info;
+
+        ensure!(
+            tile_info.global_tile_position == after.tile_info.global_tile_position,
+            error::UnalignedRasterTiles {
+                position_a: tile_info.global_tile_position,
+                time_a: before.time,
+                position_b: after.tile_info.global_tile_position,
+                time_b: after.time,
+            }
+        );
+
+        let no_data_value = self.no_data_value();
+
+        let grid = match (before.values, after.values) {
+            (Some(before), Some(after)) => {
+                let data = before
+                    .into_iter()
+                    .zip(after)
+                    .map(|values| match values {
+                        (Some(before), Some(after)) => self.output_value(before, after),
+                        _ => no_data_value,
+                    })
+                    .collect();
+
+                Grid2D::new(tile_info.tile_size_in_pixels, data, Some(no_data_value))?.into()
+            }
+            _ => EmptyGrid2D::new(tile_info.tile_size_in_pixels, no_data_value).into(),
+        };
+
+        Ok(RasterTile2D::new_with_tile_info(self.time, tile_info, grid))
+    }
+
+    fn output_value(&self, before: f64, after: f64) -> T {
+        match (self.method.change(before, after), &self.thresholds) {
+            (Some(change), Some(thresholds)) => T::from_(thresholds.classify(change)),
+            (Some(change), None) => T::from_(change),
+            (None, _) => self.no_data_value(),
+        }
+    }
+}
+
+#[async_trait]
+impl<T> QueryProcessor for ChangeDetectionProcessor<T>
+where
+    T: Pixel,
+{
+    type Output = RasterTile2D<T>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        if !query.time_interval.intersects(&self.time) {
+            return Ok(stream::empty().boxed());
+        }
+
+        let (before, after) = try_join!(
+            Self::values_at(&self.before, self.time.start(), query, ctx),
+            Self::values_at(&self.after, self.time.end(), query, ctx)
+        )?;
+
+        Ok(before
+            .zip(after)
+            .map(move |(before, after)| self.change_tile(before?, after?))
+            .boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::primitives::SpatialResolution;
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+
+    fn mock_raster() -> Box<dyn RasterOperator> {
+        let tile_info = TileInformation {
+            global_geo_transform: Default::default(),
+            global_tile_position: [-1, 0].into(),
+            tile_size_in_pixels: [1, 4].into(),
+        };
+
+        let data = vec![
+            RasterTile2D::new_with_tile_info(
+                TimeInterval::new_unchecked(0, 10),
+                tile_info,
+                Grid2D::new([1, 4].into(), vec![10, 10, 0, 4], Some(0))
+                    .unwrap()
+                    .into(),
+            ),
+            RasterTile2D::new_with_tile_info(
+                TimeInterval::new_unchecked(10, 20),
+                tile_info,
+                Grid2D::new([1, 4].into(), vec![12, 30, 5, 2], Some(0))
+                    .unwrap()
+                    .into(),
+            ),
+        ];
+
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data,
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: Some(0.),
+                    bands: Vec::new(),
+                },
+            },
+        }
+        .boxed()
+    }
+
+    async fn detect_changes(
+        method: ChangeDetectionMethod,
+        thresholds: Option<ChangeThresholds>,
+    ) -> TypedRasterQueryProcessor {
+        ChangeDetection {
+            params: ChangeDetectionParams {
+                before: TimeInstance::from_millis_unchecked(5),
+                after: TimeInstance::from_millis_unchecked(15),
+                method,
+                thresholds,
+            },
+            sources: ChangeDetectionSources {
+                raster: mock_raster(),
+                after_raster: None,
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+    }
+
+    async fn query<T: Pixel>(
+        processor: Box<dyn RasterQueryProcessor<RasterType = T>>,
+        time_interval: TimeInterval,
+    ) -> Vec<RasterTile2D<T>> {
+        processor
+            .raster_query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new_unchecked(
+                        (0., 1.).into(),
+                        (4., 0.).into(),
+                    ),
+                    time_interval,
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn difference() {
+        let processor = detect_changes(ChangeDetectionMethod::Difference, None)
+            .await
+            .get_f64()
+            .unwrap();
+
+        let mut tiles = query(processor, TimeInterval::new_unchecked(0, 20)).await;
+
+        assert_eq!(tiles.len(), 1);
+        assert_eq!(tiles[0].time, TimeInterval::new_unchecked(5, 15));
+
+        let values: Vec<Option<f64>> = TileValues::new(tiles.remove(0)).values.unwrap();
+        assert_eq!(values, vec![Some(2.), Some(20.), None, Some(-2.)]);
+    }
+
+    #[tokio::test]
+    async fn ratio_classification() {
+        let processor = detect_changes(
+            ChangeDetectionMethod::Ratio,
+            Some(ChangeThresholds {
+                lower: Some(0.75),
+                upper: Some(1.5),
+            }),
+        )
+        .await
+        .get_u8()
+        .unwrap();
+
+        let mut tiles = query(processor, TimeInterval::new_instant(10).unwrap()).await;
+
+        assert_eq!(tiles.len(), 1);
+        assert_eq!(
+            tiles.remove(0).grid_array.into_materialized_grid().data,
+            vec![NO_CHANGE, CHANGE, CLASSIFICATION_NO_DATA, CHANGE]
+        );
+    }
+
+    #[tokio::test]
+    async fn outside_of_the_interval() {
+        let processor = detect_changes(ChangeDetectionMethod::Difference, None)
+            .await
+            .get_f64()
+            .unwrap();
+
+        assert!(query(processor, TimeInterval::new_unchecked(15, 20))
+            .await
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn invalid_params() {
+        let operator = |before, thresholds| ChangeDetection {
+            params: ChangeDetectionParams {
+                before: TimeInstance::from_millis_unchecked(before),
+                after: TimeInstance::from_millis_unchecked(15),
+                method: ChangeDetectionMethod::Difference,
+                thresholds,
+            },
+            sources: ChangeDetectionSources {
+                raster: mock_raster(),
+                after_raster: None,
+            },
+        };
+
+        for operator in [
+            operator(15, None),
+            operator(
+                5,
+                Some(ChangeThresholds {
+                    lower: None,
+                    upper: None,
+                }),
+            ),
+            operator(
+                5,
+                Some(ChangeThresholds {
+                    lower: Some(2.),
+                    upper: Some(1.),
+                }),
+            ),
+        ] {
+            assert!(matches!(
+                operator
+                    .boxed()
+                    .initialize(&MockExecutionContext::default())
+                    .await,
+                Err(error::Error::InvalidOperatorSpec { .. })
+            ));
+        }
+    }
+}
@@ -15,11 +15,12 @@ use geoengine_datatypes::collections::{
 };
 use geoengine_datatypes::primitives::{BoundingBox2D, FeatureDataType, FeatureDataValue, Geometry};
 use geoengine_datatypes::util::arrow::ArrowTyped;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
 use std::ops::RangeInclusive;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ColumnRangeFilterParams {
     pub column: String,
@@ -135,6 +136,14 @@ where
                         expected: "text, float, or int".to_string(),
                         found: "category".to_string(),
                     }),
+                    FeatureDataType::Bytes => Err(error::Error::InvalidType {
+                        expected: "text, float, or int".to_string(),
+                        found: "bytes".to_string(),
+                    }),
+                    FeatureDataType::Dictionary => Err(error::Error::InvalidType {
+                        expected: "text, float, or int".to_string(),
+                        found: "dictionary".to_string(),
+                    }),
                 };
 
             collection
@@ -0,0 +1,975 @@
+use crate::error::Error;
+use crate::util::Result;
+
+/// The offsets of neighbor accesses are clamped to this radius, i.e., to a 3x3 window
+pub const NEIGHBORHOOD_RADIUS: isize = 1;
+
+/// The variable names of the input rasters in the order of the inputs
+pub const RASTER_VARIABLES: [&str; 8] = ["A", "B", "C", "D", "E", "F", "G", "H"];
+
+/// An expression compiled into a sequence of stack machine instructions.
+///
+/// Compiling and evaluating do not recurse, so neither deeply nested expressions can overflow the call stack
+/// nor is an external compiler required.
+/// The evaluation of a pixel neither allocates nor parses and constant sub-expressions are folded beforehand.
+///
+/// The expression language consists of
+/// - numbers, e.g., `1`, `0.5` or `1e-3`,
+/// - the raster variables `A` to `H`, and the variables `A_PREV`, `B_PREV`, `t` and `dt`,
+/// - the neighbor accesses `A_AT(dx, dy)` to `H_AT(dx, dy)` with constant integer offsets,
+/// - the operators `+`, `-`, `*`, `/`, `%`, `<`, `<=`, `>`, `>=`, `==`, `!=`, `&&`, `||` and `!`,
+/// - the functions `abs`, `sqrt`, `exp`, `ln`, `log10`, `floor`, `ceil`, `round`, `isnan`, `min`, `max` and `pow`, and
+/// - the conditional `if condition then value else other_value`, whose `else` branch extends as far as possible.
+///
+/// Comparisons and logical operators evaluate to `1` or `0` and a condition holds if it is not `0`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpressionProgram {
+    instructions: Vec<Instruction>,
+    stack_size: usize,
+}
+
+/// The values of all pixels of the input tiles that an `ExpressionProgram` can access
+#[derive(Debug, Clone, Copy)]
+pub struct PixelInputs<'a> {
+    /// the values of the input rasters in the order of `RASTER_VARIABLES`
+    pub rasters: &'a [&'a [f64]],
+    /// may be empty if the program does not use the previous time step
+    pub previous_a: &'a [f64],
+    /// may be empty if the program does not use the previous time step
+    pub previous_b: &'a [f64],
+    pub width: usize,
+    pub height: usize,
+    pub t: f64,
+    pub dt: f64,
+}
+
+impl<'a> PixelInputs<'a> {
+    fn value(&self, variable: Variable, index: usize) -> f64 {
+        match variable {
+            Variable::Raster(raster) => self.rasters[raster][index],
+            Variable::PreviousA => self.previous_a[index],
+            Variable::PreviousB => self.previous_b[index],
+            Variable::T => self.t,
+            Variable::Dt => self.dt,
+        }
+    }
+
+    /// Neighbors beyond the border of the inputs are clamped to the border.
+    /// Callers that need the actual neighbors evaluate only pixels that are at least
+    /// `NEIGHBORHOOD_RADIUS` pixels away from the border.
+    fn neighbor(&self, raster: usize, x: usize, y: usize, dx: isize, dy: isize) -> f64 {
+        let x = (x as isize + dx).max(0).min(self.width as isize - 1) as usize;
+        let y = (y as isize + dy).max(0).min(self.height as isize - 1) as usize;
+
+        self.rasters[raster][y * self.width + x]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Instruction {
+    Constant(f64),
+    Load(Variable),
+    LoadNeighbor {
+        raster: usize,
+        dx: isize,
+        dy: isize,
+    },
+    Unary(UnaryOperator),
+    Binary(BinaryOperator),
+    /// Chooses between the second and the third operand depending on the first one
+    Select,
+}
+
+impl Instruction {
+    /// The number of values the instruction pops from the stack
+    fn operands(self) -> usize {
+        match self {
+            Instruction::Constant(_) | Instruction::Load(_) | Instruction::LoadNeighbor { .. } => 0,
+            Instruction::Unary(_) => 1,
+            Instruction::Binary(_) => 2,
+            Instruction::Select => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Variable {
+    /// the index of the raster in `RASTER_VARIABLES`
+    Raster(usize),
+    PreviousA,
+    PreviousB,
+    T,
+    Dt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UnaryOperator {
+    Negate,
+    Not,
+    Abs,
+    Sqrt,
+    Exp,
+    Ln,
+    Log10,
+    Floor,
+    Ceil,
+    Round,
+    IsNan,
+}
+
+impl UnaryOperator {
+    fn apply(self, value: f64) -> f64 {
+        match self {
+            UnaryOperator::Negate => -value,
+            UnaryOperator::Not => boolean(value == 0.),
+            UnaryOperator::Abs => value.abs(),
+            UnaryOperator::Sqrt => value.sqrt(),
+            UnaryOperator::Exp => value.exp(),
+            UnaryOperator::Ln => value.ln(),
+            UnaryOperator::Log10 => value.log10(),
+            UnaryOperator::Floor => value.floor(),
+            UnaryOperator::Ceil => value.ceil(),
+            UnaryOperator::Round => value.round(),
+            UnaryOperator::IsNan => boolean(value.is_nan()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinaryOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+    And,
+    Or,
+    Min,
+    Max,
+    Pow,
+}
+
+impl BinaryOperator {
+    #[allow(clippy::float_cmp)]
+    fn apply(self, left: f64, right: f64) -> f64 {
+        match self {
+            BinaryOperator::Add => left + right,
+            BinaryOperator::Subtract => left - right,
+            BinaryOperator::Multiply => left * right,
+            BinaryOperator::Divide => left / right,
+            BinaryOperator::Remainder => left % right,
+            BinaryOperator::Less => boolean(left < right),
+            BinaryOperator::LessOrEqual => boolean(left <= right),
+            BinaryOperator::Greater => boolean(left > right),
+            BinaryOperator::GreaterOrEqual => boolean(left >= right),
+            BinaryOperator::Equal => boolean(left == right),
+            BinaryOperator::NotEqual => boolean(left != right),
+            BinaryOperator::And => boolean(left != 0. && right != 0.),
+            BinaryOperator::Or => boolean(left != 0. || right != 0.),
+            BinaryOperator::Min => left.min(right),
+            BinaryOperator::Max => left.max(right),
+            BinaryOperator::Pow => left.powf(right),
+        }
+    }
+
+    /// The binding strength of infix operators, higher values bind stronger
+    fn precedence(self) -> u8 {
+        match self {
+            BinaryOperator::Or => 1,
+            BinaryOperator::And => 2,
+            BinaryOperator::Equal | BinaryOperator::NotEqual => 3,
+            BinaryOperator::Less
+            | BinaryOperator::LessOrEqual
+            | BinaryOperator::Greater
+            | BinaryOperator::GreaterOrEqual => 4,
+            BinaryOperator::Add | BinaryOperator::Subtract => 5,
+            BinaryOperator::Multiply | BinaryOperator::Divide | BinaryOperator::Remainder => 6,
+            BinaryOperator::Min | BinaryOperator::Max | BinaryOperator::Pow => {
+                unreachable!("functions are no infix operators")
+            }
+        }
+    }
+}
+
+fn boolean(value: bool) -> f64 {
+    if value {
+        1.
+    } else {
+        0.
+    }
+}
+
+#[allow(clippy::float_cmp)]
+fn select(condition: f64, value: f64, other_value: f64) -> f64 {
+    if condition == 0. {
+        other_value
+    } else {
+        value
+    }
+}
+
+fn raster_index(name: &str) -> Option<usize> {
+    RASTER_VARIABLES
+        .iter()
+        .position(|variable| *variable == name)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Identifier(String),
+    Symbol(&'static str),
+}
+
+/// Symbols that consist of two characters must precede their one-character prefixes
+const SYMBOLS: [&str; 17] = [
+    "<=", ">=", "==", "!=", "&&", "||", "+", "-", "*", "/", "%", "<", ">", "!", "(", ")", ",",
+];
+
+fn tokenize(expression: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = expression.trim_start();
+
+    while !rest.is_empty() {
+        let first = rest.chars().next().expect("rest is not empty");
+
+        let length = if first.is_ascii_digit() || first == '.' {
+            let mut length = rest
+                .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+                .unwrap_or_else(|| rest.len());
+
+            // exponent, e.g., `1e-3`
+            if rest[length..].starts_with(|c: char| c == 'e' || c == 'E') {
+                let exponent = &rest[length + 1..];
+                let sign = usize::from(exponent.starts_with(|c: char| c == '+' || c == '-'));
+                let digits = exponent[sign..]
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or_else(|| exponent.len() - sign);
+
+                if digits > 0 {
+                    length += 1 + sign + digits;
+                }
+            }
+
+            let number = rest[..length]
+                .parse()
+                .map_err(|_| syntax_error(format!("`{}` is no number", &rest[..length])))?;
+            tokens.push(Token::Number(number));
+
+            length
+        } else if first.is_ascii_alphabetic() || first == '_' {
+            let length = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or_else(|| rest.len());
+            tokens.push(Token::Identifier(rest[..length].to_string()));
+
+            length
+        } else if let Some(symbol) = SYMBOLS
+            .iter()
+            .copied()
+            .find(|symbol| rest.starts_with(*symbol))
+        {
+            tokens.push(Token::Symbol(symbol));
+
+            symbol.len()
+        } else {
+            return Err(syntax_error(format!("unexpected character `{}`", first)));
+        };
+
+        rest = rest[length..].trim_start();
+    }
+
+    Ok(tokens)
+}
+
+fn syntax_error(reason: String) -> Error {
+    Error::InvalidExpressionSyntax { reason }
+}
+
+/// The entries of the operator stack of the shunting-yard algorithm
+#[derive(Debug, Clone, Copy)]
+enum Pending {
+    Unary(UnaryOperator),
+    Binary(BinaryOperator),
+    Parenthesis,
+    Function {
+        function: Function,
+        arguments: usize,
+    },
+    Conditional(Branch),
+}
+
+/// The part of an `if condition then value else other_value` conditional that is currently parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Branch {
+    Condition,
+    Then,
+    Else,
+}
+
+impl Pending {
+    /// Whether the entry delimits the operators that belong to the current (sub-)expression
+    fn is_barrier(self) -> bool {
+        matches!(
+            self,
+            Pending::Parenthesis
+                | Pending::Function { .. }
+                | Pending::Conditional(Branch::Condition | Branch::Then)
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Function {
+    Unary(UnaryOperator),
+    Binary(BinaryOperator),
+    Neighbor(usize),
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "abs" => Function::Unary(UnaryOperator::Abs),
+            "sqrt" => Function::Unary(UnaryOperator::Sqrt),
+            "exp" => Function::Unary(UnaryOperator::Exp),
+            "ln" => Function::Unary(UnaryOperator::Ln),
+            "log10" => Function::Unary(UnaryOperator::Log10),
+            "floor" => Function::Unary(UnaryOperator::Floor),
+            "ceil" => Function::Unary(UnaryOperator::Ceil),
+            "round" => Function::Unary(UnaryOperator::Round),
+            "isnan" => Function::Unary(UnaryOperator::IsNan),
+            "min" => Function::Binary(BinaryOperator::Min),
+            "max" => Function::Binary(BinaryOperator::Max),
+            "pow" => Function::Binary(BinaryOperator::Pow),
+            _ => Function::Neighbor(name.strip_suffix("_AT").and_then(raster_index)?),
+        })
+    }
+
+    fn arity(self) -> usize {
+        match self {
+            Function::Unary(_) => 1,
+            Function::Binary(_) | Function::Neighbor(_) => 2,
+        }
+    }
+}
+
+impl ExpressionProgram {
+    /// Parses the `expression` with the shunting-yard algorithm and emits the instructions in postfix order
+    pub fn compile(expression: &str) -> Result<Self> {
+        let mut instructions = Vec::new();
+        let mut pending: Vec<Pending> = Vec::new();
+        let mut expects_operand = true;
+
+        let mut tokens = tokenize(expression)?.into_iter().peekable();
+
+        while let Some(token) = tokens.next() {
+            match token {
+                Token::Number(number) if expects_operand => {
+                    instructions.push(Instruction::Constant(number));
+                    expects_operand = false;
+                }
+                Token::Identifier(name) if expects_operand && name == "if" => {
+                    pending.push(Pending::Conditional(Branch::Condition));
+                }
+                Token::Identifier(name) if expects_operand => {
+                    if tokens.peek() == Some(&Token::Symbol("(")) {
+                        tokens.next();
+
+                        let function = Function::from_name(&name)
+                            .ok_or_else(|| syntax_error(format!("unknown function `{}`", name)))?;
+                        pending.push(Pending::Function {
+                            function,
+                            arguments: 0,
+                        });
+                    } else {
+                        let variable = match name.as_str() {
+                            "A_PREV" => Variable::PreviousA,
+                            "B_PREV" => Variable::PreviousB,
+                            "t" => Variable::T,
+                            "dt" => Variable::Dt,
+                            _ => Variable::Raster(raster_index(&name).ok_or_else(|| {
+                                syntax_error(format!("unknown variable `{}`", name))
+                            })?),
+                        };
+                        instructions.push(Instruction::Load(variable));
+                        expects_operand = false;
+                    }
+                }
+                Token::Symbol("(") if expects_operand => pending.push(Pending::Parenthesis),
+                Token::Symbol(")") if !expects_operand => {
+                    Self::close_parenthesis(&mut instructions, &mut pending)?;
+                }
+                Token::Symbol(",") if !expects_operand => {
+                    Self::emit_pending_until_barrier(&mut instructions, &mut pending)?;
+
+                    match pending.last_mut() {
+                        Some(Pending::Function { arguments, .. }) => *arguments += 1,
+                        _ => return Err(syntax_error("unexpected `,`".to_string())),
+                    }
+                    expects_operand = true;
+                }
+                Token::Symbol(symbol) if expects_operand => {
+                    let operator = match symbol {
+                        "-" => UnaryOperator::Negate,
+                        "!" => UnaryOperator::Not,
+                        "+" => continue,
+                        _ => return Err(syntax_error(format!("unexpected `{}`", symbol))),
+                    };
+                    pending.push(Pending::Unary(operator));
+                }
+                Token::Symbol(symbol) => {
+                    let operator = match symbol {
+                        "+" => BinaryOperator::Add,
+                        "-" => BinaryOperator::Subtract,
+                        "*" => BinaryOperator::Multiply,
+                        "/" => BinaryOperator::Divide,
+                        "%" => BinaryOperator::Remainder,
+                        "<" => BinaryOperator::Less,
+                        "<=" => BinaryOperator::LessOrEqual,
+                        ">" => BinaryOperator::Greater,
+                        ">=" => BinaryOperator::GreaterOrEqual,
+                        "==" => BinaryOperator::Equal,
+                        "!=" => BinaryOperator::NotEqual,
+                        "&&" => BinaryOperator::And,
+                        "||" => BinaryOperator::Or,
+                        _ => return Err(syntax_error(format!("unexpected `{}`", symbol))),
+                    };
+
+                    // all binary operators are left-associative and prefix operators bind stronger
+                    while let Some(&top) = pending.last() {
+                        match top {
+                            Pending::Unary(_) => {}
+                            Pending::Binary(other)
+                                if other.precedence() >= operator.precedence() => {}
+                            _ => break,
+                        }
+                        pending.pop();
+                        Self::emit(&mut instructions, top)?;
+                    }
+
+                    pending.push(Pending::Binary(operator));
+                    expects_operand = true;
+                }
+                Token::Identifier(keyword) if keyword == "then" || keyword == "else" => {
+                    Self::emit_pending_until_barrier(&mut instructions, &mut pending)?;
+
+                    let (expected, next) = if keyword == "then" {
+                        (Branch::Condition, Branch::Then)
+                    } else {
+                        (Branch::Then, Branch::Else)
+                    };
+
+                    match pending.last_mut() {
+                        Some(Pending::Conditional(branch)) if *branch == expected => *branch = next,
+                        _ => return Err(syntax_error(format!("unexpected `{}`", keyword))),
+                    }
+                    expects_operand = true;
+                }
+                Token::Number(_) | Token::Identifier(_) => {
+                    return Err(syntax_error(
+                        "missing operator between operands".to_string(),
+                    ));
+                }
+            }
+        }
+
+        if expects_operand {
+            return Err(syntax_error("incomplete expression".to_string()));
+        }
+
+        while let Some(top) = pending.pop() {
+            match top {
+                Pending::Parenthesis | Pending::Function { .. } => {
+                    return Err(syntax_error("unbalanced `(`".to_string()));
+                }
+                Pending::Conditional(Branch::Condition | Branch::Then) => {
+                    return Err(syntax_error("`if` without `else`".to_string()));
+                }
+                _ => Self::emit(&mut instructions, top)?,
+            }
+        }
+
+        let stack_size = Self::stack_size(&instructions);
+
+        Ok(Self {
+            instructions,
+            stack_size,
+        })
+    }
+
+    fn close_parenthesis(
+        instructions: &mut Vec<Instruction>,
+        pending: &mut Vec<Pending>,
+    ) -> Result<()> {
+        Self::emit_pending_until_barrier(instructions, pending)?;
+
+        match pending.pop() {
+            Some(Pending::Parenthesis) => Ok(()),
+            Some(Pending::Function {
+                function,
+                arguments,
+            }) => {
+                // the last argument is not followed by a comma
+                let arguments = arguments + 1;
+
+                if arguments != function.arity() {
+                    return Err(syntax_error(format!(
+                        "function expects {} arguments but got {}",
+                        function.arity(),
+                        arguments
+                    )));
+                }
+
+                Self::emit(
+                    instructions,
+                    Pending::Function {
+                        function,
+                        arguments,
+                    },
+                )
+            }
+            Some(Pending::Conditional(_)) => Err(syntax_error("`if` without `else`".to_string())),
+            _ => Err(syntax_error("unbalanced `)`".to_string())),
+        }
+    }
+
+    fn emit_pending_until_barrier(
+        instructions: &mut Vec<Instruction>,
+        pending: &mut Vec<Pending>,
+    ) -> Result<()> {
+        while let Some(&top) = pending.last() {
+            if top.is_barrier() {
+                return Ok(());
+            }
+            pending.pop();
+            Self::emit(instructions, top)?;
+        }
+
+        Err(syntax_error("unbalanced parentheses".to_string()))
+    }
+
+    /// Appends the instruction of an operator and folds it with constant operands
+    #[allow(clippy::float_cmp)]
+    fn emit(instructions: &mut Vec<Instruction>, operator: Pending) -> Result<()> {
+        let instruction = match operator {
+            Pending::Unary(operator)
+            | Pending::Function {
+                function: Function::Unary(operator),
+                ..
+            } => Instruction::Unary(operator),
+            Pending::Binary(operator)
+            | Pending::Function {
+                function: Function::Binary(operator),
+                ..
+            } => Instruction::Binary(operator),
+            Pending::Function {
+                function: Function::Neighbor(raster),
+                ..
+            } => {
+                let offset = |instruction: Option<Instruction>| match instruction {
+                    Some(Instruction::Constant(value)) if value.trunc() == value => Ok((value
+                        as isize)
+                        .max(-NEIGHBORHOOD_RADIUS)
+                        .min(NEIGHBORHOOD_RADIUS)),
+                    _ => Err(syntax_error(
+                        "neighbor offsets must be integer constants".to_string(),
+                    )),
+                };
+
+                let dy = offset(instructions.pop())?;
+                let dx = offset(instructions.pop())?;
+
+                Instruction::LoadNeighbor { raster, dx, dy }
+            }
+            Pending::Conditional(Branch::Else) => Instruction::Select,
+            Pending::Parenthesis => return Err(syntax_error("unbalanced `(`".to_string())),
+            Pending::Conditional(_) => return Err(syntax_error("`if` without `else`".to_string())),
+        };
+
+        // the operands of an operator end with its last instructions, so constant operands are directly in front of it
+        let operands = instruction.operands();
+        let constants: Vec<f64> = instructions
+            .iter()
+            .rev()
+            .take(operands)
+            .filter_map(|instruction| match instruction {
+                Instruction::Constant(value) => Some(*value),
+                _ => None,
+            })
+            .collect();
+
+        if operands > 0 && constants.len() == operands {
+            let value = match instruction {
+                Instruction::Unary(operator) => operator.apply(constants[0]),
+                Instruction::Binary(operator) => operator.apply(constants[1], constants[0]),
+                Instruction::Select => select(constants[2], constants[1], constants[0]),
+                _ => unreachable!("only operators have operands"),
+            };
+
+            instructions.truncate(instructions.len() - operands);
+            instructions.push(Instruction::Constant(value));
+        } else {
+            instructions.push(instruction);
+        }
+
+        Ok(())
+    }
+
+    fn stack_size(instructions: &[Instruction]) -> usize {
+        let mut size = 0;
+        let mut max_size = 0;
+
+        for instruction in instructions {
+            size = size - instruction.operands() + 1;
+            max_size = max_size.max(size);
+        }
+
+        max_size
+    }
+
+    /// Whether the program accesses `A_PREV`, `B_PREV` or `dt`
+    pub fn uses_previous_time_step(&self) -> bool {
+        self.instructions.iter().any(|instruction| {
+            matches!(
+                instruction,
+                Instruction::Load(Variable::PreviousA | Variable::PreviousB | Variable::Dt)
+            )
+        })
+    }
+
+    /// The distinct neighbor accesses of the program as `(raster, dx, dy)`
+    pub fn neighbors(&self) -> Vec<(usize, isize, isize)> {
+        let mut neighbors = Vec::new();
+
+        for instruction in &self.instructions {
+            if let Instruction::LoadNeighbor { raster, dx, dy } = *instruction {
+                if !neighbors.contains(&(raster, dx, dy)) {
+                    neighbors.push((raster, dx, dy));
+                }
+            }
+        }
+
+        neighbors
+    }
+
+    /// The number of input rasters the program needs, i.e., one more than the index of the last raster it accesses
+    pub fn number_of_rasters(&self) -> usize {
+        self.instructions
+            .iter()
+            .filter_map(|instruction| match *instruction {
+                Instruction::Load(Variable::Raster(raster))
+                | Instruction::LoadNeighbor { raster, .. } => Some(raster + 1),
+                Instruction::Load(Variable::PreviousA) => Some(1),
+                Instruction::Load(Variable::PreviousB) => Some(2),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// A stack for `evaluate` that does not need to grow
+    pub fn stack(&self) -> Vec<f64> {
+        Vec::with_capacity(self.stack_size)
+    }
+
+    /// Evaluates the program for the pixel at `x` and `y`
+    ///
+    /// # Panics
+    /// If `inputs` lack the values of a variable the program accesses
+    pub fn evaluate(&self, inputs: &PixelInputs, x: usize, y: usize, stack: &mut Vec<f64>) -> f64 {
+        let index = y * inputs.width + x;
+
+        stack.clear();
+
+        for instruction in &self.instructions {
+            let value = match *instruction {
+                Instruction::Constant(value) => value,
+                Instruction::Load(variable) => inputs.value(variable, index),
+                Instruction::LoadNeighbor { raster, dx, dy } => {
+                    inputs.neighbor(raster, x, y, dx, dy)
+                }
+                Instruction::Unary(operator) => {
+                    let value = stack.pop().expect("checked during compilation");
+                    operator.apply(value)
+                }
+                Instruction::Binary(operator) => {
+                    let right = stack.pop().expect("checked during compilation");
+                    let left = stack.pop().expect("checked during compilation");
+                    operator.apply(left, right)
+                }
+                Instruction::Select => {
+                    let other_value = stack.pop().expect("checked during compilation");
+                    let value = stack.pop().expect("checked during compilation");
+                    let condition = stack.pop().expect("checked during compilation");
+                    select(condition, value, other_value)
+                }
+            };
+
+            stack.push(value);
+        }
+
+        stack.pop().expect("checked during compilation")
+    }
+}
+
+/// A naive interpreter that recursively evaluates the syntax tree of an expression.
+/// It serves as a reference for the `ExpressionProgram` in tests and benchmarks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpressionTree(Node);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Leaf(Instruction),
+    Unary(UnaryOperator, Box<Node>),
+    Binary(BinaryOperator, Box<Node>, Box<Node>),
+    Select(Box<Node>, Box<Node>, Box<Node>),
+}
+
+impl From<&ExpressionProgram> for ExpressionTree {
+    fn from(program: &ExpressionProgram) -> Self {
+        let mut trees: Vec<Node> = Vec::with_capacity(program.stack_size);
+
+        for instruction in &program.instructions {
+            let tree = match *instruction {
+                Instruction::Unary(operator) => {
+                    let operand = trees.pop().expect("checked during compilation");
+                    Node::Unary(operator, Box::new(operand))
+                }
+                Instruction::Binary(operator) => {
+                    let right = trees.pop().expect("checked during compilation");
+                    let left = trees.pop().expect("checked during compilation");
+                    Node::Binary(operator, Box::new(left), Box::new(right))
+                }
+                Instruction::Select => {
+                    let other_value = trees.pop().expect("checked during compilation");
+                    let value = trees.pop().expect("checked during compilation");
+                    let condition = trees.pop().expect("checked during compilation");
+                    Node::Select(Box::new(condition), Box::new(value), Box::new(other_value))
+                }
+                leaf => Node::Leaf(leaf),
+            };
+
+            trees.push(tree);
+        }
+
+        ExpressionTree(trees.pop().expect("checked during compilation"))
+    }
+}
+
+impl ExpressionTree {
+    pub fn evaluate(&self, inputs: &PixelInputs, x: usize, y: usize) -> f64 {
+        self.0.evaluate(inputs, x, y)
+    }
+}
+
+impl Node {
+    fn evaluate(&self, inputs: &PixelInputs, x: usize, y: usize) -> f64 {
+        match self {
+            Node::Leaf(Instruction::Constant(value)) => *value,
+            Node::Leaf(Instruction::Load(variable)) => {
+                inputs.value(*variable, y * inputs.width + x)
+            }
+            Node::Leaf(Instruction::LoadNeighbor { raster, dx, dy }) => {
+                inputs.neighbor(*raster, x, y, *dx, *dy)
+            }
+            Node::Leaf(_) => unreachable!("operators are no leafs"),
+            Node::Unary(operator, operand) => operator.apply(operand.evaluate(inputs, x, y)),
+            Node::Binary(operator, left, right) => {
+                operator.apply(left.evaluate(inputs, x, y), right.evaluate(inputs, x, y))
+            }
+            Node::Select(condition, value, other_value) => select(
+                condition.evaluate(inputs, x, y),
+                value.evaluate(inputs, x, y),
+                other_value.evaluate(inputs, x, y),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs() -> PixelInputs<'static> {
+        PixelInputs {
+            rasters: &[
+                &[1., 2., 3., 4., 5., 6.],
+                &[6., 5., 4., 3., 2., 1.],
+                &[0., f64::NAN, 0., 1., 0., 1.],
+            ],
+            previous_a: &[0., 1., 2., 3., 4., 5.],
+            previous_b: &[],
+            width: 2,
+            height: 3,
+            t: 10.,
+            dt: 5.,
+        }
+    }
+
+    #[allow(clippy::float_cmp)]
+    fn evaluate(expression: &str, x: usize, y: usize) -> f64 {
+        let program = ExpressionProgram::compile(expression).unwrap();
+        let value = program.evaluate(&inputs(), x, y, &mut program.stack());
+
+        assert_eq!(
+            ExpressionTree::from(&program).evaluate(&inputs(), x, y),
+            value
+        );
+
+        value
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn it_evaluates() {
+        assert_eq!(evaluate("A + B * 2", 0, 0), 13.);
+        assert_eq!(evaluate("(A + B) * 2", 0, 0), 14.);
+        assert_eq!(evaluate("A - B - 1", 1, 2), 4.);
+        assert_eq!(evaluate("-A * -2", 1, 0), 4.);
+        assert_eq!(evaluate("2 * -(A + 1)", 1, 0), -6.);
+        assert_eq!(evaluate("A % 4 + 1e1 - 2.5E-1", 1, 2), 11.75);
+        assert_eq!(evaluate("A > B || !(A == 1)", 0, 0), 0.);
+        assert_eq!(evaluate("A < B && B <= 6 && A != B", 0, 0), 1.);
+        assert_eq!(evaluate("min(A, B) + max(A, pow(B, 2))", 0, 1), 19.);
+        assert_eq!(
+            evaluate("sqrt(abs(-A * 5)) + floor(ln(exp(1.5)))", 0, 2),
+            6.
+        );
+        assert_eq!(evaluate("(A - A_PREV) * dt + t", 1, 1), 15.);
+        assert_eq!(evaluate("A_AT(1, 0) + B_AT(0, -5)", 1, 2), 9.);
+        assert_eq!(evaluate("A_AT(-1, 1)", 0, 0), 3.);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn it_evaluates_conditionals() {
+        assert_eq!(evaluate("if C then A else B", 0, 0), 6.);
+        assert_eq!(evaluate("if C then A else B", 1, 1), 4.);
+        assert_eq!(evaluate("if isnan(C) then -1 else C", 1, 0), -1.);
+        assert_eq!(evaluate("1 + if A > 2 then A else B + 10", 0, 0), 17.);
+        assert_eq!(
+            evaluate("if A < 3 then if B > 5 then 1 else 2 else 3", 0, 0),
+            1.
+        );
+        assert_eq!(
+            evaluate("if A < 3 then if B > 5 then 1 else 2 else 3", 1, 0),
+            2.
+        );
+        assert_eq!(
+            evaluate("max(if A > 3 then A else 0, C_AT(0, 1))", 0, 1),
+            0.
+        );
+        assert_eq!(evaluate("-if 1 then 2 else 3", 0, 0), -2.);
+    }
+
+    #[test]
+    fn it_folds_constants() {
+        let program = ExpressionProgram::compile("A * (2 + 3 * 4) - -1").unwrap();
+
+        assert_eq!(
+            program.instructions,
+            vec![
+                Instruction::Load(Variable::A),
+                Instruction::Constant(14.),
+                Instruction::Binary(BinaryOperator::Multiply),
+                Instruction::Constant(-1.),
+                Instruction::Binary(BinaryOperator::Subtract),
+            ]
+        );
+        assert_eq!(program.stack_size, 2);
+        assert!(!program.uses_previous_time_step());
+
+        assert!(ExpressionProgram::compile("A_PREV + 1")
+            .unwrap()
+            .uses_previous_time_step());
+
+        assert_eq!(
+            ExpressionProgram::compile("if 1 > 2 then 4 else 5")
+                .unwrap()
+                .instructions,
+            vec![Instruction::Constant(5.)]
+        );
+    }
+
+    #[test]
+    fn it_counts_rasters() {
+        for (expression, rasters) in &[
+            ("1 + 2", 0),
+            ("A", 1),
+            ("B_PREV", 2),
+            ("C + A", 3),
+            ("H_AT(1, 1) - A", 8),
+        ] {
+            assert_eq!(
+                ExpressionProgram::compile(expression)
+                    .unwrap()
+                    .number_of_rasters(),
+                *rasters
+            );
+        }
+    }
+
+    #[test]
+    fn it_lists_neighbors() {
+        assert_eq!(
+            ExpressionProgram::compile("A_AT(1, 0) + B_AT(-5, 0) * A_AT(1, 0) + A")
+                .unwrap()
+                .neighbors(),
+            vec![(0, 1, 0), (1, -1, 0)]
+        );
+        assert!(ExpressionProgram::compile("A + B_PREV")
+            .unwrap()
+            .neighbors()
+            .is_empty());
+    }
+
+    #[test]
+    fn it_compiles_deeply_nested_expressions() {
+        let depth = 100_000;
+        let expression = format!("{}A{}", "(".repeat(depth), " + 1)".repeat(depth));
+
+        let program = ExpressionProgram::compile(&expression).unwrap();
+
+        assert_eq!(program.stack_size, 2);
+        assert!((program.evaluate(&inputs(), 0, 0, &mut program.stack()) - 100_001.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn it_rejects_invalid_expressions() {
+        for expression in &[
+            "",
+            "A +",
+            "A B",
+            "(A + 1",
+            "A + 1)",
+            "A; return 1",
+            "I + 1",
+            "I_AT(0, 0)",
+            "if A then B",
+            "if A else B",
+            "A then B else C",
+            "if A then B else C else D",
+            "(if A then B) else C",
+            "min(if A, B)",
+            "foo(A)",
+            "min(A)",
+            "abs(A, B)",
+            "A_AT(A, 1)",
+            "A_AT(0.5, 1)",
+            "A ,B",
+            "1..2",
+        ] {
+            assert!(
+                ExpressionProgram::compile(expression).is_err(),
+                "{} must not compile",
+                expression
+            );
+        }
+    }
+}
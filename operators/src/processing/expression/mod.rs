@@ -0,0 +1,2039 @@
+use crate::adapters::{
+    RasterArrayZip, RasterStreamExt, SubQueryTileAggregator, TileSubQueryEnlarged,
+};
+use crate::engine::{
+    InitializedRasterOperator, Operator, OperatorDatasets, QueryContext, QueryProcessor,
+    RasterOperator, RasterQueryProcessor, RasterQueryRectangle, RasterResultDescriptor,
+    TypedRasterQueryProcessor,
+};
+use crate::error::Error;
+use crate::util::Result;
+use crate::{
+    call_bi_generic_processor, call_generic_raster_processor, call_on_generic_raster_processor,
+};
+use crate::{
+    engine::ExecutionContext,
+    opencl::{ClProgram, CompiledClProgram, IterationType, RasterArgument},
+};
+use async_trait::async_trait;
+use futures::future::try_join_all;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::primitives::{Measurement, SpatialPartition2D, TimeInterval};
+use geoengine_datatypes::raster::{
+    EmptyGrid, FromPrimitive, Grid2D, GridShape2D, GridShapeAccess, GridSize, NoDataValue, Pixel,
+    RasterDataType, RasterTile2D, TilingSpecification, TypedGrid2D,
+};
+use num_traits::AsPrimitive;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, Schema, SchemaObject, SubschemaValidation};
+use schemars::JsonSchema;
+use serde::Serializer;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use snafu::ensure;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+pub use bytecode::{
+    ExpressionProgram, ExpressionTree, PixelInputs, NEIGHBORHOOD_RADIUS, RASTER_VARIABLES,
+};
+
+mod bytecode;
+
+/// Parameters for the `Expression` operator.
+/// * The `expression` must only contain simple arithmetic
+///     calculations over the pixel values `A` to `H` of the up to eight input rasters.
+///     The bytecode backend additionally supports the functions `min`, `max`, `abs`, `sqrt`, `ln` etc.
+///     and conditionals of the form `if A > B then A else B` (cf. [`ExpressionProgram`]).
+///     Furthermore, it can refer to
+///     - `t`, the start of the current time step in milliseconds,
+///     - `A_PREV` and `B_PREV`, the pixel values of the previous time step,
+///       and `dt`, the milliseconds between the starts of the previous and the current time step,
+///       if there are exactly two input rasters,
+///     - `A_AT(dx, dy)` and `B_AT(dx, dy)`, the pixel values in the 3x3 neighborhood of the current pixel.
+///       Offsets outside the neighborhood are clamped. The neighbors across tile borders are queried from the adjacent tiles
+///       and no data neighbors make the output no data, unless no data is mapped or replaced by a default.
+/// * `output_type` is the data type of the produced raster tiles.
+/// * `output_no_data_value` is the no data value of the output raster
+/// * `output_measurement` is the measurement description of the output
+/// * `backend` evaluates the expression, by default the OpenCL backend
+/// * `map_no_data` evaluates the expression for pixels with no data inputs, too, instead of outputting no data.
+///     The no data inputs are `NaN`, which the expression can check with `isnan`, and `NaN` results become no data.
+///     Only the bytecode backend supports it, the default OpenCL backend rejects it.
+/// * `no_data_defaults` replaces no data of the inputs `A` to `H` with a value before the expression is evaluated,
+///     e.g., `{"A": 0, "B": 0}` to sum sparse rasters. Inputs without a default make the output no data.
+///     Only the bytecode backend supports it, the default OpenCL backend rejects it.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpressionParams {
+    pub expression: String,
+    pub output_type: RasterDataType,
+    #[serde(deserialize_with = "parse_no_data")]
+    #[serde(serialize_with = "write_no_data")]
+    #[schemars(schema_with = "no_data_schema")]
+    pub output_no_data_value: f64, // TODO: check value is valid for given output type during deserialization
+    pub output_measurement: Option<Measurement>,
+    #[serde(default, skip_serializing_if = "ExpressionBackend::is_default")]
+    pub backend: ExpressionBackend,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub map_no_data: bool,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub no_data_defaults: BTreeMap<String, f64>,
+}
+
+/// How the `Expression` operator evaluates its expression
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ExpressionBackend {
+    /// Interprets the expression as an [`ExpressionProgram`] on the CPU
+    Bytecode,
+    /// Compiles the expression into an OpenCL kernel, which allows arbitrary OpenCL C expressions
+    OpenCl,
+}
+
+impl ExpressionBackend {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Default for ExpressionBackend {
+    fn default() -> Self {
+        Self::OpenCl
+    }
+}
+
+/// Parse no data from either number or "nan"
+pub fn parse_no_data<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Number(n) => n
+            .as_f64()
+            .ok_or_else(|| serde::de::Error::custom("Invalid no data value")),
+        Value::String(s) if s.to_lowercase() == "nan" => Ok(f64::NAN),
+        _ => Err(serde::de::Error::custom("Invalid no data value")),
+    }
+}
+
+/// write no data as either number or "nan"
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn write_no_data<S>(x: &f64, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if x.is_nan() {
+        s.serialize_str("nan")
+    } else {
+        s.serialize_f64(*x)
+    }
+}
+
+/// no data is either a number or "nan"
+fn no_data_schema(gen: &mut SchemaGenerator) -> Schema {
+    let nan = SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        enum_values: Some(vec!["nan".into()]),
+        ..SchemaObject::default()
+    };
+
+    SchemaObject {
+        subschemas: Some(Box::new(SubschemaValidation {
+            any_of: Some(vec![gen.subschema_for::<f64>(), nan.into()]),
+            ..SubschemaValidation::default()
+        })),
+        ..SchemaObject::default()
+    }
+    .into()
+}
+
+// TODO: custom type or simple string?
+#[derive(Debug, Clone)]
+struct SafeExpression {
+    expression: String,
+}
+
+impl SafeExpression {
+    // TODO: also check this when creating original operator parameters
+    fn is_allowed_expression(expression: &str) -> bool {
+        // TODO: perform actual syntax checking
+        let disallowed_chars: HashSet<_> = ";[]{}#%\"\'\\".chars().collect();
+        let disallowed_strs: HashSet<String> =
+            vec!["/*".into(), "//".into(), "*/".into(), "return".into()]
+                .into_iter()
+                .collect();
+
+        expression.chars().all(|c| !disallowed_chars.contains(&c))
+            && disallowed_strs.iter().all(|s| !expression.contains(s))
+    }
+
+    fn references(&self, identifier: &str) -> bool {
+        self.expression
+            .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .any(|token| token == identifier)
+    }
+
+    /// Pixels without a previous time step are no data if the expression refers to it
+    fn uses_previous_time_step(&self) -> bool {
+        ["A_PREV", "B_PREV", "dt"]
+            .iter()
+            .any(|identifier| self.references(identifier))
+    }
+
+    /// Whether the expression accesses neighbors with `A_AT` or `B_AT`
+    fn uses_neighborhood(&self) -> bool {
+        ["A_AT", "B_AT"]
+            .iter()
+            .any(|identifier| self.references(identifier))
+    }
+}
+
+impl TryFrom<String> for SafeExpression {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if Self::is_allowed_expression(&value) {
+            Ok(Self { expression: value })
+        } else {
+            Err(Error::InvalidExpression)
+        }
+    }
+}
+
+/// The `Expression` operator calculates an expression for all pixels of the input rasters and
+/// produces raster tiles of a given output type
+pub type Expression = Operator<ExpressionParams, ExpressionSources>;
+
+/// The input rasters of the `Expression` operator, which the expression refers to as `A` to `H`.
+/// They must be assigned without gaps, e.g., `c` requires `b`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpressionSources {
+    pub a: Box<dyn RasterOperator>,
+    pub b: Option<Box<dyn RasterOperator>>,
+    pub c: Option<Box<dyn RasterOperator>>,
+    pub d: Option<Box<dyn RasterOperator>>,
+    pub e: Option<Box<dyn RasterOperator>>,
+    pub f: Option<Box<dyn RasterOperator>>,
+    pub g: Option<Box<dyn RasterOperator>>,
+    pub h: Option<Box<dyn RasterOperator>>,
+}
+
+impl OperatorDatasets for ExpressionSources {
+    fn datasets_collect(&self, datasets: &mut Vec<DatasetId>) {
+        self.a.datasets_collect(datasets);
+
+        for source in self.optional_sources().iter().copied().flatten() {
+            source.datasets_collect(datasets);
+        }
+    }
+}
+
+impl ExpressionSources {
+    pub fn new_a(a: Box<dyn RasterOperator>) -> Self {
+        Self {
+            a,
+            b: None,
+            c: None,
+            d: None,
+            e: None,
+            f: None,
+            g: None,
+            h: None,
+        }
+    }
+
+    pub fn new_a_b(a: Box<dyn RasterOperator>, b: Box<dyn RasterOperator>) -> Self {
+        Self {
+            b: Some(b),
+            ..Self::new_a(a)
+        }
+    }
+
+    pub fn new_a_b_c(
+        a: Box<dyn RasterOperator>,
+        b: Box<dyn RasterOperator>,
+        c: Box<dyn RasterOperator>,
+    ) -> Self {
+        Self {
+            c: Some(c),
+            ..Self::new_a_b(a, b)
+        }
+    }
+
+    fn optional_sources(&self) -> [Option<&dyn RasterOperator>; 7] {
+        [
+            self.b.as_deref(),
+            self.c.as_deref(),
+            self.d.as_deref(),
+            self.e.as_deref(),
+            self.f.as_deref(),
+            self.g.as_deref(),
+            self.h.as_deref(),
+        ]
+    }
+
+    fn number_of_sources(&self) -> usize {
+        1 + self
+            .optional_sources()
+            .iter()
+            .filter(|source| source.is_some())
+            .count()
+    }
+
+    /// Whether no source is missing in front of another one
+    fn is_contiguous(&self) -> bool {
+        self.optional_sources()
+            .windows(2)
+            .all(|pair| pair[0].is_some() || pair[1].is_none())
+    }
+
+    async fn initialize(
+        self,
+        context: &dyn ExecutionContext,
+    ) -> Result<ExpressionInitializedSources> {
+        let sources = std::iter::once(Some(self.a))
+            .chain(vec![self.b, self.c, self.d, self.e, self.f, self.g, self.h])
+            .flatten()
+            .map(|source| source.initialize(context));
+
+        Ok(ExpressionInitializedSources {
+            rasters: try_join_all(sources).await?,
+        })
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for Expression {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn crate::engine::ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let number_of_sources = self.sources.number_of_sources();
+
+        ensure!(
+            self.sources.is_contiguous(),
+            crate::error::NonContiguousExpressionSources
+        );
+
+        let (expression, uses_neighborhood) = match self.params.backend {
+            ExpressionBackend::Bytecode => {
+                let program = ExpressionProgram::compile(&self.params.expression)?;
+
+                ensure!(
+                    program.number_of_rasters() <= number_of_sources,
+                    crate::error::InvalidNumberOfRasterInputs {
+                        expected: program.number_of_rasters()..RASTER_VARIABLES.len() + 1,
+                        found: number_of_sources
+                    }
+                );
+
+                // only the processor for exactly two inputs keeps track of the previous time step
+                ensure!(
+                    number_of_sources == 2 || !program.uses_previous_time_step(),
+                    crate::error::InvalidNumberOfRasterInputs {
+                        expected: 2..3,
+                        found: number_of_sources
+                    }
+                );
+
+                let uses_neighborhood = !program.neighbors().is_empty();
+
+                (
+                    CompiledExpression::Bytecode(Arc::new(program)),
+                    uses_neighborhood,
+                )
+            }
+            ExpressionBackend::OpenCl => {
+                // the options are checked first, s.t. using them with the default backend
+                // reports them instead of the number of inputs
+                ensure!(
+                    !self.params.map_no_data,
+                    crate::error::UnsupportedOpenClExpressionFeature {
+                        feature: "mapping no data, use the bytecode backend instead"
+                    }
+                );
+
+                ensure!(
+                    self.params.no_data_defaults.is_empty(),
+                    crate::error::UnsupportedOpenClExpressionFeature {
+                        feature: "no data defaults, use the bytecode backend instead"
+                    }
+                );
+
+                // TODO: generate kernels for other numbers of inputs
+                ensure!(
+                    number_of_sources == 2,
+                    crate::error::InvalidNumberOfRasterInputs {
+                        expected: 2..3,
+                        found: number_of_sources
+                    }
+                );
+
+                let expression = SafeExpression::try_from(self.params.expression)?;
+                let uses_neighborhood = expression.uses_neighborhood();
+
+                (CompiledExpression::OpenCl(expression), uses_neighborhood)
+            }
+        };
+
+        ensure!(
+            self.params
+                .output_type
+                .is_valid(self.params.output_no_data_value),
+            crate::error::InvalidNoDataValueValueForOutputDataType
+        );
+
+        let no_data_defaults =
+            no_data_defaults_per_source(&self.params.no_data_defaults, number_of_sources)?;
+
+        let sources = self.sources.initialize(context).await?;
+
+        for source in &sources.rasters {
+            ensure!(
+                source.result_descriptor().number_of_bands() == 1,
+                crate::error::InvalidOperatorSpec {
+                    reason: "multiband rasters cannot be combined by an expression".to_string()
+                }
+            );
+        }
+
+        let spatial_reference = sources.rasters[0].result_descriptor().spatial_reference;
+
+        for other_spatial_refenence in sources
+            .rasters
+            .iter()
+            .skip(1)
+            .map(|source| source.result_descriptor().spatial_reference)
+        {
+            ensure!(
+                spatial_reference == other_spatial_refenence,
+                crate::error::InvalidSpatialReference {
+                    expected: spatial_reference,
+                    found: other_spatial_refenence,
+                }
+            );
+        }
+
+        let result_descriptor = RasterResultDescriptor {
+            data_type: self.params.output_type,
+            spatial_reference,
+            measurement: self
+                .params
+                .output_measurement
+                .as_ref()
+                .map_or(Measurement::Unitless, Measurement::clone),
+            no_data_value: Some(self.params.output_no_data_value), // TODO: is it possible to have none?, bands: Vec::new(),
+        };
+
+        let initialized_operator = InitializedExpression {
+            result_descriptor,
+            sources,
+            expression,
+            map_no_data: self.params.map_no_data,
+            no_data_defaults,
+            margin: if uses_neighborhood {
+                NEIGHBORHOOD_RADIUS as usize
+            } else {
+                0
+            },
+            tiling_specification: context.tiling_specification(),
+        };
+
+        Ok(initialized_operator.boxed())
+    }
+}
+
+/// Orders the defaults by the index of their source
+fn no_data_defaults_per_source(
+    defaults: &BTreeMap<String, f64>,
+    number_of_sources: usize,
+) -> Result<Vec<Option<f64>>> {
+    let mut defaults_per_source = vec![None; number_of_sources];
+
+    for (variable, &default) in defaults {
+        let index = RASTER_VARIABLES[..number_of_sources]
+            .iter()
+            .position(|raster_variable| raster_variable == variable)
+            .ok_or_else(|| Error::InvalidOperatorSpec {
+                reason: format!("there is no input `{}` for a no data default", variable),
+            })?;
+
+        ensure!(
+            !default.is_nan(),
+            crate::error::InvalidOperatorSpec {
+                reason: format!(
+                    "the no data default of input `{}` must be a number",
+                    variable
+                )
+            }
+        );
+
+        defaults_per_source[index] = Some(default);
+    }
+
+    Ok(defaults_per_source)
+}
+
+pub struct InitializedExpression {
+    result_descriptor: RasterResultDescriptor,
+    sources: ExpressionInitializedSources,
+    expression: CompiledExpression,
+    map_no_data: bool,
+    no_data_defaults: Vec<Option<f64>>,
+    /// the number of pixels by which the input tiles are enlarged for accessing neighbors
+    margin: usize,
+    tiling_specification: TilingSpecification,
+}
+
+#[derive(Debug, Clone)]
+enum CompiledExpression {
+    OpenCl(SafeExpression),
+    Bytecode(Arc<ExpressionProgram>),
+}
+
+pub struct ExpressionInitializedSources {
+    /// the sources `a` to `h` without the missing ones
+    rasters: Vec<Box<dyn InitializedRasterOperator>>,
+}
+
+impl InitializedRasterOperator for InitializedExpression {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let expression = self.expression.clone();
+        let output_type = self.result_descriptor().data_type;
+        // TODO: allow processing expression without NO DATA
+        let output_no_data_value = self.result_descriptor().no_data_value.unwrap_or_default();
+
+        let source_query = SourceQuery {
+            margin: self.margin,
+            tiling_specification: self.tiling_specification,
+            // TODO: add option to force a no_data_value
+            no_data_values: self
+                .sources
+                .rasters
+                .iter()
+                .map(|raster| raster.result_descriptor().no_data_value.unwrap_or(0.))
+                .collect(),
+        };
+
+        match (self.sources.rasters.as_slice(), expression) {
+            ([a, b], expression) => {
+                let a = a.query_processor()?;
+                let b = b.query_processor()?;
+
+                call_bi_generic_processor!(a, b, (p_a, p_b) => {
+                    let res = call_generic_raster_processor!(
+                        output_type,
+                        ExpressionQueryProcessor::new(
+                            &expression,
+                            p_a,
+                            p_b,
+                            output_no_data_value.as_(),
+                            self.map_no_data,
+                            [self.no_data_defaults[0], self.no_data_defaults[1]],
+                            source_query.clone(),
+                        ).boxed()
+                    );
+                    Ok(res)
+                })
+            }
+            (rasters, CompiledExpression::Bytecode(program)) => {
+                let sources = rasters
+                    .iter()
+                    .map(|raster| raster.query_processor())
+                    .collect::<Result<Vec<_>>>()?;
+
+                Ok(call_generic_raster_processor!(
+                    output_type,
+                    MultiExpressionQueryProcessor {
+                        sources,
+                        program,
+                        no_data_value: output_no_data_value.as_(),
+                        map_no_data: self.map_no_data,
+                        no_data_defaults: self.no_data_defaults.clone(),
+                        source_query,
+                    }
+                    .boxed()
+                ))
+            }
+            (_, CompiledExpression::OpenCl(_)) => {
+                Err(crate::error::Error::InvalidNumberOfExpressionInputs)
+            }
+        }
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// Queries the sources of an expression.
+/// If the expression accesses neighbors, the tiles are enlarged by a margin,
+/// s.t. the neighbors across tile borders are available, and the results are cropped to the tiles.
+#[derive(Debug, Clone)]
+struct SourceQuery {
+    /// the number of pixels by which the tiles are enlarged on every side
+    margin: usize,
+    tiling_specification: TilingSpecification,
+    /// the no data values of the sources, which fill the margin where there are no tiles
+    no_data_values: Vec<f64>,
+}
+
+impl SourceQuery {
+    async fn query<'a, S, T>(
+        &self,
+        source_index: usize,
+        source: &'a S,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<RasterTile2D<T>>>>
+    where
+        S: QueryProcessor<Output = RasterTile2D<T>, SpatialBounds = SpatialPartition2D>
+            + Sync
+            + Send,
+        T: Pixel,
+    {
+        if self.margin == 0 {
+            return source.query(query, ctx).await;
+        }
+
+        Ok(TileSubQueryEnlarged {
+            margin: self.margin,
+            no_data_value: T::from_(self.no_data_values[source_index]),
+        }
+        .into_raster_overlap_adapter(source, query, ctx, self.tiling_specification)
+        .boxed())
+    }
+
+    /// The shape of the output tile for input tiles of the `enlarged_shape`
+    fn tile_shape(&self, enlarged_shape: GridShape2D) -> GridShape2D {
+        let [size_y, size_x] = enlarged_shape.into_inner();
+        [size_y - 2 * self.margin, size_x - 2 * self.margin].into()
+    }
+}
+
+struct ExpressionQueryProcessor<T1, T2, TO>
+where
+    T1: Pixel,
+    T2: Pixel,
+    TO: Pixel,
+{
+    pub source_a: Box<dyn RasterQueryProcessor<RasterType = T1>>,
+    pub source_b: Box<dyn RasterQueryProcessor<RasterType = T2>>,
+    pub phantom_data: PhantomData<TO>,
+    pub kernel: ExpressionKernel,
+    pub no_data_value: TO,
+    pub uses_previous_time_step: bool,
+    pub map_no_data: bool,
+    /// the no data defaults of `a` and `b`
+    pub no_data_defaults: [Option<f64>; 2],
+    pub source_query: SourceQuery,
+}
+
+#[derive(Clone)]
+enum ExpressionKernel {
+    OpenCl(CompiledClProgram),
+    Bytecode(Arc<ExpressionProgram>),
+}
+
+/// The input tiles of the time step that precedes the currently processed one at a tile position
+struct PreviousTimeStep<T1, T2> {
+    time: TimeInterval,
+    a: Grid2D<T1>,
+    b: Grid2D<T2>,
+}
+
+impl<T1, T2, TO> ExpressionQueryProcessor<T1, T2, TO>
+where
+    T1: Pixel,
+    T2: Pixel,
+    TO: Pixel,
+{
+    fn new(
+        expression: &CompiledExpression,
+        source_a: Box<dyn RasterQueryProcessor<RasterType = T1>>,
+        source_b: Box<dyn RasterQueryProcessor<RasterType = T2>>,
+        no_data_value: TO,
+        map_no_data: bool,
+        no_data_defaults: [Option<f64>; 2],
+        source_query: SourceQuery,
+    ) -> Self {
+        let (kernel, uses_previous_time_step) = match expression {
+            CompiledExpression::OpenCl(expression) => (
+                ExpressionKernel::OpenCl(Self::create_cl_program(expression, source_query.margin)),
+                expression.uses_previous_time_step(),
+            ),
+            CompiledExpression::Bytecode(program) => (
+                ExpressionKernel::Bytecode(program.clone()),
+                program.uses_previous_time_step(),
+            ),
+        };
+
+        Self {
+            source_a,
+            source_b,
+            kernel,
+            phantom_data: PhantomData::default(),
+            no_data_value,
+            uses_previous_time_step,
+            map_no_data,
+            no_data_defaults,
+            source_query,
+        }
+    }
+
+    /// The kernel reads the input tiles enlarged by the `margin`
+    /// and writes the output tile without it
+    fn create_cl_program(expression: &SafeExpression, margin: usize) -> CompiledClProgram {
+        let uses_previous_time_step = expression.uses_previous_time_step();
+
+        // the tiles of the previous time step are passed as additional input rasters
+        let (previous_inputs, previous_values) = if uses_previous_time_step {
+            (
+                r#"
+            __global const IN_TYPE2 *in_data2,
+            __global const RasterInfo *in_info2,
+            __global const IN_TYPE3 *in_data3,
+            __global const RasterInfo *in_info3,"#,
+                r#"
+    if (!time[2]) {
+        out_data[out_gid] = out_info->no_data;
+        return;
+    }
+
+    IN_TYPE2 A_PREV = in_data2[gid];
+    if (ISNODATA2(A_PREV, in_info2)) {
+        out_data[out_gid] = out_info->no_data;
+        return;
+    }
+
+    IN_TYPE3 B_PREV = in_data3[gid];
+    if (ISNODATA3(B_PREV, in_info3)) {
+        out_data[out_gid] = out_info->no_data;
+        return;
+    }
+"#,
+            )
+        } else {
+            ("", "")
+        };
+
+        // TODO: generate code for arbitrary amount of inputs
+        // a no data neighbor is remembered in `no_data_neighbor` and makes the output no data
+        let source = r#"
+#define MARGIN %%%MARGIN%%%
+#define NEIGHBORHOOD_RADIUS %%%NEIGHBORHOOD_RADIUS%%%
+#define NEIGHBOR_INDEX(info, dx, dy) \
+    (pixel_y + MARGIN + clamp((int) (dy), -NEIGHBORHOOD_RADIUS, NEIGHBORHOOD_RADIUS)) * info->size[0] \
+    + pixel_x + MARGIN + clamp((int) (dx), -NEIGHBORHOOD_RADIUS, NEIGHBORHOOD_RADIUS)
+#define NEIGHBOR(data, info, is_no_data, dx, dy) ( \
+    no_data_neighbor |= is_no_data(data[NEIGHBOR_INDEX(info, dx, dy)], info), \
+    data[NEIGHBOR_INDEX(info, dx, dy)])
+#define A_AT(dx, dy) NEIGHBOR(in_data0, in_info0, ISNODATA0, dx, dy)
+#define B_AT(dx, dy) NEIGHBOR(in_data1, in_info1, ISNODATA1, dx, dy)
+
+__kernel void expressionkernel(
+            __global const IN_TYPE0 *in_data0,
+            __global const RasterInfo *in_info0,
+            __global const IN_TYPE1 *in_data1,
+            __global const RasterInfo *in_info1,%%%PREVIOUS_INPUTS%%%
+            __global OUT_TYPE0* out_data,
+            __global const RasterInfo *out_info,
+            __constant const long *time,
+            const int time_len)
+{
+    int const pixel_x = get_global_id(0);
+    int const pixel_y = get_global_id(1);
+    uint const out_gid = pixel_x + pixel_y * out_info->size[0];
+    if (out_gid >= out_info->size[0]*out_info->size[1]*out_info->size[2])
+        return;
+    uint const gid = (pixel_x + MARGIN) + (pixel_y + MARGIN) * in_info0->size[0];
+
+    long const t = time[0];
+    long const dt = time[1];
+
+    IN_TYPE0 A = in_data0[gid];
+    if (ISNODATA0(A, in_info0)) {
+        out_data[out_gid] = out_info->no_data;
+        return;
+    }
+
+    IN_TYPE1 B = in_data1[gid];
+    if (ISNODATA1(B, in_info1)) {
+        out_data[out_gid] = out_info->no_data;
+        return;
+    }
+%%%PREVIOUS_VALUES%%%
+    int no_data_neighbor = 0;
+    OUT_TYPE0 result = %%%EXPRESSION%%%;
+    if (no_data_neighbor) {
+        out_data[out_gid] = out_info->no_data;
+        return;
+    }
+	out_data[out_gid] = result;
+}"#
+        .replace("%%%MARGIN%%%", &margin.to_string())
+        .replace("%%%NEIGHBORHOOD_RADIUS%%%", &NEIGHBORHOOD_RADIUS.to_string())
+        .replace("%%%PREVIOUS_INPUTS%%%", previous_inputs)
+        .replace("%%%PREVIOUS_VALUES%%%", previous_values)
+        .replace("%%%EXPRESSION%%%", &expression.expression);
+
+        let mut cl_program = ClProgram::new(IterationType::Raster);
+        cl_program.add_input_raster(RasterArgument::new(T1::TYPE));
+        cl_program.add_input_raster(RasterArgument::new(T2::TYPE));
+        if uses_previous_time_step {
+            cl_program.add_input_raster(RasterArgument::new(T1::TYPE));
+            cl_program.add_input_raster(RasterArgument::new(T2::TYPE));
+        }
+        cl_program.add_output_raster(RasterArgument::new(TO::TYPE));
+        cl_program.add_generic_input::<i64>();
+
+        cl_program.compile(&source, "expressionkernel").unwrap()
+    }
+
+    /// Loads the non-empty tiles that are valid right before the start of the `query`
+    async fn query_previous_time_step<'b>(
+        &'b self,
+        query: RasterQueryRectangle,
+        ctx: &'b dyn QueryContext,
+    ) -> Result<HashMap<[isize; 2], PreviousTimeStep<T1, T2>>> {
+        let time_interval = match TimeInterval::new_instant(query.time_interval.start().inner() - 1)
+        {
+            Ok(time_interval) => time_interval,
+            Err(_) => return Ok(HashMap::new()), // there is no time before the query
+        };
+
+        let query = RasterQueryRectangle {
+            time_interval,
+            ..query
+        };
+
+        self.source_query
+            .query(0, &self.source_a, query, ctx)
+            .await?
+            .zip_aligned(
+                self.source_query
+                    .query(1, &self.source_b, query, ctx)
+                    .await?,
+            )
+            .try_filter_map(|(a, b)| async move {
+                if a.grid_array.is_empty() || b.grid_array.is_empty() {
+                    return Ok(None);
+                }
+
+                let a = a.into_materialized_tile();
+                let b = b.into_materialized_tile();
+
+                Ok(Some((
+                    a.tile_position.0,
+                    PreviousTimeStep {
+                        time: a.time,
+                        a: a.grid_array,
+                        b: b.grid_array,
+                    },
+                )))
+            })
+            .try_collect()
+            .await
+    }
+
+    fn run_cl_program(
+        &self,
+        cl_program: &mut CompiledClProgram,
+        a: &Grid2D<T1>,
+        b: &Grid2D<T2>,
+        previous: Option<&PreviousTimeStep<T1, T2>>,
+        time: [i64; 3],
+    ) -> Grid2D<TO> {
+        let tile_shape = self.source_query.tile_shape(a.grid_shape());
+
+        let mut out = Grid2D::new(
+            tile_shape,
+            vec![TO::zero(); tile_shape.number_of_elements()], // TODO: initialization required?
+            Some(self.no_data_value),                          // TODO
+        )
+        .expect("raster creation must succeed")
+        .into();
+
+        let a_typed: TypedGrid2D = a.clone().into();
+        let b_typed: TypedGrid2D = b.clone().into();
+
+        // the kernel does not read the current tiles in place of missing previous ones
+        let (previous_a, previous_b): (TypedGrid2D, TypedGrid2D) = match previous {
+            Some(previous) if self.uses_previous_time_step => {
+                (previous.a.clone().into(), previous.b.clone().into())
+            }
+            _ => (a_typed.clone(), b_typed.clone()),
+        };
+
+        let mut params = cl_program.runnable();
+
+        params.set_input_raster(0, &a_typed).unwrap();
+        params.set_input_raster(1, &b_typed).unwrap();
+        if self.uses_previous_time_step {
+            params.set_input_raster(2, &previous_a).unwrap();
+            params.set_input_raster(3, &previous_b).unwrap();
+        }
+        params.set_output_raster(0, &mut out).unwrap();
+        params.set_generic_input(0, &time).unwrap();
+        cl_program.run(params).unwrap();
+
+        Grid2D::<TO>::try_from(out).expect("must be correct")
+    }
+
+    fn run_bytecode(
+        &self,
+        program: &ExpressionProgram,
+        a: &Grid2D<T1>,
+        b: &Grid2D<T2>,
+        previous: Option<&PreviousTimeStep<T1, T2>>,
+        time: [i64; 3],
+    ) -> Grid2D<TO> {
+        let [a_default, b_default] = self.no_data_defaults;
+
+        let a_values = program_input(a, self.map_no_data, a_default);
+        let b_values = program_input(b, self.map_no_data, b_default);
+        let (previous_a_values, previous_b_values) = match previous {
+            Some(previous) if self.uses_previous_time_step => (
+                program_input(&previous.a, self.map_no_data, a_default),
+                program_input(&previous.b, self.map_no_data, b_default),
+            ),
+            _ => (vec![], vec![]),
+        };
+
+        let [height, width] = a.grid_shape_array();
+
+        let inputs = PixelInputs {
+            rasters: &[&a_values, &b_values],
+            previous_a: &previous_a_values,
+            previous_b: &previous_b_values,
+            width,
+            height,
+            t: time[0] as f64,
+            dt: time[1] as f64,
+        };
+
+        let neighbors = program.neighbors();
+        let is_no_data_neighbor = |raster: usize, index: usize| match raster {
+            0 => is_no_data_input(a, index, a_default),
+            _ => is_no_data_input(b, index, b_default),
+        };
+
+        let tile_shape = self.source_query.tile_shape(a.grid_shape());
+        let margin = self.source_query.margin;
+
+        let mut stack = program.stack();
+        let mut data = Vec::with_capacity(tile_shape.number_of_elements());
+
+        for y in margin..height - margin {
+            for x in margin..width - margin {
+                let index = y * width + x;
+
+                let is_no_data = (self.uses_previous_time_step && previous.is_none())
+                    || (!self.map_no_data
+                        && (is_no_data_input(a, index, a_default)
+                            || is_no_data_input(b, index, b_default)
+                            || previous.map_or(false, |previous| {
+                                self.uses_previous_time_step
+                                    && (is_no_data_input(&previous.a, index, a_default)
+                                        || is_no_data_input(&previous.b, index, b_default))
+                            })
+                            || has_no_data_neighbor(&neighbors, x, y, width, is_no_data_neighbor)));
+
+                data.push(if is_no_data {
+                    self.no_data_value
+                } else {
+                    program_output(
+                        program.evaluate(&inputs, x, y, &mut stack),
+                        self.no_data_value,
+                        self.map_no_data,
+                    )
+                });
+            }
+        }
+
+        Grid2D::new(tile_shape, data, Some(self.no_data_value))
+            .expect("raster creation must succeed")
+    }
+}
+
+#[async_trait]
+impl<'a, T1, T2, TO> QueryProcessor for ExpressionQueryProcessor<T1, T2, TO>
+where
+    T1: Pixel,
+    T2: Pixel,
+    TO: Pixel,
+{
+    type Output = RasterTile2D<TO>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'b>(
+        &'b self,
+        query: RasterQueryRectangle,
+        ctx: &'b dyn QueryContext,
+    ) -> Result<BoxStream<'b, Result<Self::Output>>> {
+        let mut kernel = self.kernel.clone();
+
+        let mut previous_time_steps = if self.uses_previous_time_step {
+            self.query_previous_time_step(query, ctx).await?
+        } else {
+            HashMap::new()
+        };
+
+        Ok(self
+            .source_query
+            .query(0, &self.source_a, query, ctx)
+            .await?
+            .zip_aligned(
+                self.source_query
+                    .query(1, &self.source_b, query, ctx)
+                    .await?,
+            )
+            .map(move |tiles| match tiles {
+                // the output is computed if both inputs have defaults for their missing data
+                Ok((a, b))
+                    if a.grid_array.is_empty()
+                        && b.grid_array.is_empty()
+                        && self.no_data_defaults.iter().any(Option::is_none) =>
+                {
+                    previous_time_steps.remove(&a.tile_position.0);
+
+                    Ok(RasterTile2D::new(
+                        a.time,
+                        a.tile_position,
+                        a.global_geo_transform,
+                        EmptyGrid::new(
+                            self.source_query.tile_shape(a.grid_array.grid_shape()),
+                            self.no_data_value,
+                        )
+                        .into(),
+                    ))
+                }
+
+                Ok((a, b)) => {
+                    let a = a.into_materialized_tile(); // TODO: find cases where we don't need this.
+                    let b = b.into_materialized_tile();
+
+                    // a previous time step that is the current one, e.g., of data without time, is ignored
+                    let previous = previous_time_steps
+                        .get(&a.tile_position.0)
+                        .filter(|previous| previous.time != a.time);
+
+                    // the start of the current time step, the time since the previous one and whether there is a previous one
+                    let time = match previous {
+                        Some(previous) => [
+                            a.time.start().inner(),
+                            a.time.start().inner() - previous.time.start().inner(),
+                            1,
+                        ],
+                        None => [a.time.start().inner(), 0, 0],
+                    };
+
+                    let raster = match &mut kernel {
+                        ExpressionKernel::OpenCl(cl_program) => self.run_cl_program(
+                            cl_program,
+                            &a.grid_array,
+                            &b.grid_array,
+                            previous,
+                            time,
+                        ),
+                        ExpressionKernel::Bytecode(program) => {
+                            self.run_bytecode(program, &a.grid_array, &b.grid_array, previous, time)
+                        }
+                    };
+
+                    if self.uses_previous_time_step {
+                        previous_time_steps.insert(
+                            a.tile_position.0,
+                            PreviousTimeStep {
+                                time: a.time,
+                                a: a.grid_array,
+                                b: b.grid_array,
+                            },
+                        );
+                    }
+
+                    Ok(RasterTile2D::new(
+                        a.time,
+                        a.tile_position,
+                        a.global_geo_transform,
+                        raster.into(),
+                    ))
+                }
+                Err(error) => Err(error),
+            })
+            .boxed())
+    }
+}
+
+/// The pixel values of a grid as input of an [`ExpressionProgram`].
+/// No data becomes the `no_data_default` or `NaN` if it is mapped.
+fn program_input<T: Pixel>(
+    grid: &Grid2D<T>,
+    map_no_data: bool,
+    no_data_default: Option<f64>,
+) -> Vec<f64> {
+    grid.data
+        .iter()
+        .map(|&value| match no_data_default {
+            Some(default) if grid.is_no_data(value) => default,
+            None if map_no_data && grid.is_no_data(value) => f64::NAN,
+            _ => value.as_(),
+        })
+        .collect()
+}
+
+/// Whether the pixel at `index` is no data that is not replaced by a default
+fn is_no_data_input<T: Pixel>(
+    grid: &Grid2D<T>,
+    index: usize,
+    no_data_default: Option<f64>,
+) -> bool {
+    no_data_default.is_none() && grid.is_no_data(grid.data[index])
+}
+
+/// Whether a neighbor that a program accesses at the pixel at `x` and `y` is no data
+/// that is not replaced by a default.
+/// The pixel must be at least `NEIGHBORHOOD_RADIUS` pixels away from the border of the grids.
+fn has_no_data_neighbor(
+    neighbors: &[(usize, isize, isize)],
+    x: usize,
+    y: usize,
+    width: usize,
+    is_no_data_input: impl Fn(usize, usize) -> bool,
+) -> bool {
+    neighbors.iter().any(|&(raster, dx, dy)| {
+        let index = (y as isize + dy) as usize * width + (x as isize + dx) as usize;
+        is_no_data_input(raster, index)
+    })
+}
+
+/// The output pixel for a result of an [`ExpressionProgram`], where `NaN` becomes no data if no data is mapped
+fn program_output<TO: Pixel>(value: f64, no_data_value: TO, map_no_data: bool) -> TO {
+    if map_no_data && value.is_nan() {
+        no_data_value
+    } else {
+        TO::from_(value)
+    }
+}
+
+/// Evaluates an [`ExpressionProgram`] over any number of input rasters of arbitrary data types.
+/// It does not support accessing the previous time step.
+struct MultiExpressionQueryProcessor<TO>
+where
+    TO: Pixel,
+{
+    sources: Vec<TypedRasterQueryProcessor>,
+    program: Arc<ExpressionProgram>,
+    no_data_value: TO,
+    map_no_data: bool,
+    /// the no data defaults in the order of the sources
+    no_data_defaults: Vec<Option<f64>>,
+    source_query: SourceQuery,
+}
+
+impl<TO> MultiExpressionQueryProcessor<TO>
+where
+    TO: Pixel,
+{
+    fn compute_tile(&self, tiles: Vec<RasterTile2D<f64>>) -> RasterTile2D<TO> {
+        let time = tiles[0].time;
+        let tile_position = tiles[0].tile_position;
+        let global_geo_transform = tiles[0].global_geo_transform;
+        let tile_shape = self
+            .source_query
+            .tile_shape(tiles[0].grid_array.grid_shape());
+
+        // without mapping, a single empty input tile without a default results in no data only
+        if !self.map_no_data
+            && tiles
+                .iter()
+                .zip(&self.no_data_defaults)
+                .any(|(tile, default)| tile.grid_array.is_empty() && default.is_none())
+        {
+            return RasterTile2D::new(
+                time,
+                tile_position,
+                global_geo_transform,
+                EmptyGrid::new(tile_shape, self.no_data_value).into(),
+            );
+        }
+
+        let grids: Vec<Grid2D<f64>> = tiles
+            .into_iter()
+            .map(|tile| tile.into_materialized_tile().grid_array)
+            .collect();
+        let values: Vec<Vec<f64>> = grids
+            .iter()
+            .zip(&self.no_data_defaults)
+            .map(|(grid, &default)| program_input(grid, self.map_no_data, default))
+            .collect();
+        let rasters: Vec<&[f64]> = values.iter().map(Vec::as_slice).collect();
+
+        let [height, width] = grids[0].grid_shape_array();
+
+        let inputs = PixelInputs {
+            rasters: &rasters,
+            previous_a: &[],
+            previous_b: &[],
+            width,
+            height,
+            t: time.start().inner() as f64,
+            dt: 0.,
+        };
+
+        let neighbors = self.program.neighbors();
+        let is_no_data_neighbor = |raster: usize, index: usize| {
+            is_no_data_input(&grids[raster], index, self.no_data_defaults[raster])
+        };
+
+        let margin = self.source_query.margin;
+
+        let mut stack = self.program.stack();
+        let mut data = Vec::with_capacity(tile_shape.number_of_elements());
+
+        for y in margin..height - margin {
+            for x in margin..width - margin {
+                let index = y * width + x;
+
+                let is_no_data = !self.map_no_data
+                    && (grids
+                        .iter()
+                        .zip(&self.no_data_defaults)
+                        .any(|(grid, &default)| is_no_data_input(grid, index, default))
+                        || has_no_data_neighbor(&neighbors, x, y, width, is_no_data_neighbor));
+
+                data.push(if is_no_data {
+                    self.no_data_value
+                } else {
+                    program_output(
+                        self.program.evaluate(&inputs, x, y, &mut stack),
+                        self.no_data_value,
+                        self.map_no_data,
+                    )
+                });
+            }
+        }
+
+        RasterTile2D::new(
+            time,
+            tile_position,
+            global_geo_transform,
+            Grid2D::new(tile_shape, data, Some(self.no_data_value))
+                .expect("raster creation must succeed")
+                .into(),
+        )
+    }
+}
+
+#[async_trait]
+impl<TO> QueryProcessor for MultiExpressionQueryProcessor<TO>
+where
+    TO: Pixel,
+{
+    type Output = RasterTile2D<TO>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'b>(
+        &'b self,
+        query: RasterQueryRectangle,
+        ctx: &'b dyn QueryContext,
+    ) -> Result<BoxStream<'b, Result<Self::Output>>> {
+        let mut streams = Vec::with_capacity(self.sources.len());
+
+        for (index, source) in self.sources.iter().enumerate() {
+            streams.push(call_on_generic_raster_processor!(source, processor => {
+                self.source_query
+                    .query(index, processor, query, ctx)
+                    .await?
+                    .map(|tile| tile.map(|tile| tile.convert::<f64>()))
+                    .boxed()
+            }));
+        }
+
+        Ok(RasterArrayZip::new(streams)
+            .map(move |tiles| Ok(self.compute_tile(tiles?)))
+            .boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::primitives::{
+        Measurement, SpatialPartition2D, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::raster::TileInformation;
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+
+    #[test]
+    fn deserialize_params() {
+        let s = r#"{"expression":"1*A","outputType":"F64","outputNoDataValue":0.0,"outputMeasurement":null}"#;
+
+        assert_eq!(
+            serde_json::from_str::<ExpressionParams>(s).unwrap(),
+            ExpressionParams {
+                expression: "1*A".to_owned(),
+                output_type: RasterDataType::F64,
+                output_no_data_value: 0.0,
+                output_measurement: None,
+                backend: ExpressionBackend::OpenCl,
+                map_no_data: false,
+                no_data_defaults: Default::default(),
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_params_no_data() {
+        let s = r#"{"expression":"1*A","outputType":"F64","outputNoDataValue":"nan","outputMeasurement":null}"#;
+
+        assert!(f64::is_nan(
+            serde_json::from_str::<ExpressionParams>(s)
+                .unwrap()
+                .output_no_data_value
+        ),);
+    }
+
+    #[test]
+    fn deserialize_params_missing_no_data() {
+        let s = r#"{"expression":"1*A","outputType":"F64","outputNoDataValue":null,"outputMeasurement":null}"#;
+
+        assert!(serde_json::from_str::<ExpressionParams>(s).is_err());
+    }
+
+    #[test]
+    fn serialize_params() {
+        let s = r#"{"expression":"1*A","outputType":"F64","outputNoDataValue":0.0,"outputMeasurement":null}"#;
+
+        assert_eq!(
+            s,
+            serde_json::to_string(&ExpressionParams {
+                expression: "1*A".to_owned(),
+                output_type: RasterDataType::F64,
+                output_no_data_value: 0.0,
+                output_measurement: None,
+                backend: ExpressionBackend::OpenCl,
+                map_no_data: false,
+                no_data_defaults: Default::default(),
+            })
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn serialize_params_no_data() {
+        let s = r#"{"expression":"1*A","outputType":"F64","outputNoDataValue":"nan","outputMeasurement":null}"#;
+
+        assert_eq!(
+            s,
+            serde_json::to_string(&ExpressionParams {
+                expression: "1*A".to_owned(),
+                output_type: RasterDataType::F64,
+                output_no_data_value: f64::NAN,
+                output_measurement: None,
+                backend: ExpressionBackend::OpenCl,
+                map_no_data: false,
+                no_data_defaults: Default::default(),
+            })
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn serialize_params_bytecode_options() {
+        let s = r#"{"expression":"A+B","outputType":"F64","outputNoDataValue":0.0,"outputMeasurement":null,"backend":"bytecode","mapNoData":true,"noDataDefaults":{"A":0.0}}"#;
+
+        let params = ExpressionParams {
+            expression: "A+B".to_owned(),
+            output_type: RasterDataType::F64,
+            output_no_data_value: 0.0,
+            output_measurement: None,
+            backend: ExpressionBackend::Bytecode,
+            map_no_data: true,
+            no_data_defaults: [("A".to_string(), 0.0)].iter().cloned().collect(),
+        };
+
+        assert_eq!(s, serde_json::to_string(&params).unwrap());
+        assert_eq!(serde_json::from_str::<ExpressionParams>(s).unwrap(), params);
+    }
+
+    #[tokio::test]
+    async fn basic() {
+        let no_data_value = 42;
+        let no_data_value_option = Some(no_data_value);
+
+        let raster_a = make_raster();
+        let raster_b = make_raster();
+
+        let o = Expression {
+            params: ExpressionParams {
+                expression: "A+B".to_string(),
+                output_type: RasterDataType::I8,
+                output_no_data_value: no_data_value.as_(), //  cast no_data_valuee to f64
+                output_measurement: Some(Measurement::Unitless),
+                backend: ExpressionBackend::OpenCl,
+                map_no_data: false,
+                no_data_defaults: Default::default(),
+            },
+            sources: ExpressionSources::new_a_b(raster_a, raster_b),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .unwrap();
+
+        let processor = o.query_processor().unwrap().get_i8().unwrap();
+
+        let ctx = MockQueryContext::new(1);
+        let result_stream = processor
+            .query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new_unchecked(
+                        (0., 4.).into(),
+                        (3., 0.).into(),
+                    ),
+                    time_interval: Default::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &ctx,
+            )
+            .await
+            .unwrap();
+
+        let result: Vec<Result<RasterTile2D<i8>>> = result_stream.collect().await;
+
+        assert_eq!(result.len(), 1);
+
+        assert_eq!(
+            result[0].as_ref().unwrap().grid_array,
+            Grid2D::new(
+                [3, 2].into(),
+                vec![2, 4, 6, 8, 10, 12],
+                no_data_value_option,
+            )
+            .unwrap()
+            .into()
+        );
+    }
+
+    #[tokio::test]
+    async fn temporal_difference() {
+        let no_data_value = 42;
+
+        let time_steps = vec![
+            (TimeInterval::new_unchecked(0, 10), vec![1, 2, 3, 4, 5, 6]),
+            (
+                TimeInterval::new_unchecked(10, 20),
+                vec![2, 4, 6, 8, 10, 12],
+            ),
+        ];
+
+        let result = query_expression(
+            "(A - A_PREV) * dt / 10",
+            time_steps,
+            no_data_value,
+            TimeInterval::new_unchecked(0, 20),
+        )
+        .await;
+
+        assert_eq!(result.len(), 2);
+
+        // the first time step has no predecessor
+        assert_eq!(
+            result[0].as_ref().unwrap().grid_array,
+            Grid2D::new([3, 2].into(), vec![42; 6], Some(no_data_value))
+                .unwrap()
+                .into()
+        );
+        assert_eq!(
+            result[1].as_ref().unwrap().grid_array,
+            Grid2D::new([3, 2].into(), vec![1, 2, 3, 4, 5, 6], Some(no_data_value))
+                .unwrap()
+                .into()
+        );
+
+        // the predecessor of the queried time step is loaded on its own
+        let time_steps = vec![
+            (TimeInterval::new_unchecked(0, 10), vec![1, 2, 3, 4, 5, 6]),
+            (
+                TimeInterval::new_unchecked(10, 20),
+                vec![2, 4, 6, 8, 10, 12],
+            ),
+        ];
+
+        let result = query_expression(
+            "B - A_PREV",
+            time_steps,
+            no_data_value,
+            TimeInterval::new_unchecked(10, 20),
+        )
+        .await;
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].as_ref().unwrap().grid_array,
+            Grid2D::new([3, 2].into(), vec![1, 2, 3, 4, 5, 6], Some(no_data_value))
+                .unwrap()
+                .into()
+        );
+    }
+
+    #[tokio::test]
+    async fn time_and_neighborhood() {
+        let no_data_value = 42;
+
+        let time_steps = vec![(TimeInterval::new_unchecked(10, 20), vec![1, 2, 3, 4, 5, 6])];
+
+        let result = query_expression(
+            "A_AT(1, 0) + B_AT(0, -5) + t",
+            time_steps,
+            no_data_value,
+            TimeInterval::new_unchecked(10, 20),
+        )
+        .await;
+
+        assert_eq!(result.len(), 1);
+
+        // right neighbors plus upper neighbors, there is no data beyond the raster
+        assert_eq!(
+            result[0].as_ref().unwrap().grid_array,
+            Grid2D::new(
+                [3, 2].into(),
+                vec![42, 42, 15, 42, 19, 42],
+                Some(no_data_value),
+            )
+            .unwrap()
+            .into()
+        );
+    }
+
+    #[tokio::test]
+    async fn neighborhood_across_tile_borders() {
+        let no_data_value = 0;
+        let tiles: Vec<RasterTile2D<u8>> =
+            [([-1, 0], vec![1, 2, 3, 4]), ([-1, 1], vec![5, 6, 7, 0])]
+                .iter()
+                .map(|(tile_position, values)| {
+                    RasterTile2D::new_with_tile_info(
+                        TimeInterval::default(),
+                        TileInformation {
+                            global_tile_position: (*tile_position).into(),
+                            tile_size_in_pixels: [2, 2].into(),
+                            global_geo_transform: Default::default(),
+                        },
+                        Grid2D::new([2, 2].into(), values.clone(), Some(no_data_value))
+                            .unwrap()
+                            .into(),
+                    )
+                })
+                .collect();
+        let raster = || {
+            MockRasterSource {
+                params: MockRasterSourceParams {
+                    data: tiles.clone(),
+                    result_descriptor: RasterResultDescriptor {
+                        data_type: RasterDataType::U8,
+                        spatial_reference: SpatialReference::epsg_4326().into(),
+                        measurement: Measurement::Unitless,
+                        no_data_value: Some(no_data_value.into()),
+                        bands: Vec::new(),
+                    },
+                },
+            }
+            .boxed()
+        };
+
+        let exe_ctx = MockExecutionContext {
+            tiling_specification: TilingSpecification::new((0., 0.).into(), [2, 2].into()),
+            ..Default::default()
+        };
+
+        // one and two sources are computed by different processors
+        for (expression, sources) in [
+            ("A_AT(1, 0)", ExpressionSources::new_a(raster())),
+            (
+                "A_AT(1, 0) + 0 * B",
+                ExpressionSources::new_a_b(raster(), raster()),
+            ),
+        ] {
+            let o = Expression {
+                params: ExpressionParams {
+                    expression: expression.to_string(),
+                    output_type: RasterDataType::I8,
+                    output_no_data_value: -1.,
+                    output_measurement: None,
+                    backend: ExpressionBackend::Bytecode,
+                    map_no_data: false,
+                    no_data_defaults: Default::default(),
+                },
+                sources,
+            }
+            .boxed()
+            .initialize(&exe_ctx)
+            .await
+            .unwrap();
+
+            let processor = o.query_processor().unwrap().get_i8().unwrap();
+
+            let ctx = MockQueryContext::new(1);
+            let result: Vec<Grid2D<i8>> = processor
+                .query(
+                    RasterQueryRectangle {
+                        spatial_bounds: SpatialPartition2D::new_unchecked(
+                            (0., 2.).into(),
+                            (4., 0.).into(),
+                        ),
+                        time_interval: Default::default(),
+                        spatial_resolution: SpatialResolution::one(),
+                    },
+                    &ctx,
+                )
+                .await
+                .unwrap()
+                .map(|tile| tile.unwrap().into_materialized_tile().grid_array)
+                .collect()
+                .await;
+
+            // the right neighbors of the first tile are in the second tile,
+            // the right neighbors of the second tile are no data or beyond the raster
+            assert_eq!(
+                result,
+                vec![
+                    Grid2D::new([2, 2].into(), vec![2, 5, 4, 7], Some(-1)).unwrap(),
+                    Grid2D::new([2, 2].into(), vec![6, -1, -1, -1], Some(-1)).unwrap(),
+                ]
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn multiple_inputs_of_different_types() {
+        let no_data_value = -1.;
+
+        let o = Expression {
+            params: ExpressionParams {
+                expression: "if C > 0 then max(A, B) * C else abs(A - B)".to_string(),
+                output_type: RasterDataType::F32,
+                output_no_data_value: no_data_value,
+                output_measurement: None,
+                backend: ExpressionBackend::Bytecode,
+                map_no_data: false,
+                no_data_defaults: Default::default(),
+            },
+            sources: ExpressionSources::new_a_b_c(
+                make_typed_raster(vec![1, 2, 3, 4, 5, 6], None, RasterDataType::I8),
+                make_typed_raster(vec![6, 5, 4, 3, 2, 1], None, RasterDataType::U16),
+                make_typed_raster(vec![1, 0, 2, 255, 3, 0], Some(255), RasterDataType::F32),
+            ),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .unwrap();
+
+        let processor = o.query_processor().unwrap().get_f32().unwrap();
+
+        let ctx = MockQueryContext::new(1);
+        let result: Vec<Result<RasterTile2D<f32>>> = processor
+            .query(query_rectangle(), &ctx)
+            .await
+            .unwrap()
+            .collect()
+            .await;
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].as_ref().unwrap().grid_array,
+            Grid2D::new(
+                [3, 2].into(),
+                vec![6., 3., 8., -1., 15., 5.],
+                Some(no_data_value as f32),
+            )
+            .unwrap()
+            .into()
+        );
+    }
+
+    #[tokio::test]
+    async fn map_no_data() {
+        for (map_no_data, expected) in [
+            (false, vec![2, 4, -1, 8, 10, 12]),
+            (true, vec![2, 4, 100, 8, 10, 12]),
+        ] {
+            let o = Expression {
+                params: ExpressionParams {
+                    expression: "if isnan(A) then 100 else A * 2".to_string(),
+                    output_type: RasterDataType::I16,
+                    output_no_data_value: -1.,
+                    output_measurement: None,
+                    backend: ExpressionBackend::Bytecode,
+                    map_no_data,
+                    no_data_defaults: Default::default(),
+                },
+                sources: ExpressionSources::new_a(make_typed_raster(
+                    vec![1, 2, 0, 4, 5, 6],
+                    Some(0),
+                    RasterDataType::I8,
+                )),
+            }
+            .boxed()
+            .initialize(&MockExecutionContext::default())
+            .await
+            .unwrap();
+
+            let processor = o.query_processor().unwrap().get_i16().unwrap();
+
+            let ctx = MockQueryContext::new(1);
+            let result: Vec<Result<RasterTile2D<i16>>> = processor
+                .query(query_rectangle(), &ctx)
+                .await
+                .unwrap()
+                .collect()
+                .await;
+
+            assert_eq!(result.len(), 1);
+            assert_eq!(
+                result[0].as_ref().unwrap().grid_array,
+                Grid2D::new([3, 2].into(), expected, Some(-1))
+                    .unwrap()
+                    .into()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn no_data_defaults() {
+        let a = || make_typed_raster(vec![1, 0, 0, 4, 5, 6], Some(0), RasterDataType::U8);
+        let b = || make_typed_raster(vec![0, 2, 0, 4, 5, 6], Some(0), RasterDataType::U8);
+        let c = || make_typed_raster(vec![1, 1, 1, 1, 1, 1], None, RasterDataType::U8);
+
+        for (defaults, sources, expected) in [
+            (
+                vec![("A", 0.), ("B", 0.)],
+                ExpressionSources::new_a_b(a(), b()),
+                vec![1, 2, 0, 8, 10, 12],
+            ),
+            (
+                vec![("B", 10.)],
+                ExpressionSources::new_a_b(a(), b()),
+                vec![11, -1, -1, 8, 10, 12],
+            ),
+            (
+                vec![("A", 0.), ("B", 0.)],
+                ExpressionSources::new_a_b_c(a(), b(), c()),
+                vec![1, 2, 0, 8, 10, 12],
+            ),
+        ] {
+            let o = Expression {
+                params: ExpressionParams {
+                    expression: "A + B".to_string(),
+                    output_type: RasterDataType::I16,
+                    output_no_data_value: -1.,
+                    output_measurement: None,
+                    backend: ExpressionBackend::Bytecode,
+                    map_no_data: false,
+                    no_data_defaults: defaults
+                        .into_iter()
+                        .map(|(variable, default)| (variable.to_string(), default))
+                        .collect(),
+                },
+                sources,
+            }
+            .boxed()
+            .initialize(&MockExecutionContext::default())
+            .await
+            .unwrap();
+
+            let processor = o.query_processor().unwrap().get_i16().unwrap();
+
+            let ctx = MockQueryContext::new(1);
+            let result: Vec<Result<RasterTile2D<i16>>> = processor
+                .query(query_rectangle(), &ctx)
+                .await
+                .unwrap()
+                .collect()
+                .await;
+
+            assert_eq!(result.len(), 1);
+            assert_eq!(
+                result[0].as_ref().unwrap().grid_array,
+                Grid2D::new([3, 2].into(), expected, Some(-1))
+                    .unwrap()
+                    .into()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn no_data_options_require_the_bytecode_backend() {
+        for (map_no_data, no_data_defaults) in [
+            (true, BTreeMap::new()),
+            (false, [("A".to_string(), 0.)].iter().cloned().collect()),
+        ] {
+            let result = Expression {
+                params: ExpressionParams {
+                    expression: "A * 2".to_string(),
+                    output_type: RasterDataType::I8,
+                    output_no_data_value: 0.,
+                    output_measurement: None,
+                    backend: ExpressionBackend::default(),
+                    map_no_data,
+                    no_data_defaults,
+                },
+                sources: ExpressionSources::new_a(make_raster()),
+            }
+            .boxed()
+            .initialize(&MockExecutionContext::default())
+            .await;
+
+            assert!(matches!(
+                result,
+                Err(crate::error::Error::UnsupportedOpenClExpressionFeature { .. })
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn invalid_sources() {
+        let params = |expression: &str, backend: ExpressionBackend| ExpressionParams {
+            expression: expression.to_string(),
+            output_type: RasterDataType::I8,
+            output_no_data_value: 0.,
+            output_measurement: None,
+            backend,
+            map_no_data: false,
+            no_data_defaults: Default::default(),
+        };
+
+        for (params, sources) in [
+            (
+                params("A + C", ExpressionBackend::Bytecode),
+                ExpressionSources {
+                    c: Some(make_raster()),
+                    ..ExpressionSources::new_a(make_raster())
+                },
+            ),
+            (
+                params("A + C", ExpressionBackend::Bytecode),
+                ExpressionSources::new_a_b(make_raster(), make_raster()),
+            ),
+            (
+                params("A + A_PREV", ExpressionBackend::Bytecode),
+                ExpressionSources::new_a(make_raster()),
+            ),
+            (
+                params("A + B + C", ExpressionBackend::OpenCl),
+                ExpressionSources::new_a_b_c(make_raster(), make_raster(), make_raster()),
+            ),
+            (
+                ExpressionParams {
+                    map_no_data: true,
+                    ..params("A + B", ExpressionBackend::OpenCl)
+                },
+                ExpressionSources::new_a_b(make_raster(), make_raster()),
+            ),
+            (
+                ExpressionParams {
+                    no_data_defaults: [("A".to_string(), 0.)].iter().cloned().collect(),
+                    ..params("A + B", ExpressionBackend::OpenCl)
+                },
+                ExpressionSources::new_a_b(make_raster(), make_raster()),
+            ),
+            (
+                ExpressionParams {
+                    no_data_defaults: [("C".to_string(), 0.)].iter().cloned().collect(),
+                    ..params("A + B", ExpressionBackend::Bytecode)
+                },
+                ExpressionSources::new_a_b(make_raster(), make_raster()),
+            ),
+        ] {
+            assert!(Expression { params, sources }
+                .boxed()
+                .initialize(&MockExecutionContext::default())
+                .await
+                .is_err());
+        }
+    }
+
+    fn query_rectangle() -> RasterQueryRectangle {
+        RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new_unchecked((0., 4.).into(), (3., 0.).into()),
+            time_interval: Default::default(),
+            spatial_resolution: SpatialResolution::one(),
+        }
+    }
+
+    /// Queries the expression with both backends and checks that they agree
+    async fn query_expression(
+        expression: &str,
+        time_steps: Vec<(TimeInterval, Vec<i8>)>,
+        no_data_value: i8,
+        time_interval: TimeInterval,
+    ) -> Vec<Result<RasterTile2D<i8>>> {
+        let mut results = vec![];
+
+        for backend in [ExpressionBackend::Bytecode, ExpressionBackend::OpenCl] {
+            let o = Expression {
+                params: ExpressionParams {
+                    expression: expression.to_string(),
+                    output_type: RasterDataType::I8,
+                    output_no_data_value: no_data_value.as_(),
+                    output_measurement: Some(Measurement::Unitless),
+                    backend,
+                    map_no_data: false,
+                    no_data_defaults: Default::default(),
+                },
+                sources: ExpressionSources::new_a_b(
+                    make_temporal_raster(time_steps.clone()),
+                    make_temporal_raster(time_steps.clone()),
+                ),
+            }
+            .boxed()
+            .initialize(&MockExecutionContext {
+                tiling_specification: TilingSpecification::new((0., 0.).into(), [3, 2].into()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+            let processor = o.query_processor().unwrap().get_i8().unwrap();
+
+            let ctx = MockQueryContext::new(1);
+            let result: Vec<Result<RasterTile2D<i8>>> = processor
+                .query(
+                    RasterQueryRectangle {
+                        spatial_bounds: SpatialPartition2D::new_unchecked(
+                            (0., 3.).into(),
+                            (2., 0.).into(),
+                        ),
+                        time_interval,
+                        spatial_resolution: SpatialResolution::one(),
+                    },
+                    &ctx,
+                )
+                .await
+                .unwrap()
+                .collect()
+                .await;
+
+            results.push(result);
+        }
+
+        let open_cl = results.pop().unwrap();
+        let bytecode = results.pop().unwrap();
+
+        assert_eq!(
+            bytecode
+                .iter()
+                .map(|tile| tile.as_ref().unwrap().grid_array.clone())
+                .collect::<Vec<_>>(),
+            open_cl
+                .iter()
+                .map(|tile| tile.as_ref().unwrap().grid_array.clone())
+                .collect::<Vec<_>>()
+        );
+
+        bytecode
+    }
+
+    fn make_temporal_raster(time_steps: Vec<(TimeInterval, Vec<i8>)>) -> Box<dyn RasterOperator> {
+        let data = time_steps
+            .into_iter()
+            .map(|(time, values)| {
+                RasterTile2D::new_with_tile_info(
+                    time,
+                    TileInformation {
+                        global_tile_position: [-1, 0].into(),
+                        tile_size_in_pixels: [3, 2].into(),
+                        global_geo_transform: Default::default(),
+                    },
+                    Grid2D::new([3, 2].into(), values, None).unwrap().into(),
+                )
+            })
+            .collect();
+
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data,
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::I8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: None,
+                    bands: Vec::new(),
+                },
+            },
+        }
+        .boxed()
+    }
+
+    /// A raster of the `data_type` with the `values` of a mock source
+    fn make_typed_raster(
+        values: Vec<u8>,
+        no_data_value: Option<u8>,
+        data_type: RasterDataType,
+    ) -> Box<dyn RasterOperator> {
+        let raster_tile = RasterTile2D::new_with_tile_info(
+            TimeInterval::default(),
+            TileInformation {
+                global_tile_position: [-1, 0].into(),
+                tile_size_in_pixels: [3, 2].into(),
+                global_geo_transform: Default::default(),
+            },
+            Grid2D::new([3, 2].into(), values, no_data_value)
+                .unwrap()
+                .into(),
+        );
+
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![raster_tile],
+                result_descriptor: RasterResultDescriptor {
+                    data_type,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: no_data_value.map(AsPrimitive::as_),
+                    bands: Vec::new(),
+                },
+            },
+        }
+        .boxed()
+    }
+
+    fn make_raster() -> Box<dyn RasterOperator> {
+        let no_data_value = None;
+        let raster = Grid2D::new([3, 2].into(), vec![1, 2, 3, 4, 5, 6], no_data_value).unwrap();
+
+        let raster_tile = RasterTile2D::new_with_tile_info(
+            TimeInterval::default(),
+            TileInformation {
+                global_tile_position: [-1, 0].into(),
+                tile_size_in_pixels: [3, 2].into(),
+                global_geo_transform: Default::default(),
+            },
+            raster.into(),
+        );
+
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![raster_tile],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::I8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: no_data_value.map(AsPrimitive::as_),
+                    bands: Vec::new(),
+                },
+            },
+        }
+        .boxed()
+    }
+}
@@ -0,0 +1,624 @@
+use crate::engine::{
+    ExecutionContext, InitializedVectorOperator, Operator, QueryContext, QueryProcessor,
+    SingleVectorSource, TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor,
+    VectorQueryRectangle, VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    BuilderProvider, DataCollection, FeatureCollection, FeatureCollectionInfos, VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, FeatureDataType, FeatureDataValue, Geometry, TimeInterval,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::collections::HashMap;
+
+/// An operator that groups features by the values of one or more columns and aggregates
+/// other columns per group.
+///
+/// The result is a `Data` collection (without geometries) with one row per group.
+pub type FeatureAggregation = Operator<FeatureAggregationParams, SingleVectorSource>;
+
+/// The parameter spec for `FeatureAggregation`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureAggregationParams {
+    /// The columns to group by. If empty, all features form a single group.
+    #[serde(default)]
+    pub group_by: Vec<String>,
+    pub aggregations: Vec<ColumnAggregation>,
+}
+
+/// An aggregation of a single column
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnAggregation {
+    pub column: String,
+    pub function: AggregationFunction,
+    /// The name of the output column, defaults to `{column}_{function}`
+    #[serde(default)]
+    pub output_column: Option<String>,
+}
+
+impl ColumnAggregation {
+    pub fn output_column(&self) -> String {
+        self.output_column
+            .clone()
+            .unwrap_or_else(|| format!("{}_{}", self.column, self.function.name()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum AggregationFunction {
+    Sum,
+    Mean,
+    Min,
+    Max,
+    /// The number of non-null values
+    Count,
+}
+
+impl AggregationFunction {
+    fn name(self) -> &'static str {
+        match self {
+            AggregationFunction::Sum => "sum",
+            AggregationFunction::Mean => "mean",
+            AggregationFunction::Min => "min",
+            AggregationFunction::Max => "max",
+            AggregationFunction::Count => "count",
+        }
+    }
+
+    pub(crate) fn output_type(self) -> FeatureDataType {
+        match self {
+            AggregationFunction::Count => FeatureDataType::Int,
+            _ => FeatureDataType::Float,
+        }
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for FeatureAggregation {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let vector_source = self.sources.vector.initialize(context).await?;
+        let input_columns = &vector_source.result_descriptor().columns;
+
+        let mut columns =
+            HashMap::with_capacity(self.params.group_by.len() + self.params.aggregations.len());
+
+        for column in &self.params.group_by {
+            let data_type =
+                input_columns
+                    .get(column)
+                    .ok_or_else(|| error::Error::ColumnDoesNotExist {
+                        column: column.clone(),
+                    })?;
+
+            ensure!(
+                columns.insert(column.clone(), *data_type).is_none(),
+                error::InvalidOperatorSpec {
+                    reason: format!("column `{}` is grouped by more than once", column)
+                }
+            );
+        }
+
+        for aggregation in &self.params.aggregations {
+            let data_type = input_columns.get(&aggregation.column).ok_or_else(|| {
+                error::Error::ColumnDoesNotExist {
+                    column: aggregation.column.clone(),
+                }
+            })?;
+
+            ensure!(
+                aggregation.function == AggregationFunction::Count || data_type.is_numeric(),
+                error::InvalidFeatureDataType
+            );
+
+            let output_column = aggregation.output_column();
+
+            ensure!(
+                columns
+                    .insert(output_column.clone(), aggregation.function.output_type())
+                    .is_none(),
+                error::InvalidOperatorSpec {
+                    reason: format!("output column `{}` is not unique", output_column)
+                }
+            );
+        }
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: VectorDataType::Data,
+            spatial_reference: vector_source.result_descriptor().spatial_reference,
+            columns,
+        };
+
+        Ok(InitializedFeatureAggregation {
+            result_descriptor,
+            vector_source,
+            state: self.params,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedFeatureAggregation {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    state: FeatureAggregationParams,
+}
+
+impl InitializedVectorOperator for InitializedFeatureAggregation {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let processor = call_on_generic_vector_processor!(
+            self.vector_source.query_processor()?,
+            source => FeatureAggregationProcessor {
+                source,
+                params: self.state.clone(),
+                columns: self.result_descriptor.columns.clone(),
+            }.boxed()
+        );
+
+        Ok(TypedVectorQueryProcessor::Data(processor))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct FeatureAggregationProcessor<G> {
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    params: FeatureAggregationParams,
+    columns: HashMap<String, FeatureDataType>,
+}
+
+#[async_trait]
+impl<G> QueryProcessor for FeatureAggregationProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type Output = DataCollection;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let groups = self.source.query(query, ctx).await?.try_fold(
+            Groups::default(),
+            |mut groups, collection| async move {
+                groups.add_collection(&collection, &self.params)?;
+                Ok(groups)
+            },
+        );
+
+        // the aggregation can only be emitted after all input features were consumed
+        Ok(
+            stream::once(async move { groups.await?.into_collection(&self.params, &self.columns) })
+                .boxed(),
+        )
+    }
+}
+
+/// A hashable representation of a group by value
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum GroupKey {
+    Null,
+    Category(u8),
+    Int(i64),
+    Float(u64),
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl From<&FeatureDataValue> for GroupKey {
+    fn from(value: &FeatureDataValue) -> Self {
+        match value {
+            FeatureDataValue::Category(v) | FeatureDataValue::NullableCategory(Some(v)) => {
+                Self::Category(*v)
+            }
+            FeatureDataValue::Int(v) | FeatureDataValue::NullableInt(Some(v)) => Self::Int(*v),
+            FeatureDataValue::Float(v) | FeatureDataValue::NullableFloat(Some(v)) => {
+                Self::Float(v.to_bits())
+            }
+            FeatureDataValue::Text(v)
+            | FeatureDataValue::NullableText(Some(v))
+            | FeatureDataValue::Dictionary(v)
+            | FeatureDataValue::NullableDictionary(Some(v)) => Self::Text(v.clone()),
+            FeatureDataValue::Bytes(v) | FeatureDataValue::NullableBytes(Some(v)) => {
+                Self::Bytes(v.clone())
+            }
+            FeatureDataValue::NullableCategory(None)
+            | FeatureDataValue::NullableInt(None)
+            | FeatureDataValue::NullableFloat(None)
+            | FeatureDataValue::NullableText(None)
+            | FeatureDataValue::NullableBytes(None)
+            | FeatureDataValue::NullableDictionary(None) => Self::Null,
+        }
+    }
+}
+
+/// The groups in order of their first occurrence
+#[derive(Debug, Default)]
+struct Groups {
+    indices: HashMap<Vec<GroupKey>, usize>,
+    groups: Vec<Group>,
+}
+
+#[derive(Debug)]
+struct Group {
+    values: Vec<FeatureDataValue>,
+    time: TimeInterval,
+    accumulators: Vec<Accumulator>,
+}
+
+impl Groups {
+    fn add_collection<C>(&mut self, collection: &C, params: &FeatureAggregationParams) -> Result<()>
+    where
+        C: FeatureCollectionInfos,
+    {
+        let group_data = params
+            .group_by
+            .iter()
+            .map(|column| collection.data(column))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let aggregation_values = params
+            .aggregations
+            .iter()
+            .map(|aggregation| {
+                let data = collection.data(&aggregation.column)?;
+
+                let values: Vec<Option<f64>> = if aggregation.function == AggregationFunction::Count
+                {
+                    // only the presence of a value is relevant for counting
+                    data.nulls()
+                        .into_iter()
+                        .map(|is_null| if is_null { None } else { Some(1.) })
+                        .collect()
+                } else {
+                    data.float_options_iter().collect()
+                };
+
+                Ok(values)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        for (row, time) in collection.time_intervals().iter().enumerate() {
+            let values: Vec<FeatureDataValue> = group_data
+                .iter()
+                .map(|data| data.get_unchecked(row))
+                .collect();
+            let key: Vec<GroupKey> = values.iter().map(GroupKey::from).collect();
+
+            let groups = &mut self.groups;
+            let index = *self.indices.entry(key).or_insert_with(|| {
+                groups.push(Group {
+                    values,
+                    time: *time,
+                    accumulators: vec![Accumulator::default(); params.aggregations.len()],
+                });
+                groups.len() - 1
+            });
+
+            let group = &mut self.groups[index];
+            group.time = group.time.extend(time);
+
+            for (accumulator, values) in group.accumulators.iter_mut().zip(&aggregation_values) {
+                if let Some(value) = values[row] {
+                    accumulator.add(value);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn into_collection(
+        self,
+        params: &FeatureAggregationParams,
+        columns: &HashMap<String, FeatureDataType>,
+    ) -> Result<DataCollection> {
+        let mut builder = DataCollection::builder();
+        for (column, data_type) in columns {
+            builder.add_column(column.clone(), *data_type)?;
+        }
+        let mut builder = builder.finish_header();
+
+        let output_columns: Vec<String> = params
+            .aggregations
+            .iter()
+            .map(ColumnAggregation::output_column)
+            .collect();
+
+        for group in self.groups {
+            builder.push_time_interval(group.time)?;
+
+            for (column, value) in params.group_by.iter().zip(group.values) {
+                builder.push_data(column, value)?;
+            }
+
+            for ((column, aggregation), accumulator) in output_columns
+                .iter()
+                .zip(&params.aggregations)
+                .zip(&group.accumulators)
+            {
+                builder.push_data(column, accumulator.result(aggregation.function))?;
+            }
+
+            builder.finish_row();
+        }
+
+        builder.build().map_err(Into::into)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Accumulator {
+    count: u64,
+    sum: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Default for Accumulator {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            sum: 0.,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+}
+
+impl Accumulator {
+    pub(crate) fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    pub(crate) fn result(&self, function: AggregationFunction) -> FeatureDataValue {
+        if function == AggregationFunction::Count {
+            return FeatureDataValue::Int(self.count as i64);
+        }
+
+        if self.count == 0 {
+            return FeatureDataValue::NullableFloat(None);
+        }
+
+        FeatureDataValue::NullableFloat(Some(match function {
+            AggregationFunction::Sum => self.sum,
+            AggregationFunction::Mean => self.sum / self.count as f64,
+            AggregationFunction::Min => self.min,
+            AggregationFunction::Max => self.max,
+            AggregationFunction::Count => unreachable!("handled above"),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{FeatureData, MultiPoint, SpatialResolution};
+
+    #[test]
+    fn serde() {
+        let aggregation = FeatureAggregation {
+            params: FeatureAggregationParams {
+                group_by: vec!["foo".to_string()],
+                aggregations: vec![ColumnAggregation {
+                    column: "bar".to_string(),
+                    function: AggregationFunction::Mean,
+                    output_column: None,
+                }],
+            },
+            sources: MockFeatureCollectionSource::<MultiPoint>::multiple(vec![])
+                .boxed()
+                .into(),
+        }
+        .boxed();
+
+        let serialized = serde_json::to_string(&aggregation).unwrap();
+
+        assert_eq!(
+            serialized,
+            serde_json::json!({
+                "type": "FeatureAggregation",
+                "params": {
+                    "groupBy": ["foo"],
+                    "aggregations": [{
+                        "column": "bar",
+                        "function": "mean",
+                        "outputColumn": null
+                    }]
+                },
+                "sources": {
+                    "vector": {
+                        "type": "MockFeatureCollectionSourceMultiPoint",
+                        "params": {
+                            "collections": []
+                        }
+                    }
+                },
+            })
+            .to_string()
+        );
+
+        let _operator: Box<dyn VectorOperator> = serde_json::from_str(&serialized).unwrap();
+    }
+
+    #[tokio::test]
+    async fn execute() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1), (2.0, 2.1), (3.0, 3.1)]).unwrap(),
+            vec![
+                TimeInterval::new(0, 1).unwrap(),
+                TimeInterval::new(1, 2).unwrap(),
+                TimeInterval::new(0, 1).unwrap(),
+                TimeInterval::new(2, 3).unwrap(),
+            ],
+            [
+                (
+                    "group".to_string(),
+                    FeatureData::Text(vec!["a".into(), "b".into(), "a".into(), "a".into()]),
+                ),
+                (
+                    "value".to_string(),
+                    FeatureData::NullableFloat(vec![Some(1.), Some(2.), Some(3.), None]),
+                ),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+        )
+        .unwrap();
+
+        let aggregation = FeatureAggregation {
+            params: FeatureAggregationParams {
+                group_by: vec!["group".to_string()],
+                aggregations: vec![
+                    ColumnAggregation {
+                        column: "value".to_string(),
+                        function: AggregationFunction::Mean,
+                        output_column: Some("mean".to_string()),
+                    },
+                    ColumnAggregation {
+                        column: "value".to_string(),
+                        function: AggregationFunction::Count,
+                        output_column: None,
+                    },
+                ],
+            },
+            sources: MockFeatureCollectionSource::single(collection)
+                .boxed()
+                .into(),
+        }
+        .boxed();
+
+        let initialized = aggregation
+            .initialize(&MockExecutionContext::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            initialized.result_descriptor().columns,
+            [
+                ("group".to_string(), FeatureDataType::Text),
+                ("mean".to_string(), FeatureDataType::Float),
+                ("value_count".to_string(), FeatureDataType::Int),
+            ]
+            .iter()
+            .cloned()
+            .collect()
+        );
+
+        let processor = match initialized.query_processor() {
+            Ok(TypedVectorQueryProcessor::Data(processor)) => processor,
+            _ => panic!(),
+        };
+
+        let query_rectangle = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (4., 4.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::zero_point_one(),
+        };
+
+        let ctx = MockQueryContext::new(usize::MAX);
+
+        let collections: Vec<DataCollection> = processor
+            .query(query_rectangle, &ctx)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(collections.len(), 1);
+
+        let result = &collections[0];
+
+        assert_eq!(
+            result.time_intervals(),
+            &[
+                TimeInterval::new(0, 3).unwrap(),
+                TimeInterval::new(1, 2).unwrap()
+            ]
+        );
+        assert_eq!(
+            result
+                .data("group")
+                .unwrap()
+                .strings_iter()
+                .collect::<Vec<_>>(),
+            vec!["a".to_string(), "b".to_string()]
+        );
+        assert_eq!(
+            result
+                .data("mean")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(2.), Some(2.)]
+        );
+        assert_eq!(
+            result
+                .data("value_count")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(2.), Some(1.)]
+        );
+    }
+
+    #[tokio::test]
+    async fn non_numeric_aggregation() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1)]).unwrap(),
+            vec![TimeInterval::default()],
+            [("text".to_string(), FeatureData::Text(vec!["a".into()]))]
+                .iter()
+                .cloned()
+                .collect(),
+        )
+        .unwrap();
+
+        let aggregation = FeatureAggregation {
+            params: FeatureAggregationParams {
+                group_by: vec![],
+                aggregations: vec![ColumnAggregation {
+                    column: "text".to_string(),
+                    function: AggregationFunction::Sum,
+                    output_column: None,
+                }],
+            },
+            sources: MockFeatureCollectionSource::single(collection)
+                .boxed()
+                .into(),
+        }
+        .boxed();
+
+        assert!(aggregation
+            .initialize(&MockExecutionContext::default())
+            .await
+            .is_err());
+    }
+}
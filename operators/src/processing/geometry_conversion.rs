@@ -0,0 +1,726 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    BuilderProvider, FeatureCollection, FeatureCollectionInfos, GeoFeatureCollectionRowBuilder,
+    IntoGeometryIterator, MultiLineStringCollection, MultiPointCollection, MultiPolygonCollection,
+    VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, Coordinate2D, FeatureDataType, FeatureDataValue, Geometry, MultiLineString,
+    MultiLineStringAccess, MultiPoint, MultiPointAccess, MultiPolygonAccess, TimeInterval,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::engine::{
+    ExecutionContext, InitializedVectorOperator, Operator, QueryContext, QueryProcessor,
+    SingleVectorSource, TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor,
+    VectorQueryRectangle, VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::Result;
+
+/// An operator that converts the geometries of a vector source into another geometry type
+pub type GeometryConversion = Operator<GeometryConversionParams, SingleVectorSource>;
+
+/// The parameter spec for `GeometryConversion`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GeometryConversionParams {
+    pub conversion: GeometryConversionType,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum GeometryConversionType {
+    /// Converts each line or polygon feature into a multi point of its vertices.
+    /// The closing vertices of polygon rings are not repeated.
+    Vertices,
+    /// Converts each polygon feature into a multi line string of its rings
+    Boundaries,
+    /// Collects points into lines, cf. [`PointsToLines`]
+    Lines(PointsToLines),
+}
+
+/// Connects the points of each group in the order of the `sequence_column` or of the start of their time intervals.
+///
+/// The lines are valid for the union of the time intervals of their points and only keep the `group_column`.
+/// Groups with less than two points are dropped.
+/// The lines are formed of the points in the query rectangle, so they may be cut at its border.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PointsToLines {
+    /// A numeric column that orders the points of a line. Points without a sequence value are dropped.
+    #[serde(default)]
+    pub sequence_column: Option<String>,
+    /// A column whose values identify the lines. If it is missing, all points form a single line.
+    #[serde(default)]
+    pub group_column: Option<String>,
+}
+
+impl GeometryConversionType {
+    fn output_type(&self) -> VectorDataType {
+        match self {
+            GeometryConversionType::Vertices => VectorDataType::MultiPoint,
+            GeometryConversionType::Boundaries | GeometryConversionType::Lines(_) => {
+                VectorDataType::MultiLineString
+            }
+        }
+    }
+
+    fn input_types(&self) -> &'static [VectorDataType] {
+        match self {
+            GeometryConversionType::Vertices => &[
+                VectorDataType::MultiLineString,
+                VectorDataType::MultiPolygon,
+            ],
+            GeometryConversionType::Boundaries => &[VectorDataType::MultiPolygon],
+            GeometryConversionType::Lines(_) => &[VectorDataType::MultiPoint],
+        }
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for GeometryConversion {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let vector_source = self.sources.vector.initialize(context).await?;
+        let source_descriptor = vector_source.result_descriptor();
+
+        let conversion = self.params.conversion;
+        let input_types = conversion.input_types();
+
+        ensure!(
+            input_types.contains(&source_descriptor.data_type),
+            error::InvalidType {
+                expected: input_types
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" or "),
+                found: source_descriptor.data_type.to_string(),
+            }
+        );
+
+        let columns = match &conversion {
+            GeometryConversionType::Vertices | GeometryConversionType::Boundaries => {
+                source_descriptor.columns.clone()
+            }
+            GeometryConversionType::Lines(lines) => {
+                if let Some(sequence_column) = &lines.sequence_column {
+                    let data_type =
+                        source_descriptor
+                            .columns
+                            .get(sequence_column)
+                            .ok_or_else(|| error::Error::ColumnDoesNotExist {
+                                column: sequence_column.clone(),
+                            })?;
+
+                    ensure!(data_type.is_numeric(), error::InvalidFeatureDataType);
+                }
+
+                let mut columns = HashMap::new();
+
+                if let Some(group_column) = &lines.group_column {
+                    let data_type =
+                        source_descriptor.columns.get(group_column).ok_or_else(|| {
+                            error::Error::ColumnDoesNotExist {
+                                column: group_column.clone(),
+                            }
+                        })?;
+
+                    columns.insert(group_column.clone(), *data_type);
+                }
+
+                columns
+            }
+        };
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: conversion.output_type(),
+            spatial_reference: source_descriptor.spatial_reference,
+            columns,
+        };
+
+        Ok(InitializedGeometryConversion {
+            result_descriptor,
+            vector_source,
+            conversion,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedGeometryConversion {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    conversion: GeometryConversionType,
+}
+
+impl InitializedVectorOperator for InitializedGeometryConversion {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let source = self.vector_source.query_processor()?;
+
+        Ok(match (&self.conversion, source) {
+            (
+                GeometryConversionType::Vertices,
+                TypedVectorQueryProcessor::MultiLineString(source),
+            ) => TypedVectorQueryProcessor::MultiPoint(
+                CollectionConversionProcessor {
+                    source,
+                    convert: line_vertices,
+                }
+                .boxed(),
+            ),
+            (GeometryConversionType::Vertices, TypedVectorQueryProcessor::MultiPolygon(source)) => {
+                TypedVectorQueryProcessor::MultiPoint(
+                    CollectionConversionProcessor {
+                        source,
+                        convert: polygon_vertices,
+                    }
+                    .boxed(),
+                )
+            }
+            (
+                GeometryConversionType::Boundaries,
+                TypedVectorQueryProcessor::MultiPolygon(source),
+            ) => TypedVectorQueryProcessor::MultiLineString(
+                CollectionConversionProcessor {
+                    source,
+                    convert: polygon_boundaries,
+                }
+                .boxed(),
+            ),
+            (
+                GeometryConversionType::Lines(lines),
+                TypedVectorQueryProcessor::MultiPoint(source),
+            ) => TypedVectorQueryProcessor::MultiLineString(
+                PointsToLinesProcessor {
+                    source,
+                    params: lines.clone(),
+                    columns: self.result_descriptor.columns.clone(),
+                }
+                .boxed(),
+            ),
+            _ => unreachable!("checked in initialization"),
+        })
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// Converts each collection of the source on its own
+pub struct CollectionConversionProcessor<G, O> {
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    convert: fn(&FeatureCollection<G>) -> Result<FeatureCollection<O>>,
+}
+
+#[async_trait]
+impl<G, O> QueryProcessor for CollectionConversionProcessor<G, O>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+    O: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type Output = FeatureCollection<O>;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let convert = self.convert;
+
+        Ok(self
+            .source
+            .query(query, ctx)
+            .await?
+            .map(move |collection| collection.and_then(|collection| convert(&collection)))
+            .boxed())
+    }
+}
+
+fn line_vertices(collection: &MultiLineStringCollection) -> Result<MultiPointCollection> {
+    let vertices = collection
+        .geometries()
+        .map(|multi_line_string| {
+            MultiPoint::new(
+                multi_line_string
+                    .lines()
+                    .iter()
+                    .flat_map(|line| line.iter().copied())
+                    .collect(),
+            )
+        })
+        .collect::<geoengine_datatypes::util::Result<Vec<_>>>()?;
+
+    Ok(collection.with_geometries(vertices)?)
+}
+
+fn polygon_vertices(collection: &MultiPolygonCollection) -> Result<MultiPointCollection> {
+    let vertices = collection
+        .geometries()
+        .map(|multi_polygon| {
+            MultiPoint::new(
+                multi_polygon
+                    .polygons()
+                    .iter()
+                    .flat_map(|polygon| polygon.iter())
+                    .flat_map(|ring| open_ring(ring).iter().copied())
+                    .collect(),
+            )
+        })
+        .collect::<geoengine_datatypes::util::Result<Vec<_>>>()?;
+
+    Ok(collection.with_geometries(vertices)?)
+}
+
+fn polygon_boundaries(collection: &MultiPolygonCollection) -> Result<MultiLineStringCollection> {
+    let boundaries = collection
+        .geometries()
+        .map(|multi_polygon| {
+            MultiLineString::new(
+                multi_polygon
+                    .polygons()
+                    .iter()
+                    .flat_map(|polygon| polygon.iter().map(|ring| ring.to_vec()))
+                    .collect(),
+            )
+        })
+        .collect::<geoengine_datatypes::util::Result<Vec<_>>>()?;
+
+    Ok(collection.with_geometries(boundaries)?)
+}
+
+/// The vertices of a ring without the closing one
+fn open_ring(ring: &[Coordinate2D]) -> &[Coordinate2D] {
+    match (ring.first(), ring.last()) {
+        (Some(first), Some(last)) if ring.len() > 1 && first == last => &ring[..ring.len() - 1],
+        _ => ring,
+    }
+}
+
+pub struct PointsToLinesProcessor {
+    source: Box<dyn VectorQueryProcessor<VectorType = MultiPointCollection>>,
+    params: PointsToLines,
+    columns: HashMap<String, FeatureDataType>,
+}
+
+#[async_trait]
+impl QueryProcessor for PointsToLinesProcessor {
+    type Output = MultiLineStringCollection;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let tracks = self.source.query(query, ctx).await?.try_fold(
+            Tracks::default(),
+            |mut tracks, collection| async move {
+                tracks.add_collection(&collection, &self.params)?;
+                Ok(tracks)
+            },
+        );
+
+        // the points of a line can span multiple input collections, so the lines are emitted after all points were consumed
+        Ok(
+            stream::once(async move { tracks.await?.into_collection(&self.params, &self.columns) })
+                .boxed(),
+        )
+    }
+}
+
+/// The points of the lines in order of their first point
+#[derive(Debug, Default)]
+struct Tracks {
+    indices: HashMap<Option<String>, usize>,
+    tracks: Vec<Track>,
+}
+
+#[derive(Debug)]
+struct Track {
+    group: Option<FeatureDataValue>,
+    points: Vec<TrackPoint>,
+}
+
+#[derive(Debug)]
+struct TrackPoint {
+    sequence: f64,
+    time: TimeInterval,
+    coordinates: Vec<Coordinate2D>,
+}
+
+impl Tracks {
+    fn add_collection(
+        &mut self,
+        collection: &MultiPointCollection,
+        params: &PointsToLines,
+    ) -> Result<()> {
+        let sequences: Vec<Option<f64>> = match &params.sequence_column {
+            Some(column) => collection.data(column)?.float_options_iter().collect(),
+            None => collection
+                .time_intervals()
+                .iter()
+                .map(|time| Some(time.start().inner() as f64))
+                .collect(),
+        };
+
+        // nulls form their own group instead of joining the group of empty strings
+        let groups: Vec<(Option<String>, Option<FeatureDataValue>)> = match &params.group_column {
+            Some(column) => {
+                let data = collection.data(column)?;
+                data.strings_iter()
+                    .zip(data.nulls())
+                    .enumerate()
+                    .map(|(row, (key, is_null))| {
+                        (
+                            if is_null { None } else { Some(key) },
+                            Some(data.get_unchecked(row)),
+                        )
+                    })
+                    .collect()
+            }
+            None => vec![(None, None); collection.len()],
+        };
+
+        for (((multi_point, time), sequence), (key, group)) in collection
+            .geometries()
+            .zip(collection.time_intervals())
+            .zip(sequences)
+            .zip(groups)
+        {
+            let sequence = match sequence {
+                Some(sequence) => sequence,
+                None => continue,
+            };
+
+            let tracks = &mut self.tracks;
+            let index = *self.indices.entry(key).or_insert_with(|| {
+                tracks.push(Track {
+                    group,
+                    points: Vec::new(),
+                });
+                tracks.len() - 1
+            });
+
+            self.tracks[index].points.push(TrackPoint {
+                sequence,
+                time: *time,
+                coordinates: multi_point.points().to_vec(),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn into_collection(
+        self,
+        params: &PointsToLines,
+        columns: &HashMap<String, FeatureDataType>,
+    ) -> Result<MultiLineStringCollection> {
+        let mut builder = MultiLineStringCollection::builder();
+        for (column, data_type) in columns {
+            builder.add_column(column.clone(), *data_type)?;
+        }
+        let mut builder = builder.finish_header();
+
+        for mut track in self.tracks {
+            // the sort is stable, s.t. points with the same sequence value keep their order
+            track.points.sort_by(|a, b| {
+                a.sequence
+                    .partial_cmp(&b.sequence)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+
+            let coordinates: Vec<Coordinate2D> = track
+                .points
+                .iter()
+                .flat_map(|point| point.coordinates.iter().copied())
+                .collect();
+
+            if coordinates.len() < 2 {
+                continue;
+            }
+
+            let time = track
+                .points
+                .iter()
+                .skip(1)
+                .fold(track.points[0].time, |time, point| time.extend(&point.time));
+
+            builder.push_geometry(MultiLineString::new(vec![coordinates])?)?;
+            builder.push_time_interval(time)?;
+
+            if let (Some(column), Some(group)) = (&params.group_column, track.group) {
+                builder.push_data(column, group)?;
+            }
+
+            builder.finish_row();
+        }
+
+        builder.build().map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::primitives::{FeatureData, MultiPolygon, SpatialResolution};
+
+    async fn convert(
+        source: Box<dyn VectorOperator>,
+        conversion: GeometryConversionType,
+    ) -> (VectorResultDescriptor, TypedVectorQueryProcessor) {
+        let initialized = GeometryConversion {
+            params: GeometryConversionParams { conversion },
+            sources: source.into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .unwrap();
+
+        (
+            initialized.result_descriptor().clone(),
+            initialized.query_processor().unwrap(),
+        )
+    }
+
+    async fn query<G>(
+        processor: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    ) -> Vec<FeatureCollection<G>>
+    where
+        G: Geometry + ArrowTyped + 'static,
+    {
+        processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((-10., -10.).into(), (10., 10.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::new_unchecked(0, 10),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    fn squares() -> MultiPolygonCollection {
+        let mut data = HashMap::new();
+        data.insert("name".to_string(), FeatureData::Text(vec!["a".to_string()]));
+
+        MultiPolygonCollection::from_data(
+            vec![MultiPolygon::new(vec![vec![
+                vec![
+                    (0., 0.).into(),
+                    (4., 0.).into(),
+                    (4., 4.).into(),
+                    (0., 4.).into(),
+                    (0., 0.).into(),
+                ],
+                vec![
+                    (1., 1.).into(),
+                    (2., 1.).into(),
+                    (2., 2.).into(),
+                    (1., 1.).into(),
+                ],
+            ]])
+            .unwrap()],
+            vec![TimeInterval::new_unchecked(0, 1)],
+            data,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn polygons_to_vertices_and_boundaries() {
+        let (descriptor, processor) = convert(
+            MockFeatureCollectionSource::single(squares()).boxed(),
+            GeometryConversionType::Vertices,
+        )
+        .await;
+
+        assert_eq!(descriptor.data_type, VectorDataType::MultiPoint);
+        assert_eq!(descriptor.columns.get("name"), Some(&FeatureDataType::Text));
+
+        let vertices = query(processor.multi_point().unwrap()).await;
+
+        let mut data = HashMap::new();
+        data.insert("name".to_string(), FeatureData::Text(vec!["a".to_string()]));
+
+        assert_eq!(
+            vertices,
+            vec![MultiPointCollection::from_data(
+                MultiPoint::many(vec![vec![
+                    (0., 0.),
+                    (4., 0.),
+                    (4., 4.),
+                    (0., 4.),
+                    (1., 1.),
+                    (2., 1.),
+                    (2., 2.),
+                ]])
+                .unwrap(),
+                vec![TimeInterval::new_unchecked(0, 1)],
+                data.clone(),
+            )
+            .unwrap()]
+        );
+
+        let (descriptor, processor) = convert(
+            MockFeatureCollectionSource::single(squares()).boxed(),
+            GeometryConversionType::Boundaries,
+        )
+        .await;
+
+        assert_eq!(descriptor.data_type, VectorDataType::MultiLineString);
+
+        let boundaries = query(processor.multi_line_string().unwrap()).await;
+
+        assert_eq!(
+            boundaries,
+            vec![MultiLineStringCollection::from_data(
+                vec![MultiLineString::new(vec![
+                    vec![
+                        (0., 0.).into(),
+                        (4., 0.).into(),
+                        (4., 4.).into(),
+                        (0., 4.).into(),
+                        (0., 0.).into(),
+                    ],
+                    vec![
+                        (1., 1.).into(),
+                        (2., 1.).into(),
+                        (2., 2.).into(),
+                        (1., 1.).into(),
+                    ],
+                ])
+                .unwrap()],
+                vec![TimeInterval::new_unchecked(0, 1)],
+                data,
+            )
+            .unwrap()]
+        );
+    }
+
+    #[tokio::test]
+    async fn points_to_lines() {
+        let mut data = HashMap::new();
+        data.insert(
+            "track".to_string(),
+            FeatureData::Text(vec![
+                "a".to_string(),
+                "b".to_string(),
+                "a".to_string(),
+                "b".to_string(),
+                "a".to_string(),
+            ]),
+        );
+        data.insert(
+            "speed".to_string(),
+            FeatureData::Float(vec![1., 2., 3., 4., 5.]),
+        );
+
+        let points = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(2., 2.), (5., 5.), (0., 0.), (6., 6.), (1., 1.)]).unwrap(),
+            vec![
+                TimeInterval::new_unchecked(4, 5),
+                TimeInterval::new_unchecked(0, 1),
+                TimeInterval::new_unchecked(0, 1),
+                TimeInterval::new_unchecked(1, 2),
+                TimeInterval::new_unchecked(2, 3),
+            ],
+            data,
+        )
+        .unwrap();
+
+        let (descriptor, processor) = convert(
+            MockFeatureCollectionSource::single(points).boxed(),
+            GeometryConversionType::Lines(PointsToLines {
+                sequence_column: None,
+                group_column: Some("track".to_string()),
+            }),
+        )
+        .await;
+
+        assert_eq!(descriptor.data_type, VectorDataType::MultiLineString);
+        assert_eq!(
+            descriptor.columns,
+            [("track".to_string(), FeatureDataType::Text)]
+                .iter()
+                .cloned()
+                .collect()
+        );
+
+        let lines = query(processor.multi_line_string().unwrap()).await;
+
+        let mut data = HashMap::new();
+        data.insert(
+            "track".to_string(),
+            FeatureData::Text(vec!["a".to_string(), "b".to_string()]),
+        );
+
+        assert_eq!(
+            lines,
+            vec![MultiLineStringCollection::from_data(
+                vec![
+                    MultiLineString::new(vec![vec![
+                        (0., 0.).into(),
+                        (1., 1.).into(),
+                        (2., 2.).into(),
+                    ]])
+                    .unwrap(),
+                    MultiLineString::new(vec![vec![(5., 5.).into(), (6., 6.).into()]]).unwrap(),
+                ],
+                vec![
+                    TimeInterval::new_unchecked(0, 5),
+                    TimeInterval::new_unchecked(0, 2),
+                ],
+                data,
+            )
+            .unwrap()]
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_inputs() {
+        let result = GeometryConversion {
+            params: GeometryConversionParams {
+                conversion: GeometryConversionType::Boundaries,
+            },
+            sources: MockFeatureCollectionSource::single(
+                MultiPointCollection::from_data(
+                    MultiPoint::many(vec![(0., 0.)]).unwrap(),
+                    vec![TimeInterval::default()],
+                    HashMap::new(),
+                )
+                .unwrap(),
+            )
+            .boxed()
+            .into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await;
+
+        assert!(matches!(result, Err(error::Error::InvalidType { .. })));
+    }
+}
@@ -0,0 +1,547 @@
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    FeatureCollectionInfos, IntoGeometryIterator, MultiPointCollection, VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, BoundingBox2D, Coordinate2D, Measurement, MultiPointAccess,
+    SpatialPartition2D,
+};
+use geoengine_datatypes::raster::{
+    EmptyGrid, Grid2D, Pixel, RasterDataType, RasterTile2D, TileInformation, TilingSpecification,
+};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use rstar::{PointDistance, RTree, RTreeObject, AABB};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::call_generic_raster_processor;
+use crate::engine::{
+    ExecutionContext, InitializedRasterOperator, InitializedVectorOperator, Operator, QueryContext,
+    QueryProcessor, RasterOperator, RasterQueryRectangle, RasterResultDescriptor,
+    SingleVectorSource, TypedRasterQueryProcessor, VectorQueryProcessor, VectorQueryRectangle,
+};
+use crate::error;
+use crate::util::Result;
+
+/// An operator that interpolates a numeric `column` of points into a continuous raster
+/// using inverse distance weighting (IDW).
+///
+/// Each pixel is the mean of the values of its neighboring points, weighted by `1 / distance^power`.
+/// The neighbors of a pixel are the `maxNeighbors` nearest points within `maxDistance` of its center.
+/// Pixels without neighbors are no data.
+pub type IdwInterpolation = Operator<IdwInterpolationParams, SingleVectorSource>;
+
+/// The parameter spec for `IdwInterpolation`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IdwInterpolationParams {
+    /// The numeric column to interpolate
+    pub column: String,
+    /// The exponent of the distance in the weights. Larger values increase the influence of nearby points.
+    #[serde(default = "default_power")]
+    pub power: f64,
+    /// The search radius in units of the spatial reference. All points are considered if it is `None`.
+    pub max_distance: Option<f64>,
+    /// The maximum number of points that are considered for a pixel. All points are considered if it is `None`.
+    pub max_neighbors: Option<usize>,
+    pub output_type: RasterDataType,
+    pub output_no_data_value: f64,
+}
+
+fn default_power() -> f64 {
+    2.
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for IdwInterpolation {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let params = self.params;
+
+        ensure!(
+            params.power.is_finite() && params.power > 0.,
+            error::InvalidOperatorSpec {
+                reason: "the power must be positive".to_string()
+            }
+        );
+        ensure!(
+            params
+                .max_distance
+                .map_or(true, |max_distance| max_distance.is_finite()
+                    && max_distance > 0.),
+            error::InvalidOperatorSpec {
+                reason: "the max distance must be positive".to_string()
+            }
+        );
+        ensure!(
+            params.max_neighbors != Some(0),
+            error::InvalidOperatorSpec {
+                reason: "the max neighbors must be positive".to_string()
+            }
+        );
+
+        let vector_source = self.sources.vector.initialize(context).await?;
+        let source_descriptor = vector_source.result_descriptor();
+
+        ensure!(
+            source_descriptor.data_type == VectorDataType::MultiPoint,
+            error::InvalidType {
+                expected: VectorDataType::MultiPoint.to_string(),
+                found: source_descriptor.data_type.to_string(),
+            }
+        );
+
+        let data_type = source_descriptor
+            .columns
+            .get(&params.column)
+            .ok_or_else(|| error::Error::ColumnDoesNotExist {
+                column: params.column.clone(),
+            })?;
+
+        ensure!(data_type.is_numeric(), error::InvalidFeatureDataType);
+
+        // without a search radius, all points of the spatial reference's area are neighbors
+        let point_bounds = match (
+            params.max_distance,
+            Option::<SpatialReference>::from(source_descriptor.spatial_reference),
+        ) {
+            (None, Some(spatial_reference)) => Some(spatial_reference.area_of_use_projected()?),
+            _ => None,
+        };
+
+        let result_descriptor = RasterResultDescriptor {
+            data_type: params.output_type,
+            spatial_reference: source_descriptor.spatial_reference,
+            measurement: Measurement::continuous(params.column.clone(), None),
+            no_data_value: Some(params.output_no_data_value),
+            bands: Vec::new(),
+        };
+
+        Ok(InitializedIdwInterpolation {
+            result_descriptor,
+            vector_source,
+            tiling_specification: context.tiling_specification(),
+            point_bounds,
+            params,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedIdwInterpolation {
+    result_descriptor: RasterResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    tiling_specification: TilingSpecification,
+    point_bounds: Option<BoundingBox2D>,
+    params: IdwInterpolationParams,
+}
+
+impl InitializedRasterOperator for InitializedIdwInterpolation {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let source = self
+            .vector_source
+            .query_processor()?
+            .multi_point()
+            .expect("checked in initialization");
+
+        Ok(call_generic_raster_processor!(
+            self.params.output_type,
+            IdwInterpolationProcessor {
+                source,
+                params: self.params.clone(),
+                tiling_specification: self.tiling_specification,
+                point_bounds: self.point_bounds,
+                pixel_type: PhantomData,
+            }
+            .boxed()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct IdwInterpolationProcessor<T> {
+    source: Box<dyn VectorQueryProcessor<VectorType = MultiPointCollection>>,
+    params: IdwInterpolationParams,
+    tiling_specification: TilingSpecification,
+    point_bounds: Option<BoundingBox2D>,
+    pixel_type: PhantomData<T>,
+}
+
+impl<T> IdwInterpolationProcessor<T>
+where
+    T: Pixel,
+{
+    /// The bounds of the points that can be neighbors of the pixels of `spatial_bounds`
+    fn point_query_bounds(&self, spatial_bounds: SpatialPartition2D) -> Result<BoundingBox2D> {
+        if let Some(point_bounds) = self.point_bounds {
+            return Ok(point_bounds);
+        }
+
+        let max_distance = self.params.max_distance.unwrap_or_default();
+        let lower_left = spatial_bounds.lower_left();
+        let upper_right = spatial_bounds.upper_right();
+
+        BoundingBox2D::new(
+            (lower_left.x - max_distance, lower_left.y - max_distance).into(),
+            (upper_right.x + max_distance, upper_right.y + max_distance).into(),
+        )
+        .map_err(Into::into)
+    }
+
+    fn interpolate_tile(
+        &self,
+        points: &RTree<InterpolationPoint>,
+        tile_info: TileInformation,
+        query: &RasterQueryRectangle,
+    ) -> RasterTile2D<T> {
+        let no_data_value = T::from_(self.params.output_no_data_value);
+
+        if points.size() == 0 {
+            return RasterTile2D::new_with_tile_info(
+                query.time_interval,
+                tile_info,
+                EmptyGrid::new(tile_info.tile_size_in_pixels, no_data_value).into(),
+            );
+        }
+
+        let tile_geo_transform = tile_info.tile_geo_transform();
+        let [height, width] = tile_info.tile_size_in_pixels.shape_array;
+
+        let mut data = Vec::with_capacity(height * width);
+        for y in 0..height {
+            for x in 0..width {
+                let coordinate = tile_geo_transform
+                    .grid_idx_to_center_coordinate_2d([y as isize, x as isize].into());
+
+                data.push(
+                    self.interpolate(points, coordinate)
+                        .map_or(no_data_value, T::from_),
+                );
+            }
+        }
+
+        let grid = Grid2D::new(tile_info.tile_size_in_pixels, data, Some(no_data_value))
+            .expect("the data must match the tile size");
+
+        RasterTile2D::new_with_tile_info(query.time_interval, tile_info, grid.into())
+    }
+
+    /// The weighted mean of the neighbors of `coordinate` or `None` if there are no neighbors
+    fn interpolate(
+        &self,
+        points: &RTree<InterpolationPoint>,
+        coordinate: Coordinate2D,
+    ) -> Option<f64> {
+        let max_distance = self.params.max_distance.unwrap_or(f64::INFINITY);
+        let max_neighbors = self.params.max_neighbors.unwrap_or(usize::MAX);
+
+        let mut weight_sum = 0.;
+        let mut value_sum = 0.;
+
+        let neighbors = points
+            .nearest_neighbor_iter(&[coordinate.x, coordinate.y])
+            .map(|point| {
+                (
+                    point.distance_2(&[coordinate.x, coordinate.y]).sqrt(),
+                    point,
+                )
+            })
+            .take_while(|(distance, _)| *distance <= max_distance)
+            .take(max_neighbors);
+
+        for (distance, point) in neighbors {
+            // the pixel center coincides with a point
+            if distance == 0. {
+                return Some(point.value);
+            }
+
+            let weight = distance.powf(-self.params.power);
+            weight_sum += weight;
+            value_sum += weight * point.value;
+        }
+
+        if weight_sum > 0. {
+            Some(value_sum / weight_sum)
+        } else {
+            None
+        }
+    }
+}
+
+#[async_trait]
+impl<T> QueryProcessor for IdwInterpolationProcessor<T>
+where
+    T: Pixel,
+{
+    type Output = RasterTile2D<T>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let point_query = VectorQueryRectangle {
+            spatial_bounds: self.point_query_bounds(query.spatial_bounds)?,
+            time_interval: query.time_interval,
+            spatial_resolution: query.spatial_resolution,
+        };
+
+        // every tile depends on all points of the query, so they are collected upfront
+        let points = self
+            .source
+            .query(point_query, ctx)
+            .await?
+            .try_fold(Vec::new(), |mut points, collection| async move {
+                InterpolationPoint::extend_from_collection(
+                    &mut points,
+                    &collection,
+                    &self.params.column,
+                )?;
+                Ok(points)
+            })
+            .await?;
+
+        let points = RTree::bulk_load(points);
+
+        let tiling_strategy = self
+            .tiling_specification
+            .strategy(query.spatial_resolution.x, -query.spatial_resolution.y);
+
+        Ok(
+            stream::iter(tiling_strategy.tile_information_iterator(query.spatial_bounds))
+                .map(move |tile_info| Ok(self.interpolate_tile(&points, tile_info, &query)))
+                .boxed(),
+        )
+    }
+}
+
+/// A point of the spatial index with the value of the interpolated column
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct InterpolationPoint {
+    coordinate: [f64; 2],
+    value: f64,
+}
+
+impl InterpolationPoint {
+    /// Adds the points of the `collection` that have a value in the `column`
+    fn extend_from_collection(
+        points: &mut Vec<InterpolationPoint>,
+        collection: &MultiPointCollection,
+        column: &str,
+    ) -> Result<()> {
+        let data = collection.data(column)?;
+
+        for (multi_point, value) in collection.geometries().zip(data.float_options_iter()) {
+            let value = match value {
+                Some(value) => value,
+                None => continue,
+            };
+
+            points.extend(
+                multi_point
+                    .points()
+                    .iter()
+                    .map(|coordinate| InterpolationPoint {
+                        coordinate: [coordinate.x, coordinate.y],
+                        value,
+                    }),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl RTreeObject for InterpolationPoint {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        AABB::from_point(self.coordinate)
+    }
+}
+
+impl PointDistance for InterpolationPoint {
+    fn distance_2(&self, point: &[f64; 2]) -> f64 {
+        let dx = self.coordinate[0] - point[0];
+        let dy = self.coordinate[1] - point[1];
+        dx * dx + dy * dy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{
+        MockExecutionContext, MockQueryContext, RasterQueryProcessor, VectorOperator,
+    };
+    use crate::mock::MockFeatureCollectionSource;
+    use float_cmp::approx_eq;
+    use geoengine_datatypes::primitives::{
+        FeatureData, MultiPoint, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::raster::GridShape;
+
+    fn points() -> MultiPointCollection {
+        MultiPointCollection::from_data(
+            MultiPoint::many(vec![vec![(0.5, 3.5)], vec![(3.5, 3.5)], vec![(3.5, 0.5)]]).unwrap(),
+            vec![TimeInterval::default(); 3],
+            [(
+                "value".to_string(),
+                FeatureData::NullableFloat(vec![Some(10.), Some(20.), None]),
+            )]
+            .iter()
+            .cloned()
+            .collect(),
+        )
+        .unwrap()
+    }
+
+    async fn interpolate(params: IdwInterpolationParams) -> Vec<f64> {
+        let operator = IdwInterpolation {
+            params,
+            sources: SingleVectorSource {
+                vector: MockFeatureCollectionSource::single(points()).boxed(),
+            },
+        }
+        .boxed();
+
+        let mut exe_ctx = MockExecutionContext::default();
+        exe_ctx.tiling_specification.tile_size_in_pixels = GridShape {
+            shape_array: [4, 4],
+        };
+
+        let processor = operator
+            .initialize(&exe_ctx)
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .get_f64()
+            .unwrap();
+
+        let tiles: Vec<RasterTile2D<f64>> = processor
+            .raster_query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new_unchecked(
+                        (0., 4.).into(),
+                        (4., 0.).into(),
+                    ),
+                    time_interval: TimeInterval::new_instant(0).unwrap(),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(tiles.len(), 1);
+
+        tiles[0]
+            .grid_array
+            .clone()
+            .into_materialized_grid()
+            .data
+            .into_vec()
+    }
+
+    #[tokio::test]
+    #[allow(clippy::float_cmp)]
+    async fn it_interpolates() {
+        let values = interpolate(IdwInterpolationParams {
+            column: "value".to_string(),
+            power: 2.,
+            max_distance: None,
+            max_neighbors: None,
+            output_type: RasterDataType::F64,
+            output_no_data_value: -1.,
+        })
+        .await;
+
+        // the pixels of the points have their values
+        assert_eq!(values[0], 10.);
+        assert_eq!(values[3], 20.);
+
+        // weights 1 and 1/4 for the distances 1 and 2
+        assert!(approx_eq!(f64, values[1], 12.));
+        assert!(approx_eq!(f64, values[2], 18.));
+
+        // weights 1/18 and 1/9 since points without values are ignored
+        assert!(approx_eq!(f64, values[15], 50. / 3.));
+    }
+
+    #[tokio::test]
+    async fn it_restricts_neighbors() {
+        let values = interpolate(IdwInterpolationParams {
+            column: "value".to_string(),
+            power: 2.,
+            max_distance: Some(1.5),
+            max_neighbors: Some(1),
+            output_type: RasterDataType::F64,
+            output_no_data_value: -1.,
+        })
+        .await;
+
+        assert_eq!(
+            values,
+            vec![
+                10., 10., 20., 20., //
+                10., 10., 20., 20., //
+                -1., -1., -1., -1., //
+                -1., -1., -1., -1., //
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_checks_the_column() {
+        let operator = IdwInterpolation {
+            params: IdwInterpolationParams {
+                column: "foo".to_string(),
+                power: 2.,
+                max_distance: None,
+                max_neighbors: None,
+                output_type: RasterDataType::F64,
+                output_no_data_value: -1.,
+            },
+            sources: SingleVectorSource {
+                vector: MockFeatureCollectionSource::single(points()).boxed(),
+            },
+        }
+        .boxed();
+
+        assert!(matches!(
+            operator.initialize(&MockExecutionContext::default()).await,
+            Err(error::Error::ColumnDoesNotExist { column }) if column == "foo"
+        ));
+    }
+
+    #[test]
+    fn it_deserializes_defaults() {
+        let params: IdwInterpolationParams = serde_json::from_value(serde_json::json!({
+            "column": "value",
+            "maxDistance": 10.0,
+            "outputType": "F32",
+            "outputNoDataValue": 0.0
+        }))
+        .unwrap();
+
+        assert_eq!(params.power, 2.);
+        assert_eq!(params.max_distance, Some(10.));
+        assert_eq!(params.max_neighbors, None);
+    }
+}
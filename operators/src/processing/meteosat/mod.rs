@@ -1 +1,3 @@
 mod radiance;
+
+pub use radiance::{Radiance, RadianceParams};
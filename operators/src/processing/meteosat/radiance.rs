@@ -15,6 +15,7 @@ use num_traits::AsPrimitive;
 use std::convert::TryFrom;
 
 use async_trait::async_trait;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 // Output type is always f32
@@ -23,7 +24,7 @@ use RasterDataType::F32 as RasterOut;
 use TypedRasterQueryProcessor::F32 as QueryProcessorOut;
 const OUT_NO_DATA_VALUE: PixelOut = PixelOut::NAN;
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RadianceParams {}
 
@@ -65,6 +66,7 @@ impl RasterOperator for Radiance {
                 unit: Some("W·m^(-2)·sr^(-1)·cm^(-1)".into()),
             },
             no_data_value: Some(f64::from(OUT_NO_DATA_VALUE)),
+            bands: Vec::new(),
         };
 
         let initialized_operator = InitializedRadiance {
@@ -429,6 +431,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    bands: Vec::new(),
                 },
             },
         }
@@ -482,6 +485,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    bands: Vec::new(),
                 },
             },
         }
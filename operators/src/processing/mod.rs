@@ -1,12 +1,93 @@
+mod band_selection;
+mod change_detection;
 mod column_range_filter;
 mod expression;
+mod feature_aggregation;
+mod geometry_conversion;
+mod idw_interpolation;
 mod map_query;
 mod meteosat;
+mod neighborhood_aggregate;
+mod outlier_detection;
 mod point_in_polygon;
+mod raster_clip;
+mod raster_lookup;
+mod raster_mosaic;
+mod raster_sampling;
+mod raster_stacker;
 mod raster_vector_join;
+mod representative_points;
 mod reprojection;
+mod temporal_anomaly;
 mod temporal_raster_aggregation;
+mod temporal_smoothing;
+mod text_processing;
+mod time_derivation;
+mod time_series_pivot;
+mod time_series_resample;
+mod time_slicing;
+mod time_synchronization;
+mod vector_generalization;
 mod vector_join;
+mod visual_point_clustering;
 
-pub use point_in_polygon::PointInPolygonTester;
+pub use band_selection::{BandSelection, BandSelectionParams, BandSelector};
+pub use change_detection::{
+    ChangeDetection, ChangeDetectionMethod, ChangeDetectionParams, ChangeDetectionSources,
+    ChangeThresholds,
+};
+pub use column_range_filter::{ColumnRangeFilter, ColumnRangeFilterParams};
+pub use expression::{
+    Expression, ExpressionBackend, ExpressionParams, ExpressionProgram, ExpressionSources,
+    ExpressionTree, PixelInputs,
+};
+pub(crate) use feature_aggregation::Accumulator;
+pub use feature_aggregation::{
+    AggregationFunction, ColumnAggregation, FeatureAggregation, FeatureAggregationParams,
+};
+pub use geometry_conversion::{
+    GeometryConversion, GeometryConversionParams, GeometryConversionType, PointsToLines,
+};
+pub use idw_interpolation::{IdwInterpolation, IdwInterpolationParams};
+pub use meteosat::{Radiance, RadianceParams};
+pub use neighborhood_aggregate::{
+    BorderHandling, Neighborhood, NeighborhoodAggregate, NeighborhoodAggregateParams,
+};
+pub use outlier_detection::{
+    OutlierDetection, OutlierDetectionParams, OutlierMethod, RasterOutlierDetection,
+    RasterOutlierDetectionParams,
+};
+pub use point_in_polygon::{
+    PointInPolygonFilter, PointInPolygonFilterParams, PointInPolygonTester,
+};
+pub use raster_clip::{RasterClip, RasterClipParams, RasterClipSources};
+pub use raster_lookup::{RasterLookup, RasterLookupParams, RasterLookupSources};
+pub use raster_mosaic::{MosaicRule, RasterMosaic, RasterMosaicParams};
+pub use raster_sampling::{RasterSampling, RasterSamplingParams};
+pub use raster_stacker::{RasterStacker, RasterStackerBand, RasterStackerParams};
+pub use raster_vector_join::{RasterVectorJoin, RasterVectorJoinParams};
+pub use representative_points::{
+    RepresentativePointMethod, RepresentativePoints, RepresentativePointsParams,
+};
 pub use reprojection::{Reprojection, ReprojectionParams};
+pub use temporal_anomaly::{ClimatologyGrouping, TemporalAnomaly, TemporalAnomalyParams};
+pub use temporal_raster_aggregation::{
+    TemporalRasterAggregation, TemporalRasterAggregationParameters,
+};
+pub use temporal_smoothing::{TemporalSmoothing, TemporalSmoothingMethod, TemporalSmoothingParams};
+pub use text_processing::{TextFunction, TextOperation, TextProcessing, TextProcessingParams};
+pub use time_derivation::{
+    DerivedTimeColumn, TimeComponent, TimeDerivation, TimeDerivationParams, TimeDerivationSource,
+};
+pub use time_series_pivot::{PivotColumns, TimeBucket, TimeSeriesPivot, TimeSeriesPivotParams};
+pub use time_series_resample::{TimeSeriesResample, TimeSeriesResampleParams};
+pub use time_slicing::{TimeSlicing, TimeSlicingParams};
+pub use time_synchronization::{
+    TimeSynchronization, TimeSynchronizationMethod, TimeSynchronizationParams,
+    TimeSynchronizationSources,
+};
+pub use vector_generalization::{
+    GeneralizationLevel, Materialization, VectorGeneralization, VectorGeneralizationParams,
+};
+pub use vector_join::{VectorJoin, VectorJoinParams};
+pub use visual_point_clustering::{VisualPointClustering, VisualPointClusteringParams};
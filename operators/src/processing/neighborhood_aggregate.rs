@@ -0,0 +1,583 @@
+use crate::adapters::{SubQueryTileAggregator, TileSubQueryWithMargin};
+use crate::engine::{
+    ExecutionContext, InitializedRasterOperator, Operator, QueryContext, QueryProcessor,
+    RasterOperator, RasterQueryProcessor, RasterQueryRectangle, RasterResultDescriptor,
+    SingleRasterSource, TypedRasterQueryProcessor,
+};
+use crate::error;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use geoengine_datatypes::primitives::SpatialPartition2D;
+use geoengine_datatypes::raster::{Grid2D, NoDataValue, Pixel, RasterTile2D, TilingSpecification};
+use num_traits::AsPrimitive;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+/// The `NeighborhoodAggregate` operator applies a moving window to each pixel of its input raster,
+/// e.g., to smooth it. The windows of pixels at tile borders include the pixels of the neighboring tiles.
+///
+/// The output has the data type of the input, so the aggregated values are truncated for integer rasters.
+pub type NeighborhoodAggregate = Operator<NeighborhoodAggregateParams, SingleRasterSource>;
+
+/// The parameters of the `NeighborhoodAggregate` operator
+/// * `neighborhood` is the kernel that weights the pixels of the window
+/// * `border_handling` defines how windows that contain no data, e.g., beyond the border of the raster, are aggregated
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NeighborhoodAggregateParams {
+    pub neighborhood: Neighborhood,
+    pub border_handling: BorderHandling,
+}
+
+/// The kernel of a `NeighborhoodAggregate`.
+/// Its `dimensions` are the number of rows and columns of the window, which must be odd s.t. the window is centered on the pixel.
+/// Windows may have at most 255 rows and columns.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type")]
+pub enum Neighborhood {
+    /// The mean of the pixels in the window
+    #[serde(rename_all = "camelCase")]
+    Mean { dimensions: [usize; 2] },
+    /// The mean of the pixels in the window weighted by a Gaussian function of their distance to the center
+    #[serde(rename_all = "camelCase")]
+    Gaussian {
+        dimensions: [usize; 2],
+        standard_deviation: f64,
+    },
+    /// The sum of the pixels in the window multiplied by the given weights, which are given row by row
+    #[serde(rename_all = "camelCase")]
+    WeightsMatrix { weights: Vec<Vec<f64>> },
+}
+
+/// How the `NeighborhoodAggregate` treats no data pixels in a window.
+/// A no data pixel in the center of the window always results in no data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum BorderHandling {
+    /// A pixel is no data if its window contains no data
+    NoData,
+    /// The no data pixels of a window are left out, i.e., the means only consider the remaining pixels
+    /// and the weighted sums treat no data as `0`
+    Ignore,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for NeighborhoodAggregate {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let kernel = Kernel::try_from(&self.params.neighborhood)?;
+
+        let source = self.sources.raster.initialize(context).await?;
+
+        let in_desc = source.result_descriptor();
+        ensure!(
+            in_desc.number_of_bands() == 1,
+            error::InvalidOperatorSpec {
+                reason: "multiband rasters cannot be aggregated by neighborhood".to_string()
+            }
+        );
+
+        let no_data_value = in_desc.no_data_value.unwrap_or(0.); // TODO: add option to force a no_data_value
+
+        let result_descriptor = RasterResultDescriptor {
+            no_data_value: Some(no_data_value),
+            ..in_desc.clone()
+        };
+
+        Ok(InitializedNeighborhoodAggregate {
+            result_descriptor,
+            source,
+            kernel: Arc::new(kernel),
+            border_handling: self.params.border_handling,
+            tiling_specification: context.tiling_specification(),
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedNeighborhoodAggregate {
+    result_descriptor: RasterResultDescriptor,
+    source: Box<dyn InitializedRasterOperator>,
+    kernel: Arc<Kernel>,
+    border_handling: BorderHandling,
+    tiling_specification: TilingSpecification,
+}
+
+impl InitializedRasterOperator for InitializedNeighborhoodAggregate {
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let source_processor = self.source.query_processor()?;
+        let no_data_value = self.result_descriptor.no_data_value.unwrap_or_default();
+
+        Ok(call_on_generic_raster_processor!(
+            source_processor, p => NeighborhoodAggregateProcessor {
+                source: p,
+                kernel: self.kernel.clone(),
+                border_handling: self.border_handling,
+                tiling_specification: self.tiling_specification,
+                no_data_value: no_data_value.as_(),
+            }
+            .boxed()
+            .into()
+        ))
+    }
+}
+
+pub struct NeighborhoodAggregateProcessor<Q, P>
+where
+    Q: RasterQueryProcessor<RasterType = P>,
+    P: Pixel,
+{
+    source: Q,
+    kernel: Arc<Kernel>,
+    border_handling: BorderHandling,
+    tiling_specification: TilingSpecification,
+    no_data_value: P,
+}
+
+#[async_trait]
+impl<Q, P> QueryProcessor for NeighborhoodAggregateProcessor<Q, P>
+where
+    Q: QueryProcessor<Output = RasterTile2D<P>, SpatialBounds = SpatialPartition2D>,
+    P: Pixel,
+{
+    type Output = RasterTile2D<P>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let kernel = self.kernel.clone();
+        let border_handling = self.border_handling;
+        let no_data_value = self.no_data_value;
+
+        // each tile is queried with the pixels of its neighbors that the windows at its border need
+        Ok(TileSubQueryWithMargin {
+            margin: self.kernel.margin(),
+            no_data_value,
+            compute_fn: move |grid: &Grid2D<P>| kernel.apply(grid, no_data_value, border_handling),
+        }
+        .into_raster_overlap_adapter(&self.source, query, ctx, self.tiling_specification)
+        .boxed())
+    }
+}
+
+/// The maximum number of rows and columns of a window.
+/// It bounds the margin of the sub queries and the work per pixel.
+const MAX_KERNEL_DIMENSION: usize = 255;
+
+/// The weights of a window
+#[derive(Debug, Clone, PartialEq)]
+struct Kernel {
+    rows: usize,
+    columns: usize,
+    /// row by row
+    weights: Vec<f64>,
+    /// Whether the weighted sum is divided by the sum of the weights of the considered pixels
+    normalize: bool,
+}
+
+impl TryFrom<&Neighborhood> for Kernel {
+    type Error = error::Error;
+
+    fn try_from(neighborhood: &Neighborhood) -> Result<Self> {
+        let kernel = match neighborhood {
+            Neighborhood::Mean {
+                dimensions: [rows, columns],
+            } => Kernel {
+                rows: *rows,
+                columns: *columns,
+                weights: vec![1.; Kernel::checked_size(*rows, *columns)?],
+                normalize: true,
+            },
+            Neighborhood::Gaussian {
+                dimensions: [rows, columns],
+                standard_deviation,
+            } => {
+                ensure!(
+                    *standard_deviation > 0.,
+                    error::InvalidNeighborhood {
+                        reason: "the standard deviation must be positive"
+                    }
+                );
+
+                let size = Kernel::checked_size(*rows, *columns)?;
+
+                let (center_y, center_x) = ((rows / 2) as f64, (columns / 2) as f64);
+                let weights = (0..size)
+                    .map(|i| {
+                        let dy = (i / columns) as f64 - center_y;
+                        let dx = (i % columns) as f64 - center_x;
+                        (-(dx * dx + dy * dy) / (2. * standard_deviation * standard_deviation))
+                            .exp()
+                    })
+                    .collect();
+
+                Kernel {
+                    rows: *rows,
+                    columns: *columns,
+                    weights,
+                    normalize: true,
+                }
+            }
+            Neighborhood::WeightsMatrix { weights } => {
+                let columns = weights.first().map_or(0, Vec::len);
+
+                ensure!(
+                    weights.iter().all(|row| row.len() == columns),
+                    error::InvalidNeighborhood {
+                        reason: "all rows of the weights must have the same length"
+                    }
+                );
+
+                Kernel::checked_size(weights.len(), columns)?;
+
+                Kernel {
+                    rows: weights.len(),
+                    columns,
+                    weights: weights.iter().flatten().copied().collect(),
+                    normalize: false,
+                }
+            }
+        };
+
+        ensure!(
+            kernel.rows % 2 == 1 && kernel.columns % 2 == 1,
+            error::InvalidNeighborhood {
+                reason: "the numbers of rows and columns must be odd"
+            }
+        );
+
+        Ok(kernel)
+    }
+}
+
+impl Kernel {
+    /// The number of pixels of a window with the given dimensions if it does not exceed the maximum
+    fn checked_size(rows: usize, columns: usize) -> Result<usize> {
+        let too_large = || error::Error::NeighborhoodTooLarge {
+            rows,
+            columns,
+            max_dimension: MAX_KERNEL_DIMENSION,
+        };
+
+        if rows > MAX_KERNEL_DIMENSION || columns > MAX_KERNEL_DIMENSION {
+            return Err(too_large());
+        }
+
+        rows.checked_mul(columns).ok_or_else(too_large)
+    }
+
+    /// The number of pixels the window reaches beyond its center
+    fn margin(&self) -> usize {
+        (self.rows / 2).max(self.columns / 2)
+    }
+
+    /// Aggregates the windows of all pixels of the `grid`.
+    /// The results at the border of the `grid` lack the pixels beyond it.
+    fn apply<T: Pixel>(
+        &self,
+        grid: &Grid2D<T>,
+        no_data_value: T,
+        border_handling: BorderHandling,
+    ) -> Grid2D<T> {
+        let [height, width] = grid.shape.into_inner();
+
+        let values: Vec<Option<f64>> = grid
+            .data
+            .iter()
+            .map(|&value| {
+                if grid.is_no_data(value) {
+                    None
+                } else {
+                    Some(value.as_())
+                }
+            })
+            .collect();
+
+        let mut data = Vec::with_capacity(values.len());
+
+        for y in 0..height {
+            for x in 0..width {
+                let value = self.aggregate(&values, [height, width], [y, x], border_handling);
+                data.push(value.map_or(no_data_value, T::from_));
+            }
+        }
+
+        Grid2D::new(grid.shape, data, Some(no_data_value))
+            .expect("the output has the shape of the input")
+    }
+
+    #[allow(clippy::float_cmp)]
+    fn aggregate(
+        &self,
+        values: &[Option<f64>],
+        [height, width]: [usize; 2],
+        [y, x]: [usize; 2],
+        border_handling: BorderHandling,
+    ) -> Option<f64> {
+        // the center must not be no data
+        values[y * width + x]?;
+
+        let mut sum = 0.;
+        let mut weight_sum = 0.;
+
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                let pixel_y = (y + row).checked_sub(self.rows / 2).filter(|&y| y < height);
+                let pixel_x = (x + column)
+                    .checked_sub(self.columns / 2)
+                    .filter(|&x| x < width);
+
+                let value = match (pixel_y, pixel_x) {
+                    (Some(pixel_y), Some(pixel_x)) => values[pixel_y * width + pixel_x],
+                    _ => None,
+                };
+
+                match (value, border_handling) {
+                    (Some(value), _) => {
+                        let weight = self.weights[row * self.columns + column];
+                        sum += weight * value;
+                        weight_sum += weight;
+                    }
+                    (None, BorderHandling::NoData) => return None,
+                    (None, BorderHandling::Ignore) => {}
+                }
+            }
+        }
+
+        if !self.normalize {
+            return Some(sum);
+        }
+
+        (weight_sum != 0.).then(|| sum / weight_sum)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::primitives::{Measurement, SpatialResolution, TimeInterval};
+    use geoengine_datatypes::raster::{GridShape, RasterDataType};
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+
+    #[test]
+    fn deserialize_params() {
+        let params: NeighborhoodAggregateParams = serde_json::from_str(
+            r#"{
+                "neighborhood": {
+                    "type": "gaussian",
+                    "dimensions": [3, 5],
+                    "standardDeviation": 1.5
+                },
+                "borderHandling": "ignore"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            params,
+            NeighborhoodAggregateParams {
+                neighborhood: Neighborhood::Gaussian {
+                    dimensions: [3, 5],
+                    standard_deviation: 1.5
+                },
+                border_handling: BorderHandling::Ignore,
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_neighborhoods() {
+        for neighborhood in &[
+            Neighborhood::Mean { dimensions: [3, 4] },
+            Neighborhood::Mean { dimensions: [0, 1] },
+            Neighborhood::Gaussian {
+                dimensions: [3, 3],
+                standard_deviation: 0.,
+            },
+            Neighborhood::WeightsMatrix { weights: vec![] },
+            Neighborhood::WeightsMatrix {
+                weights: vec![vec![1., 2., 3.], vec![1.]],
+            },
+        ] {
+            assert!(Kernel::try_from(neighborhood).is_err());
+        }
+    }
+
+    #[test]
+    fn too_large_neighborhoods() {
+        for neighborhood in &[
+            Neighborhood::Mean {
+                dimensions: [usize::MAX, 3],
+            },
+            Neighborhood::Mean {
+                dimensions: [3, MAX_KERNEL_DIMENSION + 2],
+            },
+            Neighborhood::Gaussian {
+                dimensions: [usize::MAX, usize::MAX],
+                standard_deviation: 1.,
+            },
+            Neighborhood::WeightsMatrix {
+                weights: vec![vec![1.]; MAX_KERNEL_DIMENSION + 2],
+            },
+        ] {
+            assert!(matches!(
+                Kernel::try_from(neighborhood),
+                Err(error::Error::NeighborhoodTooLarge {
+                    max_dimension: MAX_KERNEL_DIMENSION,
+                    ..
+                })
+            ));
+        }
+    }
+
+    #[test]
+    fn gaussian_weights() {
+        let kernel = Kernel::try_from(&Neighborhood::Gaussian {
+            dimensions: [1, 3],
+            standard_deviation: 1.,
+        })
+        .unwrap();
+
+        let side = (-0.5_f64).exp();
+
+        assert_eq!(kernel.weights, vec![side, 1., side]);
+        assert_eq!(kernel.margin(), 1);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn weights_matrix() {
+        let kernel = Kernel::try_from(&Neighborhood::WeightsMatrix {
+            weights: vec![vec![0., -1., 0.], vec![-1., 4., -1.], vec![0., -1., 0.]],
+        })
+        .unwrap();
+
+        let grid = Grid2D::new(
+            [3, 3].into(),
+            vec![1., 2., 3., 4., 9., 6., 7., 8., 5.],
+            Some(-1.),
+        )
+        .unwrap();
+
+        let result = kernel.apply(&grid, -1., BorderHandling::Ignore);
+        assert_eq!(result.data, vec![-2., -5., 4., -1., 16., 7., 16., 11., 6.]);
+
+        let result = kernel.apply(&grid, -1., BorderHandling::NoData);
+        assert_eq!(
+            result.data,
+            vec![-1., -1., -1., -1., 16., -1., -1., -1., -1.]
+        );
+    }
+
+    #[tokio::test]
+    async fn mean_across_tiles() {
+        let no_data_value = 0;
+
+        // the raster consists of two tiles next to each other
+        let data = vec![
+            RasterTile2D::new(
+                TimeInterval::new_unchecked(0, 5),
+                [-1, 0].into(),
+                Default::default(),
+                Grid2D::new([2, 2].into(), vec![2, 4, 4, 6], Some(no_data_value))
+                    .unwrap()
+                    .into(),
+            ),
+            RasterTile2D::new(
+                TimeInterval::new_unchecked(0, 5),
+                [-1, 1].into(),
+                Default::default(),
+                Grid2D::new([2, 2].into(), vec![6, 8, 8, 10], Some(no_data_value))
+                    .unwrap()
+                    .into(),
+            ),
+        ];
+
+        for (border_handling, expected) in [
+            (
+                BorderHandling::NoData,
+                vec![vec![0, 4, 0, 6], vec![6, 0, 8, 0]],
+            ),
+            (
+                BorderHandling::Ignore,
+                vec![vec![3, 4, 5, 6], vec![6, 7, 8, 9]],
+            ),
+        ] {
+            let operator = NeighborhoodAggregate {
+                params: NeighborhoodAggregateParams {
+                    neighborhood: Neighborhood::Mean { dimensions: [1, 3] },
+                    border_handling,
+                },
+                sources: SingleRasterSource {
+                    raster: MockRasterSource {
+                        params: MockRasterSourceParams {
+                            data: data.clone(),
+                            result_descriptor: RasterResultDescriptor {
+                                data_type: RasterDataType::U8,
+                                spatial_reference: SpatialReference::epsg_4326().into(),
+                                measurement: Measurement::Unitless,
+                                no_data_value: Some(no_data_value.into()),
+                                bands: Vec::new(),
+                            },
+                        },
+                    }
+                    .boxed(),
+                },
+            };
+
+            let mut exe_ctx = MockExecutionContext::default();
+            exe_ctx.tiling_specification.tile_size_in_pixels = GridShape {
+                shape_array: [2, 2],
+            };
+
+            let processor = operator
+                .boxed()
+                .initialize(&exe_ctx)
+                .await
+                .unwrap()
+                .query_processor()
+                .unwrap()
+                .get_u8()
+                .unwrap();
+
+            let query_ctx = MockQueryContext::new(1024 * 1024);
+            let result = processor
+                .query(
+                    RasterQueryRectangle {
+                        spatial_bounds: SpatialPartition2D::new_unchecked(
+                            (0., 2.).into(),
+                            (4., 0.).into(),
+                        ),
+                        time_interval: TimeInterval::new_unchecked(0, 5),
+                        spatial_resolution: SpatialResolution::one(),
+                    },
+                    &query_ctx,
+                )
+                .await
+                .unwrap()
+                .map(|tile| tile.unwrap().into_materialized_tile().grid_array.data)
+                .collect::<Vec<_>>()
+                .await;
+
+            assert_eq!(result, expected);
+        }
+    }
+}
@@ -0,0 +1,283 @@
+mod raster;
+mod vector;
+
+pub use raster::{RasterOutlierDetection, RasterOutlierDetectionParams};
+pub use vector::{OutlierDetection, OutlierDetectionParams};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::error;
+use crate::util::Result;
+
+/// The scale factor that makes the median absolute deviation a consistent estimator of the
+/// standard deviation of normally distributed values
+const MAD_SCALE: f64 = 1.4826;
+
+/// How to decide whether a value of a series is an outlier
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum OutlierMethod {
+    /// Values that are more than `threshold` standard deviations away from the mean of the series
+    #[serde(rename_all = "camelCase")]
+    ZScore {
+        #[serde(default = "default_threshold")]
+        threshold: f64,
+    },
+    /// Values that are more than `factor` times the interquartile range below the first
+    /// or above the third quartile of the series
+    #[serde(rename_all = "camelCase")]
+    Iqr {
+        #[serde(default = "default_iqr_factor")]
+        factor: f64,
+    },
+    /// Values that are more than `threshold` scaled median absolute deviations away from the median
+    /// of their neighbors in time, i.e., the `window` previous and next values of the series
+    #[serde(rename_all = "camelCase")]
+    Hampel {
+        #[serde(default = "default_hampel_window")]
+        window: usize,
+        #[serde(default = "default_threshold")]
+        threshold: f64,
+    },
+}
+
+fn default_threshold() -> f64 {
+    3.
+}
+
+fn default_iqr_factor() -> f64 {
+    1.5
+}
+
+fn default_hampel_window() -> usize {
+    3
+}
+
+impl OutlierMethod {
+    fn validate(self) -> Result<()> {
+        let (name, value) = match self {
+            OutlierMethod::ZScore { threshold } | OutlierMethod::Hampel { threshold, .. } => {
+                ("threshold", threshold)
+            }
+            OutlierMethod::Iqr { factor } => ("factor", factor),
+        };
+
+        ensure!(
+            value.is_finite() && value >= 0.,
+            error::InvalidOperatorSpec {
+                reason: format!("the {} of the outlier detection must not be negative", name)
+            }
+        );
+
+        if let OutlierMethod::Hampel { window, .. } = self {
+            ensure!(
+                window > 0,
+                error::InvalidOperatorSpec {
+                    reason: "the window of the Hampel filter must not be empty".to_string()
+                }
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Flags the outliers of a series of values that is ordered by time.
+    /// Missing and `NaN` values are neither outliers nor considered for the others.
+    fn detect(self, series: &[Option<f64>]) -> Vec<Option<bool>> {
+        let series: Vec<Option<f64>> = series
+            .iter()
+            .map(|value| value.filter(|v| !v.is_nan()))
+            .collect();
+        let values: Vec<f64> = series.iter().flatten().copied().collect();
+
+        let mut flags = match self {
+            OutlierMethod::ZScore { threshold } => z_score_outliers(&values, threshold),
+            OutlierMethod::Iqr { factor } => iqr_outliers(&values, factor),
+            OutlierMethod::Hampel { window, threshold } => {
+                hampel_outliers(&values, window, threshold)
+            }
+        }
+        .into_iter();
+
+        series
+            .iter()
+            .map(|value| value.and_then(|_| flags.next()))
+            .collect()
+    }
+}
+
+fn z_score_outliers(values: &[f64], threshold: f64) -> Vec<bool> {
+    let count = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / count;
+    let std_dev = (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / count).sqrt();
+
+    values
+        .iter()
+        .map(|v| (v - mean).abs() > threshold * std_dev)
+        .collect()
+}
+
+fn iqr_outliers(values: &[f64], factor: f64) -> Vec<bool> {
+    if values.is_empty() {
+        return Vec::new();
+    }
+
+    let mut sorted = values.to_vec();
+    sort(&mut sorted);
+
+    let q1 = quantile(&sorted, 0.25);
+    let q3 = quantile(&sorted, 0.75);
+    let fence = factor * (q3 - q1);
+
+    values
+        .iter()
+        .map(|&v| v < q1 - fence || v > q3 + fence)
+        .collect()
+}
+
+fn hampel_outliers(values: &[f64], window: usize, threshold: f64) -> Vec<bool> {
+    (0..values.len())
+        .map(|i| {
+            let mut neighborhood =
+                values[i.saturating_sub(window)..(i + window + 1).min(values.len())].to_vec();
+            let median = median(&mut neighborhood);
+
+            let mut deviations: Vec<f64> =
+                neighborhood.iter().map(|v| (v - median).abs()).collect();
+            let mad = median(&mut deviations);
+
+            (values[i] - median).abs() > threshold * MAD_SCALE * mad
+        })
+        .collect()
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    sort(values);
+    quantile(values, 0.5)
+}
+
+fn sort(values: &mut [f64]) {
+    values.sort_unstable_by(|a, b| a.partial_cmp(b).expect("series must not contain `NaN`s"));
+}
+
+/// Computes the quantile `q` of non-empty, sorted `values`
+/// by linear interpolation between the closest ranks
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let rank = q * (sorted.len() - 1) as f64;
+    let lower = sorted[rank.floor() as usize];
+    let upper = sorted[rank.ceil() as usize];
+
+    lower + (upper - lower) * rank.fract()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn z_score() {
+        let method = OutlierMethod::ZScore { threshold: 2. };
+
+        assert_eq!(
+            method.detect(&[
+                Some(1.),
+                Some(1.),
+                None,
+                Some(1.),
+                Some(1.),
+                Some(1.),
+                Some(10.)
+            ]),
+            vec![
+                Some(false),
+                Some(false),
+                None,
+                Some(false),
+                Some(false),
+                Some(false),
+                Some(true)
+            ]
+        );
+    }
+
+    #[test]
+    fn iqr() {
+        let method = OutlierMethod::Iqr { factor: 1.5 };
+
+        assert_eq!(
+            method.detect(&[
+                Some(-20.),
+                Some(1.),
+                Some(2.),
+                Some(3.),
+                Some(4.),
+                Some(50.)
+            ]),
+            vec![
+                Some(true),
+                Some(false),
+                Some(false),
+                Some(false),
+                Some(false),
+                Some(true)
+            ]
+        );
+    }
+
+    #[test]
+    fn hampel() {
+        let method = OutlierMethod::Hampel {
+            window: 2,
+            threshold: 3.,
+        };
+
+        // a trend is not an outlier, but a spike is
+        assert_eq!(
+            method.detect(&[
+                Some(1.),
+                Some(2.),
+                Some(3.),
+                Some(30.),
+                Some(5.),
+                Some(6.),
+                Some(7.)
+            ]),
+            vec![
+                Some(false),
+                Some(false),
+                Some(false),
+                Some(true),
+                Some(false),
+                Some(false),
+                Some(false)
+            ]
+        );
+    }
+
+    #[test]
+    fn validation() {
+        assert!(OutlierMethod::ZScore { threshold: -1. }.validate().is_err());
+        assert!(OutlierMethod::Hampel {
+            window: 0,
+            threshold: 3.
+        }
+        .validate()
+        .is_err());
+        assert!(OutlierMethod::Iqr { factor: 1.5 }.validate().is_ok());
+    }
+
+    #[test]
+    fn defaults() {
+        let method: OutlierMethod = serde_json::from_str(r#"{"type": "hampel"}"#).unwrap();
+
+        assert_eq!(
+            method,
+            OutlierMethod::Hampel {
+                window: 3,
+                threshold: 3.
+            }
+        );
+    }
+}
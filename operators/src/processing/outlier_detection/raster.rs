@@ -0,0 +1,325 @@
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::primitives::{Measurement, SpatialPartition2D};
+use geoengine_datatypes::raster::{
+    Grid2D, GridOrEmpty, GridShapeAccess, GridSize, NoDataValue, Pixel, RasterDataType,
+    RasterTile2D, TileInformation, TilingSpecification,
+};
+use num_traits::AsPrimitive;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::engine::{
+    ExecutionContext, InitializedRasterOperator, Operator, QueryContext, QueryProcessor,
+    RasterOperator, RasterQueryProcessor, RasterQueryRectangle, RasterResultDescriptor,
+    SingleRasterSource, TypedRasterQueryProcessor,
+};
+use crate::error;
+use crate::util::Result;
+
+use super::OutlierMethod;
+
+const MASK_NO_DATA: u8 = 255;
+
+/// An operator that flags outliers in the time series of each pixel of a raster.
+///
+/// The output is a mask with `1` for outliers, `0` for all other pixels
+/// and no data where the input has no data.
+/// All time steps of a query form the series. The source is queried once per tile position,
+/// s.t. only the series of a single tile position is buffered. Thus, the output is ordered by
+/// tile position and then by time.
+pub type RasterOutlierDetection = Operator<RasterOutlierDetectionParams, SingleRasterSource>;
+
+/// The parameter spec for `RasterOutlierDetection`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RasterOutlierDetectionParams {
+    pub method: OutlierMethod,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for RasterOutlierDetection {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        self.params.method.validate()?;
+
+        let source = self.sources.raster.initialize(context).await?;
+
+        ensure!(
+            source.result_descriptor().number_of_bands() == 1,
+            error::InvalidOperatorSpec {
+                reason: "multiband rasters cannot be checked for outliers".to_string()
+            }
+        );
+
+        let result_descriptor = RasterResultDescriptor {
+            data_type: RasterDataType::U8,
+            measurement: Measurement::Classification {
+                measurement: "outlier".to_string(),
+                classes: [(0, "no outlier".to_string()), (1, "outlier".to_string())]
+                    .iter()
+                    .cloned()
+                    .collect(),
+            },
+            no_data_value: Some(f64::from(MASK_NO_DATA)),
+            ..source.result_descriptor().clone()
+        };
+
+        Ok(InitializedRasterOutlierDetection {
+            result_descriptor,
+            source,
+            method: self.params.method,
+            tiling_specification: context.tiling_specification(),
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedRasterOutlierDetection {
+    result_descriptor: RasterResultDescriptor,
+    source: Box<dyn InitializedRasterOperator>,
+    method: OutlierMethod,
+    tiling_specification: TilingSpecification,
+}
+
+impl InitializedRasterOperator for InitializedRasterOutlierDetection {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let source = self.source.query_processor()?;
+
+        Ok(TypedRasterQueryProcessor::U8(
+            call_on_generic_raster_processor!(source, source => RasterOutlierDetectionProcessor {
+                source,
+                method: self.method,
+                tiling_specification: self.tiling_specification,
+            }
+            .boxed()),
+        ))
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct RasterOutlierDetectionProcessor<T> {
+    source: Box<dyn RasterQueryProcessor<RasterType = T>>,
+    method: OutlierMethod,
+    tiling_specification: TilingSpecification,
+}
+
+impl<T> RasterOutlierDetectionProcessor<T>
+where
+    T: Pixel,
+{
+    /// Queries the time series of a single tile position and flags its outliers
+    async fn detect_outliers(
+        &self,
+        tile_info: TileInformation,
+        query: RasterQueryRectangle,
+        ctx: &dyn QueryContext,
+    ) -> Result<Vec<Result<RasterTile2D<u8>>>> {
+        let tile_query = RasterQueryRectangle {
+            spatial_bounds: tile_info.spatial_partition(),
+            ..query
+        };
+
+        let mut tiles: Vec<RasterTile2D<T>> = self
+            .source
+            .query(tile_query, ctx)
+            .await?
+            .try_filter(|tile| {
+                futures::future::ready(tile.tile_position == tile_info.global_tile_position)
+            })
+            .try_collect()
+            .await?;
+
+        tiles.sort_by_key(|tile| tile.time.start());
+
+        let number_of_pixels = tiles
+            .first()
+            .map_or(0, |tile| tile.grid_array.grid_shape().number_of_elements());
+
+        let mut masks: Vec<Vec<u8>> = vec![vec![MASK_NO_DATA; number_of_pixels]; tiles.len()];
+
+        for pixel in 0..number_of_pixels {
+            let values: Vec<Option<f64>> =
+                tiles.iter().map(|tile| pixel_value(tile, pixel)).collect();
+
+            for (mask, flag) in masks.iter_mut().zip(self.method.detect(&values)) {
+                if let Some(flag) = flag {
+                    mask[pixel] = u8::from(flag);
+                }
+            }
+        }
+
+        Ok(tiles
+            .iter()
+            .zip(masks)
+            .map(|(tile, mask)| mask_tile(tile, mask))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl<T> QueryProcessor for RasterOutlierDetectionProcessor<T>
+where
+    T: Pixel,
+{
+    type Output = RasterTile2D<u8>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let tiling_strategy = self
+            .tiling_specification
+            .strategy(query.spatial_resolution.x, -query.spatial_resolution.y);
+
+        let stream = stream::iter(tiling_strategy.tile_information_iterator(query.spatial_bounds))
+            .then(move |tile_info| self.detect_outliers(tile_info, query, ctx))
+            .map_ok(stream::iter)
+            .try_flatten();
+
+        Ok(stream.boxed())
+    }
+}
+
+fn mask_tile<T: Pixel>(tile: &RasterTile2D<T>, mask: Vec<u8>) -> Result<RasterTile2D<u8>> {
+    let grid = Grid2D::new(tile.grid_array.grid_shape(), mask, Some(MASK_NO_DATA))?;
+
+    Ok(RasterTile2D::new_with_tile_info(
+        tile.time,
+        tile.tile_information(),
+        grid.into(),
+    ))
+}
+
+fn pixel_value<T: Pixel>(tile: &RasterTile2D<T>, pixel: usize) -> Option<f64> {
+    match &tile.grid_array {
+        GridOrEmpty::Grid(grid) => {
+            let value = grid.data[pixel];
+
+            if grid.is_no_data(value) {
+                None
+            } else {
+                Some(value.as_())
+            }
+        }
+        GridOrEmpty::Empty(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::primitives::{SpatialResolution, TimeInterval};
+    use geoengine_datatypes::raster::{EmptyGrid2D, TileInformation};
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+
+    #[tokio::test]
+    async fn pixel_time_series() {
+        let tile_info = TileInformation {
+            global_geo_transform: Default::default(),
+            global_tile_position: [0, 0].into(),
+            tile_size_in_pixels: [1, 2].into(),
+        };
+
+        let data: Vec<RasterTile2D<i16>> = [
+            Some([1, 5]),
+            Some([2, 5]),
+            Some([100, 0]),
+            None,
+            Some([3, 6]),
+            Some([2, 4]),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(time, values)| {
+            let grid = match values {
+                Some(values) => Grid2D::new([1, 2].into(), values.to_vec(), Some(0))
+                    .unwrap()
+                    .into(),
+                None => EmptyGrid2D::new([1, 2].into(), 0).into(),
+            };
+
+            RasterTile2D::new_with_tile_info(
+                TimeInterval::new_unchecked(time as i64, time as i64 + 1),
+                tile_info,
+                grid,
+            )
+        })
+        .collect();
+
+        let operator = RasterOutlierDetection {
+            params: RasterOutlierDetectionParams {
+                method: OutlierMethod::Iqr { factor: 1.5 },
+            },
+            sources: MockRasterSource {
+                params: MockRasterSourceParams {
+                    data,
+                    result_descriptor: RasterResultDescriptor {
+                        data_type: RasterDataType::I16,
+                        spatial_reference: SpatialReference::epsg_4326().into(),
+                        measurement: Measurement::Unitless,
+                        no_data_value: Some(0.),
+                        bands: Vec::new(),
+                    },
+                },
+            }
+            .boxed()
+            .into(),
+        }
+        .boxed();
+
+        let execution_context = MockExecutionContext {
+            tiling_specification: TilingSpecification::new((0., 0.).into(), [1, 2].into()),
+            ..Default::default()
+        };
+
+        let initialized = operator.initialize(&execution_context).await.unwrap();
+
+        assert_eq!(
+            initialized.result_descriptor().no_data_value,
+            Some(f64::from(MASK_NO_DATA))
+        );
+
+        let processor = initialized.query_processor().unwrap().get_u8().unwrap();
+
+        let query = RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new_unchecked((0., 0.).into(), (2., -1.).into()),
+            time_interval: TimeInterval::new_unchecked(0, 6),
+            spatial_resolution: SpatialResolution::one(),
+        };
+        let ctx = MockQueryContext::default();
+
+        let masks: Vec<Vec<u8>> = processor
+            .query(query, &ctx)
+            .await
+            .unwrap()
+            .map_ok(|tile| tile.grid_array.into_materialized_grid().data.into_vec())
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            masks,
+            vec![
+                vec![0, 0],
+                vec![0, 0],
+                vec![1, 255],
+                vec![255, 255],
+                vec![0, 0],
+                vec![0, 0],
+            ]
+        );
+    }
+}
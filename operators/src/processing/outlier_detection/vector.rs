@@ -0,0 +1,385 @@
+use crate::engine::{
+    ExecutionContext, InitializedVectorOperator, Operator, QueryContext, QueryProcessor,
+    SingleVectorSource, TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor,
+    VectorQueryRectangle, VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionInfos, FeatureCollectionModifications,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, FeatureData, FeatureDataType, Geometry, TimeInstance,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::collections::HashMap;
+
+use super::OutlierMethod;
+
+/// An operator that flags outliers in a numeric column.
+///
+/// The output column is a category with `1` for outliers and `0` for all other features.
+/// It is null if the value or the id of a feature is null.
+pub type OutlierDetection = Operator<OutlierDetectionParams, SingleVectorSource>;
+
+/// The parameter spec for `OutlierDetection`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OutlierDetectionParams {
+    pub column: String,
+    pub method: OutlierMethod,
+    /// Detects the outliers separately for each series of features with the same id,
+    /// e.g., per sensor.
+    /// Otherwise, all features form a single series.
+    #[serde(default)]
+    pub id_column: Option<String>,
+    #[serde(default = "default_output_column")]
+    pub output_column: String,
+}
+
+fn default_output_column() -> String {
+    "outlier".to_string()
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for OutlierDetection {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        self.params.method.validate()?;
+
+        let vector_source = self.sources.vector.initialize(context).await?;
+
+        let mut columns = vector_source.result_descriptor().columns.clone();
+
+        let data_type =
+            columns
+                .get(&self.params.column)
+                .ok_or_else(|| error::Error::ColumnDoesNotExist {
+                    column: self.params.column.clone(),
+                })?;
+        ensure!(data_type.is_numeric(), error::InvalidFeatureDataType);
+
+        if let Some(id_column) = &self.params.id_column {
+            let id_type =
+                columns
+                    .get(id_column)
+                    .ok_or_else(|| error::Error::ColumnDoesNotExist {
+                        column: id_column.clone(),
+                    })?;
+            ensure!(
+                *id_type != FeatureDataType::Bytes,
+                error::InvalidFeatureDataType
+            );
+        }
+
+        ensure!(
+            columns
+                .insert(self.params.output_column.clone(), FeatureDataType::Category)
+                .is_none(),
+            error::InvalidOperatorSpec {
+                reason: format!(
+                    "output column `{}` already exists",
+                    self.params.output_column
+                )
+            }
+        );
+
+        let result_descriptor = VectorResultDescriptor {
+            columns,
+            ..vector_source.result_descriptor().clone()
+        };
+
+        Ok(InitializedOutlierDetection {
+            result_descriptor,
+            vector_source,
+            params: self.params,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedOutlierDetection {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    params: OutlierDetectionParams,
+}
+
+impl InitializedVectorOperator for InitializedOutlierDetection {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        Ok(map_typed_query_processor!(
+            self.vector_source.query_processor()?,
+            source => OutlierDetectionProcessor {
+                source,
+                params: self.params.clone(),
+            }.boxed()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct OutlierDetectionProcessor<G> {
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    params: OutlierDetectionParams,
+}
+
+/// The position of a feature in the query result
+struct FeatureIndex {
+    collection: usize,
+    row: usize,
+    start: TimeInstance,
+    value: Option<f64>,
+}
+
+#[async_trait]
+impl<G> QueryProcessor for OutlierDetectionProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type Output = FeatureCollection<G>;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        // the statistics of a series depend on all of its values, so the whole input is required
+        let collections: Vec<FeatureCollection<G>> =
+            self.source.query(query, ctx).await?.try_collect().await?;
+
+        let mut series: HashMap<Option<String>, Vec<FeatureIndex>> = HashMap::new();
+        let mut flags: Vec<Vec<Option<u8>>> = Vec::with_capacity(collections.len());
+
+        for (collection_index, collection) in collections.iter().enumerate() {
+            flags.push(vec![None; collection.len()]);
+
+            let ids: Vec<Option<String>> = match &self.params.id_column {
+                Some(id_column) => {
+                    let ids = collection.data(id_column)?;
+                    ids.strings_iter()
+                        .zip(ids.nulls())
+                        .map(|(id, is_null)| if is_null { None } else { Some(id) })
+                        .collect()
+                }
+                None => vec![Some(String::new()); collection.len()],
+            };
+
+            let values = collection.data(&self.params.column)?.float_options_iter();
+
+            for (row, ((id, value), time)) in ids
+                .into_iter()
+                .zip(values)
+                .zip(collection.time_intervals())
+                .enumerate()
+            {
+                if id.is_none() {
+                    continue;
+                }
+
+                series.entry(id).or_default().push(FeatureIndex {
+                    collection: collection_index,
+                    row,
+                    start: time.start(),
+                    value,
+                });
+            }
+        }
+
+        for mut features in series.into_values() {
+            features.sort_by_key(|feature| feature.start);
+
+            let values: Vec<Option<f64>> = features.iter().map(|feature| feature.value).collect();
+
+            for (feature, flag) in features.iter().zip(self.params.method.detect(&values)) {
+                flags[feature.collection][feature.row] = flag.map(u8::from);
+            }
+        }
+
+        let output_column = self.params.output_column.as_str();
+        let results = collections
+            .into_iter()
+            .zip(flags)
+            .map(move |(collection, flags)| {
+                collection
+                    .add_column(output_column, FeatureData::NullableCategory(flags))
+                    .map_err(Into::into)
+            });
+
+        Ok(stream::iter(results).boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::{DataCollection, MultiPointCollection};
+    use geoengine_datatypes::primitives::{
+        MultiPoint, NoGeometry, SpatialResolution, TimeInterval,
+    };
+
+    #[tokio::test]
+    async fn outliers_per_series() {
+        let times = [0, 1, 2, 3, 4, 5, 0, 1, 2, 3, 4, 5]
+            .iter()
+            .map(|&t| TimeInterval::new_instant(t).unwrap())
+            .collect::<Vec<_>>();
+
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1); 12]).unwrap(),
+            times,
+            [
+                (
+                    "sensor".to_string(),
+                    FeatureData::NullableText(vec![
+                        Some("a".into()),
+                        Some("a".into()),
+                        Some("a".into()),
+                        Some("a".into()),
+                        Some("a".into()),
+                        Some("a".into()),
+                        Some("b".into()),
+                        Some("b".into()),
+                        Some("b".into()),
+                        Some("b".into()),
+                        Some("b".into()),
+                        None,
+                    ]),
+                ),
+                (
+                    "temperature".to_string(),
+                    FeatureData::NullableFloat(vec![
+                        Some(10.),
+                        Some(11.),
+                        Some(12.),
+                        Some(40.),
+                        Some(14.),
+                        Some(15.),
+                        Some(40.),
+                        Some(41.),
+                        None,
+                        Some(39.),
+                        Some(40.),
+                        Some(100.),
+                    ]),
+                ),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+        )
+        .unwrap();
+
+        let operator = OutlierDetection {
+            params: OutlierDetectionParams {
+                column: "temperature".to_string(),
+                method: OutlierMethod::Hampel {
+                    window: 2,
+                    threshold: 3.,
+                },
+                id_column: Some("sensor".to_string()),
+                output_column: "outlier".to_string(),
+            },
+            sources: MockFeatureCollectionSource::single(collection.clone())
+                .boxed()
+                .into(),
+        }
+        .boxed();
+
+        let initialized = operator
+            .initialize(&MockExecutionContext::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            initialized.result_descriptor().columns.get("outlier"),
+            Some(&FeatureDataType::Category)
+        );
+
+        let processor = initialized
+            .query_processor()
+            .unwrap()
+            .multi_point()
+            .unwrap();
+
+        let query_rectangle = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (4., 4.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::zero_point_one(),
+        };
+        let ctx = MockQueryContext::new(usize::MAX);
+
+        let result: Vec<MultiPointCollection> = processor
+            .query(query_rectangle, &ctx)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0],
+            collection
+                .add_column(
+                    "outlier",
+                    FeatureData::NullableCategory(vec![
+                        Some(0),
+                        Some(0),
+                        Some(0),
+                        Some(1),
+                        Some(0),
+                        Some(0),
+                        Some(0),
+                        Some(0),
+                        None,
+                        Some(0),
+                        Some(0),
+                        None,
+                    ])
+                )
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn existing_output_column() {
+        let operator = OutlierDetection {
+            params: OutlierDetectionParams {
+                column: "foo".to_string(),
+                method: OutlierMethod::ZScore { threshold: 3. },
+                id_column: None,
+                output_column: "foo".to_string(),
+            },
+            sources: MockFeatureCollectionSource::single(
+                DataCollection::from_slices(
+                    &[] as &[NoGeometry],
+                    &[TimeInterval::default(); 2],
+                    &[("foo", FeatureData::Float(vec![1., 2.]))],
+                )
+                .unwrap(),
+            )
+            .boxed()
+            .into(),
+        }
+        .boxed();
+
+        assert!(operator
+            .initialize(&MockExecutionContext::default())
+            .await
+            .is_err());
+    }
+}
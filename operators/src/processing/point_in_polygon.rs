@@ -1,14 +1,19 @@
 use futures::stream::BoxStream;
-use futures::{StreamExt, TryStreamExt};
+use futures::{try_join, StreamExt, TryStreamExt};
 use geoengine_datatypes::dataset::DatasetId;
+use rstar::{RTree, RTreeObject, AABB};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use snafu::ensure;
 
 use geoengine_datatypes::collections::{
-    FeatureCollectionInfos, FeatureCollectionModifications, GeometryCollection,
-    MultiPointCollection, MultiPolygonCollection, VectorDataType,
+    BuilderProvider, FeatureCollectionInfos, FeatureCollectionModifications,
+    GeoFeatureCollectionRowBuilder, GeometryCollection, GeometryRandomAccess, MultiPointCollection,
+    MultiPolygonCollection, VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    Coordinate2D, FeatureDataType, FeatureDataValue, MultiPoint, TimeInterval,
 };
-use geoengine_datatypes::primitives::{Coordinate2D, TimeInterval};
 
 use crate::adapters::FeatureCollectionChunkMerger;
 use crate::engine::{
@@ -18,17 +23,40 @@ use crate::engine::{
 use crate::engine::{OperatorDatasets, QueryProcessor};
 use crate::error;
 use crate::util::Result;
-use arrow::array::BooleanArray;
 use async_trait::async_trait;
 
 /// The point in polygon filter requires two inputs in the following order:
 /// 1. a `MultiPointCollection` source
 /// 2. a `MultiPolygonCollection` source
-/// Then, it filters the `MultiPolygonCollection`s so that only those features are retained that are in any polygon.
+/// Then, it filters the `MultiPointCollection`s so that only those features are retained that are in any polygon.
+/// Alternatively, it drops those features and retains the ones outside of all polygons.
 pub type PointInPolygonFilter = Operator<PointInPolygonFilterParams, PointInPolygonFilterSource>;
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct PointInPolygonFilterParams {}
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PointInPolygonFilterParams {
+    #[serde(default)]
+    pub mode: PointInPolygonFilterMode,
+    /// Columns of the polygons, e.g., an id, that are attached to the retained points.
+    /// A point gets the values of the first polygon that contains it.
+    /// This is only possible in the `keep` mode.
+    #[serde(default)]
+    pub attach_columns: Vec<String>,
+}
+
+/// What to do with points that are in any polygon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum PointInPolygonFilterMode {
+    Keep,
+    Drop,
+}
+
+impl Default for PointInPolygonFilterMode {
+    fn default() -> Self {
+        Self::Keep
+    }
+}
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct PointInPolygonFilterSource {
@@ -50,8 +78,10 @@ impl VectorOperator for PointInPolygonFilter {
         self: Box<Self>,
         context: &dyn ExecutionContext,
     ) -> Result<Box<dyn InitializedVectorOperator>> {
-        let points = self.sources.points.initialize(context).await?;
-        let polygons = self.sources.polygons.initialize(context).await?;
+        let (points, polygons) = try_join!(
+            self.sources.points.initialize(context),
+            self.sources.polygons.initialize(context)
+        )?;
 
         ensure!(
             points.result_descriptor().data_type == VectorDataType::MultiPoint,
@@ -68,10 +98,47 @@ impl VectorOperator for PointInPolygonFilter {
             }
         );
 
+        ensure!(
+            self.params.attach_columns.is_empty()
+                || self.params.mode == PointInPolygonFilterMode::Keep,
+            error::InvalidOperatorSpec {
+                reason: "Polygon columns can only be attached to the points that are kept"
+                    .to_string(),
+            }
+        );
+
+        let mut attach_columns = Vec::with_capacity(self.params.attach_columns.len());
+        for column in &self.params.attach_columns {
+            let data_type = *polygons
+                .result_descriptor()
+                .columns
+                .get(column)
+                .ok_or_else(|| error::Error::ColumnDoesNotExist {
+                    column: column.clone(),
+                })?;
+
+            ensure!(
+                !points.result_descriptor().columns.contains_key(column),
+                error::InvalidOperatorSpec {
+                    reason: format!("The points already have a column `{}`", column),
+                }
+            );
+
+            attach_columns.push((column.clone(), data_type));
+        }
+
+        let result_descriptor = points.result_descriptor().map_columns(|columns| {
+            let mut columns = columns.clone();
+            columns.extend(attach_columns.iter().cloned());
+            columns
+        });
+
         let initialized_operator = InitializedPointInPolygonFilter {
-            result_descriptor: points.result_descriptor().clone(),
+            result_descriptor,
             points,
             polygons,
+            mode: self.params.mode,
+            attach_columns,
         };
 
         Ok(initialized_operator.boxed())
@@ -82,6 +149,8 @@ pub struct InitializedPointInPolygonFilter {
     points: Box<dyn InitializedVectorOperator>,
     polygons: Box<dyn InitializedVectorOperator>,
     result_descriptor: VectorResultDescriptor,
+    mode: PointInPolygonFilterMode,
+    attach_columns: Vec<(String, FeatureDataType)>,
 }
 
 impl InitializedVectorOperator for InitializedPointInPolygonFilter {
@@ -99,7 +168,13 @@ impl InitializedVectorOperator for InitializedPointInPolygonFilter {
             .expect("checked in `PointInPolygonFilter` constructor");
 
         Ok(TypedVectorQueryProcessor::MultiPoint(
-            PointInPolygonFilterProcessor::new(point_processor, polygon_processor).boxed(),
+            PointInPolygonFilterProcessor::new(
+                point_processor,
+                polygon_processor,
+                self.mode,
+                self.attach_columns.clone(),
+            )
+            .boxed(),
         ))
     }
 
@@ -111,40 +186,125 @@ impl InitializedVectorOperator for InitializedPointInPolygonFilter {
 pub struct PointInPolygonFilterProcessor {
     points: Box<dyn VectorQueryProcessor<VectorType = MultiPointCollection>>,
     polygons: Box<dyn VectorQueryProcessor<VectorType = MultiPolygonCollection>>,
+    mode: PointInPolygonFilterMode,
+    attach_columns: Vec<(String, FeatureDataType)>,
 }
 
+/// The values of the attached columns for each point that is in any polygon
+type PointMatches = Vec<Option<Vec<FeatureDataValue>>>;
+
 impl PointInPolygonFilterProcessor {
     pub fn new(
         points: Box<dyn VectorQueryProcessor<VectorType = MultiPointCollection>>,
         polygons: Box<dyn VectorQueryProcessor<VectorType = MultiPolygonCollection>>,
+        mode: PointInPolygonFilterMode,
+        attach_columns: Vec<(String, FeatureDataType)>,
     ) -> Self {
-        Self { points, polygons }
+        Self {
+            points,
+            polygons,
+            mode,
+            attach_columns,
+        }
     }
 
-    fn filter_points(
+    /// Matches the points that did not match in previous polygon collections
+    fn match_points(
+        &self,
         points: &MultiPointCollection,
         polygons: MultiPolygonCollection,
-        initial_filter: &BooleanArray,
-    ) -> Result<BooleanArray> {
-        let mut filter = Vec::with_capacity(points.len());
-
+        matches: &mut PointMatches,
+    ) -> Result<()> {
         let tester = PointInPolygonTester::new(polygons);
 
+        let attach_data = self
+            .attach_columns
+            .iter()
+            .map(|(column, _)| tester.polygons_ref().data(column))
+            .collect::<Result<Vec<_>, _>>()?;
+
         let coordinates = points.coordinates();
 
-        for ((coordinates_start_index, coordinates_end_index), time_interval) in
+        for (((coordinates_start_index, coordinates_end_index), time_interval), point_match) in
             two_tuple_windows(points.feature_offsets().iter().map(|&c| c as usize))
                 .zip(points.time_intervals())
+                .zip(matches.iter_mut())
         {
-            let is_multi_point_in_polygon_collection = coordinates
-                [coordinates_start_index..coordinates_end_index]
+            if point_match.is_some() {
+                continue;
+            }
+
+            let polygon_index = coordinates[coordinates_start_index..coordinates_end_index]
+                .iter()
+                .filter_map(|coordinate| {
+                    tester.multi_polygon_containing_coordinate(coordinate, time_interval)
+                })
+                .min();
+
+            if let Some(polygon_index) = polygon_index {
+                *point_match = Some(
+                    attach_data
+                        .iter()
+                        .map(|data| data.get_unchecked(polygon_index))
+                        .collect(),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn filter_points(
+        &self,
+        points: &MultiPointCollection,
+        matches: PointMatches,
+    ) -> Result<MultiPointCollection> {
+        let keep_matches = self.mode == PointInPolygonFilterMode::Keep;
+
+        if self.attach_columns.is_empty() {
+            let filter: Vec<bool> = matches
                 .iter()
-                .any(|coordinate| tester.is_coordinate_in_any_polygon(coordinate, time_interval));
+                .map(|point_match| point_match.is_some() == keep_matches)
+                .collect();
+
+            return points.filter(filter).map_err(Into::into);
+        }
+
+        let mut builder = MultiPointCollection::builder();
+        for (column, data_type) in points.column_types() {
+            builder.add_column(column, data_type)?;
+        }
+        for (column, data_type) in &self.attach_columns {
+            builder.add_column(column.clone(), *data_type)?;
+        }
+        let mut builder = builder.finish_header();
+
+        let point_data = points
+            .column_names()
+            .map(|column| Ok((column.clone(), points.data(column)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        for (index, point_match) in matches.into_iter().enumerate() {
+            let attached_values = match point_match {
+                Some(attached_values) => attached_values,
+                None => continue,
+            };
+
+            let geometry: MultiPoint = points.geometry_at(index).expect("must exist").into();
+            builder.push_geometry(geometry)?;
+            builder.push_time_interval(points.time_intervals()[index])?;
 
-            filter.push(is_multi_point_in_polygon_collection);
+            for (column, data) in &point_data {
+                builder.push_data(column, data.get_unchecked(index))?;
+            }
+            for ((column, _), value) in self.attach_columns.iter().zip(attached_values) {
+                builder.push_data(column, value)?;
+            }
+
+            builder.finish_row();
         }
 
-        arrow::compute::or(initial_filter, &filter.into()).map_err(Into::into)
+        builder.build().map_err(Into::into)
     }
 }
 
@@ -164,24 +324,25 @@ impl VectorQueryProcessor for PointInPolygonFilterProcessor {
                 .query(query, ctx)
                 .await?
                 .and_then(move |points| async move {
-                    let initial_filter = BooleanArray::from(vec![false; points.len()]);
+                    let initial_matches: PointMatches = vec![None; points.len()];
 
-                    let filter = self
+                    let matches = self
                         .polygons
                         .query(query, ctx)
                         .await?
-                        .fold(Ok(initial_filter), |filter, polygons| async {
+                        .fold(Ok(initial_matches), |matches, polygons| async {
                             let polygons = polygons?;
+                            let mut matches = matches?;
 
-                            if polygons.is_empty() {
-                                return filter;
+                            if !polygons.is_empty() {
+                                self.match_points(&points, polygons, &mut matches)?;
                             }
 
-                            Self::filter_points(&points, polygons, &filter?)
+                            Ok(matches)
                         })
                         .await?;
 
-                    points.filter(filter).map_err(Into::into)
+                    self.filter_points(&points, matches)
                 });
 
         Ok(
@@ -195,10 +356,28 @@ impl VectorQueryProcessor for PointInPolygonFilterProcessor {
 ///
 /// The algorithm is taken from <http://alienryderflex.com/polygon/>
 ///
+/// The bounding boxes of the multi polygons are indexed, s.t. only the multi polygons
+/// whose bounding box contains a coordinate have to be checked.
+///
 pub struct PointInPolygonTester {
     polygons: MultiPolygonCollection,
     constants: Vec<f64>,
     multiples: Vec<f64>,
+    index: RTree<MultiPolygonEnvelope>,
+}
+
+/// The bounding box of the multi polygon with index `feature_index`
+struct MultiPolygonEnvelope {
+    feature_index: usize,
+    envelope: AABB<[f64; 2]>,
+}
+
+impl RTreeObject for MultiPolygonEnvelope {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.envelope
+    }
 }
 
 impl PointInPolygonTester {
@@ -206,13 +385,54 @@ impl PointInPolygonTester {
         let (constants, multiples) =
             Self::precalculate_polygons(&polygons, polygons.coordinates().len());
 
+        let index = Self::index_polygons(&polygons);
+
         Self {
             polygons,
             constants,
             multiples,
+            index,
         }
     }
 
+    fn index_polygons(polygons: &MultiPolygonCollection) -> RTree<MultiPolygonEnvelope> {
+        let polygon_offsets = polygons.polygon_offsets();
+        let ring_offsets = polygons.ring_offsets();
+        let coordinates = polygons.coordinates();
+
+        let envelopes = two_tuple_windows(polygons.feature_offsets().iter().map(|&c| c as usize))
+            .enumerate()
+            .map(
+                |(feature_index, (multi_polygon_start_index, multi_polygon_end_index))| {
+                    let coordinates_start_index =
+                        ring_offsets[polygon_offsets[multi_polygon_start_index] as usize] as usize;
+                    let coordinates_end_index =
+                        ring_offsets[polygon_offsets[multi_polygon_end_index] as usize] as usize;
+
+                    let (lower_left, upper_right) = coordinates
+                        [coordinates_start_index..coordinates_end_index]
+                        .iter()
+                        .fold(
+                            ([f64::INFINITY; 2], [f64::NEG_INFINITY; 2]),
+                            |(lower_left, upper_right), c| {
+                                (
+                                    [lower_left[0].min(c.x), lower_left[1].min(c.y)],
+                                    [upper_right[0].max(c.x), upper_right[1].max(c.y)],
+                                )
+                            },
+                        );
+
+                    MultiPolygonEnvelope {
+                        feature_index,
+                        envelope: AABB::from_corners(lower_left, upper_right),
+                    }
+                },
+            )
+            .collect();
+
+        RTree::bulk_load(envelopes)
+    }
+
     pub fn polygons_ref(&self) -> &MultiPolygonCollection {
         &self.polygons
     }
@@ -367,36 +587,36 @@ impl PointInPolygonTester {
         is_coordinate_in_multi_polygon
     }
 
-    fn coordinate_in_multi_polygon_iter<'p>(
-        &'p self,
-        coordinate: &'p Coordinate2D,
-        time_interval: &'p TimeInterval,
-    ) -> impl Iterator<Item = bool> + 'p {
-        let polygon_offsets = self.polygons.polygon_offsets();
-        let ring_offsets = self.polygons.ring_offsets();
+    /// Is the coordinate contained in the multi polygon with index `feature_index` and do their time intervals intersect?
+    fn is_coordinate_in_feature(
+        &self,
+        coordinate: &Coordinate2D,
+        time_interval: &TimeInterval,
+        feature_index: usize,
+    ) -> bool {
+        if !self.polygons.time_intervals()[feature_index].intersects(time_interval) {
+            return false;
+        }
 
-        let time_intervals = self.polygons.time_intervals();
+        let feature_offsets = self.polygons.feature_offsets();
 
-        two_tuple_windows(self.polygons.feature_offsets().iter().map(|&c| c as usize))
-            .zip(time_intervals)
-            .map(
-                move |(
-                    (multi_polygon_start_index, multi_polygon_end_index),
-                    multi_polygon_time_interval,
-                )| {
-                    if !multi_polygon_time_interval.intersects(time_interval) {
-                        return false;
-                    }
+        self.check_coordinate_in_multipolygons(
+            coordinate,
+            self.polygons.polygon_offsets(),
+            self.polygons.ring_offsets(),
+            feature_offsets[feature_index] as usize,
+            feature_offsets[feature_index + 1] as usize,
+        )
+    }
 
-                    self.check_coordinate_in_multipolygons(
-                        coordinate,
-                        polygon_offsets,
-                        ring_offsets,
-                        multi_polygon_start_index,
-                        multi_polygon_end_index,
-                    )
-                },
-            )
+    /// Indices of the multi polygons whose bounding box contains the coordinate (in no particular order)
+    fn candidate_multi_polygons<'p>(
+        &'p self,
+        coordinate: &Coordinate2D,
+    ) -> impl Iterator<Item = usize> + 'p {
+        self.index
+            .locate_in_envelope_intersecting(&AABB::from_point([coordinate.x, coordinate.y]))
+            .map(|envelope| envelope.feature_index)
     }
 
     /// Is the coordinate contained in any polygon of the collection?
@@ -412,8 +632,23 @@ impl PointInPolygonTester {
         coordinate: &Coordinate2D,
         time_interval: &TimeInterval,
     ) -> bool {
-        self.coordinate_in_multi_polygon_iter(coordinate, time_interval)
-            .any(std::convert::identity)
+        self.candidate_multi_polygons(coordinate)
+            .any(|feature_index| {
+                self.is_coordinate_in_feature(coordinate, time_interval, feature_index)
+            })
+    }
+
+    /// Returns the index of the first multi polygon that contains the coordinate
+    pub fn multi_polygon_containing_coordinate(
+        &self,
+        coordinate: &Coordinate2D,
+        time_interval: &TimeInterval,
+    ) -> Option<usize> {
+        self.candidate_multi_polygons(coordinate)
+            .filter(|&feature_index| {
+                self.is_coordinate_in_feature(coordinate, time_interval, feature_index)
+            })
+            .min()
     }
 
     #[allow(dead_code)]
@@ -422,8 +657,14 @@ impl PointInPolygonTester {
         coordinate: &Coordinate2D,
         time_interval: &TimeInterval,
     ) -> Vec<bool> {
-        self.coordinate_in_multi_polygon_iter(coordinate, time_interval)
-            .collect()
+        let mut contained = vec![false; self.polygons.len()];
+
+        for feature_index in self.candidate_multi_polygons(coordinate) {
+            contained[feature_index] =
+                self.is_coordinate_in_feature(coordinate, time_interval, feature_index);
+        }
+
+        contained
     }
 }
 
@@ -446,7 +687,7 @@ where
 #[cfg(test)]
 mod tests {
     use geoengine_datatypes::primitives::{
-        BoundingBox2D, MultiPoint, MultiPolygon, SpatialResolution, TimeInterval,
+        BoundingBox2D, FeatureData, MultiPoint, MultiPolygon, SpatialResolution, TimeInterval,
     };
 
     use crate::{engine::VectorQueryRectangle, mock::MockFeatureCollectionSource};
@@ -604,7 +845,7 @@ mod tests {
             .boxed();
 
         let operator = PointInPolygonFilter {
-            params: PointInPolygonFilterParams {},
+            params: PointInPolygonFilterParams::default(),
             sources: PointInPolygonFilterSource {
                 points: point_source,
                 polygons: polygon_source,
@@ -653,7 +894,7 @@ mod tests {
         .boxed();
 
         let operator = PointInPolygonFilter {
-            params: PointInPolygonFilterParams {},
+            params: PointInPolygonFilterParams::default(),
             sources: PointInPolygonFilterSource {
                 points: point_source,
                 polygons: polygon_source,
@@ -715,7 +956,7 @@ mod tests {
             .boxed();
 
         let operator = PointInPolygonFilter {
-            params: PointInPolygonFilterParams {},
+            params: PointInPolygonFilterParams::default(),
             sources: PointInPolygonFilterSource {
                 points: point_source,
                 polygons: polygon_source,
@@ -794,7 +1035,7 @@ mod tests {
         .boxed();
 
         let operator = PointInPolygonFilter {
-            params: PointInPolygonFilterParams {},
+            params: PointInPolygonFilterParams::default(),
             sources: PointInPolygonFilterSource {
                 points: point_source,
                 polygons: polygon_source,
@@ -849,4 +1090,115 @@ mod tests {
 
         Ok(())
     }
+
+    async fn query_with_params(
+        params: PointInPolygonFilterParams,
+    ) -> Result<Vec<MultiPointCollection>> {
+        let points = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(1.0, 1.1), (15.0, 15.1), (25.0, 25.1)]).unwrap(),
+            vec![TimeInterval::new(0, 1)?; 3],
+            [(
+                "name".to_string(),
+                FeatureData::Text(vec!["a".into(), "b".into(), "c".into()]),
+            )]
+            .iter()
+            .cloned()
+            .collect(),
+        )?;
+
+        let polygon1 = MultiPolygon::new(vec![vec![vec![
+            (0.0, 0.0).into(),
+            (10.0, 0.0).into(),
+            (10.0, 10.0).into(),
+            (0.0, 10.0).into(),
+            (0.0, 0.0).into(),
+        ]]])?;
+        let polygon2 = MultiPolygon::new(vec![vec![vec![
+            (10.0, 10.0).into(),
+            (20.0, 10.0).into(),
+            (20.0, 20.0).into(),
+            (10.0, 20.0).into(),
+            (10.0, 10.0).into(),
+        ]]])?;
+
+        let polygons = MultiPolygonCollection::from_data(
+            vec![polygon1, polygon2],
+            vec![TimeInterval::new(0, 1)?; 2],
+            [("id".to_string(), FeatureData::Int(vec![1, 2]))]
+                .iter()
+                .cloned()
+                .collect(),
+        )?;
+
+        let operator = PointInPolygonFilter {
+            params,
+            sources: PointInPolygonFilterSource {
+                points: MockFeatureCollectionSource::single(points).boxed(),
+                polygons: MockFeatureCollectionSource::single(polygons).boxed(),
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await?;
+
+        let query_processor = operator.query_processor()?.multi_point().unwrap();
+
+        let query_rectangle = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (30., 30.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::zero_point_one(),
+        };
+        let ctx = MockQueryContext::new(usize::MAX);
+
+        query_processor
+            .query(query_rectangle, &ctx)
+            .await?
+            .try_collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn drop_matches() -> Result<()> {
+        let result = query_with_params(PointInPolygonFilterParams {
+            mode: PointInPolygonFilterMode::Drop,
+            attach_columns: vec![],
+        })
+        .await?;
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].data("name")?.strings_iter().collect::<Vec<_>>(),
+            vec!["c".to_string()]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn attach_columns() -> Result<()> {
+        let result = query_with_params(PointInPolygonFilterParams {
+            mode: PointInPolygonFilterMode::Keep,
+            attach_columns: vec!["id".to_string()],
+        })
+        .await?;
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].data("name")?.strings_iter().collect::<Vec<_>>(),
+            vec!["a".to_string(), "b".to_string()]
+        );
+        assert_eq!(
+            result[0].data("id")?.strings_iter().collect::<Vec<_>>(),
+            vec!["1".to_string(), "2".to_string()]
+        );
+
+        assert!(query_with_params(PointInPolygonFilterParams {
+            mode: PointInPolygonFilterMode::Drop,
+            attach_columns: vec!["id".to_string()],
+        })
+        .await
+        .is_err());
+
+        Ok(())
+    }
 }
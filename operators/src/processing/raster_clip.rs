@@ -0,0 +1,395 @@
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{try_join, StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{MultiPolygonCollection, VectorDataType};
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, BoundingBox2D, SpatialPartition2D, SpatialPartitioned,
+};
+use geoengine_datatypes::raster::{
+    EmptyGrid, GridOrEmpty, GridShapeAccess, NoDataValue, Pixel, RasterTile2D,
+};
+use num_traits::AsPrimitive;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::engine::{
+    ExecutionContext, InitializedRasterOperator, InitializedVectorOperator, Operator,
+    OperatorDatasets, QueryContext, QueryProcessor, RasterOperator, RasterQueryProcessor,
+    RasterQueryRectangle, RasterResultDescriptor, TypedRasterQueryProcessor, VectorOperator,
+    VectorQueryProcessor, VectorQueryRectangle,
+};
+use crate::error;
+use crate::processing::PointInPolygonTester;
+use crate::util::Result;
+
+/// An operator that clips a raster to the polygons of a vector source, e.g., a study area.
+///
+/// Pixels whose centers do not lie in any polygon that is valid at the time of their tile become no data.
+pub type RasterClip = Operator<RasterClipParams, RasterClipSources>;
+
+/// The parameter spec for `RasterClip`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RasterClipParams {
+    /// The no data value of the output, which is only required if the raster has none
+    #[serde(default)]
+    pub no_data_value: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RasterClipSources {
+    pub raster: Box<dyn RasterOperator>,
+    pub polygons: Box<dyn VectorOperator>,
+}
+
+impl OperatorDatasets for RasterClipSources {
+    fn datasets_collect(&self, datasets: &mut Vec<DatasetId>) {
+        self.raster.datasets_collect(datasets);
+        self.polygons.datasets_collect(datasets);
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for RasterClip {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let (raster, polygons) = try_join!(
+            self.sources.raster.initialize(context),
+            self.sources.polygons.initialize(context)
+        )?;
+
+        let raster_descriptor = raster.result_descriptor();
+        let polygons_descriptor = polygons.result_descriptor();
+
+        ensure!(
+            polygons_descriptor.data_type == VectorDataType::MultiPolygon,
+            error::InvalidType {
+                expected: VectorDataType::MultiPolygon.to_string(),
+                found: polygons_descriptor.data_type.to_string(),
+            }
+        );
+        ensure!(
+            raster_descriptor.spatial_reference == polygons_descriptor.spatial_reference,
+            error::InvalidSpatialReference {
+                expected: raster_descriptor.spatial_reference,
+                found: polygons_descriptor.spatial_reference,
+            }
+        );
+
+        let no_data_value = raster_descriptor
+            .no_data_value
+            .or(self.params.no_data_value)
+            .ok_or_else(|| error::Error::InvalidOperatorSpec {
+                reason: "the raster has no no data value, so the output requires one".to_string(),
+            })?;
+
+        ensure!(
+            raster_descriptor.data_type.is_valid(no_data_value),
+            error::InvalidNoDataValueValueForOutputDataType
+        );
+
+        let result_descriptor = RasterResultDescriptor {
+            no_data_value: Some(no_data_value),
+            ..raster_descriptor.clone()
+        };
+
+        Ok(InitializedRasterClip {
+            result_descriptor,
+            raster,
+            polygons,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedRasterClip {
+    result_descriptor: RasterResultDescriptor,
+    raster: Box<dyn InitializedRasterOperator>,
+    polygons: Box<dyn InitializedVectorOperator>,
+}
+
+impl InitializedRasterOperator for InitializedRasterClip {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let raster = self.raster.query_processor()?;
+        let no_data_value = self
+            .result_descriptor
+            .no_data_value
+            .expect("checked in initialization");
+
+        Ok(call_on_generic_raster_processor!(
+            raster, raster => RasterClipProcessor {
+                raster,
+                polygons: self
+                    .polygons
+                    .query_processor()?
+                    .multi_polygon()
+                    .expect("checked in initialization"),
+                no_data_value: no_data_value.as_(),
+            }
+            .boxed()
+            .into()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct RasterClipProcessor<T> {
+    raster: Box<dyn RasterQueryProcessor<RasterType = T>>,
+    polygons: Box<dyn VectorQueryProcessor<VectorType = MultiPolygonCollection>>,
+    no_data_value: T,
+}
+
+#[async_trait]
+impl<T> QueryProcessor for RasterClipProcessor<T>
+where
+    T: Pixel,
+{
+    type Output = RasterTile2D<T>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        Ok(self
+            .raster
+            .query(query, ctx)
+            .await?
+            .and_then(move |tile| self.clip_tile(tile, query, ctx))
+            .boxed())
+    }
+}
+
+impl<T: Pixel> RasterClipProcessor<T> {
+    /// Sets the pixels of the `tile` that lie outside of all polygons to no data
+    async fn clip_tile(
+        &self,
+        tile: RasterTile2D<T>,
+        query: RasterQueryRectangle,
+        ctx: &dyn QueryContext,
+    ) -> Result<RasterTile2D<T>> {
+        if tile.is_empty() {
+            return Ok(self.empty_tile(tile));
+        }
+
+        let partition = tile.spatial_partition();
+        let polygon_query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new(partition.lower_left(), partition.upper_right())?,
+            time_interval: tile.time,
+            spatial_resolution: query.spatial_resolution,
+        };
+
+        let testers: Vec<PointInPolygonTester> = self
+            .polygons
+            .query(polygon_query, ctx)
+            .await?
+            .map_ok(PointInPolygonTester::new)
+            .try_collect()
+            .await?;
+
+        let geo_transform = tile.tile_information().tile_geo_transform();
+        let [height, width] = tile.grid_shape_array();
+
+        let mut mask = Vec::with_capacity(height * width);
+        for y in 0..height {
+            for x in 0..width {
+                let center =
+                    geo_transform.grid_idx_to_center_coordinate_2d([y as isize, x as isize].into());
+
+                mask.push(
+                    testers
+                        .iter()
+                        .any(|tester| tester.is_coordinate_in_any_polygon(&center, &tile.time)),
+                );
+            }
+        }
+
+        if !mask.contains(&true) {
+            return Ok(self.empty_tile(tile));
+        }
+
+        let mut tile = tile;
+        if let GridOrEmpty::Grid(grid) = &mut tile.grid_array {
+            for (pixel, is_inside) in mask.into_iter().enumerate() {
+                if !is_inside || grid.is_no_data(grid.data[pixel]) {
+                    grid.data[pixel] = self.no_data_value;
+                }
+            }
+
+            grid.no_data_value = Some(self.no_data_value);
+        }
+
+        Ok(tile)
+    }
+
+    fn empty_tile(&self, tile: RasterTile2D<T>) -> RasterTile2D<T> {
+        RasterTile2D {
+            grid_array: EmptyGrid::new(*tile.grid_array.shape_ref(), self.no_data_value).into(),
+            ..tile
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, RasterResultDescriptor};
+    use crate::mock::{MockFeatureCollectionSource, MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::primitives::{
+        Measurement, MultiPolygon, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::raster::{Grid2D, RasterDataType, TileInformation};
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+
+    fn raster(no_data_value: Option<u8>) -> Box<dyn RasterOperator> {
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![RasterTile2D::new_with_tile_info(
+                    TimeInterval::new_unchecked(0, 10),
+                    TileInformation {
+                        global_geo_transform: Default::default(),
+                        global_tile_position: [0, 0].into(),
+                        tile_size_in_pixels: [3, 3].into(),
+                    },
+                    Grid2D::new(
+                        [3, 3].into(),
+                        vec![1, 2, 3, 4, 5, 6, 7, 8, 0],
+                        no_data_value,
+                    )
+                    .unwrap()
+                    .into(),
+                )],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: no_data_value.map(f64::from),
+                    bands: Vec::new(),
+                },
+            },
+        }
+        .boxed()
+    }
+
+    fn polygons(time: TimeInterval) -> Box<dyn VectorOperator> {
+        // a triangle that contains the centers of the pixels on and below the diagonal
+        MockFeatureCollectionSource::single(
+            MultiPolygonCollection::from_data(
+                vec![MultiPolygon::new(vec![vec![vec![
+                    (-0.1, 0.3).into(),
+                    (3.3, -3.1).into(),
+                    (-0.1, -3.1).into(),
+                    (-0.1, 0.3).into(),
+                ]]])
+                .unwrap()],
+                vec![time],
+                Default::default(),
+            )
+            .unwrap(),
+        )
+        .boxed()
+    }
+
+    async fn clip(operator: RasterClip) -> Result<Vec<RasterTile2D<u8>>> {
+        let execution_context = MockExecutionContext::default();
+
+        let processor = operator
+            .boxed()
+            .initialize(&execution_context)
+            .await?
+            .query_processor()?
+            .get_u8()
+            .unwrap();
+
+        let query = RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new_unchecked((0., 0.).into(), (3., -3.).into()),
+            time_interval: TimeInterval::new_unchecked(0, 10),
+            spatial_resolution: SpatialResolution::one(),
+        };
+        let query_context = MockQueryContext::default();
+
+        processor
+            .query(query, &query_context)
+            .await?
+            .try_collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn it_clips_pixels_outside_of_polygons() {
+        let tiles = clip(RasterClip {
+            params: RasterClipParams::default(),
+            sources: RasterClipSources {
+                raster: raster(Some(0)),
+                polygons: polygons(TimeInterval::default()),
+            },
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(tiles.len(), 1);
+
+        let grid = tiles[0].grid_array.clone().into_materialized_grid();
+        assert_eq!(grid.data, vec![1, 0, 0, 4, 5, 0, 7, 8, 0]);
+        assert_eq!(grid.no_data_value, Some(0));
+    }
+
+    #[tokio::test]
+    async fn it_clips_pixels_of_polygons_of_other_times() {
+        let tiles = clip(RasterClip {
+            params: RasterClipParams::default(),
+            sources: RasterClipSources {
+                raster: raster(Some(0)),
+                polygons: polygons(TimeInterval::new_unchecked(20, 30)),
+            },
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(tiles.len(), 1);
+        assert!(tiles[0].is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_requires_a_no_data_value() {
+        let result = clip(RasterClip {
+            params: RasterClipParams::default(),
+            sources: RasterClipSources {
+                raster: raster(None),
+                polygons: polygons(TimeInterval::default()),
+            },
+        })
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(error::Error::InvalidOperatorSpec { .. })
+        ));
+
+        let tiles = clip(RasterClip {
+            params: RasterClipParams {
+                no_data_value: Some(42.),
+            },
+            sources: RasterClipSources {
+                raster: raster(None),
+                polygons: polygons(TimeInterval::default()),
+            },
+        })
+        .await
+        .unwrap();
+
+        let grid = tiles[0].grid_array.clone().into_materialized_grid();
+        assert_eq!(grid.data, vec![1, 42, 42, 4, 5, 42, 7, 8, 0]);
+        assert_eq!(grid.no_data_value, Some(42));
+    }
+}
@@ -0,0 +1,524 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{try_join, StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{DataCollection, FeatureCollectionInfos, VectorDataType};
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, BoundingBox2D, FeatureDataType, Measurement, SpatialPartition2D,
+};
+use geoengine_datatypes::raster::{
+    EmptyGrid, FromPrimitive, GridOrEmpty, GridShapeAccess, NoDataValue, Pixel, RasterTile2D,
+};
+use num_traits::AsPrimitive;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::engine::{
+    ExecutionContext, InitializedRasterOperator, InitializedVectorOperator, Operator,
+    OperatorDatasets, QueryContext, QueryProcessor, RasterOperator, RasterQueryProcessor,
+    RasterQueryRectangle, RasterResultDescriptor, TypedRasterQueryProcessor, VectorOperator,
+    VectorQueryProcessor, VectorQueryRectangle,
+};
+use crate::error;
+use crate::util::Result;
+
+/// An operator that remaps the values of a raster, e.g., class ids, using a lookup table.
+///
+/// The lookup table is a data collection without geometries, e.g., a legend that is stored as CSV.
+/// Each row maps the value of its key column to the value of its value column.
+/// Since labels cannot be stored in a raster, both columns must contain numbers.
+pub type RasterLookup = Operator<RasterLookupParams, RasterLookupSources>;
+
+/// The parameter spec for `RasterLookup`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RasterLookupParams {
+    /// The column of the lookup table that contains the raster values to replace
+    pub key_column: String,
+    /// The column of the lookup table that contains the replacements
+    pub value_column: String,
+    /// The value of pixels that have no entry in the lookup table, which become no data otherwise
+    #[serde(default)]
+    pub default_value: Option<f64>,
+    /// The no data value of the output, which is only required if the raster has none
+    #[serde(default)]
+    pub no_data_value: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RasterLookupSources {
+    pub raster: Box<dyn RasterOperator>,
+    pub table: Box<dyn VectorOperator>,
+}
+
+impl OperatorDatasets for RasterLookupSources {
+    fn datasets_collect(&self, datasets: &mut Vec<DatasetId>) {
+        self.raster.datasets_collect(datasets);
+        self.table.datasets_collect(datasets);
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for RasterLookup {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let (raster, table) = try_join!(
+            self.sources.raster.initialize(context),
+            self.sources.table.initialize(context)
+        )?;
+
+        let raster_descriptor = raster.result_descriptor();
+        let table_descriptor = table.result_descriptor();
+
+        ensure!(
+            table_descriptor.data_type == VectorDataType::Data,
+            error::InvalidType {
+                expected: VectorDataType::Data.to_string(),
+                found: table_descriptor.data_type.to_string(),
+            }
+        );
+
+        for column in [&self.params.key_column, &self.params.value_column] {
+            let data_type = table_descriptor.columns.get(column).ok_or_else(|| {
+                error::Error::ColumnDoesNotExist {
+                    column: column.clone(),
+                }
+            })?;
+
+            ensure!(
+                *data_type != FeatureDataType::Bytes,
+                error::InvalidOperatorSpec {
+                    reason: format!("column `{}` must contain numbers", column)
+                }
+            );
+        }
+
+        let no_data_value = raster_descriptor
+            .no_data_value
+            .or(self.params.no_data_value)
+            .ok_or_else(|| error::Error::InvalidOperatorSpec {
+                reason: "the raster has no no data value, so the output requires one".to_string(),
+            })?;
+
+        ensure!(
+            raster_descriptor.data_type.is_valid(no_data_value),
+            error::InvalidNoDataValueValueForOutputDataType
+        );
+
+        if let Some(default_value) = self.params.default_value {
+            ensure!(
+                raster_descriptor.data_type.is_valid(default_value),
+                error::InvalidOperatorSpec {
+                    reason: format!(
+                        "the default value {} is not a valid {:?} value",
+                        default_value, raster_descriptor.data_type
+                    )
+                }
+            );
+        }
+
+        let result_descriptor = RasterResultDescriptor {
+            measurement: Measurement::Unitless,
+            no_data_value: Some(no_data_value),
+            ..raster_descriptor.clone()
+        };
+
+        Ok(InitializedRasterLookup {
+            params: self.params,
+            result_descriptor,
+            raster,
+            table,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedRasterLookup {
+    params: RasterLookupParams,
+    result_descriptor: RasterResultDescriptor,
+    raster: Box<dyn InitializedRasterOperator>,
+    table: Box<dyn InitializedVectorOperator>,
+}
+
+impl InitializedRasterOperator for InitializedRasterLookup {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let raster = self.raster.query_processor()?;
+        let no_data_value = self
+            .result_descriptor
+            .no_data_value
+            .expect("checked in initialization");
+
+        Ok(call_on_generic_raster_processor!(
+            raster, raster => RasterLookupProcessor {
+                raster,
+                table: self
+                    .table
+                    .query_processor()?
+                    .data()
+                    .expect("checked in initialization"),
+                key_column: self.params.key_column.clone(),
+                value_column: self.params.value_column.clone(),
+                default_value: self.params.default_value.map(AsPrimitive::as_),
+                no_data_value: no_data_value.as_(),
+            }
+            .boxed()
+            .into()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct RasterLookupProcessor<T> {
+    raster: Box<dyn RasterQueryProcessor<RasterType = T>>,
+    table: Box<dyn VectorQueryProcessor<VectorType = DataCollection>>,
+    key_column: String,
+    value_column: String,
+    default_value: Option<T>,
+    no_data_value: T,
+}
+
+#[async_trait]
+impl<T> QueryProcessor for RasterLookupProcessor<T>
+where
+    T: Pixel,
+{
+    type Output = RasterTile2D<T>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let lookup_table = self.lookup_table(query, ctx).await?;
+
+        Ok(self
+            .raster
+            .query(query, ctx)
+            .await?
+            .map_ok(move |tile| self.remap_tile(tile, &lookup_table))
+            .boxed())
+    }
+}
+
+impl<T: Pixel> RasterLookupProcessor<T> {
+    /// Collects the rows of the table that are valid in the query into a lookup table
+    async fn lookup_table(
+        &self,
+        query: RasterQueryRectangle,
+        ctx: &dyn QueryContext,
+    ) -> Result<LookupTable<T>> {
+        let table_query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new(
+                query.spatial_bounds.lower_left(),
+                query.spatial_bounds.upper_right(),
+            )?,
+            time_interval: query.time_interval,
+            spatial_resolution: query.spatial_resolution,
+        };
+
+        let mut collections = self.table.query(table_query, ctx).await?;
+
+        let mut lookup_table = LookupTable::default();
+        while let Some(collection) = collections.next().await {
+            let collection = collection?;
+
+            let keys = collection.data(&self.key_column)?;
+            let values = collection.data(&self.value_column)?;
+
+            let rows = keys
+                .float_options_iter()
+                .zip(keys.nulls())
+                .zip(values.float_options_iter().zip(values.nulls()));
+
+            for ((key, key_is_null), (value, value_is_null)) in rows {
+                if key_is_null || value_is_null {
+                    continue;
+                }
+
+                let (key, value) =
+                    key.zip(value)
+                        .ok_or_else(|| error::Error::InvalidLookupTable {
+                            reason: format!(
+                                "the columns `{}` and `{}` must only contain numbers",
+                                self.key_column, self.value_column
+                            ),
+                        })?;
+
+                ensure!(
+                    T::TYPE.is_valid(value),
+                    error::InvalidLookupTable {
+                        reason: format!("{} is not a valid {:?} value", value, T::TYPE)
+                    }
+                );
+
+                lookup_table.insert(key, T::from_(value));
+            }
+        }
+
+        Ok(lookup_table)
+    }
+
+    /// Replaces the pixels of the `tile` by their entries in the `lookup_table`
+    fn remap_tile(&self, tile: RasterTile2D<T>, lookup_table: &LookupTable<T>) -> RasterTile2D<T> {
+        let mut tile = tile;
+        if let GridOrEmpty::Grid(grid) = &mut tile.grid_array {
+            grid.data = grid
+                .data
+                .iter()
+                .map(|&value| {
+                    if grid.is_no_data(value) {
+                        self.no_data_value
+                    } else {
+                        lookup_table
+                            .get(value.as_())
+                            .or(self.default_value)
+                            .unwrap_or(self.no_data_value)
+                    }
+                })
+                .collect();
+            grid.no_data_value = Some(self.no_data_value);
+            return tile;
+        }
+
+        RasterTile2D {
+            grid_array: EmptyGrid::new(*tile.grid_array.shape_ref(), self.no_data_value).into(),
+            ..tile
+        }
+    }
+}
+
+/// A mapping from raster values to their replacements.
+///
+/// If a key occurs more than once, the first entry wins.
+struct LookupTable<T> {
+    values: HashMap<u64, T>,
+}
+
+impl<T> Default for LookupTable<T> {
+    fn default() -> Self {
+        Self {
+            values: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Copy> LookupTable<T> {
+    fn insert(&mut self, key: f64, value: T) {
+        self.values.entry(Self::hash_key(key)).or_insert(value);
+    }
+
+    fn get(&self, key: f64) -> Option<T> {
+        self.values.get(&Self::hash_key(key)).copied()
+    }
+
+    /// Uses the bits of the key, where adding zero turns `-0.0` into `0.0`
+    fn hash_key(key: f64) -> u64 {
+        (key + 0.).to_bits()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockFeatureCollectionSource, MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::primitives::{
+        FeatureData, NoGeometry, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::raster::{Grid2D, RasterDataType, TileInformation};
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+
+    fn raster(no_data_value: Option<u8>) -> Box<dyn RasterOperator> {
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![RasterTile2D::new_with_tile_info(
+                    TimeInterval::new_unchecked(0, 10),
+                    TileInformation {
+                        global_geo_transform: Default::default(),
+                        global_tile_position: [0, 0].into(),
+                        tile_size_in_pixels: [3, 3].into(),
+                    },
+                    Grid2D::new(
+                        [3, 3].into(),
+                        vec![1, 2, 3, 1, 2, 3, 4, 5, 0],
+                        no_data_value,
+                    )
+                    .unwrap()
+                    .into(),
+                )],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Classification {
+                        measurement: "land cover".to_string(),
+                        classes: Default::default(),
+                    },
+                    no_data_value: no_data_value.map(f64::from),
+                    bands: Vec::new(),
+                },
+            },
+        }
+        .boxed()
+    }
+
+    fn table(keys: FeatureData, values: FeatureData) -> Box<dyn VectorOperator> {
+        MockFeatureCollectionSource::single(
+            DataCollection::from_slices(
+                &[] as &[NoGeometry],
+                &[TimeInterval::default(); 4],
+                &[("class", keys), ("group", values)],
+            )
+            .unwrap(),
+        )
+        .boxed()
+    }
+
+    fn params() -> RasterLookupParams {
+        RasterLookupParams {
+            key_column: "class".to_string(),
+            value_column: "group".to_string(),
+            default_value: None,
+            no_data_value: None,
+        }
+    }
+
+    async fn lookup(operator: RasterLookup) -> Result<Vec<RasterTile2D<u8>>> {
+        let execution_context = MockExecutionContext::default();
+
+        let processor = operator
+            .boxed()
+            .initialize(&execution_context)
+            .await?
+            .query_processor()?
+            .get_u8()
+            .unwrap();
+
+        let query = RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new_unchecked((0., 0.).into(), (3., -3.).into()),
+            time_interval: TimeInterval::new_unchecked(0, 10),
+            spatial_resolution: SpatialResolution::one(),
+        };
+        let query_context = MockQueryContext::default();
+
+        processor
+            .query(query, &query_context)
+            .await?
+            .try_collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn it_remaps_values() {
+        let tiles = lookup(RasterLookup {
+            params: params(),
+            sources: RasterLookupSources {
+                raster: raster(Some(0)),
+                table: table(
+                    FeatureData::Int(vec![1, 2, 3, 1]),
+                    FeatureData::NullableFloat(vec![Some(10.), Some(20.), None, Some(30.)]),
+                ),
+            },
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(tiles.len(), 1);
+
+        let grid = tiles[0].grid_array.clone().into_materialized_grid();
+        assert_eq!(grid.data, vec![10, 20, 0, 10, 20, 0, 0, 0, 0]);
+        assert_eq!(grid.no_data_value, Some(0));
+    }
+
+    #[tokio::test]
+    async fn it_uses_the_default_value() {
+        let tiles = lookup(RasterLookup {
+            params: RasterLookupParams {
+                default_value: Some(99.),
+                no_data_value: Some(255.),
+                ..params()
+            },
+            sources: RasterLookupSources {
+                raster: raster(None),
+                table: table(
+                    FeatureData::Text(vec![
+                        "1".to_string(),
+                        "2".to_string(),
+                        "3".to_string(),
+                        "0".to_string(),
+                    ]),
+                    FeatureData::Text(vec![
+                        "10".to_string(),
+                        "20".to_string(),
+                        "30".to_string(),
+                        "40".to_string(),
+                    ]),
+                ),
+            },
+        })
+        .await
+        .unwrap();
+
+        let grid = tiles[0].grid_array.clone().into_materialized_grid();
+        assert_eq!(grid.data, vec![10, 20, 30, 10, 20, 30, 99, 99, 40]);
+        assert_eq!(grid.no_data_value, Some(255));
+    }
+
+    #[tokio::test]
+    async fn it_rejects_labels() {
+        let result = lookup(RasterLookup {
+            params: params(),
+            sources: RasterLookupSources {
+                raster: raster(Some(0)),
+                table: table(
+                    FeatureData::Int(vec![1, 2, 3, 4]),
+                    FeatureData::Text(vec![
+                        "forest".to_string(),
+                        "water".to_string(),
+                        "urban".to_string(),
+                        "cropland".to_string(),
+                    ]),
+                ),
+            },
+        })
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(error::Error::InvalidLookupTable { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_requires_the_columns() {
+        let result = lookup(RasterLookup {
+            params: RasterLookupParams {
+                value_column: "foo".to_string(),
+                ..params()
+            },
+            sources: RasterLookupSources {
+                raster: raster(Some(0)),
+                table: table(
+                    FeatureData::Int(vec![1, 2, 3, 4]),
+                    FeatureData::Int(vec![1, 2, 3, 4]),
+                ),
+            },
+        })
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(error::Error::ColumnDoesNotExist { column }) if column == "foo"
+        ));
+    }
+}
@@ -0,0 +1,585 @@
+use async_trait::async_trait;
+use futures::future::try_join_all;
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use geoengine_datatypes::primitives::{SpatialPartition2D, TimeInterval};
+use geoengine_datatypes::raster::{
+    EmptyGrid, Grid2D, GridOrEmpty, GridSize, NoDataValue, Pixel, RasterDataType, RasterTile2D,
+    TileInformation, TilingStrategy,
+};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use num_traits::AsPrimitive;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::adapters::RasterArrayZip;
+use crate::engine::{
+    ExecutionContext, InitializedRasterOperator, MultipleRasterSources, Operator, QueryContext,
+    QueryProcessor, RasterOperator, RasterQueryProcessor, RasterQueryRectangle,
+    RasterResultDescriptor, TypedRasterQueryProcessor,
+};
+use crate::error;
+use crate::processing::reprojection::InitializedRasterReprojection;
+use crate::processing::ReprojectionParams;
+use crate::util::resampling::ResamplingMethod;
+use crate::util::Result;
+
+/// An operator that merges rasters of the same data type that cover different extents, e.g., different UTM zones, into one raster.
+///
+/// Rasters in other spatial references than the output are reprojected on the fly.
+/// The rasters may have different time steps, s.t. the output has a tile for each time span
+/// in which none of the rasters changes. Like for other operators, the rasters must fill
+/// temporal gaps with no data tiles.
+pub type RasterMosaic = Operator<RasterMosaicParams, MultipleRasterSources>;
+
+/// The parameter spec for `RasterMosaic`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RasterMosaicParams {
+    #[serde(default)]
+    pub rule: MosaicRule,
+    /// The spatial reference of the output, which defaults to the one of the first raster
+    #[serde(default)]
+    pub spatial_reference: Option<SpatialReference>,
+}
+
+/// How the `RasterMosaic` merges pixels where rasters overlap.
+/// No data pixels never overwrite the pixels of other rasters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum MosaicRule {
+    /// The value of the first raster that has data, i.e., the order of the rasters is their priority
+    FirstValid,
+    /// The mean of the rasters that have data, which is truncated for integer rasters
+    Mean,
+}
+
+impl Default for MosaicRule {
+    fn default() -> Self {
+        Self::FirstValid
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for RasterMosaic {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        ensure!(
+            !self.sources.rasters.is_empty(),
+            error::InvalidOperatorSpec {
+                reason: "there must be at least one raster to merge".to_string()
+            }
+        );
+
+        let initialized_sources = try_join_all(
+            self.sources
+                .rasters
+                .into_iter()
+                .map(|raster| raster.initialize(context)),
+        )
+        .await?;
+
+        let mut sources = Vec::with_capacity(initialized_sources.len());
+        let mut spatial_reference = self.params.spatial_reference;
+
+        for source in initialized_sources {
+            let descriptor = source.result_descriptor();
+
+            ensure!(
+                descriptor.number_of_bands() == 1,
+                error::InvalidOperatorSpec {
+                    reason: "multiband rasters cannot be merged".to_string()
+                }
+            );
+
+            let source_spatial_reference: SpatialReference =
+                Option::from(descriptor.spatial_reference).ok_or_else(|| {
+                    error::Error::InvalidOperatorSpec {
+                        reason: "only georeferenced rasters can be merged".to_string(),
+                    }
+                })?;
+            let target_spatial_reference =
+                *spatial_reference.get_or_insert(source_spatial_reference);
+
+            let source = if source_spatial_reference == target_spatial_reference {
+                source
+            } else {
+                InitializedRasterReprojection::try_new(
+                    ReprojectionParams {
+                        target_spatial_reference,
+                        resampling: ResamplingMethod::Nearest,
+                        transformation: None,
+                    },
+                    source,
+                    context.tiling_specification(),
+                )?
+                .boxed()
+            };
+
+            sources.push(source);
+        }
+
+        let first = sources[0].result_descriptor();
+
+        for source in &sources {
+            let descriptor = source.result_descriptor();
+
+            ensure!(
+                descriptor.data_type == first.data_type,
+                error::InvalidType {
+                    expected: format!("{:?}", first.data_type),
+                    found: format!("{:?}", descriptor.data_type),
+                }
+            );
+        }
+
+        let result_descriptor = RasterResultDescriptor {
+            no_data_value: Some(first.no_data_value.unwrap_or(0.)), // TODO: add option to force a no_data_value
+            ..first.clone()
+        };
+
+        Ok(InitializedRasterMosaic {
+            result_descriptor,
+            sources,
+            rule: self.params.rule,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedRasterMosaic {
+    result_descriptor: RasterResultDescriptor,
+    sources: Vec<Box<dyn InitializedRasterOperator>>,
+    rule: MosaicRule,
+}
+
+impl InitializedRasterOperator for InitializedRasterMosaic {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let sources = self
+            .sources
+            .iter()
+            .map(|source| source.query_processor())
+            .collect::<Result<Vec<_>>>()?;
+
+        let rule = self.rule;
+        let no_data_value = self.result_descriptor.no_data_value.unwrap_or_default();
+
+        macro_rules! merge {
+            ($get:ident) => {
+                RasterMosaicProcessor {
+                    sources: sources
+                        .into_iter()
+                        .map(|source| source.$get().expect("checked in initialization"))
+                        .collect(),
+                    rule,
+                    no_data_value: no_data_value.as_(),
+                }
+                .boxed()
+                .into()
+            };
+        }
+
+        Ok(match self.result_descriptor.data_type {
+            RasterDataType::U8 => merge!(get_u8),
+            RasterDataType::U16 => merge!(get_u16),
+            RasterDataType::U32 => merge!(get_u32),
+            RasterDataType::U64 => merge!(get_u64),
+            RasterDataType::I8 => merge!(get_i8),
+            RasterDataType::I16 => merge!(get_i16),
+            RasterDataType::I32 => merge!(get_i32),
+            RasterDataType::I64 => merge!(get_i64),
+            RasterDataType::F32 => merge!(get_f32),
+            RasterDataType::F64 => merge!(get_f64),
+        })
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct RasterMosaicProcessor<T> {
+    sources: Vec<Box<dyn RasterQueryProcessor<RasterType = T>>>,
+    rule: MosaicRule,
+    no_data_value: T,
+}
+
+#[async_trait]
+impl<T> QueryProcessor for RasterMosaicProcessor<T>
+where
+    T: Pixel,
+{
+    type Output = RasterTile2D<T>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let slice = MosaicSlice {
+            tiles: self.query_slice(query, ctx).await?,
+            query,
+            position: 0,
+            tiles_per_slice: None,
+        };
+
+        Ok(stream::try_unfold(Some(slice), move |slice| self.next_tile(slice, query, ctx)).boxed())
+    }
+}
+
+/// The zipped tiles of the sources for the time slice that starts at the `query`
+struct MosaicSlice<'a, T> {
+    tiles: BoxStream<'a, Result<Vec<RasterTile2D<T>>>>,
+    query: RasterQueryRectangle,
+    position: usize,
+    tiles_per_slice: Option<usize>,
+}
+
+fn number_of_tiles_in_partition(
+    tile_info: &TileInformation,
+    partition: SpatialPartition2D,
+) -> usize {
+    let strategy = TilingStrategy {
+        tile_size_in_pixels: tile_info.tile_size_in_pixels,
+        geo_transform: tile_info.global_geo_transform,
+    };
+
+    strategy.tile_grid_box(partition).number_of_elements()
+}
+
+impl<T: Pixel> RasterMosaicProcessor<T> {
+    /// Queries all sources concurrently and zips their tiles, which are aligned for the first
+    /// time slice of the `query` since all of them are valid at its start
+    async fn query_slice<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Vec<RasterTile2D<T>>>>> {
+        let streams =
+            try_join_all(self.sources.iter().map(|source| source.query(query, ctx))).await?;
+
+        Ok(RasterArrayZip::new(streams).boxed())
+    }
+
+    /// Merges the next tiles of the current time slice and queries the next slice at its end
+    async fn next_tile<'a>(
+        &'a self,
+        slice: Option<MosaicSlice<'a, T>>,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<Option<(RasterTile2D<T>, Option<MosaicSlice<'a, T>>)>> {
+        let mut slice = match slice {
+            Some(slice) => slice,
+            None => return Ok(None),
+        };
+
+        let tile = match slice.tiles.next().await {
+            Some(tiles) => self.merge(tiles?),
+            None => return Ok(None),
+        };
+
+        let tiles_per_slice = *slice.tiles_per_slice.get_or_insert_with(|| {
+            number_of_tiles_in_partition(&tile.tile_information(), query.spatial_bounds)
+        });
+        slice.position += 1;
+
+        if slice.position < tiles_per_slice {
+            return Ok(Some((tile, Some(slice))));
+        }
+
+        // the time slice ended => query the sources from the first change of a raster on
+        let next_start = tile.time.end();
+        if next_start <= slice.query.time_interval.start()
+            || next_start >= query.time_interval.end()
+        {
+            return Ok(Some((tile, None)));
+        }
+
+        let mut next_query = query;
+        next_query.time_interval = TimeInterval::new(next_start, query.time_interval.end())?;
+
+        let next_slice = MosaicSlice {
+            tiles: self.query_slice(next_query, ctx).await?,
+            query: next_query,
+            position: 0,
+            tiles_per_slice: Some(tiles_per_slice),
+        };
+
+        Ok(Some((tile, Some(next_slice))))
+    }
+
+    /// Merges the tiles of the sources at the same position for the time they are all valid
+    fn merge(&self, tiles: Vec<RasterTile2D<T>>) -> RasterTile2D<T> {
+        let grids: Vec<&Grid2D<T>> = tiles
+            .iter()
+            .filter_map(|tile| match &tile.grid_array {
+                GridOrEmpty::Grid(grid) => Some(grid),
+                GridOrEmpty::Empty(_) => None,
+            })
+            .collect();
+
+        let first = &tiles[0];
+
+        let grid = if grids.is_empty() {
+            EmptyGrid::new(*first.grid_array.shape_ref(), self.no_data_value).into()
+        } else {
+            let data = (0..grids[0].data.len())
+                .map(|pixel| {
+                    let mut values = grids.iter().filter_map(|grid| {
+                        let value = grid.data[pixel];
+                        if grid.is_no_data(value) {
+                            None
+                        } else {
+                            Some(value)
+                        }
+                    });
+
+                    let value = match self.rule {
+                        MosaicRule::FirstValid => values.next(),
+                        MosaicRule::Mean => {
+                            let (sum, count) = values.fold((0., 0_usize), |(sum, count), value| {
+                                (sum + AsPrimitive::<f64>::as_(value), count + 1)
+                            });
+                            if count > 0 {
+                                Some(T::from_(sum / count as f64))
+                            } else {
+                                None
+                            }
+                        }
+                    };
+
+                    value.unwrap_or(self.no_data_value)
+                })
+                .collect();
+
+            Grid2D::new(grids[0].shape, data, Some(self.no_data_value))
+                .expect("the output has the shape of the input")
+                .into()
+        };
+
+        let time = tiles.iter().skip(1).fold(first.time, |time, tile| {
+            time.intersect(&tile.time)
+                .expect("the validities of zipped tiles intersect")
+        });
+
+        RasterTile2D::new_with_tile_info_and_properties(
+            time,
+            first.tile_information(),
+            grid,
+            first.properties.clone(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use futures::TryStreamExt;
+    use geoengine_datatypes::primitives::{Measurement, SpatialResolution};
+    use geoengine_datatypes::spatial_reference::SpatialReferenceAuthority;
+
+    fn make_raster(
+        values: Option<Vec<u8>>,
+        spatial_reference: SpatialReference,
+    ) -> Box<dyn RasterOperator> {
+        make_time_series(vec![((0, 1), values)], spatial_reference)
+    }
+
+    fn make_time_series(
+        time_steps: Vec<((i64, i64), Option<Vec<u8>>)>,
+        spatial_reference: SpatialReference,
+    ) -> Box<dyn RasterOperator> {
+        let data = time_steps
+            .into_iter()
+            .map(|((start, end), values)| {
+                let grid = match values {
+                    Some(values) => Grid2D::new([2, 2].into(), values, Some(0)).unwrap().into(),
+                    None => EmptyGrid::new([2, 2].into(), 0).into(),
+                };
+
+                RasterTile2D::new_with_tile_info(
+                    TimeInterval::new_unchecked(start, end),
+                    TileInformation {
+                        global_tile_position: [-1, 0].into(),
+                        tile_size_in_pixels: [2, 2].into(),
+                        global_geo_transform: Default::default(),
+                    },
+                    grid,
+                )
+            })
+            .collect();
+
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data,
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: spatial_reference.into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: Some(0.),
+                    bands: Vec::new(),
+                },
+            },
+        }
+        .boxed()
+    }
+
+    async fn query(
+        rule: MosaicRule,
+        rasters: Vec<Box<dyn RasterOperator>>,
+        time_interval: TimeInterval,
+    ) -> Vec<RasterTile2D<u8>> {
+        let processor = RasterMosaic {
+            params: RasterMosaicParams {
+                rule,
+                spatial_reference: None,
+            },
+            sources: MultipleRasterSources { rasters },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .get_u8()
+        .unwrap();
+
+        processor
+            .query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new_unchecked(
+                        (0., 2.).into(),
+                        (2., 0.).into(),
+                    ),
+                    time_interval,
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    async fn merge(rule: MosaicRule, rasters: Vec<Box<dyn RasterOperator>>) -> Vec<u8> {
+        let tiles = query(rule, rasters, TimeInterval::new_unchecked(0, 1)).await;
+
+        assert_eq!(tiles.len(), 1);
+
+        tiles[0]
+            .clone()
+            .into_materialized_tile()
+            .grid_array
+            .data
+            .to_vec()
+    }
+
+    #[tokio::test]
+    async fn it_merges_first_valid_pixels() {
+        let rasters = vec![
+            make_raster(None, SpatialReference::epsg_4326()),
+            make_raster(Some(vec![0, 2, 0, 4]), SpatialReference::epsg_4326()),
+            make_raster(Some(vec![1, 3, 0, 5]), SpatialReference::epsg_4326()),
+        ];
+
+        assert_eq!(
+            merge(MosaicRule::FirstValid, rasters).await,
+            vec![1, 2, 0, 4]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_merges_means() {
+        let rasters = vec![
+            make_raster(Some(vec![0, 2, 0, 4]), SpatialReference::epsg_4326()),
+            make_raster(Some(vec![1, 3, 0, 7]), SpatialReference::epsg_4326()),
+        ];
+
+        assert_eq!(merge(MosaicRule::Mean, rasters).await, vec![1, 2, 0, 5]);
+    }
+
+    #[tokio::test]
+    async fn it_merges_rasters_with_different_time_steps() {
+        let rasters = vec![
+            make_time_series(
+                vec![
+                    ((0, 2), Some(vec![1, 0, 1, 0])),
+                    ((2, 4), Some(vec![2, 0, 2, 0])),
+                ],
+                SpatialReference::epsg_4326(),
+            ),
+            make_time_series(
+                vec![
+                    ((0, 1), Some(vec![5, 5, 5, 5])),
+                    ((1, 3), Some(vec![6, 6, 6, 6])),
+                    ((3, 4), None),
+                ],
+                SpatialReference::epsg_4326(),
+            ),
+        ];
+
+        let tiles = query(
+            MosaicRule::FirstValid,
+            rasters,
+            TimeInterval::new_unchecked(0, 4),
+        )
+        .await;
+
+        let merged: Vec<(TimeInterval, Vec<u8>)> = tiles
+            .into_iter()
+            .map(|tile| {
+                (
+                    tile.time,
+                    tile.into_materialized_tile().grid_array.data.to_vec(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            merged,
+            vec![
+                (TimeInterval::new_unchecked(0, 1), vec![1, 5, 1, 5]),
+                (TimeInterval::new_unchecked(1, 2), vec![1, 6, 1, 6]),
+                (TimeInterval::new_unchecked(2, 3), vec![2, 6, 2, 6]),
+                (TimeInterval::new_unchecked(3, 4), vec![2, 0, 2, 0]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_reprojects_rasters() {
+        let web_mercator = SpatialReference::new(SpatialReferenceAuthority::Epsg, 3857);
+
+        let initialized = RasterMosaic {
+            params: RasterMosaicParams {
+                rule: MosaicRule::FirstValid,
+                spatial_reference: Some(web_mercator),
+            },
+            sources: MultipleRasterSources {
+                rasters: vec![
+                    make_raster(Some(vec![1, 2, 3, 4]), SpatialReference::epsg_4326()),
+                    make_raster(Some(vec![1, 2, 3, 4]), web_mercator),
+                ],
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .unwrap();
+
+        assert_eq!(
+            initialized.result_descriptor().spatial_reference,
+            web_mercator.into()
+        );
+    }
+}
@@ -0,0 +1,339 @@
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use geoengine_datatypes::collections::{MultiPointCollection, VectorDataType};
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, BoundingBox2D, Coordinate2D, FeatureDataType, MultiPoint,
+};
+use geoengine_datatypes::raster::RasterDataType;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::collections::HashMap;
+
+use crate::engine::{
+    ExecutionContext, InitializedRasterOperator, InitializedVectorOperator, Operator, QueryContext,
+    QueryProcessor, SingleRasterSource, TypedVectorQueryProcessor, VectorOperator,
+    VectorQueryProcessor, VectorQueryRectangle, VectorResultDescriptor,
+};
+use crate::error;
+use crate::processing::raster_vector_join::{FeatureAggregationMethod, RasterVectorJoinProcessor};
+use crate::util::Result;
+
+/// An operator that samples a raster at the points of a regular grid within the query rectangle, e.g., for
+/// extracting training data. It outputs a point for each grid point and raster time step with the pixel value
+/// in the given `column`, which is null for no data.
+///
+/// The grid points are the `origin` plus multiples of the `spacing`. A grid point on the right or upper border of
+/// the query rectangle is left out, s.t. adjacent queries do not return the same point twice.
+pub type RasterSampling = Operator<RasterSamplingParams, SingleRasterSource>;
+
+/// The parameter spec for `RasterSampling`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RasterSamplingParams {
+    /// The distance between neighboring grid points in x and y direction in units of the spatial reference
+    pub spacing: [f64; 2],
+    /// A grid point, which defaults to `(0, 0)`
+    #[serde(default)]
+    pub origin: Coordinate2D,
+    /// The name of the output column
+    pub column: String,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for RasterSampling {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let [spacing_x, spacing_y] = self.params.spacing;
+        ensure!(
+            spacing_x > 0. && spacing_y > 0.,
+            error::InvalidOperatorSpec {
+                reason: "the spacing of the grid must be positive".to_string()
+            }
+        );
+
+        let raster_source = self.sources.raster.initialize(context).await?;
+        let raster_descriptor = raster_source.result_descriptor();
+
+        ensure!(
+            raster_descriptor.number_of_bands() == 1,
+            error::InvalidOperatorSpec {
+                reason: "multiband rasters cannot be sampled".to_string()
+            }
+        );
+
+        let column_type = match raster_descriptor.data_type {
+            RasterDataType::U8
+            | RasterDataType::U16
+            | RasterDataType::U32
+            | RasterDataType::U64
+            | RasterDataType::I8
+            | RasterDataType::I16
+            | RasterDataType::I32
+            | RasterDataType::I64 => FeatureDataType::Int,
+            RasterDataType::F32 | RasterDataType::F64 => FeatureDataType::Float,
+        };
+
+        let mut columns = HashMap::with_capacity(1);
+        columns.insert(self.params.column.clone(), column_type);
+
+        Ok(InitializedRasterSampling {
+            result_descriptor: VectorResultDescriptor {
+                data_type: VectorDataType::MultiPoint,
+                spatial_reference: raster_descriptor.spatial_reference,
+                columns,
+            },
+            raster_source,
+            params: self.params,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedRasterSampling {
+    result_descriptor: VectorResultDescriptor,
+    raster_source: Box<dyn InitializedRasterOperator>,
+    params: RasterSamplingParams,
+}
+
+impl InitializedVectorOperator for InitializedRasterSampling {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let grid = GridPointProcessor {
+            spacing: self.params.spacing,
+            origin: self.params.origin,
+        };
+
+        Ok(TypedVectorQueryProcessor::MultiPoint(
+            RasterVectorJoinProcessor::new(
+                grid.boxed(),
+                vec![self.raster_source.query_processor()?],
+                vec![self.params.column.clone()],
+                FeatureAggregationMethod::First,
+            )
+            .boxed(),
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// Generates the grid points within the query rectangle, row by row from top to bottom
+struct GridPointProcessor {
+    spacing: [f64; 2],
+    origin: Coordinate2D,
+}
+
+impl GridPointProcessor {
+    /// The range of the multiples of `spacing` that lie in `[min, max)` when added to `origin`
+    fn steps(min: f64, max: f64, origin: f64, spacing: f64) -> std::ops::Range<i64> {
+        let first = ((min - origin) / spacing).ceil() as i64;
+        let end = ((max - origin) / spacing).ceil() as i64;
+
+        first..end.max(first)
+    }
+}
+
+#[async_trait]
+impl QueryProcessor for GridPointProcessor {
+    type Output = MultiPointCollection;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let [spacing_x, spacing_y] = self.spacing;
+        let origin = self.origin;
+        let lower_left = query.spatial_bounds.lower_left();
+        let upper_right = query.spatial_bounds.upper_right();
+
+        let columns = Self::steps(lower_left.x, upper_right.x, origin.x, spacing_x);
+        let rows = Self::steps(lower_left.y, upper_right.y, origin.y, spacing_y);
+
+        let width = (columns.end - columns.start) as usize;
+        let number_of_points = width * (rows.end - rows.start) as usize;
+
+        let chunk_size = (ctx.chunk_byte_size() / std::mem::size_of::<Coordinate2D>()).max(1);
+
+        let chunks = (0..number_of_points)
+            .step_by(chunk_size)
+            .map(move |start| start..(start + chunk_size).min(number_of_points));
+
+        Ok(stream::iter(chunks)
+            .map(move |chunk| {
+                let points: Vec<MultiPoint> = chunk
+                    .map(|index| {
+                        let column = columns.start + (index % width) as i64;
+                        let row = rows.end - 1 - (index / width) as i64;
+
+                        Coordinate2D::new(
+                            origin.x + column as f64 * spacing_x,
+                            origin.y + row as f64 * spacing_y,
+                        )
+                        .into()
+                    })
+                    .collect();
+                let time_intervals = vec![query.time_interval; points.len()];
+
+                Ok(MultiPointCollection::from_data(
+                    points,
+                    time_intervals,
+                    HashMap::new(),
+                )?)
+            })
+            .boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{
+        MockExecutionContext, MockQueryContext, RasterOperator, RasterResultDescriptor,
+    };
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use futures::TryStreamExt;
+    use geoengine_datatypes::collections::FeatureCollectionInfos;
+    use geoengine_datatypes::primitives::{
+        FeatureData, Measurement, MultiPoint, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::raster::{Grid2D, RasterTile2D, TileInformation};
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+
+    fn raster() -> Box<dyn RasterOperator> {
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![RasterTile2D::new_with_tile_info(
+                    TimeInterval::new_unchecked(0, 10),
+                    TileInformation {
+                        global_geo_transform: Default::default(),
+                        global_tile_position: [0, 0].into(),
+                        tile_size_in_pixels: [3, 3].into(),
+                    },
+                    Grid2D::new([3, 3].into(), vec![1_u8, 2, 3, 4, 5, 6, 7, 8, 0], Some(0))
+                        .unwrap()
+                        .into(),
+                )],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: Some(0.),
+                    bands: Vec::new(),
+                },
+            },
+        }
+        .boxed()
+    }
+
+    async fn sample(params: RasterSamplingParams) -> Result<Vec<MultiPointCollection>> {
+        let operator = RasterSampling {
+            params,
+            sources: SingleRasterSource { raster: raster() },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await?;
+
+        assert_eq!(
+            operator.result_descriptor().columns.get("value"),
+            Some(&FeatureDataType::Int)
+        );
+
+        let processor = operator.query_processor()?.multi_point().unwrap();
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., -3.).into(), (3., 0.).into())?,
+            time_interval: TimeInterval::new_unchecked(0, 10),
+            spatial_resolution: SpatialResolution::one(),
+        };
+
+        processor
+            .query(query, &MockQueryContext::default())
+            .await?
+            .try_collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn it_samples_pixel_centers() {
+        let collections = sample(RasterSamplingParams {
+            spacing: [1., 1.],
+            origin: (0.5, 0.5).into(),
+            column: "value".to_owned(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(collections.len(), 1);
+        assert_eq!(
+            collections[0],
+            MultiPointCollection::from_slices(
+                &MultiPoint::many(vec![
+                    (0.5, -0.5),
+                    (1.5, -0.5),
+                    (2.5, -0.5),
+                    (0.5, -1.5),
+                    (1.5, -1.5),
+                    (2.5, -1.5),
+                    (0.5, -2.5),
+                    (1.5, -2.5),
+                    (2.5, -2.5),
+                ])
+                .unwrap(),
+                &[TimeInterval::new_unchecked(0, 10); 9],
+                &[(
+                    "value",
+                    FeatureData::NullableInt(vec![
+                        Some(1),
+                        Some(2),
+                        Some(3),
+                        Some(4),
+                        Some(5),
+                        Some(6),
+                        Some(7),
+                        Some(8),
+                        None
+                    ])
+                )],
+            )
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn it_leaves_out_points_on_the_upper_right_border() {
+        let collections = sample(RasterSamplingParams {
+            spacing: [1.5, 2.],
+            origin: Coordinate2D::default(),
+            column: "value".to_owned(),
+        })
+        .await
+        .unwrap();
+
+        let points: usize = collections.iter().map(FeatureCollectionInfos::len).sum();
+
+        // x in {0, 1.5}, y in {-2}
+        assert_eq!(points, 2);
+    }
+
+    #[tokio::test]
+    async fn it_checks_the_spacing() {
+        assert!(matches!(
+            sample(RasterSamplingParams {
+                spacing: [0., 1.],
+                origin: Coordinate2D::default(),
+                column: "value".to_owned(),
+            })
+            .await,
+            Err(error::Error::InvalidOperatorSpec { .. })
+        ));
+    }
+}
@@ -0,0 +1,447 @@
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use futures::future::try_join_all;
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use geoengine_datatypes::primitives::{Measurement, SpatialPartition2D};
+use geoengine_datatypes::raster::{Pixel, RasterDataType, RasterTile2D};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::adapters::RasterArrayZip;
+use crate::engine::{
+    ExecutionContext, InitializedRasterOperator, MultipleRasterSources, Operator, QueryContext,
+    QueryProcessor, RasterBandDescriptor, RasterOperator, RasterQueryProcessor,
+    RasterQueryRectangle, RasterResultDescriptor, TypedRasterQueryProcessor,
+};
+use crate::error;
+use crate::util::Result;
+
+/// An operator that stacks single band rasters of the same data type into a multiband raster.
+///
+/// For each tile position and time step, its stream contains a tile per band in the order of the `bands`.
+/// The tiles carry the name of their band in their properties.
+pub type RasterStacker = Operator<RasterStackerParams, MultipleRasterSources>;
+
+/// The parameter spec for `RasterStacker`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RasterStackerParams {
+    /// A band for each of the `rasters` in the same order
+    pub bands: Vec<RasterStackerBand>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RasterStackerBand {
+    pub name: String,
+    /// Overrides the measurement of the source
+    #[serde(default)]
+    pub measurement: Option<Measurement>,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for RasterStacker {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        ensure!(
+            !self.sources.rasters.is_empty(),
+            error::InvalidOperatorSpec {
+                reason: "there must be at least one raster to stack".to_string()
+            }
+        );
+        ensure!(
+            self.params.bands.len() == self.sources.rasters.len(),
+            error::InvalidOperatorSpec {
+                reason: format!(
+                    "there are {} bands for {} rasters",
+                    self.params.bands.len(),
+                    self.sources.rasters.len()
+                )
+            }
+        );
+
+        let mut names = HashSet::with_capacity(self.params.bands.len());
+        for band in &self.params.bands {
+            ensure!(
+                names.insert(band.name.as_str()),
+                error::InvalidOperatorSpec {
+                    reason: format!("band name `{}` is not unique", band.name)
+                }
+            );
+        }
+
+        let sources = try_join_all(
+            self.sources
+                .rasters
+                .into_iter()
+                .map(|raster| raster.initialize(context)),
+        )
+        .await?;
+
+        let first = sources[0].result_descriptor();
+
+        for source in &sources {
+            let descriptor = source.result_descriptor();
+
+            ensure!(
+                descriptor.number_of_bands() == 1,
+                error::InvalidOperatorSpec {
+                    reason: "multiband rasters cannot be stacked".to_string()
+                }
+            );
+            ensure!(
+                descriptor.data_type == first.data_type,
+                error::InvalidType {
+                    expected: format!("{:?}", first.data_type),
+                    found: format!("{:?}", descriptor.data_type),
+                }
+            );
+            ensure!(
+                descriptor.spatial_reference == first.spatial_reference,
+                error::InvalidSpatialReference {
+                    expected: first.spatial_reference,
+                    found: descriptor.spatial_reference,
+                }
+            );
+        }
+
+        let bands: Vec<RasterBandDescriptor> = self
+            .params
+            .bands
+            .into_iter()
+            .zip(&sources)
+            .map(|(band, source)| RasterBandDescriptor {
+                name: band.name,
+                measurement: band
+                    .measurement
+                    .unwrap_or_else(|| source.result_descriptor().measurement.clone()),
+            })
+            .collect();
+
+        // the tiles keep the no data values of their sources, so the raster only has one if they agree
+        let no_data_value = first.no_data_value.filter(|no_data_value| {
+            sources.iter().all(|source| {
+                source
+                    .result_descriptor()
+                    .no_data_value
+                    .map_or(false, |other| {
+                        other == *no_data_value || (other.is_nan() && no_data_value.is_nan())
+                    })
+            })
+        });
+
+        let measurement = if bands
+            .iter()
+            .all(|band| band.measurement == bands[0].measurement)
+        {
+            bands[0].measurement.clone()
+        } else {
+            Measurement::Unitless
+        };
+
+        let result_descriptor = RasterResultDescriptor {
+            data_type: first.data_type,
+            spatial_reference: first.spatial_reference,
+            measurement,
+            no_data_value,
+            bands,
+        };
+
+        Ok(InitializedRasterStacker {
+            result_descriptor,
+            sources,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedRasterStacker {
+    result_descriptor: RasterResultDescriptor,
+    sources: Vec<Box<dyn InitializedRasterOperator>>,
+}
+
+impl InitializedRasterOperator for InitializedRasterStacker {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let sources = self
+            .sources
+            .iter()
+            .map(|source| source.query_processor())
+            .collect::<Result<Vec<_>>>()?;
+
+        let band_names: Vec<String> = self
+            .result_descriptor
+            .bands
+            .iter()
+            .map(|band| band.name.clone())
+            .collect();
+
+        macro_rules! stack {
+            ($get:ident) => {
+                RasterStackerProcessor {
+                    sources: sources
+                        .into_iter()
+                        .map(|source| source.$get().expect("checked in initialization"))
+                        .collect(),
+                    band_names,
+                }
+                .boxed()
+                .into()
+            };
+        }
+
+        Ok(match self.result_descriptor.data_type {
+            RasterDataType::U8 => stack!(get_u8),
+            RasterDataType::U16 => stack!(get_u16),
+            RasterDataType::U32 => stack!(get_u32),
+            RasterDataType::U64 => stack!(get_u64),
+            RasterDataType::I8 => stack!(get_i8),
+            RasterDataType::I16 => stack!(get_i16),
+            RasterDataType::I32 => stack!(get_i32),
+            RasterDataType::I64 => stack!(get_i64),
+            RasterDataType::F32 => stack!(get_f32),
+            RasterDataType::F64 => stack!(get_f64),
+        })
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct RasterStackerProcessor<T> {
+    sources: Vec<Box<dyn RasterQueryProcessor<RasterType = T>>>,
+    band_names: Vec<String>,
+}
+
+#[async_trait]
+impl<T> QueryProcessor for RasterStackerProcessor<T>
+where
+    T: Pixel,
+{
+    type Output = RasterTile2D<T>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let mut streams = Vec::with_capacity(self.sources.len());
+        for source in &self.sources {
+            streams.push(source.query(query, ctx).await?);
+        }
+
+        let band_names = &self.band_names;
+
+        Ok(RasterArrayZip::new(streams)
+            .flat_map(move |tiles| {
+                let tiles: Vec<Result<RasterTile2D<T>>> = match tiles {
+                    Ok(tiles) => tiles
+                        .into_iter()
+                        .zip(band_names)
+                        .map(|(mut tile, band_name)| {
+                            tile.properties.band_name = Some(band_name.clone());
+                            Ok(tile)
+                        })
+                        .collect(),
+                    Err(error) => vec![Err(error)],
+                };
+
+                stream::iter(tiles)
+            })
+            .boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::SingleRasterSource;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use crate::processing::{TemporalSmoothing, TemporalSmoothingMethod, TemporalSmoothingParams};
+    use futures::TryStreamExt;
+    use geoengine_datatypes::primitives::{SpatialResolution, TimeInterval};
+    use geoengine_datatypes::raster::{Grid2D, TileInformation};
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+
+    fn make_raster(values: Vec<u8>, measurement: Measurement) -> Box<dyn RasterOperator> {
+        let tiles = (0..2)
+            .map(|t| {
+                RasterTile2D::new_with_tile_info(
+                    TimeInterval::new_unchecked(t, t + 1),
+                    TileInformation {
+                        global_tile_position: [-1, 0].into(),
+                        tile_size_in_pixels: [2, 2].into(),
+                        global_geo_transform: Default::default(),
+                    },
+                    Grid2D::new([2, 2].into(), values.clone(), Some(0))
+                        .unwrap()
+                        .into(),
+                )
+            })
+            .collect();
+
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: tiles,
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement,
+                    no_data_value: Some(0.),
+                    bands: Vec::new(),
+                },
+            },
+        }
+        .boxed()
+    }
+
+    fn band(name: &str) -> RasterStackerBand {
+        RasterStackerBand {
+            name: name.to_string(),
+            measurement: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn it_stacks_bands() {
+        let red = Measurement::continuous("reflectance".to_string(), None);
+
+        let initialized = RasterStacker {
+            params: RasterStackerParams {
+                bands: vec![band("red"), band("nir")],
+            },
+            sources: MultipleRasterSources {
+                rasters: vec![
+                    make_raster(vec![1, 2, 3, 4], red.clone()),
+                    make_raster(vec![5, 6, 7, 8], Measurement::Unitless),
+                ],
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .unwrap();
+
+        assert_eq!(
+            initialized.result_descriptor().bands,
+            vec![
+                RasterBandDescriptor {
+                    name: "red".to_string(),
+                    measurement: red,
+                },
+                RasterBandDescriptor {
+                    name: "nir".to_string(),
+                    measurement: Measurement::Unitless,
+                },
+            ]
+        );
+        assert_eq!(initialized.result_descriptor().number_of_bands(), 2);
+        assert_eq!(
+            initialized.result_descriptor().measurement,
+            Measurement::Unitless
+        );
+
+        let processor = initialized.query_processor().unwrap().get_u8().unwrap();
+
+        let tiles: Vec<RasterTile2D<u8>> = processor
+            .query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new_unchecked(
+                        (0., 2.).into(),
+                        (2., 0.).into(),
+                    ),
+                    time_interval: TimeInterval::new_unchecked(0, 2),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        let bands: Vec<(i64, Option<String>, Vec<u8>)> = tiles
+            .into_iter()
+            .map(|tile| {
+                let time = tile.time.start().inner();
+                let band_name = tile.properties.band_name.clone();
+                let values = tile.into_materialized_tile().grid_array.data.into_vec();
+                (time, band_name, values)
+            })
+            .collect();
+
+        assert_eq!(
+            bands,
+            vec![
+                (0, Some("red".to_string()), vec![1, 2, 3, 4]),
+                (0, Some("nir".to_string()), vec![5, 6, 7, 8]),
+                (1, Some("red".to_string()), vec![1, 2, 3, 4]),
+                (1, Some("nir".to_string()), vec![5, 6, 7, 8]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_checks_the_bands() {
+        for bands in [vec![band("red")], vec![band("red"), band("red")]] {
+            let result = RasterStacker {
+                params: RasterStackerParams { bands },
+                sources: MultipleRasterSources {
+                    rasters: vec![
+                        make_raster(vec![1, 2, 3, 4], Measurement::Unitless),
+                        make_raster(vec![5, 6, 7, 8], Measurement::Unitless),
+                    ],
+                },
+            }
+            .boxed()
+            .initialize(&MockExecutionContext::default())
+            .await;
+
+            assert!(matches!(
+                result,
+                Err(error::Error::InvalidOperatorSpec { .. })
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn single_band_operators_reject_stacked_rasters() {
+        let stacked = RasterStacker {
+            params: RasterStackerParams {
+                bands: vec![band("red"), band("nir")],
+            },
+            sources: MultipleRasterSources {
+                rasters: vec![
+                    make_raster(vec![1, 2, 3, 4], Measurement::Unitless),
+                    make_raster(vec![5, 6, 7, 8], Measurement::Unitless),
+                ],
+            },
+        }
+        .boxed();
+
+        let result = TemporalSmoothing {
+            params: TemporalSmoothingParams {
+                method: TemporalSmoothingMethod::Mean,
+                window: 3,
+            },
+            sources: SingleRasterSource { raster: stacked },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(error::Error::InvalidOperatorSpec { .. })
+        ));
+    }
+}
@@ -255,6 +255,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: None,
+                    bands: Vec::new(),
                 },
             },
         }
@@ -339,6 +340,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: None,
+                    bands: Vec::new(),
                 },
             },
         }
@@ -450,6 +452,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: None,
+                    bands: Vec::new(),
                 },
             },
         }
@@ -561,6 +564,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: None,
+                    bands: Vec::new(),
                 },
             },
         }
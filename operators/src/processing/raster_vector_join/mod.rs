@@ -9,15 +9,17 @@ use crate::engine::{
     VectorQueryProcessor, VectorResultDescriptor,
 };
 use crate::error::{self, Error};
-use crate::processing::raster_vector_join::non_aggregated::RasterVectorJoinProcessor;
+pub(crate) use crate::processing::raster_vector_join::non_aggregated::RasterVectorJoinProcessor;
 use crate::util::Result;
 
 use crate::processing::raster_vector_join::aggregated::RasterVectorAggregateJoinProcessor;
 use async_trait::async_trait;
-use futures::future::join_all;
+use futures::future::try_join_all;
+use futures::try_join;
 use geoengine_datatypes::collections::VectorDataType;
 use geoengine_datatypes::primitives::FeatureDataType;
 use geoengine_datatypes::raster::{Pixel, RasterDataType};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use snafu::ensure;
 
@@ -32,7 +34,7 @@ pub type RasterVectorJoin = Operator<RasterVectorJoinParams, SingleVectorMultipl
 const MAX_NUMBER_OF_RASTER_INPUTS: usize = 8;
 
 /// The parameter spec for `RasterVectorJoin`
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RasterVectorJoinParams {
     /// Each name reflects the output column of the join result.
@@ -49,7 +51,7 @@ pub struct RasterVectorJoinParams {
 /// How to aggregate the values for the geometries inside a feature e.g.
 /// the mean of all the raster values corresponding to the individual
 /// points inside a `MultiPoint` feature.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Copy)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Copy, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum FeatureAggregationMethod {
     First,
@@ -60,7 +62,7 @@ pub enum FeatureAggregationMethod {
 /// If there are multiple rasters valid during the validity of a feature
 /// the featuer is either split into multiple (None-aggregation) or the
 /// values are aggreagated
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Copy)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Copy, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum TemporalAggregationMethod {
     None,
@@ -89,7 +91,15 @@ impl VectorOperator for RasterVectorJoin {
             }
         );
 
-        let vector_source = self.sources.vector.initialize(context).await?;
+        let (vector_source, raster_sources) = try_join!(
+            self.sources.vector.initialize(context),
+            try_join_all(
+                self.sources
+                    .rasters
+                    .into_iter()
+                    .map(|s| s.initialize(context)),
+            )
+        )?;
 
         ensure!(
             vector_source.result_descriptor().data_type != VectorDataType::Data,
@@ -104,15 +114,14 @@ impl VectorOperator for RasterVectorJoin {
             },
         );
 
-        let raster_sources = join_all(
-            self.sources
-                .rasters
-                .into_iter()
-                .map(|s| s.initialize(context)),
-        )
-        .await
-        .into_iter()
-        .collect::<Result<Vec<_>>>()?;
+        for raster_source in &raster_sources {
+            ensure!(
+                raster_source.result_descriptor().number_of_bands() == 1,
+                error::InvalidOperatorSpec {
+                    reason: "multiband rasters cannot be joined"
+                }
+            );
+        }
 
         let params = self.params;
 
@@ -769,6 +769,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: None,
+                    bands: Vec::new(),
                 },
             },
         }
@@ -926,6 +927,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: None,
+                    bands: Vec::new(),
                 },
             },
         }
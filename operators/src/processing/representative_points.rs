@@ -0,0 +1,295 @@
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionModifications, IntoGeometryIterator, MultiPointCollection,
+    VectorDataType,
+};
+use geoengine_datatypes::operations::representative_point::RepresentativePoint;
+use geoengine_datatypes::primitives::{BoundingBox2D, Geometry, MultiPointAccess};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::engine::{
+    ExecutionContext, InitializedVectorOperator, Operator, QueryContext, QueryProcessor,
+    SingleVectorSource, TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor,
+    VectorQueryRectangle, VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::Result;
+
+/// An operator that turns line and polygon collections into point collections with one point per feature,
+/// e.g., for labeling or point-based joins. The time intervals and all columns are kept.
+///
+/// Features whose point lies outside of the query rectangle are dropped,
+/// s.t. adjacent queries, e.g., map tiles, do not return the same point twice.
+pub type RepresentativePoints = Operator<RepresentativePointsParams, SingleVectorSource>;
+
+/// The parameter spec for `RepresentativePoints`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RepresentativePointsParams {
+    #[serde(default)]
+    pub method: RepresentativePointMethod,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum RepresentativePointMethod {
+    /// The center of mass, which may lie outside of concave or multi-part geometries
+    Centroid,
+    /// A point that is guaranteed to lie in the interior of polygons or on a vertex of lines
+    PointOnSurface,
+}
+
+impl Default for RepresentativePointMethod {
+    fn default() -> Self {
+        Self::Centroid
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for RepresentativePoints {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let vector_source = self.sources.vector.initialize(context).await?;
+        let source_descriptor = vector_source.result_descriptor();
+
+        ensure!(
+            source_descriptor.data_type == VectorDataType::MultiLineString
+                || source_descriptor.data_type == VectorDataType::MultiPolygon,
+            error::InvalidType {
+                expected: format!(
+                    "{} or {}",
+                    VectorDataType::MultiLineString,
+                    VectorDataType::MultiPolygon
+                ),
+                found: source_descriptor.data_type.to_string(),
+            }
+        );
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: VectorDataType::MultiPoint,
+            spatial_reference: source_descriptor.spatial_reference,
+            columns: source_descriptor.columns.clone(),
+        };
+
+        Ok(InitializedRepresentativePoints {
+            result_descriptor,
+            vector_source,
+            method: self.params.method,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedRepresentativePoints {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    method: RepresentativePointMethod,
+}
+
+impl InitializedVectorOperator for InitializedRepresentativePoints {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let method = self.method;
+
+        let processor = match self.vector_source.query_processor()? {
+            TypedVectorQueryProcessor::MultiLineString(source) => {
+                RepresentativePointsProcessor { source, method }.boxed()
+            }
+            TypedVectorQueryProcessor::MultiPolygon(source) => {
+                RepresentativePointsProcessor { source, method }.boxed()
+            }
+            TypedVectorQueryProcessor::Data(_) | TypedVectorQueryProcessor::MultiPoint(_) => {
+                unreachable!("checked in initialization")
+            }
+        };
+
+        Ok(TypedVectorQueryProcessor::MultiPoint(processor))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct RepresentativePointsProcessor<G> {
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    method: RepresentativePointMethod,
+}
+
+#[async_trait]
+impl<G> QueryProcessor for RepresentativePointsProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+    FeatureCollection<G>: RepresentativePoint<Out = MultiPointCollection>,
+{
+    type Output = MultiPointCollection;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let method = self.method;
+        let bbox = query.spatial_bounds;
+
+        Ok(self
+            .source
+            .query(query, ctx)
+            .await?
+            .map(move |collection| {
+                collection.and_then(|collection| representative_points(&collection, method, &bbox))
+            })
+            .boxed())
+    }
+}
+
+fn representative_points<C>(
+    collection: &C,
+    method: RepresentativePointMethod,
+    bbox: &BoundingBox2D,
+) -> Result<MultiPointCollection>
+where
+    C: RepresentativePoint<Out = MultiPointCollection>,
+{
+    let points = match method {
+        RepresentativePointMethod::Centroid => collection.centroid()?,
+        RepresentativePointMethod::PointOnSurface => collection.point_on_surface()?,
+    };
+
+    let mask: Vec<bool> = points
+        .geometries()
+        .map(|point| {
+            point
+                .points()
+                .iter()
+                .all(|coordinate| bbox.contains_coordinate(coordinate))
+        })
+        .collect();
+
+    Ok(points.filter(mask)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use futures::TryStreamExt;
+    use geoengine_datatypes::collections::MultiPolygonCollection;
+    use geoengine_datatypes::primitives::{
+        FeatureData, MultiPoint, MultiPolygon, SpatialResolution, TimeInterval,
+    };
+    use std::collections::HashMap;
+
+    fn squares() -> MultiPolygonCollection {
+        let square = |x: f64| {
+            MultiPolygon::new(vec![vec![vec![
+                (x, 0.).into(),
+                (x + 2., 0.).into(),
+                (x + 2., 2.).into(),
+                (x, 2.).into(),
+                (x, 0.).into(),
+            ]]])
+            .unwrap()
+        };
+
+        let mut data = HashMap::new();
+        data.insert("id".to_string(), FeatureData::Int(vec![1, 2]));
+
+        MultiPolygonCollection::from_data(
+            vec![square(0.), square(10.)],
+            vec![
+                TimeInterval::new_unchecked(0, 1),
+                TimeInterval::new_unchecked(1, 2),
+            ],
+            data,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn polygons_to_points() {
+        let operator = RepresentativePoints {
+            params: RepresentativePointsParams {
+                method: RepresentativePointMethod::PointOnSurface,
+            },
+            sources: MockFeatureCollectionSource::single(squares())
+                .boxed()
+                .into(),
+        }
+        .boxed();
+
+        let initialized = operator
+            .initialize(&MockExecutionContext::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            initialized.result_descriptor().data_type,
+            VectorDataType::MultiPoint
+        );
+
+        let processor = initialized
+            .query_processor()
+            .unwrap()
+            .multi_point()
+            .unwrap();
+
+        let collections: Vec<MultiPointCollection> = processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((0., 0.).into(), (5., 5.).into()).unwrap(),
+                    time_interval: TimeInterval::new_unchecked(0, 2),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        let mut data = HashMap::new();
+        data.insert("id".to_string(), FeatureData::Int(vec![1]));
+
+        assert_eq!(
+            collections,
+            vec![MultiPointCollection::from_data(
+                vec![MultiPoint::new(vec![(1., 1.).into()]).unwrap()],
+                vec![TimeInterval::new_unchecked(0, 1)],
+                data,
+            )
+            .unwrap()]
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_points() {
+        let points = MultiPointCollection::from_data(
+            MultiPoint::many(vec![vec![(0., 0.)]]).unwrap(),
+            vec![TimeInterval::default()],
+            HashMap::new(),
+        )
+        .unwrap();
+
+        let operator = RepresentativePoints {
+            params: RepresentativePointsParams::default(),
+            sources: MockFeatureCollectionSource::single(points).boxed().into(),
+        }
+        .boxed();
+
+        assert!(matches!(
+            operator.initialize(&MockExecutionContext::default()).await,
+            Err(error::Error::InvalidType { .. })
+        ));
+    }
+}
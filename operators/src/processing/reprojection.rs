@@ -8,8 +8,8 @@ use crate::{
         TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor, VectorQueryRectangle,
         VectorResultDescriptor,
     },
-    error::Error,
-    util::{input::RasterOrVectorOperator, Result},
+    error::{self, Error},
+    util::{input::RasterOrVectorOperator, resampling::ResamplingMethod, Result},
 };
 use async_trait::async_trait;
 use futures::stream::BoxStream;
@@ -17,33 +17,44 @@ use futures::StreamExt;
 use geoengine_datatypes::{
     operations::reproject::{
         suggest_pixel_size_from_diag_cross, suggest_pixel_size_from_diag_cross_projected,
-        CoordinateProjection, CoordinateProjector, Reproject, ReprojectClipped,
+        CoordinateProjector, CoordinateTransformation, Reproject, ReprojectClipped,
     },
     primitives::{BoundingBox2D, SpatialPartition2D},
     raster::{Pixel, RasterTile2D, TilingSpecification},
     spatial_reference::SpatialReference,
 };
 use num_traits::AsPrimitive;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use snafu::ensure;
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ReprojectionParams {
     pub target_spatial_reference: SpatialReference,
+    /// How raster pixels are computed from the pixels of the source. Ignored for vector data.
+    #[serde(default)]
+    pub resampling: ResamplingMethod,
+    /// The transformation from the source to the target spatial reference. PROJ picks one if it is not set.
+    #[serde(default)]
+    pub transformation: Option<CoordinateTransformation>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct VectorReprojectionState {
     source_srs: SpatialReference,
     target_srs: SpatialReference,
+    transformation: Option<CoordinateTransformation>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RasterReprojectionState {
     source_srs: SpatialReference,
     target_srs: SpatialReference,
+    transformation: Option<CoordinateTransformation>,
     tiling_spec: TilingSpecification,
     out_no_data_value: f64,
+    resampling: ResamplingMethod,
 }
 
 pub type Reprojection = Operator<ReprojectionParams, SingleRasterOrVectorSource>;
@@ -83,6 +94,7 @@ impl VectorOperator for Reprojection {
         let state = VectorReprojectionState {
             source_srs: Option::from(in_desc.spatial_reference).unwrap(),
             target_srs: self.params.target_spatial_reference,
+            transformation: self.params.transformation,
         };
 
         let initialized_operator = InitializedVectorReprojection {
@@ -101,11 +113,16 @@ impl InitializedVectorOperator for InitializedVectorReprojection {
     }
 
     fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
-        let state = self.state;
+        let state = self.state.clone();
         match self.source.query_processor()? {
             TypedVectorQueryProcessor::Data(source) => Ok(TypedVectorQueryProcessor::Data(
                 MapQueryProcessor::new(source, move |query| {
-                    query_rewrite_fn(query, state.source_srs, state.target_srs)
+                    query_rewrite_fn(
+                        query,
+                        state.source_srs,
+                        state.target_srs,
+                        state.transformation.clone(),
+                    )
                 })
                 .boxed(),
             )),
@@ -115,6 +132,7 @@ impl InitializedVectorOperator for InitializedVectorReprojection {
                         source,
                         self.state.source_srs,
                         self.state.target_srs,
+                        self.state.transformation.clone(),
                     )
                     .boxed(),
                 ))
@@ -125,6 +143,7 @@ impl InitializedVectorOperator for InitializedVectorReprojection {
                         source,
                         self.state.source_srs,
                         self.state.target_srs,
+                        self.state.transformation.clone(),
                     )
                     .boxed(),
                 ))
@@ -135,6 +154,7 @@ impl InitializedVectorOperator for InitializedVectorReprojection {
                         source,
                         self.state.source_srs,
                         self.state.target_srs,
+                        self.state.transformation.clone(),
                     )
                     .boxed(),
                 ))
@@ -150,14 +170,25 @@ where
     source: Q,
     from: SpatialReference,
     to: SpatialReference,
+    transformation: Option<CoordinateTransformation>,
 }
 
 impl<Q, G> VectorReprojectionProcessor<Q, G>
 where
     Q: VectorQueryProcessor<VectorType = G>,
 {
-    pub fn new(source: Q, from: SpatialReference, to: SpatialReference) -> Self {
-        Self { source, from, to }
+    pub fn new(
+        source: Q,
+        from: SpatialReference,
+        to: SpatialReference,
+        transformation: Option<CoordinateTransformation>,
+    ) -> Self {
+        Self {
+            source,
+            from,
+            to,
+            transformation,
+        }
     }
 }
 
@@ -167,9 +198,11 @@ pub fn query_rewrite_fn(
     query: VectorQueryRectangle,
     source: SpatialReference,
     target: SpatialReference,
+    transformation: Option<CoordinateTransformation>,
 ) -> Result<VectorQueryRectangle> {
-    let projector_source_target = CoordinateProjector::from_known_srs(source, target)?;
-    let projector_target_source = CoordinateProjector::from_known_srs(target, source)?;
+    let projector_source_target =
+        CoordinateProjector::from_known_srs_with_transformation(source, target, transformation)?;
+    let projector_target_source = projector_source_target.inverse()?;
 
     let p_bbox = query
         .spatial_bounds
@@ -199,7 +232,8 @@ where
         query: VectorQueryRectangle,
         ctx: &'a dyn QueryContext,
     ) -> Result<BoxStream<'a, Result<Self::Output>>> {
-        let rewritten_query = query_rewrite_fn(query, self.from, self.to)?;
+        let rewritten_query =
+            query_rewrite_fn(query, self.from, self.to, self.transformation.clone())?;
 
         Ok(self
             .source
@@ -207,9 +241,13 @@ where
             .await?
             .map(move |collection_result| {
                 collection_result.and_then(|collection| {
-                    CoordinateProjector::from_known_srs(self.from, self.to)
-                        .and_then(|projector| collection.reproject(projector.as_ref()))
-                        .map_err(Into::into)
+                    CoordinateProjector::from_known_srs_with_transformation(
+                        self.from,
+                        self.to,
+                        self.transformation.clone(),
+                    )
+                    .and_then(|projector| collection.reproject(projector.as_ref()))
+                    .map_err(Into::into)
                 })
             })
             .boxed())
@@ -230,30 +268,55 @@ impl RasterOperator for Reprojection {
 
         let raster_operator = raster_operator.initialize(context).await?;
 
-        let in_desc: &RasterResultDescriptor = raster_operator.result_descriptor();
+        let initialized_operator = InitializedRasterReprojection::try_new(
+            self.params,
+            raster_operator,
+            context.tiling_specification(),
+        )?;
+
+        Ok(initialized_operator.boxed())
+    }
+}
+
+impl InitializedRasterReprojection {
+    /// Reprojects an already initialized raster `source`
+    pub fn try_new(
+        params: ReprojectionParams,
+        source: Box<dyn InitializedRasterOperator>,
+        tiling_spec: TilingSpecification,
+    ) -> Result<Self> {
+        let in_desc: &RasterResultDescriptor = source.result_descriptor();
+        ensure!(
+            in_desc.number_of_bands() == 1,
+            error::InvalidOperatorSpec {
+                reason: "multiband rasters cannot be reprojected".to_string()
+            }
+        );
+
         let out_no_data_value = in_desc.no_data_value.unwrap_or(0.); // TODO: add option to force a no_data_value
 
         let out_desc = RasterResultDescriptor {
-            spatial_reference: self.params.target_spatial_reference.into(),
+            spatial_reference: params.target_spatial_reference.into(),
             data_type: in_desc.data_type,
             measurement: in_desc.measurement.clone(),
             no_data_value: Some(out_no_data_value),
+            bands: Vec::new(),
         };
 
         let state = RasterReprojectionState {
             source_srs: Option::from(in_desc.spatial_reference).unwrap(),
-            target_srs: self.params.target_spatial_reference,
-            tiling_spec: context.tiling_specification(),
+            target_srs: params.target_spatial_reference,
+            transformation: params.transformation,
+            tiling_spec,
             out_no_data_value,
+            resampling: params.resampling,
         };
 
-        let initialized_operator = InitializedRasterReprojection {
+        Ok(Self {
             result_descriptor: out_desc,
-            source: raster_operator,
+            source,
             state,
-        };
-
-        Ok(initialized_operator.boxed())
+        })
     }
 }
 
@@ -267,7 +330,7 @@ impl InitializedRasterOperator for InitializedRasterReprojection {
     fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
         let q = self.source.query_processor()?;
 
-        let s = self.state;
+        let s = &self.state;
 
         Ok(match self.result_descriptor.data_type {
             geoengine_datatypes::raster::RasterDataType::U8 => {
@@ -276,8 +339,10 @@ impl InitializedRasterOperator for InitializedRasterReprojection {
                     qt,
                     s.source_srs,
                     s.target_srs,
+                    s.transformation.clone(),
                     s.tiling_spec,
                     s.out_no_data_value.as_(),
+                    s.resampling,
                 )))
             }
             geoengine_datatypes::raster::RasterDataType::U16 => {
@@ -286,8 +351,10 @@ impl InitializedRasterOperator for InitializedRasterReprojection {
                     qt,
                     s.source_srs,
                     s.target_srs,
+                    s.transformation.clone(),
                     s.tiling_spec,
                     s.out_no_data_value.as_(),
+                    s.resampling,
                 )))
             }
 
@@ -297,8 +364,10 @@ impl InitializedRasterOperator for InitializedRasterReprojection {
                     qt,
                     s.source_srs,
                     s.target_srs,
+                    s.transformation.clone(),
                     s.tiling_spec,
                     s.out_no_data_value.as_(),
+                    s.resampling,
                 )))
             }
             geoengine_datatypes::raster::RasterDataType::U64 => {
@@ -307,8 +376,10 @@ impl InitializedRasterOperator for InitializedRasterReprojection {
                     qt,
                     s.source_srs,
                     s.target_srs,
+                    s.transformation.clone(),
                     s.tiling_spec,
                     s.out_no_data_value.as_(),
+                    s.resampling,
                 )))
             }
             geoengine_datatypes::raster::RasterDataType::I8 => {
@@ -317,8 +388,10 @@ impl InitializedRasterOperator for InitializedRasterReprojection {
                     qt,
                     s.source_srs,
                     s.target_srs,
+                    s.transformation.clone(),
                     s.tiling_spec,
                     s.out_no_data_value.as_(),
+                    s.resampling,
                 )))
             }
             geoengine_datatypes::raster::RasterDataType::I16 => {
@@ -327,8 +400,10 @@ impl InitializedRasterOperator for InitializedRasterReprojection {
                     qt,
                     s.source_srs,
                     s.target_srs,
+                    s.transformation.clone(),
                     s.tiling_spec,
                     s.out_no_data_value.as_(),
+                    s.resampling,
                 )))
             }
             geoengine_datatypes::raster::RasterDataType::I32 => {
@@ -337,8 +412,10 @@ impl InitializedRasterOperator for InitializedRasterReprojection {
                     qt,
                     s.source_srs,
                     s.target_srs,
+                    s.transformation.clone(),
                     s.tiling_spec,
                     s.out_no_data_value.as_(),
+                    s.resampling,
                 )))
             }
             geoengine_datatypes::raster::RasterDataType::I64 => {
@@ -347,8 +424,10 @@ impl InitializedRasterOperator for InitializedRasterReprojection {
                     qt,
                     s.source_srs,
                     s.target_srs,
+                    s.transformation.clone(),
                     s.tiling_spec,
                     s.out_no_data_value.as_(),
+                    s.resampling,
                 )))
             }
             geoengine_datatypes::raster::RasterDataType::F32 => {
@@ -357,8 +436,10 @@ impl InitializedRasterOperator for InitializedRasterReprojection {
                     qt,
                     s.source_srs,
                     s.target_srs,
+                    s.transformation.clone(),
                     s.tiling_spec,
                     s.out_no_data_value.as_(),
+                    s.resampling,
                 )))
             }
             geoengine_datatypes::raster::RasterDataType::F64 => {
@@ -367,8 +448,10 @@ impl InitializedRasterOperator for InitializedRasterReprojection {
                     qt,
                     s.source_srs,
                     s.target_srs,
+                    s.transformation.clone(),
                     s.tiling_spec,
                     s.out_no_data_value.as_(),
+                    s.resampling,
                 )))
             }
         })
@@ -382,8 +465,10 @@ where
     source: Q,
     from: SpatialReference,
     to: SpatialReference,
+    transformation: Option<CoordinateTransformation>,
     tiling_spec: TilingSpecification,
     no_data_and_fill_value: P,
+    resampling: ResamplingMethod,
 }
 
 impl<Q, P> RasterReprojectionProcessor<Q, P>
@@ -394,15 +479,19 @@ where
         source: Q,
         from: SpatialReference,
         to: SpatialReference,
+        transformation: Option<CoordinateTransformation>,
         tiling_spec: TilingSpecification,
         no_data_and_fill_value: P,
+        resampling: ResamplingMethod,
     ) -> Self {
         Self {
             source,
             from,
             to,
+            transformation,
             tiling_spec,
             no_data_and_fill_value,
+            resampling,
         }
     }
 }
@@ -423,7 +512,12 @@ where
     ) -> Result<BoxStream<'a, Result<Self::Output>>> {
         // we need a resolution for the sub-querys. And since we don't want this to change for tiles, we precompute it for the complete bbox and pass it to the sub-query spec.
         // TODO: use `rewrite_query` to determine resolution for tiles overlapping border
-        let projector = CoordinateProjector::from_known_srs(self.to, self.from)?;
+        let projector = CoordinateProjector::from_known_srs_with_transformation(
+            self.from,
+            self.to,
+            self.transformation.clone(),
+        )?
+        .inverse()?;
         let p_spatial_resolution = suggest_pixel_size_from_diag_cross(
             query.spatial_bounds,
             query.spatial_resolution,
@@ -433,9 +527,11 @@ where
         let sub_query_spec = TileReprojectionSubQuery {
             in_srs: self.from,
             out_srs: self.to,
+            transformation: self.transformation.clone(),
             no_data_and_fill_value: self.no_data_and_fill_value,
             fold_fn: fold_by_coordinate_lookup_future,
             in_spatial_res: p_spatial_resolution,
+            resampling: self.resampling,
         };
         let s = RasterSubQueryAdapter::<'a, P, _, _>::new(
             &self.source,
@@ -458,7 +554,10 @@ mod tests {
         util::gdal::add_ndvi_dataset,
     };
     use geoengine_datatypes::{
-        collections::{MultiLineStringCollection, MultiPointCollection, MultiPolygonCollection},
+        collections::{
+            GeometryCollection, MultiLineStringCollection, MultiPointCollection,
+            MultiPolygonCollection,
+        },
         primitives::{
             AxisAlignedRectangle, BoundingBox2D, Measurement, MultiLineString, MultiPoint,
             MultiPolygon, SpatialPartition2D, SpatialResolution, TimeInterval,
@@ -510,6 +609,8 @@ mod tests {
         let initialized_operator = VectorOperator::boxed(Reprojection {
             params: ReprojectionParams {
                 target_spatial_reference,
+                resampling: ResamplingMethod::Nearest,
+                transformation: None,
             },
             sources: SingleRasterOrVectorSource {
                 source: point_source.into(),
@@ -547,6 +648,80 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn multi_point_with_pipeline() -> Result<()> {
+        let points = MultiPointCollection::from_data(
+            MultiPoint::many(vec![MARBURG_EPSG_4326, COLOGNE_EPSG_4326]).unwrap(),
+            vec![TimeInterval::new_unchecked(0, 1); 2],
+            Default::default(),
+        )?;
+
+        let params: ReprojectionParams = serde_json::from_value(serde_json::json!({
+            "targetSpatialReference": "EPSG:900913",
+            "transformation": {
+                "type": "pipeline",
+                "definition": "+proj=pipeline +step +proj=unitconvert +xy_in=deg +xy_out=rad +step +proj=webmerc"
+            }
+        }))
+        .unwrap();
+
+        let initialized_operator = VectorOperator::boxed(Reprojection {
+            params,
+            sources: SingleRasterOrVectorSource {
+                source: MockFeatureCollectionSource::single(points).boxed().into(),
+            },
+        })
+        .initialize(&MockExecutionContext::default())
+        .await?;
+
+        let query_processor = initialized_operator
+            .query_processor()?
+            .multi_point()
+            .unwrap();
+
+        let query_rectangle = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new(
+                (COLOGNE_EPSG_900_913.x, MARBURG_EPSG_900_913.y).into(),
+                (MARBURG_EPSG_900_913.x, COLOGNE_EPSG_900_913.y).into(),
+            )
+            .unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        };
+        let ctx = MockQueryContext::new(usize::MAX);
+
+        let result = query_processor
+            .query(query_rectangle, &ctx)
+            .await?
+            .map(Result::unwrap)
+            .collect::<Vec<MultiPointCollection>>()
+            .await;
+
+        assert_eq!(result.len(), 1);
+
+        let coordinates = result[0].coordinates();
+        assert_eq!(coordinates.len(), 2);
+        for (projected, expected) in coordinates
+            .iter()
+            .zip(&[MARBURG_EPSG_900_913, COLOGNE_EPSG_900_913])
+        {
+            assert!(float_cmp::approx_eq!(
+                f64,
+                projected.x,
+                expected.x,
+                epsilon = 0.000_01
+            ));
+            assert!(float_cmp::approx_eq!(
+                f64,
+                projected.y,
+                expected.y,
+                epsilon = 0.000_01
+            ));
+        }
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn multi_lines() -> Result<()> {
         let lines = MultiLineStringCollection::from_data(
@@ -579,6 +754,8 @@ mod tests {
         let initialized_operator = VectorOperator::boxed(Reprojection {
             params: ReprojectionParams {
                 target_spatial_reference,
+                resampling: ResamplingMethod::Nearest,
+                transformation: None,
             },
             sources: SingleRasterOrVectorSource {
                 source: lines_source.into(),
@@ -650,6 +827,8 @@ mod tests {
         let initialized_operator = VectorOperator::boxed(Reprojection {
             params: ReprojectionParams {
                 target_spatial_reference,
+                resampling: ResamplingMethod::Nearest,
+                transformation: None,
             },
             sources: SingleRasterOrVectorSource {
                 source: polygon_source.into(),
@@ -743,6 +922,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    bands: Vec::new(),
                 },
             },
         }
@@ -754,13 +934,13 @@ mod tests {
             shape_array: [2, 2],
         };
 
-        let query_ctx = MockQueryContext {
-            chunk_byte_size: 1024 * 1024,
-        };
+        let query_ctx = MockQueryContext::new(1024 * 1024);
 
         let initialized_operator = RasterOperator::boxed(Reprojection {
             params: ReprojectionParams {
                 target_spatial_reference: projection, // This test will do a identity reprojhection
+                resampling: ResamplingMethod::Nearest,
+                transformation: None,
             },
             sources: SingleRasterOrVectorSource {
                 source: mrs1.into(),
@@ -792,6 +972,151 @@ mod tests {
         Ok(())
     }
 
+    async fn reproject_u8_tiles_identically(
+        data: Vec<RasterTile2D<u8>>,
+        resampling: ResamplingMethod,
+        spatial_resolution: SpatialResolution,
+    ) -> Result<Vec<RasterTile2D<u8>>> {
+        let mrs = MockRasterSource {
+            params: MockRasterSourceParams {
+                data,
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: Some(0.),
+                    bands: Vec::new(),
+                },
+            },
+        }
+        .boxed();
+
+        let mut exe_ctx = MockExecutionContext::default();
+        exe_ctx.tiling_specification.tile_size_in_pixels = GridShape {
+            shape_array: [2, 2],
+        };
+
+        let initialized_operator = RasterOperator::boxed(Reprojection {
+            params: ReprojectionParams {
+                target_spatial_reference: SpatialReference::epsg_4326(),
+                resampling,
+                transformation: None,
+            },
+            sources: SingleRasterOrVectorSource { source: mrs.into() },
+        })
+        .initialize(&exe_ctx)
+        .await?;
+
+        let qp = initialized_operator
+            .query_processor()
+            .unwrap()
+            .get_u8()
+            .unwrap();
+
+        let query_rect = RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new_unchecked((0., 2.).into(), (4., 0.).into()),
+            time_interval: TimeInterval::new_unchecked(0, 5),
+            spatial_resolution,
+        };
+
+        let query_ctx = MockQueryContext::new(1024 * 1024);
+
+        Ok(qp
+            .raster_query(query_rect, &query_ctx)
+            .await?
+            .map(Result::unwrap)
+            .collect()
+            .await)
+    }
+
+    #[tokio::test]
+    async fn raster_identity_bilinear() -> Result<()> {
+        let data = vec![
+            RasterTile2D {
+                time: TimeInterval::new_unchecked(0, 5),
+                tile_position: [-1, 0].into(),
+                global_geo_transform: Default::default(),
+                grid_array: Grid::new([2, 2].into(), vec![1, 2, 3, 4], Some(0))
+                    .unwrap()
+                    .into(),
+                properties: Default::default(),
+            },
+            RasterTile2D {
+                time: TimeInterval::new_unchecked(0, 5),
+                tile_position: [-1, 1].into(),
+                global_geo_transform: Default::default(),
+                grid_array: Grid::new([2, 2].into(), vec![7, 8, 9, 10], Some(0))
+                    .unwrap()
+                    .into(),
+                properties: Default::default(),
+            },
+        ];
+
+        // the pixel centers coincide, so the interpolation reproduces the input
+        let res = reproject_u8_tiles_identically(
+            data.clone(),
+            ResamplingMethod::Bilinear,
+            SpatialResolution::one(),
+        )
+        .await?;
+
+        assert_eq!(data, res);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn raster_downsampling_average() -> Result<()> {
+        let data = vec![
+            RasterTile2D {
+                time: TimeInterval::new_unchecked(0, 5),
+                tile_position: [-1, 0].into(),
+                global_geo_transform: Default::default(),
+                grid_array: Grid::new([2, 2].into(), vec![2, 4, 6, 8], Some(0))
+                    .unwrap()
+                    .into(),
+                properties: Default::default(),
+            },
+            RasterTile2D {
+                time: TimeInterval::new_unchecked(0, 5),
+                tile_position: [-1, 1].into(),
+                global_geo_transform: Default::default(),
+                grid_array: Grid::new([2, 2].into(), vec![7, 8, 9, 0], Some(0))
+                    .unwrap()
+                    .into(),
+                properties: Default::default(),
+            },
+        ];
+
+        let res = reproject_u8_tiles_identically(
+            data.clone(),
+            ResamplingMethod::Average,
+            SpatialResolution::new_unchecked(2., 2.),
+        )
+        .await?;
+
+        assert_eq!(res.len(), 1);
+        // the upper half of the output tile lies outside of the query and no data is ignored in the mean
+        assert_eq!(
+            res[0].clone().into_materialized_tile().grid_array.data,
+            vec![0, 0, 5, 8]
+        );
+
+        let res = reproject_u8_tiles_identically(
+            data,
+            ResamplingMethod::Nearest,
+            SpatialResolution::new_unchecked(2., 2.),
+        )
+        .await?;
+
+        assert_eq!(
+            res[0].clone().into_materialized_tile().grid_array.data,
+            vec![0, 0, 2, 7]
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn raster_ndvi_3857() -> Result<()> {
         let mut exe_ctx = MockExecutionContext::default();
@@ -821,6 +1146,8 @@ mod tests {
         let initialized_operator = RasterOperator::boxed(Reprojection {
             params: ReprojectionParams {
                 target_spatial_reference: projection,
+                resampling: ResamplingMethod::Nearest,
+                transformation: None,
             },
             sources: SingleRasterOrVectorSource {
                 source: gdal_op.into(),
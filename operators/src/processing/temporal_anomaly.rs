@@ -0,0 +1,444 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::Datelike;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::primitives::{
+    Measurement, SpatialPartition2D, SpatialPartitioned, SpatialResolution, TimeInterval,
+};
+use geoengine_datatypes::raster::{
+    EmptyGrid2D, Grid2D, GridOrEmpty, GridSize, NoDataValue, Pixel, RasterDataType, RasterTile2D,
+    TileInformation,
+};
+use num_traits::AsPrimitive;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::engine::{
+    ExecutionContext, InitializedRasterOperator, Operator, QueryContext, QueryProcessor,
+    RasterOperator, RasterQueryProcessor, RasterQueryRectangle, RasterResultDescriptor,
+    SingleRasterSource, TypedRasterQueryProcessor,
+};
+use crate::error;
+use crate::util::number_statistics::NumberStatistics;
+use crate::util::Result;
+
+/// An operator that computes the anomaly of each pixel as its z-score w.r.t. the climatology
+/// of its source.
+///
+/// The climatology is the per-pixel mean and standard deviation of the source within the
+/// `referencePeriod`. It is queried from the source once per tile position and month of a query.
+/// The output is no data if the pixel has no data or its climatology has less than two values
+/// or no variation.
+pub type TemporalAnomaly = Operator<TemporalAnomalyParams, SingleRasterSource>;
+
+/// The parameter spec for `TemporalAnomaly`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TemporalAnomalyParams {
+    pub reference_period: TimeInterval,
+    #[serde(default)]
+    pub grouping: ClimatologyGrouping,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ClimatologyGrouping {
+    /// Compare with all values of the reference period
+    Total,
+    /// Compare only with the values of the same calendar month, e.g., to remove seasonal effects
+    Monthly,
+}
+
+impl Default for ClimatologyGrouping {
+    fn default() -> Self {
+        ClimatologyGrouping::Total
+    }
+}
+
+impl ClimatologyGrouping {
+    fn group(self, time: TimeInterval) -> Option<u32> {
+        match self {
+            ClimatologyGrouping::Total => None,
+            ClimatologyGrouping::Monthly => time.start().as_utc_date_time().map(|t| t.month()),
+        }
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for TemporalAnomaly {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let source = self.sources.raster.initialize(context).await?;
+
+        ensure!(
+            source.result_descriptor().number_of_bands() == 1,
+            error::InvalidOperatorSpec {
+                reason: "multiband rasters cannot be compared to a reference period".to_string()
+            }
+        );
+
+        let result_descriptor = RasterResultDescriptor {
+            data_type: RasterDataType::F64,
+            measurement: Measurement::Unitless,
+            no_data_value: Some(f64::NAN),
+            ..source.result_descriptor().clone()
+        };
+
+        Ok(InitializedTemporalAnomaly {
+            result_descriptor,
+            source,
+            params: self.params,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedTemporalAnomaly {
+    result_descriptor: RasterResultDescriptor,
+    source: Box<dyn InitializedRasterOperator>,
+    params: TemporalAnomalyParams,
+}
+
+impl InitializedRasterOperator for InitializedTemporalAnomaly {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let source = self.source.query_processor()?;
+
+        Ok(TypedRasterQueryProcessor::F64(
+            call_on_generic_raster_processor!(source, source => TemporalAnomalyProcessor {
+                source,
+                reference_period: self.params.reference_period,
+                grouping: self.params.grouping,
+            }
+            .boxed()),
+        ))
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct TemporalAnomalyProcessor<T> {
+    source: Box<dyn RasterQueryProcessor<RasterType = T>>,
+    reference_period: TimeInterval,
+    grouping: ClimatologyGrouping,
+}
+
+/// The mean and standard deviation of each pixel of a tile
+type Climatology = Vec<(f64, f64)>;
+
+/// The climatologies of a query by tile position and group
+type ClimatologyCache = Mutex<HashMap<([isize; 2], Option<u32>), Arc<Climatology>>>;
+
+impl<T> TemporalAnomalyProcessor<T>
+where
+    T: Pixel,
+{
+    async fn climatology(
+        &self,
+        tile_info: TileInformation,
+        group: Option<u32>,
+        spatial_resolution: SpatialResolution,
+        ctx: &dyn QueryContext,
+    ) -> Result<Climatology> {
+        let query = RasterQueryRectangle {
+            spatial_bounds: tile_info.spatial_partition(),
+            time_interval: self.reference_period,
+            spatial_resolution,
+        };
+
+        let mut statistics =
+            vec![NumberStatistics::default(); tile_info.tile_size_in_pixels.number_of_elements()];
+
+        let mut tiles = self.source.raster_query(query, ctx).await?;
+        while let Some(tile) = tiles.next().await {
+            let tile = tile?;
+
+            if tile.tile_position != tile_info.global_tile_position
+                || self.grouping.group(tile.time) != group
+            {
+                continue;
+            }
+
+            match &tile.grid_array {
+                GridOrEmpty::Grid(grid) => {
+                    for (pixel_statistics, &value) in statistics.iter_mut().zip(&grid.data) {
+                        if grid.is_no_data(value) {
+                            pixel_statistics.add_no_data();
+                        } else {
+                            pixel_statistics.add(value);
+                        }
+                    }
+                }
+                GridOrEmpty::Empty(_) => statistics
+                    .iter_mut()
+                    .for_each(NumberStatistics::add_no_data),
+            }
+        }
+
+        Ok(statistics
+            .iter()
+            .map(|pixel_statistics| (pixel_statistics.mean(), pixel_statistics.sample_std_dev()))
+            .collect())
+    }
+
+    async fn anomaly_tile(
+        &self,
+        tile: RasterTile2D<T>,
+        cache: &ClimatologyCache,
+        spatial_resolution: SpatialResolution,
+        ctx: &dyn QueryContext,
+    ) -> Result<RasterTile2D<f64>> {
+        let tile_info = tile.tile_information();
+
+        let grid = match &tile.grid_array {
+            GridOrEmpty::Grid(grid) => {
+                let key = (*tile.tile_position.inner(), self.grouping.group(tile.time));
+
+                let cached = cache
+                    .lock()
+                    .expect("climatologies must be accessible")
+                    .get(&key)
+                    .cloned();
+
+                let climatology = match cached {
+                    Some(climatology) => climatology,
+                    None => {
+                        let climatology = Arc::new(
+                            self.climatology(tile_info, key.1, spatial_resolution, ctx)
+                                .await?,
+                        );
+                        cache
+                            .lock()
+                            .expect("climatologies must be accessible")
+                            .insert(key, climatology.clone());
+                        climatology
+                    }
+                };
+
+                let data = grid
+                    .data
+                    .iter()
+                    .zip(climatology.iter())
+                    .map(|(&value, &(mean, std_dev))| {
+                        if grid.is_no_data(value) {
+                            return f64::NAN;
+                        }
+
+                        let z_score = (value.as_() - mean) / std_dev;
+                        if z_score.is_finite() {
+                            z_score
+                        } else {
+                            f64::NAN
+                        }
+                    })
+                    .collect();
+
+                Grid2D::new(tile_info.tile_size_in_pixels, data, Some(f64::NAN))?.into()
+            }
+            GridOrEmpty::Empty(_) => {
+                EmptyGrid2D::new(tile_info.tile_size_in_pixels, f64::NAN).into()
+            }
+        };
+
+        Ok(RasterTile2D::new_with_tile_info(tile.time, tile_info, grid))
+    }
+}
+
+#[async_trait]
+impl<T> QueryProcessor for TemporalAnomalyProcessor<T>
+where
+    T: Pixel,
+{
+    type Output = RasterTile2D<f64>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let cache = Arc::new(ClimatologyCache::default());
+
+        let stream = self
+            .source
+            .raster_query(query, ctx)
+            .await?
+            .and_then(move |tile| {
+                let cache = cache.clone();
+                async move {
+                    self.anomaly_tile(tile, &cache, query.spatial_resolution, ctx)
+                        .await
+                }
+            });
+
+        Ok(stream.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::primitives::TimeInstance;
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+
+    const DAY: i64 = 24 * 60 * 60 * 1000;
+
+    fn tile(time: TimeInterval, values: Vec<u8>) -> RasterTile2D<u8> {
+        RasterTile2D::new_with_tile_info(
+            time,
+            TileInformation {
+                global_geo_transform: Default::default(),
+                global_tile_position: [-1, 0].into(),
+                tile_size_in_pixels: [1, 2].into(),
+            },
+            Grid2D::new([1, 2].into(), values, Some(0)).unwrap().into(),
+        )
+    }
+
+    /// Daily values from January 1st, 1970
+    fn daily_tiles(values: &[[u8; 2]]) -> Vec<RasterTile2D<u8>> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(day, values)| {
+                let start = day as i64 * DAY;
+                tile(
+                    TimeInterval::new_unchecked(start, start + DAY),
+                    values.to_vec(),
+                )
+            })
+            .collect()
+    }
+
+    async fn anomalies(
+        data: Vec<RasterTile2D<u8>>,
+        params: TemporalAnomalyParams,
+        time_interval: TimeInterval,
+    ) -> Vec<Vec<f64>> {
+        let processor = TemporalAnomaly {
+            params,
+            sources: SingleRasterSource {
+                raster: MockRasterSource {
+                    params: MockRasterSourceParams {
+                        data,
+                        result_descriptor: RasterResultDescriptor {
+                            data_type: RasterDataType::U8,
+                            spatial_reference: SpatialReference::epsg_4326().into(),
+                            measurement: Measurement::Unitless,
+                            no_data_value: Some(0.),
+                            bands: Vec::new(),
+                        },
+                    },
+                }
+                .boxed(),
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .get_f64()
+        .unwrap();
+
+        let tiles: Vec<RasterTile2D<f64>> = processor
+            .raster_query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new_unchecked(
+                        (0., 1.).into(),
+                        (2., 0.).into(),
+                    ),
+                    time_interval,
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        tiles
+            .into_iter()
+            .map(|tile| tile.grid_array.into_materialized_grid().data.into_vec())
+            .collect()
+    }
+
+    #[tokio::test]
+    #[allow(clippy::float_cmp)]
+    async fn z_scores() {
+        let data = daily_tiles(&[[2, 5], [4, 5], [6, 0], [8, 9], [0, 1]]);
+
+        let result = anomalies(
+            data,
+            TemporalAnomalyParams {
+                reference_period: TimeInterval::new_unchecked(0, 3 * DAY),
+                grouping: ClimatologyGrouping::Total,
+            },
+            TimeInterval::new_unchecked(3 * DAY, 5 * DAY),
+        )
+        .await;
+
+        assert_eq!(result.len(), 2);
+
+        // the reference values of the first pixel have mean 4 and standard deviation 2
+        assert_eq!(result[0][0], 2.);
+        // the reference values of the second pixel have no variation
+        assert!(result[0][1].is_nan());
+        assert!(result[1][0].is_nan());
+        assert!(result[1][1].is_nan());
+    }
+
+    #[tokio::test]
+    async fn monthly_climatology() {
+        let time = |year, month| -> TimeInstance {
+            chrono::NaiveDate::from_ymd(year, month, 1)
+                .and_hms(0, 0, 0)
+                .into()
+        };
+        let month =
+            |year, month| TimeInterval::new_unchecked(time(year, month), time(year, month) + DAY);
+
+        let data = vec![
+            tile(month(2000, 1), vec![1, 1]),
+            tile(month(2000, 7), vec![10, 10]),
+            tile(month(2001, 1), vec![3, 3]),
+            tile(month(2001, 7), vec![20, 20]),
+            tile(month(2002, 1), vec![4, 1]),
+            tile(month(2002, 7), vec![20, 5]),
+        ];
+
+        let result = anomalies(
+            data,
+            TemporalAnomalyParams {
+                reference_period: TimeInterval::new_unchecked(time(2000, 1), time(2002, 1)),
+                grouping: ClimatologyGrouping::Monthly,
+            },
+            TimeInterval::new_unchecked(time(2002, 1), time(2003, 1)),
+        )
+        .await;
+
+        let std_dev_january = f64::sqrt(2.);
+        let std_dev_july = f64::sqrt(50.);
+
+        assert_eq!(
+            result,
+            vec![
+                vec![2. / std_dev_january, -1. / std_dev_january],
+                vec![5. / std_dev_july, -10. / std_dev_july],
+            ]
+        );
+    }
+}
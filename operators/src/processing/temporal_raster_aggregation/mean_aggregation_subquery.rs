@@ -164,7 +164,7 @@ where
 
         let res_grid = Grid2D {
             shape: value_grid.shape,
-            data: res,
+            data: res.into(),
             no_data_value: Some(out_no_data_value),
         };
 
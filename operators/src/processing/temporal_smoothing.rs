@@ -0,0 +1,421 @@
+use crate::adapters::{raster_time_windows, RasterTimeWindow};
+use crate::engine::{
+    ExecutionContext, InitializedRasterOperator, Operator, QueryContext, QueryProcessor,
+    RasterOperator, RasterQueryProcessor, RasterQueryRectangle, RasterResultDescriptor,
+    SingleRasterSource, TypedRasterQueryProcessor,
+};
+use crate::error;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::primitives::SpatialPartition2D;
+use geoengine_datatypes::raster::{
+    EmptyGrid, Grid2D, GridOrEmpty, NoDataValue, Pixel, RasterTile2D,
+};
+use num_traits::AsPrimitive;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+/// The `TemporalSmoothing` operator aggregates each pixel of its source over a window of time steps that is centered on the pixel's time step,
+/// e.g., to compute a moving average. It emits one tile for each tile of its source with the validity of the source tile.
+///
+/// The windows at the temporal borders of the source contain fewer time steps.
+/// The output has the data type of the input, so the means are truncated for integer rasters.
+pub type TemporalSmoothing = Operator<TemporalSmoothingParams, SingleRasterSource>;
+
+/// The parameters of the `TemporalSmoothing` operator
+/// * `method` is the aggregation of the pixels of a window
+/// * `window` is the number of time steps of a window, which must be odd s.t. the window is centered on the time step
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TemporalSmoothingParams {
+    pub method: TemporalSmoothingMethod,
+    pub window: usize,
+}
+
+/// How the `TemporalSmoothing` aggregates the pixels of a window.
+/// No data pixels are left out and a window without any data results in no data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum TemporalSmoothingMethod {
+    Mean,
+    Median,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for TemporalSmoothing {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        ensure!(self.params.window > 0, error::WindowSizeMustNotBeZero);
+        ensure!(
+            self.params.window % 2 == 1,
+            error::InvalidOperatorSpec {
+                reason: "the window must have an odd number of time steps".to_string()
+            }
+        );
+
+        let source = self.sources.raster.initialize(context).await?;
+
+        let in_desc = source.result_descriptor();
+        ensure!(
+            in_desc.number_of_bands() == 1,
+            error::InvalidOperatorSpec {
+                reason: "multiband rasters cannot be smoothed".to_string()
+            }
+        );
+
+        let no_data_value = in_desc.no_data_value.unwrap_or(0.); // TODO: add option to force a no_data_value
+
+        let result_descriptor = RasterResultDescriptor {
+            no_data_value: Some(no_data_value),
+            ..in_desc.clone()
+        };
+
+        Ok(InitializedTemporalSmoothing {
+            result_descriptor,
+            source,
+            method: self.params.method,
+            radius: self.params.window / 2,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedTemporalSmoothing {
+    result_descriptor: RasterResultDescriptor,
+    source: Box<dyn InitializedRasterOperator>,
+    method: TemporalSmoothingMethod,
+    radius: usize,
+}
+
+impl InitializedRasterOperator for InitializedTemporalSmoothing {
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let source_processor = self.source.query_processor()?;
+        let no_data_value = self.result_descriptor.no_data_value.unwrap_or_default();
+
+        Ok(call_on_generic_raster_processor!(
+            source_processor, p => TemporalSmoothingProcessor {
+                source: p,
+                method: self.method,
+                radius: self.radius,
+                no_data_value: no_data_value.as_(),
+            }
+            .boxed()
+            .into()
+        ))
+    }
+}
+
+pub struct TemporalSmoothingProcessor<P: Pixel> {
+    source: Box<dyn RasterQueryProcessor<RasterType = P>>,
+    method: TemporalSmoothingMethod,
+    radius: usize,
+    no_data_value: P,
+}
+
+#[async_trait]
+impl<P> QueryProcessor for TemporalSmoothingProcessor<P>
+where
+    P: Pixel,
+{
+    type Output = RasterTile2D<P>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        Ok(
+            raster_time_windows(self.source.as_ref(), query, ctx, self.radius)
+                .and_then(move |window| async move { self.smooth(&window) })
+                .map_ok(|tiles| stream::iter(tiles.into_iter().map(Ok)))
+                .try_flatten()
+                .boxed(),
+        )
+    }
+}
+
+impl<P: Pixel> TemporalSmoothingProcessor<P> {
+    /// Aggregates the tiles of the center time slice of the `window` with the tiles at the same positions in the other slices
+    fn smooth(&self, window: &RasterTimeWindow<P>) -> Result<Vec<RasterTile2D<P>>> {
+        let center = window.center_slice();
+
+        center
+            .tiles
+            .iter()
+            .enumerate()
+            .map(|(index, tile)| {
+                let mut grids = Vec::with_capacity(window.slices.len());
+
+                for slice in &window.slices {
+                    let other = slice.tiles.get(index).filter(|other| {
+                        other.tile_position == tile.tile_position
+                            && other.properties.band_name == tile.properties.band_name
+                    });
+
+                    match other {
+                        Some(other) => {
+                            if let GridOrEmpty::Grid(grid) = &other.grid_array {
+                                grids.push(grid);
+                            }
+                        }
+                        None => {
+                            return Err(error::Error::UnalignedRasterTiles {
+                                position_a: tile.tile_position,
+                                time_a: tile.time,
+                                position_b: slice
+                                    .tiles
+                                    .get(index)
+                                    .map_or(tile.tile_position, |other| other.tile_position),
+                                time_b: slice.time,
+                            })
+                        }
+                    }
+                }
+
+                let grid = if grids.is_empty() {
+                    EmptyGrid::new(*tile.grid_array.shape_ref(), self.no_data_value).into()
+                } else {
+                    self.aggregate(&grids).into()
+                };
+
+                Ok(RasterTile2D::new_with_tile_info_and_properties(
+                    tile.time,
+                    tile.tile_information(),
+                    grid,
+                    tile.properties.clone(),
+                ))
+            })
+            .collect()
+    }
+
+    fn aggregate(&self, grids: &[&Grid2D<P>]) -> Grid2D<P> {
+        let mut values: Vec<f64> = Vec::with_capacity(grids.len());
+
+        let data = (0..grids[0].data.len())
+            .map(|pixel| {
+                values.clear();
+                values.extend(grids.iter().filter_map(|grid| {
+                    let value = grid.data[pixel];
+                    if grid.is_no_data(value) {
+                        None
+                    } else {
+                        Some(value.as_())
+                    }
+                }));
+
+                self.method
+                    .aggregate(&mut values)
+                    .map_or(self.no_data_value, P::from_)
+            })
+            .collect();
+
+        Grid2D::new(grids[0].shape, data, Some(self.no_data_value))
+            .expect("the output has the shape of the input")
+    }
+}
+
+impl TemporalSmoothingMethod {
+    fn aggregate(self, values: &mut [f64]) -> Option<f64> {
+        if values.is_empty() {
+            return None;
+        }
+
+        match self {
+            TemporalSmoothingMethod::Mean => Some(values.iter().sum::<f64>() / values.len() as f64),
+            TemporalSmoothingMethod::Median => {
+                values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+                let middle = values.len() / 2;
+                if values.len() % 2 == 0 {
+                    Some((values[middle - 1] + values[middle]) / 2.)
+                } else {
+                    Some(values[middle])
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::primitives::{Measurement, SpatialResolution, TimeInterval};
+    use geoengine_datatypes::raster::{RasterDataType, TileInformation};
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+
+    fn make_source(values: Vec<Option<Vec<u8>>>) -> Box<dyn RasterOperator> {
+        let data = values
+            .into_iter()
+            .enumerate()
+            .map(|(t, values)| {
+                let t = t as i64 * 10;
+                let grid = match values {
+                    Some(values) => Grid2D::new([1, 2].into(), values, Some(0)).unwrap().into(),
+                    None => EmptyGrid::new([1, 2].into(), 0).into(),
+                };
+
+                RasterTile2D::new_with_tile_info(
+                    TimeInterval::new_unchecked(t, t + 10),
+                    TileInformation {
+                        global_tile_position: [-1, 0].into(),
+                        tile_size_in_pixels: [1, 2].into(),
+                        global_geo_transform: Default::default(),
+                    },
+                    grid,
+                )
+            })
+            .collect();
+
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data,
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: Some(0.),
+                    bands: Vec::new(),
+                },
+            },
+        }
+        .boxed()
+    }
+
+    async fn smooth(
+        source: Box<dyn RasterOperator>,
+        method: TemporalSmoothingMethod,
+        window: usize,
+        time_interval: TimeInterval,
+    ) -> Vec<RasterTile2D<u8>> {
+        let processor = TemporalSmoothing {
+            params: TemporalSmoothingParams { method, window },
+            sources: SingleRasterSource { raster: source },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .get_u8()
+        .unwrap();
+
+        processor
+            .query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new_unchecked(
+                        (0., 1.).into(),
+                        (2., 0.).into(),
+                    ),
+                    time_interval,
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    fn values(tiles: &[RasterTile2D<u8>]) -> Vec<(i64, Vec<u8>)> {
+        tiles
+            .iter()
+            .map(|tile| {
+                (
+                    tile.time.start().inner(),
+                    tile.clone()
+                        .into_materialized_tile()
+                        .grid_array
+                        .data
+                        .to_vec(),
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn it_computes_moving_means() {
+        let source = make_source(vec![
+            Some(vec![1, 10]),
+            Some(vec![2, 0]),
+            Some(vec![6, 20]),
+            None,
+            Some(vec![4, 30]),
+        ]);
+
+        let tiles = smooth(
+            source,
+            TemporalSmoothingMethod::Mean,
+            3,
+            TimeInterval::new_unchecked(0, 50),
+        )
+        .await;
+
+        assert_eq!(
+            values(&tiles),
+            vec![
+                (0, vec![1, 10]),
+                (10, vec![3, 15]),
+                (20, vec![4, 20]),
+                (30, vec![5, 25]),
+                (40, vec![4, 30]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_loads_neighbors_for_medians() {
+        let source = make_source(vec![
+            Some(vec![1, 50]),
+            Some(vec![9, 40]),
+            Some(vec![2, 30]),
+            Some(vec![8, 0]),
+            Some(vec![3, 0]),
+        ]);
+
+        let tiles = smooth(
+            source,
+            TemporalSmoothingMethod::Median,
+            5,
+            TimeInterval::new_unchecked(20, 30),
+        )
+        .await;
+
+        assert_eq!(values(&tiles), vec![(20, vec![3, 40])]);
+    }
+
+    #[tokio::test]
+    async fn it_checks_the_window() {
+        for window in &[0, 2] {
+            let result = TemporalSmoothing {
+                params: TemporalSmoothingParams {
+                    method: TemporalSmoothingMethod::Mean,
+                    window: *window,
+                },
+                sources: SingleRasterSource {
+                    raster: make_source(vec![Some(vec![1, 2])]),
+                },
+            }
+            .boxed()
+            .initialize(&MockExecutionContext::default())
+            .await;
+
+            assert!(result.is_err());
+        }
+    }
+}
@@ -0,0 +1,595 @@
+use crate::engine::{
+    ExecutionContext, InitializedVectorOperator, Operator, QueryContext, QueryProcessor,
+    SingleVectorSource, TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor,
+    VectorQueryRectangle, VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionInfos, FeatureCollectionModifications,
+};
+use geoengine_datatypes::primitives::{BoundingBox2D, FeatureData, FeatureDataType, Geometry};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// An operator that transforms text attributes of features.
+///
+/// The operations are applied in order, i.e., an operation can use the output columns of previous operations.
+/// All output columns are of type `Text` and replace existing columns of the same name.
+pub type TextProcessing = Operator<TextProcessingParams, SingleVectorSource>;
+
+/// The parameter spec for `TextProcessing`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TextProcessingParams {
+    pub operations: Vec<TextOperation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TextOperation {
+    /// Extracts the capture groups of `pattern` into `output_columns`.
+    /// Values that do not match the pattern result in nulls.
+    #[serde(rename_all = "camelCase")]
+    RegexExtract {
+        column: String,
+        pattern: String,
+        output_columns: Vec<String>,
+    },
+    /// Fills the `{column}` placeholders of `template` with the values of the respective columns.
+    /// `{{` and `}}` are literal braces. If any of the values is null, the result is null.
+    #[serde(rename_all = "camelCase")]
+    Concatenate {
+        template: String,
+        output_column: String,
+    },
+    /// Applies `function` to the values of `column`.
+    /// Overwrites `column` if no `output_column` is given.
+    #[serde(rename_all = "camelCase")]
+    Map {
+        column: String,
+        function: TextFunction,
+        #[serde(default)]
+        output_column: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum TextFunction {
+    Upper,
+    Lower,
+    Trim,
+}
+
+impl TextFunction {
+    fn apply(self, value: &str) -> String {
+        match self {
+            TextFunction::Upper => value.to_uppercase(),
+            TextFunction::Lower => value.to_lowercase(),
+            TextFunction::Trim => value.trim().to_string(),
+        }
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for TextProcessing {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let vector_source = self.sources.vector.initialize(context).await?;
+
+        let mut columns = vector_source.result_descriptor().columns.clone();
+
+        let operations = self
+            .params
+            .operations
+            .into_iter()
+            .map(|operation| {
+                let operation = CompiledTextOperation::compile(operation)?;
+                operation.validate_and_add_columns(&mut columns)?;
+                Ok(operation)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let result_descriptor = VectorResultDescriptor {
+            columns,
+            ..vector_source.result_descriptor().clone()
+        };
+
+        Ok(InitializedTextProcessing {
+            result_descriptor,
+            vector_source,
+            operations: Arc::new(operations),
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedTextProcessing {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    operations: Arc<Vec<CompiledTextOperation>>,
+}
+
+impl InitializedVectorOperator for InitializedTextProcessing {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        Ok(map_typed_query_processor!(
+            self.vector_source.query_processor()?,
+            source => TextProcessingProcessor {
+                source,
+                operations: self.operations.clone(),
+            }.boxed()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// A `TextOperation` with its regular expression or template already parsed
+#[derive(Debug)]
+enum CompiledTextOperation {
+    RegexExtract {
+        column: String,
+        regex: Regex,
+        output_columns: Vec<String>,
+    },
+    Concatenate {
+        template: Vec<TemplatePart>,
+        output_column: String,
+    },
+    Map {
+        column: String,
+        function: TextFunction,
+        output_column: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TemplatePart {
+    Literal(String),
+    Column(String),
+}
+
+impl CompiledTextOperation {
+    fn compile(operation: TextOperation) -> Result<Self> {
+        Ok(match operation {
+            TextOperation::RegexExtract {
+                column,
+                pattern,
+                output_columns,
+            } => {
+                let regex = Regex::new(&pattern).context(error::InvalidRegex)?;
+
+                ensure!(
+                    output_columns.len() < regex.captures_len(),
+                    error::InvalidOperatorSpec {
+                        reason: format!(
+                            "pattern `{}` has less capture groups than output columns",
+                            pattern
+                        )
+                    }
+                );
+
+                CompiledTextOperation::RegexExtract {
+                    column,
+                    regex,
+                    output_columns,
+                }
+            }
+            TextOperation::Concatenate {
+                template,
+                output_column,
+            } => CompiledTextOperation::Concatenate {
+                template: parse_template(&template)?,
+                output_column,
+            },
+            TextOperation::Map {
+                column,
+                function,
+                output_column,
+            } => CompiledTextOperation::Map {
+                output_column: output_column.unwrap_or_else(|| column.clone()),
+                column,
+                function,
+            },
+        })
+    }
+
+    fn input_columns(&self) -> Vec<&str> {
+        match self {
+            CompiledTextOperation::RegexExtract { column, .. }
+            | CompiledTextOperation::Map { column, .. } => vec![column.as_str()],
+            CompiledTextOperation::Concatenate { template, .. } => template
+                .iter()
+                .filter_map(|part| match part {
+                    TemplatePart::Column(column) => Some(column.as_str()),
+                    TemplatePart::Literal(_) => None,
+                })
+                .collect(),
+        }
+    }
+
+    fn output_columns(&self) -> Vec<&str> {
+        match self {
+            CompiledTextOperation::RegexExtract { output_columns, .. } => {
+                output_columns.iter().map(String::as_str).collect()
+            }
+            CompiledTextOperation::Concatenate { output_column, .. }
+            | CompiledTextOperation::Map { output_column, .. } => vec![output_column.as_str()],
+        }
+    }
+
+    fn validate_and_add_columns(
+        &self,
+        columns: &mut HashMap<String, FeatureDataType>,
+    ) -> Result<()> {
+        for column in self.input_columns() {
+            let data_type =
+                columns
+                    .get(column)
+                    .ok_or_else(|| error::Error::ColumnDoesNotExist {
+                        column: column.to_string(),
+                    })?;
+
+            // templates can contain columns of any type, the other operations work on text only
+            ensure!(
+                matches!(self, CompiledTextOperation::Concatenate { .. })
+                    || *data_type == FeatureDataType::Text,
+                error::InvalidType {
+                    expected: "text".to_string(),
+                    found: format!("{:?}", data_type),
+                }
+            );
+        }
+
+        for column in self.output_columns() {
+            columns.insert(column.to_string(), FeatureDataType::Text);
+        }
+
+        Ok(())
+    }
+
+    fn apply<C>(&self, collection: &C) -> Result<Vec<(String, Vec<Option<String>>)>>
+    where
+        C: FeatureCollectionInfos,
+    {
+        Ok(match self {
+            CompiledTextOperation::RegexExtract {
+                column,
+                regex,
+                output_columns,
+            } => {
+                let values = text_values(collection, column)?;
+
+                let mut outputs = vec![Vec::with_capacity(values.len()); output_columns.len()];
+
+                for value in values {
+                    let captures = value.as_deref().and_then(|value| regex.captures(value));
+
+                    for (group, output) in outputs.iter_mut().enumerate() {
+                        output.push(
+                            captures
+                                .as_ref()
+                                .and_then(|captures| captures.get(group + 1))
+                                .map(|capture| capture.as_str().to_string()),
+                        );
+                    }
+                }
+
+                output_columns.iter().cloned().zip(outputs).collect()
+            }
+            CompiledTextOperation::Concatenate {
+                template,
+                output_column,
+            } => {
+                let mut inputs = HashMap::new();
+                for column in self.input_columns() {
+                    inputs.insert(column, text_values(collection, column)?);
+                }
+
+                let output = (0..collection.len())
+                    .map(|row| {
+                        template
+                            .iter()
+                            .map(|part| match part {
+                                TemplatePart::Literal(literal) => Some(literal.as_str()),
+                                TemplatePart::Column(column) => {
+                                    inputs[column.as_str()][row].as_deref()
+                                }
+                            })
+                            .collect::<Option<String>>()
+                    })
+                    .collect();
+
+                vec![(output_column.clone(), output)]
+            }
+            CompiledTextOperation::Map {
+                column,
+                function,
+                output_column,
+            } => {
+                let output = text_values(collection, column)?
+                    .into_iter()
+                    .map(|value| value.map(|value| function.apply(&value)))
+                    .collect();
+
+                vec![(output_column.clone(), output)]
+            }
+        })
+    }
+}
+
+/// Parses a template of the form `literal {column} literal`
+fn parse_template(template: &str) -> Result<Vec<TemplatePart>> {
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut column = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => column.push(c),
+                        None => {
+                            return Err(error::Error::InvalidTextTemplate {
+                                reason: format!("unclosed placeholder `{{{}`", column),
+                            })
+                        }
+                    }
+                }
+
+                if !literal.is_empty() {
+                    parts.push(TemplatePart::Literal(std::mem::take(&mut literal)));
+                }
+                parts.push(TemplatePart::Column(column));
+            }
+            '}' => {
+                return Err(error::Error::InvalidTextTemplate {
+                    reason: "unmatched `}`".to_string(),
+                })
+            }
+            c => literal.push(c),
+        }
+    }
+
+    if !literal.is_empty() {
+        parts.push(TemplatePart::Literal(literal));
+    }
+
+    Ok(parts)
+}
+
+/// Returns the values of `column` as strings, or `None` for nulls
+fn text_values<C>(collection: &C, column: &str) -> Result<Vec<Option<String>>>
+where
+    C: FeatureCollectionInfos,
+{
+    let data = collection.data(column)?;
+
+    Ok(data
+        .strings_iter()
+        .zip(data.nulls())
+        .map(|(value, is_null)| if is_null { None } else { Some(value) })
+        .collect())
+}
+
+pub struct TextProcessingProcessor<G> {
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    operations: Arc<Vec<CompiledTextOperation>>,
+}
+
+#[async_trait]
+impl<G> QueryProcessor for TextProcessingProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type Output = FeatureCollection<G>;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let operations = self.operations.clone();
+
+        let stream = self.source.query(query, ctx).await?.map(move |collection| {
+            let mut collection = collection?;
+
+            for operation in operations.iter() {
+                for (column, values) in operation.apply(&collection)? {
+                    if collection.column_type(&column).is_ok() {
+                        collection = collection.remove_column(&column)?;
+                    }
+
+                    collection =
+                        collection.add_column(&column, FeatureData::NullableText(values))?;
+                }
+            }
+
+            Ok(collection)
+        });
+
+        Ok(stream.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{MultiPoint, SpatialResolution, TimeInterval};
+
+    #[test]
+    fn template() {
+        assert_eq!(
+            parse_template("{a}-{b} {{x}}").unwrap(),
+            vec![
+                TemplatePart::Column("a".to_string()),
+                TemplatePart::Literal("-".to_string()),
+                TemplatePart::Column("b".to_string()),
+                TemplatePart::Literal(" {x}".to_string()),
+            ]
+        );
+
+        assert!(parse_template("{a").is_err());
+        assert!(parse_template("a}").is_err());
+    }
+
+    #[tokio::test]
+    async fn execute() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1), (2.0, 2.1)]).unwrap(),
+            vec![TimeInterval::default(); 3],
+            [
+                (
+                    "name".to_string(),
+                    FeatureData::NullableText(vec![
+                        Some(" Station A-12 ".to_string()),
+                        Some("station b-7".to_string()),
+                        None,
+                    ]),
+                ),
+                ("value".to_string(), FeatureData::Int(vec![1, 2, 3])),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+        )
+        .unwrap();
+
+        let operator = TextProcessing {
+            params: TextProcessingParams {
+                operations: vec![
+                    TextOperation::Map {
+                        column: "name".to_string(),
+                        function: TextFunction::Trim,
+                        output_column: None,
+                    },
+                    TextOperation::Map {
+                        column: "name".to_string(),
+                        function: TextFunction::Upper,
+                        output_column: None,
+                    },
+                    TextOperation::RegexExtract {
+                        column: "name".to_string(),
+                        pattern: r"^STATION (\w)-(\d+)$".to_string(),
+                        output_columns: vec!["letter".to_string(), "number".to_string()],
+                    },
+                    TextOperation::Concatenate {
+                        template: "{letter}{number}: {value}".to_string(),
+                        output_column: "label".to_string(),
+                    },
+                ],
+            },
+            sources: MockFeatureCollectionSource::single(collection)
+                .boxed()
+                .into(),
+        }
+        .boxed();
+
+        let initialized = operator
+            .initialize(&MockExecutionContext::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            initialized.result_descriptor().columns.get("label"),
+            Some(&FeatureDataType::Text)
+        );
+
+        let processor = match initialized.query_processor() {
+            Ok(TypedVectorQueryProcessor::MultiPoint(processor)) => processor,
+            _ => panic!(),
+        };
+
+        let query_rectangle = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (4., 4.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::zero_point_one(),
+        };
+
+        let ctx = MockQueryContext::new(usize::MAX);
+
+        let collections: Vec<MultiPointCollection> = processor
+            .query(query_rectangle, &ctx)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(collections.len(), 1);
+
+        assert_eq!(
+            text_values(&collections[0], "name").unwrap(),
+            vec![
+                Some("STATION A-12".to_string()),
+                Some("STATION B-7".to_string()),
+                None
+            ]
+        );
+        assert_eq!(
+            text_values(&collections[0], "label").unwrap(),
+            vec![Some("A12: 1".to_string()), Some("B7: 2".to_string()), None]
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_regex() {
+        let operator = TextProcessing {
+            params: TextProcessingParams {
+                operations: vec![TextOperation::RegexExtract {
+                    column: "name".to_string(),
+                    pattern: "(".to_string(),
+                    output_columns: vec!["x".to_string()],
+                }],
+            },
+            sources: MockFeatureCollectionSource::single(
+                MultiPointCollection::from_data(
+                    MultiPoint::many(vec![(0.0, 0.1)]).unwrap(),
+                    vec![TimeInterval::default()],
+                    [("name".to_string(), FeatureData::Text(vec!["a".to_string()]))]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                )
+                .unwrap(),
+            )
+            .boxed()
+            .into(),
+        }
+        .boxed();
+
+        assert!(matches!(
+            operator.initialize(&MockExecutionContext::default()).await,
+            Err(error::Error::InvalidRegex { .. })
+        ));
+    }
+}
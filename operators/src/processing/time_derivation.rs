@@ -0,0 +1,406 @@
+use crate::engine::{
+    ExecutionContext, InitializedVectorOperator, Operator, QueryContext, QueryProcessor,
+    SingleVectorSource, TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor,
+    VectorQueryRectangle, VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionInfos, FeatureCollectionModifications,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, DataRef, FeatureData, FeatureDataRef, FeatureDataType, Geometry, TimeInstance,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+/// An operator that derives numeric columns, e.g. the year or month, from the time of features.
+pub type TimeDerivation = Operator<TimeDerivationParams, SingleVectorSource>;
+
+/// The parameter spec for `TimeDerivation`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeDerivationParams {
+    #[serde(default)]
+    pub source: TimeDerivationSource,
+    pub columns: Vec<DerivedTimeColumn>,
+}
+
+/// Where to take the date and time from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TimeDerivationSource {
+    /// The start of the feature's validity
+    Start,
+    /// The end of the feature's validity
+    End,
+    /// A column containing either RFC 3339 datetime strings or milliseconds since the Unix epoch
+    Column { column: String },
+}
+
+impl Default for TimeDerivationSource {
+    fn default() -> Self {
+        TimeDerivationSource::Start
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DerivedTimeColumn {
+    pub component: TimeComponent,
+    pub output_column: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum TimeComponent {
+    Year,
+    Month,
+    DayOfYear,
+    Hour,
+}
+
+impl TimeComponent {
+    fn extract(self, date_time: &DateTime<Utc>) -> i64 {
+        i64::from(match self {
+            TimeComponent::Year => date_time.year(),
+            TimeComponent::Month => date_time.month() as i32,
+            TimeComponent::DayOfYear => date_time.ordinal() as i32,
+            TimeComponent::Hour => date_time.hour() as i32,
+        })
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for TimeDerivation {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let vector_source = self.sources.vector.initialize(context).await?;
+
+        let mut columns = vector_source.result_descriptor().columns.clone();
+
+        if let TimeDerivationSource::Column { column } = &self.params.source {
+            let data_type =
+                columns
+                    .get(column)
+                    .ok_or_else(|| error::Error::ColumnDoesNotExist {
+                        column: column.clone(),
+                    })?;
+
+            ensure!(
+                *data_type == FeatureDataType::Text || *data_type == FeatureDataType::Int,
+                error::InvalidFeatureDataType
+            );
+        }
+
+        for column in &self.params.columns {
+            columns.insert(column.output_column.clone(), FeatureDataType::Int);
+        }
+
+        let result_descriptor = VectorResultDescriptor {
+            columns,
+            ..vector_source.result_descriptor().clone()
+        };
+
+        Ok(InitializedTimeDerivation {
+            result_descriptor,
+            vector_source,
+            state: self.params,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedTimeDerivation {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    state: TimeDerivationParams,
+}
+
+impl InitializedVectorOperator for InitializedTimeDerivation {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        Ok(map_typed_query_processor!(
+            self.vector_source.query_processor()?,
+            source => TimeDerivationProcessor {
+                source,
+                params: self.state.clone(),
+            }.boxed()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct TimeDerivationProcessor<G> {
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    params: TimeDerivationParams,
+}
+
+#[async_trait]
+impl<G> QueryProcessor for TimeDerivationProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type Output = FeatureCollection<G>;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let stream = self.source.query(query, ctx).await?.map(move |collection| {
+            let collection = collection?;
+
+            let date_times = date_times(&collection, &self.params.source)?;
+
+            let mut collection = collection;
+            for column in &self.params.columns {
+                let values = date_times
+                    .iter()
+                    .map(|date_time| {
+                        date_time
+                            .as_ref()
+                            .map(|date_time| column.component.extract(date_time))
+                    })
+                    .collect();
+
+                if collection.column_type(&column.output_column).is_ok() {
+                    collection = collection.remove_column(&column.output_column)?;
+                }
+
+                collection = collection
+                    .add_column(&column.output_column, FeatureData::NullableInt(values))?;
+            }
+
+            Ok(collection)
+        });
+
+        Ok(stream.boxed())
+    }
+}
+
+/// Retrieves the date and time of each feature.
+/// Nulls, unparsable values and unbounded validities result in `None`.
+fn date_times<C>(
+    collection: &C,
+    source: &TimeDerivationSource,
+) -> Result<Vec<Option<DateTime<Utc>>>>
+where
+    C: FeatureCollectionInfos,
+{
+    let from_instance = |instance: TimeInstance| {
+        if instance == TimeInstance::MIN || instance == TimeInstance::MAX {
+            None
+        } else {
+            instance.as_utc_date_time()
+        }
+    };
+
+    Ok(match source {
+        TimeDerivationSource::Start => collection
+            .time_intervals()
+            .iter()
+            .map(|time| from_instance(time.start()))
+            .collect(),
+        TimeDerivationSource::End => collection
+            .time_intervals()
+            .iter()
+            .map(|time| from_instance(time.end()))
+            .collect(),
+        TimeDerivationSource::Column { column } => match collection.data(column)? {
+            FeatureDataRef::Int(data) => data
+                .as_ref()
+                .iter()
+                .zip(data.nulls())
+                .map(|(&millis, is_null)| {
+                    if is_null {
+                        None
+                    } else {
+                        TimeInstance::from_millis(millis)
+                            .ok()
+                            .and_then(from_instance)
+                    }
+                })
+                .collect(),
+            data @ FeatureDataRef::Text(_) => data
+                .strings_iter()
+                .zip(data.nulls())
+                .map(|(value, is_null)| {
+                    if is_null {
+                        None
+                    } else {
+                        DateTime::parse_from_rfc3339(&value)
+                            .ok()
+                            .map(|date_time| date_time.with_timezone(&Utc))
+                    }
+                })
+                .collect(),
+            _ => return Err(error::Error::InvalidFeatureDataType),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{MultiPoint, SpatialResolution, TimeInterval};
+
+    #[test]
+    fn serde() {
+        let params: TimeDerivationParams = serde_json::from_value(serde_json::json!({
+            "source": {
+                "type": "column",
+                "column": "date"
+            },
+            "columns": [{
+                "component": "dayOfYear",
+                "outputColumn": "doy"
+            }]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            params,
+            TimeDerivationParams {
+                source: TimeDerivationSource::Column {
+                    column: "date".to_string()
+                },
+                columns: vec![DerivedTimeColumn {
+                    component: TimeComponent::DayOfYear,
+                    output_column: "doy".to_string()
+                }]
+            }
+        );
+    }
+
+    async fn derive(source: TimeDerivationSource) -> MultiPointCollection {
+        let start = TimeInstance::from(
+            DateTime::parse_from_rfc3339("2021-03-04T05:06:07Z")
+                .unwrap()
+                .with_timezone(&Utc),
+        );
+
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1)]).unwrap(),
+            vec![
+                TimeInterval::new(start, start + 1).unwrap(),
+                TimeInterval::default(),
+            ],
+            [(
+                "date".to_string(),
+                FeatureData::NullableText(vec![Some("2021-03-04T05:06:07Z".to_string()), None]),
+            )]
+            .iter()
+            .cloned()
+            .collect(),
+        )
+        .unwrap();
+
+        let operator = TimeDerivation {
+            params: TimeDerivationParams {
+                source,
+                columns: vec![
+                    DerivedTimeColumn {
+                        component: TimeComponent::Year,
+                        output_column: "year".to_string(),
+                    },
+                    DerivedTimeColumn {
+                        component: TimeComponent::Month,
+                        output_column: "month".to_string(),
+                    },
+                    DerivedTimeColumn {
+                        component: TimeComponent::DayOfYear,
+                        output_column: "doy".to_string(),
+                    },
+                    DerivedTimeColumn {
+                        component: TimeComponent::Hour,
+                        output_column: "hour".to_string(),
+                    },
+                ],
+            },
+            sources: MockFeatureCollectionSource::single(collection)
+                .boxed()
+                .into(),
+        }
+        .boxed();
+
+        let initialized = operator
+            .initialize(&MockExecutionContext::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            initialized.result_descriptor().columns.get("doy"),
+            Some(&FeatureDataType::Int)
+        );
+
+        let processor = match initialized.query_processor() {
+            Ok(TypedVectorQueryProcessor::MultiPoint(processor)) => processor,
+            _ => panic!(),
+        };
+
+        let query_rectangle = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (4., 4.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::zero_point_one(),
+        };
+
+        let ctx = MockQueryContext::new(usize::MAX);
+
+        let mut collections: Vec<MultiPointCollection> = processor
+            .query(query_rectangle, &ctx)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(collections.len(), 1);
+
+        collections.remove(0)
+    }
+
+    fn values(collection: &MultiPointCollection, column: &str) -> Vec<Option<f64>> {
+        collection
+            .data(column)
+            .unwrap()
+            .float_options_iter()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn from_start() {
+        let collection = derive(TimeDerivationSource::Start).await;
+
+        assert_eq!(values(&collection, "year"), vec![Some(2021.), None]);
+        assert_eq!(values(&collection, "month"), vec![Some(3.), None]);
+        assert_eq!(values(&collection, "doy"), vec![Some(63.), None]);
+        assert_eq!(values(&collection, "hour"), vec![Some(5.), None]);
+    }
+
+    #[tokio::test]
+    async fn from_column() {
+        let collection = derive(TimeDerivationSource::Column {
+            column: "date".to_string(),
+        })
+        .await;
+
+        assert_eq!(values(&collection, "year"), vec![Some(2021.), None]);
+        assert_eq!(values(&collection, "doy"), vec![Some(63.), None]);
+    }
+}
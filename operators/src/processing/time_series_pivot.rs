@@ -0,0 +1,570 @@
+use crate::engine::{
+    ExecutionContext, InitializedVectorOperator, Operator, QueryContext, QueryProcessor,
+    SingleVectorSource, TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor,
+    VectorQueryRectangle, VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    BuilderProvider, DataCollection, FeatureCollection, FeatureCollectionInfos, VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, FeatureDataType, FeatureDataValue, Geometry, TimeInterval,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::collections::HashMap;
+
+use super::feature_aggregation::{Accumulator, AggregationFunction};
+
+/// An operator that pivots time series in long format, i.e., one feature per series and time,
+/// into a wide format with one column per series or per time bucket.
+///
+/// The result is a `Data` collection (without geometries).
+/// Features with a null id or value are left out.
+pub type TimeSeriesPivot = Operator<TimeSeriesPivotParams, SingleVectorSource>;
+
+/// The parameter spec for `TimeSeriesPivot`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeSeriesPivotParams {
+    /// The column that identifies the series of a feature
+    pub id_column: String,
+    /// The column that contains the values of the series
+    pub value_column: String,
+    pub columns: PivotColumns,
+    /// Combines the values of the features that fall into the same cell
+    #[serde(default = "default_aggregation")]
+    pub aggregation: AggregationFunction,
+}
+
+fn default_aggregation() -> AggregationFunction {
+    AggregationFunction::Mean
+}
+
+/// The columns of the pivoted time series
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum PivotColumns {
+    /// One row per time interval of the features and one column per id.
+    /// Features of other ids are left out.
+    #[serde(rename_all = "camelCase")]
+    Ids { ids: Vec<String> },
+    /// One row per id and one column per time bucket.
+    /// A feature contributes to all buckets that intersect its time interval.
+    #[serde(rename_all = "camelCase")]
+    TimeBuckets { buckets: Vec<TimeBucket> },
+}
+
+/// A named time interval that becomes a column
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeBucket {
+    pub name: String,
+    pub time: TimeInterval,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for TimeSeriesPivot {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let vector_source = self.sources.vector.initialize(context).await?;
+        let input_columns = &vector_source.result_descriptor().columns;
+
+        let id_type = input_columns.get(&self.params.id_column).ok_or_else(|| {
+            error::Error::ColumnDoesNotExist {
+                column: self.params.id_column.clone(),
+            }
+        })?;
+        let value_type = input_columns
+            .get(&self.params.value_column)
+            .ok_or_else(|| error::Error::ColumnDoesNotExist {
+                column: self.params.value_column.clone(),
+            })?;
+
+        ensure!(
+            *id_type != FeatureDataType::Bytes,
+            error::InvalidFeatureDataType
+        );
+        ensure!(
+            self.params.aggregation == AggregationFunction::Count || value_type.is_numeric(),
+            error::InvalidFeatureDataType
+        );
+
+        let (output_columns, id_column) = match &self.params.columns {
+            PivotColumns::Ids { ids } => (ids.clone(), None),
+            PivotColumns::TimeBuckets { buckets } => (
+                buckets.iter().map(|bucket| bucket.name.clone()).collect(),
+                Some(self.params.id_column.clone()),
+            ),
+        };
+
+        ensure!(
+            !output_columns.is_empty(),
+            error::InvalidOperatorSpec {
+                reason: "the pivot needs at least one column".to_string()
+            }
+        );
+
+        let mut columns = HashMap::with_capacity(output_columns.len() + 1);
+        if let Some(id_column) = &id_column {
+            columns.insert(id_column.clone(), FeatureDataType::Text);
+        }
+
+        for column in &output_columns {
+            ensure!(
+                columns
+                    .insert(column.clone(), self.params.aggregation.output_type())
+                    .is_none(),
+                error::InvalidOperatorSpec {
+                    reason: format!("output column `{}` is not unique", column)
+                }
+            );
+        }
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: VectorDataType::Data,
+            spatial_reference: vector_source.result_descriptor().spatial_reference,
+            columns,
+        };
+
+        Ok(InitializedTimeSeriesPivot {
+            result_descriptor,
+            vector_source,
+            output_columns,
+            id_column,
+            params: self.params,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedTimeSeriesPivot {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    output_columns: Vec<String>,
+    id_column: Option<String>,
+    params: TimeSeriesPivotParams,
+}
+
+impl InitializedVectorOperator for InitializedTimeSeriesPivot {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let source = self.vector_source.query_processor()?;
+
+        Ok(TypedVectorQueryProcessor::Data(
+            call_on_generic_vector_processor!(source, source => TimeSeriesPivotProcessor {
+                source,
+                params: self.params.clone(),
+                output_columns: self.output_columns.clone(),
+                id_column: self.id_column.clone(),
+            }
+            .boxed()),
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct TimeSeriesPivotProcessor<G> {
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    params: TimeSeriesPivotParams,
+    output_columns: Vec<String>,
+    /// The output column of the ids if there is one row per id
+    id_column: Option<String>,
+}
+
+#[async_trait]
+impl<G> QueryProcessor for TimeSeriesPivotProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type Output = DataCollection;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let table = self.source.query(query, ctx).await?.try_fold(
+            PivotTable::new(&self.params),
+            |mut table, collection| async move {
+                table.add_collection(&collection, &self.params)?;
+                Ok(table)
+            },
+        );
+
+        // the pivot can only be emitted after all input features were consumed
+        Ok(stream::once(async move {
+            table.await?.into_collection(
+                &self.output_columns,
+                self.id_column.as_deref(),
+                self.params.aggregation,
+            )
+        })
+        .boxed())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum RowKey {
+    Time(i64, i64),
+    Id(String),
+}
+
+#[derive(Debug)]
+struct PivotRow {
+    id: Option<String>,
+    time: TimeInterval,
+    cells: Vec<Accumulator>,
+}
+
+#[derive(Debug)]
+struct PivotTable {
+    number_of_columns: usize,
+    /// The column of each id if there is one column per id
+    id_columns: HashMap<String, usize>,
+    /// The time of the rows if there is one row per id
+    bucket_time: Option<TimeInterval>,
+    row_indices: HashMap<RowKey, usize>,
+    rows: Vec<PivotRow>,
+}
+
+impl PivotTable {
+    fn new(params: &TimeSeriesPivotParams) -> Self {
+        let (number_of_columns, id_columns, bucket_time) = match &params.columns {
+            PivotColumns::Ids { ids } => (
+                ids.len(),
+                ids.iter()
+                    .enumerate()
+                    .map(|(column, id)| (id.clone(), column))
+                    .collect(),
+                None,
+            ),
+            PivotColumns::TimeBuckets { buckets } => (
+                buckets.len(),
+                HashMap::new(),
+                buckets
+                    .iter()
+                    .map(|bucket| bucket.time)
+                    .reduce(|a, b| a.extend(&b)),
+            ),
+        };
+
+        Self {
+            number_of_columns,
+            id_columns,
+            bucket_time,
+            row_indices: HashMap::new(),
+            rows: Vec::new(),
+        }
+    }
+
+    fn add_collection<C>(&mut self, collection: &C, params: &TimeSeriesPivotParams) -> Result<()>
+    where
+        C: FeatureCollectionInfos,
+    {
+        let ids = collection.data(&params.id_column)?;
+        let data = collection.data(&params.value_column)?;
+
+        let values: Vec<Option<f64>> = if params.aggregation == AggregationFunction::Count {
+            // only the presence of a value is relevant for counting
+            data.nulls()
+                .into_iter()
+                .map(|is_null| if is_null { None } else { Some(1.) })
+                .collect()
+        } else {
+            data.float_options_iter().collect()
+        };
+
+        let rows = ids
+            .strings_iter()
+            .zip(ids.nulls())
+            .zip(values)
+            .zip(collection.time_intervals());
+
+        for (((id, id_is_null), value), time) in rows {
+            let value = match value {
+                Some(value) if !id_is_null => value,
+                _ => continue,
+            };
+
+            match &params.columns {
+                PivotColumns::Ids { .. } => {
+                    if let Some(&column) = self.id_columns.get(&id) {
+                        let key = RowKey::Time(time.start().inner(), time.end().inner());
+                        self.add(key, None, *time, column, value);
+                    }
+                }
+                PivotColumns::TimeBuckets { buckets } => {
+                    let bucket_time = self.bucket_time.expect("buckets were checked");
+
+                    for (column, bucket) in buckets.iter().enumerate() {
+                        if bucket.time.intersects(time) {
+                            let key = RowKey::Id(id.clone());
+                            self.add(key, Some(id.clone()), bucket_time, column, value);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add(
+        &mut self,
+        key: RowKey,
+        id: Option<String>,
+        time: TimeInterval,
+        column: usize,
+        value: f64,
+    ) {
+        let number_of_columns = self.number_of_columns;
+        let rows = &mut self.rows;
+        let index = *self.row_indices.entry(key).or_insert_with(|| {
+            rows.push(PivotRow {
+                id,
+                time,
+                cells: vec![Accumulator::default(); number_of_columns],
+            });
+            rows.len() - 1
+        });
+
+        self.rows[index].cells[column].add(value);
+    }
+
+    fn into_collection(
+        mut self,
+        output_columns: &[String],
+        id_column: Option<&str>,
+        aggregation: AggregationFunction,
+    ) -> Result<DataCollection> {
+        let mut builder = DataCollection::builder();
+        if let Some(id_column) = id_column {
+            builder.add_column(id_column.to_string(), FeatureDataType::Text)?;
+        }
+        for column in output_columns {
+            builder.add_column(column.clone(), aggregation.output_type())?;
+        }
+        let mut builder = builder.finish_header();
+
+        self.rows.sort_by(|a, b| {
+            (a.time.start(), a.time.end(), &a.id).cmp(&(b.time.start(), b.time.end(), &b.id))
+        });
+
+        for row in self.rows {
+            builder.push_time_interval(row.time)?;
+
+            if let (Some(id_column), Some(id)) = (id_column, row.id) {
+                builder.push_data(id_column, FeatureDataValue::Text(id))?;
+            }
+
+            for (name, accumulator) in output_columns.iter().zip(&row.cells) {
+                builder.push_data(name, accumulator.result(aggregation))?;
+            }
+
+            builder.finish_row();
+        }
+
+        builder.build().map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{FeatureData, MultiPoint, SpatialResolution};
+
+    fn source() -> Box<dyn VectorOperator> {
+        MockFeatureCollectionSource::single(
+            MultiPointCollection::from_data(
+                MultiPoint::many(vec![(0.0, 0.1); 5]).unwrap(),
+                vec![
+                    TimeInterval::new(0, 10).unwrap(),
+                    TimeInterval::new(0, 10).unwrap(),
+                    TimeInterval::new(10, 20).unwrap(),
+                    TimeInterval::new(10, 20).unwrap(),
+                    TimeInterval::new(10, 20).unwrap(),
+                ],
+                [
+                    (
+                        "station".to_string(),
+                        FeatureData::NullableText(vec![
+                            Some("b".into()),
+                            Some("a".into()),
+                            Some("a".into()),
+                            Some("a".into()),
+                            None,
+                        ]),
+                    ),
+                    (
+                        "temperature".to_string(),
+                        FeatureData::NullableFloat(vec![
+                            Some(1.),
+                            Some(2.),
+                            Some(3.),
+                            Some(5.),
+                            Some(7.),
+                        ]),
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            )
+            .unwrap(),
+        )
+        .boxed()
+    }
+
+    async fn pivot(columns: PivotColumns) -> Result<DataCollection> {
+        let pivot = TimeSeriesPivot {
+            params: TimeSeriesPivotParams {
+                id_column: "station".to_string(),
+                value_column: "temperature".to_string(),
+                columns,
+                aggregation: AggregationFunction::Mean,
+            },
+            sources: source().into(),
+        }
+        .boxed();
+
+        let processor = pivot
+            .initialize(&MockExecutionContext::default())
+            .await?
+            .query_processor()?
+            .data()
+            .unwrap();
+
+        let query_rectangle = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (4., 4.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::zero_point_one(),
+        };
+
+        let ctx = MockQueryContext::new(usize::MAX);
+
+        let mut collections: Vec<DataCollection> = processor
+            .query(query_rectangle, &ctx)
+            .await?
+            .try_collect()
+            .await?;
+
+        assert_eq!(collections.len(), 1);
+
+        Ok(collections.remove(0))
+    }
+
+    #[tokio::test]
+    async fn column_per_id() {
+        let result = pivot(PivotColumns::Ids {
+            ids: vec!["a".to_string(), "b".to_string()],
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            result.time_intervals(),
+            &[
+                TimeInterval::new(0, 10).unwrap(),
+                TimeInterval::new(10, 20).unwrap()
+            ]
+        );
+        assert_eq!(
+            result
+                .data("a")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(2.), Some(4.)]
+        );
+        assert_eq!(
+            result
+                .data("b")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(1.), None]
+        );
+    }
+
+    #[tokio::test]
+    async fn column_per_time_bucket() {
+        let result = pivot(PivotColumns::TimeBuckets {
+            buckets: vec![
+                TimeBucket {
+                    name: "first".to_string(),
+                    time: TimeInterval::new(0, 5).unwrap(),
+                },
+                TimeBucket {
+                    name: "second".to_string(),
+                    time: TimeInterval::new(5, 20).unwrap(),
+                },
+            ],
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            result.time_intervals(),
+            &[
+                TimeInterval::new(0, 20).unwrap(),
+                TimeInterval::new(0, 20).unwrap()
+            ]
+        );
+        assert_eq!(
+            result
+                .data("station")
+                .unwrap()
+                .strings_iter()
+                .collect::<Vec<_>>(),
+            vec!["a".to_string(), "b".to_string()]
+        );
+        assert_eq!(
+            result
+                .data("first")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(2.), Some(1.)]
+        );
+        assert_eq!(
+            result
+                .data("second")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(10. / 3.), Some(1.)]
+        );
+    }
+
+    #[tokio::test]
+    async fn duplicate_columns() {
+        let result = pivot(PivotColumns::TimeBuckets {
+            buckets: vec![TimeBucket {
+                name: "station".to_string(),
+                time: TimeInterval::default(),
+            }],
+        })
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(error::Error::InvalidOperatorSpec { .. })
+        ));
+    }
+}
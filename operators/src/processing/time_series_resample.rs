@@ -0,0 +1,463 @@
+use crate::engine::{
+    ExecutionContext, InitializedVectorOperator, Operator, QueryContext, QueryProcessor,
+    SingleVectorSource, TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor,
+    VectorQueryRectangle, VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    BuilderProvider, DataCollection, FeatureCollection, FeatureCollectionInfos, VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, FeatureDataType, FeatureDataValue, Geometry, TimeInstance, TimeInterval,
+    TimeStep,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::collections::{BTreeMap, HashMap};
+
+use super::feature_aggregation::{Accumulator, AggregationFunction};
+
+/// An operator that aggregates the observations of time series to regular time steps,
+/// e.g., the daily mean per station, so that the series of different sources are aligned.
+///
+/// A feature belongs to the step that contains the start of its validity.
+/// The result is a `Data` collection (without geometries) with one row per series and step
+/// that contains at least one observation. Features with a null id are left out.
+pub type TimeSeriesResample = Operator<TimeSeriesResampleParams, SingleVectorSource>;
+
+/// The parameter spec for `TimeSeriesResample`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeSeriesResampleParams {
+    /// The column that identifies the series of a feature, e.g., the station id.
+    /// Otherwise, all features form a single series.
+    #[serde(default)]
+    pub id_column: Option<String>,
+    /// The columns to aggregate, which keep their names
+    pub columns: Vec<String>,
+    /// The length of the steps, e.g., seven days for weeks
+    pub step: TimeStep,
+    /// The start of one of the steps, which aligns all others, defaults to the Unix epoch
+    #[serde(default = "default_step_reference")]
+    pub step_reference: TimeInstance,
+    #[serde(default = "default_aggregation")]
+    pub aggregation: AggregationFunction,
+}
+
+fn default_step_reference() -> TimeInstance {
+    TimeInstance::from_millis_unchecked(0)
+}
+
+fn default_aggregation() -> AggregationFunction {
+    AggregationFunction::Mean
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for TimeSeriesResample {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        ensure!(self.params.step.step > 0, error::WindowSizeMustNotBeZero);
+        ensure!(
+            !self.params.columns.is_empty(),
+            error::InvalidOperatorSpec {
+                reason: "the resampling needs at least one column".to_string()
+            }
+        );
+
+        let vector_source = self.sources.vector.initialize(context).await?;
+        let input_columns = &vector_source.result_descriptor().columns;
+
+        let mut columns = HashMap::with_capacity(self.params.columns.len() + 1);
+
+        if let Some(id_column) = &self.params.id_column {
+            let id_type =
+                input_columns
+                    .get(id_column)
+                    .ok_or_else(|| error::Error::ColumnDoesNotExist {
+                        column: id_column.clone(),
+                    })?;
+            ensure!(
+                *id_type != FeatureDataType::Bytes,
+                error::InvalidFeatureDataType
+            );
+
+            columns.insert(id_column.clone(), FeatureDataType::Text);
+        }
+
+        for column in &self.params.columns {
+            let data_type =
+                input_columns
+                    .get(column)
+                    .ok_or_else(|| error::Error::ColumnDoesNotExist {
+                        column: column.clone(),
+                    })?;
+            ensure!(
+                self.params.aggregation == AggregationFunction::Count || data_type.is_numeric(),
+                error::InvalidFeatureDataType
+            );
+
+            ensure!(
+                columns
+                    .insert(column.clone(), self.params.aggregation.output_type())
+                    .is_none(),
+                error::InvalidOperatorSpec {
+                    reason: format!("column `{}` is not unique", column)
+                }
+            );
+        }
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: VectorDataType::Data,
+            spatial_reference: vector_source.result_descriptor().spatial_reference,
+            columns,
+        };
+
+        Ok(InitializedTimeSeriesResample {
+            result_descriptor,
+            vector_source,
+            params: self.params,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedTimeSeriesResample {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    params: TimeSeriesResampleParams,
+}
+
+impl InitializedVectorOperator for InitializedTimeSeriesResample {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let processor = call_on_generic_vector_processor!(
+            self.vector_source.query_processor()?,
+            source => TimeSeriesResampleProcessor {
+                source,
+                params: self.params.clone(),
+            }.boxed()
+        );
+
+        Ok(TypedVectorQueryProcessor::Data(processor))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct TimeSeriesResampleProcessor<G> {
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    params: TimeSeriesResampleParams,
+}
+
+/// The accumulated columns of a series in one step, keyed by the id and the start of the step
+type Steps = BTreeMap<(String, TimeInstance), (TimeInterval, Vec<Accumulator>)>;
+
+#[async_trait]
+impl<G> QueryProcessor for TimeSeriesResampleProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type Output = DataCollection;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let steps = self.source.query(query, ctx).await?.try_fold(
+            Steps::new(),
+            |mut steps, collection| async move {
+                self.add_collection(&mut steps, &collection)?;
+                Ok(steps)
+            },
+        );
+
+        // a step may contain features of any collection, so the result is only complete at the end
+        Ok(stream::once(async move { self.build_collection(steps.await?) }).boxed())
+    }
+}
+
+impl<G> TimeSeriesResampleProcessor<G> {
+    fn add_collection<C>(&self, steps: &mut Steps, collection: &C) -> Result<()>
+    where
+        C: FeatureCollectionInfos,
+    {
+        let ids: Vec<Option<String>> = match &self.params.id_column {
+            Some(id_column) => {
+                let ids = collection.data(id_column)?;
+                ids.strings_iter()
+                    .zip(ids.nulls())
+                    .map(|(id, is_null)| if is_null { None } else { Some(id) })
+                    .collect()
+            }
+            None => vec![Some(String::new()); collection.len()],
+        };
+
+        let mut columns = Vec::with_capacity(self.params.columns.len());
+        for column in &self.params.columns {
+            let data = collection.data(column)?;
+
+            let values: Vec<Option<f64>> = if self.params.aggregation == AggregationFunction::Count
+            {
+                // only the presence of a value is relevant for counting
+                data.nulls()
+                    .into_iter()
+                    .map(|is_null| if is_null { None } else { Some(1.) })
+                    .collect()
+            } else {
+                data.float_options_iter().collect()
+            };
+
+            columns.push(values);
+        }
+
+        for (row, (id, time)) in ids.into_iter().zip(collection.time_intervals()).enumerate() {
+            let id = match id {
+                Some(id) => id,
+                None => continue,
+            };
+
+            let step_start = self
+                .params
+                .step
+                .snap_relative(self.params.step_reference, time.start())?;
+            let step_time = TimeInterval::new(step_start, (step_start + self.params.step)?)?;
+
+            let (_, accumulators) = steps.entry((id, step_start)).or_insert_with(|| {
+                (
+                    step_time,
+                    vec![Accumulator::default(); self.params.columns.len()],
+                )
+            });
+
+            for (accumulator, values) in accumulators.iter_mut().zip(&columns) {
+                if let Some(value) = values[row] {
+                    accumulator.add(value);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn build_collection(&self, steps: Steps) -> Result<DataCollection> {
+        let mut builder = DataCollection::builder();
+        if let Some(id_column) = &self.params.id_column {
+            builder.add_column(id_column.clone(), FeatureDataType::Text)?;
+        }
+        for column in &self.params.columns {
+            builder.add_column(column.clone(), self.params.aggregation.output_type())?;
+        }
+        let mut builder = builder.finish_header();
+
+        for ((id, _), (time, accumulators)) in steps {
+            builder.push_time_interval(time)?;
+
+            if let Some(id_column) = &self.params.id_column {
+                builder.push_data(id_column, FeatureDataValue::Text(id))?;
+            }
+
+            for (column, accumulator) in self.params.columns.iter().zip(&accumulators) {
+                builder.push_data(column, accumulator.result(self.params.aggregation))?;
+            }
+
+            builder.finish_row();
+        }
+
+        builder.build().map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{
+        FeatureData, MultiPoint, SpatialResolution, TimeGranularity,
+    };
+
+    const DAY: i64 = 24 * 60 * 60 * 1000;
+
+    fn source() -> Box<dyn VectorOperator> {
+        MockFeatureCollectionSource::single(
+            MultiPointCollection::from_data(
+                MultiPoint::many(vec![(0.0, 0.1); 6]).unwrap(),
+                vec![
+                    TimeInterval::new_instant(0).unwrap(),
+                    TimeInterval::new_instant(DAY / 2).unwrap(),
+                    TimeInterval::new_instant(DAY + 1).unwrap(),
+                    TimeInterval::new_instant(DAY / 4).unwrap(),
+                    TimeInterval::new_instant(3 * DAY).unwrap(),
+                    TimeInterval::new_instant(0).unwrap(),
+                ],
+                [
+                    (
+                        "station".to_string(),
+                        FeatureData::NullableText(vec![
+                            Some("a".into()),
+                            Some("a".into()),
+                            Some("a".into()),
+                            Some("b".into()),
+                            Some("b".into()),
+                            None,
+                        ]),
+                    ),
+                    (
+                        "temperature".to_string(),
+                        FeatureData::NullableFloat(vec![
+                            Some(1.),
+                            Some(3.),
+                            Some(5.),
+                            Some(7.),
+                            None,
+                            Some(100.),
+                        ]),
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            )
+            .unwrap(),
+        )
+        .boxed()
+    }
+
+    async fn resample(params: TimeSeriesResampleParams) -> Result<DataCollection> {
+        let processor = TimeSeriesResample {
+            params,
+            sources: source().into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await?
+        .query_processor()?
+        .data()
+        .unwrap();
+
+        let query_rectangle = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (4., 4.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::zero_point_one(),
+        };
+        let ctx = MockQueryContext::new(usize::MAX);
+
+        let mut collections: Vec<DataCollection> = processor
+            .query(query_rectangle, &ctx)
+            .await?
+            .try_collect()
+            .await?;
+
+        assert_eq!(collections.len(), 1);
+
+        Ok(collections.remove(0))
+    }
+
+    fn daily() -> TimeStep {
+        TimeStep {
+            granularity: TimeGranularity::Days,
+            step: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn daily_mean_per_station() {
+        let result = resample(TimeSeriesResampleParams {
+            id_column: Some("station".to_string()),
+            columns: vec!["temperature".to_string()],
+            step: daily(),
+            step_reference: default_step_reference(),
+            aggregation: AggregationFunction::Mean,
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            result.time_intervals(),
+            &[
+                TimeInterval::new(0, DAY).unwrap(),
+                TimeInterval::new(DAY, 2 * DAY).unwrap(),
+                TimeInterval::new(0, DAY).unwrap(),
+                TimeInterval::new(3 * DAY, 4 * DAY).unwrap(),
+            ]
+        );
+        assert_eq!(
+            result
+                .data("station")
+                .unwrap()
+                .strings_iter()
+                .collect::<Vec<_>>(),
+            vec!["a", "a", "b", "b"]
+        );
+        assert_eq!(
+            result
+                .data("temperature")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(2.), Some(5.), Some(7.), None]
+        );
+    }
+
+    #[tokio::test]
+    async fn count_of_single_series() {
+        let result = resample(TimeSeriesResampleParams {
+            id_column: None,
+            columns: vec!["temperature".to_string()],
+            step: TimeStep {
+                granularity: TimeGranularity::Days,
+                step: 2,
+            },
+            step_reference: default_step_reference(),
+            aggregation: AggregationFunction::Count,
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            result.time_intervals(),
+            &[
+                TimeInterval::new(0, 2 * DAY).unwrap(),
+                TimeInterval::new(2 * DAY, 4 * DAY).unwrap(),
+            ]
+        );
+        assert_eq!(
+            result
+                .data("temperature")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(5.), Some(0.)]
+        );
+    }
+
+    #[tokio::test]
+    async fn zero_step() {
+        let result = resample(TimeSeriesResampleParams {
+            id_column: None,
+            columns: vec!["temperature".to_string()],
+            step: TimeStep {
+                granularity: TimeGranularity::Days,
+                step: 0,
+            },
+            step_reference: default_step_reference(),
+            aggregation: AggregationFunction::Mean,
+        })
+        .await;
+
+        assert!(matches!(result, Err(error::Error::WindowSizeMustNotBeZero)));
+    }
+}
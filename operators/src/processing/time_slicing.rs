@@ -0,0 +1,351 @@
+use async_trait::async_trait;
+use futures::future::{self, Ready};
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use geoengine_datatypes::primitives::{
+    SpatialPartition2D, SpatialPartitioned, TimeInstance, TimeInterval, TimeStep,
+};
+use geoengine_datatypes::raster::{
+    EmptyGrid2D, FromPrimitive, Grid2D, Pixel, RasterTile2D, TileInformation, TilingSpecification,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::adapters::SubQueryTileAggregator;
+use crate::engine::{
+    ExecutionContext, InitializedRasterOperator, Operator, QueryContext, QueryProcessor,
+    RasterOperator, RasterQueryProcessor, RasterQueryRectangle, RasterResultDescriptor,
+    SingleRasterSource, TypedRasterQueryProcessor,
+};
+use crate::error;
+use crate::util::Result;
+
+/// An operator that re-emits its source raster in regular time slices.
+///
+/// The query's time interval is split into steps of length `step` that are aligned to the
+/// `stepReference`, so the output has the same cadence regardless of the query.
+/// Each output tile has the interval of its step and the values of the source at the step's start.
+/// Use a `TemporalRasterAggregation` instead to combine all values within a step.
+pub type TimeSlicing = Operator<TimeSlicingParams, SingleRasterSource>;
+
+/// The parameter spec for `TimeSlicing`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeSlicingParams {
+    pub step: TimeStep,
+    /// A point in time where a step starts, the default is `1970-01-01T00:00:00Z`
+    #[serde(default)]
+    pub step_reference: Option<TimeInstance>,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for TimeSlicing {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        ensure!(
+            self.params.step.step > 0,
+            error::InvalidOperatorSpec {
+                reason: "the `step` must not be zero".to_string(),
+            }
+        );
+
+        let source = self.sources.raster.initialize(context).await?;
+
+        Ok(InitializedTimeSlicing {
+            result_descriptor: source.result_descriptor().clone(),
+            source,
+            step: self.params.step,
+            step_reference: self
+                .params
+                .step_reference
+                .unwrap_or_else(|| TimeInstance::from_millis_unchecked(0)),
+            tiling_specification: context.tiling_specification(),
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedTimeSlicing {
+    result_descriptor: RasterResultDescriptor,
+    source: Box<dyn InitializedRasterOperator>,
+    step: TimeStep,
+    step_reference: TimeInstance,
+    tiling_specification: TilingSpecification,
+}
+
+impl InitializedRasterOperator for InitializedTimeSlicing {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let no_data_value = self.result_descriptor.no_data_value;
+
+        Ok(call_on_generic_raster_processor!(
+            self.source.query_processor()?, source => TimeSlicingProcessor {
+                source,
+                step: self.step,
+                step_reference: self.step_reference,
+                tiling_specification: self.tiling_specification,
+                no_data_value: no_data_value.map(FromPrimitive::from_),
+            }.boxed().into()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct TimeSlicingProcessor<T> {
+    source: Box<dyn RasterQueryProcessor<RasterType = T>>,
+    step: TimeStep,
+    step_reference: TimeInstance,
+    tiling_specification: TilingSpecification,
+    no_data_value: Option<T>,
+}
+
+#[async_trait]
+impl<T> QueryProcessor for TimeSlicingProcessor<T>
+where
+    T: Pixel,
+{
+    type Output = RasterTile2D<T>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let sub_query = TimeSlicingSubQuery {
+            step: self.step,
+            step_reference: self.step_reference,
+            no_data_value: self.no_data_value,
+        };
+
+        Ok(sub_query
+            .into_raster_overlap_adapter(&self.source, query, ctx, self.tiling_specification)
+            .boxed())
+    }
+}
+
+/// Queries the source at the start of each step and stretches the tiles to the whole step
+#[derive(Debug, Clone, Copy)]
+struct TimeSlicingSubQuery<T> {
+    step: TimeStep,
+    step_reference: TimeInstance,
+    no_data_value: Option<T>,
+}
+
+impl<T> TimeSlicingSubQuery<T> {
+    fn step_start(&self, time: TimeInstance) -> Result<TimeInstance> {
+        Ok(self.step.snap_relative(self.step_reference, time)?)
+    }
+}
+
+impl<T> SubQueryTileAggregator<T> for TimeSlicingSubQuery<T>
+where
+    T: Pixel,
+{
+    type FoldFuture = Ready<Result<RasterTile2D<T>>>;
+    type FoldMethod = fn(RasterTile2D<T>, RasterTile2D<T>) -> Self::FoldFuture;
+    type TileAccu = RasterTile2D<T>;
+
+    fn result_no_data_value(&self) -> Option<T> {
+        self.no_data_value
+    }
+
+    fn initial_fill_value(&self) -> T {
+        T::from_(0)
+    }
+
+    fn new_fold_accu(
+        &self,
+        tile_info: TileInformation,
+        query_rect: RasterQueryRectangle,
+    ) -> Result<Self::TileAccu> {
+        let step_start = query_rect.time_interval.start();
+        let time = TimeInterval::new(step_start, (step_start + self.step)?)?;
+
+        let grid = if let Some(no_data_value) = self.no_data_value {
+            EmptyGrid2D::new(tile_info.tile_size_in_pixels, no_data_value).into()
+        } else {
+            Grid2D::new_filled(
+                tile_info.tile_size_in_pixels,
+                self.initial_fill_value(),
+                None,
+            )
+            .into()
+        };
+
+        Ok(RasterTile2D::new_with_tile_info(time, tile_info, grid))
+    }
+
+    fn tile_query_rectangle(
+        &self,
+        tile_info: TileInformation,
+        query_rect: RasterQueryRectangle,
+        start_time: TimeInstance,
+    ) -> Result<RasterQueryRectangle> {
+        Ok(RasterQueryRectangle {
+            spatial_bounds: tile_info.spatial_partition(),
+            time_interval: TimeInterval::new_instant(self.step_start(start_time)?)?,
+            spatial_resolution: query_rect.spatial_resolution,
+        })
+    }
+
+    fn fold_method(&self) -> Self::FoldMethod {
+        take_tile_data
+    }
+}
+
+fn take_tile_data<T: Pixel>(
+    mut accu: RasterTile2D<T>,
+    tile: RasterTile2D<T>,
+) -> Ready<Result<RasterTile2D<T>>> {
+    accu.grid_array = tile.grid_array;
+    future::ok(accu)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use futures::TryStreamExt;
+    use geoengine_datatypes::primitives::{Measurement, SpatialResolution, TimeGranularity};
+    use geoengine_datatypes::raster::{GridOrEmpty, RasterDataType};
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+
+    #[tokio::test]
+    async fn slices() {
+        let tile_info = TileInformation {
+            global_geo_transform: Default::default(),
+            global_tile_position: [-1, 0].into(),
+            tile_size_in_pixels: [1, 2].into(),
+        };
+
+        let data = vec![
+            RasterTile2D::new_with_tile_info(
+                TimeInterval::new_unchecked(0, 10),
+                tile_info,
+                Grid2D::new([1, 2].into(), vec![1, 2], Some(0))
+                    .unwrap()
+                    .into(),
+            ),
+            RasterTile2D::new_with_tile_info(
+                TimeInterval::new_unchecked(10, 20),
+                tile_info,
+                Grid2D::new([1, 2].into(), vec![3, 4], Some(0))
+                    .unwrap()
+                    .into(),
+            ),
+        ];
+
+        let operator = TimeSlicing {
+            params: TimeSlicingParams {
+                step: TimeStep {
+                    granularity: TimeGranularity::Millis,
+                    step: 4,
+                },
+                step_reference: Some(TimeInstance::from_millis_unchecked(2)),
+            },
+            sources: SingleRasterSource {
+                raster: MockRasterSource {
+                    params: MockRasterSourceParams {
+                        data,
+                        result_descriptor: RasterResultDescriptor {
+                            data_type: RasterDataType::U8,
+                            spatial_reference: SpatialReference::epsg_4326().into(),
+                            measurement: Measurement::Unitless,
+                            no_data_value: Some(0.),
+                            bands: Vec::new(),
+                        },
+                    },
+                }
+                .boxed(),
+            },
+        }
+        .boxed();
+
+        let execution_context = MockExecutionContext {
+            tiling_specification: TilingSpecification::new((0., 0.).into(), [1, 2].into()),
+            ..Default::default()
+        };
+
+        let processor = operator
+            .initialize(&execution_context)
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .get_u8()
+            .unwrap();
+
+        let query = RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new_unchecked((0., 1.).into(), (2., 0.).into()),
+            time_interval: TimeInterval::new_unchecked(3, 15),
+            spatial_resolution: SpatialResolution::one(),
+        };
+
+        let tiles: Vec<(TimeInterval, Vec<u8>)> = processor
+            .query(query, &MockQueryContext::default())
+            .await
+            .unwrap()
+            .map_ok(|tile| {
+                let values = match tile.grid_array {
+                    GridOrEmpty::Grid(grid) => grid.data.into_vec(),
+                    GridOrEmpty::Empty(_) => Vec::new(),
+                };
+                (tile.time, values)
+            })
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            tiles,
+            vec![
+                (TimeInterval::new_unchecked(2, 6), vec![1, 2]),
+                (TimeInterval::new_unchecked(6, 10), vec![1, 2]),
+                (TimeInterval::new_unchecked(10, 14), vec![3, 4]),
+                (TimeInterval::new_unchecked(14, 18), vec![3, 4]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn zero_step() {
+        let operator = TimeSlicing {
+            params: TimeSlicingParams {
+                step: TimeStep {
+                    granularity: TimeGranularity::Days,
+                    step: 0,
+                },
+                step_reference: None,
+            },
+            sources: SingleRasterSource {
+                raster: MockRasterSource {
+                    params: MockRasterSourceParams {
+                        data: Vec::<RasterTile2D<u8>>::new(),
+                        result_descriptor: RasterResultDescriptor {
+                            data_type: RasterDataType::U8,
+                            spatial_reference: SpatialReference::epsg_4326().into(),
+                            measurement: Measurement::Unitless,
+                            no_data_value: None,
+                            bands: Vec::new(),
+                        },
+                    },
+                }
+                .boxed(),
+            },
+        }
+        .boxed();
+
+        assert!(matches!(
+            operator.initialize(&MockExecutionContext::default()).await,
+            Err(error::Error::InvalidOperatorSpec { .. })
+        ));
+    }
+}
@@ -0,0 +1,399 @@
+use crate::engine::{
+    ExecutionContext, InitializedRasterOperator, Operator, OperatorDatasets, QueryContext,
+    QueryProcessor, RasterOperator, RasterQueryProcessor, RasterQueryRectangle,
+    RasterResultDescriptor, TypedRasterQueryProcessor,
+};
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{try_join, StreamExt, TryStreamExt};
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::primitives::{
+    SpatialPartition2D, SpatialPartitioned, SpatialResolution, TimeInstance, TimeInterval,
+};
+use geoengine_datatypes::raster::{
+    EmptyGrid, FromPrimitive, Grid, GridOrEmpty2D, Pixel, RasterTile2D, TileInformation,
+};
+use num_traits::AsPrimitive;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+/// An operator that resamples a raster onto the time steps of a reference raster.
+///
+/// For each tile of the `reference` raster, the `raster` is sampled at the start of the tile's validity.
+/// The output has the values of `raster` and the temporal sampling of `reference`.
+/// This allows combining rasters with different temporal resolutions, e.g., in an `Expression`.
+pub type TimeSynchronization = Operator<TimeSynchronizationParams, TimeSynchronizationSources>;
+
+/// The parameter spec for `TimeSynchronization`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeSynchronizationParams {
+    pub method: TimeSynchronizationMethod,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum TimeSynchronizationMethod {
+    /// Use the tile that is valid at the reference time
+    Previous,
+    /// Use the tile whose start is nearest to the reference time
+    Nearest,
+    /// Interpolate linearly between the tile that is valid at the reference time and its successor
+    Linear,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeSynchronizationSources {
+    /// The raster that defines the time steps
+    pub reference: Box<dyn RasterOperator>,
+    /// The raster that is resampled
+    pub raster: Box<dyn RasterOperator>,
+}
+
+impl OperatorDatasets for TimeSynchronizationSources {
+    fn datasets_collect(&self, datasets: &mut Vec<DatasetId>) {
+        self.reference.datasets_collect(datasets);
+        self.raster.datasets_collect(datasets);
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for TimeSynchronization {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let (reference, raster) = try_join!(
+            self.sources.reference.initialize(context),
+            self.sources.raster.initialize(context)
+        )?;
+
+        let expected = reference.result_descriptor().spatial_reference;
+        let found = raster.result_descriptor().spatial_reference;
+        ensure!(
+            expected == found,
+            crate::error::InvalidSpatialReference { expected, found }
+        );
+
+        Ok(InitializedTimeSynchronization {
+            result_descriptor: raster.result_descriptor().clone(),
+            reference,
+            raster,
+            method: self.params.method,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedTimeSynchronization {
+    result_descriptor: RasterResultDescriptor,
+    reference: Box<dyn InitializedRasterOperator>,
+    raster: Box<dyn InitializedRasterOperator>,
+    method: TimeSynchronizationMethod,
+}
+
+impl InitializedRasterOperator for InitializedTimeSynchronization {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let reference = self.reference.query_processor()?;
+        let no_data_value = self.result_descriptor.no_data_value;
+
+        Ok(call_on_generic_raster_processor!(
+            self.raster.query_processor()?, raster =>
+            TimeSynchronizationProcessor {
+                reference,
+                raster,
+                method: self.method,
+                no_data_value: no_data_value.map(FromPrimitive::from_),
+            }.boxed().into()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct TimeSynchronizationProcessor<P>
+where
+    P: Pixel,
+{
+    reference: TypedRasterQueryProcessor,
+    raster: Box<dyn RasterQueryProcessor<RasterType = P>>,
+    method: TimeSynchronizationMethod,
+    no_data_value: Option<P>,
+}
+
+impl<P> TimeSynchronizationProcessor<P>
+where
+    P: Pixel,
+{
+    /// Produces the output tile for a tile of the reference raster
+    async fn synchronize_tile(
+        &self,
+        time: TimeInterval,
+        tile_info: TileInformation,
+        spatial_resolution: SpatialResolution,
+        ctx: &dyn QueryContext,
+    ) -> Result<RasterTile2D<P>> {
+        let instant = time.start();
+
+        let previous = self
+            .raster_tile(instant, tile_info, spatial_resolution, ctx)
+            .await?;
+
+        let grid = match previous {
+            None => self.empty_grid(tile_info),
+            Some(previous) if self.method == TimeSynchronizationMethod::Previous => {
+                previous.grid_array
+            }
+            Some(previous) => {
+                let next = if previous.time.end() < TimeInstance::MAX {
+                    self.raster_tile(previous.time.end(), tile_info, spatial_resolution, ctx)
+                        .await?
+                        .filter(|next| next.time.start() > previous.time.start())
+                } else {
+                    None
+                };
+
+                match next {
+                    None => previous.grid_array,
+                    Some(next) if self.method == TimeSynchronizationMethod::Nearest => {
+                        let distance_previous = instant.inner() - previous.time.start().inner();
+                        let distance_next = next.time.start().inner() - instant.inner();
+
+                        if distance_previous <= distance_next {
+                            previous.grid_array
+                        } else {
+                            next.grid_array
+                        }
+                    }
+                    Some(next) => self.interpolate(previous, next, instant)?,
+                }
+            }
+        };
+
+        Ok(RasterTile2D::new_with_tile_info(time, tile_info, grid))
+    }
+
+    /// Queries the tile of `raster` at `tile_info` that is valid at `instant`
+    async fn raster_tile(
+        &self,
+        instant: TimeInstance,
+        tile_info: TileInformation,
+        spatial_resolution: SpatialResolution,
+        ctx: &dyn QueryContext,
+    ) -> Result<Option<RasterTile2D<P>>> {
+        let query = RasterQueryRectangle {
+            spatial_bounds: tile_info.spatial_partition(),
+            time_interval: TimeInterval::new_instant(instant)?,
+            spatial_resolution,
+        };
+
+        let tiles: Vec<RasterTile2D<P>> = self
+            .raster
+            .raster_query(query, ctx)
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(tiles
+            .into_iter()
+            .find(|tile| tile.tile_position == tile_info.global_tile_position))
+    }
+
+    fn interpolate(
+        &self,
+        previous: RasterTile2D<P>,
+        next: RasterTile2D<P>,
+        instant: TimeInstance,
+    ) -> Result<GridOrEmpty2D<P>> {
+        if previous.is_empty() || next.is_empty() {
+            return Ok(self.empty_grid(previous.tile_information()));
+        }
+
+        let weight = (instant.inner() - previous.time.start().inner()) as f64
+            / (next.time.start().inner() - previous.time.start().inner()) as f64;
+
+        let previous = previous.grid_array.into_materialized_grid();
+        let next = next.grid_array.into_materialized_grid();
+        let no_data_value = self.no_data_value;
+
+        let data = previous
+            .data
+            .iter()
+            .zip(&next.data)
+            .map(|(&a, &b)| match no_data_value {
+                Some(no_data) if a == no_data || b == no_data => no_data,
+                _ => {
+                    let a: f64 = a.as_();
+                    let b: f64 = b.as_();
+                    P::from_(a + (b - a) * weight)
+                }
+            })
+            .collect();
+
+        Ok(Grid::new(previous.shape, data, no_data_value)?.into())
+    }
+
+    /// An empty grid for missing data. Falls back to zero if the raster has no no-data value.
+    fn empty_grid(&self, tile_info: TileInformation) -> GridOrEmpty2D<P> {
+        EmptyGrid::new(
+            tile_info.tile_size_in_pixels,
+            self.no_data_value.unwrap_or_else(P::zero),
+        )
+        .into()
+    }
+}
+
+#[async_trait]
+impl<P> QueryProcessor for TimeSynchronizationProcessor<P>
+where
+    P: Pixel,
+{
+    type Output = RasterTile2D<P>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        // only the time and position of the reference tiles are relevant
+        let reference_tiles = call_on_generic_raster_processor!(&self.reference, reference => {
+            reference
+                .raster_query(query, ctx)
+                .await?
+                .map_ok(|tile| (tile.time, tile.tile_information()))
+                .boxed()
+        });
+
+        let stream = reference_tiles.and_then(move |(time, tile_info)| {
+            self.synchronize_tile(time, tile_info, query.spatial_resolution, ctx)
+        });
+
+        Ok(stream.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::primitives::Measurement;
+    use geoengine_datatypes::raster::{Grid2D, RasterDataType};
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+
+    fn mock_raster(tiles: &[(i64, i64, u8)]) -> Box<dyn RasterOperator> {
+        let data = tiles
+            .iter()
+            .map(|&(start, end, value)| {
+                RasterTile2D::new_with_tile_info(
+                    TimeInterval::new_unchecked(start, end),
+                    TileInformation {
+                        global_tile_position: [-1, 0].into(),
+                        tile_size_in_pixels: [3, 2].into(),
+                        global_geo_transform: Default::default(),
+                    },
+                    Grid2D::new([3, 2].into(), vec![value; 6], None)
+                        .unwrap()
+                        .into(),
+                )
+            })
+            .collect();
+
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data,
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: None,
+                    bands: Vec::new(),
+                },
+            },
+        }
+        .boxed()
+    }
+
+    async fn synchronize(method: TimeSynchronizationMethod) -> Vec<(TimeInterval, u8)> {
+        let operator = TimeSynchronization {
+            params: TimeSynchronizationParams { method },
+            sources: TimeSynchronizationSources {
+                reference: mock_raster(&[(0, 6, 1), (6, 12, 1)]),
+                raster: mock_raster(&[(0, 8, 0), (8, 16, 16), (16, 24, 32)]),
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .unwrap();
+
+        let processor = operator.query_processor().unwrap().get_u8().unwrap();
+
+        let ctx = MockQueryContext::new(1);
+        let tiles: Vec<RasterTile2D<u8>> = processor
+            .query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new_unchecked(
+                        (0., 3.).into(),
+                        (2., 0.).into(),
+                    ),
+                    time_interval: TimeInterval::new_unchecked(0, 12),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &ctx,
+            )
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        tiles
+            .into_iter()
+            .map(|tile| {
+                let grid = tile.grid_array.into_materialized_grid();
+                (tile.time, grid.data[0])
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn previous() {
+        assert_eq!(
+            synchronize(TimeSynchronizationMethod::Previous).await,
+            vec![
+                (TimeInterval::new_unchecked(0, 6), 0),
+                (TimeInterval::new_unchecked(6, 12), 0)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn nearest() {
+        assert_eq!(
+            synchronize(TimeSynchronizationMethod::Nearest).await,
+            vec![
+                (TimeInterval::new_unchecked(0, 6), 0),
+                (TimeInterval::new_unchecked(6, 12), 16)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn linear() {
+        assert_eq!(
+            synchronize(TimeSynchronizationMethod::Linear).await,
+            vec![
+                (TimeInterval::new_unchecked(0, 6), 0),
+                (TimeInterval::new_unchecked(6, 12), 12)
+            ]
+        );
+    }
+}
@@ -0,0 +1,549 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    DataCollection, FeatureCollection, FeatureCollectionInfos, FeatureCollectionModifications,
+    IntoGeometryIterator, MultiLineStringCollection, MultiPointCollection, MultiPolygonCollection,
+};
+use geoengine_datatypes::operations::simplify::Simplify;
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, Geometry, MultiLineString, MultiPoint, MultiPolygon, TimeInterval,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use lazy_static::lazy_static;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt};
+
+use crate::engine::{
+    ExecutionContext, InitializedVectorOperator, Operator, QueryContext, QueryProcessor,
+    SingleVectorSource, TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor,
+    VectorQueryRectangle, VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::{safe_lock_mutex, Result};
+
+lazy_static! {
+    /// The materialized levels of all generalizations, keyed by their operator and level
+    static ref MATERIALIZATIONS: Mutex<HashMap<String, Arc<dyn Any + Send + Sync>>> =
+        Mutex::new(HashMap::new());
+}
+
+/// An operator that simplifies line and polygon geometries depending on the resolution of the query.
+/// Coarse queries get strongly simplified geometries, while fine queries get the original ones.
+pub type VectorGeneralization = Operator<VectorGeneralizationParams, SingleVectorSource>;
+
+/// The parameter spec for `VectorGeneralization`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VectorGeneralizationParams {
+    pub levels: Vec<GeneralizationLevel>,
+    /// Pre-compute all levels for an extent once instead of simplifying every query result
+    #[serde(default)]
+    pub materialization: Option<Materialization>,
+}
+
+/// A level of detail that is used for queries whose resolution is at least `min_resolution`
+/// until the next coarser level starts
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneralizationLevel {
+    pub min_resolution: f64,
+    /// The maximum deviation of the simplified geometries in units of the spatial reference
+    pub tolerance: f64,
+}
+
+/// The extent for which all levels are pre-computed.
+/// Queries outside of it are simplified on the fly.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Materialization {
+    pub spatial_bounds: BoundingBox2D,
+    pub time_interval: TimeInterval,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for VectorGeneralization {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let mut levels = self.params.levels.clone();
+
+        for level in &levels {
+            ensure!(
+                level.min_resolution.is_finite() && level.min_resolution > 0.,
+                error::InvalidGeneralizationLevels {
+                    reason: "the minimum resolution must be positive"
+                }
+            );
+            ensure!(
+                level.tolerance.is_finite() && level.tolerance >= 0.,
+                error::InvalidGeneralizationLevels {
+                    reason: "the tolerance must not be negative"
+                }
+            );
+        }
+
+        // coarsest level first
+        levels.sort_by(|a, b| {
+            b.min_resolution
+                .partial_cmp(&a.min_resolution)
+                .expect("finite")
+        });
+
+        ensure!(
+            levels
+                .windows(2)
+                .all(|pair| pair[0].min_resolution > pair[1].min_resolution),
+            error::InvalidGeneralizationLevels {
+                reason: "the minimum resolutions must be distinct"
+            }
+        );
+
+        let materialization_key = match self.params.materialization {
+            Some(_) => Some(serde_json::to_string(&self).context(error::SerdeJson)?),
+            None => None,
+        };
+
+        let vector_source = self.sources.vector.initialize(context).await?;
+
+        Ok(InitializedVectorGeneralization {
+            result_descriptor: vector_source.result_descriptor().clone(),
+            vector_source,
+            state: GeneralizationState {
+                levels,
+                materialization: self.params.materialization,
+                materialization_key,
+            },
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedVectorGeneralization {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    state: GeneralizationState,
+}
+
+#[derive(Debug, Clone)]
+struct GeneralizationState {
+    /// The levels ordered from coarse to fine
+    levels: Vec<GeneralizationLevel>,
+    materialization: Option<Materialization>,
+    materialization_key: Option<String>,
+}
+
+impl InitializedVectorOperator for InitializedVectorGeneralization {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        Ok(map_typed_query_processor!(
+            self.vector_source.query_processor()?,
+            source => VectorGeneralizationProcessor {
+                source,
+                state: self.state.clone(),
+            }.boxed()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct VectorGeneralizationProcessor<G> {
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    state: GeneralizationState,
+}
+
+impl<G> VectorGeneralizationProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+    FeatureCollection<G>: Generalize,
+{
+    /// Loads the generalized collections of a level for the whole materialization extent
+    async fn materialized_level(
+        &self,
+        level: usize,
+        materialization: &Materialization,
+        key: &str,
+        query: VectorQueryRectangle,
+        ctx: &dyn QueryContext,
+    ) -> Result<Arc<Vec<FeatureCollection<G>>>> {
+        let key = format!("{}#{}", key, level);
+
+        let cached = safe_lock_mutex(&MATERIALIZATIONS).get(&key).cloned();
+        if let Some(Ok(collections)) =
+            cached.map(|cached| cached.downcast::<Vec<FeatureCollection<G>>>())
+        {
+            return Ok(collections);
+        }
+
+        let tolerance = self.state.levels[level].tolerance;
+        let materialization_query = VectorQueryRectangle {
+            spatial_bounds: materialization.spatial_bounds,
+            time_interval: materialization.time_interval,
+            spatial_resolution: query.spatial_resolution,
+        };
+
+        let collections: Vec<FeatureCollection<G>> = self
+            .source
+            .query(materialization_query, ctx)
+            .await?
+            .and_then(|collection| async move { collection.generalize(tolerance) })
+            .try_collect()
+            .await?;
+        let collections = Arc::new(collections);
+
+        // concurrent queries may compute the same level, but the results are equal
+        safe_lock_mutex(&MATERIALIZATIONS).insert(key, collections.clone());
+
+        Ok(collections)
+    }
+}
+
+#[async_trait]
+impl<G> QueryProcessor for VectorGeneralizationProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+    FeatureCollection<G>: Generalize,
+{
+    type Output = FeatureCollection<G>;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let level = match self
+            .state
+            .levels
+            .iter()
+            .position(|level| query.spatial_resolution.x >= level.min_resolution)
+        {
+            Some(level) => level,
+            None => return self.source.query(query, ctx).await,
+        };
+
+        if let (Some(materialization), Some(key)) =
+            (&self.state.materialization, &self.state.materialization_key)
+        {
+            if materialization
+                .spatial_bounds
+                .contains_bbox(&query.spatial_bounds)
+                && materialization.time_interval.contains(&query.time_interval)
+            {
+                let collections = self
+                    .materialized_level(level, materialization, key, query, ctx)
+                    .await?;
+
+                let (bbox, time_interval) = (query.spatial_bounds, query.time_interval);
+                let intersecting = (0..collections.len())
+                    .map(move |i| collections[i].intersecting(&bbox, &time_interval));
+
+                return Ok(stream::iter(intersecting)
+                    .try_filter(|collection| futures::future::ready(!collection.is_empty()))
+                    .boxed());
+            }
+        }
+
+        let tolerance = self.state.levels[level].tolerance;
+
+        Ok(self
+            .source
+            .query(query, ctx)
+            .await?
+            .map(move |collection| {
+                collection.and_then(|collection| collection.generalize(tolerance))
+            })
+            .boxed())
+    }
+}
+
+/// Feature collections that can be generalized and cut out of a materialized level
+pub trait Generalize: Sized {
+    /// Simplifies the geometries with a `tolerance` in units of the spatial reference
+    fn generalize(&self, tolerance: f64) -> Result<Self>;
+
+    /// Keeps the features that intersect the `bbox` and the `time_interval`
+    fn intersecting(&self, bbox: &BoundingBox2D, time_interval: &TimeInterval) -> Result<Self>;
+}
+
+impl Generalize for DataCollection {
+    fn generalize(&self, _tolerance: f64) -> Result<Self> {
+        Ok(self.clone())
+    }
+
+    fn intersecting(&self, _bbox: &BoundingBox2D, time_interval: &TimeInterval) -> Result<Self> {
+        filter_features(self, std::iter::repeat(true), time_interval)
+    }
+}
+
+impl Generalize for MultiPointCollection {
+    /// Points have no vertices that could be removed
+    fn generalize(&self, _tolerance: f64) -> Result<Self> {
+        Ok(self.clone())
+    }
+
+    fn intersecting(&self, bbox: &BoundingBox2D, time_interval: &TimeInterval) -> Result<Self> {
+        let intersects = self
+            .geometries()
+            .map(|geometry| MultiPoint::from(geometry).intersects_bbox(bbox));
+
+        filter_features(self, intersects, time_interval)
+    }
+}
+
+impl Generalize for MultiLineStringCollection {
+    fn generalize(&self, tolerance: f64) -> Result<Self> {
+        Ok(self.simplify(tolerance)?)
+    }
+
+    fn intersecting(&self, bbox: &BoundingBox2D, time_interval: &TimeInterval) -> Result<Self> {
+        let intersects = self
+            .geometries()
+            .map(|geometry| MultiLineString::from(geometry).intersects_bbox(bbox));
+
+        filter_features(self, intersects, time_interval)
+    }
+}
+
+impl Generalize for MultiPolygonCollection {
+    fn generalize(&self, tolerance: f64) -> Result<Self> {
+        Ok(self.simplify(tolerance)?)
+    }
+
+    fn intersecting(&self, bbox: &BoundingBox2D, time_interval: &TimeInterval) -> Result<Self> {
+        let intersects = self
+            .geometries()
+            .map(|geometry| MultiPolygon::from(geometry).intersects_bbox(bbox));
+
+        filter_features(self, intersects, time_interval)
+    }
+}
+
+fn filter_features<G, I>(
+    collection: &FeatureCollection<G>,
+    intersects: I,
+    time_interval: &TimeInterval,
+) -> Result<FeatureCollection<G>>
+where
+    G: Geometry + ArrowTyped,
+    I: Iterator<Item = bool>,
+{
+    let mask: Vec<bool> = collection
+        .time_intervals()
+        .iter()
+        .zip(intersects)
+        .map(|(time, intersects)| intersects && time.intersects(time_interval))
+        .collect();
+
+    Ok(collection.filter(mask)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::primitives::SpatialResolution;
+
+    fn lines() -> MultiLineStringCollection {
+        MultiLineStringCollection::from_data(
+            vec![
+                MultiLineString::new(vec![vec![
+                    (0., 0.).into(),
+                    (1., 0.1).into(),
+                    (2., 0.).into(),
+                    (3., 0.4).into(),
+                    (4., 0.).into(),
+                ]])
+                .unwrap(),
+                MultiLineString::new(vec![vec![(10., 10.).into(), (11., 11.).into()]]).unwrap(),
+            ],
+            vec![TimeInterval::new_unchecked(0, 10); 2],
+            HashMap::new(),
+        )
+        .unwrap()
+    }
+
+    async fn generalize(
+        materialization: Option<Materialization>,
+        spatial_bounds: BoundingBox2D,
+        resolution: f64,
+    ) -> Vec<MultiLineStringCollection> {
+        let operator = VectorGeneralization {
+            params: VectorGeneralizationParams {
+                levels: vec![
+                    GeneralizationLevel {
+                        min_resolution: 0.1,
+                        tolerance: 0.2,
+                    },
+                    GeneralizationLevel {
+                        min_resolution: 1.,
+                        tolerance: 1.,
+                    },
+                ],
+                materialization,
+            },
+            sources: MockFeatureCollectionSource::single(lines()).boxed().into(),
+        }
+        .boxed();
+
+        let initialized = operator
+            .initialize(&MockExecutionContext::default())
+            .await
+            .unwrap();
+
+        let processor = match initialized.query_processor() {
+            Ok(TypedVectorQueryProcessor::MultiLineString(processor)) => processor,
+            _ => panic!(),
+        };
+
+        let query_rectangle = VectorQueryRectangle {
+            spatial_bounds,
+            time_interval: TimeInterval::new_unchecked(0, 10),
+            spatial_resolution: SpatialResolution::new_unchecked(resolution, resolution),
+        };
+
+        let ctx = MockQueryContext::new(usize::MAX);
+
+        processor
+            .query(query_rectangle, &ctx)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await
+    }
+
+    fn first_line(collections: &[MultiLineStringCollection]) -> MultiLineString {
+        collections[0].geometries().next().unwrap().into()
+    }
+
+    #[test]
+    fn serde() {
+        let params: VectorGeneralizationParams = serde_json::from_value(serde_json::json!({
+            "levels": [{
+                "minResolution": 100,
+                "tolerance": 50
+            }],
+            "materialization": {
+                "spatialBounds": {
+                    "lowerLeftCoordinate": { "x": 0, "y": 0 },
+                    "upperRightCoordinate": { "x": 10, "y": 10 }
+                },
+                "timeInterval": { "start": 0, "end": 10 }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            params,
+            VectorGeneralizationParams {
+                levels: vec![GeneralizationLevel {
+                    min_resolution: 100.,
+                    tolerance: 50.
+                }],
+                materialization: Some(Materialization {
+                    spatial_bounds: BoundingBox2D::new((0., 0.).into(), (10., 10.).into()).unwrap(),
+                    time_interval: TimeInterval::new_unchecked(0, 10),
+                }),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn it_selects_levels_by_resolution() {
+        let bbox = BoundingBox2D::new((0., 0.).into(), (20., 20.).into()).unwrap();
+
+        let original = generalize(None, bbox, 0.01).await;
+        assert_eq!(original, vec![lines()]);
+
+        let fine = generalize(None, bbox, 0.5).await;
+        assert_eq!(
+            first_line(&fine),
+            MultiLineString::new(vec![vec![
+                (0., 0.).into(),
+                (2., 0.).into(),
+                (3., 0.4).into(),
+                (4., 0.).into(),
+            ]])
+            .unwrap()
+        );
+
+        let coarse = generalize(None, bbox, 2.).await;
+        assert_eq!(
+            first_line(&coarse),
+            MultiLineString::new(vec![vec![(0., 0.).into(), (4., 0.).into()]]).unwrap()
+        );
+        assert_eq!(coarse[0].len(), 2);
+    }
+
+    #[tokio::test]
+    async fn it_serves_materialized_levels() {
+        let materialization = Materialization {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (20., 20.).into()).unwrap(),
+            time_interval: TimeInterval::new_unchecked(0, 10),
+        };
+
+        let outside = generalize(
+            Some(materialization),
+            BoundingBox2D::new((-1., -1.).into(), (5., 5.).into()).unwrap(),
+            2.,
+        )
+        .await;
+
+        // the query is not contained in the materialization, so it is simplified on the fly
+        assert_eq!(outside[0].len(), 2);
+
+        let materialized = generalize(
+            Some(materialization),
+            BoundingBox2D::new((0., 0.).into(), (5., 5.).into()).unwrap(),
+            2.,
+        )
+        .await;
+
+        assert_eq!(materialized.len(), 1);
+        assert_eq!(materialized[0].len(), 1);
+        assert_eq!(
+            first_line(&materialized),
+            MultiLineString::new(vec![vec![(0., 0.).into(), (4., 0.).into()]]).unwrap()
+        );
+
+        let empty = generalize(
+            Some(materialization),
+            BoundingBox2D::new((5., 5.).into(), (6., 6.).into()).unwrap(),
+            2.,
+        )
+        .await;
+
+        assert!(empty.is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_rejects_invalid_levels() {
+        let operator = VectorGeneralization {
+            params: VectorGeneralizationParams {
+                levels: vec![GeneralizationLevel {
+                    min_resolution: 1.,
+                    tolerance: -1.,
+                }],
+                materialization: None,
+            },
+            sources: MockFeatureCollectionSource::single(lines()).boxed().into(),
+        }
+        .boxed();
+
+        assert!(operator
+            .initialize(&MockExecutionContext::default())
+            .await
+            .is_err());
+    }
+}
@@ -8,10 +8,12 @@ use futures::StreamExt;
 
 use geoengine_datatypes::collections::{
     BuilderProvider, DataCollection, FeatureCollection, FeatureCollectionBuilder,
-    FeatureCollectionInfos, FeatureCollectionRowBuilder, GeoFeatureCollectionRowBuilder,
-    GeometryRandomAccess,
+    FeatureCollectionInfos, FeatureCollectionModifications, FeatureCollectionRowBuilder,
+    GeoFeatureCollectionRowBuilder, GeometryRandomAccess,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, FeatureDataRef, FeatureDataType, Geometry, TimeInterval,
 };
-use geoengine_datatypes::primitives::{BoundingBox2D, FeatureDataRef, Geometry, TimeInterval};
 use geoengine_datatypes::util::arrow::ArrowTyped;
 
 use crate::adapters::FeatureCollectionChunkMerger;
@@ -22,13 +24,19 @@ use crate::util::Result;
 use async_trait::async_trait;
 use futures::TryStreamExt;
 
-/// Implements an inner equi-join between a `GeoFeatureCollection` stream and a `DataCollection` stream.
+use super::spatial_join::null_value;
+
+/// Implements an equi-join between a `GeoFeatureCollection` stream and a `DataCollection` stream.
+///
+/// It is an inner join unless `keep_unmatched` is set, which turns it into a left join.
 pub struct EquiGeoToDataJoinProcessor<G> {
     left_processor: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
     right_processor: Box<dyn VectorQueryProcessor<VectorType = DataCollection>>,
     left_column: Arc<String>,
     right_column: Arc<String>,
     right_translation_table: Arc<HashMap<String, String>>,
+    right_column_types: HashMap<String, FeatureDataType>,
+    keep_unmatched: bool,
 }
 
 impl<G> EquiGeoToDataJoinProcessor<G>
@@ -44,6 +52,8 @@ where
         left_column: String,
         right_column: String,
         right_translation_table: HashMap<String, String>,
+        right_column_types: HashMap<String, FeatureDataType>,
+        keep_unmatched: bool,
     ) -> Self {
         Self {
             left_processor,
@@ -51,6 +61,8 @@ where
             left_column: Arc::new(left_column),
             right_column: Arc::new(right_column),
             right_translation_table: Arc::new(right_translation_table),
+            right_column_types,
+            keep_unmatched,
         }
     }
 
@@ -66,9 +78,31 @@ where
             self.left_column.clone(),
             self.right_column.clone(),
             self.right_translation_table.clone(),
+            self.keep_unmatched,
             chunk_byte_size,
         )
     }
+
+    /// Collects all chunks of the right side, s.t. left features without a match can be identified
+    async fn query_right_table(
+        &self,
+        query: VectorQueryRectangle,
+        ctx: &dyn QueryContext,
+    ) -> Result<DataCollection> {
+        let mut builder = DataCollection::builder();
+        for (column_name, column_type) in &self.right_column_types {
+            builder.add_column(column_name.clone(), *column_type)?;
+        }
+        let empty_table = builder.finish_header().build()?;
+
+        self.right_processor
+            .query(query, ctx)
+            .await?
+            .try_fold(empty_table, |table, chunk| async move {
+                table.append(&chunk).map_err(Into::into)
+            })
+            .await
+    }
 }
 
 struct BatchBuilderIterator<G>
@@ -80,6 +114,7 @@ where
     left_column: Arc<String>,
     right_column: Arc<String>,
     right_translation_table: Arc<HashMap<String, String>>,
+    keep_unmatched: bool,
     builder: FeatureCollectionBuilder<G>,
     chunk_byte_size: usize,
     left_idx: usize,
@@ -100,6 +135,7 @@ where
         left_column: Arc<String>,
         right_column: Arc<String>,
         right_translation_table: Arc<HashMap<String, String>>,
+        keep_unmatched: bool,
         chunk_byte_size: usize,
     ) -> Result<Self> {
         let mut builder = FeatureCollection::<G>::builder();
@@ -118,6 +154,7 @@ where
             left_column,
             right_column,
             right_translation_table,
+            keep_unmatched,
             builder,
             chunk_byte_size,
             left_idx: 0,
@@ -252,6 +289,20 @@ where
                 left_idx,
             )?;
 
+            if join_inner_batch_matches.is_empty() && self.keep_unmatched {
+                for (column_name, feature_data) in &left_data_lookup {
+                    builder.push_data(column_name, feature_data.get_unchecked(left_idx))?;
+                }
+                for (column_name, feature_data) in &right_data_lookup {
+                    builder
+                        .push_data(column_name, null_value(FeatureDataType::from(feature_data)))?;
+                }
+
+                builder.push_geometry(geometry.clone())?;
+                builder.push_time_interval(left_time_intervals[left_idx])?;
+                builder.finish_row();
+            }
+
             // add left value
             for (column_name, feature_data) in &left_data_lookup {
                 let data = feature_data.get_unchecked(left_idx);
@@ -352,6 +403,33 @@ where
         query: VectorQueryRectangle,
         ctx: &'a dyn QueryContext,
     ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        if self.keep_unmatched {
+            let right_table = self.query_right_table(query, ctx).await?;
+
+            let result_stream =
+                self.left_processor
+                    .query(query, ctx)
+                    .await?
+                    .flat_map(move |left_collection| {
+                        match left_collection.and_then(|left_collection| {
+                            self.join(
+                                Arc::new(left_collection),
+                                right_table.clone(),
+                                ctx.chunk_byte_size(),
+                            )
+                        }) {
+                            Ok(batch_iter) => stream::iter(batch_iter).boxed(),
+                            Err(e) => stream::once(async { Err(e) }).boxed(),
+                        }
+                    });
+
+            return Ok(FeatureCollectionChunkMerger::new(
+                result_stream.fuse(),
+                ctx.chunk_byte_size(),
+            )
+            .boxed());
+        }
+
         let result_stream = self
             .left_processor
             .query(query, ctx)
@@ -405,6 +483,7 @@ mod tests {
         left_join_column: &str,
         right_join_column: &str,
         right_suffix: &str,
+        keep_unmatched: bool,
     ) -> Vec<MultiPointCollection> {
         let execution_context = MockExecutionContext::default();
 
@@ -444,6 +523,8 @@ mod tests {
                 right.result_descriptor().columns.keys(),
                 right_suffix,
             ),
+            right.result_descriptor().columns.clone(),
+            keep_unmatched,
         );
 
         block_on_stream(processor.query(query_rectangle, &ctx).await.unwrap())
@@ -486,7 +567,7 @@ mod tests {
         )
         .unwrap();
 
-        let result = join_mock_collections(left, right, "foo", "bar", "").await;
+        let result = join_mock_collections(left, right, "foo", "bar", "", false).await;
 
         assert_eq!(result.len(), 1);
         assert_eq!(result[0], expected_result);
@@ -533,7 +614,7 @@ mod tests {
         )
         .unwrap();
 
-        let result = join_mock_collections(left, right, "foo", "bar", "").await;
+        let result = join_mock_collections(left, right, "foo", "bar", "", false).await;
 
         assert_eq!(result.len(), 1);
         assert_eq!(result[0], expected_result);
@@ -574,7 +655,7 @@ mod tests {
         )
         .unwrap();
 
-        let result = join_mock_collections(left, right, "foo", "foo", "2").await;
+        let result = join_mock_collections(left, right, "foo", "foo", "2", false).await;
 
         assert_eq!(result.len(), 1);
         assert_eq!(result[0], expected_result);
@@ -644,7 +725,7 @@ mod tests {
         )
         .unwrap();
 
-        let result = join_mock_collections(left, right, "foo", "bar", "").await;
+        let result = join_mock_collections(left, right, "foo", "bar", "", false).await;
 
         assert_eq!(result.len(), 1);
         assert_eq!(result[0], expected_result);
@@ -672,9 +753,60 @@ mod tests {
         )
         .unwrap();
 
-        let result = join_mock_collections(left, right, "foo", "bar", "").await;
+        let result = join_mock_collections(left, right, "foo", "bar", "", false).await;
 
         // TODO: do we need an empty collection here? (cf. `FeatureCollectionChunkMerger`)
         assert_eq!(result.len(), 0);
     }
+
+    #[tokio::test]
+    async fn keep_unmatched() {
+        let left = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1), (2.0, 2.1)]).unwrap(),
+            vec![TimeInterval::default(); 3],
+            [("foo".to_string(), FeatureData::Int(vec![1, 2, 3]))]
+                .iter()
+                .cloned()
+                .collect(),
+        )
+        .unwrap();
+
+        let right = DataCollection::from_data(
+            vec![],
+            vec![TimeInterval::default(); 1],
+            [
+                ("bar".to_string(), FeatureData::Int(vec![2])),
+                ("baz".to_string(), FeatureData::Text(vec!["b".to_string()])),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+        )
+        .unwrap();
+
+        let expected_result = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1), (2.0, 2.1)]).unwrap(),
+            vec![TimeInterval::default(); 3],
+            [
+                ("foo".to_string(), FeatureData::Int(vec![1, 2, 3])),
+                (
+                    "bar".to_string(),
+                    FeatureData::NullableInt(vec![None, Some(2), None]),
+                ),
+                (
+                    "baz".to_string(),
+                    FeatureData::NullableText(vec![None, Some("b".to_string()), None]),
+                ),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+        )
+        .unwrap();
+
+        let result = join_mock_collections(left, right, "foo", "bar", "", true).await;
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0], expected_result);
+    }
 }
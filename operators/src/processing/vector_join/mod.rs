@@ -1,8 +1,10 @@
 use geoengine_datatypes::dataset::DatasetId;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use snafu::ensure;
 
 use geoengine_datatypes::collections::VectorDataType;
+use geoengine_datatypes::primitives::FeatureDataType;
 
 use crate::engine::{
     ExecutionContext, InitializedVectorOperator, Operator, OperatorDatasets,
@@ -12,18 +14,23 @@ use crate::error;
 use crate::util::Result;
 
 use self::equi_data_join::EquiGeoToDataJoinProcessor;
+use self::nearest_neighbor_join::{NearestNeighborJoinProcessor, DEFAULT_DISTANCE_COLUMN};
+use self::spatial_join::{SpatialJoinAggregation, SpatialJoinPredicate, SpatialJoinProcessor};
 use crate::processing::vector_join::util::translation_table;
 use async_trait::async_trait;
+use futures::try_join;
 use std::collections::HashMap;
 
 mod equi_data_join;
+mod nearest_neighbor_join;
+mod spatial_join;
 mod util;
 
 /// The vector join operator requires two inputs and the join type.
 pub type VectorJoin = Operator<VectorJoinParams, VectorJoinSources>;
 
 /// A set of parameters for the `VectorJoin`
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct VectorJoinParams {
     #[serde(flatten)]
@@ -45,16 +52,49 @@ impl OperatorDatasets for VectorJoinSources {
 }
 
 /// Define the type of join
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(tag = "type")]
 pub enum VectorJoinType {
-    /// An inner equi-join between a `GeoFeatureCollection` and a `DataCollection`
+    /// An equi-join between a `GeoFeatureCollection` and a `DataCollection` that attaches
+    /// the rows of the data table to the features with the same key
     EquiGeoToData {
         left_column: String,
         right_column: String,
         /// which suffix to use if columns have conflicting names?
         /// the default is "right"
         right_column_suffix: Option<String>,
+        /// keep the features without a matching row, whose right columns are `null` then?
+        /// the default is an inner join that drops them
+        #[serde(default)]
+        keep_unmatched: bool,
+    },
+    /// A left join between two `GeoFeatureCollection`s that attaches the columns of the
+    /// right features that spatially match a left feature
+    Spatial {
+        predicate: SpatialJoinPredicate,
+        /// how to combine the values of multiple matching features?
+        /// the default is to take the values of the first match
+        #[serde(default)]
+        aggregation: SpatialJoinAggregation,
+        /// which suffix to use if columns have conflicting names?
+        /// the default is "right"
+        right_column_suffix: Option<String>,
+    },
+    /// A left join that attaches the `k` nearest right features to each feature of a
+    /// `MultiPointCollection`, resulting in one output feature per neighbor
+    NearestNeighbors {
+        k: usize,
+        /// farther features are no neighbors; the default is no limit
+        max_distance: Option<f64>,
+        /// which right columns to attach?
+        #[serde(default)]
+        right_columns: Vec<String>,
+        /// the name of the column with the distance to the neighbor
+        /// the default is "distance"
+        distance_column: Option<String>,
+        /// which suffix to use if columns have conflicting names?
+        /// the default is "right"
+        right_column_suffix: Option<String>,
     },
 }
 
@@ -65,10 +105,12 @@ impl VectorOperator for VectorJoin {
         self: Box<Self>,
         context: &dyn ExecutionContext,
     ) -> Result<Box<dyn InitializedVectorOperator>> {
-        let left = self.sources.left.initialize(context).await?;
-        let right = self.sources.right.initialize(context).await?;
+        let (left, right) = try_join!(
+            self.sources.left.initialize(context),
+            self.sources.right.initialize(context)
+        )?;
 
-        match self.params.join_type {
+        match &self.params.join_type {
             VectorJoinType::EquiGeoToData { .. } => {
                 ensure!(
                     left.result_descriptor().data_type != VectorDataType::Data,
@@ -85,6 +127,72 @@ impl VectorOperator for VectorJoin {
                     }
                 );
             }
+            VectorJoinType::Spatial { .. } => {
+                for source in &[&left, &right] {
+                    ensure!(
+                        source.result_descriptor().data_type != VectorDataType::Data,
+                        error::InvalidType {
+                            expected: "a geo data collection".to_string(),
+                            found: source.result_descriptor().data_type.to_string(),
+                        }
+                    );
+                }
+            }
+            VectorJoinType::NearestNeighbors {
+                k,
+                max_distance,
+                right_columns,
+                distance_column,
+                ..
+            } => {
+                ensure!(
+                    *k > 0,
+                    error::InvalidOperatorSpec {
+                        reason: "`k` must be greater than zero".to_string(),
+                    }
+                );
+                ensure!(
+                    max_distance.map_or(true, |max_distance| max_distance >= 0.),
+                    error::InvalidOperatorSpec {
+                        reason: "`max_distance` must not be negative".to_string(),
+                    }
+                );
+                ensure!(
+                    left.result_descriptor().data_type == VectorDataType::MultiPoint,
+                    error::InvalidType {
+                        expected: VectorDataType::MultiPoint.to_string(),
+                        found: left.result_descriptor().data_type.to_string(),
+                    }
+                );
+                ensure!(
+                    right.result_descriptor().data_type != VectorDataType::Data,
+                    error::InvalidType {
+                        expected: "a geo data collection".to_string(),
+                        found: right.result_descriptor().data_type.to_string(),
+                    }
+                );
+                for column in right_columns {
+                    ensure!(
+                        right.result_descriptor().columns.contains_key(column),
+                        error::ColumnDoesNotExist {
+                            column: column.clone(),
+                        }
+                    );
+                }
+
+                let distance_column = distance_column
+                    .as_ref()
+                    .map_or(DEFAULT_DISTANCE_COLUMN, String::as_str);
+                ensure!(
+                    !left
+                        .result_descriptor()
+                        .columns
+                        .contains_key(distance_column),
+                    error::InvalidOperatorSpec {
+                        reason: format!("column `{}` already exists", distance_column),
+                    }
+                );
+            }
         }
 
         // TODO: find out if column prefixes are the same for more than one join type and generify
@@ -92,6 +200,10 @@ impl VectorOperator for VectorJoin {
             VectorJoinType::EquiGeoToData {
                 right_column_suffix,
                 ..
+            }
+            | VectorJoinType::Spatial {
+                right_column_suffix,
+                ..
             } => {
                 let right_column_suffix: &str =
                     right_column_suffix.as_ref().map_or("right", String::as_str);
@@ -101,14 +213,51 @@ impl VectorOperator for VectorJoin {
                     right_column_suffix,
                 )
             }
+            VectorJoinType::NearestNeighbors {
+                right_columns,
+                distance_column,
+                right_column_suffix,
+                ..
+            } => {
+                let right_column_suffix: &str =
+                    right_column_suffix.as_ref().map_or("right", String::as_str);
+                let distance_column = distance_column
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_DISTANCE_COLUMN.to_string());
+                translation_table(
+                    left.result_descriptor()
+                        .columns
+                        .keys()
+                        .chain(std::iter::once(&distance_column)),
+                    right_columns.iter(),
+                    right_column_suffix,
+                )
+            }
         };
 
         let result_descriptor = left.result_descriptor().map_columns(|left_columns| {
             let mut columns = left_columns.clone();
-            for (right_column_name, right_column_type) in &right.result_descriptor().columns {
+            for (right_column_name, output_column_name) in &column_translation_table {
+                let right_column_type = right.result_descriptor().columns[right_column_name];
+                let right_column_type = match &self.params.join_type {
+                    VectorJoinType::EquiGeoToData { .. }
+                    | VectorJoinType::NearestNeighbors { .. } => right_column_type,
+                    VectorJoinType::Spatial { aggregation, .. } => {
+                        aggregation.output_type(right_column_type)
+                    }
+                };
+
+                columns.insert(output_column_name.clone(), right_column_type);
+            }
+            if let VectorJoinType::NearestNeighbors {
+                distance_column, ..
+            } = &self.params.join_type
+            {
                 columns.insert(
-                    column_translation_table[right_column_name].clone(),
-                    *right_column_type,
+                    distance_column
+                        .clone()
+                        .unwrap_or_else(|| DEFAULT_DISTANCE_COLUMN.to_string()),
+                    FeatureDataType::Float,
                 );
             }
             columns
@@ -148,7 +297,8 @@ impl InitializedVectorOperator for InitializedVectorJoin {
             VectorJoinType::EquiGeoToData {
                 left_column,
                 right_column,
-                right_column_suffix: _right_column_suffix,
+                keep_unmatched,
+                ..
             } => {
                 let right_processor = self
                     .right
@@ -168,6 +318,8 @@ impl InitializedVectorOperator for InitializedVectorJoin {
                                 left_column.clone(),
                                 right_column.clone(),
                                 self.state.column_translation_table.clone(),
+                                self.right.result_descriptor().columns.clone(),
+                                *keep_unmatched,
                             )
                             .boxed(),
                         )
@@ -180,6 +332,8 @@ impl InitializedVectorOperator for InitializedVectorJoin {
                                 left_column.clone(),
                                 right_column.clone(),
                                 self.state.column_translation_table.clone(),
+                                self.right.result_descriptor().columns.clone(),
+                                *keep_unmatched,
                             )
                             .boxed(),
                         )
@@ -192,12 +346,113 @@ impl InitializedVectorOperator for InitializedVectorJoin {
                                 left_column.clone(),
                                 right_column.clone(),
                                 self.state.column_translation_table.clone(),
+                                self.right.result_descriptor().columns.clone(),
+                                *keep_unmatched,
+                            )
+                            .boxed(),
+                        )
+                    }
+                })
+            }
+            VectorJoinType::Spatial {
+                predicate,
+                aggregation,
+                ..
+            } => {
+                let right_columns: Vec<(String, FeatureDataType, String)> = self
+                    .right
+                    .result_descriptor()
+                    .columns
+                    .iter()
+                    .map(|(column_name, column_type)| {
+                        (
+                            column_name.clone(),
+                            *column_type,
+                            self.state.column_translation_table[column_name].clone(),
+                        )
+                    })
+                    .collect();
+
+                let right_processor = self.right.query_processor()?;
+
+                Ok(match self.left.query_processor()? {
+                    TypedVectorQueryProcessor::Data(_) => unreachable!("check in constructor"),
+                    TypedVectorQueryProcessor::MultiPoint(left_processor) => {
+                        TypedVectorQueryProcessor::MultiPoint(
+                            SpatialJoinProcessor::new(
+                                left_processor,
+                                right_processor,
+                                *predicate,
+                                *aggregation,
+                                right_columns,
+                            )
+                            .boxed(),
+                        )
+                    }
+                    TypedVectorQueryProcessor::MultiLineString(left_processor) => {
+                        TypedVectorQueryProcessor::MultiLineString(
+                            SpatialJoinProcessor::new(
+                                left_processor,
+                                right_processor,
+                                *predicate,
+                                *aggregation,
+                                right_columns,
+                            )
+                            .boxed(),
+                        )
+                    }
+                    TypedVectorQueryProcessor::MultiPolygon(left_processor) => {
+                        TypedVectorQueryProcessor::MultiPolygon(
+                            SpatialJoinProcessor::new(
+                                left_processor,
+                                right_processor,
+                                *predicate,
+                                *aggregation,
+                                right_columns,
                             )
                             .boxed(),
                         )
                     }
                 })
             }
+            VectorJoinType::NearestNeighbors {
+                k,
+                max_distance,
+                right_columns,
+                distance_column,
+                ..
+            } => {
+                let right_columns: Vec<(String, FeatureDataType, String)> = right_columns
+                    .iter()
+                    .map(|column_name| {
+                        (
+                            column_name.clone(),
+                            self.right.result_descriptor().columns[column_name],
+                            self.state.column_translation_table[column_name].clone(),
+                        )
+                    })
+                    .collect();
+
+                let left_processor = self
+                    .left
+                    .query_processor()?
+                    .multi_point()
+                    .expect("checked in constructor");
+
+                Ok(TypedVectorQueryProcessor::MultiPoint(
+                    NearestNeighborJoinProcessor::new(
+                        left_processor,
+                        self.right.query_processor()?,
+                        *k,
+                        *max_distance,
+                        right_columns,
+                        distance_column
+                            .clone()
+                            .unwrap_or_else(|| DEFAULT_DISTANCE_COLUMN.to_string()),
+                    )
+                    .boxed(),
+                ))
+            }
         }
     }
 
@@ -221,6 +476,7 @@ mod tests {
                 left_column: "foo".to_string(),
                 right_column: "bar".to_string(),
                 right_column_suffix: Some("baz".to_string()),
+                keep_unmatched: false,
             },
         };
 
@@ -229,6 +485,34 @@ mod tests {
             "left_column": "foo",
             "right_column": "bar",
             "right_column_suffix": "baz",
+            "keep_unmatched": false,
+        })
+        .to_string();
+
+        assert_eq!(json, serde_json::to_string(&params).unwrap());
+
+        let params_deserialized: VectorJoinParams = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(params, params_deserialized);
+    }
+
+    #[test]
+    fn spatial_params() {
+        let params = VectorJoinParams {
+            join_type: VectorJoinType::Spatial {
+                predicate: SpatialJoinPredicate::Intersects,
+                aggregation: SpatialJoinAggregation::Mean,
+                right_column_suffix: None,
+            },
+        };
+
+        let json = serde_json::json!({
+            "type": "Spatial",
+            "predicate": {
+                "type": "intersects",
+            },
+            "aggregation": "mean",
+            "right_column_suffix": null,
         })
         .to_string();
 
@@ -239,6 +523,88 @@ mod tests {
         assert_eq!(params, params_deserialized);
     }
 
+    #[test]
+    fn nearest_neighbors_params() {
+        let params = VectorJoinParams {
+            join_type: VectorJoinType::NearestNeighbors {
+                k: 3,
+                max_distance: Some(1.5),
+                right_columns: vec!["name".to_string()],
+                distance_column: None,
+                right_column_suffix: None,
+            },
+        };
+
+        let json = serde_json::json!({
+            "type": "NearestNeighbors",
+            "k": 3,
+            "max_distance": 1.5,
+            "right_columns": ["name"],
+            "distance_column": null,
+            "right_column_suffix": null,
+        })
+        .to_string();
+
+        assert_eq!(json, serde_json::to_string(&params).unwrap());
+
+        let params_deserialized: VectorJoinParams = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(params, params_deserialized);
+    }
+
+    #[tokio::test]
+    async fn nearest_neighbors_initialization() {
+        let points = || {
+            MockFeatureCollectionSource::single(
+                MultiPointCollection::from_slices(
+                    &[(0.0, 0.1)],
+                    &[TimeInterval::default()],
+                    &[
+                        ("name", FeatureData::Text(vec!["a".to_string()])),
+                        ("value", FeatureData::Int(vec![5])),
+                    ],
+                )
+                .unwrap(),
+            )
+            .boxed()
+        };
+
+        let operator = VectorJoin {
+            params: VectorJoinParams {
+                join_type: VectorJoinType::NearestNeighbors {
+                    k: 1,
+                    max_distance: None,
+                    right_columns: vec!["name".to_string()],
+                    distance_column: None,
+                    right_column_suffix: None,
+                },
+            },
+            sources: VectorJoinSources {
+                left: points(),
+                right: points(),
+            },
+        };
+
+        let initialized = operator
+            .boxed()
+            .initialize(&MockExecutionContext::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            initialized.result_descriptor().columns,
+            [
+                ("name", FeatureDataType::Text),
+                ("value", FeatureDataType::Int),
+                ("nameright", FeatureDataType::Text),
+                ("distance", FeatureDataType::Float),
+            ]
+            .iter()
+            .map(|&(column, data_type)| (column.to_string(), data_type))
+            .collect()
+        );
+    }
+
     #[tokio::test]
     async fn initialization() {
         let operator = VectorJoin {
@@ -247,6 +613,7 @@ mod tests {
                     left_column: "foo".to_string(),
                     right_column: "bar".to_string(),
                     right_column_suffix: Some("baz".to_string()),
+                    keep_unmatched: false,
                 },
             },
             sources: VectorJoinSources {
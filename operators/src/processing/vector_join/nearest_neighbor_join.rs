@@ -0,0 +1,389 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geo::algorithm::bounding_rect::BoundingRect;
+use rstar::{PointDistance, RTree, RTreeObject, AABB};
+
+use geoengine_datatypes::collections::{
+    BuilderProvider, FeatureCollectionInfos, GeoFeatureCollectionRowBuilder, GeometryRandomAccess,
+    MultiPointCollection,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, Coordinate2D, FeatureDataType, FeatureDataValue, MultiPoint, MultiPointAccess,
+    TimeInterval,
+};
+
+use crate::adapters::FeatureCollectionChunkMerger;
+use crate::engine::{
+    QueryContext, QueryProcessor, TypedVectorQueryProcessor, VectorQueryProcessor,
+    VectorQueryRectangle,
+};
+use crate::util::Result;
+use async_trait::async_trait;
+
+use super::spatial_join::{null_value, right_features, GeometryPart, RightFeature};
+
+pub const DEFAULT_DISTANCE_COLUMN: &str = "distance";
+
+/// Implements a k-nearest-neighbor left join that outputs one feature per left feature and
+/// neighbor with the values of the neighbor and its distance.
+///
+/// Left features without any neighbor are kept once with null values.
+pub struct NearestNeighborJoinProcessor {
+    left_processor: Box<dyn VectorQueryProcessor<VectorType = MultiPointCollection>>,
+    right_processor: TypedVectorQueryProcessor,
+    k: usize,
+    max_distance: Option<f64>,
+    /// the right columns with their types and their names in the output
+    right_columns: Arc<Vec<(String, FeatureDataType, String)>>,
+    distance_column: String,
+}
+
+/// A part of a right feature in the R-tree
+struct IndexedPart {
+    feature: usize,
+    part: GeometryPart,
+    envelope: AABB<[f64; 2]>,
+}
+
+impl IndexedPart {
+    /// Returns `None` for empty parts since they have no envelope
+    fn new(feature: usize, part: GeometryPart) -> Option<Self> {
+        let envelope = match &part {
+            GeometryPart::Point(point) => AABB::from_point([point.x(), point.y()]),
+            GeometryPart::LineString(line_string) => rect_envelope(line_string.bounding_rect()?),
+            GeometryPart::Polygon(polygon) => rect_envelope(polygon.bounding_rect()?),
+        };
+
+        Some(Self {
+            feature,
+            part,
+            envelope,
+        })
+    }
+}
+
+fn rect_envelope(rect: geo::Rect<f64>) -> AABB<[f64; 2]> {
+    AABB::from_corners([rect.min().x, rect.min().y], [rect.max().x, rect.max().y])
+}
+
+impl RTreeObject for IndexedPart {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.envelope
+    }
+}
+
+impl PointDistance for IndexedPart {
+    fn distance_2(&self, point: &[f64; 2]) -> f64 {
+        self.part
+            .distance(&GeometryPart::Point(geo::Point::new(point[0], point[1])))
+            .powi(2)
+    }
+}
+
+impl NearestNeighborJoinProcessor {
+    pub fn new(
+        left_processor: Box<dyn VectorQueryProcessor<VectorType = MultiPointCollection>>,
+        right_processor: TypedVectorQueryProcessor,
+        k: usize,
+        max_distance: Option<f64>,
+        right_columns: Vec<(String, FeatureDataType, String)>,
+        distance_column: String,
+    ) -> Self {
+        Self {
+            left_processor,
+            right_processor,
+            k,
+            max_distance,
+            right_columns: Arc::new(right_columns),
+            distance_column,
+        }
+    }
+
+    /// Finds the (at most) `k` nearest right features of the `points` with their distances,
+    /// ordered by distance
+    fn nearest_neighbors(
+        &self,
+        index: &RTree<IndexedPart>,
+        right_features: &[RightFeature],
+        points: &[Coordinate2D],
+        time_interval: TimeInterval,
+    ) -> Vec<(usize, f64)> {
+        let mut distances: HashMap<usize, f64> = HashMap::new();
+
+        for point in points {
+            let point = [point.x, point.y];
+            let mut neighbors = HashSet::with_capacity(self.k);
+
+            // parts are visited by ascending distance, so the first part of a feature is closest
+            for indexed_part in index.nearest_neighbor_iter(&point) {
+                if neighbors.len() == self.k {
+                    break;
+                }
+
+                let distance = indexed_part.distance_2(&point).sqrt();
+                if self.max_distance.map_or(false, |max| distance > max) {
+                    break;
+                }
+
+                let feature = indexed_part.feature;
+                if !time_interval.intersects(&right_features[feature].time_interval)
+                    || !neighbors.insert(feature)
+                {
+                    continue;
+                }
+
+                let min_distance = distances.entry(feature).or_insert(distance);
+                *min_distance = min_distance.min(distance);
+            }
+        }
+
+        let mut neighbors: Vec<(usize, f64)> = distances.into_iter().collect();
+        neighbors.sort_by(|(a_feature, a_distance), (b_feature, b_distance)| {
+            a_distance
+                .partial_cmp(b_distance)
+                .expect("distances must not be NaN")
+                .then(a_feature.cmp(b_feature))
+        });
+        neighbors.truncate(self.k);
+
+        neighbors
+    }
+
+    fn join(
+        &self,
+        left: &MultiPointCollection,
+        index: &RTree<IndexedPart>,
+        right_features: &[RightFeature],
+    ) -> Result<MultiPointCollection> {
+        let mut builder = MultiPointCollection::builder();
+
+        for (column_name, column_type) in left.column_types() {
+            builder.add_column(column_name, column_type)?;
+        }
+        for (_, column_type, output_column_name) in self.right_columns.iter() {
+            builder.add_column(output_column_name.clone(), *column_type)?;
+        }
+        builder.add_column(self.distance_column.clone(), FeatureDataType::Float)?;
+
+        let mut builder = builder.finish_header();
+
+        let left_data: Vec<_> = left
+            .column_names()
+            .map(|column_name| {
+                (
+                    column_name.clone(),
+                    left.data(column_name).expect("must exist"),
+                )
+            })
+            .collect();
+
+        for (left_idx, &time_interval) in left.time_intervals().iter().enumerate() {
+            let geometry: MultiPoint = left.geometry_at(left_idx).expect("index must exist").into();
+
+            let neighbors =
+                self.nearest_neighbors(index, right_features, geometry.points(), time_interval);
+
+            let rows: Vec<Option<(usize, f64)>> = if neighbors.is_empty() {
+                vec![None]
+            } else {
+                neighbors.into_iter().map(Some).collect()
+            };
+
+            for neighbor in rows {
+                for (column_name, data) in &left_data {
+                    builder.push_data(column_name, data.get_unchecked(left_idx))?;
+                }
+
+                for (column_idx, (_, column_type, output_column_name)) in
+                    self.right_columns.iter().enumerate()
+                {
+                    let value = match neighbor {
+                        Some((feature, _)) => right_features[feature].values[column_idx].clone(),
+                        None => null_value(*column_type),
+                    };
+
+                    builder.push_data(output_column_name, value)?;
+                }
+
+                builder.push_data(
+                    &self.distance_column,
+                    FeatureDataValue::NullableFloat(neighbor.map(|(_, distance)| distance)),
+                )?;
+
+                builder.push_geometry(geometry.clone())?;
+                builder.push_time_interval(time_interval)?;
+                builder.finish_row();
+            }
+        }
+
+        builder.build().map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl QueryProcessor for NearestNeighborJoinProcessor {
+    type Output = MultiPointCollection;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let right_features =
+            right_features(&self.right_processor, &self.right_columns, query, ctx).await?;
+
+        let index = RTree::bulk_load(
+            right_features
+                .iter()
+                .enumerate()
+                .flat_map(|(feature, right)| {
+                    right
+                        .parts
+                        .iter()
+                        .filter_map(move |part| IndexedPart::new(feature, part.clone()))
+                })
+                .collect(),
+        );
+
+        let state = Arc::new((index, right_features));
+
+        let result_stream =
+            self.left_processor
+                .query(query, ctx)
+                .await?
+                .and_then(move |left_collection| {
+                    let state = state.clone();
+                    async move {
+                        let (index, right_features) = state.as_ref();
+                        self.join(&left_collection, index, right_features)
+                    }
+                });
+
+        Ok(FeatureCollectionChunkMerger::new(result_stream.fuse(), ctx.chunk_byte_size()).boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, VectorOperator};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::primitives::{FeatureData, SpatialResolution};
+
+    async fn join(k: usize, max_distance: Option<f64>) -> MultiPointCollection {
+        let execution_context = MockExecutionContext::default();
+
+        let left = MockFeatureCollectionSource::single(
+            MultiPointCollection::from_slices(
+                &MultiPoint::many(vec![(0.0, 0.0), (10.0, 0.0), (100.0, 100.0)]).unwrap(),
+                &[TimeInterval::default(); 3],
+                &[("id", FeatureData::Int(vec![1, 2, 3]))],
+            )
+            .unwrap(),
+        )
+        .boxed()
+        .initialize(&execution_context)
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .multi_point()
+        .unwrap();
+
+        let right = MockFeatureCollectionSource::single(
+            MultiPointCollection::from_slices(
+                &MultiPoint::many(vec![(1.0, 0.0), (3.0, 0.0), (9.0, 0.0)]).unwrap(),
+                &[TimeInterval::default(); 3],
+                &[(
+                    "name",
+                    FeatureData::Text(vec!["a".into(), "b".into(), "c".into()]),
+                )],
+            )
+            .unwrap(),
+        )
+        .boxed()
+        .initialize(&execution_context)
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap();
+
+        let processor = NearestNeighborJoinProcessor::new(
+            left,
+            right,
+            k,
+            max_distance,
+            vec![(
+                "name".to_string(),
+                FeatureDataType::Text,
+                "name".to_string(),
+            )],
+            DEFAULT_DISTANCE_COLUMN.to_string(),
+        );
+
+        let mut result = processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((0., 0.).into(), (100., 100.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::zero_point_one(),
+                },
+                &MockQueryContext::default(),
+            )
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(result.len(), 1);
+
+        result.remove(0).unwrap()
+    }
+
+    #[tokio::test]
+    #[allow(clippy::float_cmp)]
+    async fn it_attaches_k_nearest_neighbors() {
+        let result = join(2, None).await;
+
+        let ids: Vec<Option<f64>> = result.data("id").unwrap().float_options_iter().collect();
+        assert_eq!(
+            ids,
+            vec![Some(1.), Some(1.), Some(2.), Some(2.), Some(3.), Some(3.)]
+        );
+
+        let names: Vec<String> = result.data("name").unwrap().strings_iter().collect();
+        assert_eq!(names, vec!["a", "b", "c", "b", "c", "b"]);
+
+        let distances: Vec<Option<f64>> = result
+            .data(DEFAULT_DISTANCE_COLUMN)
+            .unwrap()
+            .float_options_iter()
+            .collect();
+        assert_eq!(distances[..4], [Some(1.), Some(3.), Some(1.), Some(7.)]);
+    }
+
+    #[tokio::test]
+    #[allow(clippy::float_cmp)]
+    async fn it_respects_the_max_distance() {
+        let result = join(2, Some(2.)).await;
+
+        let distances: Vec<Option<f64>> = result
+            .data(DEFAULT_DISTANCE_COLUMN)
+            .unwrap()
+            .float_options_iter()
+            .collect();
+        assert_eq!(distances, vec![Some(1.), Some(1.), None]);
+
+        assert_eq!(
+            result.data("name").unwrap().nulls(),
+            vec![false, false, true]
+        );
+    }
+}
@@ -0,0 +1,614 @@
+use std::sync::Arc;
+
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geo::algorithm::contains::Contains;
+use geo::algorithm::euclidean_distance::EuclideanDistance;
+use geo::algorithm::intersects::Intersects;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use geoengine_datatypes::collections::{
+    BuilderProvider, FeatureCollection, FeatureCollectionInfos, FeatureCollectionRowBuilder,
+    GeoFeatureCollectionRowBuilder, GeometryRandomAccess,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, FeatureDataType, FeatureDataValue, Geometry, MultiLineString, MultiPoint,
+    MultiPointAccess, MultiPolygon, TimeInterval,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+
+use crate::adapters::FeatureCollectionChunkMerger;
+use crate::engine::{
+    QueryContext, QueryProcessor, TypedVectorQueryProcessor, VectorQueryProcessor,
+    VectorQueryRectangle,
+};
+use crate::util::Result;
+use async_trait::async_trait;
+
+/// The spatial relationship between a left and a right feature that makes them match
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum SpatialJoinPredicate {
+    Intersects,
+    /// The left feature contains the right feature
+    Contains,
+    WithinDistance {
+        distance: f64,
+    },
+}
+
+/// How to combine the values of multiple right features that match the same left feature
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SpatialJoinAggregation {
+    /// Takes the values of the first matching feature
+    First,
+    Sum,
+    Mean,
+    Min,
+    Max,
+}
+
+impl Default for SpatialJoinAggregation {
+    fn default() -> Self {
+        Self::First
+    }
+}
+
+impl SpatialJoinAggregation {
+    /// Numeric aggregations turn int and float columns into float columns.
+    /// All other columns keep the value of the first match.
+    pub fn output_type(self, data_type: FeatureDataType) -> FeatureDataType {
+        match (self, data_type) {
+            (
+                Self::Sum | Self::Mean | Self::Min | Self::Max,
+                FeatureDataType::Int | FeatureDataType::Float,
+            ) => FeatureDataType::Float,
+            (_, data_type) => data_type,
+        }
+    }
+
+    fn aggregate(
+        self,
+        values: &[&FeatureDataValue],
+        data_type: FeatureDataType,
+    ) -> FeatureDataValue {
+        let is_numeric = matches!(data_type, FeatureDataType::Int | FeatureDataType::Float);
+
+        if self == Self::First || !is_numeric {
+            return values
+                .first()
+                .map_or_else(|| null_value(data_type), |&value| value.clone());
+        }
+
+        let numbers: Vec<f64> = values
+            .iter()
+            .filter_map(|value| match value {
+                FeatureDataValue::Int(v) | FeatureDataValue::NullableInt(Some(v)) => {
+                    Some(*v as f64)
+                }
+                FeatureDataValue::Float(v) | FeatureDataValue::NullableFloat(Some(v)) => Some(*v),
+                _ => None,
+            })
+            .collect();
+
+        if numbers.is_empty() {
+            return FeatureDataValue::NullableFloat(None);
+        }
+
+        let aggregate = match self {
+            Self::Sum => numbers.iter().sum(),
+            Self::Mean => numbers.iter().sum::<f64>() / numbers.len() as f64,
+            Self::Min => numbers.iter().copied().fold(f64::INFINITY, f64::min),
+            Self::Max => numbers.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            Self::First => unreachable!("handled above"),
+        };
+
+        FeatureDataValue::NullableFloat(Some(aggregate))
+    }
+}
+
+pub(super) fn null_value(data_type: FeatureDataType) -> FeatureDataValue {
+    match data_type {
+        FeatureDataType::Category => FeatureDataValue::NullableCategory(None),
+        FeatureDataType::Int => FeatureDataValue::NullableInt(None),
+        FeatureDataType::Float => FeatureDataValue::NullableFloat(None),
+        FeatureDataType::Text => FeatureDataValue::NullableText(None),
+        FeatureDataType::Bytes => FeatureDataValue::NullableBytes(None),
+        FeatureDataType::Dictionary => FeatureDataValue::NullableDictionary(None),
+    }
+}
+
+/// A single point, line string or polygon of a multi geometry
+#[derive(Debug, Clone)]
+pub enum GeometryPart {
+    Point(geo::Point<f64>),
+    LineString(geo::LineString<f64>),
+    Polygon(geo::Polygon<f64>),
+}
+
+impl GeometryPart {
+    fn intersects(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Point(a), Self::Point(b)) => a == b,
+            (Self::Point(a), Self::LineString(b)) | (Self::LineString(b), Self::Point(a)) => {
+                b.intersects(a)
+            }
+            (Self::Point(a), Self::Polygon(b)) | (Self::Polygon(b), Self::Point(a)) => {
+                b.intersects(a)
+            }
+            (Self::LineString(a), Self::LineString(b)) => a.intersects(b),
+            (Self::LineString(a), Self::Polygon(b)) | (Self::Polygon(b), Self::LineString(a)) => {
+                b.intersects(a)
+            }
+            (Self::Polygon(a), Self::Polygon(b)) => a.intersects(b),
+        }
+    }
+
+    fn contains(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Point(a), Self::Point(b)) => a == b,
+            (Self::LineString(a), Self::Point(b)) => a.contains(b),
+            (Self::Polygon(a), Self::Point(b)) => a.contains(b),
+            (Self::Polygon(a), Self::LineString(b)) => a.contains(b),
+            (Self::Polygon(a), Self::Polygon(b)) => a.contains(b.exterior()),
+            _ => false,
+        }
+    }
+
+    pub(super) fn distance(&self, other: &Self) -> f64 {
+        match (self, other) {
+            (Self::Point(a), Self::Point(b)) => a.euclidean_distance(b),
+            (Self::Point(a), Self::LineString(b)) | (Self::LineString(b), Self::Point(a)) => {
+                a.euclidean_distance(b)
+            }
+            (Self::Point(a), Self::Polygon(b)) | (Self::Polygon(b), Self::Point(a)) => {
+                a.euclidean_distance(b)
+            }
+            (Self::LineString(a), Self::LineString(b)) => a.euclidean_distance(b),
+            (Self::LineString(a), Self::Polygon(b)) | (Self::Polygon(b), Self::LineString(a)) => {
+                a.euclidean_distance(b)
+            }
+            (Self::Polygon(a), Self::Polygon(b)) => a.euclidean_distance(b),
+        }
+    }
+}
+
+/// Decomposes a multi geometry into its parts
+pub trait IntoGeometryParts {
+    fn geometry_parts(&self) -> Vec<GeometryPart>;
+}
+
+impl IntoGeometryParts for MultiPoint {
+    fn geometry_parts(&self) -> Vec<GeometryPart> {
+        self.points()
+            .iter()
+            .map(|&coordinate| GeometryPart::Point(geo::Coordinate::from(coordinate).into()))
+            .collect()
+    }
+}
+
+impl IntoGeometryParts for MultiLineString {
+    fn geometry_parts(&self) -> Vec<GeometryPart> {
+        geo::MultiLineString::<f64>::from(self)
+            .0
+            .into_iter()
+            .map(GeometryPart::LineString)
+            .collect()
+    }
+}
+
+impl IntoGeometryParts for MultiPolygon {
+    fn geometry_parts(&self) -> Vec<GeometryPart> {
+        geo::MultiPolygon::<f64>::from(self)
+            .0
+            .into_iter()
+            .map(GeometryPart::Polygon)
+            .collect()
+    }
+}
+
+impl SpatialJoinPredicate {
+    fn matches(self, left: &[GeometryPart], right: &[GeometryPart]) -> bool {
+        match self {
+            SpatialJoinPredicate::Intersects => {
+                left.iter().any(|l| right.iter().any(|r| l.intersects(r)))
+            }
+            SpatialJoinPredicate::Contains => {
+                !right.is_empty() && right.iter().all(|r| left.iter().any(|l| l.contains(r)))
+            }
+            SpatialJoinPredicate::WithinDistance { distance } => left
+                .iter()
+                .any(|l| right.iter().any(|r| l.distance(r) <= distance)),
+        }
+    }
+}
+
+/// The features of the right input with their values in the order of the requested right columns
+pub(super) struct RightFeature {
+    pub parts: Vec<GeometryPart>,
+    pub time_interval: TimeInterval,
+    pub values: Vec<FeatureDataValue>,
+}
+
+/// Implements a spatial left join that attaches the (aggregated) values of all
+/// matching right features to the features of the left input.
+pub struct SpatialJoinProcessor<G> {
+    left_processor: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    right_processor: TypedVectorQueryProcessor,
+    predicate: SpatialJoinPredicate,
+    aggregation: SpatialJoinAggregation,
+    /// the right columns with their types and their names in the output
+    right_columns: Arc<Vec<(String, FeatureDataType, String)>>,
+}
+
+impl<G> SpatialJoinProcessor<G>
+where
+    G: Geometry + ArrowTyped + IntoGeometryParts + Sync + Send + 'static,
+    for<'g> FeatureCollection<G>: GeometryRandomAccess<'g>,
+    for<'g> <FeatureCollection<G> as GeometryRandomAccess<'g>>::GeometryType: Into<G>,
+    FeatureCollectionRowBuilder<G>: GeoFeatureCollectionRowBuilder<G>,
+{
+    pub fn new(
+        left_processor: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+        right_processor: TypedVectorQueryProcessor,
+        predicate: SpatialJoinPredicate,
+        aggregation: SpatialJoinAggregation,
+        right_columns: Vec<(String, FeatureDataType, String)>,
+    ) -> Self {
+        Self {
+            left_processor,
+            right_processor,
+            predicate,
+            aggregation,
+            right_columns: Arc::new(right_columns),
+        }
+    }
+
+    fn join(
+        &self,
+        left: &FeatureCollection<G>,
+        right_features: &[RightFeature],
+    ) -> Result<FeatureCollection<G>> {
+        let mut builder = FeatureCollection::<G>::builder();
+
+        for (column_name, column_type) in left.column_types() {
+            builder.add_column(column_name, column_type)?;
+        }
+        for (_, column_type, output_column_name) in self.right_columns.iter() {
+            builder.add_column(
+                output_column_name.clone(),
+                self.aggregation.output_type(*column_type),
+            )?;
+        }
+
+        let mut builder = builder.finish_header();
+
+        let left_data: Vec<_> = left
+            .column_names()
+            .map(|column_name| {
+                (
+                    column_name.clone(),
+                    left.data(column_name).expect("must exist"),
+                )
+            })
+            .collect();
+
+        for (left_idx, &time_interval) in left.time_intervals().iter().enumerate() {
+            let geometry: G = left.geometry_at(left_idx).expect("index must exist").into();
+            let parts = geometry.geometry_parts();
+
+            let matches: Vec<&RightFeature> = right_features
+                .iter()
+                .filter(|right| {
+                    time_interval.intersects(&right.time_interval)
+                        && self.predicate.matches(&parts, &right.parts)
+                })
+                .collect();
+
+            for (column_name, data) in &left_data {
+                builder.push_data(column_name, data.get_unchecked(left_idx))?;
+            }
+
+            for (column_idx, (_, column_type, output_column_name)) in
+                self.right_columns.iter().enumerate()
+            {
+                let values: Vec<&FeatureDataValue> = matches
+                    .iter()
+                    .map(|right| &right.values[column_idx])
+                    .collect();
+
+                builder.push_data(
+                    output_column_name,
+                    self.aggregation.aggregate(&values, *column_type),
+                )?;
+            }
+
+            builder.push_geometry(geometry)?;
+            builder.push_time_interval(time_interval)?;
+            builder.finish_row();
+        }
+
+        builder.build().map_err(Into::into)
+    }
+}
+
+/// Collects the features of a geo collection input with the values of the `right_columns`
+pub(super) async fn right_features(
+    processor: &TypedVectorQueryProcessor,
+    right_columns: &[(String, FeatureDataType, String)],
+    query: VectorQueryRectangle,
+    ctx: &dyn QueryContext,
+) -> Result<Vec<RightFeature>> {
+    match processor {
+        TypedVectorQueryProcessor::Data(_) => unreachable!("checked in constructor"),
+        TypedVectorQueryProcessor::MultiPoint(processor) => {
+            collect_right_features(processor.as_ref(), right_columns, query, ctx).await
+        }
+        TypedVectorQueryProcessor::MultiLineString(processor) => {
+            collect_right_features(processor.as_ref(), right_columns, query, ctx).await
+        }
+        TypedVectorQueryProcessor::MultiPolygon(processor) => {
+            collect_right_features(processor.as_ref(), right_columns, query, ctx).await
+        }
+    }
+}
+
+async fn collect_right_features<R>(
+    processor: &dyn VectorQueryProcessor<VectorType = FeatureCollection<R>>,
+    right_columns: &[(String, FeatureDataType, String)],
+    query: VectorQueryRectangle,
+    ctx: &dyn QueryContext,
+) -> Result<Vec<RightFeature>>
+where
+    R: Geometry + ArrowTyped + IntoGeometryParts,
+    for<'g> FeatureCollection<R>: GeometryRandomAccess<'g>,
+    for<'g> <FeatureCollection<R> as GeometryRandomAccess<'g>>::GeometryType: Into<R>,
+{
+    let mut stream = processor.query(query, ctx).await?;
+    let mut features = Vec::new();
+
+    while let Some(collection) = stream.next().await {
+        let collection = collection?;
+
+        let data = right_columns
+            .iter()
+            .map(|(column_name, _, _)| collection.data(column_name))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (idx, &time_interval) in collection.time_intervals().iter().enumerate() {
+            let geometry: R = collection
+                .geometry_at(idx)
+                .expect("index must exist")
+                .into();
+
+            features.push(RightFeature {
+                parts: geometry.geometry_parts(),
+                time_interval,
+                values: data.iter().map(|data| data.get_unchecked(idx)).collect(),
+            });
+        }
+    }
+
+    Ok(features)
+}
+
+#[async_trait]
+impl<G> QueryProcessor for SpatialJoinProcessor<G>
+where
+    G: Geometry + ArrowTyped + IntoGeometryParts + Sync + Send + 'static,
+    for<'g> FeatureCollection<G>: GeometryRandomAccess<'g>,
+    for<'g> <FeatureCollection<G> as GeometryRandomAccess<'g>>::GeometryType: Into<G>,
+    FeatureCollectionRowBuilder<G>: GeoFeatureCollectionRowBuilder<G>,
+{
+    type Output = FeatureCollection<G>;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        // This implementation is a nested-loop join
+        let right_features =
+            Arc::new(right_features(&self.right_processor, &self.right_columns, query, ctx).await?);
+
+        let result_stream =
+            self.left_processor
+                .query(query, ctx)
+                .await?
+                .and_then(move |left_collection| {
+                    let right_features = right_features.clone();
+                    async move { self.join(&left_collection, &right_features) }
+                });
+
+        Ok(FeatureCollectionChunkMerger::new(result_stream.fuse(), ctx.chunk_byte_size()).boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, VectorOperator};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::{MultiPointCollection, MultiPolygonCollection};
+    use geoengine_datatypes::primitives::{FeatureData, SpatialResolution};
+
+    fn polygons() -> MultiPolygonCollection {
+        MultiPolygonCollection::from_slices(
+            &[
+                MultiPolygon::new(vec![vec![vec![
+                    (0.0, 0.0).into(),
+                    (10.0, 0.0).into(),
+                    (10.0, 10.0).into(),
+                    (0.0, 10.0).into(),
+                    (0.0, 0.0).into(),
+                ]]])
+                .unwrap(),
+                MultiPolygon::new(vec![vec![vec![
+                    (20.0, 20.0).into(),
+                    (30.0, 20.0).into(),
+                    (30.0, 30.0).into(),
+                    (20.0, 30.0).into(),
+                    (20.0, 20.0).into(),
+                ]]])
+                .unwrap(),
+            ],
+            &[TimeInterval::default(); 2],
+            &[("name", FeatureData::Text(vec!["a".into(), "b".into()]))],
+        )
+        .unwrap()
+    }
+
+    fn points() -> MultiPointCollection {
+        MultiPointCollection::from_slices(
+            &MultiPoint::many(vec![(1.0, 1.0), (5.0, 5.0), (15.0, 15.0)]).unwrap(),
+            &[TimeInterval::default(); 3],
+            &[
+                (
+                    "name",
+                    FeatureData::Text(vec!["x".into(), "y".into(), "z".into()]),
+                ),
+                ("value", FeatureData::Int(vec![1, 2, 4])),
+            ],
+        )
+        .unwrap()
+    }
+
+    async fn join(
+        predicate: SpatialJoinPredicate,
+        aggregation: SpatialJoinAggregation,
+    ) -> MultiPolygonCollection {
+        let execution_context = MockExecutionContext::default();
+
+        let left = MockFeatureCollectionSource::single(polygons())
+            .boxed()
+            .initialize(&execution_context)
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .multi_polygon()
+            .unwrap();
+        let right = MockFeatureCollectionSource::single(points())
+            .boxed()
+            .initialize(&execution_context)
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap();
+
+        let processor = SpatialJoinProcessor::new(
+            left,
+            right,
+            predicate,
+            aggregation,
+            vec![
+                (
+                    "name".to_string(),
+                    FeatureDataType::Text,
+                    "name_right".to_string(),
+                ),
+                (
+                    "value".to_string(),
+                    FeatureDataType::Int,
+                    "value".to_string(),
+                ),
+            ],
+        );
+
+        let mut result = processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((0., 0.).into(), (30., 30.).into()).unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::zero_point_one(),
+                },
+                &MockQueryContext::default(),
+            )
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(result.len(), 1);
+
+        result.remove(0).unwrap()
+    }
+
+    #[test]
+    fn params() {
+        let predicate = SpatialJoinPredicate::WithinDistance { distance: 1.5 };
+
+        let json = serde_json::to_value(&predicate).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "withinDistance",
+                "distance": 1.5,
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<SpatialJoinPredicate>(json).unwrap(),
+            predicate
+        );
+    }
+
+    #[tokio::test]
+    async fn it_attaches_first_matches() {
+        let result = join(
+            SpatialJoinPredicate::Contains,
+            SpatialJoinAggregation::First,
+        )
+        .await;
+
+        assert_eq!(
+            result
+                .data("name")
+                .unwrap()
+                .strings_iter()
+                .collect::<Vec<_>>(),
+            vec!["a".to_string(), "b".to_string()]
+        );
+        assert_eq!(
+            result.data("name_right").unwrap().nulls(),
+            vec![false, true]
+        );
+        assert_eq!(
+            result.data("name_right").unwrap().get_unchecked(0),
+            FeatureDataValue::NullableText(Some("x".to_string()))
+        );
+        assert_eq!(
+            result.data("value").unwrap().get_unchecked(0),
+            FeatureDataValue::NullableInt(Some(1))
+        );
+    }
+
+    #[tokio::test]
+    async fn it_aggregates_matches() {
+        let result = join(
+            SpatialJoinPredicate::WithinDistance { distance: 8. },
+            SpatialJoinAggregation::Sum,
+        )
+        .await;
+
+        assert_eq!(
+            result
+                .data("value")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(7.), Some(4.)]
+        );
+        assert_eq!(
+            result
+                .data("name_right")
+                .unwrap()
+                .strings_iter()
+                .collect::<Vec<_>>(),
+            vec!["x".to_string(), "z".to_string()]
+        );
+    }
+}
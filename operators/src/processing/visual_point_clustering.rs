@@ -0,0 +1,451 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    BuilderProvider, FeatureCollectionInfos, GeoFeatureCollectionRowBuilder, IntoGeometryIterator,
+    MultiPointCollection, VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, Coordinate2D, FeatureDataType, FeatureDataValue, MultiPoint, MultiPointAccess,
+    TimeInterval,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use super::feature_aggregation::{Accumulator, AggregationFunction, ColumnAggregation};
+use crate::engine::{
+    ExecutionContext, InitializedVectorOperator, Operator, QueryContext, QueryProcessor,
+    SingleVectorSource, TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor,
+    VectorQueryRectangle, VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::Result;
+
+/// The column with the number of points of a cluster
+const COUNT_COLUMN: &str = "count";
+
+/// An operator that merges points that are close to each other at the resolution of the query into clusters,
+/// s.t. maps of dense point collections stay readable and small at small scales.
+///
+/// The points are clustered on a grid whose cells are `clusterSizePx` pixels large.
+/// Each cluster is a point at the mean location of its points with the number of points in a `count` column
+/// and the aggregated `columnAggregates` of their columns. Other columns are dropped.
+pub type VisualPointClustering = Operator<VisualPointClusteringParams, SingleVectorSource>;
+
+/// The parameter spec for `VisualPointClustering`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VisualPointClusteringParams {
+    /// The edge length of the grid cells in pixels of the query resolution
+    pub cluster_size_px: f64,
+    #[serde(default)]
+    pub column_aggregates: Vec<ColumnAggregation>,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for VisualPointClustering {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        ensure!(
+            self.params.cluster_size_px.is_finite() && self.params.cluster_size_px > 0.,
+            error::InvalidOperatorSpec {
+                reason: "the cluster size must be positive".to_string()
+            }
+        );
+
+        let vector_source = self.sources.vector.initialize(context).await?;
+        let source_descriptor = vector_source.result_descriptor();
+
+        ensure!(
+            source_descriptor.data_type == VectorDataType::MultiPoint,
+            error::InvalidType {
+                expected: VectorDataType::MultiPoint.to_string(),
+                found: source_descriptor.data_type.to_string(),
+            }
+        );
+
+        let mut columns = HashMap::with_capacity(self.params.column_aggregates.len() + 1);
+        columns.insert(COUNT_COLUMN.to_string(), FeatureDataType::Int);
+
+        for aggregation in &self.params.column_aggregates {
+            let data_type = source_descriptor
+                .columns
+                .get(&aggregation.column)
+                .ok_or_else(|| error::Error::ColumnDoesNotExist {
+                    column: aggregation.column.clone(),
+                })?;
+
+            ensure!(data_type.is_numeric(), error::InvalidFeatureDataType);
+
+            let output_column = aggregation.output_column();
+
+            ensure!(
+                columns
+                    .insert(output_column.clone(), aggregation.function.output_type())
+                    .is_none(),
+                error::InvalidOperatorSpec {
+                    reason: format!("output column `{}` is not unique", output_column)
+                }
+            );
+        }
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: VectorDataType::MultiPoint,
+            spatial_reference: source_descriptor.spatial_reference,
+            columns,
+        };
+
+        Ok(InitializedVisualPointClustering {
+            result_descriptor,
+            vector_source,
+            state: self.params,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedVisualPointClustering {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    state: VisualPointClusteringParams,
+}
+
+impl InitializedVectorOperator for InitializedVisualPointClustering {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let source = self
+            .vector_source
+            .query_processor()?
+            .multi_point()
+            .expect("checked in initialization");
+
+        Ok(TypedVectorQueryProcessor::MultiPoint(
+            VisualPointClusteringProcessor {
+                source,
+                params: self.state.clone(),
+                columns: self.result_descriptor.columns.clone(),
+            }
+            .boxed(),
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct VisualPointClusteringProcessor {
+    source: Box<dyn VectorQueryProcessor<VectorType = MultiPointCollection>>,
+    params: VisualPointClusteringParams,
+    columns: HashMap<String, FeatureDataType>,
+}
+
+#[async_trait]
+impl QueryProcessor for VisualPointClusteringProcessor {
+    type Output = MultiPointCollection;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        // the grid is anchored at the origin, s.t. adjacent queries, e.g., map tiles, produce the same clusters
+        let cell_size = Coordinate2D::new(
+            self.params.cluster_size_px * query.spatial_resolution.x,
+            self.params.cluster_size_px * query.spatial_resolution.y,
+        );
+
+        let clusters = self.source.query(query, ctx).await?.try_fold(
+            Clusters::new(cell_size),
+            |mut clusters, collection| async move {
+                clusters.add_collection(&collection, &self.params)?;
+                Ok(clusters)
+            },
+        );
+
+        // clusters can span multiple input collections, so they are emitted after all points were consumed
+        Ok(stream::once(
+            async move { clusters.await?.into_collection(&self.params, &self.columns) },
+        )
+        .boxed())
+    }
+}
+
+/// The clusters in order of their first point
+#[derive(Debug)]
+struct Clusters {
+    cell_size: Coordinate2D,
+    indices: HashMap<(i64, i64), usize>,
+    clusters: Vec<Cluster>,
+}
+
+#[derive(Debug)]
+struct Cluster {
+    coordinate_sum: Coordinate2D,
+    count: u64,
+    time: TimeInterval,
+    accumulators: Vec<Accumulator>,
+}
+
+impl Clusters {
+    fn new(cell_size: Coordinate2D) -> Self {
+        Self {
+            cell_size,
+            indices: HashMap::new(),
+            clusters: Vec::new(),
+        }
+    }
+
+    fn add_collection(
+        &mut self,
+        collection: &MultiPointCollection,
+        params: &VisualPointClusteringParams,
+    ) -> Result<()> {
+        let aggregation_values = params
+            .column_aggregates
+            .iter()
+            .map(|aggregation| {
+                let data = collection.data(&aggregation.column)?;
+
+                let values: Vec<Option<f64>> = if aggregation.function == AggregationFunction::Count
+                {
+                    // only the presence of a value is relevant for counting
+                    data.nulls()
+                        .into_iter()
+                        .map(|is_null| if is_null { None } else { Some(1.) })
+                        .collect()
+                } else {
+                    data.float_options_iter().collect()
+                };
+
+                Ok(values)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        for (row, (multi_point, time)) in collection
+            .geometries()
+            .zip(collection.time_intervals())
+            .enumerate()
+        {
+            // a feature with multiple points is clustered at their mean location
+            let points = multi_point.points();
+            let coordinate = points
+                .iter()
+                .fold(Coordinate2D::new(0., 0.), |sum, &point| sum + point)
+                / points.len() as f64;
+
+            let cell = (
+                (coordinate.x / self.cell_size.x).floor() as i64,
+                (coordinate.y / self.cell_size.y).floor() as i64,
+            );
+
+            let clusters = &mut self.clusters;
+            let index = *self.indices.entry(cell).or_insert_with(|| {
+                clusters.push(Cluster {
+                    coordinate_sum: Coordinate2D::new(0., 0.),
+                    count: 0,
+                    time: *time,
+                    accumulators: vec![Accumulator::default(); params.column_aggregates.len()],
+                });
+                clusters.len() - 1
+            });
+
+            let cluster = &mut self.clusters[index];
+            cluster.coordinate_sum = cluster.coordinate_sum + coordinate;
+            cluster.count += 1;
+            cluster.time = cluster.time.extend(time);
+
+            for (accumulator, values) in cluster.accumulators.iter_mut().zip(&aggregation_values) {
+                if let Some(value) = values[row] {
+                    accumulator.add(value);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn into_collection(
+        self,
+        params: &VisualPointClusteringParams,
+        columns: &HashMap<String, FeatureDataType>,
+    ) -> Result<MultiPointCollection> {
+        let mut builder = MultiPointCollection::builder();
+        for (column, data_type) in columns {
+            builder.add_column(column.clone(), *data_type)?;
+        }
+        let mut builder = builder.finish_header();
+
+        let output_columns: Vec<String> = params
+            .column_aggregates
+            .iter()
+            .map(ColumnAggregation::output_column)
+            .collect();
+
+        for cluster in self.clusters {
+            builder.push_geometry(MultiPoint::new(vec![
+                cluster.coordinate_sum / cluster.count as f64,
+            ])?)?;
+            builder.push_time_interval(cluster.time)?;
+            builder.push_data(COUNT_COLUMN, FeatureDataValue::Int(cluster.count as i64))?;
+
+            for ((column, aggregation), accumulator) in output_columns
+                .iter()
+                .zip(&params.column_aggregates)
+                .zip(&cluster.accumulators)
+            {
+                builder.push_data(column, accumulator.result(aggregation.function))?;
+            }
+
+            builder.finish_row();
+        }
+
+        builder.build().map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::GeometryCollection;
+    use geoengine_datatypes::primitives::{FeatureData, SpatialResolution};
+
+    fn clustering(
+        collection: MultiPointCollection,
+        column_aggregates: Vec<ColumnAggregation>,
+    ) -> Box<dyn VectorOperator> {
+        VisualPointClustering {
+            params: VisualPointClusteringParams {
+                cluster_size_px: 2.,
+                column_aggregates,
+            },
+            sources: MockFeatureCollectionSource::single(collection)
+                .boxed()
+                .into(),
+        }
+        .boxed()
+    }
+
+    #[tokio::test]
+    async fn it_clusters_points() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.0), (1.0, 1.5), (10.0, 10.0), (0.5, 0.5)]).unwrap(),
+            vec![
+                TimeInterval::new(0, 1).unwrap(),
+                TimeInterval::new(1, 2).unwrap(),
+                TimeInterval::new(0, 1).unwrap(),
+                TimeInterval::new(4, 5).unwrap(),
+            ],
+            [(
+                "foo".to_string(),
+                FeatureData::NullableFloat(vec![Some(1.), Some(2.), Some(3.), None]),
+            )]
+            .iter()
+            .cloned()
+            .collect(),
+        )
+        .unwrap();
+
+        let operator = clustering(
+            collection,
+            vec![ColumnAggregation {
+                column: "foo".to_string(),
+                function: AggregationFunction::Mean,
+                output_column: None,
+            }],
+        )
+        .initialize(&MockExecutionContext::default())
+        .await
+        .unwrap();
+
+        assert_eq!(
+            operator.result_descriptor().columns,
+            [
+                ("count".to_string(), FeatureDataType::Int),
+                ("foo_mean".to_string(), FeatureDataType::Float)
+            ]
+            .iter()
+            .cloned()
+            .collect()
+        );
+
+        let processor = operator.query_processor().unwrap().multi_point().unwrap();
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (20., 20.).into()).unwrap(),
+            time_interval: TimeInterval::new(0, 10).unwrap(),
+            spatial_resolution: SpatialResolution::one(),
+        };
+
+        let collections: Vec<MultiPointCollection> = processor
+            .query(query, &MockQueryContext::default())
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(collections.len(), 1);
+
+        let result = &collections[0];
+
+        assert_eq!(
+            result.coordinates(),
+            &[(0.5, 2. / 3.).into(), (10.0, 10.0).into()]
+        );
+        assert_eq!(
+            result.time_intervals(),
+            &[
+                TimeInterval::new(0, 5).unwrap(),
+                TimeInterval::new(0, 1).unwrap()
+            ]
+        );
+        assert_eq!(
+            result
+                .data("count")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(3.), Some(1.)]
+        );
+        assert_eq!(
+            result
+                .data("foo_mean")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(1.5), Some(3.)]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_requires_a_positive_cluster_size() {
+        let collection = MultiPointCollection::empty();
+
+        let result = VisualPointClustering {
+            params: VisualPointClusteringParams {
+                cluster_size_px: 0.,
+                column_aggregates: vec![],
+            },
+            sources: MockFeatureCollectionSource::single(collection)
+                .boxed()
+                .into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(error::Error::InvalidOperatorSpec { .. })
+        ));
+    }
+}
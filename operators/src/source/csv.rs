@@ -8,6 +8,7 @@ use futures::stream::BoxStream;
 use futures::task::{Context, Poll};
 use futures::{Stream, StreamExt};
 use geoengine_datatypes::dataset::DatasetId;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use snafu::{ensure, OptionExt, ResultExt};
 
@@ -63,7 +64,7 @@ use std::sync::atomic::Ordering;
 ///     },
 /// });
 /// ```
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CsvSourceParameters {
     pub file_path: PathBuf,
@@ -73,14 +74,14 @@ pub struct CsvSourceParameters {
     pub time: CsvTimeSpecification,
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum CsvGeometrySpecification {
     #[allow(clippy::upper_case_acronyms)]
     XY { x: String, y: String },
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, JsonSchema)]
 pub enum CsvTimeSpecification {
     None,
 }
@@ -153,8 +154,10 @@ impl OperatorDatasets for CsvSourceParameters {
 impl VectorOperator for CsvSource {
     async fn initialize(
         self: Box<Self>,
-        _context: &dyn crate::engine::ExecutionContext,
+        context: &dyn crate::engine::ExecutionContext,
     ) -> Result<Box<dyn InitializedVectorOperator>> {
+        context.file_path_policy().check(&self.params.file_path)?;
+
         let initialized_source = InitializedCsvSource {
             result_descriptor: VectorResultDescriptor {
                 data_type: VectorDataType::MultiPoint, // TODO: get as user input
@@ -0,0 +1,331 @@
+use std::fmt::Debug;
+use std::path::{Component, Path, PathBuf};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{MetaData, ResultDescriptor};
+use crate::error::Error;
+use crate::source::{GdalLoadingInfo, OgrSourceDataset};
+use crate::util::Result;
+
+/// Virtual file systems of GDAL that address remote resources instead of local files
+const REMOTE_VIRTUAL_FILE_SYSTEMS: [&str; 9] = [
+    "vsicurl",
+    "vsis3",
+    "vsigs",
+    "vsiaz",
+    "vsiadls",
+    "vsioss",
+    "vsiswift",
+    "vsihdfs",
+    "vsiwebhdfs",
+];
+
+/// Restricts the file paths that sources read or hand to GDAL,
+/// s.t. workflows and dataset definitions cannot read arbitrary files of the server.
+///
+/// Paths may be prefixed with (chained) virtual file systems, e.g., `/vsizip/data/ports.zip/ports.shp`.
+/// Remote resources, i.e., URLs, GDAL connection strings like `PG:dbname=…` and remote virtual file systems
+/// like `/vsicurl/`, are only checked against the `denied_schemes`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilePathPolicy {
+    /// Local files must be within one of these directories. All local files are allowed if it is `None`.
+    pub data_roots: Option<Vec<PathBuf>>,
+    /// URL schemes, GDAL connection prefixes and virtual file systems, e.g., `file`, `PG` or `vsicurl`
+    pub denied_schemes: Vec<String>,
+}
+
+impl FilePathPolicy {
+    pub fn check(&self, path: &Path) -> Result<()> {
+        let path_string = path.to_string_lossy();
+        let (file_systems, rest) = Self::split_virtual_file_systems(&path_string);
+
+        let mut remote = false;
+        for file_system in file_systems {
+            self.check_scheme(file_system)?;
+            remote |= REMOTE_VIRTUAL_FILE_SYSTEMS.contains(&file_system);
+        }
+
+        // `/vsicurl?url=…` has no path
+        let rest = match rest {
+            Some(rest) => rest,
+            None => return Ok(()),
+        };
+
+        if let Some(scheme) = scheme(rest) {
+            return self.check_scheme(scheme);
+        }
+
+        let data_roots = match &self.data_roots {
+            Some(data_roots) if !remote => data_roots,
+            _ => return Ok(()),
+        };
+
+        let path = normalize(Path::new(rest));
+
+        if data_roots
+            .iter()
+            .any(|data_root| path.starts_with(normalize(data_root)))
+        {
+            Ok(())
+        } else {
+            Err(Error::FilePathOutsideOfDataRoots {
+                path: path_string.to_string(),
+            })
+        }
+    }
+
+    /// Splits the leading (chained) virtual file systems off the `path`, e.g.,
+    /// `vsizip` and `vsicurl` for `/vsizip//vsicurl/http://…` or `/vsizip/{/vsicurl/…}/…`.
+    /// Streaming variants like `/vsicurl_streaming/` are reported as their base file system.
+    ///
+    /// The remaining path is `None` if the last virtual file system has options instead of a path,
+    /// e.g., `/vsicurl?url=…`.
+    pub fn split_virtual_file_systems(path: &str) -> (Vec<&str>, Option<&str>) {
+        let mut file_systems = Vec::new();
+        let mut rest = path;
+
+        while let Some(prefixed) = rest
+            .trim_start_matches('{')
+            .strip_prefix('/')
+            .filter(|prefixed| prefixed.starts_with("vsi"))
+        {
+            let end = prefixed
+                .find(|c| c == '/' || c == '?')
+                .unwrap_or_else(|| prefixed.len());
+
+            file_systems.push(prefixed[..end].trim_end_matches("_streaming"));
+
+            if prefixed[end..].starts_with('?') {
+                return (file_systems, None);
+            }
+
+            rest = prefixed.get(end + 1..).unwrap_or_default();
+        }
+
+        (file_systems, Some(rest.trim_start_matches('{')))
+    }
+
+    fn check_scheme(&self, scheme: &str) -> Result<()> {
+        if self
+            .denied_schemes
+            .iter()
+            .any(|denied| denied.eq_ignore_ascii_case(scheme))
+        {
+            Err(Error::FilePathSchemeNotAllowed {
+                scheme: scheme.to_string(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The scheme of URLs and GDAL connection strings, e.g., `https` or `PG`.
+/// Single letters are drive letters on Windows.
+fn scheme(path: &str) -> Option<&str> {
+    let (scheme, _) = path.split_once(':')?;
+
+    let mut chars = scheme.chars();
+    let is_scheme = scheme.len() > 1
+        && chars.next().map_or(false, |c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.' | '_'));
+
+    if is_scheme {
+        Some(scheme)
+    } else {
+        None
+    }
+}
+
+/// Makes the `path` absolute and resolves `.` and `..` without accessing the file system
+fn normalize(path: &Path) -> PathBuf {
+    let path = std::env::current_dir()
+        .map(|current_dir| current_dir.join(path))
+        .unwrap_or_else(|_| path.to_path_buf());
+
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    normalized
+}
+
+/// Loading infos that contain the paths of the files to read
+pub trait FilePaths {
+    fn file_paths(&self) -> Result<Vec<PathBuf>>;
+}
+
+impl FilePaths for GdalLoadingInfo {
+    fn file_paths(&self) -> Result<Vec<PathBuf>> {
+        self.info
+            .clone()
+            .map(|part| part.map(|part| part.params.file_path))
+            .collect()
+    }
+}
+
+impl FilePaths for OgrSourceDataset {
+    fn file_paths(&self) -> Result<Vec<PathBuf>> {
+        Ok(vec![self.file_name.clone()])
+    }
+}
+
+/// Checks the file paths of the loading infos of another `MetaData` before a source reads them
+#[derive(Debug)]
+pub struct FilePathCheckedMetaData<L, R, Q> {
+    meta_data: Box<dyn MetaData<L, R, Q>>,
+    policy: FilePathPolicy,
+}
+
+impl<L, R, Q> FilePathCheckedMetaData<L, R, Q>
+where
+    L: FilePaths + Debug + Clone + Send + Sync + 'static,
+    R: ResultDescriptor + Debug + Send + Sync + 'static,
+    Q: Debug + Clone + Send + Sync + 'static,
+{
+    pub fn boxed(
+        meta_data: Box<dyn MetaData<L, R, Q>>,
+        policy: FilePathPolicy,
+    ) -> Box<dyn MetaData<L, R, Q>> {
+        Box::new(Self { meta_data, policy })
+    }
+}
+
+#[async_trait]
+impl<L, R, Q> MetaData<L, R, Q> for FilePathCheckedMetaData<L, R, Q>
+where
+    L: FilePaths + Debug + Clone + Send + Sync + 'static,
+    R: ResultDescriptor + Debug + Send + Sync + 'static,
+    Q: Debug + Clone + Send + Sync + 'static,
+{
+    async fn loading_info(&self, query: Q) -> Result<L> {
+        let loading_info = self.meta_data.loading_info(query).await?;
+
+        for file_path in loading_info.file_paths()? {
+            self.policy.check(&file_path)?;
+        }
+
+        Ok(loading_info)
+    }
+
+    async fn result_descriptor(&self) -> Result<R> {
+        self.meta_data.result_descriptor().await
+    }
+
+    fn box_clone(&self) -> Box<dyn MetaData<L, R, Q>> {
+        Box::new(Self {
+            meta_data: self.meta_data.clone(),
+            policy: self.policy.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(data_roots: Option<&[&str]>, denied_schemes: &[&str]) -> FilePathPolicy {
+        FilePathPolicy {
+            data_roots: data_roots.map(|roots| roots.iter().map(PathBuf::from).collect()),
+            denied_schemes: denied_schemes.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn it_jails_local_files() {
+        let policy = policy(Some(&["test-data", "/data"]), &[]);
+
+        assert!(policy.check(Path::new("test-data/raster/ndvi.tif")).is_ok());
+        assert!(policy
+            .check(Path::new("./test-data/../test-data/a.tif"))
+            .is_ok());
+        assert!(policy.check(Path::new("/data/a.tif")).is_ok());
+        assert!(policy.check(Path::new("/vsizip//data/a.zip/a.shp")).is_ok());
+        assert!(policy
+            .check(Path::new("/vsizip/{/data/a.zip}/a.shp"))
+            .is_ok());
+
+        for path in [
+            "/etc/passwd",
+            "test-data/../../secret.tif",
+            "/data/../etc/passwd",
+            "/vsizip//etc/a.zip/a.shp",
+            "/vsimem/export.tiff",
+        ] {
+            assert!(
+                matches!(
+                    policy.check(Path::new(path)),
+                    Err(Error::FilePathOutsideOfDataRoots { .. })
+                ),
+                "{}",
+                path
+            );
+        }
+
+        assert!(self::policy(None, &[])
+            .check(Path::new("/etc/passwd"))
+            .is_ok());
+    }
+
+    #[test]
+    fn it_denies_schemes() {
+        let policy = policy(Some(&["/data"]), &["file", "PG", "vsicurl"]);
+
+        assert!(policy.check(Path::new("/vsis3/bucket/ndvi.tif")).is_ok());
+        assert!(policy
+            .check(Path::new("/vsizip//vsis3/bucket/ports.zip/ports.shp"))
+            .is_ok());
+        assert!(policy
+            .check(Path::new("WFS:https://example.com/wfs"))
+            .is_ok());
+
+        for (path, scheme) in [
+            ("file:///etc/passwd", "file"),
+            ("PG:host=localhost dbname=geoengine", "PG"),
+            ("/vsicurl/https://example.com/ndvi.tif", "vsicurl"),
+            ("/vsicurl_streaming/https://example.com/ndvi.tif", "vsicurl"),
+            ("/vsicurl?url=https://example.com/ndvi.tif", "vsicurl"),
+            (
+                "/vsizip/{/vsicurl/https://example.com/a.zip}/a.shp",
+                "vsicurl",
+            ),
+        ] {
+            assert!(
+                matches!(
+                    policy.check(Path::new(path)),
+                    Err(Error::FilePathSchemeNotAllowed { scheme: s }) if s == scheme
+                ),
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn it_splits_virtual_file_systems() {
+        assert_eq!(
+            FilePathPolicy::split_virtual_file_systems("/vsizip//vsicurl_streaming/http://a/b.zip"),
+            (vec!["vsizip", "vsicurl"], Some("http://a/b.zip"))
+        );
+        assert_eq!(
+            FilePathPolicy::split_virtual_file_systems("/vsicurl?url=http://a/b.tif"),
+            (vec!["vsicurl"], None)
+        );
+
+        // only leading prefixes are virtual file systems
+        assert_eq!(
+            FilePathPolicy::split_virtual_file_systems("data/vsix_survey/a.tif"),
+            (vec![], Some("data/vsix_survey/a.tif"))
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::engine::{MetaData, OperatorDatasets, QueryProcessor, RasterQueryRectangle};
+use crate::source::FilePathCheckedMetaData;
 use crate::{
     engine::{
         InitializedRasterOperator, RasterOperator, RasterQueryProcessor, RasterResultDescriptor,
@@ -13,14 +14,15 @@ use futures::{
 };
 
 use async_trait::async_trait;
-use gdal::raster::{GdalType, RasterBand as GdalRasterBand};
-use gdal::{Dataset as GdalDataset, DatasetOptions, Metadata as GdalMetadata};
+use gdal::raster::{reproject, Buffer, GdalType, RasterBand as GdalRasterBand};
+use gdal::{Dataset as GdalDataset, DatasetOptions, Driver, Metadata as GdalMetadata};
 use geoengine_datatypes::primitives::{Coordinate2D, SpatialPartition2D, SpatialPartitioned};
 use geoengine_datatypes::raster::{
-    EmptyGrid, GeoTransform, Grid2D, GridOrEmpty2D, GridShapeAccess, Pixel, RasterDataType,
-    RasterProperties, RasterPropertiesEntry, RasterPropertiesEntryType, RasterPropertiesKey,
-    RasterTile2D,
+    EmptyGrid, GeoTransform, Grid2D, GridOrEmpty2D, GridShape2D, GridShapeAccess, Pixel,
+    RasterDataType, RasterProperties, RasterPropertiesEntry, RasterPropertiesEntryType,
+    RasterPropertiesKey, RasterTile2D,
 };
+use geoengine_datatypes::spatial_reference::SpatialReference;
 use geoengine_datatypes::{dataset::DatasetId, raster::TileInformation};
 use geoengine_datatypes::{
     primitives::{TimeInstance, TimeInterval, TimeStep, TimeStepIter},
@@ -30,8 +32,11 @@ use geoengine_datatypes::{
     },
 };
 use log::debug;
+use num_traits::AsPrimitive;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
+use std::convert::TryInto;
 use std::{marker::PhantomData, path::PathBuf};
 //use gdal::metadata::Metadata; // TODO: handle metadata
 
@@ -65,7 +70,7 @@ use std::{marker::PhantomData, path::PathBuf};
 ///     },
 /// });
 /// ```
-#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GdalSourceParameters {
     pub dataset: DatasetId,
 }
@@ -155,6 +160,10 @@ pub struct GdalDatasetParameters {
     pub no_data_value: Option<f64>,
     pub properties_mapping: Option<Vec<GdalMetadataMapping>>,
     pub gdal_open_options: Option<Vec<String>>,
+    /// Warps the file on the fly into this spatial reference, in which the `geo_transform` is given.
+    /// The file must carry its own spatial reference and only have the band `rasterband_channel` 1.
+    #[serde(default)]
+    pub reproject_to: Option<SpatialReference>,
 }
 
 impl SpatialPartitioned for GdalDatasetParameters {
@@ -469,6 +478,22 @@ where
             }
         };
 
+        // the `geo_transform` of reprojected files is given in the target spatial reference,
+        // so they are only warped if they intersect the tile, too
+        if let Some(spatial_reference) = dataset_params.reproject_to {
+            return Ok(GridWithProperties {
+                grid: read_reprojected(
+                    &dataset,
+                    spatial_reference,
+                    output_geo_transform,
+                    output_shape,
+                    no_data_value,
+                )?
+                .into(),
+                properties,
+            });
+        }
+
         let dataset_grid_bounds = geo_transform.spatial_to_grid_bounds(&dataset_intersection_area);
 
         let result_grid = if dataset_intersection_area == output_bounds {
@@ -581,7 +606,10 @@ impl RasterOperator for GdalSource {
         self: Box<Self>,
         context: &dyn crate::engine::ExecutionContext,
     ) -> Result<Box<dyn InitializedRasterOperator>> {
-        let meta_data: GdalMetaData = context.meta_data(&self.params.dataset).await?;
+        let meta_data: GdalMetaData = FilePathCheckedMetaData::boxed(
+            context.meta_data(&self.params.dataset).await?,
+            context.file_path_policy(),
+        );
 
         debug!("Initializing GdalSource for {:?}.", &self.params.dataset);
 
@@ -699,6 +727,53 @@ where
     Grid::new(tile_grid, buffer.data, no_data_value).map_err(Into::into)
 }
 
+/// Warps the single band `dataset` into a tile of the `tile_geo_transform` in the `spatial_reference`
+fn read_reprojected<T>(
+    dataset: &GdalDataset,
+    spatial_reference: SpatialReference,
+    tile_geo_transform: GeoTransform,
+    tile_shape: GridShape2D,
+    no_data_value: Option<T>,
+) -> Result<Grid2D<T>>
+where
+    T: Pixel + GdalType,
+{
+    let [tile_y_size, tile_x_size] = tile_shape.axis_size();
+    let window_size = (tile_x_size, tile_y_size);
+
+    let mut tile_dataset = Driver::get("MEM")?.create_with_band_type::<T>(
+        "",
+        tile_x_size as isize,
+        tile_y_size as isize,
+        1,
+    )?;
+    tile_dataset.set_spatial_ref(&spatial_reference.try_into()?)?;
+    tile_dataset.set_geo_transform(&tile_geo_transform.into())?;
+
+    {
+        // pixels that are not covered by the file remain no data
+        let mut band = tile_dataset.rasterband(1)?;
+        let fill_value = no_data_value.unwrap_or_else(T::zero);
+        if let Some(no_data) = no_data_value {
+            band.set_no_data_value(no_data.as_())?;
+        }
+        band.write(
+            (0, 0),
+            window_size,
+            &Buffer::new(window_size, vec![fill_value; tile_x_size * tile_y_size]),
+        )?;
+    }
+
+    reproject(dataset, &tile_dataset)?;
+
+    let buffer =
+        tile_dataset
+            .rasterband(1)?
+            .read_as::<T>((0, 0), window_size, window_size, None)?;
+
+    Grid2D::new(tile_shape, buffer.data, no_data_value).map_err(Into::into)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct GdalMetadataMapping {
     source_key: RasterPropertiesKey,
@@ -765,12 +840,13 @@ mod tests {
     use crate::util::gdal::{add_ndvi_dataset, raster_dir};
     use crate::util::Result;
     use geoengine_datatypes::primitives::{AxisAlignedRectangle, SpatialPartition2D};
+    use geoengine_datatypes::raster::GridIdx2D;
     use geoengine_datatypes::raster::{TileInformation, TilingStrategy};
+    use geoengine_datatypes::spatial_reference::SpatialReferenceAuthority;
     use geoengine_datatypes::{
         primitives::{Measurement, SpatialResolution, TimeGranularity},
         raster::GridShape2D,
     };
-    use geoengine_datatypes::{raster::GridIdx2D, spatial_reference::SpatialReference};
 
     async fn query_gdal_source(
         exe_ctx: &mut MockExecutionContext,
@@ -854,6 +930,7 @@ mod tests {
                     },
                 ]),
                 gdal_open_options: None,
+                reproject_to: None,
             },
             &TileInformation::with_partition_and_shape(output_bounds, output_shape),
         )
@@ -1026,6 +1103,7 @@ mod tests {
             no_data_value: Some(0.),
             properties_mapping: None,
             gdal_open_options: None,
+            reproject_to: None,
         };
         let replaced = params
             .replace_time_placeholder("%TIME%", "%f", TimeInstance::from_millis_unchecked(22))
@@ -1055,6 +1133,7 @@ mod tests {
                 spatial_reference: SpatialReference::epsg_4326().into(),
                 measurement: Measurement::Unitless,
                 no_data_value,
+                bands: Vec::new(),
             },
             params: GdalDatasetParameters {
                 file_path: "/foo/bar_%TIME%.tiff".into(),
@@ -1066,6 +1145,7 @@ mod tests {
                 no_data_value,
                 properties_mapping: None,
                 gdal_open_options: None,
+                reproject_to: None,
             },
             placeholder: "%TIME%".to_string(),
             time_format: "%f".to_string(),
@@ -1082,7 +1162,8 @@ mod tests {
                 data_type: RasterDataType::U8,
                 spatial_reference: SpatialReference::epsg_4326().into(),
                 measurement: Measurement::Unitless,
-                no_data_value: Some(0.)
+                no_data_value: Some(0.),
+                bands: Vec::new(),
             }
         );
 
@@ -1152,6 +1233,76 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_load_tile_data_reprojected() {
+        let output_shape: GridShape2D = [8, 8].into();
+        // the northern part of Europe in Web Mercator
+        let output_bounds = SpatialPartition2D::new_unchecked(
+            (0., 8_000_000.).into(),
+            (4_000_000., 6_000_000.).into(),
+        );
+
+        let GridWithProperties { grid, .. } = GdalSourceProcessor::<u8>::load_tile_data(
+            &GdalDatasetParameters {
+                file_path: raster_dir().join("modis_ndvi/MOD13A2_M_NDVI_2014-01-01.TIFF"),
+                rasterband_channel: 1,
+                geo_transform: GeoTransform {
+                    origin_coordinate: (-20_037_508.34, 20_037_508.34).into(),
+                    x_pixel_size: 40_075_016.68 / 3600.,
+                    y_pixel_size: -40_075_016.68 / 1800.,
+                },
+                width: 3600,
+                height: 1800,
+                file_not_found_handling: FileNotFoundHandling::NoData,
+                no_data_value: Some(0.),
+                properties_mapping: None,
+                gdal_open_options: None,
+                reproject_to: Some(SpatialReference::new(SpatialReferenceAuthority::Epsg, 3857)),
+            },
+            &TileInformation::with_partition_and_shape(output_bounds, output_shape),
+        )
+        .unwrap();
+
+        let grid = grid.into_materialized_grid();
+
+        assert_eq!(grid.data.len(), 64);
+        assert_eq!(grid.no_data_value, Some(0));
+        assert!(grid.data.iter().any(|&value| value != 0));
+    }
+
+    #[test]
+    fn test_load_tile_data_reprojected_outside_of_dataset_bounds() {
+        let output_shape: GridShape2D = [8, 8].into();
+        // east of the Web Mercator extent
+        let output_bounds = SpatialPartition2D::new_unchecked(
+            (30_000_000., 8_000_000.).into(),
+            (34_000_000., 6_000_000.).into(),
+        );
+
+        let GridWithProperties { grid, .. } = GdalSourceProcessor::<u8>::load_tile_data(
+            &GdalDatasetParameters {
+                file_path: raster_dir().join("modis_ndvi/MOD13A2_M_NDVI_2014-01-01.TIFF"),
+                rasterband_channel: 1,
+                geo_transform: GeoTransform {
+                    origin_coordinate: (-20_037_508.34, 20_037_508.34).into(),
+                    x_pixel_size: 40_075_016.68 / 3600.,
+                    y_pixel_size: -40_075_016.68 / 1800.,
+                },
+                width: 3600,
+                height: 1800,
+                file_not_found_handling: FileNotFoundHandling::NoData,
+                no_data_value: Some(0.),
+                properties_mapping: None,
+                gdal_open_options: None,
+                reproject_to: Some(SpatialReference::new(SpatialReferenceAuthority::Epsg, 3857)),
+            },
+            &TileInformation::with_partition_and_shape(output_bounds, output_shape),
+        )
+        .unwrap();
+
+        assert!(grid.is_empty());
+    }
+
     #[test]
     fn test_load_tile_data_overlaps_dataset_bounds() {
         let output_shape: GridShape2D = [8, 8].into();
@@ -1,10 +1,13 @@
 mod csv;
+mod file_path_policy;
 mod gdal_source;
 mod ogr_source;
+mod synthetic;
 
 pub use self::csv::{
     CsvGeometrySpecification, CsvSource, CsvSourceParameters, CsvSourceStream, CsvTimeSpecification,
 };
+pub use self::file_path_policy::{FilePathCheckedMetaData, FilePathPolicy, FilePaths};
 pub use self::gdal_source::{
     FileNotFoundHandling, GdalDatasetParameters, GdalLoadingInfo, GdalLoadingInfoPart,
     GdalLoadingInfoPartIterator, GdalMetaDataRegular, GdalMetaDataStatic, GdalSource,
@@ -15,3 +18,8 @@ pub use self::ogr_source::{
     OgrSourceDurationSpec, OgrSourceErrorSpec, OgrSourceParameters, OgrSourceProcessor,
     OgrSourceTimeFormat,
 };
+pub use self::synthetic::{
+    CheckerboardSource, CheckerboardSourceParams, GradientDirection, GradientSource,
+    GradientSourceParams, PixelAreaOutput, PixelAreaSource, PixelAreaSourceParams,
+    RandomPointsSource, RandomPointsSourceParams,
+};
@@ -23,6 +23,7 @@ use gdal::vector::sql::{Dialect, ResultSet};
 use gdal::vector::{Feature, FeatureIterator, FieldValue, Layer, OGRwkbGeometryType};
 use gdal::{Dataset, DatasetOptions, GdalOpenFlags};
 use log::debug;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
 use tokio::task::spawn_blocking;
@@ -32,14 +33,17 @@ use geoengine_datatypes::collections::{
     FeatureCollectionModifications, FeatureCollectionRowBuilder, GeoFeatureCollectionRowBuilder,
     VectorDataType,
 };
+use geoengine_datatypes::operations::reproject::{CoordinateProjection, CoordinateProjector};
 use geoengine_datatypes::primitives::{
     BoundingBox2D, Coordinate2D, FeatureDataType, FeatureDataValue, Geometry, MultiLineString,
     MultiPoint, MultiPolygon, NoGeometry, TimeInstance, TimeInterval, TimeStep, TypedGeometry,
 };
+use geoengine_datatypes::spatial_reference::SpatialReference;
 use geoengine_datatypes::util::arrow::ArrowTyped;
 
 use crate::engine::{OperatorDatasets, QueryProcessor, VectorQueryRectangle};
 use crate::error::Error;
+use crate::source::FilePathCheckedMetaData;
 use crate::util::Result;
 use crate::{
     engine::{
@@ -52,7 +56,7 @@ use async_trait::async_trait;
 use geoengine_datatypes::dataset::DatasetId;
 use std::convert::{TryFrom, TryInto};
 
-#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct OgrSourceParameters {
     pub dataset: DatasetId,
@@ -75,6 +79,10 @@ pub type OgrSource = SourceOperator<OgrSourceParameters>;
 ///  - `force_ogr_spatial_filter`: bool. force external spatial filter via ogr layer.
 ///    (result: empty collection), but has better performance for wfs requests (optional, false if not provided)
 ///  - `on_error`: specify the type of error handling
+///  - `sql_query`: an optional SQL query that selects the features instead of the layer
+///  - `encoding`: the encoding of the attribute values, e.g., `ISO-8859-1`, which is converted to UTF-8 (Shapefile and DBF only)
+///  - `layer_spatial_reference`: the spatial reference of the layer, overriding a missing or wrong one of the file.
+///    If it differs from the dataset's spatial reference, the features are transformed.
 ///  - `provenance`: specify the provenance of a file
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -91,6 +99,8 @@ pub struct OgrSourceDataset {
     pub force_ogr_spatial_filter: bool,
     pub on_error: OgrSourceErrorSpec,
     pub sql_query: Option<String>,
+    pub encoding: Option<String>,
+    pub layer_spatial_reference: Option<SpatialReference>,
 }
 
 impl OgrSourceDataset {
@@ -174,6 +184,7 @@ impl Default for OgrSourceTimeFormat {
 ///  - float: an array of column names containing float values
 ///  - int: an array of column names containing int values
 ///  - text: an array of column names containing alpha-numeric values
+///  - dictionary: an array of column names containing alpha-numeric values with few distinct values, e.g., classes
 ///  - rename: a. optional map of column names from data source to the name in the resulting collection
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct OgrSourceColumnSpec {
@@ -182,6 +193,8 @@ pub struct OgrSourceColumnSpec {
     pub int: Vec<String>,
     pub float: Vec<String>,
     pub text: Vec<String>,
+    #[serde(default)]
+    pub dictionary: Vec<String>,
     pub rename: Option<HashMap<String, String>>,
 }
 
@@ -198,16 +211,20 @@ impl OgrSourceColumnSpec {
         self.float
             .retain(|attribute| attributes.contains(attribute));
         self.text.retain(|attribute| attributes.contains(attribute));
+        self.dictionary
+            .retain(|attribute| attributes.contains(attribute));
     }
 }
 
 /// Specify the type of error handling
 ///  - "ignore": invalid column values are kept as null, missing/invalid geom features are skipped
+///  - "repair": like "ignore", but unclosed rings are closed and degenerated lines and rings are removed before skipping invalid geoms
 ///  - "abort": invalid column values and missing/invalid geoms result in abort
 #[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum OgrSourceErrorSpec {
     Ignore,
+    Repair,
     Abort,
 }
 
@@ -215,7 +232,7 @@ impl OgrSourceErrorSpec {
     /// handle the given error depending on the spec
     fn on_error<T>(self, error: error::Error) -> Result<Option<T>> {
         match self {
-            OgrSourceErrorSpec::Ignore => Ok(None),
+            OgrSourceErrorSpec::Ignore | OgrSourceErrorSpec::Repair => Ok(None),
             OgrSourceErrorSpec::Abort => Err(error),
         }
     }
@@ -264,7 +281,10 @@ impl VectorOperator for OgrSource {
 
         let info: Box<
             dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>,
-        > = context.meta_data(&self.params.dataset).await?;
+        > = FilePathCheckedMetaData::boxed(
+            context.meta_data(&self.params.dataset).await?,
+            context.file_path_policy(),
+        );
 
         let initialized_source = InitializedOgrSource {
             result_descriptor: info.result_descriptor().await?,
@@ -361,8 +381,16 @@ where
         query: VectorQueryRectangle,
         ctx: &'a dyn QueryContext,
     ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let spatial_reference = self
+            .dataset_information
+            .result_descriptor()
+            .await?
+            .spatial_reference
+            .into();
+
         Ok(OgrSourceStream::new(
             self.dataset_information.loading_info(query).await?,
+            spatial_reference,
             query,
             ctx.chunk_byte_size(),
         )
@@ -406,6 +434,7 @@ where
 {
     pub fn new(
         dataset_information: OgrSourceDataset,
+        spatial_reference: Option<SpatialReference>,
         query_rectangle: VectorQueryRectangle,
         chunk_byte_size: usize,
     ) -> Self {
@@ -423,6 +452,7 @@ where
             if let Err(error) = Self::compute_thread(
                 &mut work_query,
                 &dataset_information,
+                spatial_reference,
                 &work_query_receiver,
                 &poll_result_sender,
                 &query_rectangle,
@@ -449,50 +479,65 @@ where
             .as_ref()
             .ok_or(error::Error::OgrSourceColumnsSpecMissing)?;
 
-        let mut dataset_options = DatasetOptions::default();
-
         // TODO: make column x optional or allow other indication for data collection
         if columns.x.is_empty() {
-            return Ok(Dataset::open_ex(&dataset_info.file_name, dataset_options)?);
+            return Self::open_dataset(dataset_info, GdalOpenFlags::GDAL_OF_VECTOR, vec![]);
         }
 
-        if let Some(y) = &columns.y {
-            let open_opts = &[
-                &format!("X_POSSIBLE_NAMES={}", columns.x),
-                &format!("Y_POSSIBLE_NAMES={}", y),
-                "AUTODETECT_TYPE=YES",
-            ];
-
-            dataset_options.open_options = Some(open_opts);
-            return Ok(Dataset::open_ex(&dataset_info.file_name, dataset_options)?);
-        }
-
-        let open_opts = &[
-            &format!("GEOM_POSSIBLE_NAMES={}", columns.x),
-            "AUTODETECT_TYPE=YES",
-        ];
-        dataset_options.open_options = Some(open_opts);
+        let open_options = if let Some(y) = &columns.y {
+            vec![
+                format!("X_POSSIBLE_NAMES={}", columns.x),
+                format!("Y_POSSIBLE_NAMES={}", y),
+                "AUTODETECT_TYPE=YES".to_string(),
+            ]
+        } else {
+            vec![
+                format!("GEOM_POSSIBLE_NAMES={}", columns.x),
+                "AUTODETECT_TYPE=YES".to_string(),
+            ]
+        };
 
-        Ok(Dataset::open_ex(&dataset_info.file_name, dataset_options)?)
+        Self::open_dataset(dataset_info, GdalOpenFlags::GDAL_OF_VECTOR, open_options)
     }
 
     fn open_gdal_dataset(dataset_info: &OgrSourceDataset) -> Result<Dataset> {
         // TODO: reliably detect CSV files or allow defining them as such in params
         match dataset_info.file_name.extension().and_then(OsStr::to_str) {
             Some("csv" | "tsv") => Self::open_csv_dataset(dataset_info),
-            _ => Ok(Dataset::open_ex(
-                &dataset_info.file_name,
-                DatasetOptions {
-                    open_flags: GdalOpenFlags::GDAL_OF_VECTOR,
-                    ..Default::default()
-                },
-            )?),
+            _ => Self::open_dataset(dataset_info, GdalOpenFlags::GDAL_OF_VECTOR, vec![]),
         }
     }
 
+    /// Opens the dataset with the driver's `open_options` and the declared encoding
+    fn open_dataset(
+        dataset_info: &OgrSourceDataset,
+        open_flags: GdalOpenFlags,
+        mut open_options: Vec<String>,
+    ) -> Result<Dataset> {
+        if let Some(encoding) = &dataset_info.encoding {
+            open_options.push(format!("ENCODING={}", encoding));
+        }
+
+        let open_options: Vec<&str> = open_options.iter().map(String::as_str).collect();
+
+        Ok(Dataset::open_ex(
+            &dataset_info.file_name,
+            DatasetOptions {
+                open_flags,
+                open_options: if open_options.is_empty() {
+                    None
+                } else {
+                    Some(open_options.as_slice())
+                },
+                ..Default::default()
+            },
+        )?)
+    }
+
     fn compute_thread(
         work_query: &mut WorkQuery,
         dataset_information: &OgrSourceDataset,
+        spatial_reference: Option<SpatialReference>,
         work_query_receiver: &Receiver<WorkQuery>,
         poll_result_sender: &SyncSender<Option<Result<FeatureCollection<G>>>>,
         query_rectangle: &VectorQueryRectangle,
@@ -501,7 +546,11 @@ where
         // TODO: add OGR time filter if forced
         let dataset = Self::open_gdal_dataset(dataset_information)?;
 
-        let mut use_ogr_spatial_filter = dataset_information.force_ogr_spatial_filter;
+        let conversion = OgrGeometryConversion::new(dataset_information, spatial_reference)?;
+
+        // the query rectangle is not in the spatial reference of the layer if the features are transformed
+        let mut use_ogr_spatial_filter =
+            dataset_information.force_ogr_spatial_filter && !conversion.transforms();
 
         let mut features_provider = if let Some(sql) = dataset_information.sql_query.as_ref() {
             let spatial_filter = if use_ogr_spatial_filter {
@@ -523,8 +572,9 @@ where
         } else {
             let mut layer = dataset.layer_by_name(&dataset_information.layer_name)?;
 
-            use_ogr_spatial_filter = dataset_information.force_ogr_spatial_filter
-                || layer.has_capability(gdal::vector::LayerCaps::OLCFastSpatialFilter);
+            use_ogr_spatial_filter = !conversion.transforms()
+                && (dataset_information.force_ogr_spatial_filter
+                    || layer.has_capability(gdal::vector::LayerCaps::OLCFastSpatialFilter));
 
             if use_ogr_spatial_filter {
                 // rectangular geometry from West, South, East and North values.
@@ -571,6 +621,7 @@ where
                 &mut features,
                 feature_collection_builder.clone(),
                 dataset_information,
+                &conversion,
                 &data_types,
                 query_rectangle,
                 &time_extractor,
@@ -772,6 +823,12 @@ where
                     .add_column(attribute.clone(), FeatureDataType::Text)
                     .unwrap();
             }
+            for attribute in &column_spec.dictionary {
+                data_types.insert(attribute.clone(), FeatureDataType::Dictionary);
+                feature_collection_builder
+                    .add_column(attribute.clone(), FeatureDataType::Dictionary)
+                    .unwrap();
+            }
         }
         (data_types, feature_collection_builder)
     }
@@ -781,6 +838,7 @@ where
         feature_iterator: &mut Peekable<Fuse<FeatureIterator<'_>>>,
         feature_collection_builder: FeatureCollectionBuilder<G>,
         dataset_information: &OgrSourceDataset,
+        conversion: &OgrGeometryConversion,
         data_types: &HashMap<String, FeatureDataType>,
         query_rectangle: &VectorQueryRectangle,
         time_extractor: &dyn Fn(&Feature) -> Result<TimeInterval>,
@@ -792,6 +850,7 @@ where
         for feature in feature_iterator {
             if let Err(error) = Self::add_feature_to_batch(
                 dataset_information.on_error,
+                conversion,
                 data_types,
                 query_rectangle,
                 time_extractor,
@@ -801,7 +860,7 @@ where
                 was_spatial_filtered_by_ogr,
             ) {
                 match dataset_information.on_error {
-                    OgrSourceErrorSpec::Ignore => continue,
+                    OgrSourceErrorSpec::Ignore | OgrSourceErrorSpec::Repair => continue,
                     OgrSourceErrorSpec::Abort => return Err(error),
                 }
             }
@@ -817,6 +876,7 @@ where
     #[allow(clippy::too_many_arguments)]
     fn add_feature_to_batch(
         error_spec: OgrSourceErrorSpec,
+        conversion: &OgrGeometryConversion,
         data_types: &HashMap<String, FeatureDataType>,
         query_rectangle: &VectorQueryRectangle,
         time_extractor: &dyn Fn(&Feature) -> Result<TimeInterval, Error>,
@@ -832,8 +892,10 @@ where
             return Ok(());
         }
 
-        let geometry: G =
-            <G as TryFromOgrGeometry>::try_from(feature.geometry_by_index(0).map_err(Into::into))?;
+        let geometry: G = <G as TryFromOgrGeometry>::try_from(
+            feature.geometry_by_index(0).map_err(Into::into),
+            conversion,
+        )?;
 
         // filter out geometries that are not contained in the query's bounding box
         if !was_spatial_filtered_by_ogr
@@ -849,7 +911,7 @@ where
             let field = feature.field(&column);
 
             match data_type {
-                FeatureDataType::Text => {
+                FeatureDataType::Text | FeatureDataType::Dictionary => {
                     #[allow(clippy::match_same_arms)]
                     let text_option = match field {
                         Ok(Some(FieldValue::IntegerValue(v))) => Some(v.to_string()),
@@ -865,7 +927,26 @@ where
                         Err(e) => error_spec.on_error(Error::Gdal { source: e })?,
                     };
 
-                    builder.push_data(column, FeatureDataValue::NullableText(text_option))?;
+                    let value = if *data_type == FeatureDataType::Dictionary {
+                        FeatureDataValue::NullableDictionary(text_option)
+                    } else {
+                        FeatureDataValue::NullableText(text_option)
+                    };
+
+                    builder.push_data(column, value)?;
+                }
+                FeatureDataType::Bytes => {
+                    let bytes_option = match field {
+                        Ok(Some(FieldValue::StringValue(s))) => Some(s.into_bytes()),
+                        Ok(None) => None,
+                        Ok(Some(v)) => error_spec.on_error(Error::OgrColumnFieldTypeMismatch {
+                            expected: "Bytes".to_string(),
+                            field_value: v,
+                        })?, // TODO: handle other types
+                        Err(e) => error_spec.on_error(Error::Gdal { source: e })?,
+                    };
+
+                    builder.push_data(column, FeatureDataValue::NullableBytes(bytes_option))?;
                 }
                 FeatureDataType::Float => {
                     #[allow(clippy::match_same_arms)]
@@ -978,103 +1059,201 @@ where
     }
 }
 
+/// Transforms and repairs the coordinates of OGR geometries on their conversion
+pub struct OgrGeometryConversion {
+    projector: Option<CoordinateProjector>,
+    repair: bool,
+}
+
+impl OgrGeometryConversion {
+    /// Transforms from an overridden layer spatial reference into the dataset's `spatial_reference`
+    /// and repairs geometries if the error handling says so
+    fn new(
+        dataset_information: &OgrSourceDataset,
+        spatial_reference: Option<SpatialReference>,
+    ) -> Result<Self> {
+        let projector = match (
+            dataset_information.layer_spatial_reference,
+            spatial_reference,
+        ) {
+            (Some(from), Some(to)) if from != to => {
+                Some(CoordinateProjector::from_known_srs(from, to)?)
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            projector,
+            repair: dataset_information.on_error == OgrSourceErrorSpec::Repair,
+        })
+    }
+
+    fn transforms(&self) -> bool {
+        self.projector.is_some()
+    }
+
+    fn coordinates(&self, points: Vec<(f64, f64, f64)>) -> Result<Vec<Coordinate2D>> {
+        let coordinates: Vec<Coordinate2D> = points
+            .into_iter()
+            .map(|(x, y, _z)| Coordinate2D::new(x, y))
+            .collect();
+
+        match &self.projector {
+            Some(projector) => Ok(projector.project_coordinates(&coordinates)?),
+            None => Ok(coordinates),
+        }
+    }
+
+    /// Removes lines with less than two points
+    fn lines(&self, mut lines: Vec<Vec<Coordinate2D>>) -> Vec<Vec<Coordinate2D>> {
+        if self.repair {
+            lines.retain(|line| line.len() >= 2);
+        }
+
+        lines
+    }
+
+    /// Closes rings and removes rings with less than three distinct points
+    /// as well as polygons without a valid exterior ring
+    fn polygons(&self, polygons: Vec<Vec<Vec<Coordinate2D>>>) -> Vec<Vec<Vec<Coordinate2D>>> {
+        if !self.repair {
+            return polygons;
+        }
+
+        polygons
+            .into_iter()
+            .filter_map(|rings| {
+                let mut rings = rings.into_iter().map(close_ring).map(|ring| {
+                    if ring.len() >= 4 {
+                        Some(ring)
+                    } else {
+                        None
+                    }
+                });
+
+                let exterior = rings.next().flatten()?;
+
+                Some(std::iter::once(exterior).chain(rings.flatten()).collect())
+            })
+            .collect()
+    }
+}
+
+fn close_ring(mut ring: Vec<Coordinate2D>) -> Vec<Coordinate2D> {
+    if let (Some(&first), Some(&last)) = (ring.first(), ring.last()) {
+        if first != last {
+            ring.push(first);
+        }
+    }
+
+    ring
+}
+
 // use `TryFrom` in `datatypes` if this is used on more than one occasion
 pub trait TryFromOgrGeometry: Sized {
-    fn try_from(geometry: Result<&gdal::vector::Geometry>) -> Result<Self>;
+    fn try_from(
+        geometry: Result<&gdal::vector::Geometry>,
+        conversion: &OgrGeometryConversion,
+    ) -> Result<Self>;
 }
 
 /// Implement direct conversions from OGR geometries to our geometries
 /// Unfortunately, we cannot convert to `geo`'s geometries since the implementation panics on unknown types.
 impl TryFromOgrGeometry for MultiPoint {
-    fn try_from(geometry: Result<&gdal::vector::Geometry>) -> Result<Self> {
-        fn coordinate(geometry: &gdal::vector::Geometry) -> Coordinate2D {
-            let (x, y, _) = geometry.get_point(0);
-            Coordinate2D::new(x, y)
-        }
-
+    fn try_from(
+        geometry: Result<&gdal::vector::Geometry>,
+        conversion: &OgrGeometryConversion,
+    ) -> Result<Self> {
         let geometry = geometry?;
 
-        match geometry.geometry_type() {
-            OGRwkbGeometryType::wkbPoint => Ok(MultiPoint::new(vec![coordinate(geometry)])?),
-            OGRwkbGeometryType::wkbMultiPoint => {
-                let coordinates = (0..geometry.geometry_count())
-                    .map(|i| coordinate(&unsafe { geometry.get_unowned_geometry(i) }))
-                    .collect();
-
-                Ok(MultiPoint::new(coordinates)?)
+        let points = match geometry.geometry_type() {
+            OGRwkbGeometryType::wkbPoint => vec![geometry.get_point(0)],
+            OGRwkbGeometryType::wkbMultiPoint => (0..geometry.geometry_count())
+                .map(|i| unsafe { geometry.get_unowned_geometry(i) }.get_point(0))
+                .collect(),
+            _ => {
+                return Err(Error::InvalidType {
+                    expected: format!("{:?}", VectorDataType::MultiPoint),
+                    found: format!("{:?}", OgrSource::ogr_geometry_type(geometry)),
+                })
             }
-            _ => Err(Error::InvalidType {
-                expected: format!("{:?}", VectorDataType::MultiPoint),
-                found: format!("{:?}", OgrSource::ogr_geometry_type(geometry)),
-            }),
-        }
+        };
+
+        Ok(MultiPoint::new(conversion.coordinates(points)?)?)
     }
 }
 
 impl TryFromOgrGeometry for MultiLineString {
-    fn try_from(geometry: Result<&gdal::vector::Geometry>) -> Result<Self> {
-        fn coordinates(geometry: &gdal::vector::Geometry) -> Vec<Coordinate2D> {
-            geometry
-                .get_point_vec()
-                .into_iter()
-                .map(|(x, y, _z)| Coordinate2D::new(x, y))
-                .collect()
-        }
-
+    fn try_from(
+        geometry: Result<&gdal::vector::Geometry>,
+        conversion: &OgrGeometryConversion,
+    ) -> Result<Self> {
         let geometry = geometry?;
 
-        match geometry.geometry_type() {
+        let lines = match geometry.geometry_type() {
             OGRwkbGeometryType::wkbLineString => {
-                Ok(MultiLineString::new(vec![coordinates(geometry)])?)
+                vec![conversion.coordinates(geometry.get_point_vec())?]
             }
-            OGRwkbGeometryType::wkbMultiLineString => Ok(MultiLineString::new(
-                (0..geometry.geometry_count())
-                    .map(|i| coordinates(&unsafe { geometry.get_unowned_geometry(i) }))
-                    .collect(),
-            )?),
-            _ => Err(Error::InvalidType {
-                expected: format!("{:?}", VectorDataType::MultiPoint),
-                found: format!("{:?}", OgrSource::ogr_geometry_type(geometry)),
-            }),
-        }
+            OGRwkbGeometryType::wkbMultiLineString => (0..geometry.geometry_count())
+                .map(|i| {
+                    conversion
+                        .coordinates(unsafe { geometry.get_unowned_geometry(i) }.get_point_vec())
+                })
+                .collect::<Result<_>>()?,
+            _ => {
+                return Err(Error::InvalidType {
+                    expected: format!("{:?}", VectorDataType::MultiPoint),
+                    found: format!("{:?}", OgrSource::ogr_geometry_type(geometry)),
+                })
+            }
+        };
+
+        Ok(MultiLineString::new(conversion.lines(lines))?)
     }
 }
 
 impl TryFromOgrGeometry for MultiPolygon {
-    fn try_from(geometry: Result<&gdal::vector::Geometry>) -> Result<Self> {
-        fn coordinates(geometry: &gdal::vector::Geometry) -> Vec<Coordinate2D> {
-            geometry
-                .get_point_vec()
-                .into_iter()
-                .map(|(x, y, _z)| Coordinate2D::new(x, y))
-                .collect()
-        }
-        fn rings(geometry: &gdal::vector::Geometry) -> Vec<Vec<Coordinate2D>> {
+    fn try_from(
+        geometry: Result<&gdal::vector::Geometry>,
+        conversion: &OgrGeometryConversion,
+    ) -> Result<Self> {
+        fn rings(
+            geometry: &gdal::vector::Geometry,
+            conversion: &OgrGeometryConversion,
+        ) -> Result<Vec<Vec<Coordinate2D>>> {
             let ring_count = geometry.geometry_count();
             (0..ring_count)
-                .map(|i| coordinates(&unsafe { geometry.get_unowned_geometry(i) }))
+                .map(|i| {
+                    conversion
+                        .coordinates(unsafe { geometry.get_unowned_geometry(i) }.get_point_vec())
+                })
                 .collect()
         }
 
         let geometry = geometry?;
 
-        match geometry.geometry_type() {
-            OGRwkbGeometryType::wkbPolygon => Ok(MultiPolygon::new(vec![rings(geometry)])?),
-            OGRwkbGeometryType::wkbMultiPolygon => Ok(MultiPolygon::new(
-                (0..geometry.geometry_count())
-                    .map(|i| rings(&unsafe { geometry.get_unowned_geometry(i) }))
-                    .collect(),
-            )?),
-            _ => Err(Error::InvalidType {
-                expected: format!("{:?}", VectorDataType::MultiPoint),
-                found: format!("{:?}", OgrSource::ogr_geometry_type(geometry)),
-            }),
-        }
+        let polygons = match geometry.geometry_type() {
+            OGRwkbGeometryType::wkbPolygon => vec![rings(geometry, conversion)?],
+            OGRwkbGeometryType::wkbMultiPolygon => (0..geometry.geometry_count())
+                .map(|i| rings(&unsafe { geometry.get_unowned_geometry(i) }, conversion))
+                .collect::<Result<_>>()?,
+            _ => {
+                return Err(Error::InvalidType {
+                    expected: format!("{:?}", VectorDataType::MultiPoint),
+                    found: format!("{:?}", OgrSource::ogr_geometry_type(geometry)),
+                })
+            }
+        };
+
+        Ok(MultiPolygon::new(conversion.polygons(polygons))?)
     }
 }
 
 impl TryFromOgrGeometry for NoGeometry {
-    fn try_from(_geometry: Result<&gdal::vector::Geometry>) -> Result<Self> {
+    fn try_from(
+        _geometry: Result<&gdal::vector::Geometry>,
+        _conversion: &OgrGeometryConversion,
+    ) -> Result<Self> {
         Ok(NoGeometry)
     }
 }
@@ -1123,6 +1302,7 @@ mod tests {
     use super::*;
 
     use crate::engine::{MockExecutionContext, MockQueryContext, StaticMetaData};
+    use crate::source::FilePathPolicy;
     use futures::TryStreamExt;
     use geoengine_datatypes::collections::{
         DataCollection, GeometryCollection, MultiPointCollection, MultiPolygonCollection,
@@ -1131,7 +1311,9 @@ mod tests {
     use geoengine_datatypes::primitives::{
         BoundingBox2D, FeatureData, SpatialResolution, TimeGranularity,
     };
-    use geoengine_datatypes::spatial_reference::{SpatialReference, SpatialReferenceOption};
+    use geoengine_datatypes::spatial_reference::{
+        SpatialReference, SpatialReferenceAuthority, SpatialReferenceOption,
+    };
     use geoengine_datatypes::util::Identifier;
     use serde_json::json;
 
@@ -1158,12 +1340,15 @@ mod tests {
                 float: vec!["num".to_string()],
                 int: vec!["dec1".to_string(), "dec2".to_string()],
                 text: vec!["text".to_string()],
+                dictionary: vec![],
                 rename: None,
             }),
             force_ogr_time_filter: false,
             force_ogr_spatial_filter: false,
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
+            encoding: None,
+            layer_spatial_reference: None,
         };
 
         let serialized_spec = serde_json::to_string(&spec).unwrap();
@@ -1193,12 +1378,15 @@ mod tests {
                     "int": ["dec1", "dec2"],
                     "float": ["num"],
                     "text": ["text"],
+                    "dictionary": [],
                     "rename": null
                 },
                 "forceOgrTimeFilter": false,
                 "forceOgrSpatialFilter": false,
                 "onError": "ignore",
-                "sqlQuery": null
+                "sqlQuery": null,
+                "encoding": null,
+                "layerSpatialReference": null
             })
             .to_string()
         );
@@ -1256,6 +1444,8 @@ mod tests {
             force_ogr_spatial_filter: false,
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
+            encoding: None,
+            layer_spatial_reference: None,
         };
 
         let info = StaticMetaData {
@@ -1303,6 +1493,8 @@ mod tests {
             force_ogr_spatial_filter: false,
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
+            encoding: None,
+            layer_spatial_reference: None,
         };
 
         let info = StaticMetaData {
@@ -1350,6 +1542,8 @@ mod tests {
             force_ogr_spatial_filter: false,
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
+            encoding: None,
+            layer_spatial_reference: None,
         };
         let info = StaticMetaData {
             loading_info: dataset_information,
@@ -1392,6 +1586,210 @@ mod tests {
         Ok(())
     }
 
+    async fn broken_polygons(on_error: OgrSourceErrorSpec) -> Result<Vec<MultiPolygonCollection>> {
+        let dataset_information = OgrSourceDataset {
+            file_name: "test-data/vector/data/broken_polygons.json".into(),
+            layer_name: "broken_polygons".to_string(),
+            data_type: None,
+            time: OgrSourceDatasetTimeType::None,
+            columns: None,
+            force_ogr_time_filter: false,
+            force_ogr_spatial_filter: false,
+            on_error,
+            sql_query: None,
+            encoding: None,
+            layer_spatial_reference: None,
+        };
+        let info = StaticMetaData {
+            loading_info: dataset_information,
+            result_descriptor: VectorResultDescriptor {
+                data_type: VectorDataType::MultiPolygon,
+                spatial_reference: SpatialReferenceOption::Unreferenced,
+                columns: Default::default(),
+            },
+            phantom: Default::default(),
+        };
+
+        let query_processor = OgrSourceProcessor::<MultiPolygon>::new(Box::new(info));
+
+        let context = MockQueryContext::new(usize::MAX);
+        let query = query_processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((0., 0.).into(), (5., 5.).into())?,
+                    time_interval: Default::default(),
+                    spatial_resolution: SpatialResolution::new(1., 1.)?,
+                },
+                &context,
+            )
+            .await?;
+
+        query.try_collect().await
+    }
+
+    #[tokio::test]
+    async fn on_error_repair() -> Result<()> {
+        let ignored = broken_polygons(OgrSourceErrorSpec::Ignore).await?;
+        let repaired = broken_polygons(OgrSourceErrorSpec::Repair).await?;
+
+        assert_eq!(repaired.len(), 1);
+        assert!(ignored[0].len() < repaired[0].len());
+
+        assert_eq!(
+            repaired[0],
+            MultiPolygonCollection::from_data(
+                vec![
+                    MultiPolygon::new(vec![vec![vec![
+                        (0.0, 0.0).into(),
+                        (1.0, 0.0).into(),
+                        (1.0, 1.0).into(),
+                        (0.0, 1.0).into(),
+                        (0.0, 0.0).into(),
+                    ]]])?,
+                    MultiPolygon::new(vec![vec![vec![
+                        (0.0, 0.0).into(),
+                        (4.0, 0.0).into(),
+                        (4.0, 4.0).into(),
+                        (0.0, 4.0).into(),
+                        (0.0, 0.0).into(),
+                    ]]])?,
+                ],
+                vec![Default::default(); 2],
+                HashMap::new(),
+            )?
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn layer_spatial_reference() -> Result<()> {
+        let info = StaticMetaData {
+            loading_info: OgrSourceDataset {
+                file_name: "test-data/vector/data/ne_10m_ports/projected_3857/ne_10m_ports.shp"
+                    .into(),
+                layer_name: "ne_10m_ports".to_string(),
+                data_type: Some(VectorDataType::MultiPoint),
+                time: OgrSourceDatasetTimeType::None,
+                columns: None,
+                force_ogr_time_filter: false,
+                // not applicable since the query is not in the spatial reference of the layer
+                force_ogr_spatial_filter: true,
+                on_error: OgrSourceErrorSpec::Ignore,
+                sql_query: None,
+                encoding: None,
+                layer_spatial_reference: Some(SpatialReference::new(
+                    SpatialReferenceAuthority::Epsg,
+                    3857,
+                )),
+            },
+            result_descriptor: VectorResultDescriptor {
+                data_type: VectorDataType::MultiPoint,
+                spatial_reference: SpatialReference::epsg_4326().into(),
+                columns: Default::default(),
+            },
+            phantom: Default::default(),
+        };
+
+        let query_processor = OgrSourceProcessor::<MultiPoint>::new(Box::new(info));
+
+        let context = MockQueryContext::new(usize::MAX);
+        let query = query_processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((1.85, 50.88).into(), (4.82, 52.95).into())?,
+                    time_interval: Default::default(),
+                    spatial_resolution: SpatialResolution::new(1., 1.)?,
+                },
+                &context,
+            )
+            .await?;
+
+        let result: Vec<MultiPointCollection> = query.try_collect().await?;
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].len(), 10);
+
+        // the features are transformed back to WGS 84
+        let first_port = result[0].coordinates()[0];
+        assert!((first_port.x - 2.933_686_69).abs() < 1e-6);
+        assert!((first_port.y - 51.23).abs() < 1e-6);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn it_checks_file_paths() -> Result<()> {
+        let dataset = DatasetId::Internal {
+            dataset_id: InternalDatasetId::new(),
+        };
+        let mut exe_ctx = MockExecutionContext::default();
+        exe_ctx.add_meta_data::<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>(
+            dataset.clone(),
+            Box::new(StaticMetaData {
+                loading_info: OgrSourceDataset {
+                    file_name: "test-data/vector/data/ne_10m_ports/ne_10m_ports.shp".into(),
+                    layer_name: "ne_10m_ports".to_string(),
+                    data_type: Some(VectorDataType::MultiPoint),
+                    time: OgrSourceDatasetTimeType::None,
+                    columns: None,
+                    force_ogr_time_filter: false,
+                    force_ogr_spatial_filter: false,
+                    on_error: OgrSourceErrorSpec::Ignore,
+                    sql_query: None,
+                    encoding: None,
+                    layer_spatial_reference: None,
+                },
+                result_descriptor: VectorResultDescriptor {
+                    data_type: VectorDataType::MultiPoint,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    columns: Default::default(),
+                },
+                phantom: Default::default(),
+            }),
+        );
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((1.85, 50.88).into(), (4.82, 52.95).into())?,
+            time_interval: Default::default(),
+            spatial_resolution: SpatialResolution::new(1., 1.)?,
+        };
+        let context = MockQueryContext::new(usize::MAX);
+
+        for (data_root, allowed) in [("test-data/raster", false), ("test-data/vector", true)] {
+            exe_ctx.file_path_policy = FilePathPolicy {
+                data_roots: Some(vec![data_root.into()]),
+                denied_schemes: vec![],
+            };
+
+            let query_processor = OgrSource {
+                params: OgrSourceParameters {
+                    dataset: dataset.clone(),
+                    attribute_projection: None,
+                },
+            }
+            .boxed()
+            .initialize(&exe_ctx)
+            .await?
+            .query_processor()?
+            .multi_point()
+            .unwrap();
+
+            let result = query_processor.query(query, &context).await;
+
+            if allowed {
+                assert!(result.is_ok());
+            } else {
+                assert!(matches!(
+                    result,
+                    Err(Error::FilePathOutsideOfDataRoots { .. })
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn ne_10m_ports_bbox_filter() -> Result<()> {
         let dataset = DatasetId::Internal {
@@ -1411,6 +1809,8 @@ mod tests {
                     force_ogr_spatial_filter: false,
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
+                    encoding: None,
+                    layer_spatial_reference: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -1504,6 +1904,8 @@ mod tests {
                     force_ogr_spatial_filter: true,
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
+                    encoding: None,
+                    layer_spatial_reference: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -1599,6 +2001,8 @@ mod tests {
                     force_ogr_spatial_filter: false,
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
+                    encoding: None,
+                    layer_spatial_reference: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -1698,12 +2102,15 @@ mod tests {
                             "name".to_string(),
                             "website".to_string(),
                         ],
+                        dictionary: vec![],
                         rename: None,
                     }),
                     force_ogr_time_filter: false,
                     force_ogr_spatial_filter: false,
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
+                    encoding: None,
+                    layer_spatial_reference: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -1881,6 +2288,8 @@ mod tests {
                     force_ogr_spatial_filter: false,
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
+                    encoding: None,
+                    layer_spatial_reference: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -3042,12 +3451,15 @@ mod tests {
                 float: vec!["b".to_string()],
                 int: vec!["a".to_string()],
                 text: vec!["c".to_string()],
+                dictionary: vec![],
                 rename: None,
             }),
             force_ogr_time_filter: false,
             force_ogr_spatial_filter: false,
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
+            encoding: None,
+            layer_spatial_reference: None,
         };
 
         let info = StaticMetaData {
@@ -3137,6 +3549,8 @@ mod tests {
                     force_ogr_spatial_filter: false,
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
+                    encoding: None,
+                    layer_spatial_reference: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -3382,6 +3796,8 @@ mod tests {
                     force_ogr_spatial_filter: false,
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
+                    encoding: None,
+                    layer_spatial_reference: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -3457,12 +3873,15 @@ mod tests {
                         int: vec![],
                         float: vec![],
                         text: vec![],
+                        dictionary: vec![],
                         rename: None,
                     }),
                     force_ogr_time_filter: false,
                     force_ogr_spatial_filter: false,
                     on_error: OgrSourceErrorSpec::Abort,
                     sql_query: None,
+                    encoding: None,
+                    layer_spatial_reference: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPolygon,
@@ -3547,12 +3966,15 @@ mod tests {
                         int: vec!["num".to_owned()],
                         float: vec![],
                         text: vec!["txt".to_owned()],
+                        dictionary: vec![],
                         rename: None,
                     }),
                     force_ogr_time_filter: false,
                     force_ogr_spatial_filter: false,
                     on_error: OgrSourceErrorSpec::Abort,
                     sql_query: None,
+                    encoding: None,
+                    layer_spatial_reference: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -3658,12 +4080,15 @@ mod tests {
                         int: vec![],
                         float: vec![],
                         text: vec!["Name".to_owned()],
+                        dictionary: vec![],
                         rename: None,
                     }),
                     force_ogr_time_filter: false,
                     force_ogr_spatial_filter: false,
                     on_error: OgrSourceErrorSpec::Abort,
                     sql_query: None,
+                    encoding: None,
+                    layer_spatial_reference: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -3762,12 +4187,15 @@ mod tests {
                         int: vec![],
                         float: vec![],
                         text: vec!["Name".to_owned()],
+                        dictionary: vec![],
                         rename: None,
                     }),
                     force_ogr_time_filter: false,
                     force_ogr_spatial_filter: false,
                     on_error: OgrSourceErrorSpec::Abort,
                     sql_query: None,
+                    encoding: None,
+                    layer_spatial_reference: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -3866,12 +4294,15 @@ mod tests {
                         int: vec![],
                         float: vec![],
                         text: vec!["Name".to_owned()],
+                        dictionary: vec![],
                         rename: None,
                     }),
                     force_ogr_time_filter: false,
                     force_ogr_spatial_filter: false,
                     on_error: OgrSourceErrorSpec::Abort,
                     sql_query: None,
+                    encoding: None,
+                    layer_spatial_reference: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -3954,6 +4385,7 @@ mod tests {
                 float: vec!["b".to_string()],
                 int: vec!["a".to_string()],
                 text: vec!["c".to_string()],
+                dictionary: vec![],
                 rename: Some(
                     [("a".to_owned(), "foo".to_owned())]
                         .iter()
@@ -3965,6 +4397,8 @@ mod tests {
             force_ogr_spatial_filter: false,
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
+            encoding: None,
+            layer_spatial_reference: None,
         };
 
         let info = StaticMetaData {
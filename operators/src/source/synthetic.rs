@@ -0,0 +1,1011 @@
+use crate::call_generic_raster_processor;
+use crate::engine::{
+    ExecutionContext, InitializedRasterOperator, InitializedVectorOperator, OperatorDatasets,
+    QueryContext, QueryProcessor, RasterOperator, RasterQueryRectangle, RasterResultDescriptor,
+    SourceOperator, TypedRasterQueryProcessor, TypedVectorQueryProcessor, VectorOperator,
+    VectorQueryRectangle, VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::random::{split_mix, unit_interval};
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use geoengine_datatypes::collections::{MultiPointCollection, VectorDataType};
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, BoundingBox2D, Coordinate2D, Measurement, SpatialPartition2D,
+    SpatialPartitioned, TimeInterval,
+};
+use geoengine_datatypes::raster::{
+    EmptyGrid, Grid2D, GridOrEmpty2D, Pixel, RasterDataType, RasterTile2D, TileInformation,
+    TilingSpecification,
+};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+/// Parameters for the `GradientSource`, which produces a raster whose values change linearly
+/// from `fromValue` at one edge of the `spatialBounds` to `toValue` at the opposite edge.
+/// Pixels outside the bounds are no data.
+///
+/// # Examples
+///
+/// ```rust
+/// use geoengine_operators::engine::RasterOperator;
+///
+/// let json = r#"{
+///     "type": "GradientSource",
+///     "params": {
+///         "dataType": "F32",
+///         "spatialReference": "EPSG:4326",
+///         "spatialBounds": {
+///             "upperLeftCoordinate": { "x": -180.0, "y": 90.0 },
+///             "lowerRightCoordinate": { "x": 180.0, "y": -90.0 }
+///         },
+///         "noDataValue": -1.0,
+///         "fromValue": 0.0,
+///         "toValue": 100.0,
+///         "direction": "vertical"
+///     }
+/// }"#;
+///
+/// let _operator: Box<dyn RasterOperator> = serde_json::from_str(json).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GradientSourceParams {
+    pub data_type: RasterDataType,
+    pub spatial_reference: SpatialReference,
+    pub spatial_bounds: SpatialPartition2D,
+    /// The validity of the raster, which is the whole time if omitted
+    #[serde(default)]
+    pub time: TimeInterval,
+    pub no_data_value: f64,
+    pub from_value: f64,
+    pub to_value: f64,
+    #[serde(default)]
+    pub direction: GradientDirection,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum GradientDirection {
+    /// From the left to the right edge
+    Horizontal,
+    /// From the upper to the lower edge
+    Vertical,
+}
+
+impl Default for GradientDirection {
+    fn default() -> Self {
+        Self::Horizontal
+    }
+}
+
+pub type GradientSource = SourceOperator<GradientSourceParams>;
+
+/// Parameters for the `CheckerboardSource`, which produces a raster of square cells with an edge
+/// length of `cellSize` that alternate between the two `values`. The cells start at the upper
+/// left corner of the `spatialBounds` and pixels outside the bounds are no data.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckerboardSourceParams {
+    pub data_type: RasterDataType,
+    pub spatial_reference: SpatialReference,
+    pub spatial_bounds: SpatialPartition2D,
+    /// The validity of the raster, which is the whole time if omitted
+    #[serde(default)]
+    pub time: TimeInterval,
+    pub no_data_value: f64,
+    /// The edge length of a cell in units of the spatial reference
+    pub cell_size: f64,
+    pub values: [f64; 2],
+}
+
+pub type CheckerboardSource = SourceOperator<CheckerboardSourceParams>;
+
+/// Parameters for the `RandomPointsSource`, which produces `count` points that are uniformly
+/// distributed inside the `spatialBounds`. The same `seed` always produces the same points.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RandomPointsSourceParams {
+    pub spatial_reference: SpatialReference,
+    pub spatial_bounds: BoundingBox2D,
+    /// The validity of the points, which is the whole time if omitted
+    #[serde(default)]
+    pub time: TimeInterval,
+    pub count: usize,
+    pub seed: u64,
+}
+
+pub type RandomPointsSource = SourceOperator<RandomPointsSourceParams>;
+
+/// Parameters for the `PixelAreaSource`, which produces a raster in `EPSG:4326` whose pixels
+/// contain their own area or a weight that is proportional to it, e.g., for area-weighted means
+/// of global rasters. The pixels take the resolution of the query and have zero area outside
+/// the valid latitudes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PixelAreaSourceParams {
+    /// Either `F32` or `F64`
+    pub data_type: RasterDataType,
+    pub output: PixelAreaOutput,
+    /// The validity of the raster, which is the whole time if omitted
+    #[serde(default)]
+    pub time: TimeInterval,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum PixelAreaOutput {
+    /// The area on the authalic sphere of WGS 84 in square meters
+    SquareMeters,
+    /// The area on the authalic sphere of WGS 84 in square kilometers
+    SquareKilometers,
+    /// The cosine of the latitude of the pixel's center, i.e., the area of the pixel
+    /// relative to a pixel of the same size at the equator
+    CosineLatitude,
+}
+
+/// The radius of the sphere that has the same surface area as the WGS 84 ellipsoid
+const AUTHALIC_EARTH_RADIUS_METERS: f64 = 6_371_007.2;
+
+impl PixelAreaOutput {
+    /// Computes the value of a pixel between the latitudes `top` and `bottom` that spans
+    /// `width` degrees of longitude
+    fn value(self, top: f64, bottom: f64, width: f64) -> f64 {
+        let top = top.clamp(-90., 90.);
+        let bottom = bottom.clamp(-90., 90.);
+
+        let area_in_square_meters = || {
+            AUTHALIC_EARTH_RADIUS_METERS.powi(2)
+                * width.to_radians()
+                * (top.to_radians().sin() - bottom.to_radians().sin()).abs()
+        };
+
+        match self {
+            PixelAreaOutput::SquareMeters => area_in_square_meters(),
+            PixelAreaOutput::SquareKilometers => area_in_square_meters() / 1_000_000.,
+            PixelAreaOutput::CosineLatitude => ((top + bottom) / 2.).to_radians().cos(),
+        }
+    }
+
+    fn measurement(self) -> Measurement {
+        match self {
+            PixelAreaOutput::SquareMeters => {
+                Measurement::continuous("area".to_string(), Some("m²".to_string()))
+            }
+            PixelAreaOutput::SquareKilometers => {
+                Measurement::continuous("area".to_string(), Some("km²".to_string()))
+            }
+            PixelAreaOutput::CosineLatitude => Measurement::Unitless,
+        }
+    }
+}
+
+pub type PixelAreaSource = SourceOperator<PixelAreaSourceParams>;
+
+impl OperatorDatasets for GradientSource {
+    fn datasets_collect(&self, _datasets: &mut Vec<DatasetId>) {}
+}
+
+impl OperatorDatasets for CheckerboardSource {
+    fn datasets_collect(&self, _datasets: &mut Vec<DatasetId>) {}
+}
+
+impl OperatorDatasets for RandomPointsSource {
+    fn datasets_collect(&self, _datasets: &mut Vec<DatasetId>) {}
+}
+
+impl OperatorDatasets for PixelAreaSource {
+    fn datasets_collect(&self, _datasets: &mut Vec<DatasetId>) {}
+}
+
+/// The function that assigns a value to every pixel of a synthetic raster
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RasterPattern {
+    Gradient {
+        from_value: f64,
+        to_value: f64,
+        direction: GradientDirection,
+    },
+    Checkerboard {
+        cell_size: f64,
+        values: [f64; 2],
+    },
+}
+
+impl RasterPattern {
+    fn value(&self, bounds: &SpatialPartition2D, coordinate: Coordinate2D) -> f64 {
+        let upper_left = bounds.upper_left();
+
+        match *self {
+            RasterPattern::Gradient {
+                from_value,
+                to_value,
+                direction,
+            } => {
+                let fraction = match direction {
+                    GradientDirection::Horizontal => {
+                        (coordinate.x - upper_left.x) / bounds.size_x()
+                    }
+                    GradientDirection::Vertical => (upper_left.y - coordinate.y) / bounds.size_y(),
+                };
+
+                from_value + fraction * (to_value - from_value)
+            }
+            RasterPattern::Checkerboard { cell_size, values } => {
+                let column = ((coordinate.x - upper_left.x) / cell_size).floor() as i64;
+                let row = ((upper_left.y - coordinate.y) / cell_size).floor() as i64;
+
+                values[(column + row).rem_euclid(2) as usize]
+            }
+        }
+    }
+}
+
+/// The parts of the synthetic raster sources that are independent of their pattern
+#[derive(Debug, Clone, Copy)]
+struct SyntheticRaster {
+    spatial_bounds: SpatialPartition2D,
+    time: TimeInterval,
+    no_data_value: f64,
+    pattern: RasterPattern,
+}
+
+pub struct SyntheticRasterProcessor<T> {
+    tiling_specification: TilingSpecification,
+    raster: SyntheticRaster,
+    phantom_data: PhantomData<T>,
+}
+
+impl<T> SyntheticRasterProcessor<T>
+where
+    T: Pixel,
+{
+    fn generate_tile(&self, tile_info: TileInformation) -> RasterTile2D<T> {
+        let raster = &self.raster;
+        let no_data_value = T::from_(raster.no_data_value);
+
+        if !tile_info
+            .spatial_partition()
+            .intersects(&raster.spatial_bounds)
+        {
+            return RasterTile2D::new_with_tile_info(
+                raster.time,
+                tile_info,
+                EmptyGrid::new(tile_info.tile_size_in_pixels, no_data_value).into(),
+            );
+        }
+
+        let upper_left = raster.spatial_bounds.upper_left();
+        let lower_right = raster.spatial_bounds.lower_right();
+        let tile_geo_transform = tile_info.tile_geo_transform();
+        let [height, width] = tile_info.tile_size_in_pixels.shape_array;
+
+        let mut data = Vec::with_capacity(height * width);
+        for y in 0..height {
+            for x in 0..width {
+                let coordinate = tile_geo_transform
+                    .grid_idx_to_center_coordinate_2d([y as isize, x as isize].into());
+
+                let inside_bounds = coordinate.x >= upper_left.x
+                    && coordinate.x < lower_right.x
+                    && coordinate.y <= upper_left.y
+                    && coordinate.y > lower_right.y;
+
+                data.push(if inside_bounds {
+                    T::from_(raster.pattern.value(&raster.spatial_bounds, coordinate))
+                } else {
+                    no_data_value
+                });
+            }
+        }
+
+        let grid: GridOrEmpty2D<T> =
+            Grid2D::new(tile_info.tile_size_in_pixels, data, Some(no_data_value))
+                .expect("the data must match the tile size")
+                .into();
+
+        RasterTile2D::new_with_tile_info(raster.time, tile_info, grid)
+    }
+}
+
+#[async_trait]
+impl<T> QueryProcessor for SyntheticRasterProcessor<T>
+where
+    T: Pixel,
+{
+    type Output = RasterTile2D<T>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        _ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        if !query.time_interval.intersects(&self.raster.time) {
+            return Ok(stream::empty().boxed());
+        }
+
+        let tiling_strategy = self
+            .tiling_specification
+            .strategy(query.spatial_resolution.x, -query.spatial_resolution.y);
+
+        Ok(
+            stream::iter(tiling_strategy.tile_information_iterator(query.spatial_bounds))
+                .map(move |tile_info| Ok(self.generate_tile(tile_info)))
+                .boxed(),
+        )
+    }
+}
+
+pub struct InitializedSyntheticRasterSource {
+    result_descriptor: RasterResultDescriptor,
+    tiling_specification: TilingSpecification,
+    raster: SyntheticRaster,
+}
+
+impl InitializedSyntheticRasterSource {
+    fn new(
+        data_type: RasterDataType,
+        spatial_reference: SpatialReference,
+        raster: SyntheticRaster,
+        context: &dyn ExecutionContext,
+    ) -> Self {
+        Self {
+            result_descriptor: RasterResultDescriptor {
+                data_type,
+                spatial_reference: spatial_reference.into(),
+                measurement: Measurement::Unitless,
+                no_data_value: Some(raster.no_data_value),
+                bands: Vec::new(),
+            },
+            tiling_specification: context.tiling_specification(),
+            raster,
+        }
+    }
+}
+
+impl InitializedRasterOperator for InitializedSyntheticRasterSource {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        Ok(call_generic_raster_processor!(
+            self.result_descriptor.data_type,
+            SyntheticRasterProcessor {
+                tiling_specification: self.tiling_specification,
+                raster: self.raster,
+                phantom_data: PhantomData,
+            }
+            .boxed()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for GradientSource {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let params = self.params;
+
+        let raster = SyntheticRaster {
+            spatial_bounds: params.spatial_bounds,
+            time: params.time,
+            no_data_value: params.no_data_value,
+            pattern: RasterPattern::Gradient {
+                from_value: params.from_value,
+                to_value: params.to_value,
+                direction: params.direction,
+            },
+        };
+
+        Ok(InitializedSyntheticRasterSource::new(
+            params.data_type,
+            params.spatial_reference,
+            raster,
+            context,
+        )
+        .boxed())
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for CheckerboardSource {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let params = self.params;
+
+        ensure!(
+            params.cell_size > 0.,
+            error::InvalidOperatorSpec {
+                reason: "`cellSize` must be positive"
+            }
+        );
+
+        let raster = SyntheticRaster {
+            spatial_bounds: params.spatial_bounds,
+            time: params.time,
+            no_data_value: params.no_data_value,
+            pattern: RasterPattern::Checkerboard {
+                cell_size: params.cell_size,
+                values: params.values,
+            },
+        };
+
+        Ok(InitializedSyntheticRasterSource::new(
+            params.data_type,
+            params.spatial_reference,
+            raster,
+            context,
+        )
+        .boxed())
+    }
+}
+
+pub struct PixelAreaProcessor<T> {
+    tiling_specification: TilingSpecification,
+    output: PixelAreaOutput,
+    time: TimeInterval,
+    phantom_data: PhantomData<T>,
+}
+
+impl<T> PixelAreaProcessor<T>
+where
+    T: Pixel,
+{
+    fn generate_tile(&self, tile_info: TileInformation) -> RasterTile2D<T> {
+        let geo_transform = tile_info.tile_geo_transform();
+        let [height, width] = tile_info.tile_size_in_pixels.shape_array;
+
+        let mut data = Vec::with_capacity(height * width);
+        for y in 0..height {
+            // all pixels of a row have the same area
+            let top = geo_transform.origin_coordinate.y + y as f64 * geo_transform.y_pixel_size;
+            let value = self.output.value(
+                top,
+                top + geo_transform.y_pixel_size,
+                geo_transform.x_pixel_size.abs(),
+            );
+
+            data.extend(std::iter::repeat(T::from_(value)).take(width));
+        }
+
+        let grid: GridOrEmpty2D<T> = Grid2D::new(tile_info.tile_size_in_pixels, data, None)
+            .expect("the data must match the tile size")
+            .into();
+
+        RasterTile2D::new_with_tile_info(self.time, tile_info, grid)
+    }
+}
+
+#[async_trait]
+impl<T> QueryProcessor for PixelAreaProcessor<T>
+where
+    T: Pixel,
+{
+    type Output = RasterTile2D<T>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        _ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        if !query.time_interval.intersects(&self.time) {
+            return Ok(stream::empty().boxed());
+        }
+
+        let tiling_strategy = self
+            .tiling_specification
+            .strategy(query.spatial_resolution.x, -query.spatial_resolution.y);
+
+        Ok(
+            stream::iter(tiling_strategy.tile_information_iterator(query.spatial_bounds))
+                .map(move |tile_info| Ok(self.generate_tile(tile_info)))
+                .boxed(),
+        )
+    }
+}
+
+pub struct InitializedPixelAreaSource {
+    result_descriptor: RasterResultDescriptor,
+    tiling_specification: TilingSpecification,
+    output: PixelAreaOutput,
+    time: TimeInterval,
+}
+
+impl InitializedRasterOperator for InitializedPixelAreaSource {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        Ok(call_generic_raster_processor!(
+            self.result_descriptor.data_type,
+            PixelAreaProcessor {
+                tiling_specification: self.tiling_specification,
+                output: self.output,
+                time: self.time,
+                phantom_data: PhantomData,
+            }
+            .boxed()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for PixelAreaSource {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let params = self.params;
+
+        ensure!(
+            matches!(params.data_type, RasterDataType::F32 | RasterDataType::F64),
+            error::InvalidOperatorSpec {
+                reason: "`dataType` must be `F32` or `F64`"
+            }
+        );
+
+        Ok(InitializedPixelAreaSource {
+            result_descriptor: RasterResultDescriptor {
+                data_type: params.data_type,
+                spatial_reference: SpatialReference::epsg_4326().into(),
+                measurement: params.output.measurement(),
+                no_data_value: None,
+                bands: Vec::new(),
+            },
+            tiling_specification: context.tiling_specification(),
+            output: params.output,
+            time: params.time,
+        }
+        .boxed())
+    }
+}
+
+/// Generates the `index`-th point of the sequence of the `seed`
+fn random_point(bounds: &BoundingBox2D, seed: u64, index: u64) -> Coordinate2D {
+    let x = unit_interval(split_mix(seed.wrapping_add(2 * index)));
+    let y = unit_interval(split_mix(seed.wrapping_add(2 * index + 1)));
+
+    let lower_left = bounds.lower_left();
+
+    Coordinate2D::new(
+        lower_left.x + x * bounds.size_x(),
+        lower_left.y + y * bounds.size_y(),
+    )
+}
+
+pub struct RandomPointsSourceProcessor {
+    params: RandomPointsSourceParams,
+}
+
+#[async_trait]
+impl QueryProcessor for RandomPointsSourceProcessor {
+    type Output = MultiPointCollection;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let params = &self.params;
+
+        let points: Vec<Coordinate2D> = if query.time_interval.intersects(&params.time) {
+            (0..params.count as u64)
+                .map(|index| random_point(&params.spatial_bounds, params.seed, index))
+                .filter(|point| query.spatial_bounds.contains_coordinate(point))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let chunk_size = (ctx.chunk_byte_size() / std::mem::size_of::<Coordinate2D>()).max(1);
+        let chunks: Vec<Vec<Coordinate2D>> = points.chunks(chunk_size).map(<[_]>::to_vec).collect();
+
+        Ok(stream::iter(chunks.into_iter().map(move |chunk| {
+            Ok(MultiPointCollection::from_data(
+                chunk.iter().map(Into::into).collect(),
+                vec![params.time; chunk.len()],
+                HashMap::new(),
+            )?)
+        }))
+        .boxed())
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for RandomPointsSource {
+    async fn initialize(
+        self: Box<Self>,
+        _context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        Ok(InitializedRandomPointsSource {
+            result_descriptor: VectorResultDescriptor {
+                data_type: VectorDataType::MultiPoint,
+                spatial_reference: self.params.spatial_reference.into(),
+                columns: Default::default(),
+            },
+            params: self.params,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedRandomPointsSource {
+    result_descriptor: VectorResultDescriptor,
+    params: RandomPointsSourceParams,
+}
+
+impl InitializedVectorOperator for InitializedRandomPointsSource {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        Ok(TypedVectorQueryProcessor::MultiPoint(
+            RandomPointsSourceProcessor {
+                params: self.params.clone(),
+            }
+            .boxed(),
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, RasterQueryProcessor};
+    use futures::TryStreamExt;
+    use geoengine_datatypes::collections::{FeatureCollectionInfos, GeometryCollection};
+    use geoengine_datatypes::primitives::SpatialResolution;
+    use geoengine_datatypes::raster::{GridShape, NoDataValue};
+
+    async fn query_u8(
+        operator: Box<dyn RasterOperator>,
+        query: RasterQueryRectangle,
+    ) -> Vec<RasterTile2D<u8>> {
+        let mut exe_ctx = MockExecutionContext::default();
+        exe_ctx.tiling_specification.tile_size_in_pixels = GridShape {
+            shape_array: [2, 2],
+        };
+
+        let processor = operator
+            .initialize(&exe_ctx)
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .get_u8()
+            .unwrap();
+
+        let ctx = MockQueryContext::default();
+        processor
+            .raster_query(query, &ctx)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    fn tile_values(tile: &RasterTile2D<u8>) -> Vec<u8> {
+        tile.grid_array
+            .clone()
+            .into_materialized_grid()
+            .data
+            .into_vec()
+    }
+
+    #[tokio::test]
+    async fn gradient() {
+        let source = GradientSource {
+            params: GradientSourceParams {
+                data_type: RasterDataType::U8,
+                spatial_reference: SpatialReference::epsg_4326(),
+                spatial_bounds: SpatialPartition2D::new_unchecked((0., 2.).into(), (3., 0.).into()),
+                time: TimeInterval::default(),
+                no_data_value: 255.,
+                from_value: 0.,
+                to_value: 30.,
+                direction: GradientDirection::Horizontal,
+            },
+        }
+        .boxed();
+
+        let tiles = query_u8(
+            source,
+            RasterQueryRectangle {
+                spatial_bounds: SpatialPartition2D::new_unchecked((0., 2.).into(), (4., 0.).into()),
+                time_interval: TimeInterval::new_instant(0).unwrap(),
+                spatial_resolution: SpatialResolution::one(),
+            },
+        )
+        .await;
+
+        assert_eq!(tiles.len(), 2);
+        assert_eq!(tile_values(&tiles[0]), vec![5, 15, 5, 15]);
+        assert_eq!(tile_values(&tiles[1]), vec![25, 255, 25, 255]);
+        assert!(tiles[1].is_no_data(tile_values(&tiles[1])[1]));
+    }
+
+    #[tokio::test]
+    async fn checkerboard() {
+        let source = CheckerboardSource {
+            params: CheckerboardSourceParams {
+                data_type: RasterDataType::U8,
+                spatial_reference: SpatialReference::epsg_4326(),
+                spatial_bounds: SpatialPartition2D::new_unchecked((0., 4.).into(), (4., 0.).into()),
+                time: TimeInterval::new_unchecked(0, 10),
+                no_data_value: 0.,
+                cell_size: 1.,
+                values: [1., 2.],
+            },
+        }
+        .boxed();
+
+        let tiles = query_u8(
+            source,
+            RasterQueryRectangle {
+                spatial_bounds: SpatialPartition2D::new_unchecked((0., 2.).into(), (2., 0.).into()),
+                time_interval: TimeInterval::new_instant(5).unwrap(),
+                spatial_resolution: SpatialResolution::new_unchecked(0.5, 0.5),
+            },
+        )
+        .await;
+
+        assert_eq!(tiles.len(), 4);
+        // the query starts in the third row of cells
+        assert_eq!(tile_values(&tiles[0]), vec![1, 1, 1, 1]);
+        assert_eq!(tile_values(&tiles[1]), vec![2, 2, 2, 2]);
+        assert_eq!(tile_values(&tiles[2]), vec![2, 2, 2, 2]);
+        assert_eq!(tile_values(&tiles[3]), vec![1, 1, 1, 1]);
+        assert!(tiles
+            .iter()
+            .all(|tile| tile.time == TimeInterval::new_unchecked(0, 10)));
+    }
+
+    #[tokio::test]
+    async fn checkerboard_outside_time() {
+        let source = CheckerboardSource {
+            params: CheckerboardSourceParams {
+                data_type: RasterDataType::U8,
+                spatial_reference: SpatialReference::epsg_4326(),
+                spatial_bounds: SpatialPartition2D::new_unchecked((0., 4.).into(), (4., 0.).into()),
+                time: TimeInterval::new_unchecked(0, 10),
+                no_data_value: 0.,
+                cell_size: 1.,
+                values: [1., 2.],
+            },
+        }
+        .boxed();
+
+        let tiles = query_u8(
+            source,
+            RasterQueryRectangle {
+                spatial_bounds: SpatialPartition2D::new_unchecked((0., 2.).into(), (2., 0.).into()),
+                time_interval: TimeInterval::new_unchecked(10, 20),
+                spatial_resolution: SpatialResolution::one(),
+            },
+        )
+        .await;
+
+        assert!(tiles.is_empty());
+    }
+
+    #[tokio::test]
+    async fn checkerboard_requires_positive_cell_size() {
+        let source = CheckerboardSource {
+            params: CheckerboardSourceParams {
+                data_type: RasterDataType::U8,
+                spatial_reference: SpatialReference::epsg_4326(),
+                spatial_bounds: SpatialPartition2D::new_unchecked((0., 4.).into(), (4., 0.).into()),
+                time: TimeInterval::default(),
+                no_data_value: 0.,
+                cell_size: 0.,
+                values: [1., 2.],
+            },
+        }
+        .boxed();
+
+        assert!(source
+            .initialize(&MockExecutionContext::default())
+            .await
+            .is_err());
+    }
+
+    async fn pixel_values(output: PixelAreaOutput, resolution: f64) -> Vec<f64> {
+        let processor = PixelAreaSource {
+            params: PixelAreaSourceParams {
+                data_type: RasterDataType::F64,
+                output,
+                time: TimeInterval::default(),
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .get_f64()
+        .unwrap();
+
+        let query = RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new_unchecked(
+                (-180., 90.).into(),
+                (180., -90.).into(),
+            ),
+            time_interval: TimeInterval::new_instant(0).unwrap(),
+            spatial_resolution: SpatialResolution::new_unchecked(resolution, resolution),
+        };
+
+        let ctx = MockQueryContext::default();
+        let tiles: Vec<RasterTile2D<f64>> = processor
+            .raster_query(query, &ctx)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        tiles
+            .into_iter()
+            .flat_map(|tile| tile.grid_array.into_materialized_grid().data)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn pixel_areas_cover_the_earth() {
+        let values = pixel_values(PixelAreaOutput::SquareKilometers, 10.).await;
+
+        let total_area: f64 = values.iter().sum();
+        let earth_area = 4. * std::f64::consts::PI * (AUTHALIC_EARTH_RADIUS_METERS / 1000.).powi(2);
+
+        assert!((total_area - earth_area).abs() / earth_area < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn cosine_latitude_weights() {
+        let values = pixel_values(PixelAreaOutput::CosineLatitude, 90.).await;
+
+        // the tiles exceed the globe, but there are only two rows of four pixels inside
+        let weights: Vec<f64> = values.into_iter().filter(|&value| value > 1e-9).collect();
+
+        assert_eq!(weights.len(), 8);
+        assert!(weights
+            .iter()
+            .all(|weight| (weight - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-9));
+    }
+
+    #[tokio::test]
+    async fn pixel_area_requires_floats() {
+        let source = PixelAreaSource {
+            params: PixelAreaSourceParams {
+                data_type: RasterDataType::U8,
+                output: PixelAreaOutput::CosineLatitude,
+                time: TimeInterval::default(),
+            },
+        }
+        .boxed();
+
+        assert!(source
+            .initialize(&MockExecutionContext::default())
+            .await
+            .is_err());
+    }
+
+    async fn random_points(seed: u64, spatial_bounds: BoundingBox2D) -> Vec<MultiPointCollection> {
+        let source = RandomPointsSource {
+            params: RandomPointsSourceParams {
+                spatial_reference: SpatialReference::epsg_4326(),
+                spatial_bounds: BoundingBox2D::new_unchecked(
+                    (-10., -10.).into(),
+                    (10., 10.).into(),
+                ),
+                time: TimeInterval::default(),
+                count: 1000,
+                seed,
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::default())
+        .await
+        .unwrap();
+
+        let processor = source.query_processor().unwrap().multi_point().unwrap();
+
+        let ctx = MockQueryContext::new(usize::MAX);
+        processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds,
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &ctx,
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn random_points_are_deterministic() {
+        let everything = BoundingBox2D::new_unchecked((-10., -10.).into(), (10., 10.).into());
+
+        let points = random_points(42, everything).await;
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].len(), 1000);
+
+        assert_eq!(points, random_points(42, everything).await);
+        assert_ne!(points, random_points(43, everything).await);
+    }
+
+    #[tokio::test]
+    async fn random_points_are_filtered_by_query() {
+        let quadrant = BoundingBox2D::new_unchecked((0., 0.).into(), (10., 10.).into());
+
+        let points = random_points(42, quadrant).await;
+        let number_of_points: usize = points.iter().map(FeatureCollectionInfos::len).sum();
+
+        // roughly a quarter of the points
+        assert!((200..300).contains(&number_of_points));
+        assert!(points.iter().all(|collection| collection
+            .coordinates()
+            .iter()
+            .all(|coordinate| quadrant.contains_coordinate(coordinate))));
+    }
+
+    #[test]
+    fn serde() {
+        let source = RandomPointsSource {
+            params: RandomPointsSourceParams {
+                spatial_reference: SpatialReference::epsg_4326(),
+                spatial_bounds: BoundingBox2D::new_unchecked((0., 0.).into(), (1., 1.).into()),
+                time: TimeInterval::default(),
+                count: 10,
+                seed: 1,
+            },
+        }
+        .boxed();
+
+        let serialized = serde_json::to_value(&source).unwrap();
+        assert_eq!(
+            serialized,
+            serde_json::json!({
+                "type": "RandomPointsSource",
+                "params": {
+                    "spatialReference": "EPSG:4326",
+                    "spatialBounds": {
+                        "lowerLeftCoordinate": { "x": 0.0, "y": 0.0 },
+                        "upperRightCoordinate": { "x": 1.0, "y": 1.0 }
+                    },
+                    "time": serde_json::to_value(TimeInterval::default()).unwrap(),
+                    "count": 10,
+                    "seed": 1
+                }
+            })
+        );
+
+        let _operator: Box<dyn VectorOperator> = serde_json::from_value(serialized).unwrap();
+    }
+}
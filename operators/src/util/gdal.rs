@@ -64,12 +64,14 @@ pub fn create_ndvi_meta_data() -> GdalMetaDataRegular {
             no_data_value,
             properties_mapping: None,
             gdal_open_options: None,
+            reproject_to: None,
         },
         result_descriptor: RasterResultDescriptor {
             data_type: RasterDataType::U8,
             spatial_reference: SpatialReference::epsg_4326().into(),
             measurement: Measurement::Unitless,
             no_data_value,
+            bands: Vec::new(),
         },
     }
 }
@@ -123,6 +125,7 @@ pub fn raster_descriptor_from_dataset(
         spatial_reference: spatial_ref.into(),
         measurement: Measurement::Unitless,
         no_data_value: rasterband.no_data_value(),
+        bands: Vec::new(),
     })
 }
 
@@ -148,5 +151,6 @@ pub fn gdal_parameters_from_dataset(
         width: rasterband.x_size(),
         height: rasterband.y_size(),
         gdal_open_options: open_options,
+        reproject_to: None,
     })
 }
@@ -2,6 +2,6 @@ mod raster_or_vector;
 mod string_or_number;
 mod string_or_number_range;
 
-pub use raster_or_vector::RasterOrVectorOperator;
+pub use raster_or_vector::{MultiRasterOrVectorOperator, RasterOrVectorOperator};
 pub use string_or_number::StringOrNumber;
 pub use string_or_number_range::StringOrNumberRange;
@@ -56,6 +56,55 @@ impl OperatorDatasets for RasterOrVectorOperator {
     }
 }
 
+/// It is either one or more `RasterOperator`s or a single `VectorOperator`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MultiRasterOrVectorOperator {
+    Raster(Vec<Box<dyn RasterOperator>>),
+    Vector(Box<dyn VectorOperator>),
+}
+
+impl MultiRasterOrVectorOperator {
+    pub fn is_raster(&self) -> bool {
+        match self {
+            MultiRasterOrVectorOperator::Raster(_) => true,
+            MultiRasterOrVectorOperator::Vector(_) => false,
+        }
+    }
+
+    pub fn is_vector(&self) -> bool {
+        match self {
+            MultiRasterOrVectorOperator::Raster(_) => false,
+            MultiRasterOrVectorOperator::Vector(_) => true,
+        }
+    }
+}
+
+impl From<Vec<Box<dyn RasterOperator>>> for MultiRasterOrVectorOperator {
+    fn from(operators: Vec<Box<dyn RasterOperator>>) -> Self {
+        Self::Raster(operators)
+    }
+}
+
+impl From<Box<dyn VectorOperator>> for MultiRasterOrVectorOperator {
+    fn from(operator: Box<dyn VectorOperator>) -> Self {
+        Self::Vector(operator)
+    }
+}
+
+impl OperatorDatasets for MultiRasterOrVectorOperator {
+    fn datasets_collect(&self, datasets: &mut Vec<DatasetId>) {
+        match self {
+            MultiRasterOrVectorOperator::Raster(rasters) => {
+                for raster in rasters {
+                    raster.datasets_collect(datasets);
+                }
+            }
+            MultiRasterOrVectorOperator::Vector(v) => v.datasets_collect(datasets),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::source::{GdalSource, GdalSourceParameters};
@@ -131,4 +180,23 @@ mod tests {
         assert!(raster_or_vector_operator.is_vector());
         assert!(!raster_or_vector_operator.is_raster());
     }
+
+    #[test]
+    fn it_deserializes_multiple_raster_ops() {
+        let workflow = serde_json::json!([{
+            "type": "GdalSource",
+            "params": {
+                "dataset": {
+                    "type": "internal",
+                    "datasetId":  "fc734022-61e0-49da-b327-257ba9d602a7"
+                }
+            }
+        }])
+        .to_string();
+
+        let operator: MultiRasterOrVectorOperator = serde_json::from_str(&workflow).unwrap();
+
+        assert!(operator.is_raster());
+        assert!(!operator.is_vector());
+    }
 }
@@ -1,6 +1,9 @@
 use std::convert::TryFrom;
 
 use crate::error;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{Schema, SchemaObject, SubschemaValidation};
+use schemars::JsonSchema;
 use serde::de::Visitor;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
@@ -34,6 +37,26 @@ impl<'de> Deserialize<'de> for StringOrNumber {
     }
 }
 
+impl JsonSchema for StringOrNumber {
+    fn schema_name() -> String {
+        "StringOrNumber".to_owned()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![
+                    gen.subschema_for::<String>(),
+                    gen.subschema_for::<f64>(),
+                ]),
+                ..SubschemaValidation::default()
+            })),
+            ..SchemaObject::default()
+        }
+        .into()
+    }
+}
+
 struct StringOrNumberDeserializer;
 impl<'de> Visitor<'de> for StringOrNumberDeserializer {
     type Value = StringOrNumber;
@@ -5,6 +5,9 @@ use crate::util::input::StringOrNumber;
 use crate::util::Result;
 use geoengine_datatypes::primitives::FeatureDataValue;
 use num_traits::AsPrimitive;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{ArrayValidation, InstanceType, Schema, SchemaObject};
+use schemars::JsonSchema;
 use serde::de::{Error, SeqAccess, Visitor};
 use serde::ser::SerializeTuple;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -68,6 +71,27 @@ impl<'de> Deserialize<'de> for StringOrNumberRange {
     }
 }
 
+impl JsonSchema for StringOrNumberRange {
+    fn schema_name() -> String {
+        "StringOrNumberRange".to_owned()
+    }
+
+    /// A range is a pair of its inclusive bounds
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::Array.into()),
+            array: Some(Box::new(ArrayValidation {
+                items: Some(gen.subschema_for::<StringOrNumber>().into()),
+                min_items: Some(2),
+                max_items: Some(2),
+                ..ArrayValidation::default()
+            })),
+            ..SchemaObject::default()
+        }
+        .into()
+    }
+}
+
 struct StringOrNumberRangeDeserializer;
 impl<'de> Visitor<'de> for StringOrNumberRangeDeserializer {
     type Value = StringOrNumberRange;
@@ -2,8 +2,10 @@ pub mod gdal;
 pub mod input;
 pub mod math;
 pub mod number_statistics;
+pub mod random;
 pub mod raster_stream_to_geotiff;
 pub mod raster_stream_to_png;
+pub mod resampling;
 pub mod string_token;
 
 use crate::error::Error;
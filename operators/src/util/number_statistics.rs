@@ -10,6 +10,7 @@ pub struct NumberStatistics {
     max_value: f64,
     value_count: usize,
     value_nan_count: usize,
+    no_data_count: usize,
     mean_value: f64,
     m2: f64,
 }
@@ -21,6 +22,7 @@ impl Default for NumberStatistics {
             max_value: f64::MIN,
             value_count: 0,
             value_nan_count: 0,
+            no_data_count: 0,
             mean_value: 0.0,
             m2: 0.0,
         }
@@ -54,21 +56,40 @@ impl NumberStatistics {
     #[inline]
     pub fn add_no_data(&mut self) {
         self.value_nan_count += 1;
+        self.no_data_count += 1;
     }
 
     #[inline]
     pub fn add_no_data_batch(&mut self, batch_size: usize) {
         self.value_nan_count += batch_size;
+        self.no_data_count += batch_size;
     }
 
     pub fn count(&self) -> usize {
         self.value_count
     }
 
+    /// The number of `NaN` values and no data values
     pub fn nan_count(&self) -> usize {
         self.value_nan_count
     }
 
+    /// The number of no data values, e.g., masked pixels
+    pub fn no_data_count(&self) -> usize {
+        self.no_data_count
+    }
+
+    /// The share of valid values among all values or `NaN` if there are no values
+    pub fn valid_fraction(&self) -> f64 {
+        let total_count = self.value_count + self.value_nan_count;
+
+        if total_count > 0 {
+            self.value_count as f64 / total_count as f64
+        } else {
+            f64::NAN
+        }
+    }
+
     pub fn min(&self) -> f64 {
         if self.value_count > 0 {
             self.min_value
@@ -151,5 +172,9 @@ mod tests {
 
         assert_eq!(number_statistics.count(), 1);
         assert_eq!(number_statistics.nan_count(), 2);
+        assert_eq!(number_statistics.no_data_count(), 1);
+        assert!((number_statistics.valid_fraction() - 1. / 3.).abs() < f64::EPSILON);
+
+        assert!(NumberStatistics::default().valid_fraction().is_nan());
     }
 }
@@ -0,0 +1,14 @@
+/// A fast and well-distributed hash of 64 bit values.
+///
+/// Hashing a seed plus an index yields a reproducible random sequence with random access.
+pub fn split_mix(value: u64) -> u64 {
+    let mut z = value.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Maps the upper 53 bits to a float in `[0, 1)`
+pub fn unit_interval(value: u64) -> f64 {
+    (value >> 11) as f64 / (1_u64 << 53) as f64
+}
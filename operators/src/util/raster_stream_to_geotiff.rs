@@ -154,7 +154,7 @@ fn gdal_writer<T: Pixel + GdalType>(
         let shape = grid_array.axis_size();
         let window_size = (shape[1], shape[0]);
 
-        let buffer = Buffer::new(window_size, grid_array.data);
+        let buffer = Buffer::new(window_size, grid_array.data.into_vec());
 
         band.write(window, window_size, &buffer)?;
     }
@@ -103,6 +103,7 @@ mod tests {
     use geoengine_datatypes::{
         primitives::{Coordinate2D, SpatialPartition2D, SpatialResolution},
         raster::TilingSpecification,
+        util::test::{assert_image_eq, ImageTolerance},
     };
 
     use crate::{
@@ -144,10 +145,10 @@ mod tests {
         .await
         .unwrap();
 
-        assert_eq!(
-            include_bytes!("../../../operators/test-data/raster/png/png_from_stream.png")
-                as &[u8],
-            image_bytes.as_slice()
+        assert_image_eq(
+            include_bytes!("../../../operators/test-data/raster/png/png_from_stream.png"),
+            image_bytes.as_slice(),
+            ImageTolerance::default(),
         );
     }
 }
@@ -0,0 +1,125 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// How the pixel values of a raster are computed when it is reprojected or resampled to another resolution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ResamplingMethod {
+    /// The value of the input pixel that contains the output pixel
+    Nearest,
+    /// The linear interpolation of the 2x2 input pixels around the output pixel's center
+    Bilinear,
+    /// The cubic convolution of the 4x4 input pixels around the output pixel's center
+    Cubic,
+    /// The mean of the input pixels whose centers lie within the output pixel
+    Average,
+}
+
+impl Default for ResamplingMethod {
+    fn default() -> Self {
+        Self::Nearest
+    }
+}
+
+impl ResamplingMethod {
+    /// Computes the input pixels that contribute to an output pixel along one axis and their weights.
+    ///
+    /// The `position` is the output pixel's center in input pixels, relative to the center of the first input pixel.
+    /// The `footprint` is the size of the output pixel in input pixels.
+    ///
+    pub fn axis_weights(self, position: f64, footprint: f64) -> Vec<(isize, f64)> {
+        let index = position.floor();
+        let fraction = position - index;
+        let index = index as isize;
+
+        match self {
+            ResamplingMethod::Nearest => vec![((position + 0.5).floor() as isize, 1.)],
+            ResamplingMethod::Bilinear => vec![(index, 1. - fraction), (index + 1, fraction)],
+            ResamplingMethod::Cubic => vec![
+                (index - 1, cubic_kernel(1. + fraction)),
+                (index, cubic_kernel(fraction)),
+                (index + 1, cubic_kernel(1. - fraction)),
+                (index + 2, cubic_kernel(2. - fraction)),
+            ],
+            ResamplingMethod::Average => {
+                let first = (position - footprint / 2.).ceil() as isize;
+                let last = (position + footprint / 2.).floor() as isize;
+
+                if first > last {
+                    // the output pixel is smaller than an input pixel and lies between two input pixel centers
+                    ResamplingMethod::Nearest.axis_weights(position, footprint)
+                } else {
+                    (first..=last).map(|index| (index, 1.)).collect()
+                }
+            }
+        }
+    }
+}
+
+/// The cubic convolution kernel of Keys (1981) with `a = -0.5`
+fn cubic_kernel(distance: f64) -> f64 {
+    const A: f64 = -0.5;
+
+    let distance = distance.abs();
+
+    if distance <= 1. {
+        ((A + 2.) * distance - (A + 3.)) * distance * distance + 1.
+    } else if distance < 2. {
+        ((A * distance - 5. * A) * distance + 8. * A) * distance - 4. * A
+    } else {
+        0.
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bilinear_weights() {
+        assert_eq!(
+            ResamplingMethod::Bilinear.axis_weights(2.25, 1.),
+            vec![(2, 0.75), (3, 0.25)]
+        );
+        assert_eq!(
+            ResamplingMethod::Bilinear.axis_weights(-0.5, 1.),
+            vec![(-1, 0.5), (0, 0.5)]
+        );
+    }
+
+    #[test]
+    fn cubic_weights() {
+        let weights = ResamplingMethod::Cubic.axis_weights(1.5, 1.);
+
+        assert_eq!(
+            weights.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3]
+        );
+        assert_eq!(weights[0].1, weights[3].1);
+        assert_eq!(weights[1].1, weights[2].1);
+        assert!(weights[0].1 < 0.);
+        assert!(float_cmp::approx_eq!(
+            f64,
+            weights.iter().map(|(_, w)| w).sum::<f64>(),
+            1.
+        ));
+
+        // the kernel interpolates
+        assert_eq!(
+            ResamplingMethod::Cubic.axis_weights(1., 1.),
+            vec![(0, 0.), (1, 1.), (2, 0.), (3, 0.)]
+        );
+    }
+
+    #[test]
+    fn average_weights() {
+        assert_eq!(
+            ResamplingMethod::Average.axis_weights(1.5, 4.),
+            vec![(0, 1.), (1, 1.), (2, 1.), (3, 1.)]
+        );
+        assert_eq!(
+            ResamplingMethod::Average.axis_weights(1.3, 0.5),
+            vec![(1, 1.)]
+        );
+    }
+}
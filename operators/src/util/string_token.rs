@@ -67,6 +67,21 @@ macro_rules! string_token {
                 deserializer.deserialize_str(DeserializeVisitor)
             }
         }
+
+        impl schemars::JsonSchema for $struct {
+            fn schema_name() -> String {
+                stringify!($struct).to_owned()
+            }
+
+            fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+                schemars::schema::SchemaObject {
+                    instance_type: Some(schemars::schema::InstanceType::String.into()),
+                    enum_values: Some(vec![$string.into()]),
+                    ..schemars::schema::SchemaObject::default()
+                }
+                .into()
+            }
+        }
     };
 }
 
@@ -91,6 +106,20 @@ mod tests {
         serde_json::from_str::<Bar>(&serde_json::json! {"bar"}.to_string()).unwrap();
     }
 
+    #[test]
+    fn json_schema() {
+        string_token!(Bar, "bar");
+
+        assert_eq!(
+            serde_json::to_value(schemars::schema_for!(Bar).schema).unwrap(),
+            serde_json::json!({
+                "title": "Bar",
+                "type": "string",
+                "enum": ["bar"]
+            })
+        );
+    }
+
     #[test]
     fn binary() {
         string_token!(Foo);
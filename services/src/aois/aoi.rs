@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use geoengine_datatypes::identifier;
+use geoengine_datatypes::primitives::MultiPolygon;
+use geoengine_datatypes::spatial_reference::SpatialReference;
+
+use crate::error::{self, Result};
+use crate::projects::ProjectId;
+use crate::util::config::{get_config_element, AoiService};
+use crate::util::user_input::UserInput;
+
+identifier!(AoiId);
+
+/// A named area of interest, e.g., a drawn polygon or an uploaded boundary, that can be referenced by its id
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Aoi {
+    pub id: AoiId,
+    pub name: String,
+    pub description: String,
+    pub spatial_reference: SpatialReference,
+    pub geometry: MultiPolygon,
+    /// the project the area belongs to, if it is not available in all projects of the user
+    pub project: Option<ProjectId>,
+}
+
+impl Aoi {
+    pub fn from_add_aoi(id: AoiId, add: AddAoi) -> Self {
+        Self {
+            id,
+            name: add.name,
+            description: add.description,
+            spatial_reference: add.spatial_reference,
+            geometry: add.geometry,
+            project: add.project,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AddAoi {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub spatial_reference: SpatialReference,
+    pub geometry: MultiPolygon,
+    pub project: Option<ProjectId>,
+}
+
+impl UserInput for AddAoi {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            !self.name.is_empty() && self.name.len() <= 256,
+            error::InvalidStringLength {
+                parameter: "name".to_string(),
+                min: 1_usize,
+                max: 256_usize
+            }
+        );
+
+        // the geometry is deserialized without checking that its rings are closed
+        MultiPolygon::new(self.geometry.as_ref().to_vec())?;
+
+        Ok(())
+    }
+}
+
+/// An area of interest without its (potentially large) geometry
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AoiListing {
+    pub id: AoiId,
+    pub name: String,
+    pub description: String,
+    pub spatial_reference: SpatialReference,
+    pub project: Option<ProjectId>,
+}
+
+impl From<&Aoi> for AoiListing {
+    fn from(aoi: &Aoi) -> Self {
+        Self {
+            id: aoi.id,
+            name: aoi.name.clone(),
+            description: aoi.description.clone(),
+            spatial_reference: aoi.spatial_reference,
+            project: aoi.project,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AoiListOptions {
+    /// only list the areas of this project and the ones that are available in all projects
+    pub project: Option<ProjectId>,
+    pub offset: u32,
+    pub limit: u32,
+}
+
+impl UserInput for AoiListOptions {
+    fn validate(&self) -> Result<()> {
+        let limit = get_config_element::<AoiService>()?.list_limit;
+        ensure!(
+            self.limit <= limit,
+            error::InvalidListLimit {
+                limit: limit as usize
+            }
+        );
+
+        Ok(())
+    }
+}
+
+impl AoiListOptions {
+    /// Whether an area is part of the listing
+    pub fn includes(&self, aoi: &Aoi) -> bool {
+        match (self.project, aoi.project) {
+            (Some(project), Some(aoi_project)) => project == aoi_project,
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::util::Identifier;
+
+    fn add_aoi(geometry: MultiPolygon) -> AddAoi {
+        AddAoi {
+            name: "Marburg".to_string(),
+            description: String::new(),
+            spatial_reference: SpatialReference::epsg_4326(),
+            geometry,
+            project: None,
+        }
+    }
+
+    #[test]
+    fn it_validates_geometries() {
+        let valid = MultiPolygon::new(vec![vec![vec![
+            (0., 0.).into(),
+            (1., 0.).into(),
+            (1., 1.).into(),
+            (0., 0.).into(),
+        ]]])
+        .unwrap();
+        assert!(add_aoi(valid).validated().is_ok());
+
+        let unclosed: MultiPolygon = serde_json::from_value(serde_json::json!({
+            "polygons": [[[
+                {"x": 0.0, "y": 0.0},
+                {"x": 1.0, "y": 0.0},
+                {"x": 1.0, "y": 1.0},
+                {"x": 0.0, "y": 1.0}
+            ]]]
+        }))
+        .unwrap();
+        assert!(add_aoi(unclosed).validated().is_err());
+    }
+
+    #[test]
+    fn it_filters_by_project() {
+        let project = ProjectId::new();
+        let geometry = MultiPolygon::new(vec![vec![vec![
+            (0., 0.).into(),
+            (1., 0.).into(),
+            (1., 1.).into(),
+            (0., 0.).into(),
+        ]]])
+        .unwrap();
+
+        let shared = Aoi::from_add_aoi(AoiId::new(), add_aoi(geometry.clone()));
+        let in_project = Aoi::from_add_aoi(
+            AoiId::new(),
+            AddAoi {
+                project: Some(project),
+                ..add_aoi(geometry.clone())
+            },
+        );
+        let in_other_project = Aoi::from_add_aoi(
+            AoiId::new(),
+            AddAoi {
+                project: Some(ProjectId::new()),
+                ..add_aoi(geometry)
+            },
+        );
+
+        let options = AoiListOptions {
+            project: Some(project),
+            offset: 0,
+            limit: 10,
+        };
+
+        assert!(options.includes(&shared));
+        assert!(options.includes(&in_project));
+        assert!(!options.includes(&in_other_project));
+    }
+}
@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use geoengine_datatypes::util::Identifier;
+
+use crate::contexts::SimpleSession;
+use crate::error::{self, Result};
+use crate::util::user_input::Validated;
+
+use super::aoi::{AddAoi, Aoi, AoiId, AoiListOptions, AoiListing};
+use super::storage::AoiDb;
+
+#[derive(Default)]
+pub struct HashMapAoiDb {
+    aois: HashMap<AoiId, Aoi>,
+}
+
+#[async_trait]
+impl AoiDb<SimpleSession> for HashMapAoiDb {
+    async fn add(&mut self, _session: &SimpleSession, aoi: Validated<AddAoi>) -> Result<AoiId> {
+        let id = AoiId::new();
+        self.aois.insert(id, Aoi::from_add_aoi(id, aoi.user_input));
+        Ok(id)
+    }
+
+    async fn load(&self, _session: &SimpleSession, id: AoiId) -> Result<Aoi> {
+        self.aois
+            .get(&id)
+            .cloned()
+            .ok_or(error::Error::UnknownAoiId)
+    }
+
+    async fn list(
+        &self,
+        _session: &SimpleSession,
+        options: Validated<AoiListOptions>,
+    ) -> Result<Vec<AoiListing>> {
+        let options = options.user_input;
+
+        let mut aois: Vec<AoiListing> = self
+            .aois
+            .values()
+            .filter(|aoi| options.includes(aoi))
+            .map(AoiListing::from)
+            .collect();
+
+        aois.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(aois
+            .into_iter()
+            .skip(options.offset as usize)
+            .take(options.limit as usize)
+            .collect())
+    }
+
+    async fn delete(&mut self, _session: &SimpleSession, id: AoiId) -> Result<()> {
+        self.aois
+            .remove(&id)
+            .map(|_| ())
+            .ok_or(error::Error::UnknownAoiId)
+    }
+}
@@ -0,0 +1,3 @@
+pub mod aoi;
+pub mod in_memory;
+pub mod storage;
@@ -0,0 +1,24 @@
+use async_trait::async_trait;
+
+use crate::contexts::Session;
+use crate::error::Result;
+use crate::util::user_input::Validated;
+
+use super::aoi::{AddAoi, Aoi, AoiId, AoiListOptions, AoiListing};
+
+/// Storage of the areas of interest of users
+#[async_trait]
+pub trait AoiDb<S: Session>: Send + Sync {
+    async fn add(&mut self, session: &S, aoi: Validated<AddAoi>) -> Result<AoiId>;
+
+    async fn load(&self, session: &S, id: AoiId) -> Result<Aoi>;
+
+    /// List the areas of the user, ordered by name
+    async fn list(
+        &self,
+        session: &S,
+        options: Validated<AoiListOptions>,
+    ) -> Result<Vec<AoiListing>>;
+
+    async fn delete(&mut self, session: &S, id: AoiId) -> Result<()>;
+}
@@ -0,0 +1,354 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use futures::future::try_join_all;
+use geoengine_datatypes::primitives::{AxisAlignedRectangle, SpatialPartition2D};
+use geoengine_datatypes::spatial_reference::{SpatialReference, SpatialReferenceAuthority};
+use image::{DynamicImage, ImageFormat, RgbaImage};
+use snafu::{ensure, ResultExt};
+use tokio::fs;
+use uuid::Uuid;
+
+use crate::error::{self, Result};
+use crate::util::config::{self, get_config_element};
+use crate::util::http_client::shared_http_client;
+use crate::util::request_limiter::{RequestLimiter, RequestLimits};
+
+/// Half the width of the Web Mercator world in meters
+const WEB_MERCATOR_EXTENT: f64 = 20_037_508.342_789_244;
+
+/// The nominal size of a tile in pixels
+const TILE_SIZE: u32 = 256;
+
+/// An XYZ or WMTS tile service in Web Mercator whose tiles are proxied through a file cache
+#[derive(Debug, Clone)]
+pub struct Basemap {
+    url_template: String,
+    cache_directory: PathBuf,
+    max_zoom: u8,
+    client: reqwest::Client,
+    request_limiter: Arc<RequestLimiter>,
+}
+
+impl Basemap {
+    pub fn new(url_template: String, cache_directory: PathBuf, max_zoom: u8) -> Self {
+        Self {
+            url_template,
+            cache_directory,
+            max_zoom,
+            client: reqwest::Client::new(),
+            request_limiter: Arc::new(RequestLimiter::new(RequestLimits::default())),
+        }
+    }
+
+    /// Sends the requests to the tile service with the `client`, e.g., to share its connection pool
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Limits the requests to the tile service with a limiter that may be shared with other basemaps
+    pub fn with_request_limiter(mut self, request_limiter: Arc<RequestLimiter>) -> Self {
+        self.request_limiter = request_limiter;
+        self
+    }
+
+    /// Creates the basemap of the settings or returns `None` if there is no `url_template` configured
+    pub fn from_config() -> Result<Option<Self>> {
+        let config = get_config_element::<config::Basemap>()?;
+
+        let url_template = match config.url_template {
+            Some(url_template) => url_template,
+            None => return Ok(None),
+        };
+
+        let request_limits = RequestLimits {
+            requests_per_second: config.requests_per_second,
+            max_concurrent_requests: config.max_concurrent_requests,
+        };
+
+        Ok(Some(
+            Self::new(url_template, config.cache_directory, config.max_zoom)
+                .with_client(shared_http_client()?)
+                .with_request_limiter(RequestLimiter::shared("basemap", request_limits)),
+        ))
+    }
+
+    /// The spatial reference of the basemap, i.e., Web Mercator
+    pub fn spatial_reference() -> SpatialReference {
+        SpatialReference::new(SpatialReferenceAuthority::Epsg, 3857)
+    }
+
+    /// Loads a tile from the cache or, if it is missing, from the tile service and caches it
+    pub async fn tile(&self, z: u8, x: u32, y: u32) -> Result<Vec<u8>> {
+        ensure!(
+            z <= self.max_zoom && x < tiles_per_axis(z) && y < tiles_per_axis(z),
+            error::InvalidBasemapTile { z, x, y }
+        );
+
+        let directory = self.cache_directory.join(z.to_string()).join(x.to_string());
+        let path = directory.join(y.to_string());
+
+        if let Ok(bytes) = fs::read(&path).await {
+            return Ok(bytes);
+        }
+
+        let _permit = self.request_limiter.acquire().await;
+
+        let response = self.client.get(&self.tile_url(z, x, y)).send().await?;
+
+        ensure!(
+            response.status().is_success(),
+            error::BasemapLoading {
+                details: format!("the tile service responded with {}", response.status()),
+            }
+        );
+
+        let bytes = response.bytes().await?.to_vec();
+
+        // write to a unique temporary file first, s.t. concurrent requests for the same tile or
+        // a crash never leave a partially written tile in the cache
+        let temp_path = directory.join(format!("{}.{}.tmp", y, Uuid::new_v4()));
+
+        fs::create_dir_all(&directory).await.context(error::Io)?;
+        fs::write(&temp_path, &bytes).await.context(error::Io)?;
+
+        if let Err(error) = fs::rename(&temp_path, &path).await {
+            let _ = fs::remove_file(&temp_path).await;
+            return Err(error).context(error::Io);
+        }
+
+        Ok(bytes)
+    }
+
+    /// Renders the basemap for a `bbox` in Web Mercator as PNG image of `width` × `height` pixels
+    pub async fn render(
+        &self,
+        bbox: SpatialPartition2D,
+        width: u32,
+        height: u32,
+    ) -> Result<Vec<u8>> {
+        let x_resolution = bbox.size_x() / f64::from(width);
+        let y_resolution = bbox.size_y() / f64::from(height);
+
+        let z = self.zoom_level(x_resolution);
+        let world_pixels = f64::from(TILE_SIZE) * f64::from(tiles_per_axis(z));
+        let upper_left = bbox.upper_left();
+
+        // the pixel centers of the image in pixel coordinates of the whole world at zoom level `z`
+        let columns: Vec<f64> = (0..width)
+            .map(|column| {
+                let x = upper_left.x + (f64::from(column) + 0.5) * x_resolution;
+                (x + WEB_MERCATOR_EXTENT) / (2. * WEB_MERCATOR_EXTENT) * world_pixels
+            })
+            .collect();
+        let rows: Vec<f64> = (0..height)
+            .map(|row| {
+                let y = upper_left.y - (f64::from(row) + 0.5) * y_resolution;
+                (WEB_MERCATOR_EXTENT - y) / (2. * WEB_MERCATOR_EXTENT) * world_pixels
+            })
+            .collect();
+
+        let tile_columns: BTreeSet<u32> = columns
+            .iter()
+            .filter_map(|&pixel| tile_index(pixel, world_pixels))
+            .collect();
+        let tile_rows: BTreeSet<u32> = rows
+            .iter()
+            .filter_map(|&pixel| tile_index(pixel, world_pixels))
+            .collect();
+
+        let tile_indices: Vec<(u32, u32)> = tile_columns
+            .iter()
+            .flat_map(|&x| tile_rows.iter().map(move |&y| (x, y)))
+            .collect();
+
+        let tiles = try_join_all(tile_indices.iter().map(|&(x, y)| self.tile(z, x, y))).await?;
+
+        let tiles = tile_indices
+            .into_iter()
+            .zip(tiles)
+            .map(|(index, bytes)| Ok((index, decode_tile(&bytes)?)))
+            .collect::<Result<HashMap<(u32, u32), RgbaImage>>>()?;
+
+        // tiles may have a higher resolution than the nominal tile size
+        let offset = |pixel: f64, tile: u32, tile_pixels: u32| {
+            let fraction = (pixel - f64::from(tile * TILE_SIZE)) / f64::from(TILE_SIZE);
+            ((fraction * f64::from(tile_pixels)) as u32).min(tile_pixels - 1)
+        };
+
+        let mut image = RgbaImage::new(width, height);
+
+        for (row, &row_pixel) in rows.iter().enumerate() {
+            for (column, &column_pixel) in columns.iter().enumerate() {
+                let (tile_x, tile_y) = match (
+                    tile_index(column_pixel, world_pixels),
+                    tile_index(row_pixel, world_pixels),
+                ) {
+                    (Some(tile_x), Some(tile_y)) => (tile_x, tile_y),
+                    _ => continue,
+                };
+
+                let tile = &tiles[&(tile_x, tile_y)];
+
+                image.put_pixel(
+                    column as u32,
+                    row as u32,
+                    *tile.get_pixel(
+                        offset(column_pixel, tile_x, tile.width()),
+                        offset(row_pixel, tile_y, tile.height()),
+                    ),
+                );
+            }
+        }
+
+        let mut buffer = Vec::new();
+
+        DynamicImage::ImageRgba8(image)
+            .write_to(&mut buffer, ImageFormat::Png)
+            .map_err(|error| error::Error::BasemapLoading {
+                details: format!("encoding PNG failed: {}", error),
+            })?;
+
+        Ok(buffer)
+    }
+
+    fn tile_url(&self, z: u8, x: u32, y: u32) -> String {
+        self.url_template
+            .replace("{z}", &z.to_string())
+            .replace("{x}", &x.to_string())
+            .replace("{y}", &y.to_string())
+            .replace("{TileMatrix}", &z.to_string())
+            .replace("{TileCol}", &x.to_string())
+            .replace("{TileRow}", &y.to_string())
+    }
+
+    /// The lowest zoom level whose resolution is at least as fine as the given `resolution`
+    fn zoom_level(&self, resolution: f64) -> u8 {
+        let zoom_level = (2. * WEB_MERCATOR_EXTENT / (f64::from(TILE_SIZE) * resolution))
+            .log2()
+            .ceil();
+
+        if zoom_level.is_nan() || zoom_level <= 0. {
+            0
+        } else {
+            (zoom_level as u8).min(self.max_zoom)
+        }
+    }
+}
+
+fn tiles_per_axis(z: u8) -> u32 {
+    1 << z.min(31)
+}
+
+fn tile_index(pixel: f64, world_pixels: f64) -> Option<u32> {
+    if pixel < 0. || pixel >= world_pixels {
+        return None;
+    }
+
+    Some((pixel / f64::from(TILE_SIZE)) as u32)
+}
+
+fn decode_tile(bytes: &[u8]) -> Result<RgbaImage> {
+    image::load_from_memory(bytes)
+        .map(|image| image.to_rgba8())
+        .map_err(|error| error::Error::BasemapLoading {
+            details: format!("decoding the tile failed: {}", error),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::primitives::Coordinate2D;
+    use httptest::{matchers::request, responders::status_code, Expectation, Server};
+    use image::Rgba;
+
+    fn tile_png(color: Rgba<u8>) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(TILE_SIZE, TILE_SIZE, color))
+            .write_to(&mut buffer, ImageFormat::Png)
+            .unwrap();
+        buffer
+    }
+
+    #[tokio::test]
+    async fn it_caches_tiles() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/3/2/1.png"))
+                .times(1)
+                .respond_with(status_code(200).body(tile_png(Rgba([255, 0, 0, 255])))),
+        );
+
+        let cache = tempfile::tempdir().unwrap();
+        let basemap = Basemap::new(
+            format!("{}{{z}}/{{x}}/{{y}}.png", server.url_str("/")),
+            cache.path().to_owned(),
+            19,
+        );
+
+        let tile = basemap.tile(3, 2, 1).await.unwrap();
+        assert_eq!(basemap.tile(3, 2, 1).await.unwrap(), tile);
+        assert!(cache.path().join("3/2/1").exists());
+
+        // no temporary files are left behind
+        assert_eq!(
+            std::fs::read_dir(cache.path().join("3/2")).unwrap().count(),
+            1
+        );
+
+        assert!(basemap.tile(3, 8, 1).await.is_err());
+        assert!(basemap.tile(20, 0, 0).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_renders_tiles() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/1/0/0"))
+                .respond_with(status_code(200).body(tile_png(Rgba([255, 0, 0, 255])))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/1/1/0"))
+                .respond_with(status_code(200).body(tile_png(Rgba([0, 0, 255, 255])))),
+        );
+
+        let cache = tempfile::tempdir().unwrap();
+        let basemap = Basemap::new(
+            format!(
+                "{}{{TileMatrix}}/{{TileCol}}/{{TileRow}}",
+                server.url_str("/")
+            ),
+            cache.path().to_owned(),
+            19,
+        );
+
+        // the northern half of the world in two pixels per tile at zoom level 1
+        let bbox = SpatialPartition2D::new(
+            Coordinate2D::new(-WEB_MERCATOR_EXTENT, WEB_MERCATOR_EXTENT),
+            Coordinate2D::new(WEB_MERCATOR_EXTENT, 0.),
+        )
+        .unwrap();
+
+        let png = basemap.render(bbox, 512, 256).await.unwrap();
+        let image = decode_tile(&png).unwrap();
+
+        assert_eq!(image.dimensions(), (512, 256));
+        assert_eq!(image.get_pixel(0, 0), &Rgba([255, 0, 0, 255]));
+        assert_eq!(image.get_pixel(511, 255), &Rgba([0, 0, 255, 255]));
+    }
+
+    #[test]
+    fn it_selects_zoom_levels() {
+        let basemap = Basemap::new(String::new(), PathBuf::new(), 5);
+
+        let world_resolution = 2. * WEB_MERCATOR_EXTENT / f64::from(TILE_SIZE);
+
+        assert_eq!(basemap.zoom_level(world_resolution * 2.), 0);
+        assert_eq!(basemap.zoom_level(world_resolution), 0);
+        assert_eq!(basemap.zoom_level(world_resolution / 3.), 2);
+        assert_eq!(basemap.zoom_level(1.), 5);
+    }
+}
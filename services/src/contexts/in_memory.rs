@@ -4,7 +4,7 @@ use crate::error::Error;
 use crate::{
     datasets::add_from_directory::{add_datasets_from_directory, add_providers_from_directory},
     error::Result,
-    util::{dataset_defs_dir, provider_defs_dir},
+    util::{dataset_defs_dir, layer_defs_dir, provider_defs_dir},
 };
 use crate::{projects::hashmap_projectdb::HashMapProjectDb, workflows::registry::HashMapRegistry};
 use async_trait::async_trait;
@@ -12,10 +12,15 @@ use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 use super::{Context, Db, SimpleSession};
 use super::{Session, SimpleContext};
-use crate::contexts::{ExecutionContextImpl, QueryContextImpl, SessionId};
+use crate::aois::in_memory::HashMapAoiDb;
+use crate::contexts::{ExecutionContextImpl, MetaDataCache, QueryContextImpl, SessionId};
 use crate::datasets::in_memory::HashMapDatasetDb;
+use crate::layers::add_from_directory::add_layer_collections_from_directory;
+use crate::layers::in_memory::HashMapLayerDb;
+use crate::util::admission_control::AdmissionControl;
 use crate::util::config;
-use geoengine_operators::concurrency::ThreadPool;
+use crate::util::slow_query_log::SlowQueryLog;
+use geoengine_operators::concurrency::{QueryScheduler, ThreadPool};
 
 /// A context with references to in-memory versions of the individual databases.
 #[derive(Clone, Default)]
@@ -23,8 +28,14 @@ pub struct InMemoryContext {
     project_db: Db<HashMapProjectDb>,
     workflow_registry: Db<HashMapRegistry>,
     dataset_db: Db<HashMapDatasetDb>,
+    layer_db: Db<HashMapLayerDb>,
+    aoi_db: Db<HashMapAoiDb>,
     session: Db<SimpleSession>,
     thread_pool: Arc<ThreadPool>,
+    meta_data_cache: MetaDataCache,
+    query_scheduler: QueryScheduler,
+    admission_control: AdmissionControl,
+    slow_query_log: SlowQueryLog,
 }
 
 impl InMemoryContext {
@@ -34,8 +45,32 @@ impl InMemoryContext {
         add_datasets_from_directory(&mut db, dataset_defs_dir()).await;
         add_providers_from_directory(&mut db, provider_defs_dir()).await;
 
+        let mut layer_db = HashMapLayerDb::default();
+        add_layer_collections_from_directory(&mut layer_db, layer_defs_dir()).await;
+
         InMemoryContext {
             dataset_db: Arc::new(RwLock::new(db)),
+            layer_db: Arc::new(RwLock::new(layer_db)),
+            query_scheduler: QueryScheduler::new(
+                config::get_config_element::<config::QueryScheduler>()
+                    .expect("query scheduler config must be valid")
+                    .into(),
+            ),
+            admission_control: AdmissionControl::new(
+                config::get_config_element::<config::AdmissionControl>()
+                    .expect("admission control config must be valid")
+                    .into(),
+            ),
+            slow_query_log: SlowQueryLog::new(
+                config::get_config_element::<config::SlowQueryLog>()
+                    .expect("slow query log config must be valid")
+                    .into(),
+            ),
+            meta_data_cache: MetaDataCache::new(
+                config::get_config_element::<config::MetaDataCache>()
+                    .expect("meta data cache config must be valid")
+                    .into(),
+            ),
             ..Default::default()
         }
     }
@@ -47,6 +82,8 @@ impl Context for InMemoryContext {
     type ProjectDB = HashMapProjectDb;
     type WorkflowRegistry = HashMapRegistry;
     type DatasetDB = HashMapDatasetDb;
+    type LayerDB = HashMapLayerDb;
+    type AoiDB = HashMapAoiDb;
     type QueryContext = QueryContextImpl;
     type ExecutionContext = ExecutionContextImpl<SimpleSession, HashMapDatasetDb>;
 
@@ -80,6 +117,26 @@ impl Context for InMemoryContext {
         self.dataset_db.write().await
     }
 
+    fn layer_db(&self) -> Db<Self::LayerDB> {
+        self.layer_db.clone()
+    }
+    async fn layer_db_ref(&self) -> RwLockReadGuard<'_, Self::LayerDB> {
+        self.layer_db.read().await
+    }
+    async fn layer_db_ref_mut(&self) -> RwLockWriteGuard<'_, Self::LayerDB> {
+        self.layer_db.write().await
+    }
+
+    fn aoi_db(&self) -> Db<Self::AoiDB> {
+        self.aoi_db.clone()
+    }
+    async fn aoi_db_ref(&self) -> RwLockReadGuard<'_, Self::AoiDB> {
+        self.aoi_db.read().await
+    }
+    async fn aoi_db_ref_mut(&self) -> RwLockWriteGuard<'_, Self::AoiDB> {
+        self.aoi_db.write().await
+    }
+
     fn query_context(&self) -> Result<Self::QueryContext> {
         // TODO: load config only once
         Ok(QueryContextImpl::new(
@@ -88,11 +145,30 @@ impl Context for InMemoryContext {
     }
 
     fn execution_context(&self, session: SimpleSession) -> Result<Self::ExecutionContext> {
-        Ok(ExecutionContextImpl::<SimpleSession, HashMapDatasetDb> {
-            dataset_db: self.dataset_db.clone(),
-            thread_pool: self.thread_pool.clone(),
-            session,
-        })
+        Ok(
+            ExecutionContextImpl::<SimpleSession, HashMapDatasetDb>::new(
+                self.dataset_db.clone(),
+                self.thread_pool.clone(),
+                session,
+                self.meta_data_cache.clone(),
+            ),
+        )
+    }
+
+    fn meta_data_cache(&self) -> MetaDataCache {
+        self.meta_data_cache.clone()
+    }
+
+    fn query_scheduler(&self) -> QueryScheduler {
+        self.query_scheduler.clone()
+    }
+
+    fn admission_control(&self) -> AdmissionControl {
+        self.admission_control.clone()
+    }
+
+    fn slow_query_log(&self) -> SlowQueryLog {
+        self.slow_query_log.clone()
     }
 
     async fn session_by_id(&self, session_id: SessionId) -> Result<Self::Session> {
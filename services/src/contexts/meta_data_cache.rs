@@ -0,0 +1,346 @@
+use crate::contexts::SessionId;
+use crate::util::config;
+use async_trait::async_trait;
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_operators::engine::{MetaData, ResultDescriptor};
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// The parameters of a `MetaDataCache`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetaDataCacheConfig {
+    /// The cached meta data of a session is discarded after this time
+    pub session_ttl: Duration,
+    /// The number of sessions whose meta data is cached, the oldest caches are discarded first
+    pub max_sessions: usize,
+}
+
+impl Default for MetaDataCacheConfig {
+    fn default() -> Self {
+        Self {
+            session_ttl: Duration::from_secs(3600),
+            max_sessions: 1000,
+        }
+    }
+}
+
+impl From<config::MetaDataCache> for MetaDataCacheConfig {
+    fn from(config: config::MetaDataCache) -> Self {
+        Self {
+            session_ttl: Duration::from_secs(config.session_ttl_seconds),
+            max_sessions: config.max_sessions,
+        }
+    }
+}
+
+struct SessionCache {
+    created: Instant,
+    entries: HashMap<DatasetId, Box<dyn Any + Send + Sync>>,
+}
+
+impl SessionCache {
+    fn new() -> Self {
+        Self {
+            created: Instant::now(),
+            entries: HashMap::new(),
+        }
+    }
+}
+
+/// A per-session cache of `MetaData` objects and their result descriptors.
+///
+/// Fetching meta data from (external) providers can be expensive, e.g., if it requires requests to a remote
+/// catalog. Since workflows are re-initialized frequently, the execution context stores the meta data
+/// of each dataset per session. Entries stay valid until they are explicitly invalidated or the
+/// cache of their session expires, which also bounds the memory of sessions that never log out.
+#[derive(Clone, Default)]
+pub struct MetaDataCache {
+    config: MetaDataCacheConfig,
+    sessions: Arc<RwLock<HashMap<SessionId, SessionCache>>>,
+}
+
+impl MetaDataCache {
+    pub fn new(config: MetaDataCacheConfig) -> Self {
+        Self {
+            config,
+            sessions: Default::default(),
+        }
+    }
+
+    /// Get the cached meta data of `dataset` for `session`.
+    /// Returns `None` if there is no entry or the entry is of a different meta data type.
+    pub async fn get<L, R, Q>(
+        &self,
+        session: SessionId,
+        dataset: &DatasetId,
+    ) -> Option<Box<dyn MetaData<L, R, Q>>>
+    where
+        L: 'static,
+        R: 'static + ResultDescriptor,
+        Q: 'static,
+    {
+        let sessions = self.sessions.read().await;
+        let cache = sessions.get(&session)?;
+
+        if cache.created.elapsed() >= self.config.session_ttl {
+            return None;
+        }
+
+        cache
+            .entries
+            .get(dataset)?
+            .downcast_ref::<Box<dyn MetaData<L, R, Q>>>()
+            .cloned()
+    }
+
+    /// Cache the `meta_data` of `dataset` for `session`.
+    /// The result descriptor is computed once and stored alongside the meta data.
+    pub async fn insert<L, R, Q>(
+        &self,
+        session: SessionId,
+        dataset: DatasetId,
+        meta_data: Box<dyn MetaData<L, R, Q>>,
+    ) -> geoengine_operators::util::Result<Box<dyn MetaData<L, R, Q>>>
+    where
+        L: Send + Sync + 'static,
+        R: Debug + Send + Sync + 'static + ResultDescriptor,
+        Q: Send + Sync + 'static,
+    {
+        let result_descriptor = meta_data.result_descriptor().await?;
+
+        let cached: Box<dyn MetaData<L, R, Q>> = Box::new(CachedMetaData {
+            meta_data,
+            result_descriptor,
+        });
+
+        let mut sessions = self.sessions.write().await;
+
+        let session_ttl = self.config.session_ttl;
+        sessions.retain(|_, cache| cache.created.elapsed() < session_ttl);
+
+        if !sessions.contains_key(&session) && sessions.len() >= self.config.max_sessions {
+            let oldest = sessions
+                .iter()
+                .min_by_key(|(_, cache)| cache.created)
+                .map(|(session, _)| *session);
+
+            if let Some(oldest) = oldest {
+                sessions.remove(&oldest);
+            }
+        }
+
+        if self.config.max_sessions > 0 {
+            sessions
+                .entry(session)
+                .or_insert_with(SessionCache::new)
+                .entries
+                .insert(dataset, Box::new(cached.clone()));
+        }
+
+        Ok(cached)
+    }
+
+    /// Remove all cached meta data of `session`
+    pub async fn invalidate_session(&self, session: SessionId) {
+        self.sessions.write().await.remove(&session);
+    }
+
+    /// Remove the cached meta data of `dataset` for all sessions
+    pub async fn invalidate_dataset(&self, dataset: &DatasetId) {
+        for cache in self.sessions.write().await.values_mut() {
+            cache.entries.remove(dataset);
+        }
+    }
+
+    /// Remove all cached meta data
+    pub async fn clear(&self) {
+        self.sessions.write().await.clear();
+    }
+}
+
+/// Wraps `MetaData` and stores its result descriptor
+struct CachedMetaData<L, R, Q>
+where
+    R: ResultDescriptor,
+{
+    meta_data: Box<dyn MetaData<L, R, Q>>,
+    result_descriptor: R,
+}
+
+impl<L, R, Q> Debug for CachedMetaData<L, R, Q>
+where
+    R: Debug + ResultDescriptor,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachedMetaData")
+            .field("meta_data", &self.meta_data)
+            .field("result_descriptor", &self.result_descriptor)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<L, R, Q> MetaData<L, R, Q> for CachedMetaData<L, R, Q>
+where
+    L: Send + Sync + 'static,
+    R: Debug + Send + Sync + 'static + ResultDescriptor,
+    Q: Send + Sync + 'static,
+{
+    async fn loading_info(&self, query: Q) -> geoengine_operators::util::Result<L> {
+        self.meta_data.loading_info(query).await
+    }
+
+    async fn result_descriptor(&self) -> geoengine_operators::util::Result<R> {
+        Ok(self.result_descriptor.clone())
+    }
+
+    fn box_clone(&self) -> Box<dyn MetaData<L, R, Q>> {
+        Box::new(CachedMetaData {
+            meta_data: self.meta_data.clone(),
+            result_descriptor: self.result_descriptor.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::collections::VectorDataType;
+    use geoengine_datatypes::dataset::InternalDatasetId;
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::Identifier;
+    use geoengine_operators::engine::{
+        StaticMetaData, VectorQueryRectangle, VectorResultDescriptor,
+    };
+    use geoengine_operators::mock::MockDatasetDataSourceLoadingInfo;
+
+    fn mock_meta_data() -> Box<
+        dyn MetaData<
+            MockDatasetDataSourceLoadingInfo,
+            VectorResultDescriptor,
+            VectorQueryRectangle,
+        >,
+    > {
+        Box::new(StaticMetaData {
+            loading_info: MockDatasetDataSourceLoadingInfo { points: vec![] },
+            result_descriptor: VectorResultDescriptor {
+                data_type: VectorDataType::MultiPoint,
+                spatial_reference: SpatialReference::epsg_4326().into(),
+                columns: Default::default(),
+            },
+            phantom: Default::default(),
+        })
+    }
+
+    async fn is_cached(cache: &MetaDataCache, session: SessionId, dataset: &DatasetId) -> bool {
+        cache
+            .get::<MockDatasetDataSourceLoadingInfo, VectorResultDescriptor, VectorQueryRectangle>(
+                session, dataset,
+            )
+            .await
+            .is_some()
+    }
+
+    #[tokio::test]
+    async fn cache_and_invalidate() {
+        let cache = MetaDataCache::default();
+
+        let session = SessionId::new();
+        let dataset: DatasetId = InternalDatasetId::new().into();
+
+        let meta_data = mock_meta_data();
+
+        assert!(cache
+            .get::<MockDatasetDataSourceLoadingInfo, VectorResultDescriptor, VectorQueryRectangle>(
+                session, &dataset
+            )
+            .await
+            .is_none());
+
+        cache
+            .insert(session, dataset.clone(), meta_data)
+            .await
+            .unwrap();
+
+        let cached = cache
+            .get::<MockDatasetDataSourceLoadingInfo, VectorResultDescriptor, VectorQueryRectangle>(
+                session, &dataset,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            cached.result_descriptor().await.unwrap().data_type,
+            VectorDataType::MultiPoint
+        );
+
+        // other sessions do not share the cache
+        assert!(cache
+            .get::<MockDatasetDataSourceLoadingInfo, VectorResultDescriptor, VectorQueryRectangle>(
+                SessionId::new(),
+                &dataset
+            )
+            .await
+            .is_none());
+
+        cache.invalidate_dataset(&dataset).await;
+
+        assert!(cache
+            .get::<MockDatasetDataSourceLoadingInfo, VectorResultDescriptor, VectorQueryRectangle>(
+                session, &dataset
+            )
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn bounds_the_number_of_sessions() {
+        let cache = MetaDataCache::new(MetaDataCacheConfig {
+            max_sessions: 2,
+            ..Default::default()
+        });
+
+        let dataset: DatasetId = InternalDatasetId::new().into();
+        let sessions = [SessionId::new(), SessionId::new(), SessionId::new()];
+
+        for session in sessions {
+            cache
+                .insert(session, dataset.clone(), mock_meta_data())
+                .await
+                .unwrap();
+
+            // ensure distinct creation times
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+
+        let mut cached = vec![];
+        for session in sessions {
+            cached.push(is_cached(&cache, session, &dataset).await);
+        }
+
+        // the oldest session cache was discarded
+        assert_eq!(cached, vec![false, true, true]);
+    }
+
+    #[tokio::test]
+    async fn expires_session_caches() {
+        let cache = MetaDataCache::new(MetaDataCacheConfig {
+            session_ttl: Duration::ZERO,
+            ..Default::default()
+        });
+
+        let session = SessionId::new();
+        let dataset: DatasetId = InternalDatasetId::new().into();
+
+        cache
+            .insert(session, dataset.clone(), mock_meta_data())
+            .await
+            .unwrap();
+
+        assert!(!is_cached(&cache, session, &dataset).await);
+    }
+}
@@ -5,26 +5,33 @@ use std::sync::Arc;
 use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 mod in_memory;
+mod meta_data_cache;
 mod session;
 mod simple_context;
 
+use crate::aois::storage::AoiDb;
+use crate::datasets::alias::DatasetAliasDb;
 use crate::datasets::storage::DatasetDb;
+use crate::layers::storage::LayerDb;
 
+use crate::util::admission_control::AdmissionControl;
 use crate::util::config;
 use crate::util::config::get_config_element;
+use crate::util::slow_query_log::SlowQueryLog;
 use geoengine_datatypes::dataset::DatasetId;
 use geoengine_datatypes::primitives::Coordinate2D;
 use geoengine_datatypes::raster::GridShape2D;
 use geoengine_datatypes::raster::TilingSpecification;
-use geoengine_operators::concurrency::{ThreadPool, ThreadPoolContext};
+use geoengine_operators::concurrency::{QueryScheduler, ThreadPool, ThreadPoolContext};
 use geoengine_operators::engine::{
-    ExecutionContext, MetaData, MetaDataProvider, QueryContext, RasterQueryRectangle,
-    RasterResultDescriptor, VectorQueryRectangle, VectorResultDescriptor,
+    ExecutionContext, MetaData, MetaDataProvider, QueryContext, QueryContextExtensions,
+    RasterQueryRectangle, RasterResultDescriptor, VectorQueryRectangle, VectorResultDescriptor,
 };
 use geoengine_operators::mock::MockDatasetDataSourceLoadingInfo;
-use geoengine_operators::source::{GdalLoadingInfo, OgrSourceDataset};
+use geoengine_operators::source::{FilePathPolicy, GdalLoadingInfo, OgrSourceDataset};
 
 pub use in_memory::InMemoryContext;
+pub use meta_data_cache::{MetaDataCache, MetaDataCacheConfig};
 pub use session::{MockableSession, Session, SessionId, SimpleSession};
 pub use simple_context::SimpleContext;
 
@@ -39,6 +46,8 @@ pub trait Context: 'static + Send + Sync + Clone {
     type ProjectDB: ProjectDb<Self::Session>;
     type WorkflowRegistry: WorkflowRegistry;
     type DatasetDB: DatasetDb<Self::Session>;
+    type LayerDB: LayerDb;
+    type AoiDB: AoiDb<Self::Session>;
     type QueryContext: QueryContext;
     type ExecutionContext: ExecutionContext;
 
@@ -54,20 +63,44 @@ pub trait Context: 'static + Send + Sync + Clone {
     async fn dataset_db_ref(&self) -> RwLockReadGuard<Self::DatasetDB>;
     async fn dataset_db_ref_mut(&self) -> RwLockWriteGuard<Self::DatasetDB>;
 
+    fn layer_db(&self) -> Db<Self::LayerDB>;
+    async fn layer_db_ref(&self) -> RwLockReadGuard<Self::LayerDB>;
+    async fn layer_db_ref_mut(&self) -> RwLockWriteGuard<Self::LayerDB>;
+
+    fn aoi_db(&self) -> Db<Self::AoiDB>;
+    async fn aoi_db_ref(&self) -> RwLockReadGuard<Self::AoiDB>;
+    async fn aoi_db_ref_mut(&self) -> RwLockWriteGuard<Self::AoiDB>;
+
     fn query_context(&self) -> Result<Self::QueryContext>;
 
     fn execution_context(&self, session: Self::Session) -> Result<Self::ExecutionContext>;
 
+    /// The cache of dataset meta data that is shared by the execution contexts of all sessions
+    fn meta_data_cache(&self) -> MetaDataCache;
+
+    /// The scheduler that limits the number of concurrently running queries
+    fn query_scheduler(&self) -> QueryScheduler;
+
+    /// Rejects expensive queries if the server is overloaded
+    fn admission_control(&self) -> AdmissionControl;
+
+    /// Records the queries that exceed the configured duration or pixel thresholds
+    fn slow_query_log(&self) -> SlowQueryLog;
+
     async fn session_by_id(&self, session_id: SessionId) -> Result<Self::Session>;
 }
 
 pub struct QueryContextImpl {
     chunk_byte_size: usize,
+    extensions: QueryContextExtensions,
 }
 
 impl QueryContextImpl {
     pub fn new(chunk_byte_size: usize) -> Self {
-        Self { chunk_byte_size }
+        Self {
+            chunk_byte_size,
+            extensions: QueryContextExtensions::default(),
+        }
     }
 }
 
@@ -75,6 +108,14 @@ impl QueryContext for QueryContextImpl {
     fn chunk_byte_size(&self) -> usize {
         self.chunk_byte_size
     }
+
+    fn extensions(&self) -> &QueryContextExtensions {
+        &self.extensions
+    }
+
+    fn extensions_mut(&mut self) -> &mut QueryContextExtensions {
+        &mut self.extensions
+    }
 }
 
 pub struct ExecutionContextImpl<S, D>
@@ -85,6 +126,7 @@ where
     dataset_db: Db<D>,
     thread_pool: Arc<ThreadPool>,
     session: S,
+    meta_data_cache: MetaDataCache,
 }
 
 impl<S, D> ExecutionContextImpl<S, D>
@@ -92,11 +134,17 @@ where
     D: DatasetDb<S>,
     S: Session,
 {
-    pub fn new(dataset_db: Db<D>, thread_pool: Arc<ThreadPool>, session: S) -> Self {
+    pub fn new(
+        dataset_db: Db<D>,
+        thread_pool: Arc<ThreadPool>,
+        session: S,
+        meta_data_cache: MetaDataCache,
+    ) -> Self {
         Self {
             dataset_db,
             thread_pool,
             session,
+            meta_data_cache,
         }
     }
 }
@@ -131,6 +179,27 @@ where
             ]),
         }
     }
+
+    fn file_path_policy(&self) -> FilePathPolicy {
+        // TODO: load only once and handle error
+        let config_file_paths = get_config_element::<config::SourceFilePaths>().unwrap();
+
+        // the configured data directories are always allowed
+        let data_roots = config_file_paths.data_roots.map(|mut data_roots| {
+            data_roots.push(
+                get_config_element::<config::GdalSource>()
+                    .unwrap()
+                    .raster_data_root_path,
+            );
+            data_roots.push(get_config_element::<config::Upload>().unwrap().path);
+            data_roots
+        });
+
+        FilePathPolicy {
+            data_roots,
+            denied_schemes: config_file_paths.denied_schemes,
+        }
+    }
 }
 
 // TODO: use macro(?) for delegating meta_data function to DatasetDB to avoid redundant code
@@ -161,23 +230,47 @@ where
         >,
         geoengine_operators::error::Error,
     > {
-        match dataset_id {
+        if let Some(meta_data) = self
+            .meta_data_cache
+            .get(self.session.id(), dataset_id)
+            .await
+        {
+            return Ok(meta_data);
+        }
+
+        let dataset_db = self.dataset_db.read().await;
+
+        let resolved_dataset_id = dataset_db
+            .resolve_dataset_id(dataset_id)
+            .await
+            .map_err(|e| geoengine_operators::error::Error::DatasetMetaData {
+                source: Box::new(e),
+            })?;
+
+        let meta_data = match &resolved_dataset_id {
             DatasetId::Internal { dataset_id: _ } => {
-                self.dataset_db.read().await.meta_data(dataset_id).await
+                dataset_db.meta_data(&resolved_dataset_id).await
             }
             DatasetId::External(external) => {
-                self.dataset_db
-                    .read()
-                    .await
+                dataset_db
                     .dataset_provider(&self.session, external.provider_id)
                     .await
                     .map_err(|e| geoengine_operators::error::Error::DatasetMetaData {
                         source: Box::new(e),
                     })?
-                    .meta_data(dataset_id)
+                    .meta_data(&resolved_dataset_id)
                     .await
             }
-        }
+            DatasetId::Alias { alias } => Err(geoengine_operators::error::Error::DatasetMetaData {
+                source: Box::new(crate::error::Error::UnknownDatasetAlias {
+                    alias: alias.clone(),
+                }),
+            }),
+        }?;
+
+        self.meta_data_cache
+            .insert(self.session.id(), dataset_id.clone(), meta_data)
+            .await
     }
 }
 
@@ -198,23 +291,47 @@ where
         Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>>,
         geoengine_operators::error::Error,
     > {
-        match dataset_id {
+        if let Some(meta_data) = self
+            .meta_data_cache
+            .get(self.session.id(), dataset_id)
+            .await
+        {
+            return Ok(meta_data);
+        }
+
+        let dataset_db = self.dataset_db.read().await;
+
+        let resolved_dataset_id = dataset_db
+            .resolve_dataset_id(dataset_id)
+            .await
+            .map_err(|e| geoengine_operators::error::Error::DatasetMetaData {
+                source: Box::new(e),
+            })?;
+
+        let meta_data = match &resolved_dataset_id {
             DatasetId::Internal { dataset_id: _ } => {
-                self.dataset_db.read().await.meta_data(dataset_id).await
+                dataset_db.meta_data(&resolved_dataset_id).await
             }
             DatasetId::External(external) => {
-                self.dataset_db
-                    .read()
-                    .await
+                dataset_db
                     .dataset_provider(&self.session, external.provider_id)
                     .await
                     .map_err(|e| geoengine_operators::error::Error::DatasetMetaData {
                         source: Box::new(e),
                     })?
-                    .meta_data(dataset_id)
+                    .meta_data(&resolved_dataset_id)
                     .await
             }
-        }
+            DatasetId::Alias { alias } => Err(geoengine_operators::error::Error::DatasetMetaData {
+                source: Box::new(crate::error::Error::UnknownDatasetAlias {
+                    alias: alias.clone(),
+                }),
+            }),
+        }?;
+
+        self.meta_data_cache
+            .insert(self.session.id(), dataset_id.clone(), meta_data)
+            .await
     }
 }
 
@@ -235,22 +352,46 @@ where
         Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>>,
         geoengine_operators::error::Error,
     > {
-        match dataset_id {
+        if let Some(meta_data) = self
+            .meta_data_cache
+            .get(self.session.id(), dataset_id)
+            .await
+        {
+            return Ok(meta_data);
+        }
+
+        let dataset_db = self.dataset_db.read().await;
+
+        let resolved_dataset_id = dataset_db
+            .resolve_dataset_id(dataset_id)
+            .await
+            .map_err(|e| geoengine_operators::error::Error::DatasetMetaData {
+                source: Box::new(e),
+            })?;
+
+        let meta_data = match &resolved_dataset_id {
             DatasetId::Internal { dataset_id: _ } => {
-                self.dataset_db.read().await.meta_data(dataset_id).await
+                dataset_db.meta_data(&resolved_dataset_id).await
             }
             DatasetId::External(external) => {
-                self.dataset_db
-                    .read()
-                    .await
+                dataset_db
                     .dataset_provider(&self.session, external.provider_id)
                     .await
                     .map_err(|e| geoengine_operators::error::Error::DatasetMetaData {
                         source: Box::new(e),
                     })?
-                    .meta_data(dataset_id)
+                    .meta_data(&resolved_dataset_id)
                     .await
             }
-        }
+            DatasetId::Alias { alias } => Err(geoengine_operators::error::Error::DatasetMetaData {
+                source: Box::new(crate::error::Error::UnknownDatasetAlias {
+                    alias: alias.clone(),
+                }),
+            }),
+        }?;
+
+        self.meta_data_cache
+            .insert(self.session.id(), dataset_id.clone(), meta_data)
+            .await
     }
 }
@@ -0,0 +1,67 @@
+use crate::contexts::Session;
+use crate::error::{self, Result};
+use crate::util::user_input::{UserInput, Validated};
+use async_trait::async_trait;
+use geoengine_datatypes::dataset::{DatasetId, ExternalDatasetId};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+/// Maps a stable, human-readable `alias` (e.g. `sentinel2:B04`) to an external dataset
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetAliasDefinition {
+    pub alias: String,
+    pub dataset: ExternalDatasetId,
+}
+
+impl UserInput for DatasetAliasDefinition {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            !self.alias.is_empty() && self.alias.len() <= 256,
+            error::InvalidStringLength {
+                parameter: "alias".to_string(),
+                min: 1_usize,
+                max: 256_usize
+            }
+        );
+
+        ensure!(
+            self.alias
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, ':' | '_' | '-' | '.')),
+            error::InvalidDatasetAlias {
+                alias: self.alias.clone()
+            }
+        );
+
+        Ok(())
+    }
+}
+
+/// Storage and resolution of dataset aliases.
+/// Aliases are independent of the provider configuration, i.e., they can be re-pointed to a
+/// new `ExternalDatasetId` if a provider is re-configured, without breaking workflows that use them.
+#[async_trait]
+pub trait DatasetAliasDb<S: Session> {
+    /// Add or replace an alias
+    async fn add_dataset_alias(
+        &mut self,
+        session: &S,
+        alias: Validated<DatasetAliasDefinition>,
+    ) -> Result<()>;
+
+    /// List all aliases
+    async fn list_dataset_aliases(&self, session: &S) -> Result<Vec<DatasetAliasDefinition>>;
+
+    /// Get the external dataset an `alias` refers to
+    // TODO: should take a session as input, cf. `ProvenanceProvider`
+    async fn resolve_dataset_alias(&self, alias: &str) -> Result<ExternalDatasetId>;
+
+    /// Resolve `dataset` if it is an alias, otherwise return it unchanged
+    async fn resolve_dataset_id(&self, dataset: &DatasetId) -> Result<DatasetId> {
+        match dataset {
+            DatasetId::Alias { alias } => Ok(self.resolve_dataset_alias(alias).await?.into()),
+            _ => Ok(dataset.clone()),
+        }
+    }
+}
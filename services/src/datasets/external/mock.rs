@@ -4,7 +4,7 @@ use crate::{datasets::listing::DatasetListOptions, error::Result};
 use crate::{
     datasets::{
         listing::DatasetListing,
-        storage::{DatasetDefinition, DatasetProviderDefinition, MetaDataDefinition},
+        storage::{Dataset, DatasetDefinition, DatasetProviderDefinition, MetaDataDefinition},
     },
     error,
     util::user_input::Validated,
@@ -53,6 +53,24 @@ pub struct MockExternalDataProvider {
     datasets: Vec<DatasetDefinition>,
 }
 
+impl MockExternalDataProvider {
+    async fn listing(dataset: &DatasetDefinition) -> Result<DatasetListing> {
+        Ok(DatasetListing {
+            id: dataset
+                .properties
+                .id
+                .clone()
+                .ok_or(error::Error::MissingDatasetId)?,
+            name: dataset.properties.name.clone(),
+            description: dataset.properties.description.clone(),
+            tags: vec![],
+            source_operator: dataset.properties.source_operator.clone(),
+            result_descriptor: dataset.meta_data.result_descriptor().await?,
+            symbology: dataset.properties.symbology.clone(),
+        })
+    }
+}
+
 #[async_trait]
 impl DatasetProvider for MockExternalDataProvider {
     async fn list(
@@ -64,19 +82,7 @@ impl DatasetProvider for MockExternalDataProvider {
         // TODO: options
         let mut listing = vec![];
         for dataset in &self.datasets {
-            listing.push(Ok(DatasetListing {
-                id: dataset
-                    .properties
-                    .id
-                    .clone()
-                    .ok_or(error::Error::MissingDatasetId)?,
-                name: dataset.properties.name.clone(),
-                description: dataset.properties.description.clone(),
-                tags: vec![],
-                source_operator: dataset.properties.source_operator.clone(),
-                result_descriptor: dataset.meta_data.result_descriptor().await?,
-                symbology: dataset.properties.symbology.clone(),
-            }));
+            listing.push(Self::listing(dataset).await);
         }
 
         Ok(listing
@@ -88,9 +94,18 @@ impl DatasetProvider for MockExternalDataProvider {
     async fn load(
         &self,
         // _session: S,
-        _dataset: &geoengine_datatypes::dataset::DatasetId,
-    ) -> crate::error::Result<crate::datasets::storage::Dataset> {
-        Err(error::Error::NotYetImplemented)
+        dataset: &DatasetId,
+    ) -> Result<Dataset> {
+        let definition = self
+            .datasets
+            .iter()
+            .find(|d| d.properties.id.as_ref() == Some(dataset))
+            .ok_or(error::Error::UnknownDatasetId)?;
+
+        Ok(Dataset::from_listing(
+            Self::listing(definition).await?,
+            definition.properties.provenance.clone(),
+        ))
     }
 }
 
@@ -2,6 +2,7 @@ use std::path::Path;
 
 use crate::datasets::provenance::{ProvenanceOutput, ProvenanceProvider};
 use crate::error::Error;
+use crate::util::http_client::shared_http_client;
 use crate::util::parsing::string_or_string_array;
 use crate::{datasets::listing::DatasetListOptions, error::Result};
 use crate::{
@@ -33,7 +34,6 @@ use geoengine_operators::{
 use log::info;
 use quick_xml::events::Event;
 use quick_xml::Reader;
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
 
@@ -212,7 +212,7 @@ impl Nature40DataProvider {
     }
 
     async fn load_raster_dbs(&self) -> Result<RasterDbs> {
-        Client::new()
+        shared_http_client()?
             .get(format!("{}/rasterdbs.json", self.base_url))
             .basic_auth(&self.user, Some(&self.password))
             .send()
@@ -700,7 +700,8 @@ mod tests {
                         )
                         .into(),
                         measurement: Measurement::Unitless,
-                        no_data_value: None
+                        no_data_value: None,
+                        bands: Vec::new(),
                     }),
                     symbology: None
                 },
@@ -724,7 +725,8 @@ mod tests {
                         )
                         .into(),
                         measurement: Measurement::Unitless,
-                        no_data_value: None
+                        no_data_value: None,
+                        bands: Vec::new(),
                     }),
                     symbology: None
                 },
@@ -748,7 +750,8 @@ mod tests {
                         )
                         .into(),
                         measurement: Measurement::Unitless,
-                        no_data_value: None
+                        no_data_value: None,
+                        bands: Vec::new(),
                     }),
                     symbology: None
                 },
@@ -772,7 +775,8 @@ mod tests {
                         )
                         .into(),
                         measurement: Measurement::Unitless,
-                        no_data_value: None
+                        no_data_value: None,
+                        bands: Vec::new(),
                     }),
                     symbology: None
                 }
@@ -817,7 +821,8 @@ mod tests {
                 spatial_reference: SpatialReference::new(SpatialReferenceAuthority::Epsg, 25832)
                     .into(),
                 measurement: Measurement::Unitless,
-                no_data_value: None
+                no_data_value: None,
+                bands: Vec::new(),
             }
         );
 
@@ -853,6 +858,7 @@ mod tests {
                         no_data_value: None,
                         properties_mapping: None,
                         gdal_open_options: Some(vec!["UserPwd=geoengine:pwd".to_owned(), "HttpAuth=BASIC".to_owned()]),
+                        reproject_to: None,
                     }
                 }
             );
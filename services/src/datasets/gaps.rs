@@ -0,0 +1,138 @@
+use std::path::Path;
+
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, SpatialPartition2D, SpatialResolution, TimeInterval, TimeIntervalSet,
+};
+use geoengine_datatypes::spatial_reference::SpatialReferenceOption;
+use geoengine_operators::engine::{MetaData, RasterQueryRectangle, RasterResultDescriptor};
+use geoengine_operators::source::GdalLoadingInfo;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+
+use crate::error::{self, Result};
+
+/// Requests a report of the time steps of a raster dataset that have no data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GapReportRequest {
+    pub dataset: DatasetId,
+    pub time: TimeInterval,
+    /// The spatial zones to report on. If empty, the area of use of the dataset's spatial reference is used.
+    #[serde(default)]
+    pub zones: Vec<GapReportZone>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GapReportZone {
+    pub name: String,
+    pub bounds: SpatialPartition2D,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GapReport {
+    pub dataset: DatasetId,
+    pub time: TimeInterval,
+    pub zones: Vec<ZoneGapReport>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneGapReport {
+    pub name: String,
+    /// The number of time steps that the dataset lists for the zone
+    pub time_steps: usize,
+    /// The listed time steps whose files do not exist
+    pub missing_time_steps: Vec<TimeInterval>,
+    /// The parts of the requested time that are covered by existing files
+    pub coverage: TimeIntervalSet,
+    /// The parts of the requested time that are not covered by existing files
+    pub gaps: TimeIntervalSet,
+}
+
+/// Scans the loading info of a raster dataset for each zone and reports the time that is not covered by data
+pub async fn gap_report(
+    request: GapReportRequest,
+    meta_data: &dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>,
+) -> Result<GapReport> {
+    let zones = if request.zones.is_empty() {
+        let result_descriptor = meta_data.result_descriptor().await?;
+        vec![default_zone(result_descriptor.spatial_reference)?]
+    } else {
+        request.zones
+    };
+
+    let mut zone_reports = Vec::with_capacity(zones.len());
+
+    for zone in zones {
+        let loading_info = meta_data
+            .loading_info(RasterQueryRectangle {
+                spatial_bounds: zone.bounds,
+                time_interval: request.time,
+                spatial_resolution: SpatialResolution::one(),
+            })
+            .await?;
+
+        let mut time_steps = 0;
+        let mut missing_time_steps = Vec::new();
+        let mut covered = Vec::new();
+
+        for part in loading_info.info {
+            let part = part?;
+
+            time_steps += 1;
+
+            if file_exists(&part.params.file_path) {
+                covered.push(part.time);
+            } else {
+                missing_time_steps.push(part.time);
+            }
+        }
+
+        let coverage = TimeIntervalSet::new(covered).intersection(&request.time.into());
+        let gaps = coverage.gaps(request.time);
+
+        zone_reports.push(ZoneGapReport {
+            name: zone.name,
+            time_steps,
+            missing_time_steps,
+            coverage,
+            gaps,
+        });
+    }
+
+    Ok(GapReport {
+        dataset: request.dataset,
+        time: request.time,
+        zones: zone_reports,
+    })
+}
+
+fn default_zone(spatial_reference: SpatialReferenceOption) -> Result<GapReportZone> {
+    let spatial_reference = match spatial_reference {
+        SpatialReferenceOption::SpatialReference(spatial_reference) => spatial_reference,
+        SpatialReferenceOption::Unreferenced => return Err(error::Error::GapReportMissingZones),
+    };
+
+    let area_of_use = spatial_reference
+        .area_of_use_projected()
+        .context(error::DataType)?;
+
+    Ok(GapReportZone {
+        name: spatial_reference.to_string(),
+        bounds: SpatialPartition2D::new(area_of_use.upper_left(), area_of_use.lower_right())
+            .context(error::DataType)?,
+    })
+}
+
+/// Files on GDAL's virtual file systems (e.g. `/vsicurl/`) are listed by a remote catalog
+/// and are assumed to exist, because checking them would require a request per file.
+fn file_exists(file_path: &Path) -> bool {
+    let is_virtual = file_path
+        .to_str()
+        .map_or(false, |file_path| file_path.starts_with("/vsi"));
+
+    is_virtual || file_path.exists()
+}
@@ -1,15 +1,25 @@
-use crate::contexts::{MockableSession, SimpleSession};
+use crate::contexts::{MockableSession, Session, SimpleSession};
+use crate::datasets::alias::{DatasetAliasDb, DatasetAliasDefinition};
 use crate::datasets::listing::{DatasetListOptions, DatasetListing, DatasetProvider, OrderBy};
+use crate::datasets::scratch::{
+    ExpiredScratchDatasets, ScratchDataset, ScratchDatasetDb, ScratchDatasetListing,
+};
 use crate::datasets::storage::{
     AddDataset, Dataset, DatasetDb, DatasetProviderDb, DatasetProviderListOptions,
     DatasetProviderListing, DatasetStore, DatasetStorer,
 };
+use crate::datasets::tags::{
+    insert_tags, remove_tags, search_datasets, DatasetSearchOptions, DatasetSearchResult,
+    DatasetTagDb, Tags,
+};
 use crate::error;
 use crate::error::Result;
 use crate::util::user_input::Validated;
+use crate::workflows::workflow::WorkflowId;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use geoengine_datatypes::{
-    dataset::{DatasetId, DatasetProviderId, InternalDatasetId},
+    dataset::{DatasetId, DatasetProviderId, ExternalDatasetId, InternalDatasetId},
     util::Identifier,
 };
 use geoengine_operators::engine::{
@@ -47,6 +57,19 @@ pub struct HashMapDatasetDb {
     >,
     uploads: HashMap<UploadId, Upload>,
     external_providers: HashMap<DatasetProviderId, Box<dyn DatasetProviderDefinition>>,
+    aliases: HashMap<String, ExternalDatasetId>,
+    workflow_tags: HashMap<WorkflowId, Vec<String>>,
+    scratch_datasets: HashMap<InternalDatasetId, ScratchDataset>,
+}
+
+impl HashMapDatasetDb {
+    /// The datasets of the shared catalog, i.e., without the scratch datasets of sessions
+    fn catalog_datasets(&self) -> impl Iterator<Item = &Dataset> {
+        self.datasets.iter().filter(move |d| {
+            d.id.internal()
+                .map_or(true, |id| !self.scratch_datasets.contains_key(&id))
+        })
+    }
 }
 
 impl DatasetDb<SimpleSession> for HashMapDatasetDb {}
@@ -94,6 +117,247 @@ impl DatasetProviderDb<SimpleSession> for HashMapDatasetDb {
     }
 }
 
+#[async_trait]
+impl DatasetAliasDb<SimpleSession> for HashMapDatasetDb {
+    async fn add_dataset_alias(
+        &mut self,
+        _session: &SimpleSession,
+        alias: Validated<DatasetAliasDefinition>,
+    ) -> Result<()> {
+        let alias = alias.user_input;
+        self.aliases.insert(alias.alias, alias.dataset);
+        Ok(())
+    }
+
+    async fn list_dataset_aliases(
+        &self,
+        _session: &SimpleSession,
+    ) -> Result<Vec<DatasetAliasDefinition>> {
+        let mut aliases: Vec<_> = self
+            .aliases
+            .iter()
+            .map(|(alias, dataset)| DatasetAliasDefinition {
+                alias: alias.clone(),
+                dataset: dataset.clone(),
+            })
+            .collect();
+        aliases.sort_by(|a, b| a.alias.cmp(&b.alias));
+        Ok(aliases)
+    }
+
+    async fn resolve_dataset_alias(&self, alias: &str) -> Result<ExternalDatasetId> {
+        self.aliases
+            .get(alias)
+            .cloned()
+            .ok_or_else(|| error::Error::UnknownDatasetAlias {
+                alias: alias.to_owned(),
+            })
+    }
+}
+
+#[async_trait]
+impl DatasetTagDb<SimpleSession> for HashMapDatasetDb {
+    async fn add_dataset_tags(
+        &mut self,
+        _session: &SimpleSession,
+        dataset: &DatasetId,
+        tags: Validated<Tags>,
+    ) -> Result<Vec<String>> {
+        let dataset = self
+            .datasets
+            .iter_mut()
+            .find(|d| d.id == *dataset)
+            .ok_or(error::Error::UnknownDatasetId)?;
+
+        insert_tags(&mut dataset.tags, tags.user_input.tags);
+
+        Ok(dataset.tags.clone())
+    }
+
+    async fn remove_dataset_tags(
+        &mut self,
+        _session: &SimpleSession,
+        dataset: &DatasetId,
+        tags: Validated<Tags>,
+    ) -> Result<Vec<String>> {
+        let dataset = self
+            .datasets
+            .iter_mut()
+            .find(|d| d.id == *dataset)
+            .ok_or(error::Error::UnknownDatasetId)?;
+
+        remove_tags(&mut dataset.tags, &tags.user_input.tags);
+
+        Ok(dataset.tags.clone())
+    }
+
+    async fn workflow_tags(
+        &self,
+        _session: &SimpleSession,
+        workflow: WorkflowId,
+    ) -> Result<Vec<String>> {
+        Ok(self
+            .workflow_tags
+            .get(&workflow)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn add_workflow_tags(
+        &mut self,
+        _session: &SimpleSession,
+        workflow: WorkflowId,
+        tags: Validated<Tags>,
+    ) -> Result<Vec<String>> {
+        let workflow_tags = self.workflow_tags.entry(workflow).or_default();
+
+        insert_tags(workflow_tags, tags.user_input.tags);
+
+        Ok(workflow_tags.clone())
+    }
+
+    async fn remove_workflow_tags(
+        &mut self,
+        _session: &SimpleSession,
+        workflow: WorkflowId,
+        tags: Validated<Tags>,
+    ) -> Result<Vec<String>> {
+        let workflow_tags = self.workflow_tags.entry(workflow).or_default();
+
+        remove_tags(workflow_tags, &tags.user_input.tags);
+
+        Ok(workflow_tags.clone())
+    }
+
+    async fn search_datasets(
+        &self,
+        session: &SimpleSession,
+        options: Validated<DatasetSearchOptions>,
+    ) -> Result<DatasetSearchResult> {
+        let options = options.user_input;
+
+        if let Some(provider) = options.provider {
+            // the search needs all datasets to count the facets
+            let datasets = self
+                .dataset_provider(session, provider)
+                .await?
+                .list(Validated {
+                    user_input: DatasetListOptions {
+                        filter: None,
+                        order: OrderBy::NameAsc,
+                        offset: 0,
+                        limit: u32::MAX,
+                    },
+                })
+                .await?;
+
+            return Ok(search_datasets(
+                datasets.into_iter().map(|dataset| (dataset, None)),
+                &options,
+            ));
+        }
+
+        Ok(search_datasets(
+            self.catalog_datasets()
+                .map(|dataset| (dataset.listing(), dataset.temporal_extent)),
+            &options,
+        ))
+    }
+}
+
+#[async_trait]
+impl ScratchDatasetDb<SimpleSession> for HashMapDatasetDb {
+    async fn add_scratch_dataset(
+        &mut self,
+        session: &SimpleSession,
+        dataset: Validated<AddDataset>,
+        meta_data: Self::StorageType,
+        upload: UploadId,
+        expires: DateTime<Utc>,
+    ) -> Result<DatasetId> {
+        let id = self.add_dataset(session, dataset, meta_data).await?;
+
+        self.scratch_datasets.insert(
+            id.internal().expect("from AddDataset"),
+            ScratchDataset {
+                session: session.id(),
+                upload,
+                expires,
+            },
+        );
+
+        Ok(id)
+    }
+
+    async fn list_scratch_datasets(
+        &self,
+        session: &SimpleSession,
+    ) -> Result<Vec<ScratchDatasetListing>> {
+        let now = Utc::now();
+
+        let mut list: Vec<_> = self
+            .datasets
+            .iter()
+            .filter_map(|d| {
+                let scratch = self.scratch_datasets.get(&d.id.internal()?)?;
+                (scratch.session == session.id() && scratch.expires > now).then(|| {
+                    ScratchDatasetListing {
+                        id: d.id.clone(),
+                        name: d.name.clone(),
+                        expires: scratch.expires,
+                    }
+                })
+            })
+            .collect();
+        list.sort_by_key(|d| d.expires);
+
+        Ok(list)
+    }
+
+    async fn remove_expired_scratch_datasets(
+        &mut self,
+        now: DateTime<Utc>,
+    ) -> Result<ExpiredScratchDatasets> {
+        let expired: Vec<InternalDatasetId> = self
+            .scratch_datasets
+            .iter()
+            .filter(|(_, scratch)| scratch.expires <= now)
+            .map(|(id, _)| *id)
+            .collect();
+
+        let mut uploads = Vec::new();
+        for id in &expired {
+            if let Some(scratch) = self.scratch_datasets.remove(id) {
+                if !uploads.contains(&scratch.upload) {
+                    uploads.push(scratch.upload);
+                }
+            }
+
+            self.datasets.retain(|d| d.id.internal() != Some(*id));
+            self.ogr_datasets.remove(id);
+            self.mock_datasets.remove(id);
+            self.gdal_datasets.remove(id);
+        }
+
+        // keep the uploads that are still used by other scratch datasets
+        uploads.retain(|upload| {
+            !self
+                .scratch_datasets
+                .values()
+                .any(|scratch| scratch.upload == *upload)
+        });
+
+        for upload in &uploads {
+            self.uploads.remove(upload);
+        }
+
+        Ok(ExpiredScratchDatasets {
+            datasets: expired.into_iter().map(Into::into).collect(),
+            uploads,
+        })
+    }
+}
+
 pub trait HashMapStorable: Send + Sync {
     fn store(&self, id: InternalDatasetId, db: &mut HashMapDatasetDb) -> TypedResultDescriptor;
 }
@@ -171,6 +435,8 @@ impl DatasetStore<SimpleSession> for HashMapDatasetDb {
             source_operator: dataset.source_operator,
             symbology: dataset.symbology,
             provenance: dataset.provenance,
+            tags: dataset.tags,
+            temporal_extent: dataset.temporal_extent,
         };
         self.datasets.push(d);
 
@@ -195,12 +461,11 @@ impl DatasetProvider for HashMapDatasetDb {
         let options = options.user_input;
 
         let mut list: Vec<_> = if let Some(filter) = &options.filter {
-            self.datasets
-                .iter()
+            self.catalog_datasets()
                 .filter(|d| d.name.contains(filter) || d.description.contains(filter))
                 .collect()
         } else {
-            self.datasets.iter().collect()
+            self.catalog_datasets().collect()
         };
 
         match options.order {
@@ -338,6 +603,19 @@ impl ProvenanceProvider for HashMapDatasetDb {
                     .provenance(dataset)
                     .await
             }
+            DatasetId::Alias { alias } => {
+                let dataset: DatasetId = self.resolve_dataset_alias(alias).await?.into();
+                self.dataset_provider(
+                    &SimpleSession::mock(),
+                    dataset
+                        .external()
+                        .expect("resolved alias is external")
+                        .provider_id,
+                ) // TODO: get correct session into dataset provider
+                .await?
+                .provenance(&dataset)
+                .await
+            }
         }
     }
 }
@@ -371,6 +649,8 @@ mod tests {
             source_operator: "OgrSource".to_string(),
             symbology: None,
             provenance: None,
+            tags: vec![],
+            temporal_extent: None,
         };
 
         let meta = StaticMetaData {
@@ -384,6 +664,8 @@ mod tests {
                 force_ogr_spatial_filter: false,
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
+                encoding: None,
+                layer_spatial_reference: None,
             },
             result_descriptor: descriptor.clone(),
             phantom: Default::default(),
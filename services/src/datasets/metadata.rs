@@ -0,0 +1,285 @@
+use crate::datasets::storage::Dataset;
+use crate::error::{Error, Result};
+use crate::util::config::{get_config_element, Web};
+use geoengine_datatypes::dataset::{DatasetId, InternalDatasetId};
+use geoengine_datatypes::primitives::TimeInterval;
+use geoengine_datatypes::spatial_reference::{
+    SpatialReference, SpatialReferenceAuthority, SpatialReferenceOption,
+};
+use geoengine_operators::engine::TypedResultDescriptor;
+use serde_json::json;
+
+/// The encodings of metadata records that national geoportals can harvest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataFormat {
+    /// ISO 19115 in its ISO 19139 XML encoding
+    Iso19115,
+    /// DCAT-AP as JSON-LD
+    Dcat,
+}
+
+impl MetadataFormat {
+    /// Parses the name of a metadata file, i.e., `metadata.xml` or `metadata.jsonld`
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        match file_name {
+            "metadata.xml" => Some(Self::Iso19115),
+            "metadata.jsonld" => Some(Self::Dcat),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Iso19115 => "application/xml",
+            Self::Dcat => "application/ld+json",
+        }
+    }
+}
+
+/// The landing page of an internal dataset under the configured external address
+pub fn dataset_url(dataset: InternalDatasetId) -> Result<String> {
+    let base = get_config_element::<Web>()?
+        .external_address
+        .ok_or(Error::ExternalAddressNotConfigured)?;
+
+    Ok(format!("{}/dataset/internal/{}", base, dataset.to_string()))
+}
+
+/// Creates the metadata record of a `dataset` whose landing page is at `dataset_url`
+pub fn metadata_record(dataset: &Dataset, dataset_url: &str, format: MetadataFormat) -> String {
+    match format {
+        MetadataFormat::Iso19115 => format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n{}",
+            iso19115_record(dataset, dataset_url)
+        ),
+        MetadataFormat::Dcat => dcat_record(dataset, dataset_url).to_string(),
+    }
+}
+
+/// Creates the `gmd:MD_Metadata` element of a `dataset`, e.g., for embedding it into catalog responses
+pub fn iso19115_record(dataset: &Dataset, dataset_url: &str) -> String {
+    let keywords = if dataset.tags.is_empty() {
+        String::new()
+    } else {
+        format!(
+            r#"
+      <gmd:descriptiveKeywords>
+        <gmd:MD_Keywords>{keywords}
+        </gmd:MD_Keywords>
+      </gmd:descriptiveKeywords>"#,
+            keywords = dataset
+                .tags
+                .iter()
+                .map(|tag| format!(
+                    r#"
+          <gmd:keyword><gco:CharacterString>{}</gco:CharacterString></gmd:keyword>"#,
+                    escape_xml(tag)
+                ))
+                .collect::<String>()
+        )
+    };
+
+    let (credit, constraints) = if let Some(provenance) = &dataset.provenance {
+        (
+            format!(
+                r#"
+      <gmd:credit><gco:CharacterString>{}</gco:CharacterString></gmd:credit>"#,
+                escape_xml(&provenance.citation)
+            ),
+            format!(
+                r#"
+      <gmd:resourceConstraints>
+        <gmd:MD_LegalConstraints>
+          <gmd:useLimitation><gco:CharacterString>{}</gco:CharacterString></gmd:useLimitation>
+        </gmd:MD_LegalConstraints>
+      </gmd:resourceConstraints>"#,
+                escape_xml(&provenance.license)
+            ),
+        )
+    } else {
+        (String::new(), String::new())
+    };
+
+    let temporal_extent = dataset.temporal_extent.map_or(String::new(), |time| {
+        format!(
+            r#"
+      <gmd:extent>
+        <gmd:EX_Extent>
+          <gmd:temporalElement>
+            <gmd:EX_TemporalExtent>
+              <gmd:extent>
+                <gml:TimePeriod gml:id="temporal-extent">
+                  <gml:beginPosition>{begin}</gml:beginPosition>
+                  <gml:endPosition>{end}</gml:endPosition>
+                </gml:TimePeriod>
+              </gmd:extent>
+            </gmd:EX_TemporalExtent>
+          </gmd:temporalElement>
+        </gmd:EX_Extent>
+      </gmd:extent>"#,
+            begin = time.start().as_rfc3339(),
+            end = time.end().as_rfc3339()
+        )
+    });
+
+    let reference_system =
+        spatial_reference(&dataset.result_descriptor).map_or(String::new(), |spatial_reference| {
+            format!(
+                r#"
+  <gmd:referenceSystemInfo>
+    <gmd:MD_ReferenceSystem>
+      <gmd:referenceSystemIdentifier>
+        <gmd:RS_Identifier>
+          <gmd:code><gco:CharacterString>{}</gco:CharacterString></gmd:code>
+        </gmd:RS_Identifier>
+      </gmd:referenceSystemIdentifier>
+    </gmd:MD_ReferenceSystem>
+  </gmd:referenceSystemInfo>"#,
+                spatial_reference
+            )
+        });
+
+    let spatial_representation = match dataset.result_descriptor {
+        TypedResultDescriptor::Raster(_) => "grid",
+        TypedResultDescriptor::Vector(_) => "vector",
+        TypedResultDescriptor::Plot(_) | TypedResultDescriptor::Aggregate(_) => "textTable",
+    };
+
+    format!(
+        r#"<gmd:MD_Metadata xmlns:gmd="http://www.isotc211.org/2005/gmd" xmlns:gco="http://www.isotc211.org/2005/gco" xmlns:gml="http://www.opengis.net/gml/3.2" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://www.isotc211.org/2005/gmd http://schemas.opengis.net/csw/2.0.2/profiles/apiso/1.0.0/apiso.xsd">
+  <gmd:fileIdentifier><gco:CharacterString>{id}</gco:CharacterString></gmd:fileIdentifier>
+  <gmd:hierarchyLevel>
+    <gmd:MD_ScopeCode codeList="http://standards.iso.org/iso/19139/resources/gmxCodelists.xml#MD_ScopeCode" codeListValue="dataset">dataset</gmd:MD_ScopeCode>
+  </gmd:hierarchyLevel>{reference_system}
+  <gmd:identificationInfo>
+    <gmd:MD_DataIdentification>
+      <gmd:citation>
+        <gmd:CI_Citation>
+          <gmd:title><gco:CharacterString>{title}</gco:CharacterString></gmd:title>
+        </gmd:CI_Citation>
+      </gmd:citation>
+      <gmd:abstract><gco:CharacterString>{description}</gco:CharacterString></gmd:abstract>{credit}{keywords}{constraints}
+      <gmd:spatialRepresentationType>
+        <gmd:MD_SpatialRepresentationTypeCode codeList="http://standards.iso.org/iso/19139/resources/gmxCodelists.xml#MD_SpatialRepresentationTypeCode" codeListValue="{spatial_representation}">{spatial_representation}</gmd:MD_SpatialRepresentationTypeCode>
+      </gmd:spatialRepresentationType>{temporal_extent}
+    </gmd:MD_DataIdentification>
+  </gmd:identificationInfo>
+  <gmd:distributionInfo>
+    <gmd:MD_Distribution>
+      <gmd:transferOptions>
+        <gmd:MD_DigitalTransferOptions>
+          <gmd:onLine>
+            <gmd:CI_OnlineResource>
+              <gmd:linkage><gmd:URL>{url}</gmd:URL></gmd:linkage>
+            </gmd:CI_OnlineResource>
+          </gmd:onLine>
+        </gmd:MD_DigitalTransferOptions>
+      </gmd:transferOptions>
+    </gmd:MD_Distribution>
+  </gmd:distributionInfo>
+</gmd:MD_Metadata>
+"#,
+        id = escape_xml(&record_identifier(&dataset.id)),
+        reference_system = reference_system,
+        title = escape_xml(&dataset.name),
+        description = escape_xml(&dataset.description),
+        credit = credit,
+        keywords = keywords,
+        constraints = constraints,
+        spatial_representation = spatial_representation,
+        temporal_extent = temporal_extent,
+        url = escape_xml(dataset_url),
+    )
+}
+
+fn dcat_record(dataset: &Dataset, dataset_url: &str) -> serde_json::Value {
+    let mut record = json!({
+        "@context": {
+            "dcat": "http://www.w3.org/ns/dcat#",
+            "dct": "http://purl.org/dc/terms/",
+            "xsd": "http://www.w3.org/2001/XMLSchema#"
+        },
+        "@id": dataset_url,
+        "@type": "dcat:Dataset",
+        "dct:identifier": record_identifier(&dataset.id),
+        "dct:title": dataset.name,
+        "dct:description": dataset.description,
+        "dcat:keyword": dataset.tags,
+        "dcat:landingPage": { "@id": dataset_url },
+        "dcat:distribution": [{
+            "@type": "dcat:Distribution",
+            "dcat:accessURL": { "@id": dataset_url }
+        }]
+    });
+
+    if let Some(provenance) = &dataset.provenance {
+        record["dct:bibliographicCitation"] = json!(provenance.citation);
+        record["dct:license"] = json!(provenance.license);
+        record["dct:source"] = json!({ "@id": provenance.uri });
+    }
+
+    if let Some(time) = dataset.temporal_extent {
+        record["dct:temporal"] = dcat_period_of_time(time);
+    }
+
+    if let Some(spatial_reference) = spatial_reference(&dataset.result_descriptor) {
+        record["dct:conformsTo"] = json!({ "@id": spatial_reference_uri(spatial_reference) });
+    }
+
+    record
+}
+
+fn dcat_period_of_time(time: TimeInterval) -> serde_json::Value {
+    json!({
+        "@type": "dct:PeriodOfTime",
+        "dcat:startDate": {
+            "@type": "xsd:dateTime",
+            "@value": time.start().as_rfc3339()
+        },
+        "dcat:endDate": {
+            "@type": "xsd:dateTime",
+            "@value": time.end().as_rfc3339()
+        }
+    })
+}
+
+pub(crate) fn record_identifier(dataset: &DatasetId) -> String {
+    match dataset {
+        DatasetId::Internal { dataset_id } => dataset_id.to_string(),
+        DatasetId::External(external) => {
+            format!("{}:{}", external.provider_id, external.dataset_id)
+        }
+        DatasetId::Alias { alias } => alias.clone(),
+    }
+}
+
+fn spatial_reference(result_descriptor: &TypedResultDescriptor) -> Option<SpatialReference> {
+    let spatial_reference = match result_descriptor {
+        TypedResultDescriptor::Raster(descriptor) => descriptor.spatial_reference,
+        TypedResultDescriptor::Vector(descriptor) => descriptor.spatial_reference,
+        TypedResultDescriptor::Plot(_) | TypedResultDescriptor::Aggregate(_) => {
+            SpatialReferenceOption::Unreferenced
+        }
+    };
+
+    spatial_reference.into()
+}
+
+/// The OGC definition URI of EPSG codes, others are only referenced by their name
+fn spatial_reference_uri(spatial_reference: SpatialReference) -> String {
+    match *spatial_reference.authority() {
+        SpatialReferenceAuthority::Epsg => format!(
+            "http://www.opengis.net/def/crs/EPSG/0/{}",
+            spatial_reference.code()
+        ),
+        _ => spatial_reference.to_string(),
+    }
+}
+
+pub(crate) fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -1,7 +1,13 @@
 pub mod add_from_directory;
+pub mod alias;
 pub mod external;
+pub mod gaps;
 pub mod in_memory;
 pub mod listing;
+pub mod metadata;
 pub mod provenance;
+pub mod sandbox;
+pub mod scratch;
 pub mod storage;
+pub mod tags;
 pub mod upload;
@@ -2,8 +2,9 @@ use crate::error::Result;
 use async_trait::async_trait;
 use geoengine_datatypes::dataset::DatasetId;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 pub struct ProvenanceOutput {
     pub dataset: DatasetId,
     pub provenance: Option<Provenance>,
@@ -16,9 +17,198 @@ pub struct Provenance {
     pub uri: String,
 }
 
+/// The HTTP header of map and coverage responses that lists the attributions of the used datasets
+pub const ATTRIBUTION_HEADER: &str = "Attribution";
+
+impl Provenance {
+    /// A short attribution of the dataset with its citation and license
+    pub fn attribution(&self) -> String {
+        if self.license.is_empty() {
+            self.citation.clone()
+        } else {
+            format!("{} ({})", self.citation, self.license)
+        }
+    }
+}
+
 #[async_trait]
 pub trait ProvenanceProvider {
     /// get the provenance information for the `dataset`
     // TODO: should take a session as input, but this would make the DatasetProviderDefinition's initialize method generic and then it cannot be make into an object anymore
     async fn provenance(&self, dataset: &DatasetId) -> Result<ProvenanceOutput>;
 }
+
+/// The output format of a citation report
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CitationFormat {
+    Json,
+    Bibtex,
+}
+
+impl Default for CitationFormat {
+    fn default() -> Self {
+        Self::Json
+    }
+}
+
+impl ProvenanceOutput {
+    /// A citation key that is unique for the dataset and valid in `BibTeX`
+    pub fn citation_key(&self) -> String {
+        let key = match &self.dataset {
+            DatasetId::Internal { dataset_id } => dataset_id.to_string(),
+            DatasetId::External(external) => {
+                format!("{}_{}", external.provider_id, external.dataset_id)
+            }
+            DatasetId::Alias { alias } => alias.clone(),
+        };
+
+        key.chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect()
+    }
+
+    /// Render the provenance as a `BibTeX` entry.
+    /// Returns `None` if the dataset has no provenance information.
+    pub fn to_bibtex(&self) -> Option<String> {
+        let provenance = self.provenance.as_ref()?;
+
+        Some(format!(
+            "@misc{{{key},\n  title = {{{title}}},\n  howpublished = {{\\url{{{uri}}}}},\n  note = {{License: {license}}}\n}}\n",
+            key = self.citation_key(),
+            title = escape_bibtex(&provenance.citation),
+            uri = provenance.uri,
+            license = escape_bibtex(&provenance.license),
+        ))
+    }
+}
+
+/// Create a `BibTeX` bibliography for all datasets with provenance information
+pub fn bibtex_bibliography(provenance: &[ProvenanceOutput]) -> String {
+    provenance
+        .iter()
+        .filter_map(ProvenanceOutput::to_bibtex)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The distinct attributions of all datasets with provenance information in a stable order
+pub fn attributions<'a>(provenance: impl IntoIterator<Item = &'a ProvenanceOutput>) -> Vec<String> {
+    let attributions: BTreeSet<String> = provenance
+        .into_iter()
+        .filter_map(|output| output.provenance.as_ref())
+        .map(Provenance::attribution)
+        .collect();
+
+    attributions.into_iter().collect()
+}
+
+/// Joins attributions with semicolons for the `Attribution` header.
+/// Characters that are not allowed in header values, as well as `%` and `;`, are percent-encoded.
+pub fn attribution_header_value(attributions: &[String]) -> String {
+    attributions
+        .iter()
+        .map(|attribution| {
+            attribution
+                .bytes()
+                .map(|byte| match byte {
+                    b' ' | b'!'..=b'~' if byte != b'%' && byte != b';' => {
+                        (byte as char).to_string()
+                    }
+                    _ => format!("%{:02X}", byte),
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn escape_bibtex(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '{' | '}' | '&' | '%' | '$' | '#' | '_' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::dataset::{DatasetProviderId, ExternalDatasetId, InternalDatasetId};
+    use geoengine_datatypes::util::Identifier;
+    use std::str::FromStr;
+
+    #[test]
+    fn bibtex() {
+        let output = ProvenanceOutput {
+            dataset: DatasetId::External(ExternalDatasetId {
+                provider_id: DatasetProviderId::from_str("5ed3e1e2-d0ad-47ef-bd1b-1b4c1c1f1d0e")
+                    .unwrap(),
+                dataset_id: "foo-bar".to_string(),
+            }),
+            provenance: Some(Provenance {
+                citation: "Author, Data & More".to_owned(),
+                license: "CC BY 4.0".to_owned(),
+                uri: "http://example.org/".to_owned(),
+            }),
+        };
+
+        assert_eq!(
+            output.to_bibtex().unwrap(),
+            "@misc{5ed3e1e2_d0ad_47ef_bd1b_1b4c1c1f1d0e_foo_bar,\n  title = {Author, Data \\& More},\n  howpublished = {\\url{http://example.org/}},\n  note = {License: CC BY 4.0}\n}\n"
+        );
+
+        let no_provenance = ProvenanceOutput {
+            dataset: DatasetId::Internal {
+                dataset_id: InternalDatasetId::new(),
+            },
+            provenance: None,
+        };
+
+        assert!(no_provenance.to_bibtex().is_none());
+        assert_eq!(
+            bibtex_bibliography(&[output.clone(), no_provenance]),
+            output.to_bibtex().unwrap()
+        );
+    }
+
+    #[test]
+    fn attribution() {
+        let provenance = |citation: &str, license: &str| ProvenanceOutput {
+            dataset: DatasetId::Internal {
+                dataset_id: InternalDatasetId::new(),
+            },
+            provenance: Some(Provenance {
+                citation: citation.to_owned(),
+                license: license.to_owned(),
+                uri: "http://example.org/".to_owned(),
+            }),
+        };
+
+        let outputs = vec![
+            provenance("Sentinel-2; modified", "CC BY-SA 3.0 IGO"),
+            provenance("© OpenStreetMap", ""),
+            provenance("Sentinel-2; modified", "CC BY-SA 3.0 IGO"),
+        ];
+
+        let attributions = attributions(&outputs);
+
+        assert_eq!(
+            attributions,
+            vec![
+                "Sentinel-2; modified (CC BY-SA 3.0 IGO)".to_string(),
+                "© OpenStreetMap".to_string()
+            ]
+        );
+        assert_eq!(
+            attribution_header_value(&attributions),
+            "Sentinel-2%3B modified (CC BY-SA 3.0 IGO); %C2%A9 OpenStreetMap"
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use std::path::{Path, PathBuf};
+
+use geoengine_operators::source::FilePathPolicy;
+use geoengine_operators::util::gdal::gdal_open_dataset;
+use snafu::ResultExt;
+
+use crate::datasets::storage::MetaDataDefinition;
+use crate::error::{self, Result};
+use crate::util::config::{self, get_config_element};
+
+/// Restricts the GDAL/OGR drivers and virtual file systems that dataset definitions and uploads may use,
+/// s.t. users who register datasets cannot make the server fetch URLs or disclose other files.
+///
+/// Datasets of (trusted) external providers are not affected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GdalSandbox {
+    /// all drivers are allowed if this is `None`
+    allowed_drivers: Option<Vec<String>>,
+    allowed_virtual_file_systems: Vec<String>,
+}
+
+impl GdalSandbox {
+    pub fn from_config() -> Result<Self> {
+        get_config_element::<config::GdalSandbox>().map(Into::into)
+    }
+
+    /// Checks that the `path` only uses allowed virtual file systems, e.g., `/vsizip/`.
+    /// Chained virtual file systems like `/vsizip//vsicurl/` are checked as well.
+    pub fn check_path(&self, path: &Path) -> Result<()> {
+        let path = path.to_string_lossy();
+        let (file_systems, _) = FilePathPolicy::split_virtual_file_systems(&path);
+
+        for file_system in file_systems {
+            if !self
+                .allowed_virtual_file_systems
+                .iter()
+                .any(|allowed| allowed == file_system)
+            {
+                return Err(error::Error::VirtualFileSystemNotAllowed {
+                    file_system: file_system.to_string(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that GDAL opens the file at `path` with an allowed driver
+    pub async fn check_driver(&self, path: PathBuf) -> Result<()> {
+        self.check_drivers(vec![path], false).await
+    }
+
+    /// Checks that GDAL opens the uploaded `files` with allowed drivers.
+    /// Files that GDAL cannot open are only accepted if they are sidecar files, e.g., `ports.prj`,
+    /// i.e., they share their name up to the first `.` with a file that GDAL opens.
+    pub async fn check_upload(&self, files: Vec<PathBuf>) -> Result<()> {
+        self.check_drivers(files, true).await
+    }
+
+    async fn check_drivers(&self, files: Vec<PathBuf>, allow_sidecars: bool) -> Result<()> {
+        let allowed_drivers = match &self.allowed_drivers {
+            Some(allowed_drivers) => allowed_drivers.clone(),
+            None => return Ok(()),
+        };
+
+        // opening files blocks
+        tokio::task::spawn_blocking(move || {
+            let drivers: Vec<Option<String>> = files
+                .iter()
+                .map(|file| {
+                    gdal_open_dataset(file)
+                        .ok()
+                        .map(|dataset| dataset.driver().short_name())
+                })
+                .collect();
+
+            for (file, driver) in files.iter().zip(&drivers) {
+                match driver {
+                    Some(driver)
+                        if allowed_drivers
+                            .iter()
+                            .any(|allowed| allowed.eq_ignore_ascii_case(driver)) => {}
+                    Some(driver) => {
+                        return Err(error::Error::GdalDriverNotAllowed {
+                            driver: driver.clone(),
+                        })
+                    }
+                    None if allow_sidecars
+                        && files.iter().zip(&drivers).any(|(other, driver)| {
+                            driver.is_some() && dataset_name(other) == dataset_name(file)
+                        }) => {}
+                    None => {
+                        return Err(error::Error::GdalCannotOpenFile {
+                            file: file
+                                .file_name()
+                                .unwrap_or_default()
+                                .to_string_lossy()
+                                .to_string(),
+                        })
+                    }
+                }
+            }
+
+            Ok(())
+        })
+        .await
+        .context(error::TokioJoin)?
+    }
+
+    /// Checks the file paths of a dataset definition
+    pub fn check_meta_data_definition(&self, meta_data: &MetaDataDefinition) -> Result<()> {
+        match meta_data {
+            MetaDataDefinition::MockMetaData(_) => Ok(()),
+            MetaDataDefinition::OgrMetaData(m) => self.check_path(&m.loading_info.file_name),
+            MetaDataDefinition::GdalMetaDataRegular(m) => self.check_path(&m.params.file_path),
+            MetaDataDefinition::GdalStatic(m) => self.check_path(&m.params.file_path),
+        }
+    }
+}
+
+/// The file name up to the first `.`, which is shared by a dataset and its sidecar files
+fn dataset_name(file: &Path) -> Option<String> {
+    let file_name = file.file_name()?.to_string_lossy();
+    file_name.split('.').next().map(ToString::to_string)
+}
+
+impl From<config::GdalSandbox> for GdalSandbox {
+    fn from(config: config::GdalSandbox) -> Self {
+        Self {
+            allowed_drivers: config.allowed_drivers,
+            allowed_virtual_file_systems: config.allowed_virtual_file_systems,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn sandbox(
+        allowed_drivers: Option<&[&str]>,
+        allowed_virtual_file_systems: &[&str],
+    ) -> GdalSandbox {
+        GdalSandbox {
+            allowed_drivers: allowed_drivers
+                .map(|drivers| drivers.iter().map(ToString::to_string).collect()),
+            allowed_virtual_file_systems: allowed_virtual_file_systems
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn it_checks_virtual_file_systems() {
+        let sandbox = sandbox(None, &["vsizip"]);
+
+        assert!(sandbox.check_path(Path::new("upload/ports.shp")).is_ok());
+        assert!(sandbox
+            .check_path(Path::new("/vsizip/upload/ports.zip/ports.shp"))
+            .is_ok());
+
+        assert!(matches!(
+            sandbox.check_path(Path::new("/vsicurl/http://localhost:8080/secret.tif")),
+            Err(error::Error::VirtualFileSystemNotAllowed { file_system }) if file_system == "vsicurl"
+        ));
+        assert!(matches!(
+            sandbox.check_path(Path::new("/vsizip//vsicurl/http://localhost/ports.zip")),
+            Err(error::Error::VirtualFileSystemNotAllowed { file_system }) if file_system == "vsicurl"
+        ));
+        assert!(matches!(
+            sandbox.check_path(Path::new("/vsicurl?url=http://localhost/secret.tif")),
+            Err(error::Error::VirtualFileSystemNotAllowed { file_system }) if file_system == "vsicurl"
+        ));
+
+        // only leading prefixes are virtual file systems
+        assert!(sandbox
+            .check_path(Path::new("data/vsix_survey/a.tif"))
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn it_checks_drivers() {
+        let ports =
+            PathBuf::from("../operators/test-data/vector/data/ne_10m_ports/ne_10m_ports.shp");
+        let not_a_dataset =
+            PathBuf::from("../operators/test-data/vector/data/points_with_date.prj");
+
+        assert!(sandbox(None, &[]).check_driver(ports.clone()).await.is_ok());
+        assert!(sandbox(Some(&["ESRI Shapefile"]), &[])
+            .check_driver(ports.clone())
+            .await
+            .is_ok());
+
+        assert!(matches!(
+            sandbox(Some(&["GTiff", "GPKG"]), &[]).check_driver(ports).await,
+            Err(error::Error::GdalDriverNotAllowed { driver }) if driver == "ESRI Shapefile"
+        ));
+        assert!(matches!(
+            sandbox(Some(&["GTiff"]), &[]).check_driver(not_a_dataset).await,
+            Err(error::Error::GdalCannotOpenFile { file }) if file == "points_with_date.prj"
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_accepts_sidecar_files_of_uploads() {
+        let directory = PathBuf::from("../operators/test-data/vector/data/ne_10m_ports");
+        let sandbox = sandbox(Some(&["ESRI Shapefile"]), &[]);
+
+        assert!(sandbox
+            .check_upload(vec![
+                directory.join("ne_10m_ports.shp"),
+                directory.join("ne_10m_ports.dbf"),
+                directory.join("ne_10m_ports.shx"),
+            ])
+            .await
+            .is_ok());
+
+        assert!(matches!(
+            sandbox
+                .check_upload(vec![
+                    directory.join("ne_10m_ports.shp"),
+                    PathBuf::from("../operators/test-data/vector/data/points_with_date.prj"),
+                ])
+                .await,
+            Err(error::Error::GdalCannotOpenFile { file }) if file == "points_with_date.prj"
+        ));
+    }
+
+    #[test]
+    fn it_loads_the_config() {
+        assert_eq!(GdalSandbox::from_config().unwrap(), sandbox(None, &[]));
+    }
+}
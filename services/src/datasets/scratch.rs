@@ -0,0 +1,227 @@
+use crate::contexts::{Context, Session, SessionId};
+use crate::datasets::storage::{AddDataset, DatasetDefinition, DatasetStorer};
+use crate::datasets::upload::{UploadId, UploadRootPath};
+use crate::error::{self, Result};
+use crate::util::config::{get_config_element, ScratchDatasets};
+use crate::util::user_input::{UserInput, Validated};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use geoengine_datatypes::dataset::DatasetId;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+/// Creates a dataset from an upload that is only listed for the creating session and
+/// is removed together with its files once its time to live has passed.
+/// The dataset uses its own copy of the upload, s.t. its removal does not affect other datasets.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateScratchDataset {
+    pub upload: UploadId,
+    pub definition: DatasetDefinition,
+    /// the configured default is used if no ttl is given
+    pub ttl_seconds: Option<u32>,
+}
+
+impl CreateScratchDataset {
+    /// The point in time when the dataset expires if it is created `now`
+    pub fn expires(&self, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+        let ttl = match self.ttl_seconds {
+            Some(ttl) => ttl,
+            None => get_config_element::<ScratchDatasets>()?.default_ttl_seconds,
+        };
+
+        Ok(now + Duration::seconds(i64::from(ttl)))
+    }
+}
+
+impl UserInput for CreateScratchDataset {
+    fn validate(&self) -> Result<()> {
+        if let Some(ttl) = self.ttl_seconds {
+            let max_ttl = get_config_element::<ScratchDatasets>()?.max_ttl_seconds;
+            ensure!(
+                ttl > 0 && ttl <= max_ttl,
+                error::InvalidScratchDatasetTtl { max_ttl }
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScratchDatasetListing {
+    pub id: DatasetId,
+    pub name: String,
+    pub expires: DateTime<Utc>,
+}
+
+/// The scratch datasets that were removed because they expired
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpiredScratchDatasets {
+    pub datasets: Vec<DatasetId>,
+    /// The uploads that are no longer used by any dataset, s.t. their files can be deleted
+    pub uploads: Vec<UploadId>,
+}
+
+/// Bookkeeping of a scratch dataset for listing it to its session and cleaning it up
+#[derive(Debug, Clone)]
+pub struct ScratchDataset {
+    pub session: SessionId,
+    pub upload: UploadId,
+    pub expires: DateTime<Utc>,
+}
+
+/// Storage of datasets that are bound to a session and expire after some time.
+/// They can be used in workflows like any other dataset but are not part of the shared catalog.
+#[async_trait]
+pub trait ScratchDatasetDb<S: Session>: DatasetStorer {
+    /// Adds a dataset whose data is stored in `upload` and that is removed after it `expires`
+    async fn add_scratch_dataset(
+        &mut self,
+        session: &S,
+        dataset: Validated<AddDataset>,
+        meta_data: Self::StorageType,
+        upload: UploadId,
+        expires: DateTime<Utc>,
+    ) -> Result<DatasetId>;
+
+    /// Lists the scratch datasets of the `session` that have not expired yet
+    async fn list_scratch_datasets(&self, session: &S) -> Result<Vec<ScratchDatasetListing>>;
+
+    /// Removes all scratch datasets that expired before `now` together with their uploads
+    async fn remove_expired_scratch_datasets(
+        &mut self,
+        now: DateTime<Utc>,
+    ) -> Result<ExpiredScratchDatasets>;
+}
+
+/// Removes the expired scratch datasets of the context and deletes the files of their uploads
+pub async fn remove_expired_scratch_datasets<C: Context>(ctx: &C) -> Result<usize> {
+    let expired = ctx
+        .dataset_db_ref_mut()
+        .await
+        .remove_expired_scratch_datasets(Utc::now())
+        .await?;
+
+    // cached meta data would point to the deleted files
+    let meta_data_cache = ctx.meta_data_cache();
+    for dataset in &expired.datasets {
+        meta_data_cache.invalidate_dataset(dataset).await;
+    }
+
+    for upload in &expired.uploads {
+        match tokio::fs::remove_dir_all(upload.root_path()?).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+    }
+
+    Ok(expired.datasets.len())
+}
+
+/// Spawns a background task that periodically reclaims the storage of expired scratch datasets.
+/// Fails if the configured cleanup interval is zero.
+pub fn spawn_scratch_dataset_cleanup<C: Context>(ctx: C) -> Result<()> {
+    let interval = get_config_element::<ScratchDatasets>()?.cleanup_interval_seconds;
+    ensure!(interval > 0, error::InvalidScratchDatasetCleanupInterval);
+
+    tokio::spawn(clean_up_scratch_datasets(
+        ctx,
+        std::time::Duration::from_secs(interval),
+    ));
+
+    Ok(())
+}
+
+async fn clean_up_scratch_datasets<C: Context>(ctx: C, interval: std::time::Duration) {
+    let mut interval = tokio::time::interval(interval);
+
+    loop {
+        interval.tick().await;
+
+        match remove_expired_scratch_datasets(&ctx).await {
+            Ok(0) => {}
+            Ok(removed) => info!("Removed {} expired scratch datasets", removed),
+            Err(e) => warn!("Unable to remove expired scratch datasets: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contexts::{InMemoryContext, SimpleContext};
+    use crate::datasets::storage::{DatasetStore, MetaDataDefinition};
+    use geoengine_datatypes::collections::VectorDataType;
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_operators::engine::{
+        MetaDataProvider, StaticMetaData, VectorQueryRectangle, VectorResultDescriptor,
+    };
+    use geoengine_operators::mock::MockDatasetDataSourceLoadingInfo;
+
+    #[tokio::test]
+    async fn it_invalidates_the_cached_meta_data_of_removed_datasets() {
+        let ctx = InMemoryContext::default();
+        let session = ctx.default_session_ref().await.clone();
+
+        let id = {
+            let mut db = ctx.dataset_db_ref_mut().await;
+
+            let meta_data = db.wrap_meta_data(MetaDataDefinition::MockMetaData(StaticMetaData {
+                loading_info: MockDatasetDataSourceLoadingInfo {
+                    points: vec![(1.0, 2.0).into()],
+                },
+                result_descriptor: VectorResultDescriptor {
+                    data_type: VectorDataType::MultiPoint,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    columns: Default::default(),
+                },
+                phantom: Default::default(),
+            }));
+
+            let dataset = AddDataset {
+                id: None,
+                name: "Scratch Points".to_string(),
+                description: "Points for exploration".to_string(),
+                source_operator: "MockDatasetDataSource".to_string(),
+                symbology: None,
+                provenance: None,
+                tags: vec![],
+                temporal_extent: None,
+            };
+
+            db.add_scratch_dataset(
+                &session,
+                dataset.validated().unwrap(),
+                meta_data,
+                UploadId::new(),
+                Utc::now() - Duration::seconds(1),
+            )
+            .await
+            .unwrap()
+        };
+
+        let exe_ctx = ctx.execution_context(session).unwrap();
+
+        // caches the meta data
+        assert!(MetaDataProvider::<
+            MockDatasetDataSourceLoadingInfo,
+            VectorResultDescriptor,
+            VectorQueryRectangle,
+        >::meta_data(&exe_ctx, &id)
+        .await
+        .is_ok());
+
+        assert_eq!(remove_expired_scratch_datasets(&ctx).await.unwrap(), 1);
+
+        assert!(MetaDataProvider::<
+            MockDatasetDataSourceLoadingInfo,
+            VectorResultDescriptor,
+            VectorQueryRectangle,
+        >::meta_data(&exe_ctx, &id)
+        .await
+        .is_err());
+    }
+}
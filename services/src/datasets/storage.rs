@@ -1,5 +1,8 @@
 use crate::contexts::Session;
+use crate::datasets::alias::DatasetAliasDb;
 use crate::datasets::listing::{DatasetListing, DatasetProvider};
+use crate::datasets::scratch::ScratchDatasetDb;
+use crate::datasets::tags::DatasetTagDb;
 use crate::datasets::upload::UploadDb;
 use crate::datasets::upload::UploadId;
 use crate::error;
@@ -8,6 +11,7 @@ use crate::projects::Symbology;
 use crate::util::user_input::{UserInput, Validated};
 use async_trait::async_trait;
 use geoengine_datatypes::dataset::{DatasetId, DatasetProviderId};
+use geoengine_datatypes::primitives::TimeInterval;
 use geoengine_operators::engine::{MetaData, VectorQueryRectangle};
 use geoengine_operators::{engine::StaticMetaData, source::OgrSourceDataset};
 use geoengine_operators::{
@@ -31,6 +35,11 @@ pub struct Dataset {
     pub source_operator: String,
     pub symbology: Option<Symbology>,
     pub provenance: Option<Provenance>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// the time span the data covers, if it is known
+    #[serde(default)]
+    pub temporal_extent: Option<TimeInterval>,
 }
 
 impl Dataset {
@@ -39,12 +48,27 @@ impl Dataset {
             id: self.id.clone(),
             name: self.name.clone(),
             description: self.description.clone(),
-            tags: vec![], // TODO
+            tags: self.tags.clone(),
             source_operator: self.source_operator.clone(),
             result_descriptor: self.result_descriptor.clone(),
             symbology: self.symbology.clone(),
         }
     }
+
+    /// Create a full `Dataset` from its `listing` and `provenance`, e.g., for datasets of external providers
+    pub fn from_listing(listing: DatasetListing, provenance: Option<Provenance>) -> Self {
+        Self {
+            id: listing.id,
+            name: listing.name,
+            description: listing.description,
+            result_descriptor: listing.result_descriptor,
+            source_operator: listing.source_operator,
+            symbology: listing.symbology,
+            provenance,
+            tags: listing.tags,
+            temporal_extent: None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -56,6 +80,10 @@ pub struct AddDataset {
     pub source_operator: String,
     pub symbology: Option<Symbology>,
     pub provenance: Option<Provenance>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub temporal_extent: Option<TimeInterval>,
 }
 
 impl UserInput for AddDataset {
@@ -214,6 +242,9 @@ pub trait DatasetDb<S: Session>:
     DatasetStore<S>
     + DatasetProvider
     + DatasetProviderDb<S>
+    + DatasetAliasDb<S>
+    + DatasetTagDb<S>
+    + ScratchDatasetDb<S>
     + UploadDb<S>
     + ProvenanceProvider
     + Send
@@ -0,0 +1,347 @@
+use std::collections::BTreeMap;
+
+use crate::contexts::Session;
+use crate::datasets::listing::DatasetListing;
+use crate::error::{self, Result};
+use crate::ogc::util::parse_time_option;
+use crate::util::config::{get_config_element, DatasetService};
+use crate::util::user_input::{UserInput, Validated};
+use crate::workflows::workflow::WorkflowId;
+use async_trait::async_trait;
+use geoengine_datatypes::dataset::{DatasetId, DatasetProviderId};
+use geoengine_datatypes::primitives::TimeInterval;
+use geoengine_datatypes::spatial_reference::SpatialReferenceOption;
+use geoengine_operators::engine::TypedResultDescriptor;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+/// A set of keywords to add to or remove from a dataset or workflow
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Tags {
+    pub tags: Vec<String>,
+}
+
+impl UserInput for Tags {
+    fn validate(&self) -> Result<()> {
+        for tag in &self.tags {
+            ensure!(
+                !tag.is_empty() && tag.len() <= 64,
+                error::InvalidStringLength {
+                    parameter: "tag".to_string(),
+                    min: 1_usize,
+                    max: 64_usize
+                }
+            );
+
+            // commas separate the tags of a search
+            ensure!(!tag.contains(','), error::InvalidTag { tag: tag.clone() });
+        }
+
+        Ok(())
+    }
+}
+
+/// Adds the `new_tags` that are not yet in `tags`, keeping the order of insertion
+pub(crate) fn insert_tags(tags: &mut Vec<String>, new_tags: Vec<String>) {
+    for tag in new_tags {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+}
+
+pub(crate) fn remove_tags(tags: &mut Vec<String>, old_tags: &[String]) {
+    tags.retain(|tag| !old_tags.contains(tag));
+}
+
+/// Options of a faceted dataset search. All given facets must match.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetSearchOptions {
+    /// comma separated list of tags a dataset must all have
+    pub tags: Option<String>,
+    /// search the datasets of an external provider instead of the internal ones
+    pub provider: Option<DatasetProviderId>,
+    /// either `Raster`, `Plot` or a vector data type like `MultiPoint`
+    pub data_type: Option<String>,
+    pub spatial_reference: Option<SpatialReferenceOption>,
+    /// matches datasets whose temporal extent intersects the interval or is unknown
+    #[serde(default)]
+    #[serde(deserialize_with = "parse_time_option")]
+    pub time: Option<TimeInterval>,
+    pub offset: u32,
+    pub limit: u32,
+}
+
+impl DatasetSearchOptions {
+    fn tag_filter(&self) -> Vec<&str> {
+        self.tags
+            .as_deref()
+            .map(|tags| tags.split(',').filter(|tag| !tag.is_empty()).collect())
+            .unwrap_or_default()
+    }
+
+    fn matches(&self, dataset: &DatasetListing, temporal_extent: Option<TimeInterval>) -> bool {
+        let tags_match = self
+            .tag_filter()
+            .iter()
+            .all(|tag| dataset.tags.iter().any(|t| t == tag));
+
+        let data_type_matches = self.data_type.as_ref().map_or(true, |data_type| {
+            *data_type == data_type_label(&dataset.result_descriptor)
+        });
+
+        let spatial_reference_matches = self.spatial_reference.map_or(true, |spatial_reference| {
+            spatial_reference == descriptor_spatial_reference(&dataset.result_descriptor)
+        });
+
+        let time_matches = match (self.time, temporal_extent) {
+            (Some(time), Some(temporal_extent)) => time.intersects(&temporal_extent),
+            _ => true,
+        };
+
+        tags_match && data_type_matches && spatial_reference_matches && time_matches
+    }
+}
+
+impl UserInput for DatasetSearchOptions {
+    fn validate(&self) -> Result<()> {
+        let limit = get_config_element::<DatasetService>()?.list_limit;
+        ensure!(
+            self.limit <= limit,
+            error::InvalidListLimit {
+                limit: limit as usize
+            }
+        );
+
+        Ok(())
+    }
+}
+
+/// The number of matching datasets per facet value
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetSearchFacets {
+    pub tags: BTreeMap<String, usize>,
+    pub data_types: BTreeMap<String, usize>,
+    pub spatial_references: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetSearchResult {
+    /// the number of matching datasets
+    pub total: usize,
+    pub facets: DatasetSearchFacets,
+    /// the requested page of the matching datasets ordered by name
+    pub datasets: Vec<DatasetListing>,
+}
+
+/// Filters `datasets` and their temporal extents by the search `options` and counts the facets of the matches
+pub(crate) fn search_datasets<I>(datasets: I, options: &DatasetSearchOptions) -> DatasetSearchResult
+where
+    I: IntoIterator<Item = (DatasetListing, Option<TimeInterval>)>,
+{
+    let mut matches: Vec<DatasetListing> = datasets
+        .into_iter()
+        .filter(|(dataset, temporal_extent)| options.matches(dataset, *temporal_extent))
+        .map(|(dataset, _)| dataset)
+        .collect();
+    matches.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut facets = DatasetSearchFacets::default();
+    for dataset in &matches {
+        for tag in &dataset.tags {
+            *facets.tags.entry(tag.clone()).or_default() += 1;
+        }
+        *facets
+            .data_types
+            .entry(data_type_label(&dataset.result_descriptor))
+            .or_default() += 1;
+        *facets
+            .spatial_references
+            .entry(descriptor_spatial_reference(&dataset.result_descriptor).to_string())
+            .or_default() += 1;
+    }
+
+    DatasetSearchResult {
+        total: matches.len(),
+        facets,
+        datasets: matches
+            .into_iter()
+            .skip(options.offset as usize)
+            .take(options.limit as usize)
+            .collect(),
+    }
+}
+
+fn data_type_label(result_descriptor: &TypedResultDescriptor) -> String {
+    match result_descriptor {
+        TypedResultDescriptor::Plot(_) => "Plot".to_string(),
+        TypedResultDescriptor::Aggregate(_) => "Aggregate".to_string(),
+        TypedResultDescriptor::Raster(_) => "Raster".to_string(),
+        TypedResultDescriptor::Vector(descriptor) => format!("{:?}", descriptor.data_type),
+    }
+}
+
+fn descriptor_spatial_reference(
+    result_descriptor: &TypedResultDescriptor,
+) -> SpatialReferenceOption {
+    match result_descriptor {
+        TypedResultDescriptor::Plot(_) | TypedResultDescriptor::Aggregate(_) => {
+            SpatialReferenceOption::Unreferenced
+        }
+        TypedResultDescriptor::Raster(descriptor) => descriptor.spatial_reference,
+        TypedResultDescriptor::Vector(descriptor) => descriptor.spatial_reference,
+    }
+}
+
+/// Management of the tags of internal datasets and workflows and a faceted search over them
+#[async_trait]
+pub trait DatasetTagDb<S: Session> {
+    /// Adds `tags` to an internal dataset and returns all of its tags
+    async fn add_dataset_tags(
+        &mut self,
+        session: &S,
+        dataset: &DatasetId,
+        tags: Validated<Tags>,
+    ) -> Result<Vec<String>>;
+
+    /// Removes `tags` from an internal dataset and returns the remaining ones
+    async fn remove_dataset_tags(
+        &mut self,
+        session: &S,
+        dataset: &DatasetId,
+        tags: Validated<Tags>,
+    ) -> Result<Vec<String>>;
+
+    async fn workflow_tags(&self, session: &S, workflow: WorkflowId) -> Result<Vec<String>>;
+
+    /// Adds `tags` to a workflow and returns all of its tags
+    async fn add_workflow_tags(
+        &mut self,
+        session: &S,
+        workflow: WorkflowId,
+        tags: Validated<Tags>,
+    ) -> Result<Vec<String>>;
+
+    /// Removes `tags` from a workflow and returns the remaining ones
+    async fn remove_workflow_tags(
+        &mut self,
+        session: &S,
+        workflow: WorkflowId,
+        tags: Validated<Tags>,
+    ) -> Result<Vec<String>>;
+
+    async fn search_datasets(
+        &self,
+        session: &S,
+        options: Validated<DatasetSearchOptions>,
+    ) -> Result<DatasetSearchResult>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::collections::VectorDataType;
+    use geoengine_datatypes::dataset::InternalDatasetId;
+    use geoengine_datatypes::raster::RasterDataType;
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::Identifier;
+    use geoengine_operators::engine::{RasterResultDescriptor, VectorResultDescriptor};
+
+    fn listing(
+        name: &str,
+        tags: &[&str],
+        result_descriptor: TypedResultDescriptor,
+    ) -> DatasetListing {
+        DatasetListing {
+            id: InternalDatasetId::new().into(),
+            name: name.to_string(),
+            description: String::new(),
+            tags: tags.iter().map(ToString::to_string).collect(),
+            source_operator: String::new(),
+            result_descriptor,
+            symbology: None,
+        }
+    }
+
+    #[test]
+    fn it_searches_facets() {
+        let raster = RasterResultDescriptor {
+            data_type: RasterDataType::U8,
+            spatial_reference: SpatialReference::epsg_4326().into(),
+            measurement: Default::default(),
+            no_data_value: None,
+            bands: Vec::new(),
+        };
+        let points = VectorResultDescriptor {
+            data_type: VectorDataType::MultiPoint,
+            spatial_reference: SpatialReference::epsg_4326().into(),
+            columns: Default::default(),
+        };
+
+        let datasets = vec![
+            (
+                listing("NDVI", &["vegetation", "modis"], raster.clone().into()),
+                Some(TimeInterval::new_unchecked(0, 10)),
+            ),
+            (
+                listing("LST", &["modis"], raster.into()),
+                Some(TimeInterval::new_unchecked(20, 30)),
+            ),
+            (listing("Ports", &["infrastructure"], points.into()), None),
+        ];
+
+        let result = search_datasets(
+            datasets.clone(),
+            &DatasetSearchOptions {
+                tags: Some("modis".to_string()),
+                provider: None,
+                data_type: Some("Raster".to_string()),
+                spatial_reference: None,
+                time: Some(TimeInterval::new_unchecked(5, 15)),
+                offset: 0,
+                limit: 10,
+            },
+        );
+
+        assert_eq!(result.total, 1);
+        assert_eq!(result.datasets[0].name, "NDVI");
+        assert_eq!(
+            result.facets.tags,
+            [("modis".to_string(), 1), ("vegetation".to_string(), 1)]
+                .iter()
+                .cloned()
+                .collect()
+        );
+
+        let result = search_datasets(
+            datasets,
+            &DatasetSearchOptions {
+                tags: None,
+                provider: None,
+                data_type: None,
+                spatial_reference: Some(SpatialReference::epsg_4326().into()),
+                time: None,
+                offset: 1,
+                limit: 1,
+            },
+        );
+
+        assert_eq!(result.total, 3);
+        assert_eq!(result.datasets.len(), 1);
+        assert_eq!(result.datasets[0].name, "NDVI");
+        assert_eq!(
+            result.facets.data_types,
+            [("MultiPoint".to_string(), 1), ("Raster".to_string(), 2)]
+                .iter()
+                .cloned()
+                .collect()
+        );
+        assert_eq!(
+            result.facets.spatial_references,
+            [("EPSG:4326".to_string(), 3)].iter().cloned().collect()
+        );
+    }
+}
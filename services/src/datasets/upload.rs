@@ -9,6 +9,7 @@ use crate::{
 use async_trait::async_trait;
 use geoengine_datatypes::identifier;
 use serde::{Deserialize, Serialize};
+use snafu::ensure;
 
 identifier!(UploadId);
 identifier!(FileId);
@@ -52,9 +53,39 @@ pub struct UploadListing {
     pub num_files: usize,
 }
 
+/// The storage that the uploads of a user occupy and the quota that limits it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UploadQuota {
+    pub used_bytes: u64,
+    pub quota_bytes: u64,
+}
+
+impl UploadQuota {
+    /// Fails if storing `requested_bytes` more would exceed the quota
+    pub fn check(&self, requested_bytes: u64) -> Result<()> {
+        ensure!(
+            self.used_bytes.saturating_add(requested_bytes) <= self.quota_bytes,
+            error::StorageQuotaExceeded {
+                requested_bytes,
+                used_bytes: self.used_bytes,
+                quota_bytes: self.quota_bytes,
+            }
+        );
+
+        Ok(())
+    }
+}
+
 #[async_trait]
 pub trait UploadDb<S: Session> {
     async fn get_upload(&self, session: &S, upload: UploadId) -> Result<Upload>;
 
     async fn create_upload(&mut self, session: &S, upload: Upload) -> Result<()>;
+
+    /// The quota of the user of the `session` or `None` if its uploads are unlimited.
+    /// Uploads are checked against it while they are received,
+    /// s.t. they are aborted as soon as they exceed it.
+    async fn upload_quota(&self, _session: &S) -> Result<Option<UploadQuota>> {
+        Ok(None)
+    }
 }
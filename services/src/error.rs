@@ -1,4 +1,9 @@
-use geoengine_datatypes::{dataset::DatasetProviderId, spatial_reference::SpatialReferenceOption};
+use crate::workflows::workflow::WorkflowId;
+use geoengine_datatypes::{
+    dataset::DatasetProviderId,
+    spatial_reference::{SpatialReference, SpatialReferenceOption},
+};
+use geoengine_operators::engine::SchemaViolation;
 use snafu::Snafu;
 use strum::IntoStaticStr;
 use warp::reject::Reject;
@@ -25,6 +30,9 @@ pub enum Error {
     Io {
         source: std::io::Error,
     },
+    Csv {
+        source: csv::Error,
+    },
     TokioJoin {
         source: tokio::task::JoinError,
     },
@@ -83,6 +91,13 @@ pub enum Error {
     ProjectUpdateFailed,
     #[snafu(display("Failed to delete the project."))]
     ProjectDeleteFailed,
+    #[snafu(display(
+        "The playback interval of the project must not contain more than {} frames",
+        limit
+    ))]
+    TooManyPlaybackFrames {
+        limit: u32,
+    },
     PermissionFailed,
     ProjectDbUnauthorized,
 
@@ -129,7 +144,148 @@ pub enum Error {
     DatasetIdTypeMissMatch,
     UnknownDatasetId,
     UnknownProviderId,
+    #[snafu(display("Unknown dataset alias: {}", alias))]
+    UnknownDatasetAlias {
+        alias: String,
+    },
+    #[snafu(display("Dataset alias must only contain alphanumerics and `:_-.`: {}", alias))]
+    InvalidDatasetAlias {
+        alias: String,
+    },
     MissingDatasetId,
+    #[snafu(display("Tag must not contain commas: {}", tag))]
+    InvalidTag {
+        tag: String,
+    },
+
+    #[snafu(display(
+        "The time to live of a scratch dataset must be between 1 and {} seconds",
+        max_ttl
+    ))]
+    InvalidScratchDatasetTtl {
+        max_ttl: u32,
+    },
+
+    #[snafu(display("The cleanup interval of scratch datasets must be greater than zero"))]
+    InvalidScratchDatasetCleanupInterval,
+
+    #[snafu(display(
+        "Storing {} bytes exceeds the storage quota: {} of {} bytes are used",
+        requested_bytes,
+        used_bytes,
+        quota_bytes
+    ))]
+    StorageQuotaExceeded {
+        requested_bytes: u64,
+        used_bytes: u64,
+        quota_bytes: u64,
+    },
+    #[snafu(display("Only admins may perform this operation"))]
+    OperationRequiresAdminPrivilege,
+
+    UnknownLayerId,
+    UnknownLayerCollectionId,
+    #[snafu(display("A layer collection must not contain itself"))]
+    InvalidLayerCollectionHierarchy,
+
+    UnknownAoiId,
+    #[snafu(display("A plot query needs a bbox, a polygon or an area of interest"))]
+    MissingPlotQueryBounds,
+    #[snafu(display("A plot query must not have both a polygon and an area of interest"))]
+    AmbiguousPlotQueryRegion,
+    #[snafu(display("A {} plot cannot be output as {}", plot_type, format))]
+    UnsupportedPlotFormat {
+        plot_type: &'static str,
+        format: String,
+    },
+    #[snafu(display("A plot image must be between 50 and 4096 pixels wide and high"))]
+    InvalidPlotImageSize,
+    #[snafu(display("The {} of a WMS request must match its {} layers", parameter, layers))]
+    WmsLayerParameterMismatch {
+        parameter: String,
+        layers: usize,
+    },
+
+    #[snafu(display("There is no basemap configured"))]
+    BasemapNotConfigured,
+    #[snafu(display("The basemap tile {}/{}/{} does not exist", z, x, y))]
+    InvalidBasemapTile {
+        z: u8,
+        x: u32,
+        y: u32,
+    },
+    #[snafu(display(
+        "The basemap is only available in EPSG:3857, not in {}",
+        spatial_reference
+    ))]
+    BasemapUnsupportedSpatialReference {
+        spatial_reference: SpatialReference,
+    },
+    #[snafu(display("Loading the basemap failed: {}", details))]
+    BasemapLoading {
+        details: String,
+    },
+
+    #[snafu(display("The migrated workflow is invalid: {}", source))]
+    InvalidMigratedWorkflow {
+        source: serde_json::Error,
+    },
+
+    #[snafu(display(
+        "The workflow is invalid: {}",
+        violations
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ")
+    ))]
+    InvalidWorkflow {
+        violations: Vec<SchemaViolation>,
+    },
+
+    #[snafu(display("The workflow {} is not archived", workflow))]
+    WorkflowNotArchived {
+        workflow: WorkflowId,
+    },
+    #[snafu(display("Accessing the tile archive failed: {}", details))]
+    TileArchive {
+        details: String,
+    },
+
+    #[snafu(display("Creating the source archive failed: {}", source))]
+    SourceArchive {
+        source: zip::result::ZipError,
+    },
+    #[snafu(display("The source files exceed the archive limit of {} bytes", limit))]
+    SourceArchiveTooLarge {
+        limit: u64,
+    },
+    #[snafu(display("A batch query must not contain more than {} queries", limit))]
+    TooManyBatchQueries {
+        limit: usize,
+    },
+    #[snafu(display(
+        "The server is overloaded, retry after {} seconds",
+        retry_after.as_secs()
+    ))]
+    Overloaded {
+        retry_after: std::time::Duration,
+    },
+    #[snafu(display(
+        "The query was aborted because it exceeded the timeout of {} seconds",
+        timeout.as_secs_f64()
+    ))]
+    QueryTimeout {
+        timeout: std::time::Duration,
+    },
+
+    #[snafu(display("Invalid report: {}", reason))]
+    InvalidReport {
+        reason: String,
+    },
+
+    #[snafu(display("A gap report for an unreferenced dataset needs explicit zones"))]
+    GapReportMissingZones,
 
     #[snafu(display("Parameter {} must have length between {} and {}", parameter, min, max))]
     InvalidStringLength {
@@ -150,6 +306,18 @@ pub enum Error {
     InvalidUploadFileName,
     InvalidDatasetName,
     DatasetHasNoAutoImportableLayer,
+    #[snafu(display("The GDAL driver {} is not allowed", driver))]
+    GdalDriverNotAllowed {
+        driver: String,
+    },
+    #[snafu(display("GDAL cannot open the file {}", file))]
+    GdalCannotOpenFile {
+        file: String,
+    },
+    #[snafu(display("The virtual file system {} is not allowed", file_system))]
+    VirtualFileSystemNotAllowed {
+        file_system: String,
+    },
     #[snafu(display("GdalError: {}", source))]
     Gdal {
         source: gdal::errors::GdalError,
@@ -0,0 +1,63 @@
+use image::ImageFormat;
+use snafu::ResultExt;
+use warp::http::Response;
+use warp::Filter;
+
+use crate::basemap::Basemap;
+use crate::error;
+
+/// Proxies a tile of the configured basemap.
+/// The tiles are cached, so they are available even if the tile service is not reachable later on.
+///
+/// # Example
+///
+/// ```text
+/// GET /basemap/3/4/2
+/// ```
+/// Response:
+/// PNG image
+pub(crate) fn basemap_tile_handler(
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("basemap" / u8 / u32 / u32)
+        .and(warp::get())
+        .and_then(basemap_tile)
+}
+
+// TODO: move into handler once async closures are available?
+async fn basemap_tile(z: u8, x: u32, y: u32) -> Result<impl warp::Reply, warp::Rejection> {
+    let basemap = Basemap::from_config()?.ok_or(error::Error::BasemapNotConfigured)?;
+
+    let bytes = basemap.tile(z, x, y).await?;
+
+    let content_type = match image::guess_format(&bytes) {
+        Ok(ImageFormat::Jpeg) => "image/jpeg",
+        _ => "image/png",
+    };
+
+    Ok(Response::builder()
+        .header("Content-Type", content_type)
+        .body(bytes)
+        .context(error::Http)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::{handle_rejection, ErrorResponse};
+
+    #[tokio::test]
+    async fn it_requires_a_configured_basemap() {
+        let res = warp::test::request()
+            .method("GET")
+            .path("/basemap/3/4/2")
+            .reply(&basemap_tile_handler().recover(handle_rejection))
+            .await;
+
+        ErrorResponse::assert(
+            &res,
+            400,
+            "BasemapNotConfigured",
+            "There is no basemap configured",
+        );
+    }
+}
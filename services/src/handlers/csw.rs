@@ -0,0 +1,521 @@
+use snafu::ResultExt;
+use uuid::Uuid;
+use warp::{http::Response, Filter, Rejection};
+
+use geoengine_datatypes::dataset::{DatasetId, InternalDatasetId};
+
+use crate::datasets::listing::{DatasetListOptions, DatasetProvider, OrderBy};
+use crate::datasets::metadata::{dataset_url, escape_xml, iso19115_record, record_identifier};
+use crate::datasets::storage::Dataset;
+use crate::error;
+use crate::error::{Error, Result};
+use crate::handlers::Context;
+use crate::ogc::csw::request::{
+    CswRequest, ElementSetName, GetCapabilities, GetRecordById, GetRecords, OutputSchema,
+    ResultType,
+};
+use crate::util::config::{get_config_element, DatasetService, Web};
+use crate::util::user_input::Validated;
+
+pub(crate) fn csw_handler<C: Context>(
+    ctx: C,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("csw")
+        .and(warp::get())
+        .and(
+            warp::query::raw().and_then(|query_string: String| async move {
+                // TODO: make case insensitive by using serde-aux instead
+                let query_string = query_string.replace("REQUEST", "request");
+
+                serde_urlencoded::from_str::<CswRequest>(&query_string)
+                    .context(error::UnableToParseQueryString)
+                    .map_err(Rejection::from)
+            }),
+        )
+        .and(warp::any().map(move || ctx.clone()))
+        .and_then(csw)
+}
+
+// TODO: move into handler once async closures are available?
+async fn csw<C: Context>(
+    request: CswRequest,
+    ctx: C,
+) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+    // TODO: authentication
+    // TODO: expose the workflows of the layer catalog as service records
+    match request {
+        CswRequest::GetCapabilities(request) => get_capabilities(&request),
+        CswRequest::GetRecords(request) => get_records(&request, &ctx).await,
+        CswRequest::GetRecordById(request) => get_record_by_id(&request, &ctx).await,
+    }
+}
+
+fn csw_url() -> Result<String> {
+    let base = get_config_element::<Web>()?
+        .external_address
+        .ok_or(Error::ExternalAddressNotConfigured)?;
+
+    Ok(format!("{}/csw", base))
+}
+
+/// Gets details about the catalogue service and lists available operations.
+///
+/// # Example
+///
+/// ```text
+/// GET /csw?service=CSW&request=GetCapabilities&acceptVersions=2.0.2
+/// ```
+/// Response:
+/// ```text
+/// <?xml version="1.0" encoding="UTF-8"?>
+/// <csw:Capabilities version="2.0.2" ...>
+///   <ows:ServiceIdentification>
+///     <ows:Title>Geo Engine Catalogue Service</ows:Title>
+///     <ows:ServiceType>CSW</ows:ServiceType>
+///     <ows:ServiceTypeVersion>2.0.2</ows:ServiceTypeVersion>
+///   </ows:ServiceIdentification>
+///   ...
+/// </csw:Capabilities>
+/// ```
+fn get_capabilities(_request: &GetCapabilities) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+    // TODO: inspect request parameters
+
+    let csw_url = csw_url()?;
+    let capabilities = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<csw:Capabilities version="2.0.2"
+        xmlns:csw="http://www.opengis.net/cat/csw/2.0.2"
+        xmlns:ows="http://www.opengis.net/ows"
+        xmlns:xlink="http://www.w3.org/1999/xlink"
+        xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://www.opengis.net/cat/csw/2.0.2 http://schemas.opengis.net/csw/2.0.2/CSW-discovery.xsd">
+    <ows:ServiceIdentification>
+        <ows:Title>Geo Engine Catalogue Service</ows:Title>
+        <ows:ServiceType>CSW</ows:ServiceType>
+        <ows:ServiceTypeVersion>2.0.2</ows:ServiceTypeVersion>
+        <ows:Fees>NONE</ows:Fees>
+        <ows:AccessConstraints>NONE</ows:AccessConstraints>
+    </ows:ServiceIdentification>
+    <ows:ServiceProvider>
+        <ows:ProviderName>Provider Name</ows:ProviderName>
+    </ows:ServiceProvider>
+    <ows:OperationsMetadata>
+        <ows:Operation name="GetCapabilities">
+            <ows:DCP>
+                <ows:HTTP>
+                    <ows:Get xlink:href="{csw_url}?"/>
+                </ows:HTTP>
+            </ows:DCP>
+        </ows:Operation>
+        <ows:Operation name="GetRecords">
+            <ows:DCP>
+                <ows:HTTP>
+                    <ows:Get xlink:href="{csw_url}?"/>
+                </ows:HTTP>
+            </ows:DCP>
+            <ows:Parameter name="typeNames">
+                <ows:Value>csw:Record</ows:Value>
+            </ows:Parameter>
+            <ows:Parameter name="outputSchema">
+                <ows:Value>http://www.opengis.net/cat/csw/2.0.2</ows:Value>
+                <ows:Value>http://www.isotc211.org/2005/gmd</ows:Value>
+            </ows:Parameter>
+            <ows:Parameter name="resultType">
+                <ows:Value>hits</ows:Value>
+                <ows:Value>results</ows:Value>
+            </ows:Parameter>
+            <ows:Parameter name="ElementSetName">
+                <ows:Value>brief</ows:Value>
+                <ows:Value>summary</ows:Value>
+                <ows:Value>full</ows:Value>
+            </ows:Parameter>
+        </ows:Operation>
+        <ows:Operation name="GetRecordById">
+            <ows:DCP>
+                <ows:HTTP>
+                    <ows:Get xlink:href="{csw_url}?"/>
+                </ows:HTTP>
+            </ows:DCP>
+            <ows:Parameter name="outputSchema">
+                <ows:Value>http://www.opengis.net/cat/csw/2.0.2</ows:Value>
+                <ows:Value>http://www.isotc211.org/2005/gmd</ows:Value>
+            </ows:Parameter>
+        </ows:Operation>
+        <ows:Parameter name="service">
+            <ows:Value>CSW</ows:Value>
+        </ows:Parameter>
+        <ows:Parameter name="version">
+            <ows:Value>2.0.2</ows:Value>
+        </ows:Parameter>
+    </ows:OperationsMetadata>
+</csw:Capabilities>"#,
+        csw_url = csw_url
+    );
+
+    Ok(Box::new(
+        Response::builder()
+            .header("Content-Type", "application/xml")
+            .body(capabilities)
+            .context(error::Http)?,
+    ))
+}
+
+/// Pages through the metadata records of the internal datasets, ordered by their titles.
+///
+/// # Example
+///
+/// ```text
+/// GET /csw?service=CSW&version=2.0.2&request=GetRecords&typeNames=csw:Record&elementSetName=brief&startPosition=1&maxRecords=10
+/// ```
+/// Response:
+/// ```text
+/// <?xml version="1.0" encoding="UTF-8"?>
+/// <csw:GetRecordsResponse version="2.0.2" ...>
+///   <csw:SearchStatus timestamp="2021-12-01T12:00:00+00:00"/>
+///   <csw:SearchResults numberOfRecordsMatched="1" numberOfRecordsReturned="1" nextRecord="0" elementSet="brief" recordSchema="http://www.opengis.net/cat/csw/2.0.2">
+///     <csw:BriefRecord>
+///       <dc:identifier>9c874b9e-cea0-4553-b727-a13cb26ae4bb</dc:identifier>
+///       <dc:title>Germany</dc:title>
+///       <dc:type>dataset</dc:type>
+///     </csw:BriefRecord>
+///   </csw:SearchResults>
+/// </csw:GetRecordsResponse>
+/// ```
+async fn get_records<C: Context>(
+    request: &GetRecords,
+    ctx: &C,
+) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+    let list_limit = get_config_element::<DatasetService>()?.list_limit;
+    let start_position = request.startposition.unwrap_or(1).max(1) as usize;
+    let max_records = request.maxrecords.unwrap_or(10).min(list_limit) as usize;
+    let element_set = request.elementsetname.unwrap_or_default();
+    let output_schema = request.outputschema.unwrap_or_default();
+
+    let db = ctx.dataset_db_ref().await;
+    let dataset_ids: Vec<InternalDatasetId> = db
+        .list(Validated {
+            user_input: DatasetListOptions {
+                filter: None,
+                order: OrderBy::NameAsc,
+                offset: 0,
+                limit: u32::MAX,
+            },
+        })
+        .await?
+        .iter()
+        .filter_map(|dataset| dataset.id.internal())
+        .collect();
+
+    let mut records = Vec::new();
+    if request.resulttype.unwrap_or_default() == ResultType::Results {
+        for dataset_id in dataset_ids
+            .iter()
+            .skip(start_position - 1)
+            .take(max_records)
+        {
+            let dataset = db
+                .load(&DatasetId::Internal {
+                    dataset_id: *dataset_id,
+                })
+                .await?;
+            records.push(record(&dataset, *dataset_id, element_set, output_schema)?);
+        }
+    }
+
+    let next_record = start_position + records.len();
+    let next_record = if next_record > dataset_ids.len() {
+        0
+    } else {
+        next_record
+    };
+
+    let response = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<csw:GetRecordsResponse version="2.0.2" {namespaces}>
+    <csw:SearchStatus timestamp="{timestamp}"/>
+    <csw:SearchResults numberOfRecordsMatched="{matched}" numberOfRecordsReturned="{returned}" nextRecord="{next_record}" elementSet="{element_set}" recordSchema="{record_schema}">{records}
+    </csw:SearchResults>
+</csw:GetRecordsResponse>"#,
+        namespaces = NAMESPACES,
+        timestamp = chrono::Utc::now().to_rfc3339(),
+        matched = dataset_ids.len(),
+        returned = records.len(),
+        next_record = next_record,
+        element_set = element_set_name(element_set),
+        record_schema = record_schema(output_schema),
+        records = records.concat(),
+    );
+
+    Ok(Box::new(
+        Response::builder()
+            .header("Content-Type", "application/xml")
+            .body(response)
+            .context(error::Http)?,
+    ))
+}
+
+/// Retrieves the metadata records of internal datasets by their comma separated ids.
+/// Unknown ids are skipped.
+///
+/// # Example
+///
+/// ```text
+/// GET /csw?service=CSW&version=2.0.2&request=GetRecordById&id=9c874b9e-cea0-4553-b727-a13cb26ae4bb&outputSchema=http://www.isotc211.org/2005/gmd
+/// ```
+/// Response:
+/// ```text
+/// <?xml version="1.0" encoding="UTF-8"?>
+/// <csw:GetRecordByIdResponse ...>
+///   <gmd:MD_Metadata ...>
+///     ...
+///   </gmd:MD_Metadata>
+/// </csw:GetRecordByIdResponse>
+/// ```
+async fn get_record_by_id<C: Context>(
+    request: &GetRecordById,
+    ctx: &C,
+) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+    let element_set = request.elementsetname.unwrap_or_default();
+    let output_schema = request.outputschema.unwrap_or_default();
+
+    let db = ctx.dataset_db_ref().await;
+
+    let mut records = Vec::new();
+    for id in request.id.split(',') {
+        let dataset_id = match Uuid::parse_str(id.trim()) {
+            Ok(id) => InternalDatasetId(id),
+            Err(_) => continue,
+        };
+
+        if let Ok(dataset) = db.load(&DatasetId::Internal { dataset_id }).await {
+            records.push(record(&dataset, dataset_id, element_set, output_schema)?);
+        }
+    }
+
+    let response = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<csw:GetRecordByIdResponse {namespaces}>{records}
+</csw:GetRecordByIdResponse>"#,
+        namespaces = NAMESPACES,
+        records = records.concat(),
+    );
+
+    Ok(Box::new(
+        Response::builder()
+            .header("Content-Type", "application/xml")
+            .body(response)
+            .context(error::Http)?,
+    ))
+}
+
+const NAMESPACES: &str = r#"xmlns:csw="http://www.opengis.net/cat/csw/2.0.2" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dct="http://purl.org/dc/terms/" xmlns:ows="http://www.opengis.net/ows" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://www.opengis.net/cat/csw/2.0.2 http://schemas.opengis.net/csw/2.0.2/CSW-discovery.xsd""#;
+
+fn element_set_name(element_set: ElementSetName) -> &'static str {
+    match element_set {
+        ElementSetName::Brief => "brief",
+        ElementSetName::Summary => "summary",
+        ElementSetName::Full => "full",
+    }
+}
+
+fn record_schema(output_schema: OutputSchema) -> &'static str {
+    match output_schema {
+        OutputSchema::Csw => "http://www.opengis.net/cat/csw/2.0.2",
+        OutputSchema::Iso => "http://www.isotc211.org/2005/gmd",
+    }
+}
+
+/// Creates the record of a `dataset` in the requested schema.
+/// ISO records are always complete, so the element set only applies to Dublin Core records.
+fn record(
+    dataset: &Dataset,
+    dataset_id: InternalDatasetId,
+    element_set: ElementSetName,
+    output_schema: OutputSchema,
+) -> Result<String> {
+    let url = dataset_url(dataset_id)?;
+
+    Ok(match output_schema {
+        OutputSchema::Iso => format!("\n{}", iso19115_record(dataset, &url)),
+        OutputSchema::Csw => dublin_core_record(dataset, &url, element_set),
+    })
+}
+
+fn dublin_core_record(dataset: &Dataset, url: &str, element_set: ElementSetName) -> String {
+    let mut elements = vec![
+        format!(
+            "<dc:identifier>{}</dc:identifier>",
+            escape_xml(&record_identifier(&dataset.id))
+        ),
+        format!("<dc:title>{}</dc:title>", escape_xml(&dataset.name)),
+        "<dc:type>dataset</dc:type>".to_string(),
+    ];
+
+    if element_set != ElementSetName::Brief {
+        elements.extend(
+            dataset
+                .tags
+                .iter()
+                .map(|tag| format!("<dc:subject>{}</dc:subject>", escape_xml(tag))),
+        );
+        elements.push(format!(
+            "<dct:abstract>{}</dct:abstract>",
+            escape_xml(&dataset.description)
+        ));
+    }
+
+    if element_set == ElementSetName::Full {
+        if let Some(provenance) = &dataset.provenance {
+            elements.push(format!(
+                "<dc:rights>{}</dc:rights>",
+                escape_xml(&provenance.license)
+            ));
+            elements.push(format!(
+                "<dct:bibliographicCitation>{}</dct:bibliographicCitation>",
+                escape_xml(&provenance.citation)
+            ));
+            elements.push(format!(
+                "<dc:source>{}</dc:source>",
+                escape_xml(&provenance.uri)
+            ));
+        }
+        if let Some(time) = dataset.temporal_extent {
+            elements.push(format!(
+                "<dct:temporal>{}/{}</dct:temporal>",
+                time.start().as_rfc3339(),
+                time.end().as_rfc3339()
+            ));
+        }
+        elements.push(format!(
+            "<dct:references>{}</dct:references>",
+            escape_xml(url)
+        ));
+    }
+
+    let tag = match element_set {
+        ElementSetName::Brief => "csw:BriefRecord",
+        ElementSetName::Summary => "csw:SummaryRecord",
+        ElementSetName::Full => "csw:Record",
+    };
+
+    format!(
+        "\n        <{tag}>\n            {elements}\n        </{tag}>",
+        tag = tag,
+        elements = elements.join("\n            ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contexts::InMemoryContext;
+    use crate::util::tests::add_ndvi_to_datasets;
+    use xml::ParserConfig;
+
+    #[tokio::test]
+    async fn get_capabilities() {
+        let ctx = InMemoryContext::default();
+
+        let params = &[
+            ("service", "CSW"),
+            ("request", "GetCapabilities"),
+            ("acceptVersions", "2.0.2"),
+        ];
+
+        let res = warp::test::request()
+            .method("GET")
+            .path(&format!(
+                "/csw?{}",
+                serde_urlencoded::to_string(params).unwrap()
+            ))
+            .reply(&csw_handler(ctx))
+            .await;
+
+        assert_eq!(res.status(), 200);
+
+        let body = std::str::from_utf8(res.body()).unwrap();
+        assert!(body.contains(r#"<ows:Get xlink:href="http://localhost:3030/csw?"/>"#));
+
+        // TODO: validate against schema
+        for event in ParserConfig::default().create_reader(res.body().as_ref()) {
+            assert!(event.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn get_records() {
+        let ctx = InMemoryContext::default();
+
+        let id = add_ndvi_to_datasets(&ctx).await;
+
+        let params = &[
+            ("service", "CSW"),
+            ("request", "GetRecords"),
+            ("version", "2.0.2"),
+            ("typeNames", "csw:Record"),
+            ("elementSetName", "full"),
+            ("startPosition", "1"),
+            ("maxRecords", "10"),
+        ];
+
+        let res = warp::test::request()
+            .method("GET")
+            .path(&format!(
+                "/csw?{}",
+                serde_urlencoded::to_string(params).unwrap()
+            ))
+            .reply(&csw_handler(ctx))
+            .await;
+
+        assert_eq!(res.status(), 200, "{:?}", res.body());
+
+        let body = std::str::from_utf8(res.body()).unwrap();
+        assert!(body.contains(
+            r#"numberOfRecordsMatched="1" numberOfRecordsReturned="1" nextRecord="0" elementSet="full""#
+        ));
+        assert!(body.contains(&format!(
+            "<dc:identifier>{}</dc:identifier>",
+            id.internal().unwrap()
+        )));
+        assert!(body.contains("<dc:title>NDVI</dc:title>"));
+        assert!(body.contains("<dc:rights>Sample License</dc:rights>"));
+
+        for event in ParserConfig::default().create_reader(res.body().as_ref()) {
+            assert!(event.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn get_record_by_id() {
+        let ctx = InMemoryContext::default();
+
+        let id = add_ndvi_to_datasets(&ctx).await;
+        let ids = format!("{},{}", id.internal().unwrap(), Uuid::new_v4());
+
+        let params = &[
+            ("service", "CSW"),
+            ("request", "GetRecordById"),
+            ("version", "2.0.2"),
+            ("id", ids.as_str()),
+            ("outputSchema", "http://www.isotc211.org/2005/gmd"),
+        ];
+
+        let res = warp::test::request()
+            .method("GET")
+            .path(&format!(
+                "/csw?{}",
+                serde_urlencoded::to_string(params).unwrap()
+            ))
+            .reply(&csw_handler(ctx))
+            .await;
+
+        assert_eq!(res.status(), 200, "{:?}", res.body());
+
+        let body = std::str::from_utf8(res.body()).unwrap();
+        assert_eq!(body.matches("<gmd:MD_Metadata").count(), 1);
+        assert!(body.contains("<gco:CharacterString>NDVI</gco:CharacterString>"));
+
+        for event in ParserConfig::default().create_reader(res.body().as_ref()) {
+            assert!(event.is_ok());
+        }
+    }
+}
@@ -7,16 +7,26 @@ use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
 use std::error::Error as StdError;
 use std::str::FromStr;
-use warp::http::{Response, StatusCode};
+use warp::http::header::RETRY_AFTER;
+use warp::http::{HeaderValue, Response, StatusCode};
 use warp::hyper::body::Bytes;
 use warp::reject::{InvalidQuery, MethodNotAllowed, UnsupportedMediaType};
 use warp::{Filter, Rejection, Reply};
 
+pub mod aggregates;
+pub mod aois;
+pub mod basemap;
+pub mod csw;
 pub mod datasets;
+pub mod layers;
+pub mod operators;
 pub mod plots;
 pub mod projects;
+pub mod reports;
 pub mod session;
 pub mod spatial_references;
+pub mod tables;
+pub mod tile_archive;
 pub mod upload;
 pub mod wcs;
 pub mod wfs;
@@ -54,6 +64,8 @@ impl ErrorResponse {
 pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Rejection> {
     error!("Warp rejection: {:?}", err);
 
+    let mut retry_after = None;
+
     let (code, error, message) = if let Some(e) = err.find::<Error>() {
         // custom errors
 
@@ -69,6 +81,21 @@ pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Rejection> {
                 Into::<&str>::into(e).to_string(),
                 e.to_string(),
             ),
+            error::Error::Overloaded {
+                retry_after: duration,
+            } => {
+                retry_after = Some(duration.as_secs());
+                (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    Into::<&str>::into(e).to_string(),
+                    e.to_string(),
+                )
+            }
+            error::Error::QueryTimeout { .. } => (
+                StatusCode::GATEWAY_TIMEOUT,
+                Into::<&str>::into(e).to_string(),
+                e.to_string(),
+            ),
             _ => (
                 StatusCode::BAD_REQUEST,
                 Into::<&str>::into(e).to_string(),
@@ -111,7 +138,15 @@ pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Rejection> {
     };
 
     let json = warp::reply::json(&ErrorResponse { error, message });
-    Ok(warp::reply::with_status(json, code))
+    let mut response = warp::reply::with_status(json, code).into_response();
+
+    if let Some(seconds) = retry_after {
+        response
+            .headers_mut()
+            .insert(RETRY_AFTER, HeaderValue::from(seconds));
+    }
+
+    Ok(response)
 }
 
 pub fn authenticate<C: Context>(
@@ -147,3 +182,35 @@ pub fn authenticate<C: Context>(
         .and(warp::header::optional::<String>("authorization"))
         .and_then(do_authenticate)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn overloaded_responses_have_retry_after() {
+        let response = handle_rejection(warp::reject::custom(Error::Overloaded {
+            retry_after: Duration::from_secs(7),
+        }))
+        .await
+        .unwrap()
+        .into_response();
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers().get(RETRY_AFTER).unwrap(), "7");
+    }
+
+    #[tokio::test]
+    async fn query_timeouts_are_gateway_timeouts() {
+        let response = handle_rejection(warp::reject::custom(Error::QueryTimeout {
+            timeout: Duration::from_secs(30),
+        }))
+        .await
+        .unwrap()
+        .into_response();
+
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+        assert!(response.headers().get(RETRY_AFTER).is_none());
+    }
+}
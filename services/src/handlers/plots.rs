@@ -1,33 +1,81 @@
 use serde::{Deserialize, Serialize};
-use snafu::ResultExt;
+use snafu::{ensure, ResultExt};
 use uuid::Uuid;
+use warp::http::Response;
 use warp::Filter;
 
-use geoengine_datatypes::plots::PlotOutputFormat;
-use geoengine_datatypes::primitives::{BoundingBox2D, SpatialResolution, TimeInterval};
-use geoengine_operators::engine::{TypedPlotQueryProcessor, VectorQueryRectangle};
-
-use crate::contexts::Context;
+use geoengine_datatypes::plots::{PlotData, PlotOutputFormat};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, MultiPolygon, SpatialResolution, TimeInterval,
+};
+use geoengine_operators::engine::{
+    QueryContext, QueryPriority, QueryRegion, TypedPlotQueryProcessor, VectorQueryRectangle,
+};
+
+use crate::aois::aoi::AoiId;
+use crate::aois::storage::AoiDb;
+use crate::contexts::{Context, Session};
 use crate::error;
 use crate::handlers::authenticate;
-use crate::ogc::util::{parse_bbox, parse_time};
+use crate::ogc::util::{parse_bbox_option, parse_time};
+use crate::util::admission_control::query_cost;
 use crate::util::parsing::parse_spatial_resolution;
+use crate::util::query_timeout::{QueryTimeout, TimedHandler};
 use crate::workflows::registry::WorkflowRegistry;
 use crate::workflows::workflow::WorkflowId;
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct GetPlot {
-    #[serde(deserialize_with = "parse_bbox")]
-    pub bbox: BoundingBox2D,
+    #[serde(default)]
+    #[serde(deserialize_with = "parse_bbox_option")]
+    pub bbox: Option<BoundingBox2D>,
+    /// a `MultiPolygon` as JSON that restricts the plot to its inside
+    pub polygon: Option<String>,
+    /// an area of interest that restricts the plot to its inside
+    pub aoi: Option<AoiId>,
     #[serde(deserialize_with = "parse_time")]
     pub time: TimeInterval,
     #[serde(deserialize_with = "parse_spatial_resolution")]
     pub spatial_resolution: SpatialResolution,
+    /// the output format, which defaults to the output of the plot operator wrapped with its type
+    pub format: Option<PlotFormat>,
+    /// the width of `png` outputs of Vega plots in pixels
+    #[serde(default = "default_plot_width")]
+    pub width: u32,
+    /// the height of `png` outputs of Vega plots in pixels
+    #[serde(default = "default_plot_height")]
+    pub height: u32,
+}
+
+fn default_plot_width() -> u32 {
+    800
+}
+
+fn default_plot_height() -> u32 {
+    400
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum PlotFormat {
+    /// the Vega spec of the plot
+    Vega,
+    /// a rendered image of the plot
+    Png,
+    /// the plain data of the plot, e.g., the series or bins of a Vega plot
+    Json,
 }
 
 /// Generates a [plot](WrappedPlotOutput).
 ///
+/// Instead of a `bbox`, the plot can be restricted to a `polygon` or an area of interest (`aoi`).
+/// Then, the query covers the bounding box of the region and the plot only considers raster pixels whose centers lie inside of it.
+///
+/// The optional `format` selects the output instead of the wrapped plot:
+/// `vega` returns the Vega spec, `json` the plain data, e.g., the bins of a histogram,
+/// and `png` an image of `width` x `height` pixels that is rendered on the server for Vega plots.
+///
 /// # Example
 ///
 /// 1. Create a statistics workflow.
@@ -98,10 +146,13 @@ pub(crate) struct GetPlot {
 ///     {
 ///       "pixelCount": 6,
 ///       "nanCount": 0,
+///       "maskedPixelCount": 0,
+///       "validFraction": 1.0,
 ///       "min": 1.0,
 ///       "max": 6.0,
 ///       "mean": 3.5,
-///       "stddev": 1.707825127659933
+///       "stddev": 1.707825127659933,
+///       "median": 3.5
 ///     }
 ///   ]
 /// }
@@ -123,15 +174,41 @@ async fn get_plot<C: Context>(
     params: GetPlot,
     session: C::Session,
     ctx: C,
-) -> Result<impl warp::Reply, warp::Rejection> {
+) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+    ensure!(
+        (50..=4096).contains(&params.width) && (50..=4096).contains(&params.height),
+        error::InvalidPlotImageSize
+    );
+
     let workflow = ctx
         .workflow_registry_ref()
         .await
         .load(&WorkflowId(id))
         .await?;
 
+    let mut slow_query = ctx.slow_query_log().track("plot", &workflow);
+
     let operator = workflow.operator.get_plot().context(error::Operator)?;
 
+    let region = match (params.polygon.as_deref(), params.aoi) {
+        (Some(_), Some(_)) => return Err(error::Error::AmbiguousPlotQueryRegion.into()),
+        (Some(polygon), None) => {
+            Some(serde_json::from_str::<MultiPolygon>(polygon).context(error::SerdeJson)?)
+        }
+        (None, Some(aoi)) => Some(ctx.aoi_db_ref().await.load(&session, aoi).await?.geometry),
+        (None, None) => None,
+    }
+    .map(QueryRegion::new)
+    .transpose()
+    .context(error::Operator)?;
+
+    let spatial_bounds = match (&region, params.bbox) {
+        (Some(region), _) => region.bounding_box(),
+        (None, Some(bbox)) => bbox,
+        (None, None) => return Err(error::Error::MissingPlotQueryBounds.into()),
+    };
+
+    let session_id = session.id();
     let execution_context = ctx.execution_context(session)?;
 
     let initialized = operator
@@ -142,48 +219,119 @@ async fn get_plot<C: Context>(
     let processor = initialized.query_processor().context(error::Operator)?;
 
     let query_rect = VectorQueryRectangle {
-        spatial_bounds: params.bbox,
+        spatial_bounds,
         time_interval: params.time,
         spatial_resolution: params.spatial_resolution,
     };
 
-    let query_ctx = ctx.query_context()?;
+    let mut query_ctx = ctx.query_context()?;
+    query_ctx.extensions_mut().insert(session_id);
+    if let Some(region) = region {
+        query_ctx.extensions_mut().insert(region);
+    }
+    query_ctx
+        .extensions_mut()
+        .insert(QueryPriority::Interactive);
+
+    let query_timeout = QueryTimeout::for_handler(TimedHandler::Plot)?;
+    query_timeout.attach(&mut query_ctx);
+
+    let _admission = ctx.admission_control().admit(query_cost(&query_rect))?;
+
+    slow_query.query_started(&query_rect);
 
     let output_format = PlotOutputFormat::from(&processor);
     let plot_type = processor.plot_type();
 
-    let data = match processor {
-        TypedPlotQueryProcessor::JsonPlain(processor) => processor
-            .plot_query(query_rect, &query_ctx)
-            .await
-            .context(error::Operator)?,
-        TypedPlotQueryProcessor::JsonVega(processor) => {
-            let chart = processor
-                .plot_query(query_rect, &query_ctx)
-                .await
-                .context(error::Operator)?;
+    let plot = query_timeout
+        .run(async {
+            let _permit = ctx
+                .query_scheduler()
+                .acquire(QueryPriority::Interactive)
+                .await;
+
+            Ok(match processor {
+                TypedPlotQueryProcessor::JsonPlain(processor) => PlotOutput::Json(
+                    processor
+                        .plot_query(query_rect, &query_ctx)
+                        .await
+                        .context(error::Operator)?,
+                ),
+                TypedPlotQueryProcessor::JsonVega(processor) => PlotOutput::Vega(
+                    processor
+                        .plot_query(query_rect, &query_ctx)
+                        .await
+                        .context(error::Operator)?,
+                ),
+                TypedPlotQueryProcessor::ImagePng(processor) => PlotOutput::Png(
+                    processor
+                        .plot_query(query_rect, &query_ctx)
+                        .await
+                        .context(error::Operator)?,
+                ),
+            })
+        })
+        .await?;
 
-            serde_json::to_value(&chart).context(error::SerdeJson)?
-        }
-        TypedPlotQueryProcessor::ImagePng(processor) => {
-            let png_bytes = processor
-                .plot_query(query_rect, &query_ctx)
-                .await
-                .context(error::Operator)?;
+    slow_query.finish();
+
+    let reply: Box<dyn warp::Reply> = match (params.format, plot) {
+        (None, plot) => {
+            let data = match plot {
+                PlotOutput::Json(data) => data,
+                PlotOutput::Vega(chart) => {
+                    serde_json::to_value(&chart).context(error::SerdeJson)?
+                }
+                PlotOutput::Png(png_bytes) => {
+                    let data_uri = format!("data:image/png;base64,{}", base64::encode(png_bytes));
 
-            let data_uri = format!("data:image/png;base64,{}", base64::encode(png_bytes));
+                    serde_json::to_value(&data_uri).context(error::SerdeJson)?
+                }
+            };
 
-            serde_json::to_value(&data_uri).context(error::SerdeJson)?
+            Box::new(warp::reply::json(&WrappedPlotOutput {
+                output_format,
+                plot_type,
+                data,
+            }))
+        }
+        (Some(PlotFormat::Vega), PlotOutput::Vega(chart)) => Box::new(warp::reply::json(&chart)),
+        (Some(PlotFormat::Json), PlotOutput::Json(data)) => Box::new(warp::reply::json(&data)),
+        (Some(PlotFormat::Json), PlotOutput::Vega(chart)) => Box::new(warp::reply::json(
+            &chart.data_values().context(error::DataType)?,
+        )),
+        (Some(PlotFormat::Png), PlotOutput::Vega(chart)) => png_reply(
+            chart
+                .to_png(params.width, params.height)
+                .context(error::DataType)?,
+        )?,
+        (Some(PlotFormat::Png), PlotOutput::Png(png_bytes)) => png_reply(png_bytes)?,
+        (Some(format), _) => {
+            return Err(error::Error::UnsupportedPlotFormat {
+                plot_type,
+                format: format!("{:?}", format).to_lowercase(),
+            }
+            .into())
         }
     };
 
-    let output = WrappedPlotOutput {
-        output_format,
-        plot_type,
-        data,
-    };
+    Ok(reply)
+}
+
+/// The result of a plot query
+enum PlotOutput {
+    Json(serde_json::Value),
+    Vega(PlotData),
+    Png(Vec<u8>),
+}
 
-    Ok(warp::reply::json(&output))
+fn png_reply(png_bytes: Vec<u8>) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+    Ok(Box::new(
+        Response::builder()
+            .header("Content-Type", "image/png")
+            .body(png_bytes)
+            .context(error::Http)?,
+    ))
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
@@ -200,22 +348,27 @@ mod tests {
     use num_traits::AsPrimitive;
     use serde_json::json;
 
-    use geoengine_datatypes::primitives::Measurement;
+    use geoengine_datatypes::collections::DataCollection;
+    use geoengine_datatypes::primitives::{FeatureData, Measurement, NoGeometry};
     use geoengine_datatypes::raster::{Grid2D, RasterDataType, RasterTile2D, TileInformation};
     use geoengine_datatypes::spatial_reference::SpatialReference;
-    use geoengine_operators::engine::{PlotOperator, RasterOperator, RasterResultDescriptor};
-    use geoengine_operators::mock::{MockRasterSource, MockRasterSourceParams};
+    use geoengine_operators::engine::{
+        PlotOperator, RasterOperator, RasterResultDescriptor, SingleRasterOrVectorSource,
+        VectorOperator,
+    };
+    use geoengine_operators::mock::{
+        MockFeatureCollectionSource, MockRasterSource, MockRasterSourceParams,
+    };
     use geoengine_operators::plot::{
         Histogram, HistogramBounds, HistogramParams, Statistics, StatisticsParams,
     };
 
     use crate::contexts::{InMemoryContext, Session, SimpleContext};
-    use crate::handlers::handle_rejection;
+    use crate::handlers::{handle_rejection, ErrorResponse};
     use crate::workflows::workflow::Workflow;
 
     use super::*;
     use crate::util::tests::check_allowed_http_methods;
-    use warp::http::Response;
     use warp::hyper::body::Bytes;
 
     fn example_raster_source() -> Box<dyn RasterOperator> {
@@ -239,6 +392,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    bands: Vec::new(),
                 },
             },
         }
@@ -252,7 +406,7 @@ mod tests {
 
         let workflow = Workflow {
             operator: Statistics {
-                params: StatisticsParams {},
+                params: StatisticsParams::default(),
                 sources: vec![example_raster_source()].into(),
             }
             .boxed()
@@ -299,16 +453,142 @@ mod tests {
                 "data": [{
                     "pixelCount": 6,
                     "nanCount": 0,
+                    "maskedPixelCount": 0,
+                    "validFraction": 1.0,
                     "min": 1.0,
                     "max": 6.0,
                     "mean": 3.5,
-                    "stddev": 1.707_825_127_659_933
+                    "stddev": 1.707_825_127_659_933,
+                    "median": 3.5
                 }]
             })
             .to_string()
         );
     }
 
+    #[tokio::test]
+    async fn json_in_polygon() {
+        let ctx = InMemoryContext::default();
+        let session_id = ctx.default_session_ref().await.id();
+
+        let workflow = Workflow {
+            operator: Statistics {
+                params: StatisticsParams::default(),
+                sources: vec![example_raster_source()].into(),
+            }
+            .boxed()
+            .into(),
+        };
+
+        let id = ctx
+            .workflow_registry()
+            .write()
+            .await
+            .register(workflow)
+            .await
+            .unwrap();
+
+        // the upper row of the raster
+        let polygon = MultiPolygon::new(vec![vec![vec![
+            (0., 0.).into(),
+            (2., 0.).into(),
+            (2., -1.).into(),
+            (0., -1.).into(),
+            (0., 0.).into(),
+        ]]])
+        .unwrap();
+
+        let params = &[
+            ("polygon", serde_json::to_string(&polygon).unwrap().as_str()),
+            ("time", "2020-01-01T00:00:00.0Z"),
+            ("spatialResolution", "0.1,0.1"),
+        ];
+        let url = format!(
+            "/plot/{}/?{}",
+            id,
+            &serde_urlencoded::to_string(params).unwrap()
+        );
+        let response = warp::test::request()
+            .method("GET")
+            .path(&url)
+            .header(
+                "Authorization",
+                format!("Bearer {}", session_id.to_string()),
+            )
+            .reply(&get_plot_handler(ctx).recover(handle_rejection))
+            .await;
+
+        assert_eq!(response.status(), 200, "{:?}", response.body());
+
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(response.body()).unwrap(),
+            json!({
+                "outputFormat": "JsonPlain",
+                "plotType": "Statistics",
+                "data": [{
+                    "pixelCount": 2,
+                    "nanCount": 0,
+                    "maskedPixelCount": 0,
+                    "validFraction": 1.0,
+                    "min": 1.0,
+                    "max": 2.0,
+                    "mean": 1.5,
+                    "stddev": 0.5,
+                    "median": 1.5
+                }]
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn json_without_bounds() {
+        let ctx = InMemoryContext::default();
+        let session_id = ctx.default_session_ref().await.id();
+
+        let workflow = Workflow {
+            operator: Statistics {
+                params: StatisticsParams::default(),
+                sources: vec![example_raster_source()].into(),
+            }
+            .boxed()
+            .into(),
+        };
+
+        let id = ctx
+            .workflow_registry()
+            .write()
+            .await
+            .register(workflow)
+            .await
+            .unwrap();
+
+        let params = &[
+            ("time", "2020-01-01T00:00:00.0Z"),
+            ("spatialResolution", "0.1,0.1"),
+        ];
+        let url = format!(
+            "/plot/{}/?{}",
+            id,
+            &serde_urlencoded::to_string(params).unwrap()
+        );
+        let response = warp::test::request()
+            .method("GET")
+            .path(&url)
+            .header(
+                "Authorization",
+                format!("Bearer {}", session_id.to_string()),
+            )
+            .reply(&get_plot_handler(ctx).recover(handle_rejection))
+            .await;
+
+        ErrorResponse::assert(
+            &response,
+            400,
+            "MissingPlotQueryBounds",
+            "A plot query needs a bbox, a polygon or an area of interest",
+        );
+    }
+
     #[tokio::test]
     async fn json_vega() {
         let ctx = InMemoryContext::default();
@@ -377,6 +657,188 @@ mod tests {
         );
     }
 
+    async fn query_histogram(format: &str) -> Response<Bytes> {
+        query_histogram_of(example_raster_source().into(), None, format).await
+    }
+
+    async fn query_histogram_of(
+        sources: SingleRasterOrVectorSource,
+        column_name: Option<String>,
+        format: &str,
+    ) -> Response<Bytes> {
+        let ctx = InMemoryContext::default();
+        let session_id = ctx.default_session_ref().await.id();
+
+        let workflow = Workflow {
+            operator: Histogram {
+                params: HistogramParams {
+                    column_name,
+                    bounds: HistogramBounds::Values {
+                        min: 0.0,
+                        max: 10.0,
+                    },
+                    buckets: Some(4),
+                    interactive: false,
+                },
+                sources,
+            }
+            .boxed()
+            .into(),
+        };
+
+        let id = ctx
+            .workflow_registry()
+            .write()
+            .await
+            .register(workflow)
+            .await
+            .unwrap();
+
+        let params = &[
+            ("bbox", "-180,-90,180,90"),
+            ("time", "2020-01-01T00:00:00.0Z"),
+            ("spatialResolution", "0.1,0.1"),
+            ("format", format),
+            ("width", "200"),
+            ("height", "100"),
+        ];
+        let url = format!(
+            "/plot/{}/?{}",
+            id,
+            &serde_urlencoded::to_string(params).unwrap()
+        );
+        warp::test::request()
+            .method("GET")
+            .path(&url)
+            .header(
+                "Authorization",
+                format!("Bearer {}", session_id.to_string()),
+            )
+            .reply(&get_plot_handler(ctx).recover(handle_rejection))
+            .await
+    }
+
+    #[tokio::test]
+    async fn json_data_of_vega_plots() {
+        let response = query_histogram("json").await;
+
+        assert_eq!(response.status(), 200, "{:?}", response.body());
+
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(response.body()).unwrap(),
+            json!([
+                {"binStart": 0.0, "binEnd": 2.5, "Frequency": 2},
+                {"binStart": 2.5, "binEnd": 5.0, "Frequency": 2},
+                {"binStart": 5.0, "binEnd": 7.5, "Frequency": 2},
+                {"binStart": 7.5, "binEnd": 10.0, "Frequency": 0}
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn json_data_of_data_collections() {
+        let collection = DataCollection::from_slices(
+            &[] as &[NoGeometry],
+            &[TimeInterval::default(); 6],
+            &[("foo", FeatureData::Float(vec![1., 2., 3., 4., 5., 6.]))],
+        )
+        .unwrap();
+
+        let response = query_histogram_of(
+            MockFeatureCollectionSource::single(collection)
+                .boxed()
+                .into(),
+            Some("foo".to_string()),
+            "json",
+        )
+        .await;
+
+        assert_eq!(response.status(), 200, "{:?}", response.body());
+
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(response.body()).unwrap(),
+            json!([
+                {"binStart": 0.0, "binEnd": 2.5, "Frequency": 2},
+                {"binStart": 2.5, "binEnd": 5.0, "Frequency": 2},
+                {"binStart": 5.0, "binEnd": 7.5, "Frequency": 2},
+                {"binStart": 7.5, "binEnd": 10.0, "Frequency": 0}
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn vega_spec() {
+        let response = query_histogram("vega").await;
+
+        assert_eq!(response.status(), 200, "{:?}", response.body());
+
+        let chart: PlotData = serde_json::from_slice(response.body()).unwrap();
+        assert!(chart.vega_string.contains("\"mark\":\"bar\""));
+    }
+
+    #[tokio::test]
+    async fn png() {
+        let response = query_histogram("png").await;
+
+        assert_eq!(response.status(), 200, "{:?}", response.body());
+        assert_eq!(response.headers().get("Content-Type").unwrap(), "image/png");
+
+        let image =
+            image::load_from_memory_with_format(response.body(), image::ImageFormat::Png).unwrap();
+        assert_eq!(image.into_rgba8().dimensions(), (200, 100));
+    }
+
+    #[tokio::test]
+    async fn unsupported_format() {
+        let ctx = InMemoryContext::default();
+        let session_id = ctx.default_session_ref().await.id();
+
+        let workflow = Workflow {
+            operator: Statistics {
+                params: StatisticsParams::default(),
+                sources: vec![example_raster_source()].into(),
+            }
+            .boxed()
+            .into(),
+        };
+
+        let id = ctx
+            .workflow_registry()
+            .write()
+            .await
+            .register(workflow)
+            .await
+            .unwrap();
+
+        let params = &[
+            ("bbox", "-180,-90,180,90"),
+            ("time", "2020-01-01T00:00:00.0Z"),
+            ("spatialResolution", "0.1,0.1"),
+            ("format", "png"),
+        ];
+        let url = format!(
+            "/plot/{}/?{}",
+            id,
+            &serde_urlencoded::to_string(params).unwrap()
+        );
+        let response = warp::test::request()
+            .method("GET")
+            .path(&url)
+            .header(
+                "Authorization",
+                format!("Bearer {}", session_id.to_string()),
+            )
+            .reply(&get_plot_handler(ctx).recover(handle_rejection))
+            .await;
+
+        ErrorResponse::assert(
+            &response,
+            400,
+            "UnsupportedPlotFormat",
+            "A Statistics plot cannot be output as png",
+        );
+    }
+
     #[test]
     fn deserialize_get_plot() {
         let params = &[
@@ -389,13 +851,18 @@ mod tests {
             serde_urlencoded::from_str::<GetPlot>(&serde_urlencoded::to_string(params).unwrap())
                 .unwrap(),
             GetPlot {
-                bbox: BoundingBox2D::new((-180., -90.).into(), (180., 90.).into()).unwrap(),
+                bbox: Some(BoundingBox2D::new((-180., -90.).into(), (180., 90.).into()).unwrap()),
+                polygon: None,
+                aoi: None,
                 time: TimeInterval::new(
                     NaiveDate::from_ymd(2020, 1, 1).and_hms(0, 0, 0),
                     NaiveDate::from_ymd(2020, 1, 1).and_hms(0, 0, 0),
                 )
                 .unwrap(),
                 spatial_resolution: SpatialResolution::zero_point_one(),
+                format: None,
+                width: 800,
+                height: 400,
             }
         );
     }
@@ -408,7 +875,7 @@ mod tests {
 
             let workflow = Workflow {
                 operator: Statistics {
-                    params: StatisticsParams {},
+                    params: StatisticsParams::default(),
                     sources: vec![example_raster_source()].into(),
                 }
                 .boxed()
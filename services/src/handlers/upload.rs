@@ -4,6 +4,7 @@ use futures::{Stream, TryStreamExt};
 use geoengine_datatypes::util::Identifier;
 use warp::Filter;
 
+use crate::datasets::sandbox::GdalSandbox;
 use crate::datasets::upload::{FileId, FileUpload, Upload, UploadDb, UploadId, UploadRootPath};
 use crate::error;
 use crate::handlers::{authenticate, Context};
@@ -66,44 +67,80 @@ async fn upload<C: Context>(
 
     let root = upload_id.root_path()?;
 
+    let sandbox = GdalSandbox::from_config()?;
+    let quota = ctx.dataset_db_ref().await.upload_quota(&session).await?;
+
     fs::create_dir_all(&root).await.context(error::Io)?;
 
-    let mut files: Vec<FileUpload> = vec![];
-    while let Ok(Some(mut field)) = stream.try_next().await {
-        let file_name = field
-            .filename()
-            .map_err(|_| error::Error::UploadFieldMissingFileName)?
-            .to_owned();
+    let received: Result<Vec<FileUpload>, error::Error> = async {
+        let mut files: Vec<FileUpload> = vec![];
+        let mut uploaded_bytes: u64 = 0;
+
+        while let Ok(Some(mut field)) = stream.try_next().await {
+            let file_name = field
+                .filename()
+                .map_err(|_| error::Error::UploadFieldMissingFileName)?
+                .to_owned();
+
+            let file_id = FileId::new();
+            let mut file = fs::File::create(root.join(&file_name))
+                .await
+                .context(error::Io)?;
+
+            let mut byte_size = 0;
+            while let Ok(Some(bytes)) = field.try_next().await {
+                // abort as soon as the quota is exceeded instead of filling the disk first
+                uploaded_bytes += bytes.len() as u64;
+                if let Some(quota) = quota {
+                    quota.check(uploaded_bytes)?;
+                }
+
+                file.write_all(&bytes).await.context(error::Io)?;
+                byte_size += bytes.len();
+            }
+
+            files.push(FileUpload {
+                id: file_id,
+                name: file_name,
+                byte_size,
+            });
+        }
 
-        let file_id = FileId::new();
-        let mut file = fs::File::create(root.join(&file_name))
-            .await
-            .context(error::Io)?;
+        Ok(files)
+    }
+    .await;
 
-        let mut byte_size = 0;
-        while let Ok(Some(bytes)) = field.try_next().await {
-            file.write_all(&bytes).await.context(error::Io)?;
-            byte_size += bytes.len();
+    let allowed = match received {
+        Ok(files) => sandbox
+            .check_upload(files.iter().map(|file| root.join(&file.name)).collect())
+            .await
+            .map(|_| files),
+        Err(error) => Err(error),
+    };
+
+    let created = match allowed {
+        // the quota is checked again since concurrent uploads of the same user share it
+        Ok(files) => {
+            ctx.dataset_db_ref_mut()
+                .await
+                .create_upload(
+                    &session,
+                    Upload {
+                        id: upload_id,
+                        files,
+                    },
+                )
+                .await
         }
+        Err(error) => Err(error),
+    };
 
-        files.push(FileUpload {
-            id: file_id,
-            name: file_name,
-            byte_size,
-        });
+    if let Err(e) = created {
+        // the files of rejected uploads, e.g., exceeding a storage quota or using a forbidden driver, must not occupy the disk
+        fs::remove_dir_all(&root).await.context(error::Io)?;
+        return Err(e.into());
     }
 
-    ctx.dataset_db_ref_mut()
-        .await
-        .create_upload(
-            &session,
-            Upload {
-                id: upload_id,
-                files,
-            },
-        )
-        .await?;
-
     Ok(warp::reply::json(&IdResponse::from(upload_id)))
 }
 
@@ -11,18 +11,27 @@ use geoengine_datatypes::primitives::AxisAlignedRectangle;
 use geoengine_datatypes::{primitives::SpatialResolution, spatial_reference::SpatialReference};
 
 use crate::contexts::MockableSession;
+use crate::datasets::provenance::{attribution_header_value, attributions, ATTRIBUTION_HEADER};
 use crate::error::Result;
 use crate::error::{self, Error};
+use crate::handlers::wms::select_band;
+use crate::handlers::workflows::workflow_provenance;
 use crate::handlers::Context;
 use crate::ogc::wcs::request::{DescribeCoverage, GetCapabilities, GetCoverage, WcsRequest};
+use crate::util::admission_control::query_cost;
 use crate::util::config::get_config_element;
+use crate::util::query_timeout::{QueryTimeout, TimedHandler};
 use crate::workflows::registry::WorkflowRegistry;
 use crate::workflows::workflow::WorkflowId;
 
 use geoengine_datatypes::primitives::{TimeInstance, TimeInterval};
 use geoengine_operators::engine::ResultDescriptor;
-use geoengine_operators::engine::{RasterOperator, RasterQueryRectangle};
+use geoengine_operators::engine::{
+    CachePolicyHint, ExecutionContext, QueryContext, QueryPriority, RasterOperator,
+    RasterQueryRectangle, TilingSpecificationOverride,
+};
 use geoengine_operators::processing::{Reprojection, ReprojectionParams};
+use geoengine_operators::util::resampling::ResamplingMethod;
 
 pub(crate) fn wcs_handler<C: Context>(
     ctx: C,
@@ -272,23 +281,30 @@ async fn get_coverage<C: Context>(
         );
     }
 
-    let workflow = ctx
-        .workflow_registry_ref()
-        .await
-        .load(&WorkflowId::from_str(&request.identifier)?)
-        .await?;
+    let workflow_id = WorkflowId::from_str(&request.identifier)?;
+    let workflow = ctx.workflow_registry_ref().await.load(&workflow_id).await?;
+
+    let mut slow_query = ctx.slow_query_log().track("wcs", &workflow);
 
     let operator = workflow.operator.get_raster().context(error::Operator)?;
 
     // TODO: use correct session when WCS uses authenticated access
     let execution_context = ctx.execution_context(C::Session::mock())?;
 
+    let tiling_specification =
+        request.tiling_specification(execution_context.tiling_specification());
+    let execution_context =
+        TilingSpecificationOverride::new(&execution_context, tiling_specification);
+
     let initialized = operator
         .clone()
         .initialize(&execution_context)
         .await
         .context(error::Operator)?;
 
+    let (operator, initialized) =
+        select_band(operator, initialized, request.band(), &execution_context).await?;
+
     // handle request and workflow crs matching
     let workflow_spatial_ref: Option<SpatialReference> =
         initialized.result_descriptor().spatial_reference().into();
@@ -303,6 +319,8 @@ async fn get_coverage<C: Context>(
         let proj = Reprojection {
             params: ReprojectionParams {
                 target_spatial_reference: request_spatial_ref,
+                resampling: ResamplingMethod::Nearest,
+                transformation: None,
             },
             sources: operator.into(),
         };
@@ -338,96 +356,115 @@ async fn get_coverage<C: Context>(
         spatial_resolution,
     };
 
-    let query_ctx = ctx.query_context()?;
-
-    let bytes = match processor {
-        geoengine_operators::engine::TypedRasterQueryProcessor::U8(p) => {
-            raster_stream_to_geotiff_bytes(
-                p,
-                query_rect,
-                query_ctx,
-                no_data_value,
-                request_spatial_ref,
-                Some(get_config_element::<crate::util::config::Wcs>()?.tile_limit),
-            )
-            .await
-        }
-        geoengine_operators::engine::TypedRasterQueryProcessor::U16(p) => {
-            raster_stream_to_geotiff_bytes(
-                p,
-                query_rect,
-                query_ctx,
-                no_data_value,
-                request_spatial_ref,
-                Some(get_config_element::<crate::util::config::Wcs>()?.tile_limit),
-            )
-            .await
-        }
-        geoengine_operators::engine::TypedRasterQueryProcessor::U32(p) => {
-            raster_stream_to_geotiff_bytes(
-                p,
-                query_rect,
-                query_ctx,
-                no_data_value,
-                request_spatial_ref,
-                Some(get_config_element::<crate::util::config::Wcs>()?.tile_limit),
-            )
-            .await
-        }
-        geoengine_operators::engine::TypedRasterQueryProcessor::I16(p) => {
-            raster_stream_to_geotiff_bytes(
-                p,
-                query_rect,
-                query_ctx,
-                no_data_value,
-                request_spatial_ref,
-                Some(get_config_element::<crate::util::config::Wcs>()?.tile_limit),
-            )
-            .await
-        }
-        geoengine_operators::engine::TypedRasterQueryProcessor::I32(p) => {
-            raster_stream_to_geotiff_bytes(
-                p,
-                query_rect,
-                query_ctx,
-                no_data_value,
-                request_spatial_ref,
-                Some(get_config_element::<crate::util::config::Wcs>()?.tile_limit),
-            )
-            .await
-        }
-        geoengine_operators::engine::TypedRasterQueryProcessor::F32(p) => {
-            raster_stream_to_geotiff_bytes(
-                p,
-                query_rect,
-                query_ctx,
-                no_data_value,
-                request_spatial_ref,
-                Some(get_config_element::<crate::util::config::Wcs>()?.tile_limit),
-            )
-            .await
-        }
-        geoengine_operators::engine::TypedRasterQueryProcessor::F64(p) => {
-            raster_stream_to_geotiff_bytes(
-                p,
-                query_rect,
-                query_ctx,
-                no_data_value,
-                request_spatial_ref,
-                Some(get_config_element::<crate::util::config::Wcs>()?.tile_limit),
-            )
-            .await
-        }
-        _ => return Err(error::Error::RasterDataTypeNotSupportByGdal.into()),
+    let mut query_ctx = ctx.query_context()?;
+    query_ctx.extensions_mut().insert(QueryPriority::Batch);
+    query_ctx.extensions_mut().insert(CachePolicyHint::Bypass);
+
+    let query_timeout = QueryTimeout::for_handler(TimedHandler::Wcs)?;
+    query_timeout.attach(&mut query_ctx);
+
+    let _admission = ctx.admission_control().admit(query_cost(&query_rect))?;
+
+    slow_query.query_started(&query_rect);
+
+    let bytes = query_timeout
+        .run(async {
+            let _permit = ctx.query_scheduler().acquire(QueryPriority::Batch).await;
+
+            match processor {
+                geoengine_operators::engine::TypedRasterQueryProcessor::U8(p) => {
+                    raster_stream_to_geotiff_bytes(
+                        p,
+                        query_rect,
+                        query_ctx,
+                        no_data_value,
+                        request_spatial_ref,
+                        Some(get_config_element::<crate::util::config::Wcs>()?.tile_limit),
+                    )
+                    .await
+                }
+                geoengine_operators::engine::TypedRasterQueryProcessor::U16(p) => {
+                    raster_stream_to_geotiff_bytes(
+                        p,
+                        query_rect,
+                        query_ctx,
+                        no_data_value,
+                        request_spatial_ref,
+                        Some(get_config_element::<crate::util::config::Wcs>()?.tile_limit),
+                    )
+                    .await
+                }
+                geoengine_operators::engine::TypedRasterQueryProcessor::U32(p) => {
+                    raster_stream_to_geotiff_bytes(
+                        p,
+                        query_rect,
+                        query_ctx,
+                        no_data_value,
+                        request_spatial_ref,
+                        Some(get_config_element::<crate::util::config::Wcs>()?.tile_limit),
+                    )
+                    .await
+                }
+                geoengine_operators::engine::TypedRasterQueryProcessor::I16(p) => {
+                    raster_stream_to_geotiff_bytes(
+                        p,
+                        query_rect,
+                        query_ctx,
+                        no_data_value,
+                        request_spatial_ref,
+                        Some(get_config_element::<crate::util::config::Wcs>()?.tile_limit),
+                    )
+                    .await
+                }
+                geoengine_operators::engine::TypedRasterQueryProcessor::I32(p) => {
+                    raster_stream_to_geotiff_bytes(
+                        p,
+                        query_rect,
+                        query_ctx,
+                        no_data_value,
+                        request_spatial_ref,
+                        Some(get_config_element::<crate::util::config::Wcs>()?.tile_limit),
+                    )
+                    .await
+                }
+                geoengine_operators::engine::TypedRasterQueryProcessor::F32(p) => {
+                    raster_stream_to_geotiff_bytes(
+                        p,
+                        query_rect,
+                        query_ctx,
+                        no_data_value,
+                        request_spatial_ref,
+                        Some(get_config_element::<crate::util::config::Wcs>()?.tile_limit),
+                    )
+                    .await
+                }
+                geoengine_operators::engine::TypedRasterQueryProcessor::F64(p) => {
+                    raster_stream_to_geotiff_bytes(
+                        p,
+                        query_rect,
+                        query_ctx,
+                        no_data_value,
+                        request_spatial_ref,
+                        Some(get_config_element::<crate::util::config::Wcs>()?.tile_limit),
+                    )
+                    .await
+                }
+                _ => return Err(error::Error::RasterDataTypeNotSupportByGdal),
+            }
+            .map_err(error::Error::from)
+        })
+        .await?;
+
+    slow_query.finish();
+
+    let attributions = attributions(&workflow_provenance(&workflow_id, ctx).await?);
+
+    let mut response = Response::builder().header("Content-Type", "image/tiff");
+    if !attributions.is_empty() {
+        response = response.header(ATTRIBUTION_HEADER, attribution_header_value(&attributions));
     }
-    .map_err(error::Error::from)?;
 
-    Ok(Box::new(
-        Response::builder()
-            .header("Content-Type", "image/tiff")
-            .body(bytes)
-            .context(error::Http)?,
-    ))
+    Ok(Box::new(response.body(bytes).context(error::Http)?))
 }
 
 #[cfg(test)]
@@ -615,6 +652,10 @@ mod tests {
             .await;
 
         assert_eq!(res.status(), 200);
+        assert_eq!(
+            res.headers()[ATTRIBUTION_HEADER],
+            "Sample Citation (Sample License)"
+        );
         assert_eq!(
             include_bytes!("../../../operators/test-data/raster/geotiff_from_stream.tiff")
                 as &[u8],
@@ -7,6 +7,8 @@ use crate::error;
 use crate::error::Result;
 use crate::handlers::Context;
 use crate::ogc::wfs::request::{GetCapabilities, GetFeature, TypeNames, WfsRequest};
+use crate::util::admission_control::query_cost;
+use crate::util::query_timeout::{QueryTimeout, TimedHandler};
 use crate::workflows::registry::WorkflowRegistry;
 use crate::workflows::workflow::{Workflow, WorkflowId};
 use futures::StreamExt;
@@ -20,11 +22,12 @@ use geoengine_datatypes::{
     spatial_reference::SpatialReference,
 };
 use geoengine_operators::engine::{
-    QueryContext, ResultDescriptor, TypedVectorQueryProcessor, VectorQueryProcessor,
+    QueryContext, QueryPriority, ResultDescriptor, TypedVectorQueryProcessor, VectorQueryProcessor,
     VectorQueryRectangle,
 };
 use geoengine_operators::engine::{QueryProcessor, VectorOperator};
 use geoengine_operators::processing::{Reprojection, ReprojectionParams};
+use geoengine_operators::util::resampling::ResamplingMethod;
 use serde_json::json;
 use std::str::FromStr;
 
@@ -386,6 +389,8 @@ async fn get_feature<C: Context>(
         }
     };
 
+    let mut slow_query = ctx.slow_query_log().track("wfs", &workflow);
+
     let operator = workflow.operator.get_vector().context(error::Operator)?;
 
     // TODO: use correct session when WFS uses authenticated access
@@ -413,6 +418,8 @@ async fn get_feature<C: Context>(
         let proj = Reprojection {
             params: ReprojectionParams {
                 target_spatial_reference: request_spatial_ref,
+                resampling: ResamplingMethod::Nearest,
+                transformation: None,
             },
             sources: operator.into(),
         };
@@ -437,22 +444,43 @@ async fn get_feature<C: Context>(
             // TODO: find a reasonable fallback, e.g., dependent on the SRS or BBox
             .unwrap_or_else(SpatialResolution::zero_point_one),
     };
-    let query_ctx = ctx.query_context()?;
+    let mut query_ctx = ctx.query_context()?;
+    query_ctx
+        .extensions_mut()
+        .insert(QueryPriority::Interactive);
 
-    let json = match processor {
-        TypedVectorQueryProcessor::Data(p) => {
-            vector_stream_to_geojson(p, query_rect, &query_ctx).await
-        }
-        TypedVectorQueryProcessor::MultiPoint(p) => {
-            vector_stream_to_geojson(p, query_rect, &query_ctx).await
-        }
-        TypedVectorQueryProcessor::MultiLineString(p) => {
-            vector_stream_to_geojson(p, query_rect, &query_ctx).await
-        }
-        TypedVectorQueryProcessor::MultiPolygon(p) => {
-            vector_stream_to_geojson(p, query_rect, &query_ctx).await
-        }
-    }?;
+    let query_timeout = QueryTimeout::for_handler(TimedHandler::Wfs)?;
+    query_timeout.attach(&mut query_ctx);
+
+    let _admission = ctx.admission_control().admit(query_cost(&query_rect))?;
+
+    slow_query.query_started(&query_rect);
+
+    let json = query_timeout
+        .run(async {
+            let _permit = ctx
+                .query_scheduler()
+                .acquire(QueryPriority::Interactive)
+                .await;
+
+            match processor {
+                TypedVectorQueryProcessor::Data(p) => {
+                    vector_stream_to_geojson(p, query_rect, &query_ctx).await
+                }
+                TypedVectorQueryProcessor::MultiPoint(p) => {
+                    vector_stream_to_geojson(p, query_rect, &query_ctx).await
+                }
+                TypedVectorQueryProcessor::MultiLineString(p) => {
+                    vector_stream_to_geojson(p, query_rect, &query_ctx).await
+                }
+                TypedVectorQueryProcessor::MultiPolygon(p) => {
+                    vector_stream_to_geojson(p, query_rect, &query_ctx).await
+                }
+            }
+        })
+        .await?;
+
+    slow_query.finish();
 
     Ok(Box::new(
         Response::builder()
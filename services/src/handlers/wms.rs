@@ -1,27 +1,40 @@
+use futures::future::try_join_all;
 use log::debug;
-use snafu::ResultExt;
+use snafu::{ensure, ResultExt};
 use warp::reply::Reply;
 use warp::{http::Response, Filter, Rejection};
 
 use geoengine_datatypes::primitives::{AxisAlignedRectangle, SpatialPartition2D};
 use geoengine_datatypes::{
-    operations::image::{Colorizer, ToPng},
+    operations::image::{composite_pngs, Colorizer, ToPng},
     primitives::SpatialResolution,
     raster::Grid2D,
     spatial_reference::SpatialReference,
 };
 
+use crate::basemap::Basemap;
 use crate::contexts::MockableSession;
+use crate::datasets::provenance::{attribution_header_value, attributions, ATTRIBUTION_HEADER};
 use crate::error;
 use crate::error::Result;
+use crate::handlers::workflows::workflow_provenance;
 use crate::handlers::Context;
 use crate::ogc::wms::request::{GetCapabilities, GetLegendGraphic, GetMap, WmsRequest};
+use crate::tile_archive::TileArchive;
+use crate::util::admission_control::query_cost;
+use crate::util::query_timeout::{QueryTimeout, TimedHandler};
 use crate::workflows::registry::WorkflowRegistry;
 use crate::workflows::workflow::WorkflowId;
 
 use geoengine_datatypes::primitives::{TimeInstance, TimeInterval};
-use geoengine_operators::engine::{RasterOperator, RasterQueryRectangle, ResultDescriptor};
-use geoengine_operators::processing::{Reprojection, ReprojectionParams};
+use geoengine_operators::engine::{
+    ExecutionContext, InitializedRasterOperator, QueryContext, QueryPriority, RasterOperator,
+    RasterQueryRectangle, ResultDescriptor, SingleRasterSource, TilingSpecificationOverride,
+};
+use geoengine_operators::processing::{
+    BandSelection, BandSelectionParams, BandSelector, Reprojection, ReprojectionParams,
+};
+use geoengine_operators::util::resampling::ResamplingMethod;
 use geoengine_operators::{
     call_on_generic_raster_processor, util::raster_stream_to_png::raster_stream_to_png_bytes,
 };
@@ -188,6 +201,14 @@ fn get_capabilities(_request: &GetCapabilities) -> Result<Box<dyn warp::Reply>,
 
 /// Renders a map as raster image.
 ///
+/// Multiple comma-separated `layers` are rendered and alpha-composited in the given order, i.e., the first layer is at the bottom.
+/// The `styles` of the layers are comma-separated in the same order.
+/// The vendor-specific parameter `opacity` sets the comma-separated opacities of the layers between `0` and `1`.
+/// The vendor-specific parameter `basemap=true` places the configured basemap under the layers if the `crs` is `EPSG:3857`.
+///
+/// The `Attribution` header of the response lists the citations and licenses of the used datasets.
+/// They are separated by semicolons and percent-encoded where necessary.
+///
 /// # Example
 ///
 /// ```text
@@ -195,41 +216,241 @@ fn get_capabilities(_request: &GetCapabilities) -> Result<Box<dyn warp::Reply>,
 /// ```
 /// Response:
 /// PNG image
+///
+/// ```text
+/// GET /wms?request=GetMap&service=WMS&version=1.3.0&layers=df756642-c5a3-4d72-8ad7-629d312ae993,a4fa3f3c-6e5b-4c5c-8f5c-b1e8c6a7a4e1&styles=,&opacity=1,0.5&bbox=1,2,3,4&width=100&height=100&crs=EPSG%3A4326&format=image%2Fpng
+/// ```
+/// Response:
+/// PNG image
 async fn get_map<C: Context>(
     request: &GetMap,
     ctx: &C,
 ) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
     // TODO: validate request?
-    if request.layers == "mock_raster" {
-        return get_map_mock(request);
+    let layers: Vec<&str> = request.layers.split(',').collect();
+    let styles = layer_styles(&request.styles, layers.len())?;
+
+    let opacities = match &request.opacity {
+        Some(opacities) => {
+            ensure!(
+                opacities.len() == layers.len(),
+                error::WmsLayerParameterMismatch {
+                    parameter: "opacities",
+                    layers: layers.len(),
+                }
+            );
+            opacities.clone()
+        }
+        None => vec![1.; layers.len()],
+    };
+
+    // TODO: use a default spatial reference if it is not set?
+    let spatial_reference: SpatialReference =
+        request.crs.ok_or(error::Error::InvalidSpatialReference)?;
+
+    // TODO: use proj for determining axis order
+    let extent = MapExtent {
+        spatial_reference,
+        bbox: request.bbox.bounds(spatial_reference)?,
+        width: request.width,
+        height: request.height,
+        time: request.time,
+    };
+
+    let band = request.dim_band.as_deref();
+
+    let layer_images = try_join_all(
+        layers
+            .iter()
+            .zip(&styles)
+            .map(|(layer, style)| render_layer(&extent, layer, style, band, ctx)),
+    );
+
+    let (mut images, opacities) = if request.basemap == Some(true) {
+        let (basemap, layer_images) = futures::try_join!(render_basemap(&extent), layer_images)?;
+
+        (
+            std::iter::once(basemap).chain(layer_images).collect(),
+            std::iter::once(1.).chain(opacities).collect(),
+        )
+    } else {
+        (layer_images.await?, opacities)
+    };
+
+    let image_bytes = if images.len() == 1 && opacities[0] >= 1. {
+        images.pop().expect("there is one image")
+    } else {
+        let layers: Vec<(Vec<u8>, f64)> = images.into_iter().zip(opacities).collect();
+        composite_pngs(&layers).context(error::DataType)?
+    };
+
+    let attributions = layer_attributions(&layers, ctx).await?;
+
+    let mut response = Response::builder().header("Content-Type", "image/png");
+    if !attributions.is_empty() {
+        response = response.header(ATTRIBUTION_HEADER, attribution_header_value(&attributions));
+    }
+
+    Ok(Box::new(response.body(image_bytes).context(error::Http)?))
+}
+
+/// Collects the attributions of the datasets of all layers
+async fn layer_attributions<C: Context>(layers: &[&str], ctx: &C) -> Result<Vec<String>> {
+    let mut provenance = Vec::new();
+
+    for layer in layers.iter().filter(|layer| **layer != "mock_raster") {
+        provenance.extend(workflow_provenance(&WorkflowId::from_str(layer)?, ctx).await?);
+    }
+
+    Ok(attributions(&provenance))
+}
+
+/// Splits the comma-separated styles of the layers without splitting the JSON of custom styles
+fn layer_styles(styles: &str, layers: usize) -> Result<Vec<&str>> {
+    if styles.is_empty() {
+        return Ok(vec![""; layers]);
     }
 
-    let workflow = ctx
-        .workflow_registry_ref()
+    let mut split = Vec::with_capacity(layers);
+    let mut start = 0;
+    let mut depth = 0_usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, c) in styles.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => depth += 1,
+            '}' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                split.push(&styles[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    split.push(&styles[start..]);
+
+    ensure!(
+        split.len() == layers,
+        error::WmsLayerParameterMismatch {
+            parameter: "styles",
+            layers,
+        }
+    );
+
+    Ok(split)
+}
+
+/// The extent and size of a rendered map
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct MapExtent {
+    pub spatial_reference: SpatialReference,
+    pub bbox: SpatialPartition2D,
+    pub width: u32,
+    pub height: u32,
+    /// the current time if it is not set
+    pub time: Option<TimeInterval>,
+}
+
+/// Renders the configured basemap as PNG image
+pub(crate) async fn render_basemap(extent: &MapExtent) -> Result<Vec<u8>> {
+    let basemap = Basemap::from_config()?.ok_or(error::Error::BasemapNotConfigured)?;
+
+    ensure!(
+        extent.spatial_reference == Basemap::spatial_reference(),
+        error::BasemapUnsupportedSpatialReference {
+            spatial_reference: extent.spatial_reference
+        }
+    );
+
+    basemap
+        .render(extent.bbox, extent.width, extent.height)
         .await
-        .load(&WorkflowId::from_str(&request.layers)?)
-        .await?;
+}
+
+/// Renders a single layer of a `GetMap` request as PNG image
+async fn render_layer<C: Context>(
+    extent: &MapExtent,
+    layer: &str,
+    style: &str,
+    band: Option<&str>,
+    ctx: &C,
+) -> Result<Vec<u8>> {
+    if layer == "mock_raster" {
+        return get_map_mock(extent);
+    }
+
+    render_workflow(
+        extent,
+        &WorkflowId::from_str(layer)?,
+        colorizer_from_style(style)?,
+        band,
+        ctx,
+    )
+    .await
+}
+
+/// Renders a raster workflow as PNG image.
+/// The `band` is ignored for single band rasters.
+pub(crate) async fn render_workflow<C: Context>(
+    extent: &MapExtent,
+    workflow_id: &WorkflowId,
+    colorizer: Option<Colorizer>,
+    band: Option<&str>,
+    ctx: &C,
+) -> Result<Vec<u8>> {
+    let workflow = ctx.workflow_registry_ref().await.load(workflow_id).await?;
+
+    let mut slow_query = ctx.slow_query_log().track("wms", &workflow);
 
     let operator = workflow.operator.get_raster().context(error::Operator)?;
 
+    let request_spatial_ref = extent.spatial_reference;
+    let query_bbox = extent.bbox;
+    let x_query_resolution = query_bbox.size_x() / f64::from(extent.width);
+    let y_query_resolution = query_bbox.size_y() / f64::from(extent.height);
+
     // TODO: use correct session when WMS uses authenticated access
     let execution_context = ctx.execution_context(C::Session::mock())?;
 
+    // align the engine's pixel grid with the requested image to avoid resampling
+    let tiling_specification = execution_context.tiling_specification().aligned_to(
+        query_bbox.upper_left(),
+        x_query_resolution,
+        y_query_resolution,
+    );
+    let execution_context =
+        TilingSpecificationOverride::new(&execution_context, tiling_specification);
+
     let initialized = operator
         .clone()
         .initialize(&execution_context)
         .await
         .context(error::Operator)?;
 
+    // the tile archive only contains tiles of single band rasters
+    let is_multiband = !initialized.result_descriptor().bands.is_empty();
+
+    let (operator, initialized) =
+        select_band(operator, initialized, band, &execution_context).await?;
+
     // handle request and workflow crs matching
     let workflow_spatial_ref: Option<SpatialReference> =
         initialized.result_descriptor().spatial_reference().into();
     let workflow_spatial_ref = workflow_spatial_ref.ok_or(error::Error::InvalidSpatialReference)?;
 
-    // TODO: use a default spatial reference if it is not set?
-    let request_spatial_ref: SpatialReference =
-        request.crs.ok_or(error::Error::InvalidSpatialReference)?;
-
     // perform reprojection if necessary
     let initialized = if request_spatial_ref == workflow_spatial_ref {
         initialized
@@ -237,6 +458,8 @@ async fn get_map<C: Context>(
         let proj = Reprojection {
             params: ReprojectionParams {
                 target_spatial_reference: request_spatial_ref,
+                resampling: ResamplingMethod::Nearest,
+                transformation: None,
             },
             sources: operator.into(),
         };
@@ -251,15 +474,22 @@ async fn get_map<C: Context>(
     let no_data_value: Option<f64> = initialized.result_descriptor().no_data_value;
 
     let processor = initialized.query_processor().context(error::Operator)?;
-
-    // TODO: use proj for determining axis order
-    let query_bbox: SpatialPartition2D = request.bbox.bounds(request_spatial_ref)?;
-    let x_query_resolution = query_bbox.size_x() / f64::from(request.width);
-    let y_query_resolution = query_bbox.size_y() / f64::from(request.height);
+    let processor = if is_multiband {
+        processor
+    } else {
+        TileArchive::from_config()?
+            .processor(
+                *workflow_id,
+                request_spatial_ref,
+                tiling_specification,
+                processor,
+            )
+            .await?
+    };
 
     let query_rect = RasterQueryRectangle {
         spatial_bounds: query_bbox,
-        time_interval: request.time.unwrap_or_else(|| {
+        time_interval: extent.time.unwrap_or_else(|| {
             let time = TimeInstance::from(chrono::offset::Utc::now());
             TimeInterval::new_unchecked(time, time)
         }),
@@ -269,22 +499,67 @@ async fn get_map<C: Context>(
         ),
     };
 
-    let query_ctx = ctx.query_context()?;
+    let mut query_ctx = ctx.query_context()?;
+    query_ctx
+        .extensions_mut()
+        .insert(QueryPriority::Interactive);
 
-    let colorizer = colorizer_from_style(&request.styles)?;
+    let query_timeout = QueryTimeout::for_handler(TimedHandler::Wms)?;
+    query_timeout.attach(&mut query_ctx);
 
-    let image_bytes = call_on_generic_raster_processor!(
-        processor,
-        p =>
-            raster_stream_to_png_bytes(p, query_rect, query_ctx, request.width, request.height, request.time, colorizer, no_data_value.map(AsPrimitive::as_)).await
-    ).map_err(error::Error::from)?;
+    let _admission = ctx.admission_control().admit(query_cost(&query_rect))?;
 
-    Ok(Box::new(
-        Response::builder()
-            .header("Content-Type", "image/png")
-            .body(image_bytes)
-            .context(error::Http)?,
-    ))
+    slow_query.query_started(&query_rect);
+
+    let image_bytes = query_timeout
+        .run(async {
+            let _permit = ctx
+                .query_scheduler()
+                .acquire(QueryPriority::Interactive)
+                .await;
+
+            Ok(call_on_generic_raster_processor!(
+                processor,
+                p =>
+                    raster_stream_to_png_bytes(p, query_rect, query_ctx, extent.width, extent.height, extent.time, colorizer, no_data_value.map(AsPrimitive::as_)).await
+            )?)
+        })
+        .await?;
+
+    slow_query.finish();
+
+    Ok(image_bytes)
+}
+
+/// Selects the `band` or the first band of multiband rasters, while single band rasters are returned unmodified
+pub(crate) async fn select_band(
+    operator: Box<dyn RasterOperator>,
+    initialized: Box<dyn InitializedRasterOperator>,
+    band: Option<&str>,
+    execution_context: &dyn ExecutionContext,
+) -> Result<(Box<dyn RasterOperator>, Box<dyn InitializedRasterOperator>)> {
+    if initialized.result_descriptor().bands.is_empty() {
+        return Ok((operator, initialized));
+    }
+
+    let operator = BandSelection {
+        params: BandSelectionParams {
+            bands: vec![band.map_or(BandSelector::Index(0), |band| {
+                BandSelector::Name(band.to_string())
+            })],
+        },
+        sources: SingleRasterSource { raster: operator },
+    }
+    .boxed();
+
+    // TODO: avoid re-initialization of the whole operator graph
+    let initialized = operator
+        .clone()
+        .initialize(execution_context)
+        .await
+        .context(error::Operator)?;
+
+    Ok((operator, initialized))
 }
 
 fn colorizer_from_style(styles: &str) -> Result<Option<Colorizer>> {
@@ -305,7 +580,7 @@ fn get_legend_graphic<C: Context>(
     ))
 }
 
-fn get_map_mock(request: &GetMap) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+fn get_map_mock(extent: &MapExtent) -> Result<Vec<u8>> {
     let raster = Grid2D::new(
         [2, 2].into(),
         vec![
@@ -319,16 +594,9 @@ fn get_map_mock(request: &GetMap) -> Result<Box<dyn warp::Reply>, warp::Rejectio
     .context(error::DataType)?;
 
     let colorizer = Colorizer::rgba();
-    let image_bytes = raster
-        .to_png(request.width, request.height, &colorizer)
-        .context(error::DataType)?;
-
-    Ok(Box::new(
-        Response::builder()
-            .header("Content-Type", "image/png")
-            .body(image_bytes)
-            .context(error::Http)?,
-    ))
+    raster
+        .to_png(extent.width, extent.height, &colorizer)
+        .context(error::DataType)
 }
 
 #[cfg(test)]
@@ -336,13 +604,19 @@ mod tests {
     use super::*;
     use crate::contexts::{InMemoryContext, SimpleSession};
     use crate::handlers::{handle_rejection, ErrorResponse};
-    use crate::util::tests::{check_allowed_http_methods, register_ndvi_workflow_helper};
+    use crate::util::tests::{
+        add_ndvi_to_datasets, check_allowed_http_methods, register_ndvi_workflow_helper,
+    };
+    use crate::workflows::workflow::Workflow;
     use geoengine_datatypes::operations::image::RgbaColor;
     use geoengine_datatypes::primitives::SpatialPartition2D;
+    use geoengine_datatypes::util::test::{assert_image_eq, ImageTolerance};
     use geoengine_operators::engine::{
-        ExecutionContext, RasterQueryProcessor, RasterQueryRectangle,
+        ExecutionContext, MultipleRasterSources, RasterQueryProcessor, RasterQueryRectangle,
+        TypedOperator,
     };
-    use geoengine_operators::source::GdalSourceProcessor;
+    use geoengine_operators::processing::{RasterStacker, RasterStackerBand, RasterStackerParams};
+    use geoengine_operators::source::{GdalSource, GdalSourceParameters, GdalSourceProcessor};
     use geoengine_operators::util::gdal::create_ndvi_meta_data;
     use std::convert::TryInto;
     use warp::hyper::body::Bytes;
@@ -363,9 +637,10 @@ mod tests {
         let res = test_test_helper("GET", None).await;
 
         assert_eq!(res.status(), 200);
-        assert_eq!(
-            include_bytes!("../../../datatypes/test-data/colorizer/rgba.png") as &[u8],
-            res.body().to_vec().as_slice()
+        assert_image_eq(
+            include_bytes!("../../../datatypes/test-data/colorizer/rgba.png"),
+            res.body().to_vec().as_slice(),
+            ImageTolerance::default(),
         );
     }
 
@@ -459,9 +734,10 @@ mod tests {
         .await
         .unwrap();
 
-        assert_eq!(
-            include_bytes!("../../../services/test-data/wms/raster_small.png") as &[u8],
-            image_bytes.as_slice()
+        assert_image_eq(
+            include_bytes!("../../../services/test-data/wms/raster_small.png"),
+            image_bytes.as_slice(),
+            ImageTolerance::default(),
         );
     }
 
@@ -483,8 +759,13 @@ mod tests {
 
         assert_eq!(res.status(), 200);
         assert_eq!(
-            include_bytes!("../../../services/test-data/wms/get_map.png") as &[u8],
-            res.body().to_vec().as_slice()
+            res.headers()[ATTRIBUTION_HEADER],
+            "Sample Citation (Sample License)"
+        );
+        assert_image_eq(
+            include_bytes!("../../../services/test-data/wms/get_map.png"),
+            res.body().to_vec().as_slice(),
+            ImageTolerance::default(),
         );
     }
 
@@ -502,9 +783,10 @@ mod tests {
 
         assert_eq!(response.status(), 200, "{:?}", response.body());
 
-        assert_eq!(
-            include_bytes!("../../../services/test-data/wms/get_map_ndvi.png") as &[u8],
-            response.body().to_vec().as_slice()
+        assert_image_eq(
+            include_bytes!("../../../services/test-data/wms/get_map_ndvi.png"),
+            response.body().to_vec().as_slice(),
+            ImageTolerance::default(),
         );
     }
 
@@ -521,9 +803,10 @@ mod tests {
             .await;
 
         assert_eq!(res.status(), 200);
-        assert_eq!(
-            include_bytes!("../../../services/test-data/wms/get_map.png") as &[u8],
-            res.body().to_vec().as_slice()
+        assert_image_eq(
+            include_bytes!("../../../services/test-data/wms/get_map.png"),
+            res.body().to_vec().as_slice(),
+            ImageTolerance::default(),
         );
     }
 
@@ -587,9 +870,136 @@ mod tests {
             .await;
 
         assert_eq!(res.status(), 200);
+        assert_image_eq(
+            include_bytes!("../../../services/test-data/wms/get_map_colorizer.png"),
+            res.body().to_vec().as_slice(),
+            ImageTolerance::default(),
+        );
+    }
+
+    #[tokio::test]
+    async fn get_map_multiple_layers() {
+        let ctx = InMemoryContext::default();
+
+        let (_, id) = register_ndvi_workflow_helper(&ctx).await;
+
+        let res = warp::test::request()
+            .method("GET")
+            .path(&format!("/wms?request=GetMap&service=WMS&version=1.3.0&layers={},mock_raster&bbox=20,-10,80,50&width=600&height=600&crs=EPSG:4326&styles=,&opacity=1,0.5&format=image/png&time=2014-01-01T00:00:00.0Z", id.to_string()))
+            .reply(&wms_handler(ctx).recover(handle_rejection))
+            .await;
+
+        assert_eq!(res.status(), 200, "{:?}", res.body());
+
+        let image = image::load_from_memory_with_format(res.body(), image::ImageFormat::Png)
+            .unwrap()
+            .to_rgba8();
+        assert_eq!(image.dimensions(), (600, 600));
+    }
+
+    #[tokio::test]
+    async fn get_map_layer_parameter_mismatch() {
+        let res = get_map_test_helper("GET", Some("/wms?request=GetMap&service=WMS&version=1.3.0&layers=mock_raster,mock_raster&bbox=20,-10,80,50&width=600&height=600&crs=EPSG:4326&styles=&opacity=0.5&format=image/png&time=2014-01-01T00:00:00.0Z")).await;
+
+        ErrorResponse::assert(
+            &res,
+            400,
+            "WmsLayerParameterMismatch",
+            "The opacities of a WMS request must match its 2 layers",
+        );
+    }
+
+    async fn register_stacked_ndvi_workflow(ctx: &InMemoryContext) -> WorkflowId {
+        let dataset = add_ndvi_to_datasets(ctx).await;
+
+        let ndvi = || {
+            GdalSource {
+                params: GdalSourceParameters {
+                    dataset: dataset.clone(),
+                },
+            }
+            .boxed()
+        };
+
+        let band = |name: &str| RasterStackerBand {
+            name: name.to_string(),
+            measurement: None,
+        };
+
+        let workflow = Workflow {
+            operator: TypedOperator::Raster(
+                RasterStacker {
+                    params: RasterStackerParams {
+                        bands: vec![band("first"), band("second")],
+                    },
+                    sources: MultipleRasterSources {
+                        rasters: vec![ndvi(), ndvi()],
+                    },
+                }
+                .boxed(),
+            ),
+        };
+
+        ctx.workflow_registry()
+            .write()
+            .await
+            .register(workflow)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn get_map_band() {
+        let ctx = InMemoryContext::default();
+
+        let id = register_stacked_ndvi_workflow(&ctx).await;
+
+        for band in ["", "&DIM_BAND=second"] {
+            let res = warp::test::request()
+                .method("GET")
+                .path(&format!("/wms?request=GetMap&service=WMS&version=1.3.0&layers={}&bbox=20,-10,80,50&width=600&height=600&crs=EPSG:4326&styles=ssss&format=image/png&time=2014-01-01T00:00:00.0Z{}", id.to_string(), band))
+                .reply(&wms_handler(ctx.clone()).recover(handle_rejection))
+                .await;
+
+            assert_eq!(res.status(), 200, "{:?}", res.body());
+            assert_image_eq(
+                include_bytes!("../../../services/test-data/wms/get_map.png"),
+                res.body().to_vec().as_slice(),
+                ImageTolerance::default(),
+            );
+        }
+
+        let res = warp::test::request()
+            .method("GET")
+            .path(&format!("/wms?request=GetMap&service=WMS&version=1.3.0&layers={}&bbox=20,-10,80,50&width=600&height=600&crs=EPSG:4326&styles=ssss&format=image/png&time=2014-01-01T00:00:00.0Z&DIM_BAND=third", id.to_string()))
+            .reply(&wms_handler(ctx).recover(handle_rejection))
+            .await;
+
+        assert_eq!(res.status(), 400);
+        let body: serde_json::Value = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(body["error"], "Operator");
+    }
+
+    #[test]
+    fn it_splits_layer_styles() {
+        assert_eq!(layer_styles("", 2).unwrap(), vec!["", ""]);
+        assert_eq!(layer_styles("ssss", 1).unwrap(), vec!["ssss"]);
         assert_eq!(
-            include_bytes!("../../../services/test-data/wms/get_map_colorizer.png") as &[u8],
-            res.body().to_vec().as_slice()
+            layer_styles(r#"custom:{"a":[1,2],"b":"x,\"}"},"#, 2).unwrap(),
+            vec![r#"custom:{"a":[1,2],"b":"x,\"}"}"#, ""]
+        );
+        assert!(layer_styles("ssss", 2).is_err());
+    }
+
+    #[tokio::test]
+    async fn get_map_basemap_requires_configuration() {
+        let res = get_map_test_helper("GET", Some("/wms?request=GetMap&service=WMS&version=1.3.0&layers=mock_raster&bbox=20,-10,80,50&width=600&height=600&crs=EPSG:3857&styles=&basemap=true&format=image/png")).await;
+
+        ErrorResponse::assert(
+            &res,
+            400,
+            "BasemapNotConfigured",
+            "There is no basemap configured",
         );
     }
 }
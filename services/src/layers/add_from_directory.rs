@@ -0,0 +1,97 @@
+use std::{
+    fs::{self, DirEntry, File},
+    io::BufReader,
+    path::PathBuf,
+};
+
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::util::user_input::UserInput;
+
+use super::layer::{AddLayer, AddLayerCollection, LayerCollectionId};
+use super::storage::LayerDb;
+
+/// A collection together with its (nested) items as it is curated in a definition file
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LayerCollectionDefinition {
+    #[serde(flatten)]
+    pub collection: AddLayerCollection,
+    #[serde(default)]
+    pub items: Vec<LayerCollectionDefinitionItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum LayerCollectionDefinitionItem {
+    Collection(LayerCollectionDefinition),
+    Layer(AddLayer),
+}
+
+/// Adds the collection tree of every definition file in the directory as a root collection
+pub async fn add_layer_collections_from_directory<L: LayerDb>(db: &mut L, file_path: PathBuf) {
+    async fn add_collection_definition_from_dir_entry<L: LayerDb>(
+        db: &mut L,
+        entry: &DirEntry,
+    ) -> Result<()> {
+        let def: LayerCollectionDefinition =
+            serde_json::from_reader(BufReader::new(File::open(entry.path())?))?;
+
+        add_collection_definition(db, def).await?;
+
+        Ok(())
+    }
+
+    let dir = fs::read_dir(file_path);
+    if dir.is_err() {
+        warn!("Skipped adding layer collections from directory because it can't be read");
+        return;
+    }
+    let dir = dir.expect("checked");
+
+    for entry in dir {
+        if let Ok(entry) = entry {
+            if let Err(e) = add_collection_definition_from_dir_entry(db, &entry).await {
+                warn!(
+                    "Skipped adding layer collection from directory entry: {:?} error: {}",
+                    entry,
+                    e.to_string()
+                );
+            }
+        } else {
+            warn!(
+                "Skipped adding layer collection from directory entry: {:?}",
+                entry
+            );
+        }
+    }
+}
+
+fn add_collection_definition<L: LayerDb>(
+    db: &mut L,
+    def: LayerCollectionDefinition,
+) -> BoxFuture<Result<LayerCollectionId>> {
+    async move {
+        let collection = db.add_collection(def.collection.validated()?).await?;
+
+        for item in def.items {
+            match item {
+                LayerCollectionDefinitionItem::Collection(sub_collection) => {
+                    let sub_collection = add_collection_definition(db, sub_collection).await?;
+                    db.add_collection_to_parent(sub_collection, collection)
+                        .await?;
+                }
+                LayerCollectionDefinitionItem::Layer(layer) => {
+                    let layer = db.add_layer(layer.validated()?).await?;
+                    db.add_layer_to_collection(layer, collection).await?;
+                }
+            }
+        }
+
+        Ok(collection)
+    }
+    .boxed()
+}
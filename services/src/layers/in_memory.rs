@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use snafu::ensure;
+
+use geoengine_datatypes::util::Identifier;
+
+use crate::error::{self, Result};
+use crate::util::user_input::Validated;
+
+use super::layer::{
+    AddLayer, AddLayerCollection, CollectionItem, Layer, LayerCollection, LayerCollectionId,
+    LayerCollectionListOptions, LayerCollectionListing, LayerId, LayerListing,
+};
+use super::storage::LayerDb;
+
+#[derive(Default)]
+pub struct HashMapLayerDb {
+    layers: HashMap<LayerId, Layer>,
+    collections: HashMap<LayerCollectionId, AddLayerCollection>,
+    /// all collections in insertion order to keep the listings stable
+    collection_order: Vec<LayerCollectionId>,
+    collection_children: HashMap<LayerCollectionId, Vec<LayerCollectionId>>,
+    collection_layers: HashMap<LayerCollectionId, Vec<LayerId>>,
+}
+
+impl HashMapLayerDb {
+    fn collection_listing(&self, id: LayerCollectionId) -> Result<LayerCollectionListing> {
+        let collection = self
+            .collections
+            .get(&id)
+            .ok_or(error::Error::UnknownLayerCollectionId)?;
+
+        Ok(LayerCollectionListing {
+            id,
+            name: collection.name.clone(),
+            description: collection.description.clone(),
+            thumbnail: collection.thumbnail.clone(),
+        })
+    }
+
+    fn is_descendant(&self, collection: LayerCollectionId, ancestor: LayerCollectionId) -> bool {
+        let mut stack = vec![ancestor];
+
+        while let Some(current) = stack.pop() {
+            if current == collection {
+                return true;
+            }
+
+            if let Some(children) = self.collection_children.get(&current) {
+                stack.extend(children.iter().copied());
+            }
+        }
+
+        false
+    }
+}
+
+#[async_trait]
+impl LayerDb for HashMapLayerDb {
+    async fn add_layer(&mut self, layer: Validated<AddLayer>) -> Result<LayerId> {
+        let layer = layer.user_input;
+        let id = LayerId::new();
+
+        self.layers.insert(
+            id,
+            Layer {
+                id,
+                name: layer.name,
+                description: layer.description,
+                workflow: layer.workflow,
+                symbology: layer.symbology,
+                metadata: layer.metadata,
+                thumbnail: layer.thumbnail,
+            },
+        );
+
+        Ok(id)
+    }
+
+    async fn add_collection(
+        &mut self,
+        collection: Validated<AddLayerCollection>,
+    ) -> Result<LayerCollectionId> {
+        let id = LayerCollectionId::new();
+
+        self.collections.insert(id, collection.user_input);
+        self.collection_order.push(id);
+
+        Ok(id)
+    }
+
+    async fn add_layer_to_collection(
+        &mut self,
+        layer: LayerId,
+        collection: LayerCollectionId,
+    ) -> Result<()> {
+        ensure!(self.layers.contains_key(&layer), error::UnknownLayerId);
+        ensure!(
+            self.collections.contains_key(&collection),
+            error::UnknownLayerCollectionId
+        );
+
+        let layers = self.collection_layers.entry(collection).or_default();
+        if !layers.contains(&layer) {
+            layers.push(layer);
+        }
+
+        Ok(())
+    }
+
+    async fn add_collection_to_parent(
+        &mut self,
+        collection: LayerCollectionId,
+        parent: LayerCollectionId,
+    ) -> Result<()> {
+        ensure!(
+            self.collections.contains_key(&collection) && self.collections.contains_key(&parent),
+            error::UnknownLayerCollectionId
+        );
+        ensure!(
+            !self.is_descendant(parent, collection),
+            error::InvalidLayerCollectionHierarchy
+        );
+
+        let children = self.collection_children.entry(parent).or_default();
+        if !children.contains(&collection) {
+            children.push(collection);
+        }
+
+        Ok(())
+    }
+
+    async fn get_layer(&self, id: LayerId) -> Result<Layer> {
+        self.layers
+            .get(&id)
+            .cloned()
+            .ok_or(error::Error::UnknownLayerId)
+    }
+
+    async fn get_collection(
+        &self,
+        id: LayerCollectionId,
+        options: Validated<LayerCollectionListOptions>,
+    ) -> Result<LayerCollection> {
+        let options = options.user_input;
+
+        let collection = self
+            .collections
+            .get(&id)
+            .ok_or(error::Error::UnknownLayerCollectionId)?;
+
+        let no_children = Vec::new();
+        let children = self.collection_children.get(&id).unwrap_or(&no_children);
+        let no_layers = Vec::new();
+        let layers = self.collection_layers.get(&id).unwrap_or(&no_layers);
+
+        let items = children
+            .iter()
+            .map(|child| {
+                self.collection_listing(*child)
+                    .map(CollectionItem::Collection)
+            })
+            .chain(layers.iter().map(|layer| {
+                self.layers
+                    .get(layer)
+                    .map(|layer| CollectionItem::Layer(LayerListing::from(layer)))
+                    .ok_or(error::Error::UnknownLayerId)
+            }))
+            .skip(options.offset as usize)
+            .take(options.limit as usize)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(LayerCollection {
+            id,
+            name: collection.name.clone(),
+            description: collection.description.clone(),
+            metadata: collection.metadata.clone(),
+            thumbnail: collection.thumbnail.clone(),
+            items,
+        })
+    }
+
+    async fn root_collection_items(
+        &self,
+        options: Validated<LayerCollectionListOptions>,
+    ) -> Result<Vec<CollectionItem>> {
+        let options = options.user_input;
+
+        self.collection_order
+            .iter()
+            .filter(|id| {
+                !self
+                    .collection_children
+                    .values()
+                    .any(|children| children.contains(id))
+            })
+            .skip(options.offset as usize)
+            .take(options.limit as usize)
+            .map(|id| self.collection_listing(*id).map(CollectionItem::Collection))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::user_input::UserInput;
+
+    fn add_collection(name: &str) -> Validated<AddLayerCollection> {
+        AddLayerCollection {
+            name: name.to_string(),
+            description: String::new(),
+            metadata: Default::default(),
+            thumbnail: None,
+        }
+        .validated()
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn it_rejects_cycles() {
+        let mut db = HashMapLayerDb::default();
+
+        let a = db.add_collection(add_collection("A")).await.unwrap();
+        let b = db.add_collection(add_collection("B")).await.unwrap();
+        let c = db.add_collection(add_collection("C")).await.unwrap();
+
+        db.add_collection_to_parent(b, a).await.unwrap();
+        db.add_collection_to_parent(c, b).await.unwrap();
+
+        assert!(db.add_collection_to_parent(a, c).await.is_err());
+        assert!(db.add_collection_to_parent(a, a).await.is_err());
+
+        let root = db
+            .root_collection_items(
+                LayerCollectionListOptions {
+                    offset: 0,
+                    limit: 10,
+                }
+                .validated()
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            root,
+            vec![CollectionItem::Collection(LayerCollectionListing {
+                id: a,
+                name: "A".to_string(),
+                description: String::new(),
+                thumbnail: None,
+            })]
+        );
+    }
+}
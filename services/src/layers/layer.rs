@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use geoengine_datatypes::identifier;
+
+use crate::error::{self, Result};
+use crate::projects::Symbology;
+use crate::util::config::{get_config_element, LayerService};
+use crate::util::user_input::UserInput;
+use crate::workflows::workflow::Workflow;
+
+identifier!(LayerId);
+identifier!(LayerCollectionId);
+
+/// A stored workflow together with everything a client needs to present it to end-users
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Layer {
+    pub id: LayerId,
+    pub name: String,
+    pub description: String,
+    pub workflow: Workflow,
+    pub symbology: Option<Symbology>,
+    /// free-form meta data like the source or the license of the data
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    /// the url of a preview image
+    pub thumbnail: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AddLayer {
+    pub name: String,
+    pub description: String,
+    pub workflow: Workflow,
+    pub symbology: Option<Symbology>,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    pub thumbnail: Option<String>,
+}
+
+impl UserInput for AddLayer {
+    fn validate(&self) -> Result<()> {
+        validate_name(&self.name)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LayerListing {
+    pub id: LayerId,
+    pub name: String,
+    pub description: String,
+    pub thumbnail: Option<String>,
+}
+
+impl From<&Layer> for LayerListing {
+    fn from(layer: &Layer) -> Self {
+        Self {
+            id: layer.id,
+            name: layer.name.clone(),
+            description: layer.description.clone(),
+            thumbnail: layer.thumbnail.clone(),
+        }
+    }
+}
+
+/// A node of the layer catalog that contains layers and other collections
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LayerCollection {
+    pub id: LayerCollectionId,
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    pub thumbnail: Option<String>,
+    /// a page of the items of the collection
+    pub items: Vec<CollectionItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AddLayerCollection {
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    pub thumbnail: Option<String>,
+}
+
+impl UserInput for AddLayerCollection {
+    fn validate(&self) -> Result<()> {
+        validate_name(&self.name)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LayerCollectionListing {
+    pub id: LayerCollectionId,
+    pub name: String,
+    pub description: String,
+    pub thumbnail: Option<String>,
+}
+
+impl From<&LayerCollection> for LayerCollectionListing {
+    fn from(collection: &LayerCollection) -> Self {
+        Self {
+            id: collection.id,
+            name: collection.name.clone(),
+            description: collection.description.clone(),
+            thumbnail: collection.thumbnail.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum CollectionItem {
+    Collection(LayerCollectionListing),
+    Layer(LayerListing),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LayerCollectionListOptions {
+    pub offset: u32,
+    pub limit: u32,
+}
+
+impl UserInput for LayerCollectionListOptions {
+    fn validate(&self) -> Result<()> {
+        let limit = get_config_element::<LayerService>()?.list_limit;
+        ensure!(
+            self.limit <= limit,
+            error::InvalidListLimit {
+                limit: limit as usize
+            }
+        );
+
+        Ok(())
+    }
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    ensure!(
+        !name.is_empty() && name.len() <= 256,
+        error::InvalidStringLength {
+            parameter: "name".to_string(),
+            min: 1_usize,
+            max: 256_usize
+        }
+    );
+
+    Ok(())
+}
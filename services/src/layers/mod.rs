@@ -0,0 +1,4 @@
+pub mod add_from_directory;
+pub mod in_memory;
+pub mod layer;
+pub mod storage;
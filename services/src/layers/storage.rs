@@ -0,0 +1,49 @@
+use async_trait::async_trait;
+
+use crate::error::Result;
+use crate::util::user_input::Validated;
+
+use super::layer::{
+    AddLayer, AddLayerCollection, CollectionItem, Layer, LayerCollection, LayerCollectionId,
+    LayerCollectionListOptions, LayerId,
+};
+
+/// Storage of the layer catalog, i.e., a hierarchy of collections that contain layers
+#[async_trait]
+pub trait LayerDb: Send + Sync {
+    async fn add_layer(&mut self, layer: Validated<AddLayer>) -> Result<LayerId>;
+
+    async fn add_collection(
+        &mut self,
+        collection: Validated<AddLayerCollection>,
+    ) -> Result<LayerCollectionId>;
+
+    /// Adds an existing layer to a collection. A layer may be part of multiple collections.
+    async fn add_layer_to_collection(
+        &mut self,
+        layer: LayerId,
+        collection: LayerCollectionId,
+    ) -> Result<()>;
+
+    /// Makes `collection` a sub collection of `parent`. Fails if this would introduce a cycle.
+    async fn add_collection_to_parent(
+        &mut self,
+        collection: LayerCollectionId,
+        parent: LayerCollectionId,
+    ) -> Result<()>;
+
+    async fn get_layer(&self, id: LayerId) -> Result<Layer>;
+
+    /// Loads a collection with a page of its items. Sub collections are listed before layers.
+    async fn get_collection(
+        &self,
+        id: LayerCollectionId,
+        options: Validated<LayerCollectionListOptions>,
+    ) -> Result<LayerCollection>;
+
+    /// Lists the collections that are not part of any other collection
+    async fn root_collection_items(
+        &self,
+        options: Validated<LayerCollectionListOptions>,
+    ) -> Result<Vec<CollectionItem>>;
+}
@@ -25,14 +25,19 @@
 // TODO: re-activate when https://github.com/rust-lang/rust-clippy/issues/7438 is fixed
 #![allow(clippy::semicolon_if_nothing_returned)]
 
+pub mod aois;
+pub mod basemap;
 pub mod contexts;
 pub mod datasets;
 pub mod error;
 pub mod handlers;
+pub mod layers;
 pub mod ogc;
 pub mod projects;
+pub mod reports;
 pub mod server;
 pub mod stac;
+pub mod tile_archive;
 #[macro_use]
 pub mod util;
 pub mod workflows;
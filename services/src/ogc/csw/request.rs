@@ -0,0 +1,165 @@
+use crate::util::from_str_option;
+use serde::{Deserialize, Serialize};
+
+// TODO: ignore case for field names
+
+/// CSW 2.0.2 with the key-value-pair encoding
+#[derive(PartialEq, Debug, Deserialize, Serialize)]
+#[serde(tag = "request")]
+pub enum CswRequest {
+    GetCapabilities(GetCapabilities),
+    GetRecords(GetRecords),
+    GetRecordById(GetRecordById),
+}
+
+// sample: SERVICE=CSW&request=GetCapabilities&ACCEPTVERSIONS=2.0.2
+#[derive(PartialEq, Debug, Deserialize, Serialize)]
+pub struct GetCapabilities {
+    #[serde(alias = "ACCEPTVERSIONS", alias = "acceptVersions")]
+    pub acceptversions: Option<String>,
+}
+
+// sample: SERVICE=CSW&VERSION=2.0.2&request=GetRecords&TYPENAMES=csw:Record&ELEMENTSETNAME=full&RESULTTYPE=results&STARTPOSITION=1&MAXRECORDS=10
+#[derive(PartialEq, Debug, Deserialize, Serialize)]
+pub struct GetRecords {
+    #[serde(alias = "VERSION")]
+    pub version: String,
+    #[serde(alias = "TYPENAMES", alias = "typeNames")]
+    pub typenames: Option<String>,
+    #[serde(alias = "ELEMENTSETNAME", alias = "elementSetName")]
+    pub elementsetname: Option<ElementSetName>,
+    #[serde(alias = "OUTPUTSCHEMA", alias = "outputSchema")]
+    pub outputschema: Option<OutputSchema>,
+    #[serde(alias = "RESULTTYPE", alias = "resultType")]
+    pub resulttype: Option<ResultType>,
+    /// the 1-based position of the first record to return
+    #[serde(default)]
+    #[serde(alias = "STARTPOSITION", alias = "startPosition")]
+    #[serde(deserialize_with = "from_str_option")]
+    pub startposition: Option<u32>,
+    #[serde(default)]
+    #[serde(alias = "MAXRECORDS", alias = "maxRecords")]
+    #[serde(deserialize_with = "from_str_option")]
+    pub maxrecords: Option<u32>,
+    // ignored for now:
+    // CONSTRAINT, CONSTRAINTLANGUAGE: filters on the records
+    // SORTBY: records are always sorted by title
+}
+
+// sample: SERVICE=CSW&VERSION=2.0.2&request=GetRecordById&ID=9c874b9e-cea0-4553-b727-a13cb26ae4bb&ELEMENTSETNAME=full
+#[derive(PartialEq, Debug, Deserialize, Serialize)]
+pub struct GetRecordById {
+    #[serde(alias = "VERSION")]
+    pub version: String,
+    /// comma separated list of record identifiers
+    #[serde(alias = "ID")]
+    pub id: String,
+    #[serde(alias = "ELEMENTSETNAME", alias = "elementSetName")]
+    pub elementsetname: Option<ElementSetName>,
+    #[serde(alias = "OUTPUTSCHEMA", alias = "outputSchema")]
+    pub outputschema: Option<OutputSchema>,
+}
+
+/// The level of detail of the returned records
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ElementSetName {
+    Brief,
+    Summary,
+    Full,
+}
+
+impl Default for ElementSetName {
+    fn default() -> Self {
+        Self::Summary
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Deserialize, Serialize)]
+pub enum OutputSchema {
+    /// Dublin Core records
+    #[serde(rename = "http://www.opengis.net/cat/csw/2.0.2", alias = "csw:Record")]
+    Csw,
+    /// ISO 19115 records in their ISO 19139 encoding
+    #[serde(rename = "http://www.isotc211.org/2005/gmd", alias = "gmd:MD_Metadata")]
+    Iso,
+}
+
+impl Default for OutputSchema {
+    fn default() -> Self {
+        Self::Csw
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResultType {
+    /// only the number of matching records
+    Hits,
+    Results,
+}
+
+impl Default for ResultType {
+    fn default() -> Self {
+        Self::Results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_get_records() {
+        let params = &[
+            ("service", "CSW"),
+            ("request", "GetRecords"),
+            ("version", "2.0.2"),
+            ("typeNames", "csw:Record"),
+            ("elementSetName", "full"),
+            ("outputSchema", "http://www.isotc211.org/2005/gmd"),
+            ("resultType", "results"),
+            ("startPosition", "11"),
+            ("maxRecords", "10"),
+        ];
+        let string = serde_urlencoded::to_string(params).unwrap();
+
+        let request: CswRequest = serde_urlencoded::from_str(&string).unwrap();
+
+        assert_eq!(
+            request,
+            CswRequest::GetRecords(GetRecords {
+                version: "2.0.2".to_string(),
+                typenames: Some("csw:Record".to_string()),
+                elementsetname: Some(ElementSetName::Full),
+                outputschema: Some(OutputSchema::Iso),
+                resulttype: Some(ResultType::Results),
+                startposition: Some(11),
+                maxrecords: Some(10),
+            })
+        );
+    }
+
+    #[test]
+    fn deserialize_get_record_by_id() {
+        let params = &[
+            ("service", "CSW"),
+            ("request", "GetRecordById"),
+            ("version", "2.0.2"),
+            ("id", "a,b"),
+        ];
+        let string = serde_urlencoded::to_string(params).unwrap();
+
+        let request: CswRequest = serde_urlencoded::from_str(&string).unwrap();
+
+        assert_eq!(
+            request,
+            CswRequest::GetRecordById(GetRecordById {
+                version: "2.0.2".to_string(),
+                id: "a,b".to_string(),
+                elementsetname: None,
+                outputschema: None,
+            })
+        );
+    }
+}
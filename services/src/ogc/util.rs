@@ -1,8 +1,9 @@
 use chrono::FixedOffset;
 use geoengine_datatypes::primitives::{AxisAlignedRectangle, BoundingBox2D};
 use geoengine_datatypes::primitives::{Coordinate2D, SpatialResolution, TimeInterval};
+use geoengine_datatypes::raster::GridShape2D;
 use geoengine_datatypes::spatial_reference::SpatialReference;
-use serde::de::Error;
+use serde::de::{Error, IntoDeserializer};
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
 use std::str::FromStr;
@@ -44,6 +45,20 @@ where
     }
 }
 
+/// Parse an optional bbox, format is: "x1,y1,x2,y2"
+pub fn parse_bbox_option<'de, D>(deserializer: D) -> Result<Option<BoundingBox2D>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+
+    if s.is_empty() {
+        return Ok(None);
+    }
+
+    parse_bbox(s.into_deserializer()).map(Some)
+}
+
 /// Parse bbox, format is: "x1,y1,x2,y2"
 pub fn parse_ogc_bbox<'de, D>(deserializer: D) -> Result<OgcBoundingBox, D::Error>
 where
@@ -211,6 +226,78 @@ where
     }
 }
 
+/// Parse an optional coordinate, format is "x,y"
+pub fn parse_coordinate_option<'de, D>(deserializer: D) -> Result<Option<Coordinate2D>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+
+    if s.is_empty() {
+        return Ok(None);
+    }
+
+    parse_coordinate(s.into_deserializer()).map(Some)
+}
+
+/// Parse a list of coordinates, format is "x1,y1;x2,y2;..."
+pub fn parse_coordinates<'de, D>(deserializer: D) -> Result<Vec<Coordinate2D>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+
+    s.split(';')
+        .map(|coordinate| parse_coordinate(coordinate.into_deserializer()))
+        .collect()
+}
+
+/// Parse a list of opacities between 0 and 1, format is "o1,o2,..."
+pub fn parse_opacities_option<'de, D>(deserializer: D) -> Result<Option<Vec<f64>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+
+    if s.is_empty() {
+        return Ok(None);
+    }
+
+    s.split(',')
+        .map(|opacity| match opacity.parse::<f64>() {
+            Ok(opacity) if (0. ..=1.).contains(&opacity) => Ok(opacity),
+            _ => Err(D::Error::custom(format!("Invalid opacity: {}", opacity))),
+        })
+        .collect::<Result<Vec<f64>, D::Error>>()
+        .map(Some)
+}
+
+/// Parse a tile size in pixels, format is: "size" or "xSize,ySize"
+pub fn parse_tile_size_option<'de, D>(deserializer: D) -> Result<Option<GridShape2D>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+
+    if s.is_empty() {
+        return Ok(None);
+    }
+
+    let split: Vec<Result<usize, std::num::ParseIntError>> = s.split(',').map(str::parse).collect();
+
+    let (x_size, y_size) = match *split.as_slice() {
+        [Ok(size)] => (size, size),
+        [Ok(x_size), Ok(y_size)] => (x_size, y_size),
+        _ => return Err(D::Error::custom("Invalid tile size")),
+    };
+
+    if x_size == 0 || y_size == 0 {
+        return Err(D::Error::custom("Tile size must be positive"));
+    }
+
+    Ok(Some([y_size, x_size].into()))
+}
+
 /// create an axis aligned rectangle using the values "a,b,c,d" from OGC bbox-like parameters using the axis ordering for `spatial_reference`
 pub fn rectangle_from_ogc_params<A: AxisAlignedRectangle>(
     values: [f64; 4],
@@ -386,4 +473,42 @@ mod tests {
             Coordinate2D::new(1.1, 2.2)
         );
     }
+
+    #[test]
+    fn it_parses_coordinates() {
+        assert_eq!(
+            parse_coordinates(to_deserializer("1.1,2.2;-3,4")).unwrap(),
+            vec![Coordinate2D::new(1.1, 2.2), Coordinate2D::new(-3., 4.)]
+        );
+
+        assert!(parse_coordinates(to_deserializer("1.1,2.2;")).is_err());
+        assert!(parse_coordinates(to_deserializer("")).is_err());
+    }
+
+    #[test]
+    fn it_parses_opacities() {
+        assert_eq!(
+            parse_opacities_option(to_deserializer("1,0.5,0")).unwrap(),
+            Some(vec![1., 0.5, 0.])
+        );
+        assert_eq!(parse_opacities_option(to_deserializer("")).unwrap(), None);
+
+        assert!(parse_opacities_option(to_deserializer("1,1.5")).is_err());
+        assert!(parse_opacities_option(to_deserializer("1,")).is_err());
+    }
+
+    #[test]
+    fn it_parses_tile_size_options() {
+        assert_eq!(
+            parse_tile_size_option(to_deserializer("512")).unwrap(),
+            Some([512, 512].into())
+        );
+        assert_eq!(
+            parse_tile_size_option(to_deserializer("256,128")).unwrap(),
+            Some([128, 256].into())
+        );
+        assert_eq!(parse_tile_size_option(to_deserializer("")).unwrap(), None);
+        assert!(parse_tile_size_option(to_deserializer("0")).is_err());
+        assert!(parse_tile_size_option(to_deserializer("1.5")).is_err());
+    }
 }
@@ -1,10 +1,11 @@
 use crate::error::{self, Result};
 use crate::ogc::util::{
-    parse_time_option, parse_wcs_bbox, parse_wcs_crs, rectangle_from_ogc_params,
-    tuple_from_ogc_params,
+    parse_coordinate_option, parse_tile_size_option, parse_time_option, parse_wcs_bbox,
+    parse_wcs_crs, rectangle_from_ogc_params, tuple_from_ogc_params,
 };
 use crate::util::from_str_option;
 use geoengine_datatypes::primitives::{Coordinate2D, SpatialPartition2D, SpatialResolution};
+use geoengine_datatypes::raster::{GridShape2D, TilingSpecification};
 use geoengine_datatypes::{primitives::TimeInterval, spatial_reference::SpatialReference};
 use serde::de::Error;
 use serde::{Deserialize, Serialize};
@@ -64,7 +65,8 @@ pub struct GetCoverage {
     // ignored for now:
     // GRIDCS=crs: The grid CRS (URN).
     // GridType=urn:ogc:def:method:WCS:1.1:2dGridIn2dCrs:
-    // RangeSubset=selection: e.g. bands
+    #[serde(alias = "RANGESUBSET", alias = "RangeSubset")]
+    pub rangesubset: Option<String>,
     #[serde(default)]
     #[serde(deserialize_with = "parse_time_option")]
     #[serde(alias = "timesequence")] // owsLib sends it like this
@@ -77,6 +79,14 @@ pub struct GetCoverage {
     #[serde(default)]
     #[serde(deserialize_with = "from_str_option")]
     resy: Option<f64>,
+
+    // vendor parameters for overriding the tiling of the export
+    #[serde(default)]
+    #[serde(deserialize_with = "parse_tile_size_option")]
+    pub tilesize: Option<GridShape2D>,
+    #[serde(default)]
+    #[serde(deserialize_with = "parse_coordinate_option")]
+    pub tileorigin: Option<Coordinate2D>,
 }
 
 impl GetCoverage {
@@ -95,6 +105,18 @@ impl GetCoverage {
         }
     }
 
+    /// The band of the `RangeSubset`, given either as band name or as `field[axis[band]]`
+    pub fn band(&self) -> Option<&str> {
+        let range_subset = self.rangesubset.as_deref()?;
+
+        let band = match range_subset.rfind('[') {
+            Some(start) => range_subset[start + 1..].trim_end_matches(']'),
+            None => range_subset,
+        };
+
+        Some(band).filter(|band| !band.is_empty())
+    }
+
     pub fn spatial_partition(&self) -> Result<SpatialPartition2D> {
         let spatial_reference = self
             .boundingbox
@@ -103,6 +125,14 @@ impl GetCoverage {
 
         rectangle_from_ogc_params(self.boundingbox.bbox, spatial_reference)
     }
+
+    /// Applies the tile size and origin of the request, if present, to the `default` tiling specification
+    pub fn tiling_specification(&self, default: TilingSpecification) -> TilingSpecification {
+        TilingSpecification {
+            origin_coordinate: self.tileorigin.unwrap_or(default.origin_coordinate),
+            tile_size_in_pixels: self.tilesize.unwrap_or(default.tile_size_in_pixels),
+        }
+    }
 }
 
 #[derive(PartialEq, Debug, Deserialize, Serialize)]
@@ -207,6 +237,7 @@ mod tests {
             ("gridorigin", "81,-162"),
             ("gridoffsets", "-18,36"),
             ("time", "2014-01-01T00:00:00.0Z"),
+            ("tilesize", "1024,512"),
         ];
         let string = serde_urlencoded::to_string(params).unwrap();
 
@@ -227,9 +258,12 @@ mod tests {
                     x_step: -18.,
                     y_step: 36.
                 }),
+                rangesubset: None,
                 time: Some(TimeInterval::new_instant(1_388_534_400_000).unwrap()),
                 resx: None,
-                resy: None
+                resy: None,
+                tilesize: Some([512, 1024].into()),
+                tileorigin: None,
             },
             coverage
         );
@@ -245,6 +279,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_parses_range_subset_bands() {
+        for (range_subset, band) in [
+            ("nir", Some("nir")),
+            ("contents:nearest[bands[nir]]", Some("nir")),
+            ("contents[bands[]]", None),
+        ] {
+            let params = &[
+                ("version", "1.1.1"),
+                ("identifier", "nurc:Arc_Sample"),
+                ("boundingbox", "-81,-162,81,162,urn:ogc:def:crs:EPSG::4326"),
+                ("format", "image/tiff"),
+                ("gridbasecrs", "urn:ogc:def:crs:EPSG::4326"),
+                ("RangeSubset", range_subset),
+            ];
+            let string = serde_urlencoded::to_string(params).unwrap();
+
+            let coverage: GetCoverage = serde_urlencoded::from_str(&string).unwrap();
+
+            assert_eq!(coverage.band(), band);
+        }
+    }
+
     #[test]
     fn it_parses_grid_offset() {
         let s = "-8,5";
@@ -1,4 +1,4 @@
-use crate::ogc::util::{parse_ogc_bbox, parse_time_option, OgcBoundingBox};
+use crate::ogc::util::{parse_ogc_bbox, parse_opacities_option, parse_time_option, OgcBoundingBox};
 use crate::util::{bool_option_case_insensitive, from_str};
 use geoengine_datatypes::primitives::TimeInterval;
 use geoengine_datatypes::spatial_reference::SpatialReference;
@@ -52,6 +52,16 @@ pub struct GetMap {
     pub crs: Option<SpatialReference>,
     #[serde(alias = "STYLES")]
     pub styles: String,
+    /// the opacities of the layers, a vendor-specific extension
+    #[serde(default)]
+    #[serde(alias = "OPACITY")]
+    #[serde(deserialize_with = "parse_opacities_option")]
+    pub opacity: Option<Vec<f64>>,
+    /// whether to place the configured basemap under the layers, a vendor-specific extension
+    #[serde(default)]
+    #[serde(alias = "BASEMAP")]
+    #[serde(deserialize_with = "bool_option_case_insensitive")]
+    pub basemap: Option<bool>,
     #[serde(default)]
     #[serde(alias = "TIME")]
     #[serde(deserialize_with = "parse_time_option")]
@@ -70,7 +80,10 @@ pub struct GetMap {
     pub elevation: Option<String>,
     #[serde(alias = "EXCEPTIONS")]
     pub exceptions: Option<String>, // TODO: parse Option<GetMapExceptionFormat>
-                                    // TODO: DIM_<name>
+    /// The band of multiband layers, which defaults to their first band
+    #[serde(alias = "DIM_BAND")]
+    pub dim_band: Option<String>,
+    // TODO: other DIM_<name>
 }
 
 #[derive(PartialEq, Debug, Deserialize, Serialize)]
@@ -117,7 +130,7 @@ mod tests {
 
     #[test]
     fn deserialize_get_map() {
-        let query = "request=GetMap&service=WMS&version=1.3.0&layers=modis_ndvi&bbox=1,2,3,4&width=2&height=2&crs=EPSG:4326&styles=ssss&format=image/png&time=2000-01-01T00:00:00.0Z/2000-01-02T00:00:00.0Z&transparent=true&bgcolor=#000000&sld=sld_spec&sld_body=sld_body&elevation=elevation&exceptions=exceptions";
+        let query = "request=GetMap&service=WMS&version=1.3.0&layers=modis_ndvi&bbox=1,2,3,4&width=2&height=2&crs=EPSG:4326&styles=ssss&opacity=0.5&basemap=true&format=image/png&time=2000-01-01T00:00:00.0Z/2000-01-02T00:00:00.0Z&transparent=true&bgcolor=#000000&sld=sld_spec&sld_body=sld_body&elevation=elevation&exceptions=exceptions&DIM_BAND=nir";
         let parsed: WmsRequest = serde_urlencoded::from_str(query).unwrap();
 
         let request = WmsRequest::GetMap(GetMap {
@@ -126,6 +139,8 @@ mod tests {
             layers: "modis_ndvi".into(),
             crs: Some(SpatialReference::epsg_4326()),
             styles: "ssss".into(),
+            opacity: Some(vec![0.5]),
+            basemap: Some(true),
             time: Some(TimeInterval::new(946_684_800_000, 946_771_200_000).unwrap()),
             transparent: Some(true),
             bgcolor: Some("#000000".into()),
@@ -136,6 +151,7 @@ mod tests {
             height: 2,
             format: GetMapFormat::ImagePng,
             exceptions: Some("exceptions".into()),
+            dim_band: Some("nir".into()),
         });
 
         assert_eq!(parsed, request);
@@ -152,6 +168,8 @@ mod tests {
             layers: "modis_ndvi".into(),
             crs: SpatialReference::epsg_4326().into(),
             styles: "ssss".into(),
+            opacity: None,
+            basemap: None,
             time: None,
             transparent: None,
             bgcolor: None,
@@ -162,6 +180,7 @@ mod tests {
             height: 2,
             format: GetMapFormat::ImagePng,
             exceptions: None,
+            dim_band: None,
         });
 
         assert_eq!(parsed, request);
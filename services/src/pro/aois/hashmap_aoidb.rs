@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use geoengine_datatypes::util::Identifier;
+
+use crate::aois::aoi::{AddAoi, Aoi, AoiId, AoiListOptions, AoiListing};
+use crate::aois::storage::AoiDb;
+use crate::error::{self, Result};
+use crate::pro::users::{UserId, UserSession};
+use crate::util::user_input::Validated;
+
+/// Stores the areas of interest of all users, who may only access their own areas
+#[derive(Default)]
+pub struct ProHashMapAoiDb {
+    aois: HashMap<AoiId, (UserId, Aoi)>,
+}
+
+#[async_trait]
+impl AoiDb<UserSession> for ProHashMapAoiDb {
+    async fn add(&mut self, session: &UserSession, aoi: Validated<AddAoi>) -> Result<AoiId> {
+        let id = AoiId::new();
+        self.aois
+            .insert(id, (session.user.id, Aoi::from_add_aoi(id, aoi.user_input)));
+        Ok(id)
+    }
+
+    async fn load(&self, session: &UserSession, id: AoiId) -> Result<Aoi> {
+        match self.aois.get(&id) {
+            Some((owner, aoi)) if *owner == session.user.id => Ok(aoi.clone()),
+            _ => Err(error::Error::UnknownAoiId),
+        }
+    }
+
+    async fn list(
+        &self,
+        session: &UserSession,
+        options: Validated<AoiListOptions>,
+    ) -> Result<Vec<AoiListing>> {
+        let options = options.user_input;
+
+        let mut aois: Vec<AoiListing> = self
+            .aois
+            .values()
+            .filter(|(owner, aoi)| *owner == session.user.id && options.includes(aoi))
+            .map(|(_, aoi)| AoiListing::from(aoi))
+            .collect();
+
+        aois.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(aois
+            .into_iter()
+            .skip(options.offset as usize)
+            .take(options.limit as usize)
+            .collect())
+    }
+
+    async fn delete(&mut self, session: &UserSession, id: AoiId) -> Result<()> {
+        match self.aois.get(&id) {
+            Some((owner, _)) if *owner == session.user.id => {
+                self.aois.remove(&id);
+                Ok(())
+            }
+            _ => Err(error::Error::UnknownAoiId),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contexts::MockableSession;
+    use crate::util::user_input::UserInput;
+    use geoengine_datatypes::primitives::MultiPolygon;
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+
+    #[tokio::test]
+    async fn it_hides_areas_of_other_users() {
+        let mut db = ProHashMapAoiDb::default();
+
+        // every mock session belongs to a different user
+        let owner = UserSession::mock();
+        let other = UserSession::mock();
+
+        let id = db
+            .add(
+                &owner,
+                AddAoi {
+                    name: "Marburg".to_string(),
+                    description: String::new(),
+                    spatial_reference: SpatialReference::epsg_4326(),
+                    geometry: MultiPolygon::new(vec![vec![vec![
+                        (0., 0.).into(),
+                        (1., 0.).into(),
+                        (1., 1.).into(),
+                        (0., 0.).into(),
+                    ]]])
+                    .unwrap(),
+                    project: None,
+                }
+                .validated()
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let options = AoiListOptions {
+            project: None,
+            offset: 0,
+            limit: 10,
+        }
+        .validated()
+        .unwrap();
+
+        assert_eq!(db.list(&owner, options.clone()).await.unwrap().len(), 1);
+        assert!(db.list(&other, options).await.unwrap().is_empty());
+
+        assert!(db.load(&other, id).await.is_err());
+        assert!(db.delete(&other, id).await.is_err());
+
+        assert_eq!(db.load(&owner, id).await.unwrap().name, "Marburg");
+        assert!(db.delete(&owner, id).await.is_ok());
+        assert!(db.load(&owner, id).await.is_err());
+    }
+}
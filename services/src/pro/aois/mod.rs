@@ -0,0 +1,3 @@
+mod hashmap_aoidb;
+
+pub use hashmap_aoidb::ProHashMapAoiDb;
@@ -1,18 +1,23 @@
-use crate::contexts::{ExecutionContextImpl, QueryContextImpl};
+use crate::contexts::{ExecutionContextImpl, MetaDataCache, QueryContextImpl};
 use crate::error;
+use crate::layers::add_from_directory::add_layer_collections_from_directory;
+use crate::layers::in_memory::HashMapLayerDb;
+use crate::pro::aois::ProHashMapAoiDb;
 use crate::pro::contexts::{Context, Db, ProContext};
 use crate::pro::datasets::ProHashMapDatasetDb;
 use crate::pro::projects::ProHashMapProjectDb;
 use crate::pro::users::{HashMapUserDb, UserDb, UserSession};
+use crate::util::admission_control::AdmissionControl;
 use crate::util::config;
+use crate::util::slow_query_log::SlowQueryLog;
 use crate::workflows::registry::HashMapRegistry;
 use crate::{
     datasets::add_from_directory::{add_datasets_from_directory, add_providers_from_directory},
     error::Result,
-    util::{dataset_defs_dir, provider_defs_dir},
+    util::{dataset_defs_dir, layer_defs_dir, provider_defs_dir},
 };
 use async_trait::async_trait;
-use geoengine_operators::concurrency::ThreadPool;
+use geoengine_operators::concurrency::{QueryScheduler, ThreadPool};
 use snafu::ResultExt;
 use std::sync::Arc;
 use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
@@ -24,8 +29,14 @@ pub struct ProInMemoryContext {
     project_db: Db<ProHashMapProjectDb>,
     workflow_registry: Db<HashMapRegistry>,
     dataset_db: Db<ProHashMapDatasetDb>,
+    layer_db: Db<HashMapLayerDb>,
+    aoi_db: Db<ProHashMapAoiDb>,
     session: Option<UserSession>,
     thread_pool: Arc<ThreadPool>,
+    meta_data_cache: MetaDataCache,
+    query_scheduler: QueryScheduler,
+    admission_control: AdmissionControl,
+    slow_query_log: SlowQueryLog,
 }
 
 impl ProInMemoryContext {
@@ -36,8 +47,32 @@ impl ProInMemoryContext {
         add_providers_from_directory(&mut db, provider_defs_dir()).await;
         add_providers_from_directory(&mut db, provider_defs_dir().join("pro")).await;
 
+        let mut layer_db = HashMapLayerDb::default();
+        add_layer_collections_from_directory(&mut layer_db, layer_defs_dir()).await;
+
         Self {
             dataset_db: Arc::new(RwLock::new(db)),
+            layer_db: Arc::new(RwLock::new(layer_db)),
+            query_scheduler: QueryScheduler::new(
+                config::get_config_element::<config::QueryScheduler>()
+                    .expect("query scheduler config must be valid")
+                    .into(),
+            ),
+            admission_control: AdmissionControl::new(
+                config::get_config_element::<config::AdmissionControl>()
+                    .expect("admission control config must be valid")
+                    .into(),
+            ),
+            slow_query_log: SlowQueryLog::new(
+                config::get_config_element::<config::SlowQueryLog>()
+                    .expect("slow query log config must be valid")
+                    .into(),
+            ),
+            meta_data_cache: MetaDataCache::new(
+                config::get_config_element::<config::MetaDataCache>()
+                    .expect("meta data cache config must be valid")
+                    .into(),
+            ),
             ..Default::default()
         }
     }
@@ -64,6 +99,8 @@ impl Context for ProInMemoryContext {
     type ProjectDB = ProHashMapProjectDb;
     type WorkflowRegistry = HashMapRegistry;
     type DatasetDB = ProHashMapDatasetDb;
+    type LayerDB = HashMapLayerDb;
+    type AoiDB = ProHashMapAoiDb;
     type QueryContext = QueryContextImpl;
     type ExecutionContext = ExecutionContextImpl<UserSession, ProHashMapDatasetDb>;
 
@@ -97,6 +134,26 @@ impl Context for ProInMemoryContext {
         self.dataset_db.write().await
     }
 
+    fn layer_db(&self) -> Db<Self::LayerDB> {
+        self.layer_db.clone()
+    }
+    async fn layer_db_ref(&self) -> RwLockReadGuard<'_, Self::LayerDB> {
+        self.layer_db.read().await
+    }
+    async fn layer_db_ref_mut(&self) -> RwLockWriteGuard<'_, Self::LayerDB> {
+        self.layer_db.write().await
+    }
+
+    fn aoi_db(&self) -> Db<Self::AoiDB> {
+        self.aoi_db.clone()
+    }
+    async fn aoi_db_ref(&self) -> RwLockReadGuard<'_, Self::AoiDB> {
+        self.aoi_db.read().await
+    }
+    async fn aoi_db_ref_mut(&self) -> RwLockWriteGuard<'_, Self::AoiDB> {
+        self.aoi_db.write().await
+    }
+
     fn query_context(&self) -> Result<Self::QueryContext> {
         // TODO: load config only once
         Ok(QueryContextImpl::new(
@@ -110,10 +167,27 @@ impl Context for ProInMemoryContext {
                 self.dataset_db.clone(),
                 self.thread_pool.clone(),
                 session,
+                self.meta_data_cache.clone(),
             ),
         )
     }
 
+    fn meta_data_cache(&self) -> MetaDataCache {
+        self.meta_data_cache.clone()
+    }
+
+    fn query_scheduler(&self) -> QueryScheduler {
+        self.query_scheduler.clone()
+    }
+
+    fn admission_control(&self) -> AdmissionControl {
+        self.admission_control.clone()
+    }
+
+    fn slow_query_log(&self) -> SlowQueryLog {
+        self.slow_query_log.clone()
+    }
+
     async fn session_by_id(&self, session_id: crate::contexts::SessionId) -> Result<Self::Session> {
         self.user_db_ref()
             .await
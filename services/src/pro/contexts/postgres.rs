@@ -1,15 +1,20 @@
 use crate::error::{self, Result};
+use crate::layers::in_memory::HashMapLayerDb;
+use crate::pro::aois::ProHashMapAoiDb;
 use crate::pro::datasets::PostgresDatasetDb;
 use crate::pro::projects::ProjectPermission;
 use crate::pro::users::{UserDb, UserId, UserSession};
 use crate::projects::ProjectId;
+use crate::util::admission_control::AdmissionControl;
+use crate::util::config::get_config_element;
+use crate::util::slow_query_log::SlowQueryLog;
 use crate::workflows::postgres_workflow_registry::PostgresWorkflowRegistry;
 use crate::{
     contexts::{Context, Db},
     pro::users::PostgresUserDb,
 };
 use crate::{
-    contexts::{ExecutionContextImpl, QueryContextImpl},
+    contexts::{ExecutionContextImpl, MetaDataCache, QueryContextImpl},
     pro::projects::PostgresProjectDb,
 };
 use async_trait::async_trait;
@@ -19,6 +24,7 @@ use bb8_postgres::{
     tokio_postgres::{error::SqlState, tls::MakeTlsConnect, tls::TlsConnect, Config, Socket},
     PostgresConnectionManager,
 };
+use geoengine_operators::concurrency::QueryScheduler;
 use log::{debug, warn};
 use snafu::ResultExt;
 use std::sync::Arc;
@@ -38,6 +44,7 @@ where
     user_db: Db<PostgresUserDb<Tls>>,
     project_db: Db<PostgresProjectDb<Tls>>,
     workflow_registry: Db<PostgresWorkflowRegistry<Tls>>,
+    meta_data_cache: MetaDataCache,
     session: Option<UserSession>,
 }
 
@@ -59,6 +66,9 @@ where
             user_db: Arc::new(RwLock::new(PostgresUserDb::new(pool.clone()))),
             project_db: Arc::new(RwLock::new(PostgresProjectDb::new(pool.clone()))),
             workflow_registry: Arc::new(RwLock::new(PostgresWorkflowRegistry::new(pool.clone()))),
+            meta_data_cache: MetaDataCache::new(
+                get_config_element::<crate::util::config::MetaDataCache>()?.into(),
+            ),
             session: None,
         })
     }
@@ -226,13 +236,24 @@ where
                     .await?;
                     debug!("Updated user database to schema version {}", version + 1);
                 }
-                // 1 => {
+                1 => {
+                    conn.batch_execute(
+                        r#"
+                        ALTER TABLE project_versions ADD COLUMN playback_interval "TimeInterval";
+
+                        UPDATE version SET version = 2;
+                        "#,
+                    )
+                    .await?;
+                    debug!("Updated user database to schema version {}", version + 1);
+                }
+                // 2 => {
                 // next version
                 // conn.batch_execute(
                 //     "\
                 //     ALTER TABLE users ...
                 //
-                //     UPDATE version SET version = 2;\
+                //     UPDATE version SET version = 3;\
                 //     ",
                 // )
                 // .await?;
@@ -300,6 +321,8 @@ where
     type ProjectDB = PostgresProjectDb<Tls>;
     type WorkflowRegistry = PostgresWorkflowRegistry<Tls>;
     type DatasetDB = PostgresDatasetDb;
+    type LayerDB = HashMapLayerDb; // TODO: store layers in postgres
+    type AoiDB = ProHashMapAoiDb; // TODO: store areas of interest in postgres
     type QueryContext = QueryContextImpl;
     type ExecutionContext = ExecutionContextImpl<UserSession, PostgresDatasetDb>;
 
@@ -335,6 +358,30 @@ where
         todo!()
     }
 
+    fn layer_db(&self) -> Db<Self::LayerDB> {
+        todo!()
+    }
+
+    async fn layer_db_ref(&self) -> RwLockReadGuard<'_, Self::LayerDB> {
+        todo!()
+    }
+
+    async fn layer_db_ref_mut(&self) -> RwLockWriteGuard<'_, Self::LayerDB> {
+        todo!()
+    }
+
+    fn aoi_db(&self) -> Db<Self::AoiDB> {
+        todo!()
+    }
+
+    async fn aoi_db_ref(&self) -> RwLockReadGuard<'_, Self::AoiDB> {
+        todo!()
+    }
+
+    async fn aoi_db_ref_mut(&self) -> RwLockWriteGuard<'_, Self::AoiDB> {
+        todo!()
+    }
+
     fn query_context(&self) -> Result<Self::QueryContext> {
         todo!()
     }
@@ -343,6 +390,22 @@ where
         todo!()
     }
 
+    fn meta_data_cache(&self) -> MetaDataCache {
+        self.meta_data_cache.clone()
+    }
+
+    fn query_scheduler(&self) -> QueryScheduler {
+        todo!()
+    }
+
+    fn admission_control(&self) -> AdmissionControl {
+        todo!()
+    }
+
+    fn slow_query_log(&self) -> SlowQueryLog {
+        todo!()
+    }
+
     async fn session_by_id(&self, session_id: crate::contexts::SessionId) -> Result<Self::Session> {
         self.user_db_ref()
             .await
@@ -578,7 +641,7 @@ mod tests {
             .await
             .register(Workflow {
                 operator: Statistics {
-                    params: StatisticsParams {},
+                    params: StatisticsParams::default(),
                     sources: MultipleRasterSources { rasters: vec![] },
                 }
                 .boxed()
@@ -610,6 +673,7 @@ mod tests {
             })]),
             bounds: None,
             time_step: None,
+            playback_interval: None,
         };
         ctx.project_db_ref_mut()
             .await
@@ -667,6 +731,7 @@ mod tests {
                 )
                 .unwrap(),
                 time_step: None,
+                playback_interval: None,
             }
             .validated()
             .unwrap();
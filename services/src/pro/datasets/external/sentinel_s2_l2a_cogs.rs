@@ -1,9 +1,11 @@
 use crate::datasets::listing::{DatasetListOptions, DatasetListing, DatasetProvider};
 use crate::datasets::provenance::{ProvenanceOutput, ProvenanceProvider};
-use crate::datasets::storage::DatasetProviderDefinition;
+use crate::datasets::storage::{Dataset, DatasetProviderDefinition};
 use crate::error::{self, Result};
 use crate::projects::{RasterSymbology, Symbology};
 use crate::stac::{Feature as StacFeature, FeatureCollection as StacCollection, StacAsset};
+use crate::util::http_client::shared_http_client;
+use crate::util::request_limiter::{RequestLimiter, RequestLimits};
 use crate::util::user_input::Validated;
 use async_trait::async_trait;
 use chrono::{DateTime, Duration, Utc};
@@ -27,12 +29,14 @@ use geoengine_operators::source::{
     OgrSourceDataset,
 };
 use log::debug;
+use ordered_float::NotNan;
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
 use std::collections::HashMap;
 use std::convert::TryInto;
 use std::fmt::Debug;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -40,6 +44,8 @@ pub struct SentinelS2L2ACogsProviderDefinition {
     name: String,
     id: DatasetProviderId,
     api_url: String,
+    #[serde(default)]
+    request_limits: RequestLimits,
 }
 
 #[typetag::serde]
@@ -51,6 +57,7 @@ impl DatasetProviderDefinition for SentinelS2L2ACogsProviderDefinition {
         Ok(Box::new(SentinelS2L2aCogsDataProvider::new(
             self.id,
             self.api_url,
+            self.request_limits,
         )))
     }
 
@@ -72,16 +79,134 @@ pub struct Band {
     pub name: String,
     pub no_data_value: Option<f64>,
     pub data_type: RasterDataType,
+    pub kind: BandKind,
+}
+
+/// The kind of data a band contains, used to derive its measurement and default symbology
+#[derive(Debug, Clone)]
+pub enum BandKind {
+    /// Surface reflectance, stored as integers that are scaled by `scale`
+    Reflectance { scale: f64 },
+    /// Classes with a label and a default color
+    Classification {
+        measurement: String,
+        classes: Vec<(u8, String, RgbaColor)>,
+    },
 }
 
 impl Band {
-    pub fn new(name: String, no_data_value: Option<f64>, data_type: RasterDataType) -> Self {
+    pub fn new(
+        name: String,
+        no_data_value: Option<f64>,
+        data_type: RasterDataType,
+        kind: BandKind,
+    ) -> Self {
         Self {
             name,
             no_data_value,
             data_type,
+            kind,
+        }
+    }
+
+    pub fn measurement(&self) -> Measurement {
+        match &self.kind {
+            BandKind::Reflectance { scale } => {
+                Measurement::continuous("reflectance".to_owned(), Some(format!("1/{}", scale)))
+            }
+            BandKind::Classification {
+                measurement,
+                classes,
+            } => Measurement::classification(
+                measurement.clone(),
+                classes
+                    .iter()
+                    .map(|(class, label, _)| (*class, label.clone()))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// The default colorizer for this band
+    pub fn colorizer(&self) -> Colorizer {
+        match &self.kind {
+            BandKind::Reflectance { scale } => Colorizer::linear_gradient(
+                vec![
+                    (0.0, RgbaColor::black())
+                        .try_into()
+                        .expect("valid breakpoint"),
+                    (*scale, RgbaColor::white())
+                        .try_into()
+                        .expect("valid breakpoint"),
+                ],
+                RgbaColor::transparent(),
+                RgbaColor::transparent(),
+            )
+            .expect("valid colorizer"),
+            BandKind::Classification { classes, .. } => Colorizer::palette(
+                classes
+                    .iter()
+                    .map(|(class, _, color)| {
+                        (
+                            NotNan::new(f64::from(*class)).expect("class is not NaN"),
+                            *color,
+                        )
+                    })
+                    .collect(),
+                RgbaColor::transparent(),
+                RgbaColor::transparent(),
+            )
+            .expect("valid colorizer"),
         }
     }
+
+    fn scene_classification_classes() -> Vec<(u8, String, RgbaColor)> {
+        vec![
+            (
+                1,
+                "Saturated or defective".to_owned(),
+                RgbaColor::new(255, 0, 0, 255),
+            ),
+            (
+                2,
+                "Dark area pixels".to_owned(),
+                RgbaColor::new(47, 47, 47, 255),
+            ),
+            (
+                3,
+                "Cloud shadows".to_owned(),
+                RgbaColor::new(100, 50, 0, 255),
+            ),
+            (4, "Vegetation".to_owned(), RgbaColor::new(0, 160, 0, 255)),
+            (
+                5,
+                "Not vegetated".to_owned(),
+                RgbaColor::new(255, 230, 90, 255),
+            ),
+            (6, "Water".to_owned(), RgbaColor::new(0, 0, 255, 255)),
+            (
+                7,
+                "Unclassified".to_owned(),
+                RgbaColor::new(128, 128, 128, 255),
+            ),
+            (
+                8,
+                "Cloud medium probability".to_owned(),
+                RgbaColor::new(192, 192, 192, 255),
+            ),
+            (
+                9,
+                "Cloud high probability".to_owned(),
+                RgbaColor::new(255, 255, 255, 255),
+            ),
+            (
+                10,
+                "Thin cirrus".to_owned(),
+                RgbaColor::new(100, 200, 255, 255),
+            ),
+            (11, "Snow".to_owned(), RgbaColor::new(255, 150, 255, 255)),
+        ]
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -94,32 +219,74 @@ impl Zone {
     pub fn new(name: String, epsg: u32) -> Self {
         Self { name, epsg }
     }
+
+    /// The number of the UTM zone, e.g., 32 for `EPSG:32632` and `EPSG:32732`
+    pub fn utm_zone(&self) -> u32 {
+        self.epsg % 100
+    }
+}
+
+/// The latitude bands of the MGRS from 80°S to 84°N in steps of 8°, where X spans 12°
+const MGRS_LATITUDE_BANDS: &str = "CDEFGHJKLMNPQRSTUVWX";
+
+/// The UTM zones and MGRS latitude bands of the grid zones that cover the WGS84 `bbox`.
+///
+/// As Sentinel-2 tiles overlap into the adjacent grid zones, the neighboring zones and bands are included.
+fn mgrs_grid_zones(bbox: &BoundingBox2D) -> (Vec<u32>, Vec<char>) {
+    let zone_index = |x: f64| (((x + 180.) / 6.).floor() as i64).clamp(0, 59);
+    let band_index = |y: f64| (((y + 80.) / 8.).floor() as i64).clamp(0, 19);
+
+    let min_zone = zone_index(bbox.lower_left().x) - 1;
+    let max_zone = zone_index(bbox.upper_right().x) + 1;
+
+    let mut zones: Vec<u32> = (min_zone..=max_zone)
+        .map(|zone| zone.rem_euclid(60) as u32 + 1)
+        .collect();
+    zones.sort_unstable();
+    zones.dedup();
+
+    let min_band = (band_index(bbox.lower_left().y) - 1).max(0);
+    let max_band = (band_index(bbox.upper_right().y) + 1).min(19);
+
+    let bands = MGRS_LATITUDE_BANDS
+        .chars()
+        .skip(min_band as usize)
+        .take((max_band - min_band) as usize + 1)
+        .collect();
+
+    (zones, bands)
 }
 
 #[derive(Debug, Clone)]
 pub struct SentinelMetaData {
     bands: Vec<Band>,
     zones: Vec<Zone>,
+    /// The spatial reference of the datasets that merge all zones
+    merged_spatial_reference: SpatialReference,
 }
 
 #[derive(Debug, Clone)]
 pub struct SentinelDataset {
     band: Band,
-    zone: Zone,
+    /// The UTM zone or `None` if the dataset merges the scenes of all zones
+    zone: Option<Zone>,
+    spatial_reference: SpatialReference,
     listing: DatasetListing,
 }
 
 pub struct SentinelS2L2aCogsDataProvider {
     api_url: String,
+    request_limiter: Arc<RequestLimiter>,
 
     datasets: HashMap<DatasetId, SentinelDataset>,
 }
 
 impl SentinelS2L2aCogsDataProvider {
-    pub fn new(id: DatasetProviderId, api_url: String) -> Self {
+    pub fn new(id: DatasetProviderId, api_url: String, request_limits: RequestLimits) -> Self {
         let meta_data = Self::load_metadata();
         Self {
             api_url,
+            request_limiter: RequestLimiter::shared(&id.to_string(), request_limits),
             datasets: Self::create_datasets(&id, &meta_data),
         }
     }
@@ -128,71 +295,104 @@ impl SentinelS2L2aCogsDataProvider {
         // TODO: fetch dataset metadata from config or remote
         SentinelMetaData {
             bands: vec![
-                Band::new("B01".to_owned(), Some(0.), RasterDataType::U16),
-                Band::new("B02".to_owned(), Some(0.), RasterDataType::U16),
-                Band::new("B03".to_owned(), Some(0.), RasterDataType::U16),
-                Band::new("B04".to_owned(), Some(0.), RasterDataType::U16),
-                Band::new("B08".to_owned(), Some(0.), RasterDataType::U16),
-                Band::new("SCL".to_owned(), Some(0.), RasterDataType::U8),
+                Band::new(
+                    "B01".to_owned(),
+                    Some(0.),
+                    RasterDataType::U16,
+                    BandKind::Reflectance { scale: 10_000. },
+                ),
+                Band::new(
+                    "B02".to_owned(),
+                    Some(0.),
+                    RasterDataType::U16,
+                    BandKind::Reflectance { scale: 10_000. },
+                ),
+                Band::new(
+                    "B03".to_owned(),
+                    Some(0.),
+                    RasterDataType::U16,
+                    BandKind::Reflectance { scale: 10_000. },
+                ),
+                Band::new(
+                    "B04".to_owned(),
+                    Some(0.),
+                    RasterDataType::U16,
+                    BandKind::Reflectance { scale: 10_000. },
+                ),
+                Band::new(
+                    "B08".to_owned(),
+                    Some(0.),
+                    RasterDataType::U16,
+                    BandKind::Reflectance { scale: 10_000. },
+                ),
+                Band::new(
+                    "SCL".to_owned(),
+                    Some(0.),
+                    RasterDataType::U8,
+                    BandKind::Classification {
+                        measurement: "scene classification".to_owned(),
+                        classes: Band::scene_classification_classes(),
+                    },
+                ),
             ],
             zones: vec![
                 Zone::new("UTM32N".to_owned(), 32632),
                 Zone::new("UTM36S".to_owned(), 32736),
             ],
+            merged_spatial_reference: SpatialReference::epsg_4326(),
         }
     }
 
+    /// Creates a dataset for each band in each zone and a dataset for each band that merges all zones
     fn create_datasets(
         id: &DatasetProviderId,
         meta_data: &SentinelMetaData,
     ) -> HashMap<DatasetId, SentinelDataset> {
-        meta_data
+        let zones = meta_data
             .zones
             .iter()
+            .map(Some)
+            .chain(std::iter::once(None));
+
+        zones
             .flat_map(|zone| {
                 meta_data.bands.iter().map(move |band| {
+                    let (name, spatial_reference) = match zone {
+                        Some(zone) => (
+                            format!("{}:{}", zone.name, band.name),
+                            SpatialReference::new(SpatialReferenceAuthority::Epsg, zone.epsg),
+                        ),
+                        None => (band.name.clone(), meta_data.merged_spatial_reference),
+                    };
+
                     let dataset_id: DatasetId = ExternalDatasetId {
                         provider_id: *id,
-                        dataset_id: format!("{}:{}", zone.name, band.name),
+                        dataset_id: name.clone(),
                     }
                     .into();
                     let listing = DatasetListing {
                         id: dataset_id.clone(),
-                        name: format!("Sentinel S2 L2A COGS {}:{}", zone.name, band.name),
+                        name: format!("Sentinel S2 L2A COGS {}", name),
                         description: "".to_owned(),
                         tags: vec![],
                         source_operator: "GdalSource".to_owned(),
                         result_descriptor: RasterResultDescriptor {
                             data_type: band.data_type,
-                            spatial_reference: SpatialReference::new(
-                                SpatialReferenceAuthority::Epsg,
-                                zone.epsg,
-                            )
-                            .into(),
-                            measurement: Measurement::Unitless, // TODO: add measurement
+                            spatial_reference: spatial_reference.into(),
+                            measurement: band.measurement(),
                             no_data_value: band.no_data_value,
+                            bands: Vec::new(),
                         }
                         .into(),
                         symbology: Some(Symbology::Raster(RasterSymbology {
                             opacity: 1.0,
-                            colorizer: Colorizer::linear_gradient(
-                                vec![
-                                    (0.0, RgbaColor::white())
-                                        .try_into()
-                                        .expect("valid breakpoint"),
-                                    (10_000.0, RgbaColor::black())
-                                        .try_into()
-                                        .expect("valid breakpoint"),
-                                ],
-                                RgbaColor::transparent(),
-                                RgbaColor::transparent(),
-                            )
-                            .expect("valid colorizer"),
-                        })), // TODO: individual colorizer per band
+                            colorizer: band.colorizer(),
+                        })),
                     };
 
                     let dataset = SentinelDataset {
-                        zone: zone.clone(),
+                        zone: zone.cloned(),
+                        spatial_reference,
                         band: band.clone(),
                         listing,
                     };
@@ -214,11 +414,17 @@ impl DatasetProvider for SentinelS2L2aCogsDataProvider {
         Ok(x)
     }
 
-    async fn load(
-        &self,
-        _dataset: &geoengine_datatypes::dataset::DatasetId,
-    ) -> crate::error::Result<crate::datasets::storage::Dataset> {
-        Err(error::Error::NotYetImplemented)
+    async fn load(&self, dataset: &DatasetId) -> Result<Dataset> {
+        let listing = self
+            .datasets
+            .get(dataset)
+            .ok_or(error::Error::UnknownDatasetId)?
+            .listing
+            .clone();
+
+        let provenance = self.provenance(dataset).await?.provenance;
+
+        Ok(Dataset::from_listing(listing, provenance))
     }
 }
 
@@ -235,7 +441,10 @@ impl ProvenanceProvider for SentinelS2L2aCogsDataProvider {
 #[derive(Debug, Clone)]
 pub struct SentinelS2L2aCogsMetaData {
     api_url: String,
-    zone: Zone,
+    request_limiter: Arc<RequestLimiter>,
+    /// The UTM zone or `None` for reprojecting the scenes of all zones into the `spatial_reference`
+    zone: Option<Zone>,
+    spatial_reference: SpatialReference,
     band: Band,
 }
 
@@ -249,10 +458,10 @@ impl SentinelS2L2aCogsMetaData {
         debug!("number of features returned by STAC: {}", features.len());
         let mut features: Vec<StacFeature> = features
             .into_iter()
-            .filter(|f| {
-                f.properties
-                    .proj_epsg
-                    .map_or(false, |epsg| epsg == self.zone.epsg)
+            .filter(|f| match (f.properties.proj_epsg, &self.zone) {
+                (Some(epsg), Some(zone)) => epsg == zone.epsg,
+                (Some(_), None) => true,
+                (None, _) => false,
             })
             .collect();
 
@@ -292,7 +501,12 @@ impl SentinelS2L2aCogsMetaData {
                             band_name: self.band.name.clone(),
                         })?;
 
-                parts.push(self.create_loading_info_part(time_interval, asset)?);
+                let epsg = feature
+                    .properties
+                    .proj_epsg
+                    .expect("features without projection are filtered");
+
+                parts.push(self.create_loading_info_part(time_interval, asset, epsg)?);
             }
         }
         debug!("number of generated loading infos: {}", parts.len());
@@ -308,26 +522,65 @@ impl SentinelS2L2aCogsMetaData {
         &self,
         time_interval: TimeInterval,
         asset: &StacAsset,
+        epsg: u32,
     ) -> Result<GdalLoadingInfoPart> {
         let [stac_shape_y, stac_shape_x] = asset.proj_shape.ok_or(error::Error::StacInvalidBbox)?;
 
+        let params = GdalDatasetParameters {
+            file_path: PathBuf::from(format!("/vsicurl/{}", asset.href)),
+            rasterband_channel: 1,
+            geo_transform: GeoTransform::from(
+                asset
+                    .gdal_geotransform()
+                    .ok_or(error::Error::StacInvalidGeoTransform)?,
+            ),
+            width: stac_shape_x as usize,
+            height: stac_shape_y as usize,
+            file_not_found_handling: geoengine_operators::source::FileNotFoundHandling::NoData,
+            no_data_value: self.band.no_data_value,
+            properties_mapping: None,
+            gdal_open_options: None,
+            reproject_to: None,
+        };
+
+        let params = if self.zone.is_some() {
+            params
+        } else {
+            self.reprojected_dataset_parameters(params, epsg)?
+        };
+
         Ok(GdalLoadingInfoPart {
             time: time_interval,
-            params: GdalDatasetParameters {
-                file_path: PathBuf::from(format!("/vsicurl/{}", asset.href)),
-                rasterband_channel: 1,
-                geo_transform: GeoTransform::from(
-                    asset
-                        .gdal_geotransform()
-                        .ok_or(error::Error::StacInvalidGeoTransform)?,
-                ),
-                width: stac_shape_x as usize,
-                height: stac_shape_y as usize,
-                file_not_found_handling: geoengine_operators::source::FileNotFoundHandling::NoData,
-                no_data_value: self.band.no_data_value,
-                properties_mapping: None,
-                gdal_open_options: None,
-            },
+            params,
+        })
+    }
+
+    /// Describes the file of the `params` in the `spatial_reference` of the merged dataset, which the source warps it into
+    fn reprojected_dataset_parameters(
+        &self,
+        params: GdalDatasetParameters,
+        epsg: u32,
+    ) -> Result<GdalDatasetParameters> {
+        let projector = CoordinateProjector::from_known_srs(
+            SpatialReference::new(SpatialReferenceAuthority::Epsg, epsg),
+            self.spatial_reference,
+        )?;
+
+        let spatial_partition = params.spatial_partition();
+        let bbox = BoundingBox2D::new_upper_left_lower_right_unchecked(
+            spatial_partition.upper_left(),
+            spatial_partition.lower_right(),
+        )
+        .reproject_clipped(&projector)?;
+
+        Ok(GdalDatasetParameters {
+            geo_transform: GeoTransform::new(
+                bbox.upper_left(),
+                bbox.size_x() / params.width as f64,
+                -bbox.size_y() / params.height as f64,
+            ),
+            reproject_to: Some(self.spatial_reference),
+            ..params
         })
     }
 
@@ -336,7 +589,7 @@ impl SentinelS2L2aCogsMetaData {
 
         // request all features in zone in order to be able to determine the temporal validity of individual tile
         let projector = CoordinateProjector::from_known_srs(
-            SpatialReference::new(SpatialReferenceAuthority::Epsg, self.zone.epsg),
+            self.spatial_reference,
             SpatialReference::epsg_4326(),
         )?;
 
@@ -347,6 +600,25 @@ impl SentinelS2L2aCogsMetaData {
         );
         let bbox = bbox.reproject_clipped(&projector)?; // TODO: use reproject_clipped on SpatialPartition2D
 
+        let (mut utm_zones, latitude_bands) = mgrs_grid_zones(&bbox);
+        if let Some(zone) = &self.zone {
+            utm_zones = vec![zone.utm_zone()];
+        }
+
+        // pre-filter the features by the grid zones, which the STAC index answers faster than the geometric intersection
+        let grid_zone_query = serde_json::json!({
+            "sentinel:utm_zone": { "in": utm_zones },
+            "sentinel:latitude_band": {
+                "in": latitude_bands.iter().map(ToString::to_string).collect::<Vec<_>>()
+            },
+        });
+
+        // only request the asset of the band and the fields that the `StacFeature` requires
+        let fields = format!(
+            "type,id,bbox,geometry,stac_version,stac_extensions,properties,assets.{},-links",
+            self.band.name
+        );
+
         Ok(vec![
             (
                 "collections[]".to_owned(),
@@ -366,6 +638,8 @@ impl SentinelS2L2aCogsMetaData {
                 "datetime".to_owned(),
                 format!("{}/{}", t_start.to_rfc3339(), t_end.to_rfc3339()),
             ),
+            ("query".to_owned(), grid_zone_query.to_string()),
+            ("fields".to_owned(), fields),
             ("limit".to_owned(), "500".to_owned()),
         ])
     }
@@ -395,8 +669,9 @@ impl SentinelS2L2aCogsMetaData {
         params: &T,
         page: u32,
     ) -> Result<StacCollection> {
-        let client = reqwest::Client::new();
-        let text = client
+        let _permit = self.request_limiter.acquire().await;
+
+        let text = shared_http_client()?
             .get(&self.api_url)
             .query(&params)
             .query(&[("page", &page.to_string())])
@@ -460,13 +735,10 @@ impl MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>
     async fn result_descriptor(&self) -> geoengine_operators::util::Result<RasterResultDescriptor> {
         Ok(RasterResultDescriptor {
             data_type: self.band.data_type,
-            spatial_reference: SpatialReference::new(
-                SpatialReferenceAuthority::Epsg,
-                self.zone.epsg,
-            )
-            .into(),
-            measurement: Measurement::Unitless,
+            spatial_reference: self.spatial_reference.into(),
+            measurement: self.band.measurement(),
             no_data_value: self.band.no_data_value,
+            bands: Vec::new(),
         })
     }
 
@@ -495,7 +767,9 @@ impl MetaDataProvider<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectan
 
         Ok(Box::new(SentinelS2L2aCogsMetaData {
             api_url: self.api_url.clone(),
+            request_limiter: self.request_limiter.clone(),
             zone: dataset.zone.clone(),
+            spatial_reference: dataset.spatial_reference,
             band: dataset.band.clone(),
         }))
     }
@@ -545,11 +819,168 @@ mod tests {
     use futures::StreamExt;
     use geoengine_datatypes::primitives::{SpatialPartition2D, SpatialResolution};
     use geoengine_operators::{
-        engine::{MockExecutionContext, MockQueryContext, RasterOperator},
+        engine::{MockExecutionContext, MockQueryContext, RasterOperator, TypedResultDescriptor},
         source::{FileNotFoundHandling, GdalSource, GdalSourceParameters},
     };
 
     use super::*;
+    use crate::pro::util::integration::TestEnvironment;
+    use crate::util::provider_defs_dir;
+
+    #[test]
+    fn band_defaults() {
+        let datasets = SentinelS2L2aCogsDataProvider::create_datasets(
+            &DatasetProviderId::from_str("5779494c-f3a2-48b3-8a2d-5fbba8c5b6c5").unwrap(),
+            &SentinelS2L2aCogsDataProvider::load_metadata(),
+        );
+
+        let b01 = datasets
+            .values()
+            .find(|d| d.band.name == "B01")
+            .unwrap()
+            .listing
+            .clone();
+
+        if let TypedResultDescriptor::Raster(descriptor) = b01.result_descriptor {
+            assert_eq!(
+                descriptor.measurement,
+                Measurement::continuous("reflectance".to_owned(), Some("1/10000".to_owned()))
+            );
+        } else {
+            unreachable!();
+        }
+
+        let scl = datasets
+            .values()
+            .find(|d| d.band.name == "SCL")
+            .unwrap()
+            .listing
+            .clone();
+
+        if let TypedResultDescriptor::Raster(descriptor) = scl.result_descriptor {
+            if let Measurement::Classification { classes, .. } = descriptor.measurement {
+                assert_eq!(classes.len(), 11);
+                assert_eq!(classes[&4], "Vegetation");
+            } else {
+                unreachable!();
+            }
+        } else {
+            unreachable!();
+        }
+
+        if let Some(Symbology::Raster(symbology)) = scl.symbology {
+            assert_eq!(
+                symbology.colorizer.create_color_mapper().call(6_u8),
+                RgbaColor::new(0, 0, 255, 255)
+            );
+        } else {
+            unreachable!();
+        }
+    }
+
+    #[tokio::test]
+    async fn load_dataset() -> Result<()> {
+        let def: Box<dyn DatasetProviderDefinition> = serde_json::from_reader(BufReader::new(
+            File::open(provider_defs_dir().join("pro/sentinel_s2_l2a_cogs.json"))?,
+        ))?;
+
+        let provider = def.initialize().await?;
+
+        let id: DatasetId = ExternalDatasetId {
+            provider_id: DatasetProviderId::from_str("5779494c-f3a2-48b3-8a2d-5fbba8c5b6c5")?,
+            dataset_id: "UTM32N:SCL".to_owned(),
+        }
+        .into();
+
+        let dataset = provider.load(&id).await?;
+
+        assert_eq!(dataset.id, id);
+        assert_eq!(dataset.name, "Sentinel S2 L2A COGS UTM32N:SCL");
+        assert_eq!(dataset.source_operator, "GdalSource");
+        assert!(dataset.symbology.is_some());
+
+        let unknown: DatasetId = ExternalDatasetId {
+            provider_id: DatasetProviderId::from_str("5779494c-f3a2-48b3-8a2d-5fbba8c5b6c5")?,
+            dataset_id: "UTM32N:foo".to_owned(),
+        }
+        .into();
+
+        assert!(provider.load(&unknown).await.is_err());
+
+        let merged: DatasetId = ExternalDatasetId {
+            provider_id: DatasetProviderId::from_str("5779494c-f3a2-48b3-8a2d-5fbba8c5b6c5")?,
+            dataset_id: "B04".to_owned(),
+        }
+        .into();
+
+        let dataset = provider.load(&merged).await?;
+
+        assert_eq!(dataset.name, "Sentinel S2 L2A COGS B04");
+        if let TypedResultDescriptor::Raster(descriptor) = dataset.result_descriptor {
+            assert_eq!(
+                descriptor.spatial_reference,
+                SpatialReference::epsg_4326().into()
+            );
+        } else {
+            unreachable!();
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn grid_zones() {
+        // Germany
+        let (zones, bands) =
+            mgrs_grid_zones(&BoundingBox2D::new((6., 47.).into(), (15., 55.).into()).unwrap());
+        assert_eq!(zones, vec![31, 32, 33, 34]);
+        assert_eq!(bands, vec!['S', 'T', 'U', 'V']);
+
+        // across the antimeridian and the south pole
+        let (zones, bands) =
+            mgrs_grid_zones(&BoundingBox2D::new((179., -90.).into(), (180., -75.).into()).unwrap());
+        assert_eq!(zones, vec![1, 59, 60]);
+        assert_eq!(bands, vec!['C', 'D']);
+    }
+
+    #[test]
+    fn merged_dataset_parameters() {
+        let meta_data = SentinelS2L2aCogsMetaData {
+            api_url: String::new(),
+            request_limiter: RequestLimiter::shared("test", RequestLimits::default()),
+            zone: None,
+            spatial_reference: SpatialReference::epsg_4326(),
+            band: SentinelS2L2aCogsDataProvider::load_metadata().bands[0].clone(),
+        };
+
+        let asset: StacAsset = serde_json::from_value(serde_json::json!({
+            "title": "Band 1 (coastal)",
+            "type": "image/tiff; application=geotiff; profile=cloud-optimized",
+            "roles": ["data"],
+            "gsd": 60,
+            "href": "https://example.org/B01.tif",
+            "proj:shape": [1830, 1830],
+            "proj:transform": [60, 0, 600000, 0, -60, 3400020, 0, 0, 1]
+        }))
+        .unwrap();
+
+        let part = meta_data
+            .create_loading_info_part(TimeInterval::default(), &asset, 32632)
+            .unwrap();
+
+        assert_eq!(
+            part.params.reproject_to,
+            Some(SpatialReference::epsg_4326())
+        );
+        assert_eq!(part.params.width, 1830);
+
+        // the scene lies east of the central meridian (9°) of zone 32
+        let bounds = part.params.spatial_partition();
+        assert!(bounds.upper_left().x > 10. && bounds.upper_left().x < 10.1);
+        assert!(bounds.upper_left().y > 30.6 && bounds.upper_left().y < 30.8);
+        assert!(bounds.lower_right().x > 11.1 && bounds.lower_right().x < 11.3);
+        assert!(bounds.lower_right().y > 29.6 && bounds.lower_right().y < 29.8);
+    }
 
     #[tokio::test]
     async fn loading_info() -> Result<()> {
@@ -607,6 +1038,7 @@ mod tests {
                 no_data_value: Some(0.),
                 properties_mapping: None,
                 gdal_open_options: None,
+                reproject_to: None,
             },
         }];
 
@@ -623,6 +1055,64 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn loading_info_from_mock_stac() -> Result<()> {
+        let provider = SentinelS2L2aCogsDataProvider::new(
+            DatasetProviderId::from_str("5779494c-f3a2-48b3-8a2d-5fbba8c5b6c5")?,
+            TestEnvironment::get().stac_url().to_string(),
+            RequestLimits::default(),
+        );
+
+        let meta: Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>> =
+            provider
+                .meta_data(
+                    &ExternalDatasetId {
+                        provider_id: DatasetProviderId::from_str(
+                            "5779494c-f3a2-48b3-8a2d-5fbba8c5b6c5",
+                        )?,
+                        dataset_id: "UTM32N:B01".to_owned(),
+                    }
+                    .into(),
+                )
+                .await
+                .unwrap();
+
+        let loading_info = meta
+            .loading_info(RasterQueryRectangle {
+                spatial_bounds: SpatialPartition2D::new_unchecked(
+                    (166_021.44, 0.00).into(),
+                    (534_994.66, 9_329_005.18).into(),
+                ),
+                time_interval: TimeInterval::new_instant(
+                    DateTime::parse_from_rfc3339("2021-01-02T10:02:26Z")
+                        .unwrap()
+                        .timestamp_millis(),
+                )?,
+                spatial_resolution: SpatialResolution::one(),
+            })
+            .await
+            .unwrap();
+
+        if let GdalLoadingInfoPartIterator::Static { parts } = loading_info.info {
+            let result: Vec<_> = parts.collect();
+
+            assert_eq!(result.len(), 1);
+            assert_eq!(
+                result[0].time,
+                TimeInterval::new_unchecked(1_609_581_746_000, 1_609_581_747_000)
+            );
+            assert_eq!(
+                result[0].params.file_path,
+                PathBuf::from("/vsicurl/https://sentinel-cogs.s3.us-west-2.amazonaws.com/sentinel-s2-l2a-cogs/32/R/PU/2021/1/S2B_32RPU_20210102_0_L2A/B01.tif")
+            );
+        } else {
+            unreachable!();
+        }
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn query_data() -> Result<()> {
         // TODO: mock STAC endpoint
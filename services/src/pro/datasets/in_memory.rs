@@ -1,19 +1,34 @@
-use crate::contexts::MockableSession;
+use crate::contexts::{MockableSession, Session};
+use crate::datasets::alias::{DatasetAliasDb, DatasetAliasDefinition};
 use crate::datasets::listing::{DatasetListOptions, DatasetListing, DatasetProvider, OrderBy};
 use crate::datasets::provenance::{ProvenanceOutput, ProvenanceProvider};
+use crate::datasets::scratch::{
+    ExpiredScratchDatasets, ScratchDataset, ScratchDatasetDb, ScratchDatasetListing,
+};
 use crate::datasets::storage::{
     AddDataset, Dataset, DatasetDb, DatasetProviderDb, DatasetProviderDefinition,
     DatasetProviderListOptions, DatasetProviderListing, DatasetStore, DatasetStorer,
     MetaDataDefinition,
 };
-use crate::datasets::upload::{Upload, UploadDb, UploadId};
+use crate::datasets::tags::{
+    insert_tags, remove_tags, search_datasets, DatasetSearchOptions, DatasetSearchResult,
+    DatasetTagDb, Tags,
+};
+use crate::datasets::upload::{Upload, UploadDb, UploadId, UploadQuota};
 use crate::error;
 use crate::error::Result;
-use crate::pro::users::UserSession;
+use crate::pro::datasets::quota::{
+    default_storage_quota, StorageUsage, UserStorageDb, UserStorageUsage,
+};
+use crate::pro::handlers::datasets::ensure_admin;
+use crate::pro::users::{UserId, UserSession};
+use crate::tile_archive::TileArchive;
 use crate::util::user_input::Validated;
+use crate::workflows::workflow::WorkflowId;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use geoengine_datatypes::{
-    dataset::{DatasetId, DatasetProviderId, InternalDatasetId},
+    dataset::{DatasetId, DatasetProviderId, ExternalDatasetId, InternalDatasetId},
     util::Identifier,
 };
 use geoengine_operators::engine::{
@@ -22,6 +37,7 @@ use geoengine_operators::engine::{
 };
 use geoengine_operators::source::{GdalLoadingInfo, GdalMetaDataRegular, OgrSourceDataset};
 use geoengine_operators::{mock::MockDatasetDataSourceLoadingInfo, source::GdalMetaDataStatic};
+use snafu::ensure;
 use std::collections::HashMap;
 
 #[derive(Default)]
@@ -45,6 +61,58 @@ pub struct ProHashMapDatasetDb {
     >,
     uploads: HashMap<UploadId, Upload>,
     external_providers: HashMap<DatasetProviderId, Box<dyn DatasetProviderDefinition>>,
+    aliases: HashMap<String, ExternalDatasetId>,
+    workflow_tags: HashMap<WorkflowId, Vec<String>>,
+    scratch_datasets: HashMap<InternalDatasetId, ScratchDataset>,
+    dataset_owners: HashMap<InternalDatasetId, UserId>,
+    upload_owners: HashMap<UploadId, UserId>,
+    storage_quotas: HashMap<UserId, u64>,
+    materialization_owners: HashMap<WorkflowId, UserId>,
+}
+
+impl ProHashMapDatasetDb {
+    /// The datasets of the shared catalog, i.e., without the scratch datasets of sessions
+    fn catalog_datasets(&self) -> impl Iterator<Item = &Dataset> {
+        self.datasets.iter().filter(move |d| {
+            d.id.internal()
+                .map_or(true, |id| !self.scratch_datasets.contains_key(&id))
+        })
+    }
+
+    /// The size of the user's uploads and of the tiles of the workflows the user archived
+    async fn used_storage_bytes(&self, user: UserId) -> Result<u64> {
+        let upload_bytes: u64 = self
+            .uploads
+            .values()
+            .filter(|upload| self.upload_owners.get(&upload.id) == Some(&user))
+            .flat_map(|upload| &upload.files)
+            .map(|file| file.byte_size as u64)
+            .sum();
+
+        let mut materialization_bytes = 0;
+        for (workflow, owner) in &self.materialization_owners {
+            if *owner == user {
+                materialization_bytes +=
+                    TileArchive::from_config()?.stored_bytes(*workflow).await?;
+            }
+        }
+
+        Ok(upload_bytes + materialization_bytes)
+    }
+
+    fn storage_quota(&self, user: UserId) -> Result<u64> {
+        match self.storage_quotas.get(&user) {
+            Some(quota) => Ok(*quota),
+            None => default_storage_quota(),
+        }
+    }
+
+    async fn user_upload_quota(&self, user: UserId) -> Result<UploadQuota> {
+        Ok(UploadQuota {
+            used_bytes: self.used_storage_bytes(user).await?,
+            quota_bytes: self.storage_quota(user)?,
+        })
+    }
 }
 
 impl DatasetDb<UserSession> for ProHashMapDatasetDb {}
@@ -92,6 +160,252 @@ impl DatasetProviderDb<UserSession> for ProHashMapDatasetDb {
     }
 }
 
+#[async_trait]
+impl DatasetAliasDb<UserSession> for ProHashMapDatasetDb {
+    async fn add_dataset_alias(
+        &mut self,
+        session: &UserSession,
+        alias: Validated<DatasetAliasDefinition>,
+    ) -> Result<()> {
+        // aliases are shared by all users, s.t. only admins may (re-)point them
+        ensure_admin(session)?;
+
+        let alias = alias.user_input;
+        self.aliases.insert(alias.alias, alias.dataset);
+        Ok(())
+    }
+
+    async fn list_dataset_aliases(
+        &self,
+        _session: &UserSession,
+    ) -> Result<Vec<DatasetAliasDefinition>> {
+        let mut aliases: Vec<_> = self
+            .aliases
+            .iter()
+            .map(|(alias, dataset)| DatasetAliasDefinition {
+                alias: alias.clone(),
+                dataset: dataset.clone(),
+            })
+            .collect();
+        aliases.sort_by(|a, b| a.alias.cmp(&b.alias));
+        Ok(aliases)
+    }
+
+    async fn resolve_dataset_alias(&self, alias: &str) -> Result<ExternalDatasetId> {
+        self.aliases
+            .get(alias)
+            .cloned()
+            .ok_or_else(|| error::Error::UnknownDatasetAlias {
+                alias: alias.to_owned(),
+            })
+    }
+}
+
+#[async_trait]
+impl DatasetTagDb<UserSession> for ProHashMapDatasetDb {
+    async fn add_dataset_tags(
+        &mut self,
+        _session: &UserSession,
+        dataset: &DatasetId,
+        tags: Validated<Tags>,
+    ) -> Result<Vec<String>> {
+        let dataset = self
+            .datasets
+            .iter_mut()
+            .find(|d| d.id == *dataset)
+            .ok_or(error::Error::UnknownDatasetId)?;
+
+        insert_tags(&mut dataset.tags, tags.user_input.tags);
+
+        Ok(dataset.tags.clone())
+    }
+
+    async fn remove_dataset_tags(
+        &mut self,
+        _session: &UserSession,
+        dataset: &DatasetId,
+        tags: Validated<Tags>,
+    ) -> Result<Vec<String>> {
+        let dataset = self
+            .datasets
+            .iter_mut()
+            .find(|d| d.id == *dataset)
+            .ok_or(error::Error::UnknownDatasetId)?;
+
+        remove_tags(&mut dataset.tags, &tags.user_input.tags);
+
+        Ok(dataset.tags.clone())
+    }
+
+    async fn workflow_tags(
+        &self,
+        _session: &UserSession,
+        workflow: WorkflowId,
+    ) -> Result<Vec<String>> {
+        Ok(self
+            .workflow_tags
+            .get(&workflow)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn add_workflow_tags(
+        &mut self,
+        _session: &UserSession,
+        workflow: WorkflowId,
+        tags: Validated<Tags>,
+    ) -> Result<Vec<String>> {
+        let workflow_tags = self.workflow_tags.entry(workflow).or_default();
+
+        insert_tags(workflow_tags, tags.user_input.tags);
+
+        Ok(workflow_tags.clone())
+    }
+
+    async fn remove_workflow_tags(
+        &mut self,
+        _session: &UserSession,
+        workflow: WorkflowId,
+        tags: Validated<Tags>,
+    ) -> Result<Vec<String>> {
+        let workflow_tags = self.workflow_tags.entry(workflow).or_default();
+
+        remove_tags(workflow_tags, &tags.user_input.tags);
+
+        Ok(workflow_tags.clone())
+    }
+
+    async fn search_datasets(
+        &self,
+        session: &UserSession,
+        options: Validated<DatasetSearchOptions>,
+    ) -> Result<DatasetSearchResult> {
+        let options = options.user_input;
+
+        if let Some(provider) = options.provider {
+            // the search needs all datasets to count the facets
+            let datasets = self
+                .dataset_provider(session, provider)
+                .await?
+                .list(Validated {
+                    user_input: DatasetListOptions {
+                        filter: None,
+                        order: OrderBy::NameAsc,
+                        offset: 0,
+                        limit: u32::MAX,
+                    },
+                })
+                .await?;
+
+            return Ok(search_datasets(
+                datasets.into_iter().map(|dataset| (dataset, None)),
+                &options,
+            ));
+        }
+
+        Ok(search_datasets(
+            self.catalog_datasets()
+                .map(|dataset| (dataset.listing(), dataset.temporal_extent)),
+            &options,
+        ))
+    }
+}
+
+#[async_trait]
+impl ScratchDatasetDb<UserSession> for ProHashMapDatasetDb {
+    async fn add_scratch_dataset(
+        &mut self,
+        session: &UserSession,
+        dataset: Validated<AddDataset>,
+        meta_data: Self::StorageType,
+        upload: UploadId,
+        expires: DateTime<Utc>,
+    ) -> Result<DatasetId> {
+        let id = self.add_dataset(session, dataset, meta_data).await?;
+
+        self.scratch_datasets.insert(
+            id.internal().expect("from AddDataset"),
+            ScratchDataset {
+                session: session.id(),
+                upload,
+                expires,
+            },
+        );
+
+        Ok(id)
+    }
+
+    async fn list_scratch_datasets(
+        &self,
+        session: &UserSession,
+    ) -> Result<Vec<ScratchDatasetListing>> {
+        let now = Utc::now();
+
+        let mut list: Vec<_> = self
+            .datasets
+            .iter()
+            .filter_map(|d| {
+                let scratch = self.scratch_datasets.get(&d.id.internal()?)?;
+                (scratch.session == session.id() && scratch.expires > now).then(|| {
+                    ScratchDatasetListing {
+                        id: d.id.clone(),
+                        name: d.name.clone(),
+                        expires: scratch.expires,
+                    }
+                })
+            })
+            .collect();
+        list.sort_by_key(|d| d.expires);
+
+        Ok(list)
+    }
+
+    async fn remove_expired_scratch_datasets(
+        &mut self,
+        now: DateTime<Utc>,
+    ) -> Result<ExpiredScratchDatasets> {
+        let expired: Vec<InternalDatasetId> = self
+            .scratch_datasets
+            .iter()
+            .filter(|(_, scratch)| scratch.expires <= now)
+            .map(|(id, _)| *id)
+            .collect();
+
+        let mut uploads = Vec::new();
+        for id in &expired {
+            if let Some(scratch) = self.scratch_datasets.remove(id) {
+                if !uploads.contains(&scratch.upload) {
+                    uploads.push(scratch.upload);
+                }
+            }
+
+            self.datasets.retain(|d| d.id.internal() != Some(*id));
+            self.dataset_owners.remove(id);
+            self.ogr_datasets.remove(id);
+            self.mock_datasets.remove(id);
+            self.gdal_datasets.remove(id);
+        }
+
+        // keep the uploads that are still used by other scratch datasets
+        uploads.retain(|upload| {
+            !self
+                .scratch_datasets
+                .values()
+                .any(|scratch| scratch.upload == *upload)
+        });
+
+        for upload in &uploads {
+            self.uploads.remove(upload);
+            self.upload_owners.remove(upload);
+        }
+
+        Ok(ExpiredScratchDatasets {
+            datasets: expired.into_iter().map(Into::into).collect(),
+            uploads,
+        })
+    }
+}
+
 pub trait ProHashMapStorable: Send + Sync {
     fn store(&self, id: InternalDatasetId, db: &mut ProHashMapDatasetDb) -> TypedResultDescriptor;
 }
@@ -151,7 +465,7 @@ impl ProHashMapStorable for GdalMetaDataStatic {
 impl DatasetStore<UserSession> for ProHashMapDatasetDb {
     async fn add_dataset(
         &mut self,
-        _session: &UserSession,
+        session: &UserSession,
         dataset: Validated<AddDataset>,
         meta_data: Box<dyn ProHashMapStorable>,
     ) -> Result<DatasetId> {
@@ -159,7 +473,8 @@ impl DatasetStore<UserSession> for ProHashMapDatasetDb {
         let id = dataset
             .id
             .unwrap_or_else(|| InternalDatasetId::new().into());
-        let result_descriptor = meta_data.store(id.internal().expect("from AddDataset"), self);
+        let internal_id = id.internal().expect("from AddDataset");
+        let result_descriptor = meta_data.store(internal_id, self);
 
         let d: Dataset = Dataset {
             id: id.clone(),
@@ -169,8 +484,11 @@ impl DatasetStore<UserSession> for ProHashMapDatasetDb {
             source_operator: dataset.source_operator,
             symbology: dataset.symbology,
             provenance: dataset.provenance,
+            tags: dataset.tags,
+            temporal_extent: dataset.temporal_extent,
         };
         self.datasets.push(d);
+        self.dataset_owners.insert(internal_id, session.user.id);
 
         Ok(id)
     }
@@ -193,12 +511,11 @@ impl DatasetProvider for ProHashMapDatasetDb {
         let options = options.user_input;
 
         let mut list: Vec<_> = if let Some(filter) = &options.filter {
-            self.datasets
-                .iter()
+            self.catalog_datasets()
                 .filter(|d| d.name.contains(filter) || d.description.contains(filter))
                 .collect()
         } else {
-            self.datasets.iter().collect()
+            self.catalog_datasets().collect()
         };
 
         match options.order {
@@ -336,6 +653,19 @@ impl ProvenanceProvider for ProHashMapDatasetDb {
                     .provenance(dataset)
                     .await
             }
+            DatasetId::Alias { alias } => {
+                let dataset: DatasetId = self.resolve_dataset_alias(alias).await?.into();
+                self.dataset_provider(
+                    &UserSession::mock(),
+                    dataset
+                        .external()
+                        .expect("resolved alias is external")
+                        .provider_id,
+                ) // TODO: get correct session into dataset provider
+                .await?
+                .provenance(&dataset)
+                .await
+            }
         }
     }
 }
@@ -370,6 +700,8 @@ mod tests {
             source_operator: "OgrSource".to_string(),
             symbology: None,
             provenance: None,
+            tags: vec![],
+            temporal_extent: None,
         };
 
         let meta = StaticMetaData {
@@ -383,6 +715,8 @@ mod tests {
                 force_ogr_spatial_filter: false,
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
+                encoding: None,
+                layer_spatial_reference: None,
             },
             result_descriptor: descriptor.clone(),
             phantom: Default::default(),
@@ -452,9 +786,103 @@ impl UploadDb<UserSession> for ProHashMapDatasetDb {
             .ok_or(error::Error::UnknownUploadId)
     }
 
-    async fn create_upload(&mut self, _session: &UserSession, upload: Upload) -> Result<()> {
+    async fn create_upload(&mut self, session: &UserSession, upload: Upload) -> Result<()> {
         // TODO: user permission
+        let requested_bytes: u64 = upload.files.iter().map(|f| f.byte_size as u64).sum();
+        self.user_upload_quota(session.user.id)
+            .await?
+            .check(requested_bytes)?;
+
+        self.upload_owners.insert(upload.id, session.user.id);
         self.uploads.insert(upload.id, upload);
         Ok(())
     }
+
+    async fn upload_quota(&self, session: &UserSession) -> Result<Option<UploadQuota>> {
+        self.user_upload_quota(session.user.id).await.map(Some)
+    }
+}
+
+#[async_trait]
+impl UserStorageDb for ProHashMapDatasetDb {
+    async fn list_user_datasets(&self, session: &UserSession) -> Result<Vec<DatasetListing>> {
+        let mut list: Vec<_> = self
+            .datasets
+            .iter()
+            .filter(|d| {
+                d.id.internal().and_then(|id| self.dataset_owners.get(&id))
+                    == Some(&session.user.id)
+            })
+            .collect();
+        list.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(list.into_iter().map(Dataset::listing).collect())
+    }
+
+    async fn storage_usage(&self, user: UserId) -> Result<StorageUsage> {
+        Ok(StorageUsage {
+            used_bytes: self.used_storage_bytes(user).await?,
+            quota_bytes: self.storage_quota(user)?,
+        })
+    }
+
+    async fn list_storage_usage(&self) -> Result<Vec<UserStorageUsage>> {
+        let mut users: Vec<UserId> = self.storage_quotas.keys().copied().collect();
+        for user in self
+            .upload_owners
+            .values()
+            .chain(self.materialization_owners.values())
+        {
+            if !users.contains(user) {
+                users.push(*user);
+            }
+        }
+
+        let mut usages = Vec::with_capacity(users.len());
+        for user in users {
+            usages.push(UserStorageUsage {
+                user,
+                usage: StorageUsage {
+                    used_bytes: self.used_storage_bytes(user).await?,
+                    quota_bytes: self.storage_quota(user)?,
+                },
+            });
+        }
+
+        Ok(usages)
+    }
+
+    async fn set_storage_quota(&mut self, user: UserId, quota_bytes: u64) -> Result<()> {
+        self.storage_quotas.insert(user, quota_bytes);
+        Ok(())
+    }
+
+    async fn add_materialization(
+        &mut self,
+        session: &UserSession,
+        workflow: WorkflowId,
+    ) -> Result<()> {
+        if self.materialization_owners.contains_key(&workflow) {
+            return Ok(());
+        }
+
+        let quota = self.user_upload_quota(session.user.id).await?;
+        ensure!(
+            quota.used_bytes < quota.quota_bytes,
+            error::StorageQuotaExceeded {
+                requested_bytes: 0_u64,
+                used_bytes: quota.used_bytes,
+                quota_bytes: quota.quota_bytes,
+            }
+        );
+
+        self.materialization_owners
+            .insert(workflow, session.user.id);
+        Ok(())
+    }
+
+    async fn remove_materialization(&mut self, workflow: WorkflowId) -> Result<()> {
+        self.materialization_owners.remove(&workflow);
+        Ok(())
+    }
 }
@@ -1,10 +1,14 @@
 mod external;
 mod in_memory;
 mod postgres;
+mod quota;
 mod storage;
 
 pub use in_memory::{ProHashMapDatasetDb, ProHashMapStorable};
 pub use postgres::PostgresDatasetDb;
+pub use quota::{
+    default_storage_quota, StorageUsage, UpdateStorageQuota, UserStorageDb, UserStorageUsage,
+};
 pub use storage::{
     DatasetPermission, DatasetProviderPermission, UserDatasetPermission,
     UserDatasetProviderPermission,
@@ -1,18 +1,25 @@
+use crate::datasets::alias::{DatasetAliasDb, DatasetAliasDefinition};
 use crate::datasets::provenance::{ProvenanceOutput, ProvenanceProvider};
+use crate::datasets::scratch::{ExpiredScratchDatasets, ScratchDatasetDb, ScratchDatasetListing};
 use crate::datasets::storage::{
     AddDataset, Dataset, DatasetDb, DatasetProviderDb, DatasetProviderDefinition,
     DatasetProviderListOptions, DatasetProviderListing, DatasetStore, DatasetStorer,
     MetaDataDefinition,
 };
-use crate::datasets::upload::{Upload, UploadDb, UploadId};
+use crate::datasets::tags::{DatasetSearchOptions, DatasetSearchResult, DatasetTagDb, Tags};
+use crate::datasets::upload::{Upload, UploadDb, UploadId, UploadQuota};
 use crate::error::Result;
+use crate::pro::datasets::quota::{StorageUsage, UserStorageDb, UserStorageUsage};
+use crate::pro::users::UserId;
 use crate::util::user_input::Validated;
+use crate::workflows::workflow::WorkflowId;
 use crate::{
     datasets::listing::{DatasetListOptions, DatasetListing, DatasetProvider},
     pro::users::UserSession,
 };
 use async_trait::async_trait;
-use geoengine_datatypes::dataset::{DatasetId, DatasetProviderId};
+use chrono::{DateTime, Utc};
+use geoengine_datatypes::dataset::{DatasetId, DatasetProviderId, ExternalDatasetId};
 use geoengine_operators::engine::{MetaData, MetaDataProvider, ResultDescriptor};
 
 // TODO: implement in separate PR, need placeholder here to satisfy bounds of `Context`
@@ -47,6 +54,83 @@ impl DatasetProviderDb<UserSession> for PostgresDatasetDb {
     }
 }
 
+#[async_trait]
+impl DatasetAliasDb<UserSession> for PostgresDatasetDb {
+    async fn add_dataset_alias(
+        &mut self,
+        _session: &UserSession,
+        _alias: Validated<DatasetAliasDefinition>,
+    ) -> Result<()> {
+        todo!()
+    }
+
+    async fn list_dataset_aliases(
+        &self,
+        _session: &UserSession,
+    ) -> Result<Vec<DatasetAliasDefinition>> {
+        todo!()
+    }
+
+    async fn resolve_dataset_alias(&self, _alias: &str) -> Result<ExternalDatasetId> {
+        todo!()
+    }
+}
+
+#[async_trait]
+impl DatasetTagDb<UserSession> for PostgresDatasetDb {
+    async fn add_dataset_tags(
+        &mut self,
+        _session: &UserSession,
+        _dataset: &DatasetId,
+        _tags: Validated<Tags>,
+    ) -> Result<Vec<String>> {
+        todo!()
+    }
+
+    async fn remove_dataset_tags(
+        &mut self,
+        _session: &UserSession,
+        _dataset: &DatasetId,
+        _tags: Validated<Tags>,
+    ) -> Result<Vec<String>> {
+        todo!()
+    }
+
+    async fn workflow_tags(
+        &self,
+        _session: &UserSession,
+        _workflow: WorkflowId,
+    ) -> Result<Vec<String>> {
+        todo!()
+    }
+
+    async fn add_workflow_tags(
+        &mut self,
+        _session: &UserSession,
+        _workflow: WorkflowId,
+        _tags: Validated<Tags>,
+    ) -> Result<Vec<String>> {
+        todo!()
+    }
+
+    async fn remove_workflow_tags(
+        &mut self,
+        _session: &UserSession,
+        _workflow: WorkflowId,
+        _tags: Validated<Tags>,
+    ) -> Result<Vec<String>> {
+        todo!()
+    }
+
+    async fn search_datasets(
+        &self,
+        _session: &UserSession,
+        _options: Validated<DatasetSearchOptions>,
+    ) -> Result<DatasetSearchResult> {
+        todo!()
+    }
+}
+
 #[async_trait]
 impl DatasetProvider for PostgresDatasetDb {
     async fn list(
@@ -99,6 +183,35 @@ impl DatasetStore<UserSession> for PostgresDatasetDb {
     }
 }
 
+#[async_trait]
+impl ScratchDatasetDb<UserSession> for PostgresDatasetDb {
+    async fn add_scratch_dataset(
+        &mut self,
+        _session: &UserSession,
+        _dataset: Validated<AddDataset>,
+        _meta_data: i32,
+        _upload: UploadId,
+        _expires: DateTime<Utc>,
+    ) -> Result<DatasetId> {
+        todo!()
+    }
+
+    async fn list_scratch_datasets(
+        &self,
+        _session: &UserSession,
+    ) -> Result<Vec<ScratchDatasetListing>> {
+        todo!()
+    }
+
+    async fn remove_expired_scratch_datasets(
+        &mut self,
+        _now: DateTime<Utc>,
+    ) -> Result<ExpiredScratchDatasets> {
+        // TODO: remove expired scratch datasets once datasets are stored in postgres
+        Ok(ExpiredScratchDatasets::default())
+    }
+}
+
 #[async_trait]
 impl UploadDb<UserSession> for PostgresDatasetDb {
     async fn get_upload(&self, _session: &UserSession, _upload: UploadId) -> Result<Upload> {
@@ -108,6 +221,10 @@ impl UploadDb<UserSession> for PostgresDatasetDb {
     async fn create_upload(&mut self, _session: &UserSession, _upload: Upload) -> Result<()> {
         todo!()
     }
+
+    async fn upload_quota(&self, _session: &UserSession) -> Result<Option<UploadQuota>> {
+        todo!()
+    }
 }
 
 #[async_trait]
@@ -116,3 +233,34 @@ impl ProvenanceProvider for PostgresDatasetDb {
         todo!()
     }
 }
+
+#[async_trait]
+impl UserStorageDb for PostgresDatasetDb {
+    async fn list_user_datasets(&self, _session: &UserSession) -> Result<Vec<DatasetListing>> {
+        todo!()
+    }
+
+    async fn storage_usage(&self, _user: UserId) -> Result<StorageUsage> {
+        todo!()
+    }
+
+    async fn list_storage_usage(&self) -> Result<Vec<UserStorageUsage>> {
+        todo!()
+    }
+
+    async fn set_storage_quota(&mut self, _user: UserId, _quota_bytes: u64) -> Result<()> {
+        todo!()
+    }
+
+    async fn add_materialization(
+        &mut self,
+        _session: &UserSession,
+        _workflow: WorkflowId,
+    ) -> Result<()> {
+        todo!()
+    }
+
+    async fn remove_materialization(&mut self, _workflow: WorkflowId) -> Result<()> {
+        todo!()
+    }
+}
@@ -0,0 +1,66 @@
+use crate::datasets::listing::DatasetListing;
+use crate::error::Result;
+use crate::pro::users::{UserId, UserSession};
+use crate::util::config::{get_config_element, StorageQuota};
+use crate::workflows::workflow::WorkflowId;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageUsage {
+    pub used_bytes: u64,
+    pub quota_bytes: u64,
+}
+
+impl StorageUsage {
+    pub fn available_bytes(&self) -> u64 {
+        self.quota_bytes.saturating_sub(self.used_bytes)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserStorageUsage {
+    pub user: UserId,
+    #[serde(flatten)]
+    pub usage: StorageUsage,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateStorageQuota {
+    pub quota_bytes: u64,
+}
+
+/// The quota of users for whom no admin has set a different one
+pub fn default_storage_quota() -> Result<u64> {
+    Ok(get_config_element::<StorageQuota>()?.default_bytes)
+}
+
+/// The private dataset namespaces of users and the storage their uploads and materializations,
+/// i.e., the tiles of the workflows they archived, occupy.
+/// Uploads that would exceed the quota of their user are rejected by the `UploadDb`.
+#[async_trait]
+pub trait UserStorageDb: Send + Sync {
+    /// Lists the internal datasets that the user of the `session` added
+    async fn list_user_datasets(&self, session: &UserSession) -> Result<Vec<DatasetListing>>;
+
+    async fn storage_usage(&self, user: UserId) -> Result<StorageUsage>;
+
+    /// Lists the usage of all users that store uploads or have an individual quota
+    async fn list_storage_usage(&self) -> Result<Vec<UserStorageUsage>>;
+
+    async fn set_storage_quota(&mut self, user: UserId, quota_bytes: u64) -> Result<()>;
+
+    /// Records that the user of the `session` archived the tiles of the `workflow`,
+    /// s.t. they count against the user's quota. Fails if the quota is already used up.
+    /// Workflows that are already archived by another user stay with that user.
+    async fn add_materialization(
+        &mut self,
+        session: &UserSession,
+        workflow: WorkflowId,
+    ) -> Result<()>;
+
+    async fn remove_materialization(&mut self, workflow: WorkflowId) -> Result<()>;
+}
@@ -1,2 +1,5 @@
+pub mod datasets;
 pub mod projects;
+pub mod slow_queries;
+pub mod tile_archive;
 pub mod users;
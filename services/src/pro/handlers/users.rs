@@ -144,6 +144,7 @@ async fn logout<C: ProContext>(
     ctx: C,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     ctx.user_db_ref_mut().await.logout(session.id).await?;
+    ctx.meta_data_cache().invalidate_session(session.id).await;
     Ok(warp::reply().into_response())
 }
 
@@ -1,5 +1,6 @@
 // This is an inclusion point of Geo Engine Pro
 
+pub mod aois;
 pub mod contexts;
 pub mod datasets;
 pub mod handlers;
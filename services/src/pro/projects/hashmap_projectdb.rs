@@ -287,6 +287,7 @@ mod test {
             description: "Text".into(),
             bounds: strect(),
             time_step: None,
+            playback_interval: None,
         }
         .validated()
         .unwrap();
@@ -298,6 +299,7 @@ mod test {
             description: "Text".into(),
             bounds: strect(),
             time_step: None,
+            playback_interval: None,
         }
         .validated()
         .unwrap();
@@ -309,6 +311,7 @@ mod test {
             description: "Text".into(),
             bounds: strect(),
             time_step: None,
+            playback_interval: None,
         }
         .validated()
         .unwrap();
@@ -384,6 +387,7 @@ mod test {
                 )
                 .unwrap(),
                 time_step: None,
+                playback_interval: None,
             }
             .validated()
             .unwrap();
@@ -415,6 +419,7 @@ mod test {
             bounds: STRectangle::new(SpatialReferenceOption::Unreferenced, 0., 0., 1., 1., 0, 1)
                 .unwrap(),
             time_step: None,
+            playback_interval: None,
         }
         .validated()
         .unwrap();
@@ -440,6 +445,7 @@ mod test {
             bounds: STRectangle::new(SpatialReferenceOption::Unreferenced, 0., 0., 1., 1., 0, 1)
                 .unwrap(),
             time_step: None,
+            playback_interval: None,
         }
         .validated()
         .unwrap();
@@ -460,6 +466,7 @@ mod test {
             bounds: STRectangle::new(SpatialReferenceOption::Unreferenced, 0., 0., 1., 1., 0, 1)
                 .unwrap(),
             time_step: None,
+            playback_interval: None,
         }
         .validated()
         .unwrap();
@@ -474,6 +481,7 @@ mod test {
             plots: None,
             bounds: None,
             time_step: None,
+            playback_interval: None,
         }
         .validated()
         .unwrap();
@@ -494,6 +502,7 @@ mod test {
             bounds: STRectangle::new(SpatialReferenceOption::Unreferenced, 0., 0., 1., 1., 0, 1)
                 .unwrap(),
             time_step: None,
+            playback_interval: None,
         }
         .validated()
         .unwrap();
@@ -514,6 +523,7 @@ mod test {
             bounds: STRectangle::new(SpatialReferenceOption::Unreferenced, 0., 0., 1., 1., 0, 1)
                 .unwrap(),
             time_step: None,
+            playback_interval: None,
         }
         .validated()
         .unwrap();
@@ -530,6 +540,7 @@ mod test {
             plots: None,
             bounds: None,
             time_step: None,
+            playback_interval: None,
         }
         .validated()
         .unwrap();
@@ -553,6 +564,7 @@ mod test {
             bounds: STRectangle::new(SpatialReferenceOption::Unreferenced, 0., 0., 1., 1., 0, 1)
                 .unwrap(),
             time_step: None,
+            playback_interval: None,
         }
         .validated()
         .unwrap();
@@ -601,6 +613,7 @@ mod test {
             bounds: STRectangle::new(SpatialReferenceOption::Unreferenced, 0., 0., 1., 1., 0, 1)
                 .unwrap(),
             time_step: None,
+            playback_interval: None,
         }
         .validated()
         .unwrap();
@@ -649,6 +662,7 @@ mod test {
             bounds: STRectangle::new(SpatialReferenceOption::Unreferenced, 0., 0., 1., 1., 0, 1)
                 .unwrap(),
             time_step: None,
+            playback_interval: None,
         }
         .validated()
         .unwrap();
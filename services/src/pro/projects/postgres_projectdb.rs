@@ -243,10 +243,11 @@ where
                     description,
                     bounds,
                     time_step,
+                    playback_interval,
                     author_user_id,
                     changed,
                     latest)
-                    VALUES ($1, $2, $3, $4, $5, $6, $7, CURRENT_TIMESTAMP, TRUE);",
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, CURRENT_TIMESTAMP, TRUE);",
             )
             .await?;
 
@@ -260,6 +261,7 @@ where
                     &project.description,
                     &project.bounds,
                     &project.time_step,
+                    &project.playback_interval,
                     &session.user.id,
                 ],
             )
@@ -327,10 +329,11 @@ where
                     description,
                     bounds,
                     time_step,
+                    playback_interval,
                     author_user_id,
                     changed,
                     latest)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, CURRENT_TIMESTAMP, TRUE);",
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, CURRENT_TIMESTAMP, TRUE);",
             )
             .await?;
 
@@ -344,6 +347,7 @@ where
                     &project.description,
                     &project.bounds,
                     &project.time_step,
+                    &project.playback_interval,
                     &session.user.id,
                 ],
             )
@@ -451,7 +455,8 @@ where
             p.bounds,
             p.time_step,
             p.changed,
-            p.author_user_id
+            p.author_user_id,
+            p.playback_interval
         FROM user_project_permissions u JOIN project_versions p ON (u.project_id = p.project_id)
         WHERE u.user_id = $1 AND u.project_id = $2 AND p.project_version = $3",
                 )
@@ -471,7 +476,8 @@ where
             p.bounds,
             p.time_step,
             p.changed,
-            p.author_user_id
+            p.author_user_id,
+            p.playback_interval
         FROM user_project_permissions u JOIN project_versions p ON (u.project_id = p.project_id)
         WHERE u.user_id = $1 AND u.project_id = $2 AND latest IS TRUE",
                 )
@@ -488,6 +494,7 @@ where
         let time_step = row.get(5);
         let changed = row.get(6);
         let _author_id = UserId(row.get(7));
+        let playback_interval = row.get(8);
 
         let stmt = conn
             .prepare(
@@ -524,6 +531,7 @@ where
             plots: self.load_plots(&conn, &version_id).await?,
             bounds,
             time_step,
+            playback_interval,
         })
     }
 
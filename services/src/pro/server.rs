@@ -1,3 +1,4 @@
+use crate::datasets::scratch::spawn_scratch_dataset_cleanup;
 use crate::error::{Error, Result};
 use crate::handlers;
 use crate::handlers::handle_rejection;
@@ -18,6 +19,7 @@ use std::path::PathBuf;
 use tokio::sync::oneshot::Receiver;
 use warp::Filter;
 
+use super::datasets::UserStorageDb;
 use super::projects::ProProjectDb;
 
 async fn start<C>(
@@ -29,19 +31,40 @@ async fn start<C>(
 where
     C: ProContext,
     C::ProjectDB: ProProjectDb,
+    C::DatasetDB: UserStorageDb,
 {
     let handler = combine!(
         handlers::workflows::register_workflow_handler(ctx.clone()),
         handlers::workflows::load_workflow_handler(ctx.clone()),
+        handlers::workflows::diff_workflows_handler(ctx.clone()),
+        handlers::workflows::migrate_workflow_handler(ctx.clone()),
+        handlers::workflows::validate_workflow_handler(ctx.clone()),
         handlers::workflows::get_workflow_metadata_handler(ctx.clone()),
         handlers::workflows::get_workflow_provenance_handler(ctx.clone()),
+        handlers::workflows::get_workflow_citation_handler(ctx.clone()),
+        handlers::workflows::get_workflow_sources_handler(ctx.clone()),
+        handlers::workflows::get_workflow_pixel_values_handler(ctx.clone()),
+        handlers::workflows::batch_query_workflow_handler(ctx.clone()),
+        handlers::workflows::get_workflow_tags_handler(ctx.clone()),
+        handlers::workflows::add_workflow_tags_handler(ctx.clone()),
+        handlers::workflows::remove_workflow_tags_handler(ctx.clone()),
+        pro::handlers::tile_archive::archive_workflow_handler(ctx.clone()),
+        handlers::tile_archive::get_workflow_archive_handler(ctx.clone()),
+        handlers::tile_archive::refresh_workflow_archive_handler(ctx.clone()),
+        pro::handlers::tile_archive::remove_workflow_archive_handler(ctx.clone()),
         pro::handlers::users::register_user_handler(ctx.clone()),
         pro::handlers::users::anonymous_handler(ctx.clone()),
         pro::handlers::users::login_handler(ctx.clone()),
         pro::handlers::users::logout_handler(ctx.clone()),
         handlers::session::session_handler(ctx.clone()),
+        handlers::session::session_cache_invalidation_handler(ctx.clone()),
         pro::handlers::users::session_project_handler(ctx.clone()),
         pro::handlers::users::session_view_handler(ctx.clone()),
+        pro::handlers::datasets::list_user_datasets_handler(ctx.clone()),
+        pro::handlers::datasets::storage_quota_handler(ctx.clone()),
+        pro::handlers::datasets::list_storage_usage_handler(ctx.clone()),
+        pro::handlers::datasets::update_storage_quota_handler(ctx.clone()),
+        pro::handlers::slow_queries::list_slow_queries_handler(ctx.clone()),
         pro::handlers::projects::add_permission_handler(ctx.clone()),
         pro::handlers::projects::remove_permission_handler(ctx.clone()),
         pro::handlers::projects::list_permissions_handler(ctx.clone()),
@@ -50,20 +73,44 @@ where
         handlers::projects::update_project_handler(ctx.clone()),
         handlers::projects::delete_project_handler(ctx.clone()),
         pro::handlers::projects::load_project_handler(ctx.clone()),
+        handlers::projects::project_playback_frames_handler(ctx.clone()),
         pro::handlers::projects::project_versions_handler(ctx.clone()),
         handlers::datasets::list_external_datasets_handler(ctx.clone()),
+        handlers::datasets::search_datasets_handler(ctx.clone()),
         handlers::datasets::list_datasets_handler(ctx.clone()),
         handlers::datasets::list_providers_handler(ctx.clone()),
         handlers::datasets::get_dataset_handler(ctx.clone()),
+        handlers::datasets::get_dataset_metadata_handler(ctx.clone()),
+        handlers::datasets::get_external_dataset_handler(ctx.clone()),
+        handlers::datasets::dataset_gap_report_handler(ctx.clone()),
+        handlers::datasets::add_dataset_alias_handler(ctx.clone()),
+        handlers::datasets::list_dataset_aliases_handler(ctx.clone()),
+        handlers::datasets::add_dataset_tags_handler(ctx.clone()),
+        handlers::datasets::remove_dataset_tags_handler(ctx.clone()),
+        handlers::datasets::create_scratch_dataset_handler(ctx.clone()),
+        handlers::datasets::list_scratch_datasets_handler(ctx.clone()),
         handlers::datasets::auto_create_dataset_handler(ctx.clone()),
         handlers::datasets::create_dataset_handler(ctx.clone()),
         handlers::datasets::suggest_meta_data_handler(ctx.clone()),
+        handlers::layers::list_root_collections_handler(ctx.clone()),
+        handlers::layers::get_collection_handler(ctx.clone()),
+        handlers::layers::get_layer_handler(ctx.clone()),
+        handlers::aois::add_aoi_handler(ctx.clone()),
+        handlers::aois::list_aois_handler(ctx.clone()),
+        handlers::aois::load_aoi_handler(ctx.clone()),
+        handlers::aois::delete_aoi_handler(ctx.clone()),
+        handlers::csw::csw_handler(ctx.clone()),
         handlers::wcs::wcs_handler(ctx.clone()),
         handlers::wms::wms_handler(ctx.clone()),
+        handlers::basemap::basemap_tile_handler(),
         handlers::wfs::wfs_handler(ctx.clone()),
         handlers::plots::get_plot_handler(ctx.clone()),
+        handlers::aggregates::get_aggregate_handler(ctx.clone()),
+        handlers::reports::create_report_handler(ctx.clone()),
+        handlers::tables::get_table_handler(ctx.clone()),
         handlers::upload::upload_handler(ctx.clone()),
         handlers::spatial_references::get_spatial_reference_specification_handler(ctx.clone()),
+        handlers::operators::list_operators_handler(ctx.clone()),
         serve_static_directory(static_files_dir)
     )
     .recover(handle_rejection);
@@ -115,13 +162,13 @@ pub async fn start_pro_server(
     match web_config.backend {
         Backend::InMemory => {
             info!("Using in memory backend"); // TODO: log
-            start(
-                shutdown_rx,
-                static_files_dir,
-                bind_address,
-                ProInMemoryContext::new_with_data().await,
-            )
-            .await
+
+            let ctx = ProInMemoryContext::new_with_data().await;
+
+            // the Postgres backend does not support scratch datasets yet
+            spawn_scratch_dataset_cleanup(ctx.clone())?;
+
+            start(shutdown_rx, static_files_dir, bind_address, ctx).await
         }
         Backend::Postgres => {
             #[cfg(feature = "postgres")]
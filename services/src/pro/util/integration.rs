@@ -0,0 +1,299 @@
+//! Support for integration tests that need Postgres, an S3 compatible object storage or a STAC API.
+//!
+//! The services are defined in `docker/test/docker-compose.yml` and started once per test run.
+//! Tests that use them are `#[ignore]`d and run with `cargo test --features pro -- --ignored`.
+
+use std::net::TcpStream;
+use std::path::Path;
+use std::process::Command;
+use std::str::FromStr;
+use std::time::Duration;
+
+use bb8_postgres::tokio_postgres::{self, NoTls};
+use lazy_static::lazy_static;
+use uuid::Uuid;
+
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_operators::util::gdal::create_ndvi_meta_data;
+
+use crate::contexts::Context;
+use crate::datasets::provenance::Provenance;
+use crate::datasets::storage::{AddDataset, DatasetDefinition, DatasetStore, MetaDataDefinition};
+use crate::pro::contexts::{PostgresContext, ProContext};
+use crate::pro::users::{UserCredentials, UserDb, UserRegistration, UserSession};
+use crate::util::config::{self, get_config_element};
+use crate::util::user_input::UserInput;
+
+const COMPOSE_PROJECT: &str = "geoengine-test";
+const CONNECTION_ATTEMPTS: u32 = 30;
+const CONNECTION_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// The password of all users that the fixtures create
+pub const FIXTURE_PASSWORD: &str = "secret123";
+
+lazy_static! {
+    static ref TEST_ENVIRONMENT: TestEnvironment = TestEnvironment::start();
+}
+
+/// The running dependencies of the integration tests.
+///
+/// Each test gets its own database and object storage prefix, so tests can run in parallel.
+#[derive(Debug)]
+pub struct TestEnvironment {
+    config: config::IntegrationTests,
+}
+
+impl TestEnvironment {
+    /// Starts the containers on first use and waits until they accept connections
+    pub fn get() -> &'static Self {
+        &TEST_ENVIRONMENT
+    }
+
+    fn start() -> Self {
+        let config = get_config_element::<config::IntegrationTests>()
+            .expect("`integration_tests` must be configured in `Settings-test.toml`");
+
+        if config.start_containers {
+            docker_compose(&["up", "--detach", "postgres", "object-storage", "stac"]);
+            // finishes when the bucket exists
+            docker_compose(&["run", "--rm", "object-storage-setup"]);
+        }
+
+        wait_for_port(&config.object_storage_url);
+        wait_for_port(&config.stac_url);
+
+        Self { config }
+    }
+
+    /// Creates a `PostgresContext` on a new, empty database
+    #[allow(clippy::missing_panics_doc)]
+    pub async fn postgres_context(&self) -> PostgresContext<NoTls> {
+        let mut pg_config =
+            tokio_postgres::Config::from_str(&self.config.postgres).expect("valid config string");
+
+        let client = connect_with_retries(&pg_config).await;
+
+        let database = format!("test_{}", Uuid::new_v4().to_simple());
+        client
+            .batch_execute(&format!("CREATE DATABASE {}", database))
+            .await
+            .expect("test database must be creatable");
+
+        pg_config.dbname(&database);
+
+        PostgresContext::new(pg_config, NoTls)
+            .await
+            .expect("context must be creatable")
+    }
+
+    /// Returns the url of a new, empty prefix in the test bucket
+    pub fn object_storage_url(&self) -> String {
+        format!(
+            "{}/{}",
+            self.config.object_storage_url.trim_end_matches('/'),
+            Uuid::new_v4()
+        )
+    }
+
+    /// The url of a STAC item search that returns the items of `docker/test/stac/search.json`
+    pub fn stac_url(&self) -> &str {
+        &self.config.stac_url
+    }
+}
+
+fn docker_compose(args: &[&str]) {
+    let compose_file =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../docker/test/docker-compose.yml");
+
+    let status = Command::new("docker-compose")
+        .arg("--file")
+        .arg(compose_file)
+        .args(&["--project-name", COMPOSE_PROJECT])
+        .args(args)
+        .status()
+        .expect("`docker-compose` must be installed");
+
+    assert!(
+        status.success(),
+        "`docker-compose {}` failed with {}",
+        args.join(" "),
+        status
+    );
+}
+
+fn wait_for_port(url: &str) {
+    let addresses = reqwest::Url::parse(url)
+        .expect("valid url")
+        .socket_addrs(|| None)
+        .expect("resolvable host");
+
+    for _ in 0..CONNECTION_ATTEMPTS {
+        if TcpStream::connect(&*addresses).is_ok() {
+            return;
+        }
+        std::thread::sleep(CONNECTION_RETRY_DELAY);
+    }
+
+    panic!("`{}` is not reachable", url);
+}
+
+/// Postgres does not accept connections until it is initialized, which takes a while after startup
+async fn connect_with_retries(pg_config: &tokio_postgres::Config) -> tokio_postgres::Client {
+    let mut attempt = 1;
+
+    loop {
+        match pg_config.connect(NoTls).await {
+            Ok((client, connection)) => {
+                tokio::spawn(connection);
+                return client;
+            }
+            Err(error) if attempt >= CONNECTION_ATTEMPTS => {
+                panic!("cannot connect to Postgres: {}", error)
+            }
+            Err(_) => {
+                attempt += 1;
+                tokio::time::sleep(CONNECTION_RETRY_DELAY).await;
+            }
+        }
+    }
+}
+
+/// An admin and a regular user that are registered and logged in
+pub struct UserFixtures {
+    pub admin: UserSession,
+    pub user: UserSession,
+}
+
+/// Registers and logs in an admin, i.e., the first configured admin email, and a regular user
+#[allow(clippy::missing_panics_doc)]
+pub async fn seed_users<C: ProContext>(ctx: &C) -> UserFixtures {
+    let admin_email = get_config_element::<config::Admin>()
+        .expect("admins must be configured")
+        .emails
+        .into_iter()
+        .next()
+        .expect("at least one admin must be configured");
+
+    UserFixtures {
+        admin: seed_user(ctx, &admin_email).await,
+        user: seed_user(ctx, "user@geoengine.de").await,
+    }
+}
+
+/// Registers and logs in a user with the `FIXTURE_PASSWORD`
+#[allow(clippy::missing_panics_doc)]
+pub async fn seed_user<C: ProContext>(ctx: &C, email: &str) -> UserSession {
+    let mut user_db = ctx.user_db_ref_mut().await;
+
+    user_db
+        .register(
+            UserRegistration {
+                email: email.to_string(),
+                password: FIXTURE_PASSWORD.to_string(),
+                real_name: email.to_string(),
+            }
+            .validated()
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    user_db
+        .login(UserCredentials {
+            email: email.to_string(),
+            password: FIXTURE_PASSWORD.to_string(),
+        })
+        .await
+        .unwrap()
+}
+
+/// Adds the MODIS NDVI test dataset on behalf of the `session`
+#[allow(clippy::missing_panics_doc)]
+pub async fn seed_ndvi_dataset<C: ProContext>(ctx: &C, session: &UserSession) -> DatasetId {
+    let ndvi = DatasetDefinition {
+        properties: AddDataset {
+            id: None,
+            name: "NDVI".to_string(),
+            description: "NDVI data from MODIS".to_string(),
+            source_operator: "GdalSource".to_string(),
+            symbology: None,
+            provenance: Some(Provenance {
+                citation: "Sample Citation".to_owned(),
+                license: "Sample License".to_owned(),
+                uri: "http://example.org/".to_owned(),
+            }),
+            tags: vec![],
+            temporal_extent: None,
+        },
+        meta_data: MetaDataDefinition::GdalMetaDataRegular(create_ndvi_meta_data()),
+    };
+
+    let mut dataset_db = ctx.dataset_db_ref_mut().await;
+    let meta_data = dataset_db.wrap_meta_data(ndvi.meta_data);
+
+    dataset_db
+        .add_dataset(session, ndvi.properties.validated().unwrap(), meta_data)
+        .await
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasets::listing::DatasetProvider;
+    use crate::pro::contexts::ProInMemoryContext;
+    use crate::tile_archive::{ObjectStorageTileArchiveStore, TileArchiveStore};
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_fixtures() {
+        let ctx = TestEnvironment::get().postgres_context().await;
+
+        let users = seed_users(&ctx).await;
+
+        assert_ne!(users.admin.user.id, users.user.user.id);
+        assert_eq!(users.user.user.email.as_deref(), Some("user@geoengine.de"));
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn databases_are_isolated() {
+        let environment = TestEnvironment::get();
+
+        // registering the same user twice would fail if the contexts shared a database
+        seed_user(&environment.postgres_context().await, "foo@bar.de").await;
+        seed_user(&environment.postgres_context().await, "foo@bar.de").await;
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn object_storage() {
+        let store = ObjectStorageTileArchiveStore::new(
+            TestEnvironment::get().object_storage_url(),
+            reqwest::Client::new(),
+        );
+
+        assert_eq!(store.load("foo").await.unwrap(), None);
+
+        store.store("foo", vec![1, 2, 3]).await.unwrap();
+        assert_eq!(store.load("foo").await.unwrap(), Some(vec![1, 2, 3]));
+    }
+
+    #[tokio::test]
+    async fn in_memory_fixtures() {
+        let ctx = ProInMemoryContext::default();
+
+        let users = seed_users(&ctx).await;
+        let dataset = seed_ndvi_dataset(&ctx, &users.user).await;
+
+        assert_eq!(
+            ctx.dataset_db_ref()
+                .await
+                .load(&dataset)
+                .await
+                .unwrap()
+                .name,
+            "NDVI"
+        );
+    }
+}
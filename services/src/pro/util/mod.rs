@@ -1 +1,3 @@
+#[cfg(test)]
+pub mod integration;
 pub mod tests;
@@ -81,6 +81,7 @@ pub async fn create_project_helper<C: ProContext>(ctx: &C) -> (UserSession, Proj
                 )
                 .unwrap(),
                 time_step: None,
+                playback_interval: None,
             }
             .validated()
             .unwrap(),
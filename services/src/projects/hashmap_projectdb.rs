@@ -131,6 +131,7 @@ mod test {
                 )
                 .unwrap(),
                 time_step: None,
+                playback_interval: None,
             }
             .validated()
             .unwrap();
@@ -162,6 +163,7 @@ mod test {
             bounds: STRectangle::new(SpatialReferenceOption::Unreferenced, 0., 0., 1., 1., 0, 1)
                 .unwrap(),
             time_step: None,
+            playback_interval: None,
         }
         .validated()
         .unwrap();
@@ -183,6 +185,7 @@ mod test {
             bounds: STRectangle::new(SpatialReferenceOption::Unreferenced, 0., 0., 1., 1., 0, 1)
                 .unwrap(),
             time_step: None,
+            playback_interval: None,
         }
         .validated()
         .unwrap();
@@ -203,6 +206,7 @@ mod test {
             bounds: STRectangle::new(SpatialReferenceOption::Unreferenced, 0., 0., 1., 1., 0, 1)
                 .unwrap(),
             time_step: None,
+            playback_interval: None,
         }
         .validated()
         .unwrap();
@@ -217,6 +221,7 @@ mod test {
             plots: None,
             bounds: None,
             time_step: None,
+            playback_interval: None,
         }
         .validated()
         .unwrap();
@@ -237,6 +242,7 @@ mod test {
             bounds: STRectangle::new(SpatialReferenceOption::Unreferenced, 0., 0., 1., 1., 0, 1)
                 .unwrap(),
             time_step: None,
+            playback_interval: None,
         }
         .validated()
         .unwrap();
@@ -9,7 +9,9 @@ use chrono::{DateTime, Utc};
 use geoengine_datatypes::{identifier, operations::image::RgbaColor};
 use geoengine_datatypes::{operations::image::Colorizer, primitives::TimeInstance};
 use geoengine_datatypes::{
-    primitives::{BoundingBox2D, Coordinate2D, TimeGranularity, TimeInterval, TimeStep},
+    primitives::{
+        BoundingBox2D, Coordinate2D, TimeGranularity, TimeInterval, TimeStep, TimeStepIter,
+    },
     spatial_reference::SpatialReferenceOption,
 };
 use geoengine_datatypes::{
@@ -35,6 +37,9 @@ pub struct Project {
     pub plots: Vec<Plot>,
     pub bounds: STRectangle,
     pub time_step: TimeStep,
+    /// The time range that is played back in steps of `time_step`.
+    /// If it is not set, the time interval of the `bounds` is played back.
+    pub playback_interval: Option<TimeInterval>,
 }
 
 impl Project {
@@ -52,9 +57,38 @@ impl Project {
                 granularity: TimeGranularity::Days,
                 step: 1,
             }),
+            playback_interval: create.playback_interval,
         }
     }
 
+    /// Computes the start of each animation frame, i.e., the instants in steps of the `time_step`
+    /// from the start of the playback interval until its end.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the playback interval contains more than the configured maximum number of frames.
+    ///
+    pub fn playback_frames(&self) -> Result<Vec<TimeInstance>> {
+        let interval = self.playback_interval.unwrap_or(self.bounds.time_interval);
+
+        let frame_limit = get_config_element::<ProjectService>()?.playback_frame_limit;
+        let steps = self
+            .time_step
+            .num_steps_in_interval(interval)
+            .context(error::DataType)?;
+
+        ensure!(
+            steps < frame_limit,
+            error::TooManyPlaybackFrames { limit: frame_limit }
+        );
+
+        Ok(
+            TimeStepIter::new_with_interval_incl_start(interval, self.time_step)
+                .context(error::DataType)?
+                .collect(),
+        )
+    }
+
     /// Updates a project with partial fields.
     ///
     /// If the updates layer list is longer than the current list,
@@ -115,6 +149,10 @@ impl Project {
             project.time_step = time_step;
         }
 
+        if let Some(playback_interval) = update.playback_interval {
+            project.playback_interval = Some(playback_interval);
+        }
+
         Ok(project)
     }
 }
@@ -422,6 +460,8 @@ pub struct CreateProject {
     pub description: String,
     pub bounds: STRectangle,
     pub time_step: Option<TimeStep>,
+    #[serde(default)]
+    pub playback_interval: Option<TimeInterval>,
 }
 
 impl UserInput for CreateProject {
@@ -431,6 +471,10 @@ impl UserInput for CreateProject {
             error::ProjectCreateFailed
         );
 
+        if let Some(time_step) = self.time_step {
+            ensure!(time_step.step > 0, error::ProjectCreateFailed);
+        }
+
         Ok(())
     }
 }
@@ -445,6 +489,8 @@ pub struct UpdateProject {
     pub plots: Option<Vec<PlotUpdate>>,
     pub bounds: Option<STRectangle>,
     pub time_step: Option<TimeStep>,
+    #[serde(default)]
+    pub playback_interval: Option<TimeInterval>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -471,6 +517,10 @@ impl UserInput for UpdateProject {
             ensure!(!description.is_empty(), error::ProjectUpdateFailed);
         }
 
+        if let Some(time_step) = self.time_step {
+            ensure!(time_step.step > 0, error::ProjectUpdateFailed);
+        }
+
         // TODO: layers
 
         Ok(())
@@ -646,6 +696,7 @@ mod tests {
                 step: 1,
                 granularity: TimeGranularity::Days,
             }),
+            playback_interval: None,
         };
 
         let serialized = serde_json::to_string(&update).unwrap();
@@ -0,0 +1,625 @@
+use geoengine_datatypes::operations::image::{Colorizer, RgbaColor};
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, BoundingBox2D, Coordinate2D, TimeInterval,
+};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, Pixel, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::error::{self, Result};
+use crate::util::user_input::UserInput;
+
+mod font;
+mod pdf;
+
+const MILLIMETERS_PER_INCH: f64 = 25.4;
+const POINTS_PER_INCH: f64 = 72.;
+const MARGIN: f64 = 10.;
+const LEGEND_LABEL_WIDTH: f64 = 50.;
+const LEGEND_RAMP_WIDTH: f64 = 60.;
+const LEGEND_RAMP_STEPS: usize = 60;
+
+/// The parameters of a printable report of a project
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportRequest {
+    /// the extent of the map in the spatial reference of the project, defaults to the project bounds
+    pub bbox: Option<BoundingBox2D>,
+    /// defaults to the time interval of the project bounds
+    pub time: Option<TimeInterval>,
+    /// defaults to the project name
+    pub title: Option<String>,
+    #[serde(default)]
+    pub format: ReportFormat,
+    /// the width of the map on paper in millimeters
+    #[serde(default = "default_map_width")]
+    pub map_width: f64,
+    /// the height of the map on paper in millimeters
+    #[serde(default = "default_map_height")]
+    pub map_height: f64,
+    #[serde(default = "default_dpi")]
+    pub dpi: u32,
+}
+
+fn default_map_width() -> f64 {
+    180.
+}
+
+fn default_map_height() -> f64 {
+    120.
+}
+
+fn default_dpi() -> u32 {
+    150
+}
+
+impl ReportRequest {
+    /// The size of the map in pixels at the requested resolution
+    pub fn map_pixels(&self) -> (u32, u32) {
+        (
+            millimeters_to_pixels(self.map_width, self.dpi),
+            millimeters_to_pixels(self.map_height, self.dpi),
+        )
+    }
+}
+
+impl UserInput for ReportRequest {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            (10. ..=1000.).contains(&self.map_width) && (10. ..=1000.).contains(&self.map_height),
+            error::InvalidReport {
+                reason: "the map size must be between 10 and 1000 millimeters"
+            }
+        );
+        ensure!(
+            (50..=600).contains(&self.dpi),
+            error::InvalidReport {
+                reason: "the resolution must be between 50 and 600 dpi"
+            }
+        );
+
+        let (width, height) = self.map_pixels();
+        ensure!(
+            width <= 10_000 && height <= 10_000,
+            error::InvalidReport {
+                reason: "the map must not exceed 10000 pixels per axis"
+            }
+        );
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReportFormat {
+    Pdf,
+    Png,
+}
+
+impl Default for ReportFormat {
+    fn default() -> Self {
+        Self::Pdf
+    }
+}
+
+impl ReportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ReportFormat::Pdf => "application/pdf",
+            ReportFormat::Png => "image/png",
+        }
+    }
+}
+
+/// The contents of a report that are arranged on a single page
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub title: String,
+    pub map: RgbaImage,
+    /// the size of the map on paper in millimeters
+    pub map_size: [f64; 2],
+    /// the ground distance in meters that one millimeter of the map covers, if it is known
+    pub meters_per_millimeter: Option<f64>,
+    /// the layer names and their colorizers
+    pub legend: Vec<(String, Colorizer)>,
+    pub attribution: Vec<String>,
+}
+
+impl Report {
+    pub fn to_pdf(&self) -> Vec<u8> {
+        pdf::write_pdf(&self.layout())
+    }
+
+    pub fn to_png(&self, dpi: u32) -> Result<Vec<u8>> {
+        let page = self.layout();
+
+        let mut image = RgbaImage::from_pixel(
+            millimeters_to_pixels(page.width, dpi),
+            millimeters_to_pixels(page.height, dpi),
+            Rgba([255, 255, 255, 255]),
+        );
+
+        for element in &page.elements {
+            match element {
+                Element::Image {
+                    x,
+                    y,
+                    width,
+                    height,
+                    image: source,
+                } => {
+                    let source = image::imageops::resize(
+                        source,
+                        millimeters_to_pixels(*width, dpi),
+                        millimeters_to_pixels(*height, dpi),
+                        FilterType::Nearest,
+                    );
+                    image::imageops::overlay(
+                        &mut image,
+                        &source,
+                        millimeters_to_pixels(*x, dpi),
+                        millimeters_to_pixels(*y, dpi),
+                    );
+                }
+                Element::Rectangle {
+                    x,
+                    y,
+                    width,
+                    height,
+                    color,
+                } => {
+                    let x_range = millimeters_to_pixels(*x, dpi)
+                        ..millimeters_to_pixels(x + width, dpi).min(image.width());
+                    let y_range = millimeters_to_pixels(*y, dpi)
+                        ..millimeters_to_pixels(y + height, dpi).min(image.height());
+                    let color = Rgba::from(*color);
+
+                    for y in y_range {
+                        for x in x_range.clone() {
+                            image.get_pixel_mut(x, y).blend(&color);
+                        }
+                    }
+                }
+                Element::Text { x, y, size, text } => font::draw_text(
+                    &mut image,
+                    millimeters_to_fractional_pixels(*x, dpi),
+                    millimeters_to_fractional_pixels(*y, dpi),
+                    size * f64::from(dpi) / POINTS_PER_INCH,
+                    text,
+                ),
+            }
+        }
+
+        let mut buffer = Vec::new();
+        DynamicImage::ImageRgba8(image)
+            .write_to(&mut buffer, ImageFormat::Png)
+            .map_err(|error| error::Error::InvalidReport {
+                reason: format!("encoding PNG failed: {}", error),
+            })?;
+
+        Ok(buffer)
+    }
+
+    /// Arranges the title, the map, the scalebar, the legend and the attribution from top to bottom
+    fn layout(&self) -> Page {
+        let [map_width, map_height] = self.map_size;
+        let width = map_width.max(LEGEND_LABEL_WIDTH + LEGEND_RAMP_WIDTH) + 2. * MARGIN;
+
+        let mut elements = Vec::new();
+        let mut y = MARGIN;
+
+        elements.push(Element::Text {
+            x: MARGIN,
+            y: y + 6.,
+            size: 16.,
+            text: self.title.clone(),
+        });
+        y += 12.;
+
+        elements.push(Element::Image {
+            x: MARGIN,
+            y,
+            width: map_width,
+            height: map_height,
+            image: self.map.clone(),
+        });
+        y += map_height + 4.;
+
+        if let Some(meters_per_millimeter) = self.meters_per_millimeter {
+            y = layout_scalebar(&mut elements, y, map_width, meters_per_millimeter);
+        }
+
+        for (name, colorizer) in &self.legend {
+            y = layout_legend_entry(&mut elements, y, name, colorizer);
+        }
+
+        if !self.attribution.is_empty() {
+            y += 2.;
+
+            let text = format!("Data: {}", self.attribution.join("; "));
+            for line in wrap_text(&text, width - 2. * MARGIN, 7.) {
+                elements.push(Element::Text {
+                    x: MARGIN,
+                    y: y + 3.,
+                    size: 7.,
+                    text: line,
+                });
+                y += 3.5;
+            }
+        }
+
+        Page {
+            width,
+            height: y + MARGIN,
+            elements,
+        }
+    }
+}
+
+/// A page with its size and elements in millimeters from the upper left corner
+#[derive(Debug, Clone)]
+struct Page {
+    width: f64,
+    height: f64,
+    elements: Vec<Element>,
+}
+
+#[derive(Debug, Clone)]
+enum Element {
+    Image {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        image: RgbaImage,
+    },
+    Rectangle {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        color: RgbaColor,
+    },
+    /// a text whose baseline starts at `x`, `y` with a font `size` in points
+    Text {
+        x: f64,
+        y: f64,
+        size: f64,
+        text: String,
+    },
+}
+
+fn layout_scalebar(
+    elements: &mut Vec<Element>,
+    y: f64,
+    map_width: f64,
+    meters_per_millimeter: f64,
+) -> f64 {
+    let length = nice_distance(meters_per_millimeter * map_width / 4.);
+    let bar_width = length / meters_per_millimeter;
+
+    for (i, color) in [RgbaColor::black(), RgbaColor::new(160, 160, 160, 255)]
+        .iter()
+        .enumerate()
+    {
+        elements.push(Element::Rectangle {
+            x: MARGIN + i as f64 * bar_width / 2.,
+            y,
+            width: bar_width / 2.,
+            height: 1.5,
+            color: *color,
+        });
+    }
+
+    for (i, distance) in [0., length / 2., length].iter().enumerate() {
+        elements.push(Element::Text {
+            x: MARGIN + i as f64 * bar_width / 2.,
+            y: y + 5.,
+            size: 7.,
+            text: format_distance(*distance),
+        });
+    }
+
+    elements.push(Element::Text {
+        x: MARGIN + bar_width + 15.,
+        y: y + 3.,
+        size: 8.,
+        text: format!("1:{:.0}", meters_per_millimeter * 1000.),
+    });
+
+    y + 9.
+}
+
+fn layout_legend_entry(
+    elements: &mut Vec<Element>,
+    y: f64,
+    name: &str,
+    colorizer: &Colorizer,
+) -> f64 {
+    elements.push(Element::Text {
+        x: MARGIN,
+        y: y + 3.5,
+        size: 9.,
+        text: name.to_string(),
+    });
+
+    let ramp_x = MARGIN + LEGEND_LABEL_WIDTH;
+    let legend = colorizer.legend();
+
+    match colorizer {
+        Colorizer::Palette { .. } => {
+            let box_width = LEGEND_RAMP_WIDTH / legend.entries.len().max(1) as f64;
+
+            for (i, entry) in legend.entries.iter().enumerate() {
+                let x = ramp_x + i as f64 * box_width;
+
+                elements.push(Element::Rectangle {
+                    x,
+                    y,
+                    width: box_width,
+                    height: 4.,
+                    color: entry.color,
+                });
+                elements.push(Element::Text {
+                    x,
+                    y: y + 7.5,
+                    size: 6.,
+                    text: entry.value.to_string(),
+                });
+            }
+        }
+        _ => {
+            let color_mapper = colorizer.create_color_mapper();
+            let (min, max) = (colorizer.min_value(), colorizer.max_value());
+            let step_width = LEGEND_RAMP_WIDTH / LEGEND_RAMP_STEPS as f64;
+
+            for step in 0..LEGEND_RAMP_STEPS {
+                let fraction = (step as f64 + 0.5) / LEGEND_RAMP_STEPS as f64;
+
+                elements.push(Element::Rectangle {
+                    x: ramp_x + step as f64 * step_width,
+                    y,
+                    width: step_width,
+                    height: 4.,
+                    color: color_mapper.call(min + fraction * (max - min)),
+                });
+            }
+
+            elements.push(Element::Text {
+                x: ramp_x,
+                y: y + 7.5,
+                size: 6.,
+                text: min.to_string(),
+            });
+            elements.push(Element::Text {
+                x: ramp_x + LEGEND_RAMP_WIDTH - 6.,
+                y: y + 7.5,
+                size: 6.,
+                text: max.to_string(),
+            });
+        }
+    }
+
+    y + 10.
+}
+
+/// The largest distance of the form `1`, `2` or `5` times a power of ten that does not exceed `distance`
+fn nice_distance(distance: f64) -> f64 {
+    let magnitude = 10_f64.powf(distance.log10().floor());
+
+    [5., 2., 1.]
+        .iter()
+        .map(|factor| factor * magnitude)
+        .find(|&nice| nice <= distance)
+        .unwrap_or(magnitude)
+}
+
+fn format_distance(meters: f64) -> String {
+    if meters >= 1000. {
+        format!("{} km", meters / 1000.)
+    } else {
+        format!("{} m", meters)
+    }
+}
+
+/// Breaks a `text` into lines that fit into `width` millimeters, estimating the average character width
+fn wrap_text(text: &str, width: f64, font_size: f64) -> Vec<String> {
+    let character_width = font_size * 0.5 * MILLIMETERS_PER_INCH / POINTS_PER_INCH;
+    let max_characters = ((width / character_width) as usize).max(1);
+
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > max_characters {
+            lines.push(std::mem::take(&mut line));
+        }
+
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+fn millimeters_to_pixels(millimeters: f64, dpi: u32) -> u32 {
+    millimeters_to_fractional_pixels(millimeters, dpi).round() as u32
+}
+
+fn millimeters_to_fractional_pixels(millimeters: f64, dpi: u32) -> f64 {
+    millimeters / MILLIMETERS_PER_INCH * f64::from(dpi)
+}
+
+/// Grows the `bbox` around its center to the aspect ratio of the map
+pub fn fit_to_aspect_ratio(bbox: BoundingBox2D, map_width: f64, map_height: f64) -> BoundingBox2D {
+    let center = Coordinate2D::new(
+        (bbox.lower_left().x + bbox.upper_right().x) / 2.,
+        (bbox.lower_left().y + bbox.upper_right().y) / 2.,
+    );
+
+    let aspect_ratio = map_width / map_height;
+    let (mut size_x, mut size_y) = (bbox.size_x(), bbox.size_y());
+
+    if size_x / size_y < aspect_ratio {
+        size_x = size_y * aspect_ratio;
+    } else {
+        size_y = size_x / aspect_ratio;
+    }
+
+    BoundingBox2D::new_unchecked(
+        Coordinate2D::new(center.x - size_x / 2., center.y - size_y / 2.),
+        Coordinate2D::new(center.x + size_x / 2., center.y + size_y / 2.),
+    )
+}
+
+/// The ground distance in meters that one millimeter of a map with the `bbox` and `map_width` covers.
+///
+/// Geographic coordinates are measured along the center latitude of the `bbox`.
+pub fn meters_per_millimeter(
+    spatial_reference: SpatialReference,
+    bbox: BoundingBox2D,
+    map_width: f64,
+) -> Option<f64> {
+    const METERS_PER_DEGREE: f64 = 111_319.490_793;
+
+    let ground_width = if spatial_reference == SpatialReference::epsg_4326() {
+        let center_latitude = (bbox.lower_left().y + bbox.upper_right().y) / 2.;
+        bbox.size_x() * METERS_PER_DEGREE * center_latitude.to_radians().cos()
+    } else {
+        // TODO: determine the units of other spatial references
+        bbox.size_x()
+    };
+
+    if ground_width > 0. && ground_width.is_finite() {
+        Some(ground_width / map_width)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryInto;
+
+    fn report() -> Report {
+        Report {
+            title: "Marburg".to_string(),
+            map: RgbaImage::from_pixel(20, 10, Rgba([255, 0, 0, 255])),
+            map_size: [100., 50.],
+            meters_per_millimeter: Some(100.),
+            legend: vec![(
+                "NDVI".to_string(),
+                Colorizer::linear_gradient(
+                    vec![
+                        (0.0, RgbaColor::black()).try_into().unwrap(),
+                        (1.0, RgbaColor::white()).try_into().unwrap(),
+                    ],
+                    RgbaColor::transparent(),
+                    RgbaColor::transparent(),
+                )
+                .unwrap(),
+            )],
+            attribution: vec!["Sentinel-2 (CC BY-SA 3.0 IGO)".to_string()],
+        }
+    }
+
+    #[test]
+    fn it_validates_requests() {
+        let request: ReportRequest = serde_json::from_str("{}").unwrap();
+        assert_eq!(request.format, ReportFormat::Pdf);
+        assert_eq!(request.map_pixels(), (1063, 709));
+        assert!(request.validate().is_ok());
+
+        let request: ReportRequest =
+            serde_json::from_str(r#"{"mapWidth": 1000, "dpi": 600}"#).unwrap();
+        assert!(request.validate().is_err());
+
+        let request: ReportRequest = serde_json::from_str(r#"{"dpi": 10}"#).unwrap();
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn it_lays_out_reports() {
+        let page = report().layout();
+
+        assert!((page.width - 130.).abs() < f64::EPSILON);
+        assert!(page
+            .elements
+            .iter()
+            .any(|element| matches!(element, Element::Text { text, .. } if text == "Marburg")));
+        assert!(page
+            .elements
+            .iter()
+            .any(|element| matches!(element, Element::Text { text, .. } if text == "1:100000")));
+        assert!(page
+            .elements
+            .iter()
+            .any(|element| matches!(element, Element::Text { text, .. } if text == "2 km")));
+    }
+
+    #[test]
+    fn it_renders_pngs() {
+        let png = report().to_png(254).unwrap();
+        let image = image::load_from_memory_with_format(&png, ImageFormat::Png)
+            .unwrap()
+            .to_rgba8();
+
+        // ten pixels per millimeter
+        assert_eq!(image.width(), 1300);
+        assert_eq!(image.get_pixel(0, 0), &Rgba([255, 255, 255, 255]));
+        assert_eq!(image.get_pixel(100, 220), &Rgba([255, 0, 0, 255]));
+
+        // the stem of the `M` of the title, whose baseline is at 16 mm
+        assert_eq!(image.get_pixel(101, 150), &Rgba([0, 0, 0, 255]));
+        assert_eq!(image.get_pixel(101, 170), &Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn it_renders_pdfs() {
+        let pdf = report().to_pdf();
+
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        assert!(pdf.ends_with(b"%%EOF\n"));
+    }
+
+    #[test]
+    fn it_chooses_nice_distances() {
+        assert!((nice_distance(2500.) - 2000.).abs() < f64::EPSILON);
+        assert!((nice_distance(999.) - 500.).abs() < f64::EPSILON);
+        assert!((nice_distance(1.5) - 1.).abs() < f64::EPSILON);
+        assert_eq!(format_distance(2000.), "2 km");
+        assert_eq!(format_distance(500.), "500 m");
+    }
+
+    #[test]
+    fn it_fits_aspect_ratios() {
+        let bbox = fit_to_aspect_ratio(
+            BoundingBox2D::new_unchecked((0., 0.).into(), (10., 10.).into()),
+            200.,
+            100.,
+        );
+
+        assert_eq!(
+            bbox,
+            BoundingBox2D::new_unchecked((-5., 0.).into(), (15., 10.).into())
+        );
+    }
+
+    #[test]
+    fn it_wraps_text() {
+        assert_eq!(
+            wrap_text("aaa bbb ccc", 12.5, 10.),
+            vec!["aaa bbb".to_string(), "ccc".to_string()]
+        );
+    }
+}
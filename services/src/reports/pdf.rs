@@ -0,0 +1,248 @@
+use super::{Element, Page, MILLIMETERS_PER_INCH};
+
+const POINTS_PER_MILLIMETER: f64 = 72. / MILLIMETERS_PER_INCH;
+
+/// Writes a single `page` as PDF document.
+///
+/// Texts use the standard Helvetica font, so characters outside of Latin-1 are replaced.
+/// Images are embedded uncompressed on a white background.
+pub(super) fn write_pdf(page: &Page) -> Vec<u8> {
+    let mut content = Vec::new();
+    let mut images = Vec::new();
+
+    for element in &page.elements {
+        match element {
+            Element::Image {
+                x,
+                y,
+                width,
+                height,
+                image,
+            } => {
+                content.extend_from_slice(
+                    format!(
+                        "q {:.3} 0 0 {:.3} {:.3} {:.3} cm /Im{} Do Q\n",
+                        width * POINTS_PER_MILLIMETER,
+                        height * POINTS_PER_MILLIMETER,
+                        x * POINTS_PER_MILLIMETER,
+                        (page.height - y - height) * POINTS_PER_MILLIMETER,
+                        images.len()
+                    )
+                    .as_bytes(),
+                );
+                images.push(image);
+            }
+            Element::Rectangle {
+                x,
+                y,
+                width,
+                height,
+                color,
+            } => {
+                let [red, green, blue] = on_white((*color).into());
+                content.extend_from_slice(
+                    format!(
+                        "{:.3} {:.3} {:.3} rg {:.3} {:.3} {:.3} {:.3} re f\n",
+                        f64::from(red) / 255.,
+                        f64::from(green) / 255.,
+                        f64::from(blue) / 255.,
+                        x * POINTS_PER_MILLIMETER,
+                        (page.height - y - height) * POINTS_PER_MILLIMETER,
+                        width * POINTS_PER_MILLIMETER,
+                        height * POINTS_PER_MILLIMETER,
+                    )
+                    .as_bytes(),
+                );
+            }
+            Element::Text { x, y, size, text } => {
+                content.extend_from_slice(
+                    format!(
+                        "0 0 0 rg BT /F1 {:.1} Tf {:.3} {:.3} Td (",
+                        size,
+                        x * POINTS_PER_MILLIMETER,
+                        (page.height - y) * POINTS_PER_MILLIMETER,
+                    )
+                    .as_bytes(),
+                );
+                content.extend(encode_text(text));
+                content.extend_from_slice(b") Tj ET\n");
+            }
+        }
+    }
+
+    let mut writer = PdfWriter::default();
+
+    // the catalog, the page tree, the page, the content stream and the font precede the images
+    let first_image = 6;
+    let image_resources: String = (0..images.len())
+        .map(|i| format!("/Im{} {} 0 R", i, first_image + i))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    writer.add_object(b"<< /Type /Catalog /Pages 2 0 R >>");
+    writer.add_object(b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>");
+    writer.add_object(
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.3} {:.3}] /Contents 4 0 R /Resources << /Font << /F1 5 0 R >> /XObject << {} >> >> >>",
+            page.width * POINTS_PER_MILLIMETER,
+            page.height * POINTS_PER_MILLIMETER,
+            image_resources
+        )
+        .as_bytes(),
+    );
+    writer.add_stream("", &content);
+    writer.add_object(
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>",
+    );
+
+    for image in images {
+        let pixels: Vec<u8> = image
+            .pixels()
+            .flat_map(|pixel| std::array::IntoIter::new(on_white(*pixel)))
+            .collect();
+
+        writer.add_stream(
+            &format!(
+                "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB /BitsPerComponent 8",
+                image.width(),
+                image.height()
+            ),
+            &pixels,
+        );
+    }
+
+    writer.finish()
+}
+
+/// Collects numbered objects and their byte offsets for the cross-reference table
+struct PdfWriter {
+    buffer: Vec<u8>,
+    offsets: Vec<usize>,
+}
+
+impl Default for PdfWriter {
+    fn default() -> Self {
+        Self {
+            buffer: b"%PDF-1.4\n".to_vec(),
+            offsets: Vec::new(),
+        }
+    }
+}
+
+impl PdfWriter {
+    fn add_object(&mut self, object: &[u8]) {
+        self.offsets.push(self.buffer.len());
+
+        self.buffer
+            .extend_from_slice(format!("{} 0 obj\n", self.offsets.len()).as_bytes());
+        self.buffer.extend_from_slice(object);
+        self.buffer.extend_from_slice(b"\nendobj\n");
+    }
+
+    fn add_stream(&mut self, dictionary: &str, data: &[u8]) {
+        let mut object =
+            format!("<< {} /Length {} >>\nstream\n", dictionary, data.len()).into_bytes();
+        object.extend_from_slice(data);
+        object.extend_from_slice(b"\nendstream");
+
+        self.add_object(&object);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let cross_reference_offset = self.buffer.len();
+        let objects = self.offsets.len() + 1;
+
+        let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", objects);
+        for offset in &self.offsets {
+            trailer.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+        trailer.push_str(&format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects, cross_reference_offset
+        ));
+
+        self.buffer.extend_from_slice(trailer.as_bytes());
+        self.buffer
+    }
+}
+
+/// Composites a color onto a white background since there is no transparency in the content stream
+fn on_white(color: image::Rgba<u8>) -> [u8; 3] {
+    let alpha = f64::from(color[3]) / 255.;
+    let channel = |value: u8| (f64::from(value) * alpha + 255. * (1. - alpha)).round() as u8;
+
+    [channel(color[0]), channel(color[1]), channel(color[2])]
+}
+
+/// Encodes a text as escaped PDF string in `WinAnsiEncoding`, which matches Latin-1 for printable characters
+fn encode_text(text: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '\\' | '(' | ')' => bytes.extend_from_slice(&[b'\\', c as u8]),
+            ' '..='~' | '\u{a0}'..='\u{ff}' => bytes.push(c as u8),
+            _ => bytes.push(b'?'),
+        }
+    }
+
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::operations::image::RgbaColor;
+    use image::{Rgba, RgbaImage};
+
+    #[test]
+    fn it_writes_cross_references() {
+        let page = Page {
+            width: 100.,
+            height: 50.,
+            elements: vec![
+                Element::Image {
+                    x: 10.,
+                    y: 10.,
+                    width: 20.,
+                    height: 10.,
+                    image: RgbaImage::from_pixel(2, 1, Rgba([255, 0, 0, 255])),
+                },
+                Element::Rectangle {
+                    x: 10.,
+                    y: 30.,
+                    width: 5.,
+                    height: 5.,
+                    color: RgbaColor::black(),
+                },
+                Element::Text {
+                    x: 10.,
+                    y: 45.,
+                    size: 10.,
+                    text: "Title (draft)".to_string(),
+                },
+            ],
+        };
+
+        let pdf = write_pdf(&page);
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(text.contains("/MediaBox [0 0 283.465 141.732]"));
+        assert!(text.contains("/XObject << /Im0 6 0 R >>"));
+        assert!(text.contains("(Title \\(draft\\)) Tj"));
+        assert!(text.contains("/Width 2 /Height 1"));
+
+        // every cross reference points at its object
+        let cross_references = text.split("xref\n").nth(1).unwrap();
+        for (i, line) in cross_references.lines().skip(2).take(6).enumerate() {
+            let offset: usize = line[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(format!("{} 0 obj", i + 1).as_bytes()));
+        }
+    }
+
+    #[test]
+    fn it_encodes_text() {
+        assert_eq!(encode_text("a\\b"), b"a\\\\b");
+        assert_eq!(encode_text("Müller → Süd"), b"M\xfcller ? S\xfcd");
+    }
+}
@@ -1,4 +1,5 @@
 use crate::contexts::{InMemoryContext, SimpleContext};
+use crate::datasets::scratch::spawn_scratch_dataset_cleanup;
 use crate::error;
 use crate::error::{Error, Result};
 use crate::handlers;
@@ -72,13 +73,30 @@ async fn start<C>(
 where
     C: SimpleContext,
 {
+    spawn_scratch_dataset_cleanup(ctx.clone())?;
+
     let handler = combine!(
         handlers::workflows::register_workflow_handler(ctx.clone()),
         handlers::workflows::load_workflow_handler(ctx.clone()),
+        handlers::workflows::diff_workflows_handler(ctx.clone()),
+        handlers::workflows::migrate_workflow_handler(ctx.clone()),
+        handlers::workflows::validate_workflow_handler(ctx.clone()),
         handlers::workflows::get_workflow_metadata_handler(ctx.clone()),
         handlers::workflows::get_workflow_provenance_handler(ctx.clone()),
+        handlers::workflows::get_workflow_citation_handler(ctx.clone()),
+        handlers::workflows::get_workflow_sources_handler(ctx.clone()),
+        handlers::workflows::get_workflow_pixel_values_handler(ctx.clone()),
+        handlers::workflows::batch_query_workflow_handler(ctx.clone()),
+        handlers::workflows::get_workflow_tags_handler(ctx.clone()),
+        handlers::workflows::add_workflow_tags_handler(ctx.clone()),
+        handlers::workflows::remove_workflow_tags_handler(ctx.clone()),
+        handlers::tile_archive::archive_workflow_handler(ctx.clone()),
+        handlers::tile_archive::get_workflow_archive_handler(ctx.clone()),
+        handlers::tile_archive::refresh_workflow_archive_handler(ctx.clone()),
+        handlers::tile_archive::remove_workflow_archive_handler(ctx.clone()),
         handlers::session::anonymous_handler(ctx.clone()),
         handlers::session::session_handler(ctx.clone()),
+        handlers::session::session_cache_invalidation_handler(ctx.clone()),
         handlers::session::session_project_handler(ctx.clone()),
         handlers::session::session_view_handler(ctx.clone()),
         handlers::projects::create_project_handler(ctx.clone()),
@@ -86,19 +104,43 @@ where
         handlers::projects::update_project_handler(ctx.clone()),
         handlers::projects::delete_project_handler(ctx.clone()),
         handlers::projects::load_project_handler(ctx.clone()),
+        handlers::projects::project_playback_frames_handler(ctx.clone()),
         handlers::datasets::get_dataset_handler(ctx.clone()),
+        handlers::datasets::get_dataset_metadata_handler(ctx.clone()),
+        handlers::datasets::get_external_dataset_handler(ctx.clone()),
+        handlers::datasets::dataset_gap_report_handler(ctx.clone()),
+        handlers::datasets::add_dataset_alias_handler(ctx.clone()),
+        handlers::datasets::list_dataset_aliases_handler(ctx.clone()),
+        handlers::datasets::add_dataset_tags_handler(ctx.clone()),
+        handlers::datasets::remove_dataset_tags_handler(ctx.clone()),
+        handlers::datasets::create_scratch_dataset_handler(ctx.clone()),
+        handlers::datasets::list_scratch_datasets_handler(ctx.clone()),
         handlers::datasets::auto_create_dataset_handler(ctx.clone()),
         handlers::datasets::create_dataset_handler(ctx.clone()),
         handlers::datasets::suggest_meta_data_handler(ctx.clone()),
         handlers::datasets::list_providers_handler(ctx.clone()),
         handlers::datasets::list_external_datasets_handler(ctx.clone()),
+        handlers::datasets::search_datasets_handler(ctx.clone()),
         handlers::datasets::list_datasets_handler(ctx.clone()), // must come after `list_external_datasets_handler`
+        handlers::layers::list_root_collections_handler(ctx.clone()),
+        handlers::layers::get_collection_handler(ctx.clone()),
+        handlers::layers::get_layer_handler(ctx.clone()),
+        handlers::aois::add_aoi_handler(ctx.clone()),
+        handlers::aois::list_aois_handler(ctx.clone()),
+        handlers::aois::load_aoi_handler(ctx.clone()),
+        handlers::aois::delete_aoi_handler(ctx.clone()),
+        handlers::csw::csw_handler(ctx.clone()),
         handlers::wcs::wcs_handler(ctx.clone()),
         handlers::wms::wms_handler(ctx.clone()),
+        handlers::basemap::basemap_tile_handler(),
         handlers::wfs::wfs_handler(ctx.clone()),
         handlers::plots::get_plot_handler(ctx.clone()),
+        handlers::aggregates::get_aggregate_handler(ctx.clone()),
+        handlers::reports::create_report_handler(ctx.clone()),
+        handlers::tables::get_table_handler(ctx.clone()),
         handlers::upload::upload_handler(ctx.clone()),
         handlers::spatial_references::get_spatial_reference_specification_handler(ctx.clone()),
+        handlers::operators::list_operators_handler(ctx.clone()),
         show_version_handler(), // TODO: allow disabling this function via config or feature flag
         serve_static_directory(static_files_dir)
     )
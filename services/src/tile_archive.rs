@@ -0,0 +1,910 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::future::try_join_all;
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use geoengine_datatypes::primitives::SpatialPartition2D;
+use geoengine_datatypes::raster::{Pixel, RasterTile2D, TileInformation, TilingSpecification};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use geoengine_operators::engine::{
+    CachePolicyHint, QueryContext, QueryProcessor, RasterQueryProcessor, RasterQueryRectangle,
+    TypedRasterQueryProcessor,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt};
+use tokio::fs;
+
+use crate::error::{self, Result};
+use crate::util::aws_signature::AwsCredentials;
+use crate::util::config::{self, get_config_element};
+use crate::util::http_client::shared_http_client;
+use crate::workflows::workflow::WorkflowId;
+
+/// A key-value store for the archived tiles, e.g., a directory or an object storage
+#[async_trait]
+pub trait TileArchiveStore: Debug + Send + Sync {
+    /// Loads the value of `key` or returns `None` if it does not exist
+    async fn load(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    async fn store(&self, key: &str, value: Vec<u8>) -> Result<()>;
+
+    /// Removes the value of `key` and, if the store supports it, all values below `key/`
+    async fn remove(&self, key: &str) -> Result<()>;
+
+    /// The total size of all values below `key/` in bytes
+    async fn size(&self, key: &str) -> Result<u64>;
+}
+
+/// Stores each value in a file below a `directory`
+#[derive(Debug, Clone)]
+pub struct FileSystemTileArchiveStore {
+    directory: PathBuf,
+}
+
+impl FileSystemTileArchiveStore {
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+}
+
+#[async_trait]
+impl TileArchiveStore for FileSystemTileArchiveStore {
+    async fn load(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match fs::read(self.directory.join(key)).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error::Error::Io { source: error }),
+        }
+    }
+
+    async fn store(&self, key: &str, value: Vec<u8>) -> Result<()> {
+        let path = self.directory.join(key);
+
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory).await.context(error::Io)?;
+        }
+
+        fs::write(path, value).await.context(error::Io)
+    }
+
+    async fn remove(&self, key: &str) -> Result<()> {
+        let path = self.directory.join(key);
+
+        match fs::metadata(&path).await {
+            Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(path).await.context(error::Io),
+            Ok(_) => fs::remove_file(path).await.context(error::Io),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(error) => Err(error::Error::Io { source: error }),
+        }
+    }
+
+    async fn size(&self, key: &str) -> Result<u64> {
+        let mut size = 0;
+        let mut directories = vec![self.directory.join(key)];
+
+        while let Some(directory) = directories.pop() {
+            let mut entries = match fs::read_dir(&directory).await {
+                Ok(entries) => entries,
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(error) => return Err(error::Error::Io { source: error }),
+            };
+
+            while let Some(entry) = entries.next_entry().await.context(error::Io)? {
+                let metadata = entry.metadata().await.context(error::Io)?;
+
+                if metadata.is_dir() {
+                    directories.push(entry.path());
+                } else {
+                    size += metadata.len();
+                }
+            }
+        }
+
+        Ok(size)
+    }
+}
+
+/// Stores each value as an object below the `url` of an object storage, e.g., an S3 bucket.
+///
+/// The requests are signed if there are `credentials`. Otherwise, the object storage must
+/// allow anonymous requests below the `url`.
+///
+/// Object storages cannot remove all objects below a key, so tiles of outdated generations
+/// are only unreachable. They should be removed by a lifecycle rule of the bucket.
+///
+/// Sizes are determined by listing the objects of the bucket like S3 does. The bucket is the host
+/// of the `url`, e.g., `https://bucket.s3.eu-central-1.amazonaws.com/prefix`, or the first segment
+/// of its path for path-style URLs, e.g., `http://localhost:9000/bucket/prefix`.
+#[derive(Debug, Clone)]
+pub struct ObjectStorageTileArchiveStore {
+    url: String,
+    client: reqwest::Client,
+    credentials: Option<AwsCredentials>,
+    path_style: bool,
+}
+
+impl ObjectStorageTileArchiveStore {
+    pub fn new(url: String, client: reqwest::Client) -> Self {
+        Self {
+            url,
+            client,
+            credentials: None,
+            path_style: false,
+        }
+    }
+
+    /// The bucket is the first segment of the path of the `url` instead of its host
+    pub fn with_path_style(mut self) -> Self {
+        self.path_style = true;
+        self
+    }
+
+    /// Signs the requests with AWS Signature Version 4
+    pub fn with_credentials(mut self, credentials: AwsCredentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    fn object_url(&self, key: &str) -> String {
+        format!("{}/{}", self.url.trim_end_matches('/'), key)
+    }
+
+    /// The URL of the bucket and the prefix of the keys of the objects below the `url`
+    fn bucket_url_and_prefix(&self) -> Result<(reqwest::Url, String)> {
+        let mut bucket_url = parse_url(&self.url)?;
+        let path = bucket_url.path().trim_matches('/').to_string();
+
+        let prefix = if self.path_style {
+            let (bucket, prefix) = path.split_once('/').unwrap_or((path.as_str(), ""));
+            bucket_url.set_path(bucket);
+            prefix
+        } else {
+            bucket_url.set_path("/");
+            path.as_str()
+        };
+        bucket_url.set_query(None);
+
+        if prefix.is_empty() {
+            Ok((bucket_url, String::new()))
+        } else {
+            Ok((bucket_url, format!("{}/", prefix)))
+        }
+    }
+
+    /// Creates a request for the object `key`, which is signed if there are credentials
+    fn request(
+        &self,
+        method: reqwest::Method,
+        key: &str,
+        body: Vec<u8>,
+    ) -> Result<reqwest::RequestBuilder> {
+        let url = parse_url(&self.object_url(key))?;
+
+        Ok(self.signed_request(method, url, body))
+    }
+
+    fn signed_request(
+        &self,
+        method: reqwest::Method,
+        url: reqwest::Url,
+        body: Vec<u8>,
+    ) -> reqwest::RequestBuilder {
+        let mut request = self.client.request(method.clone(), url.clone());
+
+        if let Some(credentials) = &self.credentials {
+            for (name, value) in credentials.sign(&method, &url, &body, Utc::now()) {
+                request = request.header(name, value);
+            }
+        }
+
+        request.body(body)
+    }
+}
+
+fn parse_url(url: &str) -> Result<reqwest::Url> {
+    reqwest::Url::parse(url).map_err(|error| error::Error::TileArchive {
+        details: format!("invalid object storage URL: {}", error),
+    })
+}
+
+/// The texts of all elements with the `name` in the `xml`, e.g., the `Size`s of listed objects
+fn xml_element_texts<'x>(xml: &'x str, name: &str) -> Vec<&'x str> {
+    let start_tag = format!("<{}>", name);
+    let end_tag = format!("</{}>", name);
+
+    let mut texts = Vec::new();
+    let mut rest = xml;
+
+    while let Some(start) = rest.find(&start_tag) {
+        let text = &rest[start + start_tag.len()..];
+        let end = match text.find(&end_tag) {
+            Some(end) => end,
+            None => break,
+        };
+
+        texts.push(&text[..end]);
+        rest = &text[end + end_tag.len()..];
+    }
+
+    texts
+}
+
+#[async_trait]
+impl TileArchiveStore for ObjectStorageTileArchiveStore {
+    async fn load(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let response = self
+            .request(reqwest::Method::GET, key, Vec::new())?
+            .send()
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        ensure!(
+            response.status().is_success(),
+            error::TileArchive {
+                details: format!("the object storage responded with {}", response.status()),
+            }
+        );
+
+        Ok(Some(response.bytes().await?.to_vec()))
+    }
+
+    async fn store(&self, key: &str, value: Vec<u8>) -> Result<()> {
+        let response = self
+            .request(reqwest::Method::PUT, key, value)?
+            .send()
+            .await?;
+
+        ensure!(
+            response.status().is_success(),
+            error::TileArchive {
+                details: format!("the object storage responded with {}", response.status()),
+            }
+        );
+
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> Result<()> {
+        let response = self
+            .request(reqwest::Method::DELETE, key, Vec::new())?
+            .send()
+            .await?;
+
+        ensure!(
+            response.status().is_success() || response.status() == reqwest::StatusCode::NOT_FOUND,
+            error::TileArchive {
+                details: format!("the object storage responded with {}", response.status()),
+            }
+        );
+
+        Ok(())
+    }
+
+    async fn size(&self, key: &str) -> Result<u64> {
+        let (bucket_url, prefix) = self.bucket_url_and_prefix()?;
+        let prefix = format!("{}{}/", prefix, key);
+
+        let mut size = 0;
+        let mut continuation_token: Option<String> = None;
+
+        // listings are paginated
+        loop {
+            let mut url = bucket_url.clone();
+            url.query_pairs_mut()
+                .append_pair("list-type", "2")
+                .append_pair("prefix", &prefix);
+            if let Some(continuation_token) = &continuation_token {
+                url.query_pairs_mut()
+                    .append_pair("continuation-token", continuation_token);
+            }
+
+            let response = self
+                .signed_request(reqwest::Method::GET, url, Vec::new())
+                .send()
+                .await?;
+
+            ensure!(
+                response.status().is_success(),
+                error::TileArchive {
+                    details: format!("the object storage responded with {}", response.status()),
+                }
+            );
+
+            let listing = response.text().await?;
+
+            for object_size in xml_element_texts(&listing, "Size") {
+                size += object_size
+                    .parse::<u64>()
+                    .map_err(|_| error::Error::TileArchive {
+                        details: format!("invalid object size {}", object_size),
+                    })?;
+            }
+
+            continuation_token = xml_element_texts(&listing, "NextContinuationToken")
+                .first()
+                .map(ToString::to_string);
+
+            if continuation_token.is_none() {
+                return Ok(size);
+            }
+        }
+    }
+}
+
+/// The archive state of a workflow
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedWorkflow {
+    /// Tiles are only served from the current generation, i.e., refreshing starts a new one
+    pub generation: u32,
+    pub refreshed: DateTime<Utc>,
+}
+
+/// Persists the computed raster tiles of archived workflows, s.t. recurring queries are served from the archive.
+///
+/// Tiles are keyed by the spatial reference, resolution, tiling, time interval and position of the query.
+/// They are kept until the workflow is refreshed or removed from the archive.
+#[derive(Debug, Clone)]
+pub struct TileArchive {
+    store: Arc<dyn TileArchiveStore>,
+}
+
+impl TileArchive {
+    pub fn new(store: Arc<dyn TileArchiveStore>) -> Self {
+        Self { store }
+    }
+
+    /// Creates the archive of the settings, i.e., in the object storage if one is configured
+    pub fn from_config() -> Result<Self> {
+        let config = get_config_element::<config::TileArchive>()?;
+
+        let store: Arc<dyn TileArchiveStore> = match config.object_storage_url {
+            Some(url) => {
+                let mut store = ObjectStorageTileArchiveStore::new(url, shared_http_client()?);
+                if let Some(credentials) = config.object_storage_credentials {
+                    store = store.with_credentials(credentials);
+                }
+                if config.object_storage_path_style {
+                    store = store.with_path_style();
+                }
+                Arc::new(store)
+            }
+            None => Arc::new(FileSystemTileArchiveStore::new(config.directory)),
+        };
+
+        Ok(Self::new(store))
+    }
+
+    /// Returns the archive state of the `workflow` or `None` if it is not archived
+    pub async fn workflow(&self, workflow: WorkflowId) -> Result<Option<ArchivedWorkflow>> {
+        match self.store.load(&manifest_key(workflow)).await? {
+            Some(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Starts archiving the tiles of the `workflow`. Archiving an archived workflow does nothing.
+    pub async fn archive(&self, workflow: WorkflowId) -> Result<ArchivedWorkflow> {
+        if let Some(archived) = self.workflow(workflow).await? {
+            return Ok(archived);
+        }
+
+        let archived = ArchivedWorkflow {
+            generation: 0,
+            refreshed: Utc::now(),
+        };
+
+        self.store_manifest(workflow, &archived).await?;
+
+        Ok(archived)
+    }
+
+    /// Discards the archived tiles of the `workflow`, s.t. they are computed again, e.g., after its data changed
+    pub async fn refresh(&self, workflow: WorkflowId) -> Result<ArchivedWorkflow> {
+        let outdated = self
+            .workflow(workflow)
+            .await?
+            .ok_or(error::Error::WorkflowNotArchived { workflow })?;
+
+        let archived = ArchivedWorkflow {
+            generation: outdated.generation + 1,
+            refreshed: Utc::now(),
+        };
+
+        self.store_manifest(workflow, &archived).await?;
+
+        self.store
+            .remove(&generation_key(workflow, outdated.generation))
+            .await?;
+
+        Ok(archived)
+    }
+
+    /// The size of the manifest and the tiles of the `workflow` in bytes
+    pub async fn stored_bytes(&self, workflow: WorkflowId) -> Result<u64> {
+        self.store.size(&workflow.to_string()).await
+    }
+
+    /// Stops archiving the `workflow` and removes its tiles
+    pub async fn remove(&self, workflow: WorkflowId) -> Result<()> {
+        ensure!(
+            self.workflow(workflow).await?.is_some(),
+            error::WorkflowNotArchived { workflow }
+        );
+
+        self.store.remove(&manifest_key(workflow)).await?;
+        self.store.remove(&workflow.to_string()).await
+    }
+
+    /// Wraps the `processor` of the `workflow` s.t. it serves its tiles from the archive if the workflow is archived
+    pub async fn processor(
+        &self,
+        workflow: WorkflowId,
+        spatial_reference: SpatialReference,
+        tiling_specification: TilingSpecification,
+        processor: TypedRasterQueryProcessor,
+    ) -> Result<TypedRasterQueryProcessor> {
+        let archived = match self.workflow(workflow).await? {
+            Some(archived) => archived,
+            None => return Ok(processor),
+        };
+
+        let prefix = format!(
+            "{}/{}",
+            generation_key(workflow, archived.generation),
+            spatial_reference
+        );
+
+        Ok(match processor {
+            TypedRasterQueryProcessor::U8(p) => {
+                TypedRasterQueryProcessor::U8(self.archived(p, prefix, tiling_specification))
+            }
+            TypedRasterQueryProcessor::U16(p) => {
+                TypedRasterQueryProcessor::U16(self.archived(p, prefix, tiling_specification))
+            }
+            TypedRasterQueryProcessor::U32(p) => {
+                TypedRasterQueryProcessor::U32(self.archived(p, prefix, tiling_specification))
+            }
+            TypedRasterQueryProcessor::U64(p) => {
+                TypedRasterQueryProcessor::U64(self.archived(p, prefix, tiling_specification))
+            }
+            TypedRasterQueryProcessor::I8(p) => {
+                TypedRasterQueryProcessor::I8(self.archived(p, prefix, tiling_specification))
+            }
+            TypedRasterQueryProcessor::I16(p) => {
+                TypedRasterQueryProcessor::I16(self.archived(p, prefix, tiling_specification))
+            }
+            TypedRasterQueryProcessor::I32(p) => {
+                TypedRasterQueryProcessor::I32(self.archived(p, prefix, tiling_specification))
+            }
+            TypedRasterQueryProcessor::I64(p) => {
+                TypedRasterQueryProcessor::I64(self.archived(p, prefix, tiling_specification))
+            }
+            TypedRasterQueryProcessor::F32(p) => {
+                TypedRasterQueryProcessor::F32(self.archived(p, prefix, tiling_specification))
+            }
+            TypedRasterQueryProcessor::F64(p) => {
+                TypedRasterQueryProcessor::F64(self.archived(p, prefix, tiling_specification))
+            }
+        })
+    }
+
+    fn archived<T>(
+        &self,
+        source: Box<dyn RasterQueryProcessor<RasterType = T>>,
+        prefix: String,
+        tiling_specification: TilingSpecification,
+    ) -> Box<dyn RasterQueryProcessor<RasterType = T>>
+    where
+        T: Pixel + Serialize + DeserializeOwned,
+    {
+        ArchivedRasterQueryProcessor {
+            source,
+            store: self.store.clone(),
+            prefix,
+            tiling_specification,
+        }
+        .boxed()
+    }
+
+    async fn store_manifest(
+        &self,
+        workflow: WorkflowId,
+        archived: &ArchivedWorkflow,
+    ) -> Result<()> {
+        self.store
+            .store(&manifest_key(workflow), serde_json::to_vec(archived)?)
+            .await
+    }
+}
+
+fn manifest_key(workflow: WorkflowId) -> String {
+    format!("{}/manifest.json", workflow)
+}
+
+fn generation_key(workflow: WorkflowId, generation: u32) -> String {
+    format!("{}/{}", workflow, generation)
+}
+
+/// Serves the tiles of a query from the archive if all of them are archived
+/// and otherwise queries the `source` and archives its tiles
+struct ArchivedRasterQueryProcessor<T> {
+    source: Box<dyn RasterQueryProcessor<RasterType = T>>,
+    store: Arc<dyn TileArchiveStore>,
+    prefix: String,
+    tiling_specification: TilingSpecification,
+}
+
+impl<T> ArchivedRasterQueryProcessor<T>
+where
+    T: Pixel + Serialize + DeserializeOwned,
+{
+    /// The key of all tiles at the position of `tile` for the `query`
+    fn tile_key(&self, query: &RasterQueryRectangle, tile: &TileInformation) -> String {
+        let upper_left = tile.tile_geo_transform().origin_coordinate;
+        let tile_size = tile.tile_size_in_pixels();
+
+        format!(
+            "{prefix}/{res_x}_{res_y}/{size_x}_{size_y}/{start}_{end}/{x}_{y}.json",
+            prefix = self.prefix,
+            res_x = query.spatial_resolution.x,
+            res_y = query.spatial_resolution.y,
+            size_x = tile_size.axis_size_x(),
+            size_y = tile_size.axis_size_y(),
+            start = query.time_interval.start().inner(),
+            end = query.time_interval.end().inner(),
+            x = upper_left.x,
+            y = upper_left.y,
+        )
+    }
+
+    /// Loads the tiles of all `keys` or returns `None` if any of them is missing.
+    /// The tiles are ordered like the ones of the source, i.e., geo first and time second.
+    async fn load_tiles(&self, keys: &[String]) -> Result<Option<Vec<RasterTile2D<T>>>> {
+        let values = try_join_all(keys.iter().map(|key| self.store.load(key))).await?;
+
+        let mut positions = Vec::with_capacity(values.len());
+        for value in values {
+            match value {
+                Some(bytes) => {
+                    positions.push(serde_json::from_slice::<Vec<RasterTile2D<T>>>(&bytes)?)
+                }
+                None => return Ok(None),
+            }
+        }
+
+        let time_steps = positions.iter().map(Vec::len).max().unwrap_or_default();
+        let mut positions: Vec<_> = positions.into_iter().map(Vec::into_iter).collect();
+
+        let mut tiles = Vec::new();
+        for _ in 0..time_steps {
+            tiles.extend(positions.iter_mut().filter_map(Iterator::next));
+        }
+
+        Ok(Some(tiles))
+    }
+
+    /// Stores the `tiles` grouped by their position
+    async fn store_tiles(
+        &self,
+        query: &RasterQueryRectangle,
+        keys: &[String],
+        tiles: &[RasterTile2D<T>],
+    ) -> Result<()> {
+        let mut positions: HashMap<String, Vec<&RasterTile2D<T>>> =
+            keys.iter().map(|key| (key.clone(), Vec::new())).collect();
+
+        for tile in tiles {
+            positions
+                .entry(self.tile_key(query, &tile.tile_information()))
+                .or_default()
+                .push(tile);
+        }
+
+        try_join_all(positions.into_iter().map(|(key, tiles)| async move {
+            self.store.store(&key, serde_json::to_vec(&tiles)?).await
+        }))
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<T> QueryProcessor for ArchivedRasterQueryProcessor<T>
+where
+    T: Pixel + Serialize + DeserializeOwned,
+{
+    type Output = RasterTile2D<T>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> geoengine_operators::util::Result<
+        BoxStream<'a, geoengine_operators::util::Result<Self::Output>>,
+    > {
+        if ctx.extensions().get::<CachePolicyHint>() == Some(&CachePolicyHint::Bypass) {
+            return self.source.raster_query(query, ctx).await;
+        }
+
+        let keys: Vec<String> = self
+            .tiling_specification
+            .strategy(query.spatial_resolution.x, -query.spatial_resolution.y)
+            .tile_information_iterator(query.spatial_bounds)
+            .map(|tile| self.tile_key(&query, &tile))
+            .collect();
+
+        let archived = self.load_tiles(&keys).await.map_err(|error| {
+            geoengine_operators::error::Error::TileArchive {
+                source: Box::new(error),
+            }
+        })?;
+
+        let tiles = if let Some(tiles) = archived {
+            tiles
+        } else {
+            let tiles: Vec<RasterTile2D<T>> = self
+                .source
+                .raster_query(query, ctx)
+                .await?
+                .try_collect()
+                .await?;
+
+            self.store_tiles(&query, &keys, &tiles)
+                .await
+                .map_err(|error| geoengine_operators::error::Error::TileArchive {
+                    source: Box::new(error),
+                })?;
+
+            tiles
+        };
+
+        Ok(stream::iter(tiles.into_iter().map(Ok)).boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::primitives::{Measurement, SpatialResolution, TimeInterval};
+    use geoengine_datatypes::raster::{GeoTransform, Grid2D, RasterDataType};
+    use geoengine_datatypes::util::Identifier;
+    use geoengine_operators::engine::{
+        MockExecutionContext, MockQueryContext, RasterOperator, RasterResultDescriptor,
+    };
+    use geoengine_operators::mock::{MockRasterSource, MockRasterSourceParams};
+    use httptest::matchers::{contains, key, not, request, url_decoded};
+    use httptest::responders::status_code;
+    use httptest::{all_of, Expectation, Server};
+
+    fn tiles(value: u8) -> Vec<RasterTile2D<u8>> {
+        let tile = |x: isize, time: TimeInterval| {
+            RasterTile2D::new_with_tile_info(
+                time,
+                TileInformation {
+                    global_tile_position: [-1, x].into(),
+                    tile_size_in_pixels: [2, 2].into(),
+                    global_geo_transform: GeoTransform::new((0., 0.).into(), 1., -1.),
+                },
+                Grid2D::new([2, 2].into(), vec![value; 4], None)
+                    .unwrap()
+                    .into(),
+            )
+        };
+
+        vec![
+            tile(0, TimeInterval::new_unchecked(0, 5)),
+            tile(1, TimeInterval::new_unchecked(0, 5)),
+            tile(0, TimeInterval::new_unchecked(5, 10)),
+            tile(1, TimeInterval::new_unchecked(5, 10)),
+        ]
+    }
+
+    async fn processor(
+        archive: &TileArchive,
+        workflow: WorkflowId,
+        value: u8,
+    ) -> TypedRasterQueryProcessor {
+        let execution_context = MockExecutionContext {
+            tiling_specification: TilingSpecification::new((0., 0.).into(), [2, 2].into()),
+            ..Default::default()
+        };
+
+        let source = MockRasterSource {
+            params: MockRasterSourceParams {
+                data: tiles(value),
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: None,
+                    bands: Vec::new(),
+                },
+            },
+        }
+        .boxed()
+        .initialize(&execution_context)
+        .await
+        .unwrap();
+
+        archive
+            .processor(
+                workflow,
+                SpatialReference::epsg_4326(),
+                execution_context.tiling_specification,
+                source.query_processor().unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    async fn query(
+        processor: TypedRasterQueryProcessor,
+        ctx: &MockQueryContext,
+    ) -> Vec<RasterTile2D<u8>> {
+        let query = RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new_unchecked((0., 2.).into(), (4., 0.).into()),
+            time_interval: TimeInterval::new_unchecked(0, 10),
+            spatial_resolution: SpatialResolution::one(),
+        };
+
+        processor
+            .get_u8()
+            .unwrap()
+            .raster_query(query, ctx)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn it_stores_values_on_disk() {
+        let directory = tempfile::tempdir().unwrap();
+        let store = FileSystemTileArchiveStore::new(directory.path().to_path_buf());
+
+        assert_eq!(store.load("a/b.json").await.unwrap(), None);
+
+        store.store("a/b.json", vec![1, 2, 3]).await.unwrap();
+        assert_eq!(store.load("a/b.json").await.unwrap(), Some(vec![1, 2, 3]));
+
+        store.store("a/c/d.json", vec![4, 5]).await.unwrap();
+        assert_eq!(store.size("a").await.unwrap(), 5);
+
+        store.remove("a").await.unwrap();
+        assert_eq!(store.load("a/b.json").await.unwrap(), None);
+
+        store.remove("a").await.unwrap();
+        assert_eq!(store.size("a").await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn it_lists_the_size_of_objects() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/bucket"),
+                request::query(url_decoded(contains(("prefix", "archive/a/")))),
+                request::query(url_decoded(not(contains(key("continuation-token"))))),
+            ])
+            .respond_with(status_code(200).body(
+                "<ListBucketResult><KeyCount>2</KeyCount>\
+                 <Contents><Key>archive/a/b.json</Key><Size>3</Size></Contents>\
+                 <Contents><Key>archive/a/c.json</Key><Size>4</Size></Contents>\
+                 <NextContinuationToken>next+page=</NextContinuationToken></ListBucketResult>",
+            )),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/bucket"),
+                request::query(url_decoded(contains(("continuation-token", "next+page=")))),
+            ])
+            .respond_with(status_code(200).body(
+                "<ListBucketResult><KeyCount>1</KeyCount>\
+                 <Contents><Key>archive/a/d.json</Key><Size>5</Size></Contents>\
+                 </ListBucketResult>",
+            )),
+        );
+
+        let store = ObjectStorageTileArchiveStore::new(
+            server.url_str("/bucket/archive"),
+            reqwest::Client::new(),
+        )
+        .with_path_style();
+
+        assert_eq!(store.size("a").await.unwrap(), 12);
+    }
+
+    #[tokio::test]
+    async fn it_signs_object_storage_requests() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("PUT", "/archive/a/b.json"),
+                request::headers(contains((
+                    "x-amz-content-sha256",
+                    "039058c6f2c0cb492c533b0a4d14ef77cc0f78abccced5287d84a1a2011cfb81"
+                ))),
+                request::headers(contains(key("authorization"))),
+            ])
+            .respond_with(status_code(200)),
+        );
+
+        let store =
+            ObjectStorageTileArchiveStore::new(server.url_str("/archive/"), reqwest::Client::new())
+                .with_credentials(AwsCredentials {
+                    region: "eu-central-1".to_string(),
+                    access_key_id: "access".to_string(),
+                    secret_access_key: "secret".to_string(),
+                });
+
+        store.store("a/b.json", vec![1, 2, 3]).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn it_serves_archived_tiles() {
+        let directory = tempfile::tempdir().unwrap();
+        let archive = TileArchive::new(Arc::new(FileSystemTileArchiveStore::new(
+            directory.path().to_path_buf(),
+        )));
+        let workflow = WorkflowId::new();
+        let ctx = MockQueryContext::default();
+
+        assert_eq!(archive.workflow(workflow).await.unwrap(), None);
+        assert_eq!(
+            query(processor(&archive, workflow, 1).await, &ctx).await,
+            tiles(1)
+        );
+
+        assert_eq!(archive.archive(workflow).await.unwrap().generation, 0);
+
+        // the first query archives the tiles and subsequent ones are served from the archive
+        assert_eq!(
+            query(processor(&archive, workflow, 1).await, &ctx).await,
+            tiles(1)
+        );
+        assert_eq!(
+            query(processor(&archive, workflow, 2).await, &ctx).await,
+            tiles(1)
+        );
+
+        let mut bypass_ctx = MockQueryContext::default();
+        bypass_ctx.extensions_mut().insert(CachePolicyHint::Bypass);
+        assert_eq!(
+            query(processor(&archive, workflow, 2).await, &bypass_ctx).await,
+            tiles(2)
+        );
+
+        assert_eq!(archive.refresh(workflow).await.unwrap().generation, 1);
+        assert_eq!(
+            query(processor(&archive, workflow, 2).await, &ctx).await,
+            tiles(2)
+        );
+        assert_eq!(
+            query(processor(&archive, workflow, 3).await, &ctx).await,
+            tiles(2)
+        );
+
+        archive.remove(workflow).await.unwrap();
+        assert_eq!(archive.workflow(workflow).await.unwrap(), None);
+        assert_eq!(
+            query(processor(&archive, workflow, 3).await, &ctx).await,
+            tiles(3)
+        );
+        assert!(matches!(
+            archive.refresh(workflow).await,
+            Err(error::Error::WorkflowNotArchived { .. })
+        ));
+    }
+}
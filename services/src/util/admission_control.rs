@@ -0,0 +1,219 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use geoengine_datatypes::primitives::{AxisAlignedRectangle, SpatialPartitioned};
+use geoengine_operators::engine::QueryRectangle;
+
+use crate::error::{Error, Result};
+use crate::util::config;
+
+/// The parameters of an `AdmissionControl`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdmissionControlConfig {
+    /// The total cost of the running queries above which expensive queries are rejected
+    pub max_active_cost: u64,
+    /// Queries with at least this cost are rejected if the `max_active_cost` would be exceeded
+    pub expensive_cost: u64,
+    /// The time clients are asked to wait before retrying a rejected query
+    pub retry_after: Duration,
+}
+
+impl Default for AdmissionControlConfig {
+    fn default() -> Self {
+        Self {
+            max_active_cost: u64::MAX,
+            expensive_cost: u64::MAX,
+            retry_after: Duration::from_secs(5),
+        }
+    }
+}
+
+impl From<config::AdmissionControl> for AdmissionControlConfig {
+    fn from(config: config::AdmissionControl) -> Self {
+        Self {
+            max_active_cost: config.max_active_cost,
+            expensive_cost: config.expensive_cost,
+            retry_after: Duration::from_secs(config.retry_after_seconds),
+        }
+    }
+}
+
+/// Sheds load by rejecting expensive queries while the running queries are too costly in total.
+///
+/// In contrast to the `QueryScheduler`, which lets queries wait for a free slot,
+/// rejected queries fail immediately with `Error::Overloaded`,
+/// s.t. clients can retry later instead of running into timeouts.
+/// Cheap queries are always admitted, but count towards the active cost.
+/// Expensive queries are always admitted if no other query is running.
+///
+/// Clones share the same active cost.
+#[derive(Debug, Clone, Default)]
+pub struct AdmissionControl {
+    config: AdmissionControlConfig,
+    active_cost: Arc<Mutex<u64>>,
+}
+
+impl AdmissionControl {
+    pub fn new(config: AdmissionControlConfig) -> Self {
+        Self {
+            config,
+            active_cost: Default::default(),
+        }
+    }
+
+    /// Admits a query of the given `cost`, which is released when the returned permit is dropped
+    pub fn admit(&self, cost: u64) -> Result<AdmissionPermit> {
+        let mut active_cost = self
+            .active_cost
+            .lock()
+            .expect("active cost must not be poisoned");
+
+        // an idle server admits any query, since retrying a query that exceeds the
+        // `max_active_cost` on its own would never succeed
+        let total_cost = active_cost.saturating_add(cost);
+        if *active_cost > 0
+            && cost >= self.config.expensive_cost
+            && total_cost > self.config.max_active_cost
+        {
+            return Err(Error::Overloaded {
+                retry_after: self.config.retry_after,
+            });
+        }
+
+        *active_cost = total_cost;
+
+        Ok(AdmissionPermit {
+            admission_control: self.clone(),
+            cost,
+        })
+    }
+
+    pub fn active_cost(&self) -> u64 {
+        *self
+            .active_cost
+            .lock()
+            .expect("active cost must not be poisoned")
+    }
+}
+
+/// Holds the cost of an admitted query until it is dropped
+#[derive(Debug)]
+pub struct AdmissionPermit {
+    admission_control: AdmissionControl,
+    cost: u64,
+}
+
+impl Drop for AdmissionPermit {
+    fn drop(&mut self) {
+        let mut active_cost = self
+            .admission_control
+            .active_cost
+            .lock()
+            .expect("active cost must not be poisoned");
+
+        *active_cost = active_cost.saturating_sub(self.cost);
+    }
+}
+
+/// Estimates the cost of a query as the number of pixels of its query rectangle
+pub fn query_cost<S>(query: &QueryRectangle<S>) -> u64
+where
+    S: AxisAlignedRectangle,
+    QueryRectangle<S>: SpatialPartitioned,
+{
+    let partition = query.spatial_partition();
+    let resolution = query.spatial_resolution;
+
+    let pixels_x = (partition.size_x() / resolution.x).ceil();
+    let pixels_y = (partition.size_y() / resolution.y).ceil();
+
+    // saturates for infinite values
+    (pixels_x * pixels_y) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, SpatialPartition2D, SpatialResolution, TimeInterval,
+    };
+    use geoengine_operators::engine::{RasterQueryRectangle, VectorQueryRectangle};
+
+    fn admission_control() -> AdmissionControl {
+        AdmissionControl::new(AdmissionControlConfig {
+            max_active_cost: 100,
+            expensive_cost: 50,
+            retry_after: Duration::from_secs(3),
+        })
+    }
+
+    #[test]
+    fn sheds_expensive_queries() {
+        let admission_control = admission_control();
+
+        let permit = admission_control.admit(60).unwrap();
+        assert_eq!(admission_control.active_cost(), 60);
+
+        assert!(matches!(
+            admission_control.admit(50),
+            Err(Error::Overloaded { retry_after }) if retry_after == Duration::from_secs(3)
+        ));
+
+        // cheap queries are always admitted
+        let _cheap_permit = admission_control.admit(49).unwrap();
+        assert_eq!(admission_control.active_cost(), 109);
+
+        drop(permit);
+        assert_eq!(admission_control.active_cost(), 49);
+
+        admission_control.admit(50).unwrap();
+    }
+
+    #[test]
+    fn admits_expensive_queries_when_idle() {
+        let admission_control = admission_control();
+
+        let permit = admission_control.admit(150).unwrap();
+        assert_eq!(admission_control.active_cost(), 150);
+
+        assert!(matches!(
+            admission_control.admit(150),
+            Err(Error::Overloaded { .. })
+        ));
+
+        drop(permit);
+        admission_control.admit(150).unwrap();
+    }
+
+    #[test]
+    fn admits_everything_by_default() {
+        let admission_control = AdmissionControl::default();
+
+        let _permit = admission_control.admit(u64::MAX).unwrap();
+        admission_control.admit(u64::MAX).unwrap();
+    }
+
+    #[test]
+    fn estimates_pixels() {
+        assert_eq!(
+            query_cost(&RasterQueryRectangle {
+                spatial_bounds: SpatialPartition2D::new_unchecked(
+                    (0., 10.).into(),
+                    (20., 0.).into()
+                ),
+                time_interval: TimeInterval::default(),
+                spatial_resolution: SpatialResolution::new_unchecked(0.5, 1.),
+            }),
+            400
+        );
+
+        assert_eq!(
+            query_cost(&VectorQueryRectangle {
+                spatial_bounds: BoundingBox2D::new_unchecked((0., 0.).into(), (1., 1.).into()),
+                time_interval: TimeInterval::default(),
+                spatial_resolution: SpatialResolution::new_unchecked(0.5, 0.5),
+            }),
+            4
+        );
+    }
+}
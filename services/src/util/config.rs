@@ -1,7 +1,9 @@
 use std::sync::RwLock;
 
 use crate::error::{self, Result};
+use crate::util::aws_signature::AwsCredentials;
 use config::{Config, File};
+use geoengine_operators::concurrency::QuerySchedulerConfig;
 use lazy_static::lazy_static;
 use serde::Deserialize;
 use snafu::ResultExt;
@@ -122,6 +124,7 @@ impl ConfigElement for Postgres {
 #[derive(Debug, Deserialize)]
 pub struct ProjectService {
     pub list_limit: u32,
+    pub playback_frame_limit: u32,
 }
 
 impl ConfigElement for ProjectService {
@@ -137,6 +140,16 @@ impl ConfigElement for GdalSource {
     const KEY: &'static str = "operators.gdal_source";
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SourceFilePaths {
+    pub data_roots: Option<Vec<PathBuf>>,
+    pub denied_schemes: Vec<String>,
+}
+
+impl ConfigElement for SourceFilePaths {
+    const KEY: &'static str = "operators.source_file_paths";
+}
+
 #[derive(Debug, Deserialize)]
 pub struct TilingSpecification {
     pub origin_coordinate_x: f64,
@@ -158,6 +171,75 @@ impl ConfigElement for QueryContext {
     const KEY: &'static str = "query_context";
 }
 
+#[derive(Debug, Deserialize)]
+pub struct QueryScheduler {
+    pub max_concurrent_queries: usize,
+    pub interactive_weight: u32,
+    pub batch_weight: u32,
+}
+
+impl ConfigElement for QueryScheduler {
+    const KEY: &'static str = "query_scheduler";
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AdmissionControl {
+    pub max_active_cost: u64,
+    pub expensive_cost: u64,
+    pub retry_after_seconds: u64,
+}
+
+impl ConfigElement for AdmissionControl {
+    const KEY: &'static str = "admission_control";
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SlowQueryLog {
+    pub duration_threshold_ms: u64,
+    pub pixel_threshold: u64,
+    pub capacity: usize,
+}
+
+impl ConfigElement for SlowQueryLog {
+    const KEY: &'static str = "slow_query_log";
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MetaDataCache {
+    pub session_ttl_seconds: u64,
+    pub max_sessions: usize,
+}
+
+impl ConfigElement for MetaDataCache {
+    const KEY: &'static str = "meta_data_cache";
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QueryTimeouts {
+    pub wms_seconds: u64,
+    pub wcs_seconds: u64,
+    pub wfs_seconds: u64,
+    pub plot_seconds: u64,
+    pub table_seconds: u64,
+    pub aggregate_seconds: u64,
+    pub pixel_values_seconds: u64,
+    pub batch_query_seconds: u64,
+}
+
+impl ConfigElement for QueryTimeouts {
+    const KEY: &'static str = "query_timeouts";
+}
+
+impl From<QueryScheduler> for QuerySchedulerConfig {
+    fn from(config: QueryScheduler) -> Self {
+        Self {
+            max_concurrent_queries: config.max_concurrent_queries,
+            interactive_weight: config.interactive_weight,
+            batch_weight: config.batch_weight,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct DatasetService {
     pub list_limit: u32,
@@ -167,6 +249,53 @@ impl ConfigElement for DatasetService {
     const KEY: &'static str = "dataset_service";
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ScratchDatasets {
+    pub default_ttl_seconds: u32,
+    pub max_ttl_seconds: u32,
+    pub cleanup_interval_seconds: u64,
+}
+
+impl ConfigElement for ScratchDatasets {
+    const KEY: &'static str = "scratch_datasets";
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StorageQuota {
+    pub default_bytes: u64,
+}
+
+impl ConfigElement for StorageQuota {
+    const KEY: &'static str = "storage_quota";
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Admin {
+    pub emails: Vec<String>,
+}
+
+impl ConfigElement for Admin {
+    const KEY: &'static str = "admin";
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LayerService {
+    pub list_limit: u32,
+}
+
+impl ConfigElement for LayerService {
+    const KEY: &'static str = "layer_service";
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AoiService {
+    pub list_limit: u32,
+}
+
+impl ConfigElement for AoiService {
+    const KEY: &'static str = "aoi_service";
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Upload {
     pub path: PathBuf,
@@ -176,6 +305,16 @@ impl ConfigElement for Upload {
     const KEY: &'static str = "upload";
 }
 
+#[derive(Debug, Deserialize)]
+pub struct GdalSandbox {
+    pub allowed_drivers: Option<Vec<String>>,
+    pub allowed_virtual_file_systems: Vec<String>,
+}
+
+impl ConfigElement for GdalSandbox {
+    const KEY: &'static str = "gdal_sandbox";
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Logging {
     pub log_spec: String,
@@ -197,3 +336,78 @@ pub struct Wcs {
 impl ConfigElement for Wcs {
     const KEY: &'static str = "wcs";
 }
+
+#[derive(Debug, Deserialize)]
+pub struct BatchQuery {
+    pub max_queries: usize,
+    pub parallelism: usize,
+}
+
+impl ConfigElement for BatchQuery {
+    const KEY: &'static str = "batch_query";
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Basemap {
+    pub url_template: Option<String>,
+    pub cache_directory: PathBuf,
+    pub max_zoom: u8,
+    pub requests_per_second: Option<f64>,
+    pub max_concurrent_requests: Option<usize>,
+}
+
+impl ConfigElement for Basemap {
+    const KEY: &'static str = "basemap";
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HttpClient {
+    pub user_agent: Option<String>,
+    pub proxy: Option<String>,
+    #[serde(default)]
+    pub ca_certificates: Vec<PathBuf>,
+    pub pool_max_idle_per_host: usize,
+    pub pool_idle_timeout_seconds: u64,
+    pub timeout_seconds: Option<u64>,
+}
+
+impl ConfigElement for HttpClient {
+    const KEY: &'static str = "http_client";
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SourceArchive {
+    pub max_size_bytes: u64,
+}
+
+impl ConfigElement for SourceArchive {
+    const KEY: &'static str = "source_archive";
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TileArchive {
+    pub directory: PathBuf,
+    pub object_storage_url: Option<String>,
+    /// Signs the requests to the object storage, which must allow anonymous requests otherwise
+    pub object_storage_credentials: Option<AwsCredentials>,
+    /// The bucket is the first segment of the path of the `object_storage_url` instead of its host
+    #[serde(default)]
+    pub object_storage_path_style: bool,
+}
+
+impl ConfigElement for TileArchive {
+    const KEY: &'static str = "tile_archive";
+}
+
+/// The endpoints of the dockerized dependencies of the integration tests
+#[derive(Debug, Clone, Deserialize)]
+pub struct IntegrationTests {
+    pub start_containers: bool,
+    pub postgres: String,
+    pub object_storage_url: String,
+    pub stac_url: String,
+}
+
+impl ConfigElement for IntegrationTests {
+    const KEY: &'static str = "integration_tests";
+}
@@ -0,0 +1,97 @@
+use std::sync::RwLock;
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use reqwest::{Certificate, Client, Proxy};
+use snafu::ResultExt;
+
+use crate::error::{self, Result};
+use crate::util::config::{self, get_config_element};
+
+lazy_static! {
+    static ref SHARED_CLIENT: RwLock<Option<Client>> = RwLock::new(None);
+}
+
+/// Returns the HTTP client for requests to external services.
+///
+/// The client is created from the settings on first use. Clones share its connection pool,
+/// s.t. subsequent requests to the same host can reuse open connections.
+pub fn shared_http_client() -> Result<Client> {
+    if let Some(client) = SHARED_CLIENT
+        .read()
+        .expect("http client must be accessible")
+        .as_ref()
+    {
+        return Ok(client.clone());
+    }
+
+    let mut shared_client = SHARED_CLIENT
+        .write()
+        .expect("http client must be accessible");
+
+    if let Some(client) = shared_client.as_ref() {
+        return Ok(client.clone());
+    }
+
+    let client = http_client_from_config(&get_config_element::<config::HttpClient>()?)?;
+    *shared_client = Some(client.clone());
+
+    Ok(client)
+}
+
+fn http_client_from_config(config: &config::HttpClient) -> Result<Client> {
+    let user_agent = config
+        .user_agent
+        .clone()
+        .unwrap_or_else(|| format!("geoengine/{}", env!("CARGO_PKG_VERSION")));
+
+    let mut builder = Client::builder()
+        .user_agent(user_agent)
+        .pool_max_idle_per_host(config.pool_max_idle_per_host)
+        .pool_idle_timeout(Duration::from_secs(config.pool_idle_timeout_seconds));
+
+    if let Some(timeout_seconds) = config.timeout_seconds {
+        builder = builder.timeout(Duration::from_secs(timeout_seconds));
+    }
+
+    if let Some(proxy) = &config.proxy {
+        builder = builder.proxy(Proxy::all(proxy.as_str()).context(error::Reqwest)?);
+    }
+
+    for ca_certificate in &config.ca_certificates {
+        let pem = std::fs::read(ca_certificate).context(error::Io)?;
+        builder =
+            builder.add_root_certificate(Certificate::from_pem(&pem).context(error::Reqwest)?);
+    }
+
+    builder.build().context(error::Reqwest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_builds_clients_from_config() {
+        let config = config::HttpClient {
+            user_agent: None,
+            proxy: Some("http://proxy.example.org:3128".to_owned()),
+            ca_certificates: vec![],
+            pool_max_idle_per_host: 4,
+            pool_idle_timeout_seconds: 90,
+            timeout_seconds: Some(60),
+        };
+
+        assert!(http_client_from_config(&config).is_ok());
+
+        let config = config::HttpClient {
+            ca_certificates: vec!["does/not/exist.pem".into()],
+            ..config
+        };
+
+        assert!(matches!(
+            http_client_from_config(&config),
+            Err(error::Error::Io { .. })
+        ));
+    }
+}
@@ -5,8 +5,14 @@ use std::str::FromStr;
 
 pub use geoengine_datatypes::util::Identifier;
 
+pub mod admission_control;
+pub mod aws_signature;
 pub mod config;
+pub mod http_client;
 pub mod parsing;
+pub mod query_timeout;
+pub mod request_limiter;
+pub mod slow_query_log;
 pub mod tests;
 pub mod user_input;
 
@@ -92,6 +98,20 @@ pub fn provider_defs_dir() -> std::path::PathBuf {
     current_path
 }
 
+/// # Panics
+/// If current dir is not accessible
+// TODO: better way for determining layer_defs directory
+pub fn layer_defs_dir() -> std::path::PathBuf {
+    let mut current_path = std::env::current_dir().unwrap();
+
+    if !current_path.ends_with("services") {
+        current_path = current_path.join("services");
+    }
+
+    current_path = current_path.join("test-data/layer_defs");
+    current_path
+}
+
 #[cfg(test)]
 mod mod_tests {
     use super::*;
@@ -0,0 +1,129 @@
+use std::future::Future;
+use std::time::Duration;
+
+use geoengine_operators::engine::{QueryAbortToken, QueryContext};
+
+use crate::error::{Error, Result};
+use crate::util::config::{get_config_element, QueryTimeouts};
+
+/// The handlers whose queries have a configurable timeout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimedHandler {
+    Wms,
+    Wcs,
+    Wfs,
+    Plot,
+    Table,
+    Aggregate,
+    PixelValues,
+    BatchQuery,
+}
+
+/// Aborts a query that takes longer than the timeout of its handler.
+///
+/// The timeout covers waiting for a free slot of the `QueryScheduler` as well as the computation.
+/// On expiry, the query's future and thus its streams are dropped
+/// and its `QueryAbortToken` is triggered.
+#[derive(Debug, Clone)]
+pub struct QueryTimeout {
+    timeout: Option<Duration>,
+    abort_token: QueryAbortToken,
+}
+
+impl QueryTimeout {
+    /// A timeout of `None` lets queries run indefinitely
+    pub fn new(timeout: Option<Duration>) -> Self {
+        Self {
+            timeout,
+            abort_token: QueryAbortToken::default(),
+        }
+    }
+
+    /// Creates the timeout of the `handler` from the config
+    pub fn for_handler(handler: TimedHandler) -> Result<Self> {
+        let timeouts = get_config_element::<QueryTimeouts>()?;
+
+        let seconds = match handler {
+            TimedHandler::Wms => timeouts.wms_seconds,
+            TimedHandler::Wcs => timeouts.wcs_seconds,
+            TimedHandler::Wfs => timeouts.wfs_seconds,
+            TimedHandler::Plot => timeouts.plot_seconds,
+            TimedHandler::Table => timeouts.table_seconds,
+            TimedHandler::Aggregate => timeouts.aggregate_seconds,
+            TimedHandler::PixelValues => timeouts.pixel_values_seconds,
+            TimedHandler::BatchQuery => timeouts.batch_query_seconds,
+        };
+
+        // zero disables the timeout
+        Ok(Self::new(
+            Some(Duration::from_secs(seconds)).filter(|timeout| !timeout.is_zero()),
+        ))
+    }
+
+    /// Makes the query abortable by adding the `QueryAbortToken` to its context
+    pub fn attach(&self, query_ctx: &mut dyn QueryContext) {
+        query_ctx.extensions_mut().insert(self.abort_token.clone());
+    }
+
+    /// Runs the `query` and fails with `Error::QueryTimeout` if it does not finish in time
+    pub async fn run<F, T>(&self, query: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        let timeout = match self.timeout {
+            Some(timeout) => timeout,
+            None => return query.await,
+        };
+
+        match tokio::time::timeout(timeout, query).await {
+            Ok(result) => result,
+            Err(_elapsed) => {
+                self.abort_token.abort();
+                Err(Error::QueryTimeout { timeout })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contexts::QueryContextImpl;
+
+    #[tokio::test]
+    async fn aborts_slow_queries() {
+        let timeout = QueryTimeout::new(Some(Duration::from_millis(10)));
+
+        let mut query_ctx = QueryContextImpl::new(1024);
+        timeout.attach(&mut query_ctx);
+
+        let result = timeout
+            .run(async {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+                Ok(())
+            })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(Error::QueryTimeout { timeout }) if timeout == Duration::from_millis(10)
+        ));
+        assert!(query_ctx.abort_requested());
+    }
+
+    #[tokio::test]
+    async fn completes_fast_queries() {
+        let timeout = QueryTimeout::new(Some(Duration::from_secs(10)));
+
+        let mut query_ctx = QueryContextImpl::new(1024);
+        timeout.attach(&mut query_ctx);
+
+        assert_eq!(timeout.run(async { Ok(42) }).await.unwrap(), 42);
+        assert!(!query_ctx.abort_requested());
+
+        assert_eq!(
+            QueryTimeout::new(None).run(async { Ok(42) }).await.unwrap(),
+            42
+        );
+    }
+}
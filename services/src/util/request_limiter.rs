@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::{sleep_until, Instant};
+
+lazy_static! {
+    static ref SHARED_LIMITERS: Mutex<HashMap<String, Arc<RequestLimiter>>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Limits for the requests to an upstream service, e.g., a STAC API or a tile server.
+/// Unset limits are not enforced.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestLimits {
+    /// The maximum number of requests that are started per second
+    pub requests_per_second: Option<f64>,
+    /// The maximum number of requests that may run at the same time
+    pub max_concurrent_requests: Option<usize>,
+}
+
+/// An async limiter for the outgoing requests to an upstream service.
+///
+/// Requests first wait for a free connection slot and then for their turn w.r.t. the request rate.
+#[derive(Debug)]
+pub struct RequestLimiter {
+    limits: RequestLimits,
+    connections: Option<Arc<Semaphore>>,
+    next_request: tokio::sync::Mutex<Instant>,
+}
+
+/// Holds a connection slot of a `RequestLimiter` until it is dropped
+#[derive(Debug)]
+pub struct RequestPermit {
+    _connection: Option<OwnedSemaphorePermit>,
+}
+
+impl RequestLimiter {
+    pub fn new(limits: RequestLimits) -> Self {
+        Self {
+            limits,
+            connections: limits
+                .max_concurrent_requests
+                .map(|max| Arc::new(Semaphore::new(max.max(1)))),
+            next_request: tokio::sync::Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Returns the limiter that is shared by all users of the upstream service `key`.
+    ///
+    /// Providers are re-initialized for each access, so the limiter must outlive them to be effective.
+    /// If the `limits` of `key` changed, a new limiter replaces the old one.
+    pub fn shared(key: &str, limits: RequestLimits) -> Arc<Self> {
+        let mut limiters = SHARED_LIMITERS
+            .lock()
+            .expect("request limiters must be accessible");
+
+        match limiters.get(key) {
+            Some(limiter) if limiter.limits == limits => limiter.clone(),
+            _ => {
+                let limiter = Arc::new(Self::new(limits));
+                limiters.insert(key.to_owned(), limiter.clone());
+                limiter
+            }
+        }
+    }
+
+    pub fn limits(&self) -> RequestLimits {
+        self.limits
+    }
+
+    /// Waits until a request may be sent. The connection slot is released when the permit is dropped.
+    pub async fn acquire(&self) -> RequestPermit {
+        let connection = match &self.connections {
+            Some(connections) => Some(
+                connections
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .expect("the semaphore is never closed"),
+            ),
+            None => None,
+        };
+
+        if let Some(interval) = self.request_interval() {
+            let slot = {
+                let mut next_request = self.next_request.lock().await;
+                let slot = (*next_request).max(Instant::now());
+                *next_request = slot + interval;
+                slot
+            };
+
+            sleep_until(slot).await;
+        }
+
+        RequestPermit {
+            _connection: connection,
+        }
+    }
+
+    fn request_interval(&self) -> Option<Duration> {
+        self.limits
+            .requests_per_second
+            .filter(|rate| rate.is_finite() && *rate > 0.)
+            .map(|rate| Duration::from_secs_f64(1. / rate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn limits_concurrent_requests() {
+        let limiter = RequestLimiter::new(RequestLimits {
+            requests_per_second: None,
+            max_concurrent_requests: Some(1),
+        });
+
+        let permit = limiter.acquire().await;
+
+        assert!(
+            tokio::time::timeout(Duration::from_millis(20), limiter.acquire())
+                .await
+                .is_err()
+        );
+
+        drop(permit);
+
+        limiter.acquire().await;
+    }
+
+    #[tokio::test]
+    async fn limits_request_rate() {
+        let limiter = RequestLimiter::new(RequestLimits {
+            requests_per_second: Some(100.),
+            max_concurrent_requests: None,
+        });
+
+        let start = Instant::now();
+
+        for _ in 0..5 {
+            limiter.acquire().await;
+        }
+
+        assert!(start.elapsed() >= Duration::from_millis(40));
+    }
+
+    #[test]
+    fn shares_limiters_per_key() {
+        let limits = RequestLimits {
+            requests_per_second: Some(1.),
+            max_concurrent_requests: Some(2),
+        };
+
+        let limiter = RequestLimiter::shared("request_limiter_test", limits);
+
+        assert!(Arc::ptr_eq(
+            &limiter,
+            &RequestLimiter::shared("request_limiter_test", limits)
+        ));
+        assert!(!Arc::ptr_eq(
+            &limiter,
+            &RequestLimiter::shared("request_limiter_test", RequestLimits::default())
+        ));
+    }
+}
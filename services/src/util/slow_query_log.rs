@@ -0,0 +1,324 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, BoundingBox2D, SpatialPartitioned, SpatialResolution, TimeInterval,
+};
+use geoengine_operators::engine::QueryRectangle;
+use serde::Serialize;
+
+use crate::util::admission_control::query_cost;
+use crate::util::config;
+use crate::workflows::workflow::Workflow;
+
+/// The parameters of a `SlowQueryLog`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlowQueryLogConfig {
+    /// Queries that take at least this long are recorded
+    pub duration_threshold: Duration,
+    /// Queries with at least this many pixels are recorded
+    pub pixel_threshold: u64,
+    /// The number of recorded queries that are kept, the oldest ones are discarded first
+    pub capacity: usize,
+}
+
+impl Default for SlowQueryLogConfig {
+    fn default() -> Self {
+        Self {
+            duration_threshold: Duration::MAX,
+            pixel_threshold: u64::MAX,
+            capacity: 0,
+        }
+    }
+}
+
+impl From<config::SlowQueryLog> for SlowQueryLogConfig {
+    fn from(config: config::SlowQueryLog) -> Self {
+        Self {
+            duration_threshold: Duration::from_millis(config.duration_threshold_ms),
+            pixel_threshold: config.pixel_threshold,
+            capacity: config.capacity,
+        }
+    }
+}
+
+/// A query that exceeded the duration or pixel threshold of the `SlowQueryLog`.
+/// It contains everything that is necessary to reproduce the query.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlowQuery {
+    /// The handler that ran the query, e.g., `wms`
+    pub handler: String,
+    pub workflow: serde_json::Value,
+    pub query_rectangle: Option<SlowQueryRectangle>,
+    pub pixels: u64,
+    pub started: DateTime<Utc>,
+    pub timings: SlowQueryTimings,
+    /// `false` if the query failed or was aborted by the client
+    pub succeeded: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlowQueryRectangle {
+    pub spatial_bounds: BoundingBox2D,
+    pub time_interval: TimeInterval,
+    pub spatial_resolution: SpatialResolution,
+}
+
+impl<S> From<&QueryRectangle<S>> for SlowQueryRectangle
+where
+    S: AxisAlignedRectangle,
+{
+    fn from(query: &QueryRectangle<S>) -> Self {
+        Self {
+            spatial_bounds: BoundingBox2D::new_unchecked(
+                query.spatial_bounds.lower_left(),
+                query.spatial_bounds.upper_right(),
+            ),
+            time_interval: query.time_interval,
+            spatial_resolution: query.spatial_resolution,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlowQueryTimings {
+    /// The time spent on loading and initializing the workflow
+    pub initialization_ms: u64,
+    /// The time spent on computing the result after the initialization
+    pub query_ms: u64,
+    pub total_ms: u64,
+}
+
+/// Records the queries that exceed the configured duration or pixel thresholds,
+/// s.t. admins can retrieve and reproduce the requests that hurt the server most.
+///
+/// Clones share the same recorded queries.
+#[derive(Debug, Clone, Default)]
+pub struct SlowQueryLog {
+    config: SlowQueryLogConfig,
+    queries: Arc<Mutex<VecDeque<SlowQuery>>>,
+}
+
+impl SlowQueryLog {
+    pub fn new(config: SlowQueryLogConfig) -> Self {
+        Self {
+            config,
+            queries: Default::default(),
+        }
+    }
+
+    /// Starts tracking a query of the given `workflow` that is recorded when the returned tracker
+    /// is dropped, if it exceeded a threshold
+    pub fn track(&self, handler: &str, workflow: &Workflow) -> SlowQueryTracker {
+        SlowQueryTracker {
+            log: self.clone(),
+            handler: handler.to_string(),
+            workflow: serde_json::to_value(workflow).unwrap_or(serde_json::Value::Null),
+            query_rectangle: None,
+            pixels: 0,
+            started: Utc::now(),
+            start: Instant::now(),
+            query_start: None,
+            succeeded: false,
+        }
+    }
+
+    /// The recorded queries, starting with the most recent one
+    pub fn queries(&self) -> Vec<SlowQuery> {
+        self.queries
+            .lock()
+            .expect("slow query log must not be poisoned")
+            .iter()
+            .rev()
+            .cloned()
+            .collect()
+    }
+
+    fn record(&self, query: SlowQuery) {
+        if self.config.capacity == 0 {
+            return;
+        }
+
+        let mut queries = self
+            .queries
+            .lock()
+            .expect("slow query log must not be poisoned");
+
+        if queries.len() >= self.config.capacity {
+            queries.pop_front();
+        }
+        queries.push_back(query);
+    }
+}
+
+/// Measures a query and records it in the `SlowQueryLog` when it is dropped
+#[derive(Debug)]
+pub struct SlowQueryTracker {
+    log: SlowQueryLog,
+    handler: String,
+    workflow: serde_json::Value,
+    query_rectangle: Option<SlowQueryRectangle>,
+    pixels: u64,
+    started: DateTime<Utc>,
+    start: Instant,
+    query_start: Option<Instant>,
+    succeeded: bool,
+}
+
+impl SlowQueryTracker {
+    /// Marks the end of the initialization and the start of the actual query
+    pub fn query_started<S>(&mut self, query: &QueryRectangle<S>)
+    where
+        S: AxisAlignedRectangle,
+        QueryRectangle<S>: SpatialPartitioned,
+    {
+        self.query_rectangle = Some(query.into());
+        self.pixels = query_cost(query);
+        self.query_start = Some(Instant::now());
+    }
+
+    /// Marks the query as successfully completed
+    pub fn finish(mut self) {
+        self.succeeded = true;
+    }
+}
+
+impl Drop for SlowQueryTracker {
+    fn drop(&mut self) {
+        let total = self.start.elapsed();
+
+        let config = &self.log.config;
+        if total < config.duration_threshold && self.pixels < config.pixel_threshold {
+            return;
+        }
+
+        let initialization = self
+            .query_start
+            .map_or(total, |query_start| query_start - self.start);
+
+        self.log.record(SlowQuery {
+            handler: self.handler.clone(),
+            workflow: std::mem::take(&mut self.workflow),
+            query_rectangle: self.query_rectangle,
+            pixels: self.pixels,
+            started: self.started,
+            timings: SlowQueryTimings {
+                initialization_ms: initialization.as_millis() as u64,
+                query_ms: (total - initialization).as_millis() as u64,
+                total_ms: total.as_millis() as u64,
+            },
+            succeeded: self.succeeded,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::primitives::SpatialPartition2D;
+    use geoengine_operators::engine::{RasterQueryRectangle, VectorOperator};
+    use geoengine_operators::mock::{MockPointSource, MockPointSourceParams};
+
+    fn workflow() -> Workflow {
+        Workflow {
+            operator: MockPointSource {
+                params: MockPointSourceParams {
+                    points: vec![(0.0, 0.1).into()],
+                },
+            }
+            .boxed()
+            .into(),
+        }
+    }
+
+    fn query(size: f64) -> RasterQueryRectangle {
+        RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new_unchecked((0., size).into(), (size, 0.).into()),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        }
+    }
+
+    fn slow_query_log() -> SlowQueryLog {
+        SlowQueryLog::new(SlowQueryLogConfig {
+            duration_threshold: Duration::from_secs(3600),
+            pixel_threshold: 100,
+            capacity: 2,
+        })
+    }
+
+    #[test]
+    fn records_large_queries() {
+        let log = slow_query_log();
+
+        let mut tracker = log.track("wms", &workflow());
+        tracker.query_started(&query(5.));
+        tracker.finish();
+
+        assert!(log.queries().is_empty());
+
+        let mut tracker = log.track("wms", &workflow());
+        tracker.query_started(&query(10.));
+        tracker.finish();
+
+        let queries = log.queries();
+        assert_eq!(queries.len(), 1);
+
+        let query = &queries[0];
+        assert_eq!(query.handler, "wms");
+        assert_eq!(query.workflow, serde_json::to_value(&workflow()).unwrap());
+        assert_eq!(query.pixels, 100);
+        assert_eq!(
+            query.query_rectangle.unwrap().spatial_bounds,
+            BoundingBox2D::new_unchecked((0., 0.).into(), (10., 10.).into())
+        );
+        assert!(query.succeeded);
+    }
+
+    #[test]
+    fn records_slow_and_failed_queries() {
+        let log = SlowQueryLog::new(SlowQueryLogConfig {
+            duration_threshold: Duration::ZERO,
+            ..slow_query_log().config
+        });
+
+        // dropped before it is finished, e.g., because of an error
+        let tracker = log.track("plot", &workflow());
+        drop(tracker);
+
+        let queries = log.queries();
+        assert_eq!(queries.len(), 1);
+        assert_eq!(queries[0].query_rectangle, None);
+        assert!(!queries[0].succeeded);
+    }
+
+    #[test]
+    fn discards_the_oldest_queries() {
+        let log = slow_query_log();
+
+        for handler in ["a", "b", "c"] {
+            let mut tracker = log.track(handler, &workflow());
+            tracker.query_started(&query(10.));
+            tracker.finish();
+        }
+
+        let handlers: Vec<String> = log.queries().into_iter().map(|q| q.handler).collect();
+        assert_eq!(handlers, vec!["c".to_string(), "b".to_string()]);
+    }
+
+    #[test]
+    fn records_nothing_by_default() {
+        let log = SlowQueryLog::default();
+
+        let mut tracker = log.track("wms", &workflow());
+        tracker.query_started(&query(1000.));
+        drop(tracker);
+
+        assert!(log.queries().is_empty());
+    }
+}
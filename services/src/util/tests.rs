@@ -49,6 +49,7 @@ pub async fn create_project_helper<C: SimpleContext>(ctx: &C) -> (SimpleSession,
                 )
                 .unwrap(),
                 time_step: None,
+                playback_interval: None,
             }
             .validated()
             .unwrap(),
@@ -76,6 +77,7 @@ pub fn update_project_helper(project: ProjectId) -> UpdateProject {
         plots: None,
         bounds: None,
         time_step: None,
+        playback_interval: None,
     }
 }
 
@@ -116,6 +118,8 @@ pub async fn add_ndvi_to_datasets(ctx: &InMemoryContext) -> DatasetId {
                 license: "Sample License".to_owned(),
                 uri: "http://example.org/".to_owned(),
             }),
+            tags: vec![],
+            temporal_extent: None,
         },
         meta_data: MetaDataDefinition::GdalMetaDataRegular(create_ndvi_meta_data()),
     };
@@ -0,0 +1,217 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::error::Result;
+use crate::workflows::workflow::Workflow;
+
+/// A structural change between two versions of a workflow.
+///
+/// The `path` locates the operator in the operator tree, e.g., `/sources/raster` for the raster source of the root
+/// operator or `/sources/rasters/1` for the second one of multiple raster sources. The root operator has an empty path.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum WorkflowChange {
+    #[serde(rename_all = "camelCase")]
+    OperatorAdded { path: String, operator: String },
+    #[serde(rename_all = "camelCase")]
+    OperatorRemoved { path: String, operator: String },
+    #[serde(rename_all = "camelCase")]
+    OperatorReplaced {
+        path: String,
+        from: String,
+        to: String,
+    },
+    /// A parameter was added (`from` is `None`), removed (`to` is `None`) or changed
+    #[serde(rename_all = "camelCase")]
+    ParameterChanged {
+        path: String,
+        operator: String,
+        parameter: String,
+        from: Option<Value>,
+        to: Option<Value>,
+    },
+}
+
+/// Computes the structural changes that turn the `old` workflow into the `new` one.
+///
+/// Operators are matched by their position in the operator tree, i.e., by the name of the source and the index for
+/// multiple sources.
+pub fn diff_workflows(old: &Workflow, new: &Workflow) -> Result<Vec<WorkflowChange>> {
+    let old = serde_json::to_value(old)?;
+    let new = serde_json::to_value(new)?;
+
+    let mut changes = Vec::new();
+    diff_operators("", &old["operator"], &new["operator"], &mut changes);
+
+    Ok(changes)
+}
+
+fn diff_operators(path: &str, old: &Value, new: &Value, changes: &mut Vec<WorkflowChange>) {
+    let old_type = operator_type(old);
+    let new_type = operator_type(new);
+
+    if old_type == new_type {
+        diff_parameters(path, &new_type, &old["params"], &new["params"], changes);
+    } else {
+        // the parameters of different operators are not comparable, but their sources might still be
+        changes.push(WorkflowChange::OperatorReplaced {
+            path: path.to_owned(),
+            from: old_type,
+            to: new_type,
+        });
+    }
+
+    let empty = Map::new();
+    let old_sources = old["sources"].as_object().unwrap_or(&empty);
+    let new_sources = new["sources"].as_object().unwrap_or(&empty);
+
+    let mut source_names: Vec<&String> = old_sources.keys().chain(new_sources.keys()).collect();
+    source_names.sort();
+    source_names.dedup();
+
+    for source_name in source_names {
+        let old_operators = source_operators(old_sources.get(source_name));
+        let new_operators = source_operators(new_sources.get(source_name));
+        let is_multiple = old_sources.get(source_name).map_or(false, Value::is_array)
+            || new_sources.get(source_name).map_or(false, Value::is_array);
+
+        for index in 0..old_operators.len().max(new_operators.len()) {
+            let source_path = if is_multiple {
+                format!("{}/sources/{}/{}", path, source_name, index)
+            } else {
+                format!("{}/sources/{}", path, source_name)
+            };
+
+            match (old_operators.get(index), new_operators.get(index)) {
+                (Some(old), Some(new)) => diff_operators(&source_path, old, new, changes),
+                (Some(old), None) => changes.push(WorkflowChange::OperatorRemoved {
+                    path: source_path,
+                    operator: operator_type(old),
+                }),
+                (None, Some(new)) => changes.push(WorkflowChange::OperatorAdded {
+                    path: source_path,
+                    operator: operator_type(new),
+                }),
+                (None, None) => {}
+            }
+        }
+    }
+}
+
+fn diff_parameters(
+    path: &str,
+    operator: &str,
+    old: &Value,
+    new: &Value,
+    changes: &mut Vec<WorkflowChange>,
+) {
+    let empty = Map::new();
+    let old = old.as_object().unwrap_or(&empty);
+    let new = new.as_object().unwrap_or(&empty);
+
+    let mut parameters: Vec<&String> = old.keys().chain(new.keys()).collect();
+    parameters.sort();
+    parameters.dedup();
+
+    for parameter in parameters {
+        let from = old.get(parameter).filter(|value| !value.is_null());
+        let to = new.get(parameter).filter(|value| !value.is_null());
+
+        if from != to {
+            changes.push(WorkflowChange::ParameterChanged {
+                path: path.to_owned(),
+                operator: operator.to_owned(),
+                parameter: parameter.clone(),
+                from: from.cloned(),
+                to: to.cloned(),
+            });
+        }
+    }
+}
+
+fn operator_type(operator: &Value) -> String {
+    operator["type"].as_str().unwrap_or_default().to_owned()
+}
+
+/// The operators of a serialized source, i.e., a single operator or a list of operators
+fn source_operators(source: Option<&Value>) -> Vec<&Value> {
+    match source {
+        Some(Value::Array(operators)) => operators.iter().collect(),
+        Some(Value::Null) | None => vec![],
+        Some(operator) => vec![operator],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_diffs_workflows() {
+        let old: Workflow = serde_json::from_value(serde_json::json!({
+            "type": "Vector",
+            "operator": {
+                "type": "PointInPolygonFilter",
+                "params": {},
+                "sources": {
+                    "points": {
+                        "type": "MockPointSource",
+                        "params": {
+                            "points": [{"x": 1.0, "y": 2.0}]
+                        }
+                    },
+                    "polygons": {
+                        "type": "MockFeatureCollectionSourceMultiPolygon",
+                        "params": {
+                            "collections": []
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        let new: Workflow = serde_json::from_value(serde_json::json!({
+            "type": "Vector",
+            "operator": {
+                "type": "PointInPolygonFilter",
+                "params": {},
+                "sources": {
+                    "points": {
+                        "type": "MockPointSource",
+                        "params": {
+                            "points": [{"x": 3.0, "y": 4.0}]
+                        }
+                    },
+                    "polygons": {
+                        "type": "MockFeatureCollectionSourceMultiPoint",
+                        "params": {
+                            "collections": []
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            diff_workflows(&old, &new).unwrap(),
+            vec![
+                WorkflowChange::ParameterChanged {
+                    path: "/sources/points".to_owned(),
+                    operator: "MockPointSource".to_owned(),
+                    parameter: "points".to_owned(),
+                    from: Some(serde_json::json!([{"x": 1.0, "y": 2.0}])),
+                    to: Some(serde_json::json!([{"x": 3.0, "y": 4.0}])),
+                },
+                WorkflowChange::OperatorReplaced {
+                    path: "/sources/polygons".to_owned(),
+                    from: "MockFeatureCollectionSourceMultiPolygon".to_owned(),
+                    to: "MockFeatureCollectionSourceMultiPoint".to_owned(),
+                },
+            ]
+        );
+
+        assert!(diff_workflows(&old, &old).unwrap().is_empty());
+    }
+}
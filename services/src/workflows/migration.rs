@@ -0,0 +1,210 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use snafu::ResultExt;
+
+use crate::error::{self, Result};
+use crate::workflows::workflow::Workflow;
+
+/// A rewrite of the operators of a serialized workflow, e.g., for an operator that was renamed in a newer version.
+///
+/// Operators are identified by their name, i.e., the `type` of the serialized operator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum MigrationRule {
+    #[serde(rename_all = "camelCase")]
+    RenameOperator { from: String, to: String },
+    #[serde(rename_all = "camelCase")]
+    RenameParameter {
+        operator: String,
+        from: String,
+        to: String,
+    },
+    /// Sets the parameter of all operators that do not specify it
+    #[serde(rename_all = "camelCase")]
+    SetParameterDefault {
+        operator: String,
+        parameter: String,
+        value: Value,
+    },
+    #[serde(rename_all = "camelCase")]
+    RemoveParameter { operator: String, parameter: String },
+}
+
+impl MigrationRule {
+    fn apply(&self, operator: &mut Map<String, Value>) {
+        let operator_type = operator
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_owned();
+
+        match self {
+            MigrationRule::RenameOperator { from, to } => {
+                if operator_type == *from {
+                    operator.insert("type".to_owned(), Value::String(to.clone()));
+                }
+            }
+            MigrationRule::RenameParameter {
+                operator: name,
+                from,
+                to,
+            } => {
+                if operator_type != *name {
+                    return;
+                }
+
+                if let Some(params) = operator.get_mut("params").and_then(Value::as_object_mut) {
+                    if let Some(value) = params.remove(from) {
+                        params.insert(to.clone(), value);
+                    }
+                }
+            }
+            MigrationRule::SetParameterDefault {
+                operator: name,
+                parameter,
+                value,
+            } => {
+                if operator_type != *name {
+                    return;
+                }
+
+                let params = operator
+                    .entry("params")
+                    .or_insert_with(|| Value::Object(Map::new()));
+
+                if let Some(params) = params.as_object_mut() {
+                    let current = params.entry(parameter.clone()).or_insert(Value::Null);
+                    if current.is_null() {
+                        *current = value.clone();
+                    }
+                }
+            }
+            MigrationRule::RemoveParameter {
+                operator: name,
+                parameter,
+            } => {
+                if operator_type != *name {
+                    return;
+                }
+
+                if let Some(params) = operator.get_mut("params").and_then(Value::as_object_mut) {
+                    params.remove(parameter);
+                }
+            }
+        }
+    }
+}
+
+/// Applies the migration `rules` in the given order to all operators of a serialized `workflow`.
+///
+/// The workflow is only parsed after the migration, so it may refer to operators or parameters that do not exist anymore.
+pub fn migrate_workflow(mut workflow: Value, rules: &[MigrationRule]) -> Result<Workflow> {
+    if let Some(operator) = workflow.get_mut("operator") {
+        migrate_operator(operator, rules);
+    }
+
+    serde_json::from_value(workflow).context(error::InvalidMigratedWorkflow)
+}
+
+fn migrate_operator(operator: &mut Value, rules: &[MigrationRule]) {
+    let operator = match operator.as_object_mut() {
+        Some(operator) => operator,
+        None => return,
+    };
+
+    for rule in rules {
+        rule.apply(operator);
+    }
+
+    let sources = match operator.get_mut("sources").and_then(Value::as_object_mut) {
+        Some(sources) => sources,
+        None => return,
+    };
+
+    for source in sources.values_mut() {
+        match source {
+            Value::Array(operators) => {
+                for operator in operators {
+                    migrate_operator(operator, rules);
+                }
+            }
+            operator => migrate_operator(operator, rules),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_migrates_workflows() {
+        let workflow = serde_json::json!({
+            "type": "Vector",
+            "operator": {
+                "type": "PointInPolygon",
+                "params": {
+                    "attachColumns": []
+                },
+                "sources": {
+                    "points": {
+                        "type": "MockPoints",
+                        "params": {
+                            "coordinates": [{"x": 1.0, "y": 2.0}]
+                        }
+                    },
+                    "polygons": {
+                        "type": "MockFeatureCollectionSourceMultiPolygon",
+                        "params": {
+                            "collections": []
+                        }
+                    }
+                }
+            }
+        });
+
+        let rules = vec![
+            MigrationRule::RenameOperator {
+                from: "MockPoints".to_owned(),
+                to: "MockPointSource".to_owned(),
+            },
+            MigrationRule::RenameParameter {
+                operator: "MockPointSource".to_owned(),
+                from: "coordinates".to_owned(),
+                to: "points".to_owned(),
+            },
+            MigrationRule::RenameOperator {
+                from: "PointInPolygon".to_owned(),
+                to: "PointInPolygonFilter".to_owned(),
+            },
+            MigrationRule::RemoveParameter {
+                operator: "PointInPolygonFilter".to_owned(),
+                parameter: "attachColumns".to_owned(),
+            },
+            MigrationRule::SetParameterDefault {
+                operator: "PointInPolygonFilter".to_owned(),
+                parameter: "mode".to_owned(),
+                value: serde_json::json!("drop"),
+            },
+        ];
+
+        assert!(matches!(
+            migrate_workflow(workflow.clone(), &[]),
+            Err(error::Error::InvalidMigratedWorkflow { .. })
+        ));
+
+        let migrated = serde_json::to_value(migrate_workflow(workflow, &rules).unwrap()).unwrap();
+
+        assert_eq!(migrated["operator"]["type"], "PointInPolygonFilter");
+        assert_eq!(migrated["operator"]["params"]["mode"], "drop");
+        assert_eq!(
+            migrated["operator"]["sources"]["points"],
+            serde_json::json!({
+                "type": "MockPointSource",
+                "params": {
+                    "points": [{"x": 1.0, "y": 2.0}]
+                }
+            })
+        );
+    }
+}
@@ -1,4 +1,7 @@
+pub mod diff;
+pub mod migration;
 #[cfg(feature = "postgres")]
 pub mod postgres_workflow_registry;
 pub mod registry;
+pub mod sources;
 pub mod workflow;
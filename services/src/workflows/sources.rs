@@ -0,0 +1,387 @@
+use std::fs::File;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, BoundingBox2D, SpatialPartition2D, SpatialResolution, TimeInterval,
+};
+use geoengine_operators::engine::{
+    ExecutionContext, MetaData, RasterQueryRectangle, RasterResultDescriptor, VectorQueryRectangle,
+    VectorResultDescriptor,
+};
+use geoengine_operators::source::{FilePathCheckedMetaData, GdalLoadingInfo, OgrSourceDataset};
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt};
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+use crate::error::{self, Result};
+
+/// A file that a source of a workflow reads for a query
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceAsset {
+    pub dataset: DatasetId,
+    /// The validity of the file or `None` if the dataset is not split in time
+    pub time: Option<TimeInterval>,
+    /// A local path or a path of GDAL's virtual file systems, e.g., `/vsicurl/https://…`
+    pub path: PathBuf,
+}
+
+/// A `SourceAsset` in the manifest of a source archive
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArchivedSourceAsset {
+    #[serde(flatten)]
+    asset: SourceAsset,
+    /// The path of the file in the archive or `None` if it is not a local file
+    archive_path: Option<String>,
+    /// The paths of the files in the archive that accompany the file,
+    /// e.g., the `.shx` and `.dbf` files of a shapefile
+    sidecar_archive_paths: Vec<String>,
+}
+
+/// Lists the files that the GDAL and OGR sources of the `datasets` read for a query.
+///
+/// The query is passed to the sources unmodified, i.e., the `bbox` must be given in the spatial reference of the datasets.
+pub async fn source_assets(
+    datasets: &[DatasetId],
+    execution_context: &dyn ExecutionContext,
+    bbox: BoundingBox2D,
+    time: TimeInterval,
+    resolution: SpatialResolution,
+) -> Result<Vec<SourceAsset>> {
+    let mut unique_datasets: Vec<&DatasetId> = Vec::with_capacity(datasets.len());
+    for dataset in datasets {
+        if !unique_datasets.contains(&dataset) {
+            unique_datasets.push(dataset);
+        }
+    }
+
+    let file_path_policy = execution_context.file_path_policy();
+
+    let mut assets = Vec::new();
+
+    for dataset in unique_datasets {
+        let raster_meta_data: geoengine_operators::util::Result<
+            Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>>,
+        > = execution_context.meta_data(dataset).await;
+
+        let raster_meta_data = match raster_meta_data {
+            Ok(meta_data) => Some(meta_data),
+            Err(error) if is_not_a_raster_dataset(&error) => None,
+            Err(error) => return Err(error.into()),
+        };
+
+        // the sources would only read files that are allowed by the policy
+        if let Some(meta_data) = raster_meta_data {
+            let loading_info = FilePathCheckedMetaData::boxed(meta_data, file_path_policy.clone())
+                .loading_info(RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new(bbox.upper_left(), bbox.lower_right())
+                        .context(error::DataType)?,
+                    time_interval: time,
+                    spatial_resolution: resolution,
+                })
+                .await?;
+
+            for part in loading_info.info {
+                let part = part?;
+
+                assets.push(SourceAsset {
+                    dataset: dataset.clone(),
+                    time: Some(part.time),
+                    path: part.params.file_path,
+                });
+            }
+
+            continue;
+        }
+
+        let vector_meta_data: Box<
+            dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>,
+        > = execution_context.meta_data(dataset).await?;
+
+        let loading_info =
+            FilePathCheckedMetaData::boxed(vector_meta_data, file_path_policy.clone())
+                .loading_info(VectorQueryRectangle {
+                    spatial_bounds: bbox,
+                    time_interval: time,
+                    spatial_resolution: resolution,
+                })
+                .await?;
+
+        assets.push(SourceAsset {
+            dataset: dataset.clone(),
+            time: None,
+            path: loading_info.file_name,
+        });
+    }
+
+    Ok(assets)
+}
+
+/// Whether loading the raster meta data of a dataset failed because it is no raster dataset,
+/// e.g., because it is a vector dataset, s.t. its vector meta data can be loaded instead
+fn is_not_a_raster_dataset(error: &geoengine_operators::error::Error) -> bool {
+    match error {
+        geoengine_operators::error::Error::DatasetMetaData { source } => matches!(
+            source.downcast_ref::<error::Error>(),
+            Some(error::Error::UnknownDatasetId) | Some(error::Error::DatasetIdTypeMissMatch)
+        ),
+        // external providers of vector data do not implement raster meta data
+        geoengine_operators::error::Error::NotYetImplemented => true,
+        _ => false,
+    }
+}
+
+/// Creates a zip archive of the local files of the `assets` and a `sources.json` manifest that lists all assets.
+/// Files next to the local files that share their names, e.g., the `.shx` and `.dbf` files of
+/// shapefiles, are archived along with them.
+///
+/// Files of GDAL's virtual file systems are only listed in the manifest.
+pub fn source_archive(assets: Vec<SourceAsset>, max_size_bytes: u64) -> Result<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+
+    let mut size_bytes = 0;
+    let mut manifest = Vec::with_capacity(assets.len());
+
+    for (index, asset) in assets.into_iter().enumerate() {
+        let file_name = asset
+            .path
+            .file_name()
+            .filter(|_| is_local_file(&asset.path))
+            .map(|file_name| file_name.to_string_lossy().into_owned());
+
+        let file_name = match file_name {
+            Some(file_name) => file_name,
+            None => {
+                manifest.push(ArchivedSourceAsset {
+                    asset,
+                    archive_path: None,
+                    sidecar_archive_paths: vec![],
+                });
+                continue;
+            }
+        };
+
+        let sidecars = sidecar_files(&asset.path)?;
+
+        for path in
+            std::iter::once(asset.path.as_path()).chain(sidecars.iter().map(PathBuf::as_path))
+        {
+            size_bytes += path.metadata().context(error::Io)?.len();
+            ensure!(
+                size_bytes <= max_size_bytes,
+                error::SourceArchiveTooLarge {
+                    limit: max_size_bytes
+                }
+            );
+        }
+
+        // the index prevents collisions of equally named files of different datasets
+        let archive_path = format!("files/{}_{}", index, file_name);
+        add_file(&mut zip, &asset.path, &archive_path)?;
+
+        let mut sidecar_archive_paths = Vec::with_capacity(sidecars.len());
+        for sidecar in sidecars {
+            let sidecar_name = sidecar
+                .file_name()
+                .map(|file_name| file_name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let sidecar_archive_path = format!("files/{}_{}", index, sidecar_name);
+
+            add_file(&mut zip, &sidecar, &sidecar_archive_path)?;
+            sidecar_archive_paths.push(sidecar_archive_path);
+        }
+
+        manifest.push(ArchivedSourceAsset {
+            asset,
+            archive_path: Some(archive_path),
+            sidecar_archive_paths,
+        });
+    }
+
+    zip.start_file("sources.json", FileOptions::default())
+        .context(error::SourceArchive)?;
+    zip.write_all(&serde_json::to_vec_pretty(&manifest)?)
+        .context(error::Io)?;
+
+    Ok(zip.finish().context(error::SourceArchive)?.into_inner())
+}
+
+fn add_file(zip: &mut ZipWriter<Cursor<Vec<u8>>>, path: &Path, archive_path: &str) -> Result<()> {
+    zip.start_file(archive_path, FileOptions::default())
+        .context(error::SourceArchive)?;
+    std::io::copy(&mut File::open(path).context(error::Io)?, zip).context(error::Io)?;
+
+    Ok(())
+}
+
+/// The files in the directory of the `path` whose names start with its file stem,
+/// e.g., `ports.shx`, `ports.dbf` and `ports.prj` for `ports.shp`
+fn sidecar_files(path: &Path) -> Result<Vec<PathBuf>> {
+    let (file_name, file_stem) = match (path.file_name(), path.file_stem()) {
+        (Some(file_name), Some(file_stem)) => (file_name, file_stem.to_string_lossy()),
+        _ => return Ok(vec![]),
+    };
+    let prefix = format!("{}.", file_stem);
+
+    let directory = match path.parent() {
+        Some(directory) if !directory.as_os_str().is_empty() => directory,
+        _ => Path::new("."),
+    };
+
+    let mut sidecars = Vec::new();
+    for entry in std::fs::read_dir(directory).context(error::Io)? {
+        let entry = entry.context(error::Io)?;
+        let entry_name = entry.file_name();
+
+        if entry_name != file_name
+            && entry_name.to_string_lossy().starts_with(&prefix)
+            && entry.path().is_file()
+        {
+            sidecars.push(entry.path());
+        }
+    }
+    sidecars.sort();
+
+    Ok(sidecars)
+}
+
+fn is_local_file(path: &Path) -> bool {
+    let is_virtual = path.to_str().map_or(false, |path| path.starts_with("/vsi"));
+
+    !is_virtual && path.is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use geoengine_datatypes::dataset::InternalDatasetId;
+    use geoengine_datatypes::util::Identifier;
+    use zip::ZipArchive;
+
+    use super::*;
+
+    #[test]
+    fn it_archives_local_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("scene.tif");
+        std::fs::write(&file_path, b"scene").unwrap();
+
+        let dataset: DatasetId = InternalDatasetId::new().into();
+
+        let assets = vec![
+            SourceAsset {
+                dataset: dataset.clone(),
+                time: Some(TimeInterval::new_unchecked(0, 1)),
+                path: file_path,
+            },
+            SourceAsset {
+                dataset,
+                time: Some(TimeInterval::new_unchecked(1, 2)),
+                path: "/vsicurl/https://example.org/scene.tif".into(),
+            },
+        ];
+
+        assert!(matches!(
+            source_archive(assets.clone(), 4),
+            Err(error::Error::SourceArchiveTooLarge { limit: 4 })
+        ));
+
+        let archive = source_archive(assets, 1024).unwrap();
+        let mut archive = ZipArchive::new(Cursor::new(archive)).unwrap();
+
+        assert_eq!(archive.len(), 2);
+
+        let mut content = String::new();
+        archive
+            .by_name("files/0_scene.tif")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "scene");
+
+        let manifest: serde_json::Value =
+            serde_json::from_reader(archive.by_name("sources.json").unwrap()).unwrap();
+        assert_eq!(manifest[0]["archivePath"], "files/0_scene.tif");
+        assert_eq!(manifest[1]["archivePath"], serde_json::Value::Null);
+        assert_eq!(
+            manifest[1]["path"],
+            "/vsicurl/https://example.org/scene.tif"
+        );
+    }
+
+    #[test]
+    fn it_archives_the_sidecar_files_of_shapefiles() {
+        let dir = tempfile::tempdir().unwrap();
+        for file_name in [
+            "ports.shp",
+            "ports.shx",
+            "ports.dbf",
+            "ports.prj",
+            "airports.shp",
+        ] {
+            std::fs::write(dir.path().join(file_name), file_name).unwrap();
+        }
+
+        let assets = vec![SourceAsset {
+            dataset: InternalDatasetId::new().into(),
+            time: None,
+            path: dir.path().join("ports.shp"),
+        }];
+
+        // the sidecar files count towards the size limit
+        assert!(matches!(
+            source_archive(assets.clone(), 20),
+            Err(error::Error::SourceArchiveTooLarge { limit: 20 })
+        ));
+
+        let archive = source_archive(assets, 1024).unwrap();
+        let mut archive = ZipArchive::new(Cursor::new(archive)).unwrap();
+
+        let mut file_names: Vec<&str> = archive.file_names().collect();
+        file_names.sort_unstable();
+        assert_eq!(
+            file_names,
+            vec![
+                "files/0_ports.dbf",
+                "files/0_ports.prj",
+                "files/0_ports.shp",
+                "files/0_ports.shx",
+                "sources.json"
+            ]
+        );
+
+        let manifest: serde_json::Value =
+            serde_json::from_reader(archive.by_name("sources.json").unwrap()).unwrap();
+        assert_eq!(manifest[0]["archivePath"], "files/0_ports.shp");
+        assert_eq!(
+            manifest[0]["sidecarArchivePaths"],
+            serde_json::json!([
+                "files/0_ports.dbf",
+                "files/0_ports.prj",
+                "files/0_ports.shx"
+            ])
+        );
+    }
+
+    #[test]
+    fn it_falls_back_to_vector_meta_data_only_for_non_raster_datasets() {
+        let unknown = geoengine_operators::error::Error::DatasetMetaData {
+            source: Box::new(error::Error::UnknownDatasetId),
+        };
+        assert!(is_not_a_raster_dataset(&unknown));
+
+        let unknown_provider = geoengine_operators::error::Error::DatasetMetaData {
+            source: Box::new(error::Error::UnknownProviderId),
+        };
+        assert!(!is_not_a_raster_dataset(&unknown_provider));
+
+        assert!(!is_not_a_raster_dataset(
+            &geoengine_operators::error::Error::QueryProcessor
+        ));
+    }
+}
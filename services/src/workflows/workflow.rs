@@ -1,8 +1,13 @@
-use serde::{Deserialize, Serialize};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize};
 use uuid::Uuid;
 
 use geoengine_datatypes::identifier;
-use geoengine_operators::engine::TypedOperator;
+use geoengine_operators::engine::{
+    resolve_operator_aliases, validate_operator, DeprecationWarning, SchemaViolation, TypedOperator,
+};
+
+use crate::error;
 
 identifier!(WorkflowId);
 
@@ -17,12 +22,90 @@ impl WorkflowId {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize)]
 pub struct Workflow {
     #[serde(flatten)]
     pub operator: TypedOperator,
 }
 
+impl Workflow {
+    /// Lists the deprecated operator names of a serialized workflow
+    pub fn deprecation_warnings(workflow: &serde_json::Value) -> Vec<DeprecationWarning> {
+        match workflow.get("operator") {
+            Some(operator) => resolve_operator_aliases(&mut operator.clone()),
+            None => vec![],
+        }
+    }
+
+    /// Deserializes a workflow after validating it against the parameter schemas of its operators.
+    /// Unlike plain deserialization, an invalid workflow results in the locations of all invalid values,
+    /// e.g., `/operator/params/names/2`.
+    pub fn from_validated_json(workflow: serde_json::Value) -> error::Result<Self> {
+        let violations = Self::schema_violations(&workflow);
+        if !violations.is_empty() {
+            return Err(error::Error::InvalidWorkflow { violations });
+        }
+
+        serde_json::from_value(workflow).map_err(Into::into)
+    }
+
+    fn schema_violations(workflow: &serde_json::Value) -> Vec<SchemaViolation> {
+        let mut violations = Vec::new();
+
+        match workflow.get("type") {
+            Some(serde_json::Value::String(workflow_type))
+                if matches!(
+                    workflow_type.as_str(),
+                    "Vector" | "Raster" | "Plot" | "Aggregate"
+                ) => {}
+            Some(workflow_type) => violations.push(SchemaViolation {
+                pointer: "/type".to_owned(),
+                message: format!(
+                    "expected one of \"Vector\", \"Raster\", \"Plot\", \"Aggregate\", found {}",
+                    workflow_type
+                ),
+            }),
+            None => violations.push(SchemaViolation {
+                pointer: String::new(),
+                message: "missing required property `type`".to_owned(),
+            }),
+        }
+
+        match workflow.get("operator") {
+            Some(operator) => {
+                let mut operator = operator.clone();
+                resolve_operator_aliases(&mut operator);
+
+                violations.extend(validate_operator(&operator, "/operator"));
+            }
+            None => violations.push(SchemaViolation {
+                pointer: String::new(),
+                message: "missing required property `operator`".to_owned(),
+            }),
+        }
+
+        violations
+    }
+}
+
+/// Deserializes workflows with deprecated operator names as if they used their successors
+impl<'de> Deserialize<'de> for Workflow {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut workflow = serde_json::Value::deserialize(deserializer)?;
+
+        if let Some(operator) = workflow.get_mut("operator") {
+            resolve_operator_aliases(operator);
+        }
+
+        Ok(Self {
+            operator: TypedOperator::deserialize(workflow).map_err(D::Error::custom)?,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -68,6 +151,10 @@ mod tests {
             .to_string()
         );
 
-        // TODO: check deserialization
+        assert_eq!(
+            serde_json::to_string(&serde_json::from_str::<Workflow>(&serialized_workflow).unwrap())
+                .unwrap(),
+            serialized_workflow
+        );
     }
 }